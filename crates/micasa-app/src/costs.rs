@@ -0,0 +1,287 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use std::collections::BTreeMap;
+
+use time::Date;
+
+use crate::{Appliance, ApplianceId, Incident, MaintenanceItem, ServiceLogEntry, TabKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CostEventKind {
+    Purchase,
+    /// A service logged without a cost, priced at its maintenance item's
+    /// usual cost.
+    Maintenance,
+    Service,
+    Incident,
+}
+
+impl CostEventKind {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Purchase => "purchase",
+            Self::Maintenance => "maint",
+            Self::Service => "service",
+            Self::Incident => "incident",
+        }
+    }
+}
+
+/// One cost in an appliance's history, pointing back at the row it came
+/// from. Only a purchase can be undated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostEvent {
+    pub date: Option<Date>,
+    pub kind: CostEventKind,
+    pub title: String,
+    pub cost_cents: i64,
+    pub tab: TabKind,
+    pub row_id: i64,
+}
+
+/// Everything one appliance has cost, oldest first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApplianceCostHistory {
+    pub appliance_id: ApplianceId,
+    pub appliance_name: String,
+    pub events: Vec<CostEvent>,
+}
+
+impl ApplianceCostHistory {
+    /// Merges the purchase, the services of the maintenance items linked to
+    /// `appliance`, and its incidents into one timeline. Rows without a cost
+    /// are left out; an incident counts on the day it was resolved, or when
+    /// it was noticed while still open.
+    pub fn build(
+        appliance: &Appliance,
+        maintenance: &[MaintenanceItem],
+        service_log: &[ServiceLogEntry],
+        incidents: &[Incident],
+    ) -> Self {
+        let mut events = Vec::new();
+        if let Some(cost_cents) = appliance.cost_cents {
+            events.push(CostEvent {
+                date: appliance.purchase_date,
+                kind: CostEventKind::Purchase,
+                title: format!("{} purchased", appliance.name),
+                cost_cents,
+                tab: TabKind::Appliances,
+                row_id: appliance.id.get(),
+            });
+        }
+
+        let linked = maintenance
+            .iter()
+            .filter(|item| item.appliance_id == Some(appliance.id))
+            .map(|item| (item.id, item))
+            .collect::<BTreeMap<_, _>>();
+        for entry in service_log {
+            let Some(item) = linked.get(&entry.maintenance_item_id) else {
+                continue;
+            };
+            let (kind, cost_cents) = match (entry.cost_cents, item.cost_cents) {
+                (Some(cost), _) => (CostEventKind::Service, cost),
+                (None, Some(cost)) => (CostEventKind::Maintenance, cost),
+                (None, None) => continue,
+            };
+            events.push(CostEvent {
+                date: Some(entry.serviced_at),
+                kind,
+                title: item.name.clone(),
+                cost_cents,
+                tab: TabKind::ServiceLog,
+                row_id: entry.id.get(),
+            });
+        }
+
+        for incident in incidents
+            .iter()
+            .filter(|incident| incident.appliance_id == Some(appliance.id))
+        {
+            let Some(cost_cents) = incident.cost_cents else {
+                continue;
+            };
+            events.push(CostEvent {
+                date: Some(incident.date_resolved.unwrap_or(incident.date_noticed)),
+                kind: CostEventKind::Incident,
+                title: incident.title.clone(),
+                cost_cents,
+                tab: TabKind::Incidents,
+                row_id: incident.id.get(),
+            });
+        }
+
+        // Undated purchases sort first: whatever it cost came before the
+        // upkeep.
+        events.sort_by(|left, right| {
+            (left.date, left.kind, left.row_id).cmp(&(right.date, right.kind, right.row_id))
+        });
+        Self {
+            appliance_id: appliance.id,
+            appliance_name: appliance.name.clone(),
+            events,
+        }
+    }
+
+    /// Total spent up to and including each event, in event order.
+    pub fn running_totals(&self) -> Vec<i64> {
+        self.events
+            .iter()
+            .scan(0_i64, |total, event| {
+                *total = total.saturating_add(event.cost_cents);
+                Some(*total)
+            })
+            .collect()
+    }
+
+    pub fn total_cents(&self) -> i64 {
+        self.running_totals().last().copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApplianceCostHistory, CostEventKind};
+    use crate::{
+        Appliance, ApplianceId, Incident, IncidentId, IncidentSeverity, IncidentStatus,
+        MaintenanceCategoryId, MaintenanceItem, MaintenanceItemId, ServiceLogEntry,
+        ServiceLogEntryId, TabKind,
+    };
+    use time::macros::date;
+    use time::{Date, OffsetDateTime};
+
+    fn appliance(purchase_date: Option<Date>, cost_cents: Option<i64>) -> Appliance {
+        Appliance {
+            id: ApplianceId::new(1),
+            name: "Furnace".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date,
+            warranty_expiry: None,
+            location: String::new(),
+            cost_cents,
+            notes: String::new(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+            recall_checked_at: None,
+        }
+    }
+
+    fn item(id: i64, appliance_id: Option<i64>, cost_cents: Option<i64>) -> MaintenanceItem {
+        MaintenanceItem {
+            id: MaintenanceItemId::new(id),
+            name: format!("Item {id}"),
+            category_id: MaintenanceCategoryId::new(1),
+            appliance_id: appliance_id.map(ApplianceId::new),
+            last_serviced_at: None,
+            interval_months: 12,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    fn service(
+        id: i64,
+        item_id: i64,
+        serviced_at: Date,
+        cost_cents: Option<i64>,
+    ) -> ServiceLogEntry {
+        ServiceLogEntry {
+            id: ServiceLogEntryId::new(id),
+            maintenance_item_id: MaintenanceItemId::new(item_id),
+            serviced_at,
+            vendor_id: None,
+            cost_cents,
+            notes: String::new(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    fn incident(noticed: Date, resolved: Option<Date>, cost_cents: Option<i64>) -> Incident {
+        Incident {
+            id: IncidentId::new(9),
+            title: "No heat".to_owned(),
+            description: String::new(),
+            status: IncidentStatus::Resolved,
+            severity: IncidentSeverity::Urgent,
+            date_noticed: noticed,
+            date_resolved: resolved,
+            follow_up_date: None,
+            location: String::new(),
+            cost_cents,
+            appliance_id: Some(ApplianceId::new(1)),
+            vendor_id: None,
+            notes: String::new(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn history_merges_linked_costs_in_date_order_with_running_totals() {
+        let history = ApplianceCostHistory::build(
+            &appliance(Some(date!(2024 - 01 - 10)), Some(400_000)),
+            &[item(1, Some(1), Some(9_000)), item(2, None, Some(5_000))],
+            &[
+                service(1, 1, date!(2025 - 01 - 05), Some(12_000)),
+                service(2, 1, date!(2024 - 06 - 01), None),
+                service(3, 2, date!(2024 - 07 - 01), Some(1_000)),
+            ],
+            &[incident(
+                date!(2024 - 11 - 20),
+                Some(date!(2024 - 11 - 22)),
+                Some(30_000),
+            )],
+        );
+
+        let kinds = history
+            .events
+            .iter()
+            .map(|event| (event.kind, event.tab, event.row_id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                (CostEventKind::Purchase, TabKind::Appliances, 1),
+                (CostEventKind::Maintenance, TabKind::ServiceLog, 2),
+                (CostEventKind::Incident, TabKind::Incidents, 9),
+                (CostEventKind::Service, TabKind::ServiceLog, 1),
+            ]
+        );
+        assert_eq!(history.events[2].date, Some(date!(2024 - 11 - 22)));
+        assert_eq!(
+            history.running_totals(),
+            vec![400_000, 409_000, 439_000, 451_000]
+        );
+        assert_eq!(history.total_cents(), 451_000);
+    }
+
+    #[test]
+    fn undated_purchase_leads_and_costless_rows_are_left_out() {
+        let history = ApplianceCostHistory::build(
+            &appliance(None, Some(100)),
+            &[item(1, Some(1), None)],
+            &[service(1, 1, date!(2020 - 01 - 01), None)],
+            &[incident(date!(2019 - 05 - 01), None, Some(50))],
+        );
+        assert_eq!(history.events.len(), 2);
+        assert_eq!(history.events[0].kind, CostEventKind::Purchase);
+        assert_eq!(history.events[1].date, Some(date!(2019 - 05 - 01)));
+        assert_eq!(history.total_cents(), 150);
+
+        let empty = ApplianceCostHistory::build(&appliance(None, None), &[], &[], &[]);
+        assert!(empty.events.is_empty());
+        assert_eq!(empty.total_cents(), 0);
+    }
+}
@@ -5,8 +5,9 @@ use anyhow::{Result, bail};
 use time::Date;
 
 use crate::{
-    ApplianceId, DocumentEntityKind, FormKind, IncidentSeverity, IncidentStatus,
-    MaintenanceCategoryId, MaintenanceItemId, ProjectStatus, ProjectTypeId, VendorId,
+    ApplianceId, BudgetCategory, DocumentEntityKind, DocumentId, FormKind, IncidentSeverity,
+    IncidentStatus, MaintenanceCategoryId, MaintenanceItemId, ProjectStatus, ProjectTypeId,
+    VendorId,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -19,6 +20,7 @@ pub struct ProjectFormInput {
     pub end_date: Option<Date>,
     pub budget_cents: Option<i64>,
     pub actual_cents: Option<i64>,
+    pub parent_id: Option<crate::ProjectId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -29,6 +31,10 @@ pub struct VendorFormInput {
     pub phone: String,
     pub website: String,
     pub notes: String,
+    pub license_number: String,
+    pub insurance_expiry: Option<Date>,
+    /// Document holding the current insurance certificate.
+    pub insurance_document_id: Option<DocumentId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -77,6 +83,7 @@ pub struct IncidentFormInput {
     pub severity: IncidentSeverity,
     pub date_noticed: Date,
     pub date_resolved: Option<Date>,
+    pub follow_up_date: Option<Date>,
     pub location: String,
     pub cost_cents: Option<i64>,
     pub appliance_id: Option<ApplianceId>,
@@ -92,6 +99,9 @@ pub struct DocumentFormInput {
     pub entity_id: i64,
     pub mime_type: String,
     pub data: Vec<u8>,
+    /// Hex SHA-256 of `data`, filled in when a file is picked so the form
+    /// can show it; the store computes its own on save.
+    pub checksum_sha256: String,
     pub notes: String,
 }
 
@@ -135,6 +145,13 @@ pub struct ServiceLogEntryFormInput {
     pub notes: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetFormInput {
+    pub category: BudgetCategory,
+    pub monthly_cents: i64,
+    pub notes: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum FormPayload {
     HouseProfile(Box<HouseProfileFormInput>),
@@ -146,6 +163,7 @@ pub enum FormPayload {
     ServiceLogEntry(ServiceLogEntryFormInput),
     Incident(IncidentFormInput),
     Document(DocumentFormInput),
+    Budget(BudgetFormInput),
 }
 
 impl FormPayload {
@@ -160,6 +178,7 @@ impl FormPayload {
             Self::ServiceLogEntry(_) => FormKind::ServiceLogEntry,
             Self::Incident(_) => FormKind::Incident,
             Self::Document(_) => FormKind::Document,
+            Self::Budget(_) => FormKind::Budget,
         }
     }
 
@@ -203,6 +222,7 @@ impl FormPayload {
                 end_date: None,
                 budget_cents: None,
                 actual_cents: None,
+                parent_id: None,
             })),
             FormKind::Vendor => Some(Self::Vendor(VendorFormInput {
                 name: String::new(),
@@ -211,6 +231,9 @@ impl FormPayload {
                 phone: String::new(),
                 website: String::new(),
                 notes: String::new(),
+                license_number: String::new(),
+                insurance_expiry: None,
+                insurance_document_id: None,
             })),
             FormKind::Quote => Some(Self::Quote(QuoteFormInput {
                 project_id: crate::ProjectId::new(0),
@@ -265,6 +288,7 @@ impl FormPayload {
                 appliance_id: None,
                 vendor_id: None,
                 notes: String::new(),
+                follow_up_date: None,
             })),
             FormKind::Document => Some(Self::Document(DocumentFormInput {
                 title: String::new(),
@@ -273,6 +297,12 @@ impl FormPayload {
                 entity_id: 0,
                 mime_type: String::new(),
                 data: Vec::new(),
+                checksum_sha256: String::new(),
+                notes: String::new(),
+            })),
+            FormKind::Budget => Some(Self::Budget(BudgetFormInput {
+                category: BudgetCategory::Maintenance(MaintenanceCategoryId::new(0)),
+                monthly_cents: 0,
                 notes: String::new(),
             })),
         }
@@ -289,46 +319,102 @@ impl FormPayload {
             Self::ServiceLogEntry(entry) => entry.validate(),
             Self::Incident(incident) => incident.validate(),
             Self::Document(document) => document.validate(),
+            Self::Budget(budget) => budget.validate(),
+        }
+    }
+}
+
+/// Pages of the house profile form, in display order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HouseSection {
+    Address,
+    Structure,
+    Systems,
+    Insurance,
+    Hoa,
+}
+
+impl HouseSection {
+    pub const ALL: [Self; 5] = [
+        Self::Address,
+        Self::Structure,
+        Self::Systems,
+        Self::Insurance,
+        Self::Hoa,
+    ];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Structure => "structure",
+            Self::Systems => "systems",
+            Self::Insurance => "insurance",
+            Self::Hoa => "hoa",
         }
     }
 }
 
 impl HouseProfileFormInput {
+    /// Checks sections in page order and reports the first problem.
     pub fn validate(&self) -> Result<()> {
-        if self.nickname.trim().is_empty() {
-            bail!("house nickname is required -- enter a nickname and retry");
-        }
-        for (label, value) in [
-            ("year built", self.year_built),
-            ("square feet", self.square_feet),
-            ("lot square feet", self.lot_square_feet),
-            ("bedrooms", self.bedrooms),
-        ] {
-            if let Some(value) = value
-                && value < 0
-            {
-                bail!("{label} cannot be negative");
+        for section in HouseSection::ALL {
+            if let Some(issue) = self.section_issues(section).into_iter().next() {
+                bail!("{issue}");
             }
         }
-        if let Some(bathrooms) = self.bathrooms {
-            if !bathrooms.is_finite() {
-                bail!("bathrooms must be a finite number");
+        Ok(())
+    }
+
+    /// Every problem with the fields on one page, so the form can summarize
+    /// each section without stopping at the first error.
+    pub fn section_issues(&self, section: HouseSection) -> Vec<String> {
+        let mut issues = Vec::new();
+        match section {
+            HouseSection::Address => {
+                if self.nickname.trim().is_empty() {
+                    issues.push(
+                        "house nickname is required -- enter a nickname and retry".to_owned(),
+                    );
+                }
             }
-            if bathrooms < 0.0 {
-                bail!("bathrooms cannot be negative");
+            HouseSection::Structure => {
+                for (label, value) in [
+                    ("year built", self.year_built),
+                    ("square feet", self.square_feet),
+                    ("lot square feet", self.lot_square_feet),
+                    ("bedrooms", self.bedrooms),
+                ] {
+                    if let Some(value) = value
+                        && value < 0
+                    {
+                        issues.push(format!("{label} cannot be negative"));
+                    }
+                }
+                if let Some(bathrooms) = self.bathrooms {
+                    if !bathrooms.is_finite() {
+                        issues.push("bathrooms must be a finite number".to_owned());
+                    } else if bathrooms < 0.0 {
+                        issues.push("bathrooms cannot be negative".to_owned());
+                    }
+                }
+            }
+            HouseSection::Systems => {}
+            HouseSection::Insurance => {
+                if let Some(property_tax) = self.property_tax_cents
+                    && property_tax < 0
+                {
+                    issues.push("property tax cannot be negative".to_owned());
+                }
+            }
+            HouseSection::Hoa => {
+                if let Some(hoa_fee) = self.hoa_fee_cents
+                    && hoa_fee < 0
+                {
+                    issues.push("hoa fee cannot be negative".to_owned());
+                }
             }
         }
-        if let Some(property_tax) = self.property_tax_cents
-            && property_tax < 0
-        {
-            bail!("property tax cannot be negative");
-        }
-        if let Some(hoa_fee) = self.hoa_fee_cents
-            && hoa_fee < 0
-        {
-            bail!("hoa fee cannot be negative");
-        }
-        Ok(())
+        issues
     }
 }
 
@@ -454,6 +540,11 @@ impl IncidentFormInput {
         {
             bail!("incident resolved date must be on/after date noticed");
         }
+        if let Some(follow_up_date) = self.follow_up_date
+            && follow_up_date < self.date_noticed
+        {
+            bail!("incident follow-up date must be on/after date noticed");
+        }
         Ok(())
     }
 }
@@ -479,15 +570,29 @@ impl DocumentFormInput {
     }
 }
 
+impl BudgetFormInput {
+    pub fn validate(&self) -> Result<()> {
+        if self.category.id() <= 0 {
+            bail!("budget category is required -- pick a maintenance category or project type");
+        }
+        if self.monthly_cents <= 0 {
+            bail!("monthly budget must be positive");
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        ApplianceFormInput, FormPayload, HouseProfileFormInput, IncidentFormInput,
-        MaintenanceItemFormInput, ProjectFormInput, QuoteFormInput, ServiceLogEntryFormInput,
+        ApplianceFormInput, BudgetFormInput, FormPayload, HouseProfileFormInput, HouseSection,
+        IncidentFormInput, MaintenanceItemFormInput, ProjectFormInput, QuoteFormInput,
+        ServiceLogEntryFormInput,
     };
     use crate::{
-        DocumentEntityKind, FormKind, IncidentSeverity, IncidentStatus, MaintenanceCategoryId,
-        MaintenanceItemId, ProjectId, ProjectStatus, ProjectTypeId, VendorId,
+        BudgetCategory, DocumentEntityKind, FormKind, IncidentSeverity, IncidentStatus,
+        MaintenanceCategoryId, MaintenanceItemId, ProjectId, ProjectStatus, ProjectTypeId,
+        VendorId,
     };
     use time::{Date, Month};
 
@@ -510,6 +615,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         });
         assert!(payload.validate().is_err());
     }
@@ -609,10 +715,35 @@ mod tests {
             appliance_id: None,
             vendor_id: None,
             notes: String::new(),
+            follow_up_date: None,
         });
         assert!(payload.validate().is_err());
     }
 
+    #[test]
+    fn incident_validation_rejects_follow_up_before_noticed() {
+        let noticed = Date::from_calendar_date(2026, Month::January, 10).expect("valid date");
+        let mut input = IncidentFormInput {
+            title: "Leak".to_owned(),
+            description: String::new(),
+            status: IncidentStatus::Open,
+            severity: IncidentSeverity::Soon,
+            date_noticed: noticed,
+            date_resolved: None,
+            follow_up_date: noticed.previous_day(),
+            location: String::new(),
+            cost_cents: None,
+            appliance_id: None,
+            vendor_id: None,
+            notes: String::new(),
+        };
+        let error = input.validate().expect_err("follow-up before noticed");
+        assert!(error.to_string().contains("follow-up date"));
+
+        input.follow_up_date = noticed.checked_add(time::Duration::days(30));
+        assert!(input.validate().is_ok());
+    }
+
     #[test]
     fn appliance_validation_accepts_valid_payload() {
         let payload = FormPayload::Appliance(ApplianceFormInput {
@@ -638,6 +769,7 @@ mod tests {
             entity_id: 1,
             mime_type: "application/pdf".to_owned(),
             data: Vec::new(),
+            checksum_sha256: String::new(),
             notes: String::new(),
         });
         assert!(payload.validate().is_err());
@@ -652,6 +784,9 @@ mod tests {
             phone: String::new(),
             website: String::new(),
             notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
         });
         assert!(payload.validate().is_err());
     }
@@ -672,6 +807,7 @@ mod tests {
             ),
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         });
         assert!(payload.validate().is_err());
     }
@@ -687,6 +823,7 @@ mod tests {
             end_date: None,
             budget_cents: Some(-1),
             actual_cents: None,
+            parent_id: None,
         });
         assert!(payload.validate().is_err());
     }
@@ -734,10 +871,60 @@ mod tests {
             appliance_id: None,
             vendor_id: None,
             notes: String::new(),
+            follow_up_date: None,
         });
         assert!(payload.validate().is_err());
     }
 
+    #[test]
+    fn house_profile_section_issues_group_problems_by_page() {
+        let input = HouseProfileFormInput {
+            nickname: " ".to_owned(),
+            address_line_1: String::new(),
+            address_line_2: String::new(),
+            city: String::new(),
+            state: String::new(),
+            postal_code: String::new(),
+            year_built: Some(-1),
+            square_feet: Some(-1),
+            lot_square_feet: None,
+            bedrooms: None,
+            bathrooms: Some(f64::NAN),
+            foundation_type: String::new(),
+            wiring_type: String::new(),
+            roof_type: String::new(),
+            exterior_type: String::new(),
+            heating_type: String::new(),
+            cooling_type: String::new(),
+            water_source: String::new(),
+            sewer_type: String::new(),
+            parking_type: String::new(),
+            basement_type: String::new(),
+            insurance_carrier: String::new(),
+            insurance_policy: String::new(),
+            insurance_renewal: None,
+            property_tax_cents: None,
+            hoa_name: String::new(),
+            hoa_fee_cents: Some(-5),
+        };
+        assert_eq!(input.section_issues(HouseSection::Address).len(), 1);
+        assert_eq!(
+            input.section_issues(HouseSection::Structure),
+            vec![
+                "year built cannot be negative".to_owned(),
+                "square feet cannot be negative".to_owned(),
+                "bathrooms must be a finite number".to_owned(),
+            ]
+        );
+        assert!(input.section_issues(HouseSection::Systems).is_empty());
+        assert_eq!(
+            input.section_issues(HouseSection::Hoa),
+            vec!["hoa fee cannot be negative".to_owned()]
+        );
+        let error = input.validate().expect_err("address is checked first");
+        assert!(error.to_string().contains("nickname is required"));
+    }
+
     #[test]
     fn document_validation_rejects_missing_entity_id_for_linked_document() {
         let payload = FormPayload::Document(super::DocumentFormInput {
@@ -747,6 +934,7 @@ mod tests {
             entity_id: 0,
             mime_type: "application/pdf".to_owned(),
             data: vec![1, 2, 3],
+            checksum_sha256: String::new(),
             notes: String::new(),
         });
         assert!(payload.validate().is_err());
@@ -761,6 +949,7 @@ mod tests {
             entity_id: 0,
             mime_type: "text/plain".to_owned(),
             data: b"hello".to_vec(),
+            checksum_sha256: String::new(),
             notes: String::new(),
         });
         assert!(payload.validate().is_ok());
@@ -833,4 +1022,23 @@ mod tests {
         }));
         assert!(payload.validate().is_err());
     }
+
+    #[test]
+    fn budget_validation_requires_category_and_positive_amount() {
+        let mut budget = BudgetFormInput {
+            category: BudgetCategory::ProjectType(ProjectTypeId::new(2)),
+            monthly_cents: 50_000,
+            notes: String::new(),
+        };
+        assert!(FormPayload::Budget(budget.clone()).validate().is_ok());
+
+        budget.monthly_cents = 0;
+        let error = budget.validate().expect_err("zero budget should fail");
+        assert!(error.to_string().contains("must be positive"));
+
+        budget.monthly_cents = 50_000;
+        budget.category = BudgetCategory::Maintenance(MaintenanceCategoryId::new(0));
+        let error = budget.validate().expect_err("missing category should fail");
+        assert!(error.to_string().contains("category is required"));
+    }
 }
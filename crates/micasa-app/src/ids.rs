@@ -39,6 +39,10 @@ entity_id!(MaintenanceItemId);
 entity_id!(ServiceLogEntryId);
 entity_id!(IncidentId);
 entity_id!(DocumentId);
+entity_id!(BudgetId);
+entity_id!(TaskId);
 entity_id!(DeletionRecordId);
+entity_id!(AuditEntryId);
 entity_id!(SettingId);
 entity_id!(ChatInputId);
+entity_id!(TagId);
@@ -1,12 +1,18 @@
 // Copyright 2026 Phillip Cloud
 // Licensed under the Apache License, Version 2.0
 
+pub mod costs;
 pub mod forms;
 pub mod ids;
+pub mod links;
 pub mod model;
+pub mod schedule;
 pub mod state;
 
+pub use costs::*;
 pub use forms::*;
 pub use ids::*;
+pub use links::*;
 pub use model::*;
+pub use schedule::*;
 pub use state::*;
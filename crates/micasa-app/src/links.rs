@@ -0,0 +1,122 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Result, anyhow, bail};
+use std::fmt;
+
+use crate::TabKind;
+
+pub const DEEP_LINK_SCHEME: &str = "micasa://";
+
+/// A pointer to one record, written as `micasa://<tab>/<id>` using the tab
+/// labels shown in the UI (`micasa://maint/12`). Printed reports encode these
+/// so a scanned page can open the record again; `micasa open <link>` follows
+/// one at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeepLink {
+    pub tab: TabKind,
+    pub row_id: i64,
+}
+
+impl DeepLink {
+    pub fn new(tab: TabKind, row_id: i64) -> Result<Self> {
+        if matches!(
+            tab,
+            TabKind::Dashboard
+                | TabKind::House
+                | TabKind::Schedule
+                | TabKind::Settings
+                | TabKind::Query
+        ) {
+            bail!("{} has no linkable rows", tab.label());
+        }
+        if row_id <= 0 {
+            bail!("row id must be positive, got {row_id}");
+        }
+        Ok(Self { tab, row_id })
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        let path = raw.strip_prefix(DEEP_LINK_SCHEME).ok_or_else(|| {
+            anyhow!("link `{raw}` must start with {DEEP_LINK_SCHEME}, like micasa://projects/4")
+        })?;
+        let (tab, id) = path.trim_end_matches('/').split_once('/').ok_or_else(|| {
+            anyhow!("link `{raw}` needs a tab and an id, like micasa://projects/4")
+        })?;
+        let tab = tab_for_slug(tab).ok_or_else(|| {
+            anyhow!("link `{raw}` names unknown tab `{tab}`; use a tab name like projects or maint")
+        })?;
+        let row_id = id
+            .parse::<i64>()
+            .map_err(|_| anyhow!("link `{raw}` has a non-numeric id `{id}`"))?;
+        Self::new(tab, row_id)
+    }
+}
+
+/// Tab labels, plus the long and singular names people type by hand
+/// (`maintenance`, `project`, `service-log`).
+pub fn tab_for_slug(slug: &str) -> Option<TabKind> {
+    let slug = slug.to_ascii_lowercase().replace(['-', '_'], "");
+    if let Some(tab) = TabKind::ALL.into_iter().find(|tab| tab.label() == slug) {
+        return Some(tab);
+    }
+    let tab = match slug.as_str() {
+        "project" => TabKind::Projects,
+        "quote" => TabKind::Quotes,
+        "maintenance" => TabKind::Maintenance,
+        "servicelog" | "log" => TabKind::ServiceLog,
+        "incident" => TabKind::Incidents,
+        "appliance" => TabKind::Appliances,
+        "vendor" => TabKind::Vendors,
+        "documents" | "document" | "doc" => TabKind::Documents,
+        _ => return None,
+    };
+    Some(tab)
+}
+
+impl fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{DEEP_LINK_SCHEME}{}/{}", self.tab.label(), self.row_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeepLink;
+    use crate::TabKind;
+
+    #[test]
+    fn links_round_trip_through_tab_labels() {
+        let link = DeepLink::new(TabKind::Maintenance, 12).expect("valid link");
+        assert_eq!(link.to_string(), "micasa://maint/12");
+        for raw in [
+            " micasa://MAINT/12/ ",
+            "micasa://maintenance/12",
+            "micasa://Maintenance/12",
+        ] {
+            assert_eq!(DeepLink::parse(raw).expect(raw), link);
+        }
+        assert_eq!(
+            DeepLink::parse("micasa://service-log/3")
+                .expect("alias")
+                .tab,
+            TabKind::ServiceLog
+        );
+    }
+
+    #[test]
+    fn bad_links_explain_the_expected_form() {
+        for (raw, expected) in [
+            ("https://maint/12", "must start with micasa://"),
+            ("micasa://maint", "needs a tab and an id"),
+            ("micasa://garage/1", "unknown tab `garage`"),
+            ("micasa://maint/x", "non-numeric id"),
+            ("micasa://house/1", "no linkable rows"),
+            ("micasa://maint/0", "must be positive"),
+        ] {
+            let error = DeepLink::parse(raw).expect_err(raw);
+            assert!(error.to_string().contains(expected), "{raw}: {error}");
+        }
+    }
+}
@@ -106,6 +106,7 @@ pub enum DeletionEntity {
     Vendor,
     Document,
     Incident,
+    Budget,
 }
 
 impl DeletionEntity {
@@ -119,6 +120,7 @@ impl DeletionEntity {
             Self::Vendor => "vendor",
             Self::Document => "document",
             Self::Incident => "incident",
+            Self::Budget => "budget",
         }
     }
 
@@ -132,6 +134,7 @@ impl DeletionEntity {
             "vendor" => Some(Self::Vendor),
             "document" => Some(Self::Document),
             "incident" => Some(Self::Incident),
+            "budget" => Some(Self::Budget),
             _ => None,
         }
     }
@@ -150,6 +153,17 @@ pub enum DocumentEntityKind {
 }
 
 impl DocumentEntityKind {
+    pub const ALL: [Self; 8] = [
+        Self::None,
+        Self::Project,
+        Self::Quote,
+        Self::Maintenance,
+        Self::Appliance,
+        Self::ServiceLog,
+        Self::Vendor,
+        Self::Incident,
+    ];
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::None => "",
@@ -178,7 +192,7 @@ impl DocumentEntityKind {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TabKind {
     Dashboard,
     House,
@@ -190,11 +204,17 @@ pub enum TabKind {
     Appliances,
     Vendors,
     Documents,
+    Budget,
+    Schedule,
     Settings,
+    Query,
+    /// One appliance's costs over time. Only reached by drilling from the
+    /// Appliances tab, so it is not in [`TabKind::ALL`].
+    CostHistory,
 }
 
 impl TabKind {
-    pub const ALL: [Self; 11] = [
+    pub const ALL: [Self; 14] = [
         Self::Dashboard,
         Self::House,
         Self::Projects,
@@ -205,7 +225,10 @@ impl TabKind {
         Self::Appliances,
         Self::Vendors,
         Self::Documents,
+        Self::Budget,
+        Self::Schedule,
         Self::Settings,
+        Self::Query,
     ];
 
     pub const fn label(self) -> &'static str {
@@ -220,24 +243,184 @@ impl TabKind {
             Self::Appliances => "appliances",
             Self::Vendors => "vendors",
             Self::Documents => "docs",
+            Self::Budget => "budget",
+            Self::Schedule => "schedule",
             Self::Settings => "settings",
+            Self::Query => "query",
+            Self::CostHistory => "costs",
+        }
+    }
+}
+
+/// Rows per page in the SQL console.
+pub const QUERY_PAGE_ROWS: usize = 100;
+
+/// One page of a read-only SQL console query. Values arrive as display text,
+/// `NULL` as an empty string.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct QueryPage {
+    pub sql: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Zero-based; the first row is number `page * QUERY_PAGE_ROWS + 1`.
+    pub page: usize,
+    pub has_more: bool,
+}
+
+/// How a user-defined report from `reports.toml` is written out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Csv,
+    Html,
+}
+
+impl ReportFormat {
+    pub const ALL: [Self; 3] = [Self::Text, Self::Csv, Self::Html];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Csv => "csv",
+            Self::Html => "html",
         }
     }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.as_str() == value.trim().to_ascii_lowercase())
+    }
+}
+
+/// One entry of the reports menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportSummary {
+    pub name: String,
+    pub title: String,
+    pub format: ReportFormat,
+}
+
+/// A read-only tab contributed by a plugin from plugins.toml.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginTab {
+    pub plugin: String,
+    pub name: String,
+    pub title: String,
+    /// Offered on the selected row, numbered from 1 in the overlay.
+    pub actions: Vec<PluginAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginAction {
+    pub name: String,
+    pub label: String,
+}
+
+/// What a plugin tab shows. Row ids are the plugin's own and are handed back
+/// to it with each action.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PluginRows {
+    pub columns: Vec<String>,
+    pub rows: Vec<PluginRow>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginRow {
+    pub id: String,
+    pub cells: Vec<String>,
+}
+
+/// A report run from the TUI: text reports come back as lines to show, the
+/// others as the file they were written to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportOutput {
+    Lines(Vec<String>),
+    File(std::path::PathBuf),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SettingKey {
     UiShowDashboard,
     LlmModel,
+    UiTheme,
+    UiHideSettledProjects,
+    UiHideResolvedIncidents,
+    UiHideExpiredWarranties,
+    IcalPath,
+    NotifyDesktop,
+    NotifyWarrantyDays,
+    NotifyInsuranceDays,
+    PurgeDeletedDays,
+    /// Model for the SQL-generation stage of chat; empty uses `llm.model`.
+    LlmSqlModel,
+    /// Model for the answer and fallback stages of chat; empty uses
+    /// `llm.model`.
+    LlmSummaryModel,
+    AlertUrgentIncident,
+    AlertOverdueMaintenance,
+    AlertChatEdit,
+    /// Whether micasa snapshots the database on its own (startup, shutdown,
+    /// and on a timer).
+    BackupAuto,
+    /// Dashboard sections shown, top to bottom, as comma-separated names;
+    /// empty shows every section in the default order.
+    DashboardSections,
+    /// Rows listed per dashboard section, as `name=limit` pairs; sections
+    /// left out list every row.
+    DashboardRowLimits,
+    /// Seconds between background reloads of the dashboard and the active
+    /// tab; `0` reloads only after your own actions.
+    UiAutoRefresh,
 }
 
 impl SettingKey {
-    pub const ALL: [Self; 2] = [Self::UiShowDashboard, Self::LlmModel];
+    pub const ALL: [Self; 20] = [
+        Self::UiShowDashboard,
+        Self::LlmModel,
+        Self::UiTheme,
+        Self::UiHideSettledProjects,
+        Self::UiHideResolvedIncidents,
+        Self::UiHideExpiredWarranties,
+        Self::IcalPath,
+        Self::NotifyDesktop,
+        Self::NotifyWarrantyDays,
+        Self::NotifyInsuranceDays,
+        Self::PurgeDeletedDays,
+        Self::LlmSqlModel,
+        Self::LlmSummaryModel,
+        Self::AlertUrgentIncident,
+        Self::AlertOverdueMaintenance,
+        Self::AlertChatEdit,
+        Self::BackupAuto,
+        Self::DashboardSections,
+        Self::DashboardRowLimits,
+        Self::UiAutoRefresh,
+    ];
 
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::UiShowDashboard => "ui.show_dashboard",
             Self::LlmModel => "llm.model",
+            Self::UiTheme => "ui.theme",
+            Self::UiHideSettledProjects => "ui.hide_settled_projects",
+            Self::UiHideResolvedIncidents => "ui.hide_resolved_incidents",
+            Self::UiHideExpiredWarranties => "ui.hide_expired_warranties",
+            Self::IcalPath => "ical.path",
+            Self::NotifyDesktop => "notify.desktop",
+            Self::NotifyWarrantyDays => "notify.warranty_days",
+            Self::NotifyInsuranceDays => "notify.insurance_days",
+            Self::PurgeDeletedDays => "data.purge_deleted_days",
+            Self::LlmSqlModel => "llm.sql_model",
+            Self::LlmSummaryModel => "llm.summary_model",
+            Self::AlertUrgentIncident => "alert.urgent_incident",
+            Self::AlertOverdueMaintenance => "alert.overdue_maintenance",
+            Self::AlertChatEdit => "alert.chat_edit",
+            Self::BackupAuto => "backup.auto",
+            Self::DashboardSections => "dashboard.sections",
+            Self::DashboardRowLimits => "dashboard.row_limits",
+            Self::UiAutoRefresh => "ui.auto_refresh_seconds",
         }
     }
 
@@ -245,6 +428,24 @@ impl SettingKey {
         match value {
             "ui.show_dashboard" => Some(Self::UiShowDashboard),
             "llm.model" => Some(Self::LlmModel),
+            "ui.theme" => Some(Self::UiTheme),
+            "ui.hide_settled_projects" => Some(Self::UiHideSettledProjects),
+            "ui.hide_resolved_incidents" => Some(Self::UiHideResolvedIncidents),
+            "ui.hide_expired_warranties" => Some(Self::UiHideExpiredWarranties),
+            "ical.path" => Some(Self::IcalPath),
+            "notify.desktop" => Some(Self::NotifyDesktop),
+            "notify.warranty_days" => Some(Self::NotifyWarrantyDays),
+            "notify.insurance_days" => Some(Self::NotifyInsuranceDays),
+            "data.purge_deleted_days" => Some(Self::PurgeDeletedDays),
+            "llm.sql_model" => Some(Self::LlmSqlModel),
+            "llm.summary_model" => Some(Self::LlmSummaryModel),
+            "alert.urgent_incident" => Some(Self::AlertUrgentIncident),
+            "alert.overdue_maintenance" => Some(Self::AlertOverdueMaintenance),
+            "alert.chat_edit" => Some(Self::AlertChatEdit),
+            "backup.auto" => Some(Self::BackupAuto),
+            "dashboard.sections" => Some(Self::DashboardSections),
+            "dashboard.row_limits" => Some(Self::DashboardRowLimits),
+            "ui.auto_refresh_seconds" => Some(Self::UiAutoRefresh),
             _ => None,
         }
     }
@@ -253,27 +454,300 @@ impl SettingKey {
         match self {
             Self::UiShowDashboard => "dashboard startup",
             Self::LlmModel => "llm model",
+            Self::UiTheme => "theme",
+            Self::UiHideSettledProjects => "hide settled projects",
+            Self::UiHideResolvedIncidents => "hide resolved incidents",
+            Self::UiHideExpiredWarranties => "hide expired warranties",
+            Self::IcalPath => "calendar feed path",
+            Self::NotifyDesktop => "desktop notifications",
+            Self::NotifyWarrantyDays => "warranty notice",
+            Self::NotifyInsuranceDays => "insurance notice",
+            Self::PurgeDeletedDays => "auto-purge deleted",
+            Self::LlmSqlModel => "llm sql model",
+            Self::LlmSummaryModel => "llm summary model",
+            Self::AlertUrgentIncident => "urgent incident alert",
+            Self::AlertOverdueMaintenance => "overdue maintenance alert",
+            Self::AlertChatEdit => "chat edit alert",
+            Self::BackupAuto => "automatic backups",
+            Self::DashboardSections => "dashboard sections",
+            Self::DashboardRowLimits => "dashboard row limits",
+            Self::UiAutoRefresh => "auto refresh",
         }
     }
 
     pub const fn expected_value_kind(self) -> SettingValueKind {
         match self {
-            Self::UiShowDashboard => SettingValueKind::Bool,
-            Self::LlmModel => SettingValueKind::Text,
+            Self::UiShowDashboard
+            | Self::UiHideSettledProjects
+            | Self::UiHideResolvedIncidents
+            | Self::UiHideExpiredWarranties
+            | Self::NotifyDesktop
+            | Self::BackupAuto => SettingValueKind::Bool,
+            Self::LlmModel
+            | Self::LlmSqlModel
+            | Self::LlmSummaryModel
+            | Self::UiTheme
+            | Self::IcalPath
+            | Self::AlertUrgentIncident
+            | Self::AlertOverdueMaintenance
+            | Self::AlertChatEdit
+            | Self::DashboardSections
+            | Self::DashboardRowLimits
+            | Self::UiAutoRefresh => SettingValueKind::Text,
+            Self::NotifyWarrantyDays | Self::NotifyInsuranceDays | Self::PurgeDeletedDays => {
+                SettingValueKind::Days
+            }
+        }
+    }
+
+    /// Tab whose settled rows start hidden when this setting is on.
+    pub const fn hides_settled_on(self) -> Option<TabKind> {
+        match self {
+            Self::UiHideSettledProjects => Some(TabKind::Projects),
+            Self::UiHideResolvedIncidents => Some(TabKind::Incidents),
+            Self::UiHideExpiredWarranties => Some(TabKind::Appliances),
+            Self::UiShowDashboard
+            | Self::LlmModel
+            | Self::UiTheme
+            | Self::IcalPath
+            | Self::NotifyDesktop
+            | Self::NotifyWarrantyDays
+            | Self::NotifyInsuranceDays
+            | Self::PurgeDeletedDays
+            | Self::LlmSqlModel
+            | Self::LlmSummaryModel
+            | Self::AlertUrgentIncident
+            | Self::AlertOverdueMaintenance
+            | Self::AlertChatEdit
+            | Self::BackupAuto
+            | Self::DashboardSections
+            | Self::DashboardRowLimits
+            | Self::UiAutoRefresh => None,
         }
     }
+
+    /// Event whose alert this setting picks, if it is an alert setting.
+    pub const fn alert_event(self) -> Option<AlertEvent> {
+        match self {
+            Self::AlertUrgentIncident => Some(AlertEvent::UrgentIncident),
+            Self::AlertOverdueMaintenance => Some(AlertEvent::OverdueMaintenance),
+            Self::AlertChatEdit => Some(AlertEvent::ChatEdit),
+            _ => None,
+        }
+    }
+
+    /// Setting holding the default for the tab's `t` toggle, if it has one.
+    pub fn hide_settled_for(tab: TabKind) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|key| key.hides_settled_on() == Some(tab))
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SettingValueKind {
     Bool,
     Text,
+    /// A non-negative number of days.
+    Days,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SettingValue {
     Bool(bool),
     Text(String),
+    Days(i64),
+}
+
+/// Events urgent enough to ring or flash as well as take the status line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum AlertEvent {
+    /// An urgent incident showed up that was not there at the last refresh.
+    UrgentIncident,
+    /// A maintenance item went overdue since the last reminder check.
+    OverdueMaintenance,
+    /// Chat drafted a change and is waiting for you to save it.
+    ChatEdit,
+}
+
+impl AlertEvent {
+    pub const ALL: [Self; 3] = [
+        Self::UrgentIncident,
+        Self::OverdueMaintenance,
+        Self::ChatEdit,
+    ];
+
+    pub const fn setting(self) -> SettingKey {
+        match self {
+            Self::UrgentIncident => SettingKey::AlertUrgentIncident,
+            Self::OverdueMaintenance => SettingKey::AlertOverdueMaintenance,
+            Self::ChatEdit => SettingKey::AlertChatEdit,
+        }
+    }
+}
+
+/// What an alert does beyond its status line entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AlertStyle {
+    /// Status line only.
+    Off,
+    /// Ring the terminal bell.
+    #[default]
+    Bell,
+    /// Flash the screen.
+    Flash,
+    Both,
+}
+
+impl AlertStyle {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Bell, Self::Flash, Self::Both];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Bell => "bell",
+            Self::Flash => "flash",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|style| style.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+
+    /// The style after this one, wrapping around to off.
+    pub fn next(self) -> Self {
+        let index = Self::ALL
+            .iter()
+            .position(|style| *style == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub const fn rings(self) -> bool {
+        matches!(self, Self::Bell | Self::Both)
+    }
+
+    pub const fn flashes(self) -> bool {
+        matches!(self, Self::Flash | Self::Both)
+    }
+}
+
+/// Lead times `enter` cycles through for the notice settings.
+pub const NOTICE_DAY_CHOICES: [i64; 6] = [0, 7, 14, 30, 60, 90];
+
+/// The notice lead time after `current`, wrapping around; an off-list value
+/// starts over from the shortest.
+pub fn next_notice_days(current: i64) -> i64 {
+    NOTICE_DAY_CHOICES
+        .into_iter()
+        .find(|days| *days > current)
+        .unwrap_or(NOTICE_DAY_CHOICES[0])
+}
+
+/// Ages `enter` cycles through for the auto-purge setting; 0 keeps deleted
+/// rows until they are purged by hand.
+pub const PURGE_DAY_CHOICES: [i64; 5] = [0, 30, 90, 180, 365];
+
+/// The auto-purge age after `current`, wrapping around to off.
+pub fn next_purge_days(current: i64) -> i64 {
+    PURGE_DAY_CHOICES
+        .into_iter()
+        .find(|days| *days > current)
+        .unwrap_or(PURGE_DAY_CHOICES[0])
+}
+
+/// Intervals, in seconds, `enter` cycles through for the auto-refresh
+/// setting; 0 turns it off.
+pub const AUTO_REFRESH_CHOICES: [u64; 5] = [0, 30, 60, 300, 900];
+
+/// The auto-refresh interval stored as `raw`: empty is off, anything but a
+/// whole number of seconds is `None`.
+pub fn parse_auto_refresh(raw: &str) -> Option<u64> {
+    match raw.trim() {
+        "" => Some(0),
+        raw => raw.parse().ok(),
+    }
+}
+
+/// The auto-refresh interval after `current`, wrapping around to off.
+pub fn next_auto_refresh(current: u64) -> u64 {
+    AUTO_REFRESH_CHOICES
+        .into_iter()
+        .find(|seconds| *seconds > current)
+        .unwrap_or(AUTO_REFRESH_CHOICES[0])
+}
+
+/// Which soft-deleted rows a purge removes for good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PurgeScope {
+    /// Every deleted row, as `P` in Edit mode does.
+    All,
+    /// Rows deleted longer ago than the `data.purge_deleted_days` setting;
+    /// nothing when the setting is off.
+    Expired,
+}
+
+/// Outcome of a purge. `kept` counts deleted rows that were due but stay
+/// because another row, deleted or not, still points at them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PurgeSummary {
+    pub purged: usize,
+    pub kept: usize,
+}
+
+/// Rows a recalculation pass corrected, per stored derived field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RecalcSummary {
+    pub last_serviced: usize,
+    pub document_checksums: usize,
+}
+
+/// A CSV file read for import into one tab: its header row, the first few
+/// rows, and which column fills each field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvImportPreview {
+    pub tab: TabKind,
+    pub headers: Vec<String>,
+    pub samples: Vec<Vec<String>>,
+    /// Data rows in the file, header excluded.
+    pub row_count: usize,
+    pub fields: Vec<&'static str>,
+    /// Header index per field, parallel to `fields`; `None` leaves the
+    /// field blank.
+    pub mapping: Vec<Option<usize>>,
+}
+
+/// A CSV row that was not imported, by its line in the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvImportFailure {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CsvImportReport {
+    pub imported: usize,
+    pub failures: Vec<CsvImportFailure>,
+}
+
+impl CsvImportReport {
+    pub fn summary(&self, tab: TabKind) -> String {
+        let total = self.imported + self.failures.len();
+        let rows = if total == 1 { "row" } else { "rows" };
+        if self.failures.is_empty() {
+            format!("imported {total} {rows} into {}", tab.label())
+        } else {
+            format!(
+                "imported {} of {total} {rows} into {}; {} failed",
+                self.imported,
+                tab.label(),
+                self.failures.len()
+            )
+        }
+    }
 }
 
 impl SettingValue {
@@ -285,6 +759,12 @@ impl SettingValue {
                 _ => None,
             },
             SettingValueKind::Text => Some(Self::Text(raw.to_owned())),
+            SettingValueKind::Days => raw
+                .trim()
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days >= 0)
+                .map(Self::Days),
         }
     }
 
@@ -293,7 +773,14 @@ impl SettingValue {
             (SettingValueKind::Bool, Self::Bool(value)) => {
                 Some(if *value { "true" } else { "false" }.to_owned())
             }
-            (SettingValueKind::Text, Self::Text(value)) => Some(value.clone()),
+            (SettingValueKind::Text, Self::Text(value))
+                if (key.alert_event().is_none() || AlertStyle::parse(value).is_some())
+                    && (key != SettingKey::UiAutoRefresh
+                        || parse_auto_refresh(value).is_some()) =>
+            {
+                Some(value.clone())
+            }
+            (SettingValueKind::Days, Self::Days(days)) if *days >= 0 => Some(days.to_string()),
             _ => None,
         }
     }
@@ -303,6 +790,8 @@ impl SettingValue {
             Self::Bool(true) => "on".to_owned(),
             Self::Bool(false) => "off".to_owned(),
             Self::Text(value) => value.clone(),
+            Self::Days(1) => "1 day".to_owned(),
+            Self::Days(days) => format!("{days} days"),
         }
     }
 }
@@ -324,6 +813,7 @@ pub enum FormKind {
     Appliance,
     Vendor,
     Document,
+    Budget,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -353,6 +843,20 @@ pub enum FilterScope {
     Global,
 }
 
+/// A table layout saved under a name for one tab. Columns are kept by name,
+/// so a view still applies after columns are added or reordered.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SavedView {
+    pub name: String,
+    pub sorts: Vec<(String, SortDirection)>,
+    /// Pinned column and the pinned value as text.
+    pub pin: Option<(String, String)>,
+    pub filter_active: bool,
+    pub filter_inverted: bool,
+    pub hidden_columns: Vec<String>,
+    pub hide_settled: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct HouseProfile {
     pub id: HouseProfileId,
@@ -385,6 +889,9 @@ pub struct HouseProfile {
     pub hoa_fee_cents: Option<i64>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
+    /// Coordinates for weather forecasts; set with `micasa --set-location`.
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -404,6 +911,10 @@ pub struct Vendor {
     pub phone: String,
     pub website: String,
     pub notes: String,
+    pub license_number: String,
+    pub insurance_expiry: Option<Date>,
+    /// Document holding the current insurance certificate.
+    pub insurance_document_id: Option<DocumentId>,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
@@ -423,6 +934,31 @@ pub struct Project {
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
+    /// Project this one is a sub-project of.
+    pub parent_id: Option<ProjectId>,
+}
+
+/// One checklist step of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub id: TaskId,
+    pub project_id: ProjectId,
+    pub title: String,
+    /// When it was checked off; `None` while it is still to do.
+    pub done_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
+}
+
+/// Starting point for a new project: its type and checklist.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    pub name: String,
+    /// Project type name; a type that no longer exists falls back to the
+    /// first one.
+    pub project_type: String,
+    pub tasks: Vec<String>,
+    /// Ships with micasa; a saved template of the same name replaces it.
+    pub builtin: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -439,6 +975,8 @@ pub struct Quote {
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
+    /// When the quote was accepted; several quotes on one project may be.
+    pub accepted_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -449,6 +987,50 @@ pub struct MaintenanceCategory {
     pub updated_at: OffsetDateTime,
 }
 
+/// Named lists that records pick an entry from, such as maintenance
+/// categories and project types.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
+pub enum LookupKind {
+    #[default]
+    MaintenanceCategory,
+    ProjectType,
+}
+
+impl LookupKind {
+    /// Singular name of one entry.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::MaintenanceCategory => "category",
+            Self::ProjectType => "project type",
+        }
+    }
+
+    pub const fn title(self) -> &'static str {
+        match self {
+            Self::MaintenanceCategory => "maintenance categories",
+            Self::ProjectType => "project types",
+        }
+    }
+
+    /// Singular name of the records that use an entry.
+    pub const fn record_label(self) -> &'static str {
+        match self {
+            Self::MaintenanceCategory => "item",
+            Self::ProjectType => "project",
+        }
+    }
+}
+
+/// One lookup entry plus how many live records use it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupEntry {
+    pub id: i64,
+    pub name: String,
+    pub uses: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Appliance {
     pub id: ApplianceId,
@@ -464,6 +1046,8 @@ pub struct Appliance {
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
+    /// Last run of `micasa --check-recalls` against this appliance.
+    pub recall_checked_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -492,6 +1076,9 @@ pub struct Incident {
     pub severity: IncidentSeverity,
     pub date_noticed: Date,
     pub date_resolved: Option<Date>,
+    /// When to check a resolved incident for recurrence; it resurfaces on
+    /// the dashboard from this date until settled.
+    pub follow_up_date: Option<Date>,
     pub location: String,
     pub cost_cents: Option<i64>,
     pub appliance_id: Option<ApplianceId>,
@@ -527,11 +1114,71 @@ pub struct Document {
     pub checksum_sha256: String,
     pub data: Vec<u8>,
     pub notes: String,
+    /// Text read out of the file when it was saved with extraction on;
+    /// empty otherwise.
+    pub extracted_text: String,
     pub created_at: OffsetDateTime,
     pub updated_at: OffsetDateTime,
     pub deleted_at: Option<OffsetDateTime>,
 }
 
+/// What a budget caps: spend in one maintenance category, or on projects of
+/// one type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum BudgetCategory {
+    Maintenance(MaintenanceCategoryId),
+    ProjectType(ProjectTypeId),
+}
+
+impl BudgetCategory {
+    pub const fn scope(self) -> &'static str {
+        match self {
+            Self::Maintenance(_) => "maintenance",
+            Self::ProjectType(_) => "project",
+        }
+    }
+
+    pub const fn id(self) -> i64 {
+        match self {
+            Self::Maintenance(id) => id.get(),
+            Self::ProjectType(id) => id.get(),
+        }
+    }
+
+    pub fn from_parts(scope: &str, id: i64) -> Option<Self> {
+        match scope {
+            "maintenance" => Some(Self::Maintenance(MaintenanceCategoryId::new(id))),
+            "project" => Some(Self::ProjectType(ProjectTypeId::new(id))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Budget {
+    pub id: BudgetId,
+    pub category: BudgetCategory,
+    /// Name of the maintenance category or project type.
+    pub category_name: String,
+    pub monthly_cents: i64,
+    pub notes: String,
+    pub created_at: OffsetDateTime,
+    pub updated_at: OffsetDateTime,
+    pub deleted_at: Option<OffsetDateTime>,
+}
+
+/// One category's spend this calendar year in the real plan and in the
+/// what-if sandbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScenarioLine {
+    pub category: BudgetCategory,
+    pub category_name: String,
+    /// Twelve months of the category's budget, when it has one.
+    pub annual_budget_cents: Option<i64>,
+    pub real_cents: i64,
+    pub what_if_cents: i64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeletionRecord {
     pub id: DeletionRecordId,
@@ -541,6 +1188,78 @@ pub struct DeletionRecord {
     pub restored_at: Option<OffsetDateTime>,
 }
 
+/// Where a recorded change came from, so automated edits can be told apart
+/// from ones typed into a form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AuditSource {
+    #[default]
+    Manual,
+    Chat,
+    Import,
+    /// An outside assistant connected through `micasa mcp`.
+    Agent,
+}
+
+impl AuditSource {
+    pub const ALL: [Self; 4] = [Self::Manual, Self::Chat, Self::Import, Self::Agent];
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Chat => "chat",
+            Self::Import => "import",
+            Self::Agent => "agent",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "manual" => Some(Self::Manual),
+            "chat" => Some(Self::Chat),
+            "import" => Some(Self::Import),
+            "agent" => Some(Self::Agent),
+            _ => None,
+        }
+    }
+}
+
+/// One create, edit, delete or restore, tagged with its source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: AuditEntryId,
+    pub entity: DeletionEntity,
+    pub target_id: i64,
+    /// `create`, `delete`, `restore`, or `edit <field>`.
+    pub action: String,
+    pub source: AuditSource,
+    pub recorded_at: OffsetDateTime,
+}
+
+/// Per-project totals from live quotes, linked documents, and tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ProjectRollup {
+    pub quote_count: usize,
+    /// Sum of accepted quote totals; `None` when no quote is accepted.
+    pub accepted_cents: Option<i64>,
+    pub doc_count: usize,
+    pub task_count: usize,
+    pub tasks_done: usize,
+}
+
+/// Per-appliance counts of live maintenance items and linked documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct ApplianceRollup {
+    pub maintenance_count: usize,
+    pub doc_count: usize,
+}
+
+/// Per-vendor counts of live quotes and service log jobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct VendorRollup {
+    pub quote_count: usize,
+    pub job_count: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub struct DashboardCounts {
     pub projects_due: usize,
@@ -548,6 +1267,122 @@ pub struct DashboardCounts {
     pub incidents_open: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReminderKind {
+    Warranty,
+    Maintenance,
+    Insurance,
+}
+
+/// Something expiring soon or already overdue: counted on the tab bar and
+/// sent as a desktop notification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reminder {
+    pub kind: ReminderKind,
+    /// Appliance, maintenance item, carrier, or vendor name.
+    pub title: String,
+    /// Negative once the date has passed.
+    pub days_from_now: i64,
+}
+
+impl Reminder {
+    /// One line for a notification body, like "Dishwasher warranty ends in
+    /// 12 days".
+    pub fn summary(&self) -> String {
+        let when = match self.days_from_now {
+            0 => "today".to_owned(),
+            1 => "tomorrow".to_owned(),
+            -1 => "yesterday".to_owned(),
+            days if days > 0 => format!("in {days} days"),
+            days => format!("{} days ago", -days),
+        };
+        let past = self.days_from_now < 0;
+        match self.kind {
+            ReminderKind::Warranty if past => format!("{} warranty ended {when}", self.title),
+            ReminderKind::Warranty => format!("{} warranty ends {when}", self.title),
+            ReminderKind::Maintenance => format!("{} was due {when}", self.title),
+            ReminderKind::Insurance if past => format!("{} insurance lapsed {when}", self.title),
+            ReminderKind::Insurance => format!("{} insurance renews {when}", self.title),
+        }
+    }
+}
+
+/// A kind of suspect record the data quality sweep looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DataIssueKind {
+    /// A maintenance item that never comes due.
+    ZeroInterval,
+    /// A quote whose labor, materials and other parts don't add up to the
+    /// total.
+    QuoteParts,
+    ResolvedBeforeNoticed,
+    EmptyDocument,
+}
+
+impl DataIssueKind {
+    pub const fn tab(self) -> TabKind {
+        match self {
+            Self::ZeroInterval => TabKind::Maintenance,
+            Self::QuoteParts => TabKind::Quotes,
+            Self::ResolvedBeforeNoticed => TabKind::Incidents,
+            Self::EmptyDocument => TabKind::Documents,
+        }
+    }
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::ZeroInterval => "zero interval",
+            Self::QuoteParts => "parts != total",
+            Self::ResolvedBeforeNoticed => "resolved before noticed",
+            Self::EmptyDocument => "empty file",
+        }
+    }
+}
+
+/// One row the data quality sweep flagged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataIssue {
+    pub kind: DataIssueKind,
+    pub row_id: i64,
+    /// Item, incident or document title; project and vendor for quotes.
+    pub title: String,
+    /// What to check, like "parts $1,200.00 vs total $1,500.00".
+    pub detail: String,
+}
+
+/// A free-form label shared by projects, incidents, appliances, vendors and
+/// documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: TagId,
+    pub name: String,
+    /// Rows currently carrying the tag.
+    pub uses: usize,
+}
+
+/// Splits `#deck, winter` into normalized tag names: lowercase, without the
+/// leading `#`, sorted and deduplicated. Names may use letters, digits, `-`
+/// and `_`.
+pub fn parse_tags(input: &str) -> anyhow::Result<Vec<String>> {
+    let mut tags = Vec::new();
+    for raw in input.split(|ch: char| ch.is_whitespace() || ch == ',') {
+        let name = raw.trim_start_matches('#').to_lowercase();
+        if name.is_empty() {
+            continue;
+        }
+        if !name
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_')
+        {
+            anyhow::bail!("tag `{raw}` has odd characters -- use letters, digits, - or _");
+        }
+        tags.push(name);
+    }
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ChatInput {
     pub id: ChatInputId,
@@ -557,7 +1392,67 @@ pub struct ChatInput {
 
 #[cfg(test)]
 mod tests {
-    use super::{SettingKey, SettingValue, TabKind};
+    use super::{
+        Reminder, ReminderKind, SettingKey, SettingValue, TabKind, next_notice_days, parse_tags,
+    };
+
+    #[test]
+    fn days_setting_parses_displays_and_cycles() {
+        let key = SettingKey::NotifyWarrantyDays;
+        assert_eq!(
+            SettingValue::parse_for_key(key, " 45 "),
+            Some(SettingValue::Days(45))
+        );
+        assert_eq!(SettingValue::parse_for_key(key, "-1"), None);
+        assert_eq!(SettingValue::parse_for_key(key, "soon"), None);
+        assert_eq!(
+            SettingValue::Days(14).to_storage(key).as_deref(),
+            Some("14")
+        );
+        assert_eq!(SettingValue::Days(-3).to_storage(key), None);
+        assert_eq!(SettingValue::Days(1).display(), "1 day");
+        assert_eq!(SettingValue::Days(30).display(), "30 days");
+
+        assert_eq!(next_notice_days(30), 60);
+        assert_eq!(next_notice_days(90), 0);
+        assert_eq!(next_notice_days(45), 60);
+    }
+
+    #[test]
+    fn tags_parse_normalized_and_reject_odd_characters() {
+        assert_eq!(
+            parse_tags(" #Deck, winter  #deck,,").expect("parse tags"),
+            vec!["deck".to_owned(), "winter".to_owned()]
+        );
+        assert!(parse_tags("").expect("parse empty").is_empty());
+        let error = parse_tags("roof/attic").expect_err("slash is not a tag character");
+        assert!(error.to_string().contains("roof/attic"));
+    }
+
+    #[test]
+    fn reminder_summaries_read_as_sentences() {
+        let reminder = |kind, days_from_now| Reminder {
+            kind,
+            title: "Dishwasher".to_owned(),
+            days_from_now,
+        };
+        assert_eq!(
+            reminder(ReminderKind::Warranty, 12).summary(),
+            "Dishwasher warranty ends in 12 days"
+        );
+        assert_eq!(
+            reminder(ReminderKind::Maintenance, -1).summary(),
+            "Dishwasher was due yesterday"
+        );
+        assert_eq!(
+            reminder(ReminderKind::Insurance, -5).summary(),
+            "Dishwasher insurance lapsed 5 days ago"
+        );
+        assert_eq!(
+            reminder(ReminderKind::Insurance, 0).summary(),
+            "Dishwasher insurance renews today"
+        );
+    }
 
     #[test]
     fn bool_setting_parse_and_storage_round_trip() {
@@ -0,0 +1,401 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use time::{Date, Duration, Month, Weekday};
+
+use crate::{Appliance, HouseProfile, MaintenanceItem, TabKind};
+
+/// How far ahead the Schedule tab looks.
+pub const SCHEDULE_HORIZON_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ScheduleEventKind {
+    Maintenance,
+    Warranty,
+    InsuranceRenewal,
+}
+
+impl ScheduleEventKind {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Maintenance => "maint",
+            Self::Warranty => "warranty",
+            Self::InsuranceRenewal => "insurance",
+        }
+    }
+}
+
+/// One dated event on the schedule, pointing back at the row it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleEvent {
+    pub date: Date,
+    pub days_from_now: i64,
+    pub kind: ScheduleEventKind,
+    pub title: String,
+    pub tab: TabKind,
+    pub row_id: i64,
+}
+
+impl ScheduleEvent {
+    /// Monday of the event's week, used to group rows like a calendar.
+    pub fn week_start(&self) -> Date {
+        let offset = self.date.weekday().number_days_from_monday();
+        self.date - Duration::days(i64::from(offset))
+    }
+}
+
+/// Next due date for an item serviced on `last_serviced_at` every
+/// `interval_months`; `None` when it was never serviced or has no interval.
+pub fn next_due(last_serviced_at: Option<Date>, interval_months: i32) -> Option<Date> {
+    add_months_clamped(last_serviced_at?, interval_months)
+}
+
+/// Due dates of `item` from `today` through `until`. An overdue item shows up
+/// once on the date it was missed; the following dates depend on when it
+/// actually gets done, so they are not projected.
+pub fn maintenance_occurrences(item: &MaintenanceItem, today: Date, until: Date) -> Vec<Date> {
+    let Some(last) = item.last_serviced_at else {
+        return Vec::new();
+    };
+    let mut dates = Vec::new();
+    // Step from the service date each time so a 31st stays a 31st after a
+    // short month instead of drifting to the 28th.
+    for step in 1.. {
+        let Some(months) = item.interval_months.checked_mul(step) else {
+            break;
+        };
+        let Some(due) = add_months_clamped(last, months) else {
+            break;
+        };
+        if due > until {
+            break;
+        }
+        if due < today {
+            if step == 1 {
+                dates.push(due);
+                break;
+            }
+            continue;
+        }
+        dates.push(due);
+    }
+    dates
+}
+
+/// Maintenance, warranty-expiry, and insurance-renewal events from `today`
+/// through `horizon_days` ahead, plus maintenance already overdue. Sorted by
+/// date, then kind, then newest row first.
+pub fn build_schedule(
+    today: Date,
+    horizon_days: i64,
+    maintenance: &[MaintenanceItem],
+    appliances: &[Appliance],
+    house: Option<&HouseProfile>,
+) -> Vec<ScheduleEvent> {
+    let until = today + Duration::days(horizon_days);
+    let event = |date: Date, kind, title: &str, tab, row_id| ScheduleEvent {
+        date,
+        days_from_now: days_from_to(today, date),
+        kind,
+        title: title.to_owned(),
+        tab,
+        row_id,
+    };
+
+    let mut events = Vec::new();
+    for item in maintenance {
+        for due in maintenance_occurrences(item, today, until) {
+            events.push(event(
+                due,
+                ScheduleEventKind::Maintenance,
+                &item.name,
+                TabKind::Maintenance,
+                item.id.get(),
+            ));
+        }
+    }
+    for appliance in appliances {
+        if let Some(expiry) = appliance.warranty_expiry
+            && (today..=until).contains(&expiry)
+        {
+            events.push(event(
+                expiry,
+                ScheduleEventKind::Warranty,
+                &format!("{} warranty ends", appliance.name),
+                TabKind::Appliances,
+                appliance.id.get(),
+            ));
+        }
+    }
+    if let Some(house) = house
+        && let Some(renewal) = house.insurance_renewal
+        && (today..=until).contains(&renewal)
+    {
+        let carrier = house.insurance_carrier.trim();
+        let title = if carrier.is_empty() {
+            "insurance renewal".to_owned()
+        } else {
+            format!("{carrier} renewal")
+        };
+        events.push(event(
+            renewal,
+            ScheduleEventKind::InsuranceRenewal,
+            &title,
+            TabKind::House,
+            house.id.get(),
+        ));
+    }
+    events.sort_by(|left, right| {
+        (left.date, left.kind, std::cmp::Reverse(left.row_id)).cmp(&(
+            right.date,
+            right.kind,
+            std::cmp::Reverse(right.row_id),
+        ))
+    });
+    events
+}
+
+pub fn add_months_clamped(date: Date, months: i32) -> Option<Date> {
+    if months <= 0 {
+        return None;
+    }
+
+    let base_month = i32::from(date.month() as u8);
+    let total_month = base_month - 1 + months;
+    let year = date.year() + total_month.div_euclid(12);
+    let month_number = (total_month.rem_euclid(12) + 1) as u8;
+    let month = Month::try_from(month_number).ok()?;
+
+    let day = date.day();
+    let max_day = last_day_of_month(year, month)?;
+    let clamped_day = day.min(max_day);
+    Date::from_calendar_date(year, month, clamped_day).ok()
+}
+
+fn last_day_of_month(year: i32, month: Month) -> Option<u8> {
+    let (next_year, next_month) = if month == Month::December {
+        (year + 1, Month::January)
+    } else {
+        let next = Month::try_from((month as u8) + 1).ok()?;
+        (year, next)
+    };
+
+    let first_next_month = Date::from_calendar_date(next_year, next_month, 1).ok()?;
+    let last = first_next_month - Duration::days(1);
+    Some(last.day())
+}
+
+pub fn days_from_to(from: Date, to: Date) -> i64 {
+    i64::from(to.to_julian_day() - from.to_julian_day())
+}
+
+/// Three-letter weekday, as shown in the Schedule tab.
+pub const fn weekday_label(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Monday => "mon",
+        Weekday::Tuesday => "tue",
+        Weekday::Wednesday => "wed",
+        Weekday::Thursday => "thu",
+        Weekday::Friday => "fri",
+        Weekday::Saturday => "sat",
+        Weekday::Sunday => "sun",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ScheduleEventKind, add_months_clamped, build_schedule, maintenance_occurrences, next_due,
+    };
+    use crate::{
+        Appliance, ApplianceId, HouseProfile, HouseProfileId, MaintenanceCategoryId,
+        MaintenanceItem, MaintenanceItemId, TabKind,
+    };
+    use time::macros::date;
+    use time::{Date, OffsetDateTime};
+
+    fn item(id: i64, name: &str, last: Option<Date>, interval_months: i32) -> MaintenanceItem {
+        MaintenanceItem {
+            id: MaintenanceItemId::new(id),
+            name: name.to_owned(),
+            category_id: MaintenanceCategoryId::new(1),
+            appliance_id: None,
+            last_serviced_at: last,
+            interval_months,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn next_due_clamps_to_month_end() {
+        assert_eq!(
+            next_due(Some(date!(2026 - 01 - 31)), 1),
+            Some(date!(2026 - 02 - 28))
+        );
+        assert_eq!(next_due(Some(date!(2026 - 01 - 31)), 0), None);
+        assert_eq!(next_due(None, 6), None);
+        assert_eq!(
+            add_months_clamped(date!(2026 - 11 - 15), 3),
+            Some(date!(2027 - 02 - 15))
+        );
+    }
+
+    #[test]
+    fn occurrences_repeat_inside_the_window_without_drifting() {
+        let today = date!(2026 - 01 - 01);
+        let until = date!(2026 - 04 - 30);
+        let monthly = item(1, "Filter", Some(date!(2025 - 12 - 31)), 1);
+        assert_eq!(
+            maintenance_occurrences(&monthly, today, until),
+            vec![
+                date!(2026 - 01 - 31),
+                date!(2026 - 02 - 28),
+                date!(2026 - 03 - 31),
+                date!(2026 - 04 - 30),
+            ]
+        );
+
+        let overdue = item(2, "Gutters", Some(date!(2025 - 06 - 01)), 3);
+        assert_eq!(
+            maintenance_occurrences(&overdue, today, until),
+            vec![date!(2025 - 09 - 01)]
+        );
+        let unserviced = item(3, "Sump", None, 12);
+        assert!(maintenance_occurrences(&unserviced, today, until).is_empty());
+    }
+
+    #[test]
+    fn schedule_merges_maintenance_warranty_and_insurance_by_date() {
+        let today = date!(2026 - 10 - 16);
+        let maintenance = vec![
+            item(1, "HVAC filter", Some(date!(2026 - 09 - 20)), 1),
+            item(2, "Chimney sweep", Some(date!(2025 - 06 - 01)), 12),
+        ];
+        let appliances = vec![
+            Appliance {
+                id: ApplianceId::new(4),
+                name: "Dishwasher".to_owned(),
+                warranty_expiry: Some(date!(2026 - 11 - 01)),
+                ..appliance_defaults()
+            },
+            Appliance {
+                id: ApplianceId::new(5),
+                name: "Washer".to_owned(),
+                warranty_expiry: Some(date!(2027 - 06 - 01)),
+                ..appliance_defaults()
+            },
+        ];
+        let house = HouseProfile {
+            id: HouseProfileId::new(1),
+            insurance_carrier: "Acme".to_owned(),
+            insurance_renewal: Some(date!(2026 - 12 - 20)),
+            ..house_defaults()
+        };
+
+        let events = build_schedule(today, 90, &maintenance, &appliances, Some(&house));
+        let summary = events
+            .iter()
+            .map(|event| (event.date, event.kind, event.title.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    date!(2026 - 06 - 01),
+                    ScheduleEventKind::Maintenance,
+                    "Chimney sweep"
+                ),
+                (
+                    date!(2026 - 10 - 20),
+                    ScheduleEventKind::Maintenance,
+                    "HVAC filter"
+                ),
+                (
+                    date!(2026 - 11 - 01),
+                    ScheduleEventKind::Warranty,
+                    "Dishwasher warranty ends"
+                ),
+                (
+                    date!(2026 - 11 - 20),
+                    ScheduleEventKind::Maintenance,
+                    "HVAC filter"
+                ),
+                (
+                    date!(2026 - 12 - 20),
+                    ScheduleEventKind::Maintenance,
+                    "HVAC filter"
+                ),
+                (
+                    date!(2026 - 12 - 20),
+                    ScheduleEventKind::InsuranceRenewal,
+                    "Acme renewal"
+                ),
+            ]
+        );
+        assert_eq!(events[0].days_from_now, -137);
+        assert_eq!(events[5].tab, TabKind::House);
+        assert_eq!(events[1].week_start(), date!(2026 - 10 - 19));
+    }
+
+    fn appliance_defaults() -> Appliance {
+        Appliance {
+            id: ApplianceId::new(0),
+            name: String::new(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: None,
+            location: String::new(),
+            cost_cents: None,
+            notes: String::new(),
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            deleted_at: None,
+            recall_checked_at: None,
+        }
+    }
+
+    fn house_defaults() -> HouseProfile {
+        HouseProfile {
+            id: HouseProfileId::new(0),
+            nickname: String::new(),
+            address_line_1: String::new(),
+            address_line_2: String::new(),
+            city: String::new(),
+            state: String::new(),
+            postal_code: String::new(),
+            year_built: None,
+            square_feet: None,
+            lot_square_feet: None,
+            bedrooms: None,
+            bathrooms: None,
+            foundation_type: String::new(),
+            wiring_type: String::new(),
+            roof_type: String::new(),
+            exterior_type: String::new(),
+            heating_type: String::new(),
+            cooling_type: String::new(),
+            water_source: String::new(),
+            sewer_type: String::new(),
+            parking_type: String::new(),
+            basement_type: String::new(),
+            insurance_carrier: String::new(),
+            insurance_policy: String::new(),
+            insurance_renewal: None,
+            property_tax_cents: None,
+            hoa_name: String::new(),
+            hoa_fee_cents: None,
+            created_at: OffsetDateTime::UNIX_EPOCH,
+            updated_at: OffsetDateTime::UNIX_EPOCH,
+            latitude: None,
+            longitude: None,
+        }
+    }
+}
@@ -2,6 +2,7 @@
 // Licensed under the Apache License, Version 2.0
 
 use anyhow::{Result, bail};
+use std::collections::BTreeMap;
 
 use crate::{AppMode, FormKind, FormPayload, TabKind};
 
@@ -21,6 +22,40 @@ pub struct AppState {
     pub form_payload: Option<FormPayload>,
     pub form_submission_count: usize,
     pub form_return_mode: AppMode,
+    pub dirty: DirtyTabs,
+}
+
+/// Change stamps per tab. A view that cached a tab's rows at
+/// [`DirtyTabs::stamp`] may patch them with just the rows that changed, until
+/// [`DirtyTabs::changed_since`] says something it can't see happened (the
+/// deleted filter flipped, an import replaced every row) and the tab has to
+/// reload in full.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DirtyTabs {
+    clock: u64,
+    marked: BTreeMap<TabKind, u64>,
+}
+
+impl DirtyTabs {
+    pub fn stamp(&self) -> u64 {
+        self.clock
+    }
+
+    pub fn mark(&mut self, tab: TabKind) {
+        self.clock += 1;
+        self.marked.insert(tab, self.clock);
+    }
+
+    pub fn mark_all(&mut self) {
+        self.clock += 1;
+        for tab in TabKind::ALL {
+            self.marked.insert(tab, self.clock);
+        }
+    }
+
+    pub fn changed_since(&self, tab: TabKind, stamp: u64) -> bool {
+        self.marked.get(&tab).is_some_and(|marked| *marked > stamp)
+    }
 }
 
 impl Default for AppState {
@@ -34,6 +69,7 @@ impl Default for AppState {
             form_payload: None,
             form_submission_count: 0,
             form_return_mode: AppMode::Nav,
+            dirty: DirtyTabs::default(),
         }
     }
 }
@@ -56,6 +92,9 @@ pub enum AppCommand {
     CloseChat,
     SetStatus(String),
     ClearStatus,
+    /// Rows of one tab changed in a way a patch can't pick up.
+    MarkDirty(TabKind),
+    MarkAllDirty,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -69,6 +108,7 @@ pub enum AppEvent {
     FormCanceled(FormKind),
     StatusUpdated(String),
     StatusCleared,
+    TabsDirtied,
 }
 
 impl AppState {
@@ -144,6 +184,7 @@ impl AppState {
             }
             AppCommand::ToggleDeleted => {
                 self.show_deleted = !self.show_deleted;
+                self.dirty.mark_all();
                 let label = if self.show_deleted {
                     "deleted shown"
                 } else {
@@ -176,6 +217,14 @@ impl AppState {
                 self.status_line = Some(message.clone());
                 vec![AppEvent::StatusUpdated(message)]
             }
+            AppCommand::MarkDirty(tab) => {
+                self.dirty.mark(tab);
+                vec![AppEvent::TabsDirtied]
+            }
+            AppCommand::MarkAllDirty => {
+                self.dirty.mark_all();
+                vec![AppEvent::TabsDirtied]
+            }
         }
     }
 
@@ -246,7 +295,7 @@ mod tests {
     #[test]
     fn tab_rotation_wraps() {
         let mut state = AppState {
-            active_tab: TabKind::Settings,
+            active_tab: TabKind::Query,
             ..AppState::default()
         };
 
@@ -264,8 +313,8 @@ mod tests {
         assert_eq!(first, vec![AppEvent::TabChanged(TabKind::Dashboard)]);
 
         let last = state.dispatch(AppCommand::LastTab);
-        assert_eq!(state.active_tab, TabKind::Settings);
-        assert_eq!(last, vec![AppEvent::TabChanged(TabKind::Settings)]);
+        assert_eq!(state.active_tab, TabKind::Query);
+        assert_eq!(last, vec![AppEvent::TabChanged(TabKind::Query)]);
 
         let set = state.dispatch(AppCommand::SetActiveTab(TabKind::Maintenance));
         assert_eq!(state.active_tab, TabKind::Maintenance);
@@ -339,6 +388,7 @@ mod tests {
             end_date: None,
             budget_cents: Some(1_000_000),
             actual_cents: None,
+            parent_id: None,
         });
         state.dispatch(AppCommand::SetFormPayload(payload));
 
@@ -366,6 +416,7 @@ mod tests {
             end_date: None,
             budget_cents: Some(1_000_000),
             actual_cents: None,
+            parent_id: None,
         });
         state.dispatch(AppCommand::SetFormPayload(payload));
 
@@ -394,6 +445,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         });
         state.dispatch(AppCommand::SetFormPayload(payload));
 
@@ -462,6 +514,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn dirty_marks_only_count_after_the_stamp_they_follow() {
+        let mut state = AppState::default();
+        let loaded = state.dirty.stamp();
+        assert!(!state.dirty.changed_since(TabKind::Quotes, loaded));
+
+        let events = state.dispatch(AppCommand::MarkDirty(TabKind::Quotes));
+        assert_eq!(events, vec![AppEvent::TabsDirtied]);
+        assert!(state.dirty.changed_since(TabKind::Quotes, loaded));
+        assert!(!state.dirty.changed_since(TabKind::ServiceLog, loaded));
+
+        let reloaded = state.dirty.stamp();
+        assert!(!state.dirty.changed_since(TabKind::Quotes, reloaded));
+        state.dispatch(AppCommand::ToggleDeleted);
+        assert!(
+            TabKind::ALL
+                .into_iter()
+                .all(|tab| state.dirty.changed_since(tab, reloaded)),
+            "the deleted filter applies to every tab"
+        );
+    }
+
     #[test]
     fn validated_form_payload_returns_error_when_form_not_open() {
         let state = AppState::default();
@@ -0,0 +1,333 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Automatic backups: timestamped snapshots of the database in one
+//! directory, each checked with `PRAGMA integrity_check` and rotated down to
+//! the newest few. The timestamp in the file name is the only schedule, so
+//! nothing else has to be stored to know when the last backup ran.
+
+use anyhow::{Context, Result, bail};
+use micasa_db::{Store, check_integrity};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use time::OffsetDateTime;
+
+/// Where snapshots go, how many are kept, and how often the timer takes one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupPolicy {
+    pub dir: PathBuf,
+    pub keep: usize,
+    pub interval: Duration,
+}
+
+/// `house.db` at 2026-10-16 09:30:00 becomes `house-20261016-093000.db`.
+/// The fixed-width stamp makes name order the same as time order.
+fn snapshot_name(db_path: &Path, at: OffsetDateTime) -> String {
+    let (stem, extension) = name_parts(db_path);
+    format!(
+        "{stem}-{:04}{:02}{:02}-{:02}{:02}{:02}.{extension}",
+        at.year(),
+        u8::from(at.month()),
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second()
+    )
+}
+
+fn name_parts(db_path: &Path) -> (String, String) {
+    let stem = db_path
+        .file_stem()
+        .map_or_else(|| "micasa".into(), |stem| stem.to_string_lossy());
+    let extension = db_path
+        .extension()
+        .map_or_else(|| "db".into(), |extension| extension.to_string_lossy());
+    (stem.into_owned(), extension.into_owned())
+}
+
+/// Snapshots of `db_path` in `dir`, oldest first. Other files are ignored.
+pub fn list_backups(dir: &Path, db_path: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let (stem, extension) = name_parts(db_path);
+    let prefix = format!("{stem}-");
+    let suffix = format!(".{extension}");
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(dir).with_context(|| format!("read backups in {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let is_snapshot = name
+            .strip_prefix(&prefix)
+            .and_then(|rest| rest.strip_suffix(&suffix))
+            .is_some_and(|stamp| {
+                stamp.len() == 15
+                    && stamp.char_indices().all(|(at, ch)| {
+                        if at == 8 {
+                            ch == '-'
+                        } else {
+                            ch.is_ascii_digit()
+                        }
+                    })
+            });
+        if is_snapshot {
+            backups.push(path);
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+/// Writes a verified snapshot of `store` into the backup directory without
+/// rotating. A second call within the same second returns the same file.
+fn write_snapshot(
+    store: &Store,
+    db_path: &Path,
+    policy: &BackupPolicy,
+    now: OffsetDateTime,
+) -> Result<PathBuf> {
+    fs::create_dir_all(&policy.dir)
+        .with_context(|| format!("create backup directory {}", policy.dir.display()))?;
+    let path = policy.dir.join(snapshot_name(db_path, now));
+    if path.exists() {
+        return Ok(path);
+    }
+    store.backup_to(&path)?;
+    if let Err(error) = check_integrity(&path) {
+        let _ = fs::remove_file(&path);
+        return Err(error.context("backup was removed; check the disk and retry"));
+    }
+    Ok(path)
+}
+
+/// Takes a snapshot now, then deletes the oldest ones beyond `policy.keep`.
+pub fn snapshot(
+    store: &Store,
+    db_path: &Path,
+    policy: &BackupPolicy,
+    now: OffsetDateTime,
+) -> Result<PathBuf> {
+    let path = write_snapshot(store, db_path, policy, now)?;
+    let backups = list_backups(&policy.dir, db_path)?;
+    let excess = backups.len().saturating_sub(policy.keep);
+    for old in &backups[..excess] {
+        fs::remove_file(old).with_context(|| format!("remove old backup {}", old.display()))?;
+    }
+    Ok(path)
+}
+
+/// Takes a snapshot when the newest one is at least `policy.interval` old.
+pub fn snapshot_if_due(
+    store: &Store,
+    db_path: &Path,
+    policy: &BackupPolicy,
+    now: OffsetDateTime,
+) -> Result<Option<PathBuf>> {
+    let cutoff = snapshot_name(db_path, now - policy.interval);
+    let newest = list_backups(&policy.dir, db_path)?.pop();
+    let due = newest.is_none_or(|path| {
+        path.file_name()
+            .is_some_and(|name| name.to_string_lossy().as_ref() <= cutoff.as_str())
+    });
+    if !due {
+        return Ok(None);
+    }
+    snapshot(store, db_path, policy, now).map(Some)
+}
+
+/// Replaces the database at `db_path` with `source` (`latest` picks the
+/// newest snapshot). The source is verified first and the current database
+/// is snapshotted before it is overwritten; returns that safety copy.
+pub fn restore(
+    db_path: &Path,
+    source: &str,
+    policy: &BackupPolicy,
+    now: OffsetDateTime,
+) -> Result<Option<PathBuf>> {
+    let source = if source == "latest" {
+        list_backups(&policy.dir, db_path)?.pop().with_context(|| {
+            format!(
+                "no backups in {}; run `micasa backup` first",
+                policy.dir.display()
+            )
+        })?
+    } else {
+        PathBuf::from(source)
+    };
+    if !source.exists() {
+        bail!("backup {} does not exist", source.display());
+    }
+    check_integrity(&source).context("refusing to restore a damaged backup")?;
+
+    let safety = if db_path.exists() {
+        let store = Store::open(db_path)?;
+        // Not rotated: with a small `keep`, rotation could delete `source`.
+        Some(write_snapshot(&store, db_path, policy, now)?)
+    } else {
+        None
+    };
+    for suffix in ["-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let sidecar = PathBuf::from(sidecar);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar)
+                .with_context(|| format!("remove stale {}", sidecar.display()))?;
+        }
+    }
+    fs::copy(&source, db_path).with_context(|| {
+        format!(
+            "copy {} over {}; the previous database is kept in the backup directory",
+            source.display(),
+            db_path.display()
+        )
+    })?;
+    Ok(safety)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BackupPolicy, list_backups, restore, snapshot, snapshot_if_due};
+    use anyhow::Result;
+    use micasa_db::{NewVendor, Store};
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+    use time::macros::datetime;
+
+    fn vendor(name: &str) -> NewVendor {
+        NewVendor {
+            name: name.to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        }
+    }
+
+    fn house_store(path: &Path, name: &str) -> Result<Store> {
+        let store = Store::open(path)?;
+        store.bootstrap()?;
+        store.create_vendor(&vendor(name))?;
+        Ok(store)
+    }
+
+    #[test]
+    fn snapshots_rotate_to_the_newest_and_wait_for_the_interval() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let store = house_store(&db_path, "Acme Plumbing")?;
+        let policy = BackupPolicy {
+            dir: temp.path().join("backups"),
+            keep: 2,
+            interval: Duration::from_secs(60 * 60),
+        };
+        fs::create_dir_all(&policy.dir)?;
+        fs::write(policy.dir.join("notes.txt"), "not a backup")?;
+
+        let first = snapshot(
+            &store,
+            &db_path,
+            &policy,
+            datetime!(2026-10-16 09:30:00 UTC),
+        )?;
+        assert_eq!(
+            first.file_name().and_then(|name| name.to_str()),
+            Some("house-20261016-093000.db")
+        );
+        assert_eq!(
+            snapshot_if_due(
+                &store,
+                &db_path,
+                &policy,
+                datetime!(2026-10-16 10:00:00 UTC)
+            )?,
+            None
+        );
+        let due = datetime!(2026-10-16 10:30:00 UTC);
+        assert!(snapshot_if_due(&store, &db_path, &policy, due)?.is_some());
+        snapshot(
+            &store,
+            &db_path,
+            &policy,
+            datetime!(2026-10-16 11:00:00 UTC),
+        )?;
+
+        let kept = list_backups(&policy.dir, &db_path)?;
+        assert_eq!(kept.len(), 2);
+        assert!(!first.exists(), "oldest rotated out");
+        assert!(policy.dir.join("notes.txt").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn restore_verifies_the_source_and_keeps_the_current_database() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let policy = BackupPolicy {
+            dir: temp.path().join("backups"),
+            keep: 1,
+            interval: Duration::from_secs(60),
+        };
+        {
+            let store = house_store(&db_path, "Acme Plumbing")?;
+            snapshot(
+                &store,
+                &db_path,
+                &policy,
+                datetime!(2026-10-16 09:00:00 UTC),
+            )?;
+            store.create_vendor(&vendor("Later Roofing"))?;
+        }
+
+        let junk = temp.path().join("junk.db");
+        fs::write(&junk, b"definitely not sqlite, just some bytes long enough")?;
+        let error = restore(
+            &db_path,
+            &junk.to_string_lossy(),
+            &policy,
+            datetime!(2026-10-16 10:00:00 UTC),
+        )
+        .expect_err("damaged backup");
+        assert!(format!("{error:#}").contains("refusing to restore"));
+
+        let safety = restore(
+            &db_path,
+            "latest",
+            &policy,
+            datetime!(2026-10-16 10:00:00 UTC),
+        )?
+        .expect("current database is kept");
+        let names = |store: &Store| -> Result<Vec<String>> {
+            Ok(store
+                .list_vendors(false)?
+                .into_iter()
+                .map(|vendor| vendor.name)
+                .collect())
+        };
+        assert_eq!(names(&Store::open(&db_path)?)?, vec!["Acme Plumbing"]);
+        assert_eq!(names(&Store::open(&safety)?)?.len(), 2);
+        assert_eq!(list_backups(&policy.dir, &db_path)?.len(), 2, "not rotated");
+
+        let empty = BackupPolicy {
+            dir: temp.path().join("none"),
+            ..policy
+        };
+        let error = restore(
+            &db_path,
+            "latest",
+            &empty,
+            datetime!(2026-10-16 11:00:00 UTC),
+        )
+        .expect_err("no backups");
+        assert!(error.to_string().contains("run `micasa backup` first"));
+        Ok(())
+    }
+}
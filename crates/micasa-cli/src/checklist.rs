@@ -0,0 +1,242 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use crate::report::{escape_html, html_page, qr_figure};
+use anyhow::Result;
+use micasa_app::{DeepLink, DeletionEntity, MaintenanceItemId, TabKind, days_from_to, next_due};
+use micasa_db::Store;
+use std::collections::HashMap;
+use time::Date;
+
+/// How far ahead the checklist looks; matches the dashboard's upcoming window.
+pub const CHECKLIST_HORIZON_DAYS: i64 = 30;
+
+const PAGE_WIDTH: usize = 72;
+
+struct ChecklistLine {
+    id: MaintenanceItemId,
+    name: String,
+    context: String,
+    due: Option<Date>,
+    days_from_now: i64,
+}
+
+impl ChecklistLine {
+    fn when(&self) -> Option<String> {
+        let due = self.due?;
+        let relative = match self.days_from_now {
+            0 => "today".to_owned(),
+            days if days < 0 => format!("{} days overdue", -days),
+            days => format!("in {days} days"),
+        };
+        Some(format!("due {due}, {relative}"))
+    }
+}
+
+struct Checklist {
+    title: String,
+    sections: Vec<(String, Vec<ChecklistLine>)>,
+}
+
+/// Overdue, upcoming, and never-serviced maintenance. Private items are left
+/// off since the page is meant to be posted somewhere.
+fn collect_checklist(store: &Store, today: Date) -> Result<Checklist> {
+    let private = store.private_row_ids(DeletionEntity::Maintenance)?;
+    let categories = store
+        .list_maintenance_categories()?
+        .into_iter()
+        .map(|category| (category.id, category.name))
+        .collect::<HashMap<_, _>>();
+    let appliances = store
+        .list_appliances(false)?
+        .into_iter()
+        .map(|appliance| (appliance.id, appliance.name))
+        .collect::<HashMap<_, _>>();
+
+    let mut overdue = Vec::new();
+    let mut upcoming = Vec::new();
+    let mut unserviced = Vec::new();
+    for item in store.list_maintenance_with_schedule()? {
+        if private.contains(&item.id.get()) {
+            continue;
+        }
+        let context = [
+            categories.get(&item.category_id),
+            item.appliance_id.and_then(|id| appliances.get(&id)),
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+        let due = next_due(item.last_serviced_at, item.interval_months);
+        let line = ChecklistLine {
+            id: item.id,
+            name: item.name,
+            context,
+            due,
+            days_from_now: due.map_or(0, |due| days_from_to(today, due)),
+        };
+        match line.due {
+            None => unserviced.push(line),
+            Some(_) if line.days_from_now < 0 => overdue.push(line),
+            Some(_) if line.days_from_now <= CHECKLIST_HORIZON_DAYS => upcoming.push(line),
+            Some(_) => {}
+        }
+    }
+    overdue.sort_by_key(|line| line.days_from_now);
+    upcoming.sort_by_key(|line| line.days_from_now);
+
+    let title = match store.get_house_profile()? {
+        Some(house) if !house.nickname.trim().is_empty() => {
+            format!("{} maintenance checklist", house.nickname.trim())
+        }
+        _ => "Maintenance checklist".to_owned(),
+    };
+    let sections = [
+        ("OVERDUE".to_owned(), overdue),
+        (
+            format!("DUE IN THE NEXT {CHECKLIST_HORIZON_DAYS} DAYS"),
+            upcoming,
+        ),
+        ("NOT YET SERVICED".to_owned(), unserviced),
+    ]
+    .into_iter()
+    .filter(|(_, lines)| !lines.is_empty())
+    .collect();
+    Ok(Checklist { title, sections })
+}
+
+/// Plain-text checklist, one checkbox per item with room for handwritten
+/// notes.
+pub fn render_checklist(store: &Store, today: Date) -> Result<String> {
+    let checklist = collect_checklist(store, today)?;
+    let mut out = format!("{}\nprinted {today}\n", checklist.title);
+    out.push_str(&format!("{}\n", "=".repeat(PAGE_WIDTH)));
+    if checklist.sections.is_empty() {
+        out.push_str(&format!(
+            "\nNothing due in the next {CHECKLIST_HORIZON_DAYS} days.\n"
+        ));
+        return Ok(out);
+    }
+    for (heading, lines) in checklist.sections {
+        out.push_str(&format!("\n{heading}\n\n"));
+        for line in lines {
+            out.push_str(&format!("[ ] {}", line.name));
+            if !line.context.is_empty() {
+                out.push_str(&format!(" ({})", line.context));
+            }
+            out.push('\n');
+            if let Some(when) = line.when() {
+                out.push_str(&format!("    {when}\n"));
+            }
+            out.push_str(&format!(
+                "    done ________  notes {}\n\n",
+                "_".repeat(PAGE_WIDTH - 25)
+            ));
+        }
+    }
+    Ok(out)
+}
+
+/// The same checklist as a printable HTML page, with a QR code per item that
+/// opens it again via `micasa --open`.
+pub fn render_checklist_html(store: &Store, today: Date) -> Result<String> {
+    let checklist = collect_checklist(store, today)?;
+    let mut body = format!("<p>printed {today}</p>\n");
+    if checklist.sections.is_empty() {
+        body.push_str(&format!(
+            "<p>Nothing due in the next {CHECKLIST_HORIZON_DAYS} days.</p>\n"
+        ));
+    }
+    for (heading, lines) in checklist.sections {
+        body.push_str(&format!("<h2>{}</h2>\n", escape_html(&heading)));
+        for line in lines {
+            let link = DeepLink::new(TabKind::Maintenance, line.id.get())?;
+            body.push_str("<div class=\"item\">");
+            body.push_str(&qr_figure(&link)?);
+            body.push_str(&format!(
+                "<p>&#9744; <strong>{}</strong>",
+                escape_html(&line.name)
+            ));
+            if !line.context.is_empty() {
+                body.push_str(&format!(" ({})", escape_html(&line.context)));
+            }
+            if let Some(when) = line.when() {
+                body.push_str(&format!("<br>{}", escape_html(&when)));
+            }
+            body.push_str("</p><p class=\"blank\">done ________ notes</p></div>\n");
+        }
+    }
+    Ok(html_page(&checklist.title, &body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_checklist, render_checklist_html};
+    use anyhow::Result;
+    use micasa_app::MaintenanceItemId;
+    use micasa_db::{LifecycleEntityRef, NewMaintenanceItem, Store};
+    use time::macros::date;
+
+    fn item(store: &Store, name: &str, last: Option<time::Date>, months: i32) -> Result<i64> {
+        let category_id = store.list_maintenance_categories()?[0].id;
+        Ok(store
+            .create_maintenance_item(&NewMaintenanceItem {
+                name: name.to_owned(),
+                category_id,
+                appliance_id: None,
+                last_serviced_at: last,
+                interval_months: months,
+                manual_url: String::new(),
+                manual_text: String::new(),
+                notes: String::new(),
+                cost_cents: None,
+            })?
+            .get())
+    }
+
+    #[test]
+    fn checklist_groups_due_items_with_checkboxes() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        item(&store, "Clean gutters", Some(date!(2026 - 04 - 01)), 6)?;
+        item(
+            &store,
+            "Replace HVAC filter",
+            Some(date!(2026 - 08 - 01)),
+            3,
+        )?;
+        item(
+            &store,
+            "Flush water heater",
+            Some(date!(2026 - 06 - 01)),
+            12,
+        )?;
+        item(&store, "Test sump pump", None, 12)?;
+        let hidden = item(&store, "Check safe", Some(date!(2026 - 01 - 01)), 1)?;
+        store.set_row_private(
+            LifecycleEntityRef::MaintenanceItem(MaintenanceItemId::new(hidden)),
+            true,
+        )?;
+
+        let text = render_checklist(&store, date!(2026 - 10 - 16))?;
+        let overdue = text.find("OVERDUE").expect("overdue section");
+        let upcoming = text.find("DUE IN THE NEXT 30 DAYS").expect("upcoming");
+        let never = text.find("NOT YET SERVICED").expect("unserviced");
+        assert!(overdue < upcoming && upcoming < never);
+        assert!(text.contains("[ ] Clean gutters ("));
+        assert!(text.contains("    due 2026-10-01, 15 days overdue\n"));
+        assert!(text.contains("    due 2026-11-01, in 16 days\n"));
+        assert!(text.contains("[ ] Test sump pump"));
+        assert!(!text.contains("Flush water heater"));
+        assert!(!text.contains("Check safe"));
+        assert!(text.lines().all(|line| line.len() <= 72));
+
+        let html = render_checklist_html(&store, date!(2026 - 10 - 16))?;
+        assert!(html.contains("<h2>OVERDUE</h2>"));
+        assert!(html.contains("micasa://maint/1</figcaption>"));
+        assert_eq!(html.matches("<svg").count(), 3);
+        Ok(())
+    }
+}
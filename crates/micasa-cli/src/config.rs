@@ -2,15 +2,22 @@
 // Licensed under the Apache License, Version 2.0
 
 use anyhow::{Context, Result, anyhow, bail};
+use micasa_llm::LlmProvider;
+use micasa_tui::keymap::Keymap;
+use micasa_tui::theme::Theme;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 const CONFIG_VERSION: i64 = 2;
-const DEFAULT_LLM_BASE_URL: &str = "http://localhost:11434/v1";
 const DEFAULT_LLM_MODEL: &str = "qwen3";
+const DEFAULT_BUDGET_ALERT_THRESHOLD_PCT: i64 = 100;
+const DEFAULT_HOME_CURRENCY: &str = "USD";
+const DEFAULT_BACKUP_KEEP: i64 = 7;
+const DEFAULT_BACKUP_INTERVAL: &str = "24h";
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -21,6 +28,18 @@ pub struct Config {
     pub ui: Ui,
     #[serde(default)]
     pub llm: Llm,
+    #[serde(default)]
+    pub budget: Budget,
+    #[serde(default)]
+    pub currency: Currency,
+    #[serde(default)]
+    pub weather: Weather,
+    #[serde(default)]
+    pub recalls: Recalls,
+    #[serde(default)]
+    pub extraction: Extraction,
+    #[serde(default)]
+    pub backup: Backup,
 }
 
 impl Default for Config {
@@ -30,6 +49,12 @@ impl Default for Config {
             storage: Storage::default(),
             ui: Ui::default(),
             llm: Llm::default(),
+            budget: Budget::default(),
+            currency: Currency::default(),
+            weather: Weather::default(),
+            recalls: Recalls::default(),
+            extraction: Extraction::default(),
+            backup: Backup::default(),
         }
     }
 }
@@ -54,12 +79,23 @@ impl Default for Storage {
 #[derive(Debug, Clone, Deserialize)]
 pub struct Ui {
     pub show_dashboard: Option<bool>,
+    pub lock_after: Option<String>,
+    pub lock_passphrase_hash: Option<String>,
+    /// Unsalted digest from older releases; only read to ask for a new hash.
+    pub lock_passphrase_sha256: Option<String>,
+    pub theme: Option<String>,
+    pub palette: Option<BTreeMap<String, String>>,
 }
 
 impl Default for Ui {
     fn default() -> Self {
         Self {
             show_dashboard: Some(true),
+            lock_after: None,
+            lock_passphrase_hash: None,
+            lock_passphrase_sha256: None,
+            theme: None,
+            palette: None,
         }
     }
 }
@@ -67,20 +103,124 @@ impl Default for Ui {
 #[derive(Debug, Clone, Deserialize)]
 pub struct Llm {
     pub enabled: Option<bool>,
+    pub provider: Option<String>,
     pub base_url: Option<String>,
+    pub api_key: Option<String>,
     pub model: Option<String>,
     pub extra_context: Option<String>,
     pub timeout: Option<String>,
+    pub embedding_model: Option<String>,
 }
 
 impl Default for Llm {
     fn default() -> Self {
         Self {
             enabled: Some(true),
-            base_url: Some(DEFAULT_LLM_BASE_URL.to_owned()),
+            provider: Some(micasa_llm::DEFAULT_PROVIDER.to_owned()),
+            base_url: None,
+            api_key: None,
             model: Some(DEFAULT_LLM_MODEL.to_owned()),
             extra_context: Some(String::new()),
             timeout: Some("5s".to_owned()),
+            embedding_model: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Budget {
+    pub alert_threshold_pct: Option<i64>,
+    pub monthly_limit: Option<String>,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            alert_threshold_pct: Some(DEFAULT_BUDGET_ALERT_THRESHOLD_PCT),
+            monthly_limit: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Currency {
+    pub home: Option<String>,
+    pub rates_url: Option<String>,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self {
+            home: Some(DEFAULT_HOME_CURRENCY.to_owned()),
+            rates_url: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Weather {
+    pub enabled: Option<bool>,
+    pub provider: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            enabled: Some(false),
+            provider: Some(crate::weather::DEFAULT_WEATHER_PROVIDER.to_owned()),
+            base_url: Some(crate::weather::DEFAULT_WEATHER_BASE_URL.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Recalls {
+    pub provider: Option<String>,
+    pub base_url: Option<String>,
+    pub dataset_path: Option<String>,
+}
+
+impl Default for Recalls {
+    fn default() -> Self {
+        Self {
+            provider: Some(crate::recalls::DEFAULT_RECALL_PROVIDER.to_owned()),
+            base_url: Some(crate::recalls::DEFAULT_CPSC_BASE_URL.to_owned()),
+            dataset_path: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Extraction {
+    pub enabled: Option<bool>,
+    pub pdftotext: Option<String>,
+    pub tesseract: Option<String>,
+}
+
+impl Default for Extraction {
+    fn default() -> Self {
+        Self {
+            enabled: Some(false),
+            pdftotext: Some(crate::extract::DEFAULT_PDFTOTEXT.to_owned()),
+            tesseract: Some(crate::extract::DEFAULT_TESSERACT.to_owned()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Backup {
+    pub dir: Option<String>,
+    pub keep: Option<i64>,
+    pub interval: Option<String>,
+}
+
+impl Default for Backup {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            keep: Some(DEFAULT_BACKUP_KEEP),
+            interval: Some(DEFAULT_BACKUP_INTERVAL.to_owned()),
         }
     }
 }
@@ -169,6 +309,164 @@ impl Config {
             );
         }
 
+        if let Some(lock_after) = &self.ui.lock_after {
+            let parsed = parse_duration(lock_after)?;
+            if parsed <= Duration::ZERO {
+                bail!(
+                    "ui.lock_after in {} must be positive, got {}",
+                    path.display(),
+                    lock_after
+                );
+            }
+        }
+
+        if self.ui.lock_passphrase_sha256.is_some() {
+            bail!(
+                "ui.lock_passphrase_sha256 in {} is no longer supported; run `micasa --hash-passphrase` again and save the output as ui.lock_passphrase_hash",
+                path.display()
+            );
+        }
+        if let Some(hash) = &self.ui.lock_passphrase_hash {
+            micasa_db::crypto::parse_passphrase_hash(hash).with_context(|| {
+                format!(
+                    "invalid ui.lock_passphrase_hash in {}; generate it with `micasa --hash-passphrase`",
+                    path.display()
+                )
+            })?;
+        }
+
+        let themes = self
+            .themes()
+            .with_context(|| format!("invalid [ui.palette] in {}", path.display()))?;
+        if !themes.iter().any(|theme| theme.name == self.theme_name()) {
+            bail!(
+                "ui.theme in {} is {:?}; use one of: {} (custom needs a [ui.palette] table)",
+                path.display(),
+                self.theme_name(),
+                themes
+                    .iter()
+                    .map(|theme| theme.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+
+        if let Some(threshold) = self.budget.alert_threshold_pct
+            && !(1..=1000).contains(&threshold)
+        {
+            bail!(
+                "budget.alert_threshold_pct in {} must be between 1 and 1000, got {}",
+                path.display(),
+                threshold
+            );
+        }
+
+        if let Some(limit) = &self.budget.monthly_limit {
+            match micasa_db::validation::parse_required_cents(limit) {
+                Ok(cents) if cents > 0 => {}
+                _ => bail!(
+                    "budget.monthly_limit in {} must be a positive amount like \"1500.00\", got {:?}",
+                    path.display(),
+                    limit
+                ),
+            }
+        }
+
+        if let Some(home) = &self.currency.home
+            && micasa_db::currency::parse_currency_code(home).is_err()
+        {
+            bail!(
+                "currency.home in {} must be a three-letter code like \"USD\", got {:?}",
+                path.display(),
+                home
+            );
+        }
+
+        if let Some(url) = &self.currency.rates_url
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            bail!(
+                "currency.rates_url in {} must be an http(s) URL, got {:?}",
+                path.display(),
+                url
+            );
+        }
+
+        if let Some(provider) = &self.weather.provider
+            && !crate::weather::WEATHER_PROVIDERS.contains(&provider.as_str())
+        {
+            bail!(
+                "weather.provider in {} must be one of {:?}, got {:?}",
+                path.display(),
+                crate::weather::WEATHER_PROVIDERS,
+                provider
+            );
+        }
+
+        if let Some(url) = &self.weather.base_url
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            bail!(
+                "weather.base_url in {} must be an http(s) URL, got {:?}",
+                path.display(),
+                url
+            );
+        }
+
+        if let Some(provider) = &self.recalls.provider
+            && !crate::recalls::RECALL_PROVIDERS.contains(&provider.as_str())
+        {
+            bail!(
+                "recalls.provider in {} must be one of {:?}, got {:?}",
+                path.display(),
+                crate::recalls::RECALL_PROVIDERS,
+                provider
+            );
+        }
+
+        for (key, program) in [
+            ("pdftotext", &self.extraction.pdftotext),
+            ("tesseract", &self.extraction.tesseract),
+        ] {
+            if program
+                .as_deref()
+                .is_some_and(|program| program.trim().is_empty())
+            {
+                bail!(
+                    "extraction.{key} in {} must name a program; remove it to use {key:?} from PATH",
+                    path.display()
+                );
+            }
+        }
+
+        if self.recalls_provider() == "dataset" && self.recalls.dataset_path.is_none() {
+            bail!(
+                "recalls.provider = \"dataset\" in {} needs recalls.dataset_path",
+                path.display()
+            );
+        }
+
+        if let Some(url) = &self.recalls.base_url
+            && !(url.starts_with("http://") || url.starts_with("https://"))
+        {
+            bail!(
+                "recalls.base_url in {} must be an http(s) URL, got {:?}",
+                path.display(),
+                url
+            );
+        }
+
+        if let Some(provider) = &self.llm.provider
+            && !micasa_llm::PROVIDERS.contains(&provider.as_str())
+        {
+            bail!(
+                "llm.provider in {} must be one of {:?}, got {:?}",
+                path.display(),
+                micasa_llm::PROVIDERS,
+                provider
+            );
+        }
+
         if let Some(timeout) = &self.llm.timeout {
             let parsed = parse_duration(timeout)?;
             if parsed <= Duration::ZERO {
@@ -180,6 +478,36 @@ impl Config {
             }
         }
 
+        if let Some(keep) = self.backup.keep
+            && keep < 1
+        {
+            bail!(
+                "backup.keep in {} must be at least 1, got {}",
+                path.display(),
+                keep
+            );
+        }
+
+        if let Some(interval) = &self.backup.interval {
+            let parsed = parse_duration(interval)?;
+            if parsed <= Duration::ZERO {
+                bail!(
+                    "backup.interval in {} must be positive, got {}",
+                    path.display(),
+                    interval
+                );
+            }
+        }
+
+        if let Some(dir) = &self.backup.dir
+            && dir.trim().is_empty()
+        {
+            bail!(
+                "backup.dir in {} must name a directory; remove it to use backups/ next to the database",
+                path.display()
+            );
+        }
+
         Ok(())
     }
 
@@ -194,6 +522,130 @@ impl Config {
         self.ui.show_dashboard.unwrap_or(true)
     }
 
+    pub fn lock_after(&self) -> Result<Option<Duration>> {
+        self.ui
+            .lock_after
+            .as_deref()
+            .map(parse_duration)
+            .transpose()
+    }
+
+    pub fn lock_passphrase_hash(&self) -> Option<&str> {
+        self.ui.lock_passphrase_hash.as_deref()
+    }
+
+    pub fn theme_name(&self) -> &str {
+        self.ui.theme.as_deref().map_or("dark", str::trim)
+    }
+
+    /// Built-in palettes, plus `custom` when `[ui.palette]` is set.
+    pub fn themes(&self) -> Result<Vec<Theme>> {
+        let mut themes = Theme::presets();
+        if let Some(palette) = &self.ui.palette {
+            themes.push(Theme::custom(palette)?);
+        }
+        Ok(themes)
+    }
+
+    pub fn budget_alert_threshold_pct(&self) -> i64 {
+        self.budget
+            .alert_threshold_pct
+            .unwrap_or(DEFAULT_BUDGET_ALERT_THRESHOLD_PCT)
+    }
+
+    pub fn budget_monthly_limit_cents(&self) -> Option<i64> {
+        let limit = self.budget.monthly_limit.as_deref()?;
+        micasa_db::validation::parse_required_cents(limit).ok()
+    }
+
+    pub fn home_currency(&self) -> String {
+        self.currency
+            .home
+            .as_deref()
+            .and_then(|home| micasa_db::currency::parse_currency_code(home).ok())
+            .unwrap_or_else(|| DEFAULT_HOME_CURRENCY.to_owned())
+    }
+
+    pub fn currency_rates_url(&self) -> Option<&str> {
+        self.currency.rates_url.as_deref()
+    }
+
+    pub fn weather_enabled(&self) -> bool {
+        self.weather.enabled.unwrap_or(false)
+    }
+
+    pub fn weather_provider(&self) -> &str {
+        self.weather
+            .provider
+            .as_deref()
+            .unwrap_or(crate::weather::DEFAULT_WEATHER_PROVIDER)
+    }
+
+    pub fn weather_base_url(&self) -> &str {
+        self.weather
+            .base_url
+            .as_deref()
+            .unwrap_or(crate::weather::DEFAULT_WEATHER_BASE_URL)
+    }
+
+    pub fn recalls_provider(&self) -> &str {
+        self.recalls
+            .provider
+            .as_deref()
+            .unwrap_or(crate::recalls::DEFAULT_RECALL_PROVIDER)
+    }
+
+    pub fn recalls_base_url(&self) -> &str {
+        self.recalls
+            .base_url
+            .as_deref()
+            .unwrap_or(crate::recalls::DEFAULT_CPSC_BASE_URL)
+    }
+
+    pub fn recalls_dataset_path(&self) -> Option<PathBuf> {
+        self.recalls.dataset_path.as_deref().map(PathBuf::from)
+    }
+
+    /// Where and how often automatic backups go for the database at
+    /// `db_path`; `backup.dir` defaults to `backups/` beside it.
+    pub fn backup_policy(&self, db_path: &Path) -> Result<crate::backup::BackupPolicy> {
+        let dir = match &self.backup.dir {
+            Some(dir) => PathBuf::from(dir),
+            None => db_path
+                .parent()
+                .unwrap_or_else(|| Path::new("."))
+                .join("backups"),
+        };
+        let keep = self.backup.keep.unwrap_or(DEFAULT_BACKUP_KEEP);
+        Ok(crate::backup::BackupPolicy {
+            dir,
+            keep: usize::try_from(keep).context("backup.keep was validated on load")?,
+            interval: parse_duration(
+                self.backup
+                    .interval
+                    .as_deref()
+                    .unwrap_or(DEFAULT_BACKUP_INTERVAL),
+            )?,
+        })
+    }
+
+    /// The document text extractor, when `[extraction]` is enabled.
+    pub fn text_extractor(&self) -> Option<crate::extract::TextExtractor> {
+        if !self.extraction.enabled.unwrap_or(false) {
+            return None;
+        }
+        Some(crate::extract::TextExtractor::new(
+            self.extraction
+                .pdftotext
+                .as_deref()
+                .unwrap_or(crate::extract::DEFAULT_PDFTOTEXT),
+            self.extraction
+                .tesseract
+                .as_deref()
+                .unwrap_or(crate::extract::DEFAULT_TESSERACT),
+        ))
+    }
+
     pub fn max_document_size(&self) -> i64 {
         self.storage
             .max_document_size
@@ -208,12 +660,26 @@ impl Config {
         self.llm.enabled.unwrap_or(true)
     }
 
-    pub fn llm_base_url(&self) -> &str {
+    pub fn llm_provider(&self) -> &str {
         self.llm
-            .base_url
+            .provider
             .as_deref()
-            .unwrap_or(DEFAULT_LLM_BASE_URL)
-            .trim_end_matches('/')
+            .unwrap_or(micasa_llm::DEFAULT_PROVIDER)
+    }
+
+    /// `llm.base_url`, or the provider's usual local address.
+    pub fn llm_base_url(&self) -> &str {
+        match &self.llm.base_url {
+            Some(url) => url.trim_end_matches('/'),
+            None => micasa_llm::provider_named(self.llm_provider())
+                .map_or(micasa_llm::Ollama.default_base_url(), |provider| {
+                    provider.default_base_url()
+                }),
+        }
+    }
+
+    pub fn llm_api_key(&self) -> Option<&str> {
+        self.llm.api_key.as_deref()
     }
 
     pub fn llm_model(&self) -> &str {
@@ -228,18 +694,94 @@ impl Config {
         self.llm.extra_context.as_deref().unwrap_or("")
     }
 
+    /// `llm.embedding_model`; unset or blank leaves document retrieval off.
+    pub fn llm_embedding_model(&self) -> Option<&str> {
+        self.llm
+            .embedding_model
+            .as_deref()
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+    }
+
     pub fn example_config(path: &Path) -> String {
         format!(
-            "# micasa Rust config\n# Place this file at: {}\n\nversion = 2\n\n[storage]\n# Optional. Default is platform data dir (for example ~/.local/share/micasa/micasa.db)\n# db_path = \"/absolute/path/to/micasa.db\"\nmax_document_size = {}\ncache_ttl_days = 30\n\n[ui]\nshow_dashboard = true\n\n[llm]\nenabled = true\nbase_url = \"{}\"\nmodel = \"{}\"\nextra_context = \"\"\ntimeout = \"5s\"\n",
+            "# micasa Rust config\n# Place this file at: {}\n\nversion = 2\n\n[storage]\n# Optional. Default is platform data dir (for example ~/.local/share/micasa/micasa.db)\n# db_path = \"/absolute/path/to/micasa.db\"\nmax_document_size = {}\ncache_ttl_days = 30\n\n[ui]\nshow_dashboard = true\n# dark, light, high-contrast, or custom (built from [ui.palette])\ntheme = \"dark\"\n# Optional. Blank the screen after this much idle time (ctrl+l locks on demand)\n# lock_after = \"15m\"\n# Optional. Output of `micasa --hash-passphrase`; without it any enter unlocks\n# lock_passphrase_hash = \"\"\n# Optional. Colors for theme = \"custom\", starting from the base preset\n# [ui.palette]\n# base = \"dark\"\n# accent = \"#1e90ff\"\n\n[llm]\nenabled = true\n# ollama, openai (any OpenAI-compatible server), or llamacpp; switch in chat\n# with /provider\nprovider = \"{}\"\nbase_url = \"{}\"\n# Optional. Sent as a bearer token, for hosted OpenAI-compatible APIs\n# api_key = \"\"\nmodel = \"{}\"\nextra_context = \"\"\ntimeout = \"5s\"\n# Optional. Embeds document text so chat can quote it; rebuild with\n# `micasa --reindex-documents`\n# embedding_model = \"nomic-embed-text\"\n\n[budget]\n# Warn when a new quote or service cost reaches this share of a budget\nalert_threshold_pct = 100\n# Optional. Monthly cap on service-log spend\n# monthly_limit = \"500.00\"\n\n[currency]\n# Totals and reports are normalized to this currency\nhome = \"{}\"\n# Optional. JSON source for `micasa --fetch-rates`; {{home}} is replaced\n# rates_url = \"https://api.frankfurter.app/latest?from={{home}}\"\n\n[weather]\n# Opt-in. Flags weather-sensitive maintenance on the dashboard; set the\n# house location with `micasa --set-location LAT,LON`\nenabled = false\nprovider = \"{}\"\nbase_url = \"{}\"\n\n[recalls]\n# Source for `micasa --check-recalls`: \"cpsc\" or \"dataset\"\nprovider = \"{}\"\nbase_url = \"{}\"\n# Required when provider = \"dataset\"; a JSON array of {{id, title, brand, models}}\n# dataset_path = \"/absolute/path/to/recalls.json\"\n\n[extraction]\n# Opt-in. Reads text out of saved PDFs (pdftotext) and images (tesseract) for\n# search and chat\nenabled = false\npdftotext = \"{}\"\ntesseract = \"{}\"\n\n[backup]\n# Optional. Default is a backups/ directory next to the database\n# dir = \"/absolute/path/to/backups\"\n# Snapshots kept; older ones are deleted. Turn automatic backups off on\n# the Settings tab\nkeep = {}\ninterval = \"{}\"\n",
             path.display(),
             micasa_db::MAX_DOCUMENT_SIZE,
-            DEFAULT_LLM_BASE_URL,
+            micasa_llm::DEFAULT_PROVIDER,
+            micasa_llm::Ollama.default_base_url(),
             DEFAULT_LLM_MODEL,
+            DEFAULT_HOME_CURRENCY,
+            crate::weather::DEFAULT_WEATHER_PROVIDER,
+            crate::weather::DEFAULT_WEATHER_BASE_URL,
+            crate::recalls::DEFAULT_RECALL_PROVIDER,
+            crate::recalls::DEFAULT_CPSC_BASE_URL,
+            crate::extract::DEFAULT_PDFTOTEXT,
+            crate::extract::DEFAULT_TESSERACT,
+            DEFAULT_BACKUP_KEEP,
+            DEFAULT_BACKUP_INTERVAL,
         )
     }
 }
 
-fn parse_duration(raw: &str) -> Result<Duration> {
+/// keys.toml lives next to config.toml so `--config` relocates both.
+pub fn keymap_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("keys.toml")
+}
+
+/// reports.toml sits there too; see `report::load_reports`.
+pub fn reports_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("reports.toml")
+}
+
+/// plugins.toml too; see `plugin::load_plugins`.
+pub fn plugins_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name("plugins.toml")
+}
+
+/// Loads key overrides from `path`; a missing file means the default keys.
+/// Each entry maps an action name to a chord or a list of chords, and an
+/// empty list unbinds the action.
+pub fn load_keymap(path: &Path) -> Result<Keymap> {
+    if !path.exists() {
+        return Ok(Keymap::default());
+    }
+
+    let raw =
+        fs::read_to_string(path).with_context(|| format!("read keys file {}", path.display()))?;
+    let table: toml::Table =
+        toml::from_str(&raw).with_context(|| format!("parse TOML keys {}", path.display()))?;
+    keymap_from_table(&table, &path.display().to_string())
+}
+
+/// Builds a keymap from keys.toml entries; `source` names where they came
+/// from in error messages.
+pub fn keymap_from_table(table: &toml::Table, source: &str) -> Result<Keymap> {
+    let mut overrides = Vec::with_capacity(table.len());
+    for (action, value) in table {
+        let chords = match value {
+            toml::Value::String(chord) => vec![chord.clone()],
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    toml::Value::String(chord) => Ok(chord.clone()),
+                    other => Err(anyhow!(
+                        "`{action}` in {source} has a non-string chord {other}; use strings like \"ctrl+d\""
+                    )),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            other => bail!(
+                "`{action}` in {source} must be a chord string or a list of them, got {other}"
+            ),
+        };
+        overrides.push((action, chords));
+    }
+
+    Keymap::with_overrides(overrides)
+        .with_context(|| format!("invalid keys file {source}; fix or remove the listed entries"))
+}
+
+pub(crate) fn parse_duration(raw: &str) -> Result<Duration> {
     if let Some(value) = raw.strip_suffix("ms") {
         let millis: u64 = value
             .parse()
@@ -258,14 +800,22 @@ fn parse_duration(raw: &str) -> Result<Duration> {
             .with_context(|| format!("invalid timeout duration {raw:?}"))?;
         return Ok(Duration::from_secs(mins * 60));
     }
+    if let Some(value) = raw.strip_suffix('h') {
+        let hours: u64 = value
+            .parse()
+            .with_context(|| format!("invalid timeout duration {raw:?}"))?;
+        return Ok(Duration::from_secs(hours * 60 * 60));
+    }
 
-    bail!("invalid duration {raw:?}; use one of: <N>ms, <N>s, <N>m (for example 500ms or 5s)")
+    bail!("invalid duration {raw:?}; use one of: <N>ms, <N>s, <N>m, <N>h (for example 500ms or 5s)")
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, parse_duration};
+    use super::{Config, keymap_path, load_keymap, parse_duration};
     use anyhow::Result;
+    use micasa_tui::keymap::{Action, Keymap};
+    use micasa_tui::theme::Theme;
     use std::path::PathBuf;
     use std::sync::{Mutex, OnceLock};
     use std::time::Duration;
@@ -447,10 +997,44 @@ mod tests {
     }
 
     #[test]
-    fn llm_timeout_parses_ms_seconds_and_minutes() -> Result<()> {
+    fn llm_embedding_model_is_off_unless_set_to_a_name() -> Result<()> {
+        assert_eq!(Config::default().llm_embedding_model(), None);
+        let (_temp, path) = write_config("version = 2\n[llm]\nembedding_model = \"  \"\n")?;
+        assert_eq!(Config::load(&path)?.llm_embedding_model(), None);
+        let (_temp, path) =
+            write_config("version = 2\n[llm]\nembedding_model = \"nomic-embed-text\"\n")?;
+        assert_eq!(
+            Config::load(&path)?.llm_embedding_model(),
+            Some("nomic-embed-text")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn llm_provider_picks_its_default_url_and_rejects_unknown_names() -> Result<()> {
+        assert_eq!(Config::default().llm_provider(), "ollama");
+        assert_eq!(
+            Config::default().llm_base_url(),
+            "http://localhost:11434/v1"
+        );
+
+        let (_temp, path) = write_config("version = 2\n[llm]\nprovider = \"llamacpp\"\n")?;
+        let config = Config::load(&path)?;
+        assert_eq!(config.llm_base_url(), "http://localhost:8080/v1");
+        assert_eq!(config.llm_api_key(), None);
+
+        let (_temp, path) = write_config("version = 2\n[llm]\nprovider = \"claude\"\n")?;
+        let error = Config::load(&path).expect_err("unknown provider should fail");
+        assert!(error.to_string().contains("llm.provider"));
+        Ok(())
+    }
+
+    #[test]
+    fn llm_timeout_parses_ms_seconds_minutes_and_hours() -> Result<()> {
         assert_eq!(parse_duration("500ms")?, Duration::from_millis(500));
         assert_eq!(parse_duration("5s")?, Duration::from_secs(5));
         assert_eq!(parse_duration("2m")?, Duration::from_secs(120));
+        assert_eq!(parse_duration("6h")?, Duration::from_secs(6 * 60 * 60));
         Ok(())
     }
 
@@ -474,6 +1058,203 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn lock_settings_parse_and_validate() -> Result<()> {
+        let hash = micasa_db::crypto::hash_passphrase("correct horse")?;
+        let (_temp, path) = write_config(&format!(
+            "version = 2\n[ui]\nlock_after = \"15m\"\nlock_passphrase_hash = \"{hash}\"\n"
+        ))?;
+        let config = Config::load(&path)?;
+        assert_eq!(config.lock_after()?, Some(Duration::from_secs(900)));
+        assert_eq!(config.lock_passphrase_hash(), Some(hash.as_str()));
+
+        let (_temp, path) =
+            write_config("version = 2\n[ui]\nlock_passphrase_hash = \"hunter2\"\n")?;
+        let error = Config::load(&path).expect_err("plain passphrase should be rejected");
+        assert!(error.to_string().contains("--hash-passphrase"));
+
+        let (_temp, path) = write_config(&format!(
+            "version = 2\n[ui]\nlock_passphrase_sha256 = \"{}\"\n",
+            "a".repeat(64)
+        ))?;
+        let error = Config::load(&path).expect_err("unsalted digest should be rejected");
+        assert!(error.to_string().contains("ui.lock_passphrase_hash"));
+
+        let (_temp, path) = write_config("version = 2\n[ui]\nlock_after = \"0m\"\n")?;
+        let error = Config::load(&path).expect_err("zero lock delay should fail");
+        assert!(error.to_string().contains("ui.lock_after"));
+        Ok(())
+    }
+
+    #[test]
+    fn theme_settings_parse_and_validate() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert_eq!(defaults.theme_name(), "dark");
+        assert_eq!(defaults.themes()?.len(), 3);
+
+        let (_temp, path) = write_config(
+            "version = 2\n[ui]\ntheme = \"custom\"\n[ui.palette]\nbase = \"light\"\naccent = \"#1e90ff\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert_eq!(config.theme_name(), "custom");
+        let themes = config.themes()?;
+        assert_eq!(
+            themes.last(),
+            Some(&Theme::custom([("base", "light"), ("accent", "#1e90ff")])?)
+        );
+
+        let (_temp, path) = write_config("version = 2\n[ui]\ntheme = \"custom\"\n")?;
+        let error = Config::load(&path).expect_err("custom without palette should fail");
+        assert!(error.to_string().contains("ui.theme"));
+        assert!(error.to_string().contains("dark, light, high-contrast"));
+
+        let (_temp, path) = write_config("version = 2\n[ui.palette]\nborders = \"red\"\n")?;
+        let error = Config::load(&path).expect_err("unknown palette slot should fail");
+        assert!(format!("{error:#}").contains("unknown palette entry `borders`"));
+        Ok(())
+    }
+
+    #[test]
+    fn budget_settings_parse_and_validate() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert_eq!(defaults.budget_alert_threshold_pct(), 100);
+        assert_eq!(defaults.budget_monthly_limit_cents(), None);
+
+        let (_temp, path) = write_config(
+            "version = 2\n[budget]\nalert_threshold_pct = 80\nmonthly_limit = \"1500.00\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert_eq!(config.budget_alert_threshold_pct(), 80);
+        assert_eq!(config.budget_monthly_limit_cents(), Some(150_000));
+
+        let (_temp, path) = write_config("version = 2\n[budget]\nalert_threshold_pct = 0\n")?;
+        let error = Config::load(&path).expect_err("zero threshold should fail");
+        assert!(error.to_string().contains("budget.alert_threshold_pct"));
+
+        let (_temp, path) = write_config("version = 2\n[budget]\nmonthly_limit = \"lots\"\n")?;
+        let error = Config::load(&path).expect_err("non-money limit should fail");
+        assert!(error.to_string().contains("positive amount"));
+        Ok(())
+    }
+
+    #[test]
+    fn currency_settings_parse_and_validate() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert_eq!(defaults.home_currency(), "USD");
+        assert_eq!(defaults.currency_rates_url(), None);
+
+        let (_temp, path) = write_config(
+            "version = 2\n[currency]\nhome = \"cad\"\nrates_url = \"https://rates.example/{home}\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert_eq!(config.home_currency(), "CAD");
+        assert_eq!(
+            config.currency_rates_url(),
+            Some("https://rates.example/{home}")
+        );
+
+        let (_temp, path) = write_config("version = 2\n[currency]\nhome = \"dollars\"\n")?;
+        let error = Config::load(&path).expect_err("long code should fail");
+        assert!(error.to_string().contains("currency.home"));
+
+        let (_temp, path) = write_config("version = 2\n[currency]\nrates_url = \"ftp://x\"\n")?;
+        let error = Config::load(&path).expect_err("non-http url should fail");
+        assert!(error.to_string().contains("currency.rates_url"));
+        Ok(())
+    }
+
+    #[test]
+    fn weather_settings_are_opt_in_and_validated() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert!(!defaults.weather_enabled());
+        assert_eq!(defaults.weather_provider(), "open-meteo");
+
+        let (_temp, path) = write_config(
+            "version = 2\n[weather]\nenabled = true\nbase_url = \"http://localhost:8080/forecast\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert!(config.weather_enabled());
+        assert_eq!(config.weather_base_url(), "http://localhost:8080/forecast");
+
+        let (_temp, path) = write_config("version = 2\n[weather]\nprovider = \"darksky\"\n")?;
+        let error = Config::load(&path).expect_err("unknown provider should fail");
+        assert!(error.to_string().contains("weather.provider"));
+        Ok(())
+    }
+
+    #[test]
+    fn extraction_is_opt_in_and_rejects_blank_programs() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert_eq!(defaults.text_extractor(), None);
+
+        let (_temp, path) = write_config(
+            "version = 2\n[extraction]\nenabled = true\ntesseract = \"/opt/bin/tesseract\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert_eq!(
+            config.text_extractor(),
+            Some(crate::extract::TextExtractor::new(
+                "pdftotext",
+                "/opt/bin/tesseract"
+            ))
+        );
+
+        let (_temp, path) = write_config("version = 2\n[extraction]\npdftotext = \" \"\n")?;
+        let error = Config::load(&path).expect_err("blank program should fail");
+        assert!(error.to_string().contains("extraction.pdftotext"));
+        Ok(())
+    }
+
+    #[test]
+    fn backups_default_beside_the_database_and_reject_keep_zero() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        let policy = defaults.backup_policy(&PathBuf::from("/data/micasa/house.db"))?;
+        assert_eq!(policy.dir, PathBuf::from("/data/micasa/backups"));
+        assert_eq!(policy.keep, 7);
+        assert_eq!(policy.interval, Duration::from_secs(24 * 60 * 60));
+
+        let (_temp, path) = write_config(
+            "version = 2\n[backup]\ndir = \"/mnt/nas/micasa\"\nkeep = 3\ninterval = \"6h\"\n",
+        )?;
+        let policy = Config::load(&path)?.backup_policy(&PathBuf::from("/data/house.db"))?;
+        assert_eq!(policy.dir, PathBuf::from("/mnt/nas/micasa"));
+        assert_eq!(policy.keep, 3);
+        assert_eq!(policy.interval, Duration::from_secs(6 * 60 * 60));
+
+        let (_temp, path) = write_config("version = 2\n[backup]\nkeep = 0\n")?;
+        let error = Config::load(&path).expect_err("keep = 0 should fail");
+        assert!(error.to_string().contains("backup.keep"));
+        Ok(())
+    }
+
+    #[test]
+    fn recall_settings_default_to_cpsc_and_require_a_dataset_path() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let defaults = Config::load(&temp.path().join("missing.toml"))?;
+        assert_eq!(defaults.recalls_provider(), "cpsc");
+        assert_eq!(defaults.recalls_dataset_path(), None);
+
+        let (_temp, path) = write_config("version = 2\n[recalls]\nprovider = \"dataset\"\n")?;
+        let error = Config::load(&path).expect_err("dataset without a path should fail");
+        assert!(error.to_string().contains("recalls.dataset_path"));
+
+        let (_temp, path) = write_config(
+            "version = 2\n[recalls]\nprovider = \"dataset\"\ndataset_path = \"/tmp/recalls.json\"\n",
+        )?;
+        let config = Config::load(&path)?;
+        assert_eq!(
+            config.recalls_dataset_path(),
+            Some(PathBuf::from("/tmp/recalls.json"))
+        );
+        Ok(())
+    }
+
     #[test]
     fn storage_limits_are_validated() -> Result<()> {
         let (_temp, path) =
@@ -498,4 +1279,64 @@ mod tests {
         assert!(example.contains("[llm]"));
         Ok(())
     }
+
+    fn write_keys(content: &str) -> Result<(tempfile::TempDir, PathBuf)> {
+        let temp = tempfile::tempdir()?;
+        let path = keymap_path(&temp.path().join("config.toml"));
+        std::fs::write(&path, content)?;
+        Ok((temp, path))
+    }
+
+    #[test]
+    fn keymap_path_sits_next_to_config() {
+        assert_eq!(
+            keymap_path(&PathBuf::from("/etc/micasa/config.toml")),
+            PathBuf::from("/etc/micasa/keys.toml")
+        );
+    }
+
+    #[test]
+    fn missing_keys_file_uses_default_keymap() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        assert_eq!(
+            load_keymap(&temp.path().join("keys.toml"))?,
+            Keymap::default()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keys_file_accepts_single_and_list_chords() -> Result<()> {
+        let (_temp, path) =
+            write_keys("next_tab = \"O\"\nhalf_page_down = [\"ctrl+d\", \"J\"]\nqr_link = []\n")?;
+        let keymap = load_keymap(&path)?;
+        assert_eq!(
+            keymap
+                .chords(Action::NextTab)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec!["O"]
+        );
+        assert_eq!(keymap.chords(Action::HalfPageDown).len(), 2);
+        assert!(keymap.chords(Action::QrLink).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn keys_file_rejects_unknown_actions_and_conflicts() -> Result<()> {
+        let (_temp, path) = write_keys("launch_rockets = \"x\"\n")?;
+        let error = format!("{:#}", load_keymap(&path).expect_err("unknown action"));
+        assert!(error.contains("keys.toml"), "{error}");
+        assert!(error.contains("unknown action `launch_rockets`"), "{error}");
+
+        let (_temp, path) = write_keys("next_tab = \"b\"\n")?;
+        let error = format!("{:#}", load_keymap(&path).expect_err("conflict"));
+        assert!(error.contains("key conflicts"), "{error}");
+
+        let (_temp, path) = write_keys("next_tab = 3\n")?;
+        let error = format!("{:#}", load_keymap(&path).expect_err("bad value"));
+        assert!(error.contains("must be a chord string"), "{error}");
+        Ok(())
+    }
 }
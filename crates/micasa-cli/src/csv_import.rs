@@ -0,0 +1,347 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Reading CSV files into new rows. Headers are matched to fields by name,
+//! and each row becomes a form payload so it passes the same validation as
+//! the add form.
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{ApplianceFormInput, CsvImportPreview, FormPayload, TabKind, VendorFormInput};
+use micasa_db::validation::{DATE_LAYOUT, parse_optional_cents, parse_optional_date};
+use std::path::Path;
+use time::Date;
+
+/// Rows shown under the headers in the import preview.
+const SAMPLE_ROWS: usize = 3;
+
+/// Fields a CSV can fill for a tab, as its column label and the database
+/// column; a header matches either, ignoring case, spaces and underscores.
+fn fields_for(tab: TabKind) -> Result<&'static [(&'static str, &'static str)]> {
+    Ok(match tab {
+        TabKind::Vendors => &[
+            ("name", "name"),
+            ("contact", "contact_name"),
+            ("email", "email"),
+            ("phone", "phone"),
+            ("website", "website"),
+            ("license", "license_number"),
+            ("insured", "insurance_expiry"),
+            ("notes", "notes"),
+        ],
+        TabKind::Appliances => &[
+            ("name", "name"),
+            ("brand", "brand"),
+            ("model", "model_number"),
+            ("serial", "serial_number"),
+            ("purchased", "purchase_date"),
+            ("warranty", "warranty_expiry"),
+            ("location", "location"),
+            ("cost", "cost_cents"),
+            ("notes", "notes"),
+        ],
+        other => bail!(
+            "{} rows can't be imported from CSV -- use vendors or appliances",
+            other.label()
+        ),
+    })
+}
+
+/// Reads `path` and proposes a mapping from its headers.
+pub fn preview(path: &Path, tab: TabKind) -> Result<CsvImportPreview> {
+    let fields = fields_for(tab)?;
+    let (headers, rows) = read_csv(path)?;
+    let mapping = fields
+        .iter()
+        .map(|(label, column)| {
+            let (label, column) = (normalize(label), normalize(column));
+            headers.iter().position(|header| {
+                let header = normalize(header);
+                header == label || header == column
+            })
+        })
+        .collect();
+    Ok(CsvImportPreview {
+        tab,
+        samples: rows
+            .iter()
+            .take(SAMPLE_ROWS)
+            .map(|(_, row)| row.clone())
+            .collect(),
+        row_count: rows.len(),
+        headers,
+        fields: fields.iter().map(|(label, _)| *label).collect(),
+        mapping,
+    })
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Applies `--map field=Header` overrides; `field=` leaves the field blank.
+pub fn apply_overrides(preview: &mut CsvImportPreview, overrides: &[String]) -> Result<()> {
+    for raw in overrides {
+        let (field, header) = raw
+            .split_once('=')
+            .ok_or_else(|| anyhow!("--map `{raw}` needs FIELD=HEADER, like name=Company"))?;
+        let index = preview
+            .fields
+            .iter()
+            .position(|label| label.eq_ignore_ascii_case(field.trim()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "--map `{raw}`: {} has no field `{}`; use {}",
+                    preview.tab.label(),
+                    field.trim(),
+                    preview.fields.join(", ")
+                )
+            })?;
+        let header = header.trim();
+        preview.mapping[index] = if header.is_empty() {
+            None
+        } else {
+            Some(
+                preview
+                    .headers
+                    .iter()
+                    .position(|candidate| candidate.trim().eq_ignore_ascii_case(header))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "--map `{raw}`: the file has no column `{header}`; it has {}",
+                            preview.headers.join(", ")
+                        )
+                    })?,
+            )
+        };
+    }
+    Ok(())
+}
+
+/// One form payload per data row, with the line the row starts on. A row
+/// whose values don't parse carries the error instead; validation is left
+/// to the form submit that saves it.
+pub fn payloads(
+    path: &Path,
+    tab: TabKind,
+    mapping: &[Option<usize>],
+) -> Result<Vec<(usize, Result<FormPayload>)>> {
+    let fields = fields_for(tab)?;
+    if mapping.len() != fields.len() {
+        bail!(
+            "the mapping covers {} fields but {} has {} -- reopen the import and retry",
+            mapping.len(),
+            tab.label(),
+            fields.len()
+        );
+    }
+    let (_, rows) = read_csv(path)?;
+    Ok(rows
+        .into_iter()
+        .map(|(line, row)| {
+            let value = |label: &str| {
+                fields
+                    .iter()
+                    .position(|(field, _)| *field == label)
+                    .and_then(|index| mapping[index])
+                    .and_then(|column| row.get(column))
+                    .map(|value| value.trim().to_owned())
+                    .unwrap_or_default()
+            };
+            (line, row_payload(tab, &value))
+        })
+        .collect())
+}
+
+fn row_payload(tab: TabKind, value: &dyn Fn(&str) -> String) -> Result<FormPayload> {
+    let date = |label: &str| -> Result<Option<Date>> {
+        let raw = value(label);
+        parse_optional_date(&raw)
+            .map_err(|error| anyhow!("{label} `{raw}`: {error}; expected {DATE_LAYOUT}"))
+    };
+    let money = |label: &str| -> Result<Option<i64>> {
+        let raw = value(label);
+        parse_optional_cents(&raw)
+            .map_err(|error| anyhow!("{label} `{raw}`: {error}; expected dollars like 125.50"))
+    };
+    Ok(match tab {
+        TabKind::Vendors => FormPayload::Vendor(VendorFormInput {
+            name: value("name"),
+            contact_name: value("contact"),
+            email: value("email"),
+            phone: value("phone"),
+            website: value("website"),
+            notes: value("notes"),
+            license_number: value("license"),
+            insurance_expiry: date("insured")?,
+            insurance_document_id: None,
+        }),
+        TabKind::Appliances => FormPayload::Appliance(ApplianceFormInput {
+            name: value("name"),
+            brand: value("brand"),
+            model_number: value("model"),
+            serial_number: value("serial"),
+            purchase_date: date("purchased")?,
+            warranty_expiry: date("warranty")?,
+            location: value("location"),
+            cost_cents: money("cost")?,
+            notes: value("notes"),
+        }),
+        other => bail!("{} rows can't be imported from CSV", other.label()),
+    })
+}
+
+type CsvRows = Vec<(usize, Vec<String>)>;
+
+fn read_csv(path: &Path) -> Result<(Vec<String>, CsvRows)> {
+    let text = std::fs::read_to_string(path).with_context(|| {
+        format!(
+            "read {} -- check the path and that it is UTF-8",
+            path.display()
+        )
+    })?;
+    let mut records = parse_csv(text.trim_start_matches('\u{feff}'))
+        .with_context(|| format!("parse {}", path.display()))?
+        .into_iter();
+    let (_, headers) = records.next().ok_or_else(|| {
+        anyhow!(
+            "{} is empty -- the first line must name the columns",
+            path.display()
+        )
+    })?;
+    Ok((headers, records.collect()))
+}
+
+/// Splits RFC 4180 CSV into records, each with the line it starts on.
+/// Blank lines are skipped.
+fn parse_csv(text: &str) -> Result<CsvRows> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut start = 1;
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => quoted = false,
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                }
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => quoted = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                if record.iter().any(|value| !value.is_empty()) {
+                    records.push((start, std::mem::take(&mut record)));
+                }
+                record.clear();
+                line += 1;
+                start = line;
+            }
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        bail!("line {start} opens a quote that never closes");
+    }
+    record.push(field);
+    if record.iter().any(|value| !value.is_empty()) {
+        records.push((start, record));
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{apply_overrides, parse_csv, payloads, preview};
+    use anyhow::Result;
+    use micasa_app::{FormPayload, TabKind};
+    use time::macros::date;
+
+    #[test]
+    fn csv_records_keep_quoted_commas_and_newlines() -> Result<()> {
+        let records =
+            parse_csv("name,notes\r\n\"Acme, Inc\",\"say \"\"hi\"\"\nthen leave\"\n\nBob,\n")?;
+        assert_eq!(
+            records,
+            vec![
+                (1, vec!["name".to_owned(), "notes".to_owned()]),
+                (
+                    2,
+                    vec!["Acme, Inc".to_owned(), "say \"hi\"\nthen leave".to_owned()]
+                ),
+                (5, vec!["Bob".to_owned(), String::new()]),
+            ]
+        );
+        let error = parse_csv("name\n\"open").expect_err("unclosed quote");
+        assert!(error.to_string().contains("line 2 opens a quote"));
+        Ok(())
+    }
+
+    #[test]
+    fn headers_map_by_label_or_column_and_rows_become_payloads() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("vendors.csv");
+        std::fs::write(
+            &path,
+            "Company,Contact Name,EMAIL,insurance_expiry,Rating\nAcme,Alex,a@acme.test,2027-03-01,5\n,Bo,,,\nTrim,,,soon,\n",
+        )?;
+
+        let mut preview = preview(&path, TabKind::Vendors)?;
+        assert_eq!(preview.row_count, 3);
+        assert_eq!(preview.samples.len(), 3);
+        assert_eq!(&preview.fields[..3], ["name", "contact", "email"]);
+        assert_eq!(&preview.mapping[..3], [None, Some(1), Some(2)]);
+        assert_eq!(preview.mapping[6], Some(3));
+
+        apply_overrides(
+            &mut preview,
+            &["name=company".to_owned(), "email=".to_owned()],
+        )?;
+        assert_eq!(preview.mapping[0], Some(0));
+        assert_eq!(preview.mapping[2], None);
+        for (raw, expected) in [
+            ("name", "needs FIELD=HEADER"),
+            ("rating=Rating", "no field `rating`"),
+            ("name=Title", "no column `Title`"),
+        ] {
+            let error = apply_overrides(&mut preview, &[raw.to_owned()]).expect_err(raw);
+            assert!(error.to_string().contains(expected), "{raw}: {error}");
+        }
+
+        let rows = payloads(&path, TabKind::Vendors, &preview.mapping)?;
+        assert_eq!(
+            rows.iter().map(|(line, _)| *line).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+        let Ok(FormPayload::Vendor(vendor)) = &rows[0].1 else {
+            panic!("expected a vendor payload: {:?}", rows[0].1);
+        };
+        assert_eq!(vendor.name, "Acme");
+        assert_eq!(vendor.contact_name, "Alex");
+        assert!(vendor.email.is_empty());
+        assert_eq!(vendor.insurance_expiry, Some(date!(2027 - 03 - 01)));
+        assert!(rows[1].1.is_ok(), "blank names are caught by validation");
+        let error = rows[2].1.as_ref().expect_err("loose date");
+        assert!(error.to_string().contains("insured `soon`"));
+
+        let error = super::preview(&path, TabKind::Projects).expect_err("projects don't import");
+        assert!(error.to_string().contains("use vendors or appliances"));
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_db::{DumpValue, Store, TableDump};
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+const DATABASE_EXPORT_FORMAT: &str = "micasa-export";
+const DATABASE_EXPORT_VERSION: i64 = 1;
+
+/// Blobs (document contents) are written as `{"hex": "..."}` so the rest of
+/// the document stays plain JSON scalars.
+const BLOB_KEY: &str = "hex";
+
+/// Renders every table as a versioned JSON document:
+/// `{"format", "version", "exported_at", "house", "tables": {name: {columns, rows}}}`.
+/// `house` names the house the rows belong to and is informational; imports
+/// ignore it. Private rows are left out unless `include_private` is set.
+pub fn export_database(
+    store: &Store,
+    now: OffsetDateTime,
+    include_private: bool,
+) -> Result<String> {
+    let dumps = if include_private {
+        store.dump_tables()?
+    } else {
+        store.dump_tables_without_private()?
+    };
+    let mut tables = Map::new();
+    for dump in dumps {
+        let rows = dump
+            .rows
+            .iter()
+            .map(|row| Value::Array(row.iter().map(value_to_json).collect()))
+            .collect::<Vec<_>>();
+        tables.insert(dump.table, json!({ "columns": dump.columns, "rows": rows }));
+    }
+    let document = json!({
+        "format": DATABASE_EXPORT_FORMAT,
+        "version": DATABASE_EXPORT_VERSION,
+        "exported_at": now.format(&Rfc3339).context("format export timestamp")?,
+        "house": store.active_house_name()?,
+        "tables": tables,
+    });
+    serde_json::to_string_pretty(&document).context("serialize database export")
+}
+
+/// Parses the whole document before touching the database, then replaces
+/// every table in one transaction. Returns the number of rows imported.
+pub fn import_database(store: &Store, raw: &str) -> Result<usize> {
+    let document: Value = serde_json::from_str(raw).context("parse database export JSON")?;
+    if document.get("format").and_then(Value::as_str) != Some(DATABASE_EXPORT_FORMAT) {
+        bail!("not a micasa database export; create one with `micasa export FILE`");
+    }
+    let version = document
+        .get("version")
+        .and_then(Value::as_i64)
+        .ok_or_else(|| anyhow!("database export is missing `version`; re-export it"))?;
+    if version != DATABASE_EXPORT_VERSION {
+        bail!(
+            "unsupported database export version {version}; expected {DATABASE_EXPORT_VERSION} -- re-export it with this micasa build"
+        );
+    }
+    let tables = document
+        .get("tables")
+        .and_then(Value::as_object)
+        .ok_or_else(|| anyhow!("database export needs a `tables` object"))?;
+
+    let mut dumps = Vec::with_capacity(tables.len());
+    for (table, body) in tables {
+        dumps.push(parse_table(table, body).with_context(|| format!("read table `{table}`"))?);
+    }
+    store.restore_tables(&dumps)
+}
+
+pub fn export_file_name(now: OffsetDateTime) -> String {
+    format!(
+        "micasa-export-{:04}{:02}{:02}-{:02}{:02}{:02}.json",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+/// Newest `micasa-export-*.json` in `dir`; the timestamped names sort in
+/// creation order.
+pub fn latest_export_in(dir: &Path) -> Result<Option<PathBuf>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("list {}", dir.display()))?;
+    let mut latest: Option<PathBuf> = None;
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("list {}", dir.display()))?
+            .path();
+        let is_export = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("micasa-export-") && name.ends_with(".json"));
+        if is_export && latest.as_ref().is_none_or(|current| path > *current) {
+            latest = Some(path);
+        }
+    }
+    Ok(latest)
+}
+
+pub fn export_database_to_path(store: &Store, path: &Path, include_private: bool) -> Result<()> {
+    let rendered = export_database(store, OffsetDateTime::now_utc(), include_private)?;
+    fs::write(path, rendered).with_context(|| {
+        format!(
+            "write database export {}; check the directory is writable",
+            path.display()
+        )
+    })
+}
+
+pub fn import_database_from_path(store: &Store, path: &Path) -> Result<usize> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("read database export {}", path.display()))?;
+    import_database(store, &raw).with_context(|| format!("import database {}", path.display()))
+}
+
+fn parse_table(table: &str, body: &Value) -> Result<TableDump> {
+    let columns = body
+        .get("columns")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("`columns` must be a list of column names"))?
+        .iter()
+        .map(|column| {
+            column
+                .as_str()
+                .map(str::to_owned)
+                .ok_or_else(|| anyhow!("column names must be strings"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let rows = body
+        .get("rows")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("`rows` must be a list of value lists"))?
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            row.as_array()
+                .ok_or_else(|| anyhow!("row {} must be a list of values", index + 1))?
+                .iter()
+                .map(json_to_value)
+                .collect::<Result<Vec<_>>>()
+                .with_context(|| format!("row {}", index + 1))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(TableDump {
+        table: table.to_owned(),
+        columns,
+        rows,
+    })
+}
+
+pub(crate) fn value_to_json(value: &DumpValue) -> Value {
+    match value {
+        DumpValue::Null => Value::Null,
+        DumpValue::Integer(value) => json!(value),
+        DumpValue::Real(value) => json!(value),
+        DumpValue::Text(value) => json!(value),
+        DumpValue::Blob(bytes) => {
+            let hex = bytes
+                .iter()
+                .map(|byte| format!("{byte:02x}"))
+                .collect::<String>();
+            json!({ BLOB_KEY: hex })
+        }
+    }
+}
+
+fn json_to_value(value: &Value) -> Result<DumpValue> {
+    match value {
+        Value::Null => Ok(DumpValue::Null),
+        Value::String(text) => Ok(DumpValue::Text(text.clone())),
+        Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(value), _) => Ok(DumpValue::Integer(value)),
+            (None, Some(value)) => Ok(DumpValue::Real(value)),
+            (None, None) => bail!("number {number} does not fit in SQLite"),
+        },
+        Value::Object(object) if object.len() == 1 => {
+            let hex = object
+                .get(BLOB_KEY)
+                .and_then(Value::as_str)
+                .ok_or_else(|| anyhow!("blob values must look like {{\"{BLOB_KEY}\": \"...\"}}"))?;
+            decode_hex(hex).map(DumpValue::Blob)
+        }
+        other => bail!("unsupported value {other}; expected null, a number, a string, or a blob"),
+    }
+}
+
+fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        bail!("blob hex has an odd number of digits");
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|start| {
+            hex.get(start..start + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| anyhow!("blob hex contains a non-hex digit"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        export_database, export_file_name, import_database, json_to_value, latest_export_in,
+        value_to_json,
+    };
+    use anyhow::Result;
+    use micasa_db::{DumpValue, LifecycleEntityRef, Store};
+    use time::macros::datetime;
+
+    fn bootstrapped_store() -> Result<Store> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        Ok(store)
+    }
+
+    #[test]
+    fn export_then_import_round_trips_the_database() -> Result<()> {
+        let source = bootstrapped_store()?;
+        source.seed_demo_data()?;
+        let exported = export_database(&source, datetime!(2026-10-16 09:30 UTC), true)?;
+        assert!(exported.contains("\"format\": \"micasa-export\""));
+        assert!(exported.contains("\"exported_at\": \"2026-10-16T09:30:00Z\""));
+        let house = source
+            .active_house_name()?
+            .expect("demo data names the house");
+        assert!(exported.contains(&format!("\"house\": \"{house}\"")));
+
+        let target = bootstrapped_store()?;
+        let imported = import_database(&target, &exported)?;
+        assert!(imported > 0);
+        assert_eq!(target.dump_tables()?, source.dump_tables()?);
+        assert_eq!(
+            export_database(&target, datetime!(2026-10-16 09:30 UTC), true)?,
+            exported
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locked_export_leaves_out_private_rows_and_rows_that_point_at_them() -> Result<()> {
+        let source = bootstrapped_store()?;
+        source.seed_demo_data()?;
+        let ids = |exported: &str, table: &str, column: &str| -> Result<Vec<i64>> {
+            let document: serde_json::Value = serde_json::from_str(exported)?;
+            let body = &document["tables"][table];
+            let index = body["columns"]
+                .as_array()
+                .and_then(|columns| columns.iter().position(|name| name == column))
+                .expect("column");
+            Ok(body["rows"]
+                .as_array()
+                .expect("rows")
+                .iter()
+                .filter_map(|row| row[index].as_i64())
+                .collect())
+        };
+
+        let full = export_database(&source, datetime!(2026-10-16 09:30 UTC), true)?;
+        let logged = ids(&full, "service_log_entries", "maintenance_item_id")?;
+        let item = micasa_app::MaintenanceItemId::new(logged[0]);
+        source.set_row_private(LifecycleEntityRef::MaintenanceItem(item), true)?;
+        let full = export_database(&source, datetime!(2026-10-16 09:30 UTC), true)?;
+        assert!(ids(&full, "maintenance_items", "id")?.contains(&item.get()));
+        assert_eq!(ids(&full, "private_rows", "target_id")?, vec![item.get()]);
+
+        let locked = export_database(&source, datetime!(2026-10-16 09:30 UTC), false)?;
+        assert!(!ids(&locked, "maintenance_items", "id")?.contains(&item.get()));
+        assert!(!ids(&locked, "service_log_entries", "maintenance_item_id")?.contains(&item.get()));
+        assert!(ids(&locked, "private_rows", "target_id")?.is_empty());
+
+        let target = bootstrapped_store()?;
+        assert!(import_database(&target, &locked)? > 0);
+        assert_eq!(
+            target.list_maintenance_items(true)?.len() + 1,
+            source.list_maintenance_items(true)?.len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn import_rejects_unknown_tables_and_decodes_blobs_and_reals() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let exported = export_database(&store, datetime!(2026-10-16 09:30 UTC), true)?;
+        let mut document: serde_json::Value = serde_json::from_str(&exported)?;
+        document["tables"]["scratch"] = serde_json::json!({ "columns": [], "rows": [] });
+        let error =
+            import_database(&store, &document.to_string()).expect_err("unknown table should fail");
+        assert!(format!("{error:#}").contains("unknown table `scratch`"));
+
+        let blob = DumpValue::Blob(vec![0x00, 0xff, 0x10]);
+        assert_eq!(value_to_json(&blob), serde_json::json!({ "hex": "00ff10" }));
+        assert_eq!(
+            json_to_value(&serde_json::json!({ "hex": "00ff10" }))?,
+            blob
+        );
+        assert_eq!(
+            json_to_value(&serde_json::json!(1.5))?,
+            DumpValue::Real(1.5)
+        );
+        assert!(json_to_value(&serde_json::json!({ "hex": "0g" })).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn import_rejects_foreign_or_future_documents_without_writes() -> Result<()> {
+        let store = bootstrapped_store()?;
+        store.seed_demo_data()?;
+        let before = store.dump_tables()?;
+
+        let error = import_database(&store, "{\"version\": 1, \"tables\": {}}")
+            .expect_err("missing format should fail");
+        assert!(error.to_string().contains("not a micasa database export"));
+
+        let error = import_database(
+            &store,
+            "{\"format\": \"micasa-export\", \"version\": 9, \"tables\": {}}",
+        )
+        .expect_err("future version should fail");
+        assert!(
+            error
+                .to_string()
+                .contains("unsupported database export version 9")
+        );
+
+        assert_eq!(store.dump_tables()?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn latest_export_picks_newest_timestamped_file() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        assert_eq!(latest_export_in(temp.path())?, None);
+        for now in [
+            datetime!(2026-10-16 09:30 UTC),
+            datetime!(2026-10-17 08:00 UTC),
+        ] {
+            std::fs::write(temp.path().join(export_file_name(now)), "{}")?;
+        }
+        std::fs::write(temp.path().join("micasa-projects-20270101-000000.csv"), "")?;
+        assert_eq!(
+            latest_export_in(temp.path())?,
+            Some(temp.path().join("micasa-export-20261017-080000.json"))
+        );
+        Ok(())
+    }
+}
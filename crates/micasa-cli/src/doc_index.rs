@@ -0,0 +1,166 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Embeddings index over document text for chat. Each document's title,
+//! notes, and extracted text, and each maintenance item's manual text, are
+//! split into passages and embedded with `llm.embedding_model`; chat
+//! questions pull the closest passages into the answer prompt next to the
+//! SQL results.
+
+use anyhow::{Context, Result};
+use micasa_app::{DocumentId, MaintenanceItemId};
+use micasa_db::Store;
+use micasa_db::embeddings::{CHUNK_CHARS, Embedded, chunk_text, nearest_chunks};
+use micasa_llm::{Client as LlmClient, build_document_excerpts};
+
+/// Passages quoted into one answer.
+const RETRIEVED_CHUNKS: usize = 4;
+/// Cosine similarity below which a passage is treated as unrelated.
+const MIN_SIMILARITY: f32 = 0.3;
+
+/// Re-embeds one document and returns how many passages it now has. Deleted
+/// documents and ones with no text end up with none.
+pub fn index_document(
+    store: &Store,
+    client: &LlmClient,
+    model: &str,
+    document_id: DocumentId,
+) -> Result<usize> {
+    let document = store.get_document(document_id)?;
+    let passages = if document.deleted_at.is_some() {
+        Vec::new()
+    } else {
+        let text = [&document.title, &document.notes, &document.extracted_text]
+            .iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        chunk_text(&text, CHUNK_CHARS)
+    };
+    let embeddings = client
+        .embed(model, &passages)
+        .with_context(|| format!("embed document {} with `{model}`", document_id.get()))?;
+    let chunks = passages.into_iter().zip(embeddings).collect::<Vec<_>>();
+    store.replace_document_chunks(document_id, model, &chunks)?;
+    Ok(chunks.len())
+}
+
+/// Re-embeds one maintenance item's manual text and returns how many
+/// passages it now has. Deleted items and ones with no manual get none.
+pub fn index_manual(
+    store: &Store,
+    client: &LlmClient,
+    model: &str,
+    maintenance_id: MaintenanceItemId,
+) -> Result<usize> {
+    let item = store
+        .list_maintenance_items(true)?
+        .into_iter()
+        .find(|item| item.id == maintenance_id);
+    let passages = match item {
+        Some(item) if item.deleted_at.is_none() => chunk_text(&item.manual_text, CHUNK_CHARS),
+        _ => Vec::new(),
+    };
+    let embeddings = client.embed(model, &passages).with_context(|| {
+        format!(
+            "embed manual for maintenance item {} with `{model}`",
+            maintenance_id.get()
+        )
+    })?;
+    let chunks = passages.into_iter().zip(embeddings).collect::<Vec<_>>();
+    store.replace_manual_chunks(maintenance_id, model, &chunks)?;
+    Ok(chunks.len())
+}
+
+/// Rebuilds the index for every live document and maintenance manual, for
+/// `--reindex-documents`.
+pub fn reindex_documents(store: &Store, client: &LlmClient, model: &str) -> Result<String> {
+    let documents = store.list_documents(false)?;
+    let mut passages = 0;
+    for document in &documents {
+        passages += index_document(store, client, model, document.id)?;
+    }
+    let items = store.list_maintenance_items(false)?;
+    for item in &items {
+        passages += index_manual(store, client, model, item.id)?;
+    }
+    let manuals = items
+        .iter()
+        .filter(|item| !item.manual_text.trim().is_empty())
+        .count();
+    Ok(format!(
+        "indexed {} document(s) and {manuals} manual(s) into {passages} passage(s) with {model}\n",
+        documents.len()
+    ))
+}
+
+/// A document or manual passage, ranked together for one question.
+struct Passage<'a> {
+    title: String,
+    content: &'a str,
+    embedding: &'a [f32],
+}
+
+impl Embedded for Passage<'_> {
+    fn embedding(&self) -> &[f32] {
+        self.embedding
+    }
+}
+
+/// The prompt section quoting passages closest to `question`, or empty when
+/// nothing is close. Errors are returned so chat can say the answer went
+/// without passages instead of quietly leaving them out.
+pub fn document_excerpts(
+    store: &Store,
+    client: &LlmClient,
+    model: &str,
+    question: &str,
+) -> Result<String> {
+    let documents = store
+        .document_chunks(model)
+        .context("read the document index")?;
+    let manuals = store
+        .manual_chunks(model)
+        .context("read the manual index")?;
+    let chunks = documents
+        .iter()
+        .map(|chunk| Passage {
+            title: chunk.title.clone(),
+            content: &chunk.content,
+            embedding: &chunk.embedding,
+        })
+        .chain(manuals.iter().map(|chunk| Passage {
+            title: format!("{} manual", chunk.title),
+            content: &chunk.content,
+            embedding: &chunk.embedding,
+        }))
+        .collect::<Vec<_>>();
+    if chunks.is_empty() {
+        return Ok(String::new());
+    }
+    let query = client
+        .embed(model, &[question.to_owned()])
+        .with_context(|| format!("embed the question with `{model}`"))?
+        .pop()
+        .with_context(|| format!("`{model}` returned no embedding for the question"))?;
+    let excerpts = nearest_chunks(&chunks, &query, RETRIEVED_CHUNKS, MIN_SIMILARITY)
+        .into_iter()
+        .map(|chunk| (chunk.title.as_str(), chunk.content))
+        .collect::<Vec<_>>();
+    Ok(build_document_excerpts(&excerpts))
+}
+
+/// Splits a retrieval result into prompt text and a notice for the user when
+/// it failed; the question is still answered, just without passages.
+pub fn excerpts_or_notice(result: Result<String>) -> (String, Option<String>) {
+    match result {
+        Ok(excerpts) => (excerpts, None),
+        Err(error) => (
+            String::new(),
+            Some(format!(
+                "answered without document passages: {error:#}; check llm.embedding_model"
+            )),
+        ),
+    }
+}
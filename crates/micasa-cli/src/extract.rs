@@ -0,0 +1,138 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Text extraction for saved documents. PDFs go through `pdftotext` and
+//! images through `tesseract`; both are external tools, so extraction is
+//! off unless `[extraction] enabled = true`.
+
+use anyhow::{Context, Result, bail};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+pub const DEFAULT_PDFTOTEXT: &str = "pdftotext";
+pub const DEFAULT_TESSERACT: &str = "tesseract";
+/// Longer transcripts are cut here; search and chat only need the gist.
+const MAX_EXTRACTED_CHARS: usize = 100_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextExtractor {
+    pdftotext: String,
+    tesseract: String,
+}
+
+impl TextExtractor {
+    pub fn new(pdftotext: &str, tesseract: &str) -> Self {
+        Self {
+            pdftotext: pdftotext.to_owned(),
+            tesseract: tesseract.to_owned(),
+        }
+    }
+
+    /// Text of a file with the given MIME type, or `None` for types there is
+    /// no extractor for.
+    pub fn extract(&self, mime_type: &str, data: &[u8]) -> Result<Option<String>> {
+        let raw = match mime_type {
+            "application/pdf" => run_tool(&self.pdftotext, &["-layout", "-", "-"], data)?,
+            "image/png" | "image/jpeg" | "image/jpg" | "image/gif" | "image/webp" => {
+                run_tool(&self.tesseract, &["stdin", "stdout"], data)?
+            }
+            mime if mime.starts_with("text/") => String::from_utf8_lossy(data).into_owned(),
+            _ => return Ok(None),
+        };
+        Ok(Some(tidy_text(&raw)))
+    }
+}
+
+/// Pipes `input` through `program` and returns what it printed.
+fn run_tool(program: &str, args: &[&str], input: &[u8]) -> Result<String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("run {program}; install it or set its path under [extraction]"))?;
+    let mut stdin = child.stdin.take().context("open extractor stdin")?;
+    let input = input.to_vec();
+    // Written from another thread so a tool that streams output while
+    // reading can't deadlock against a full pipe.
+    let writer = thread::spawn(move || stdin.write_all(&input));
+    let output = child
+        .wait_with_output()
+        .with_context(|| format!("wait for {program}"))?;
+    // A tool that exits early closes its stdin; what it printed decides.
+    let _ = writer.join();
+    if !output.status.success() {
+        bail!(
+            "{program} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Trims each line, drops blank runs and form feeds, and caps the length.
+fn tidy_text(raw: &str) -> String {
+    let mut text = String::new();
+    for line in raw
+        .lines()
+        .map(|line| line.trim_matches(|ch: char| ch.is_whitespace() || ch == '\u{c}'))
+    {
+        if line.is_empty() {
+            if !text.is_empty() && !text.ends_with("\n\n") {
+                text.push('\n');
+            }
+            continue;
+        }
+        text.push_str(line);
+        text.push('\n');
+    }
+    let text = text.trim_end();
+    match text.char_indices().nth(MAX_EXTRACTED_CHARS) {
+        Some((cut, _)) => text[..cut].to_owned(),
+        None => text.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MAX_EXTRACTED_CHARS, TextExtractor, tidy_text};
+
+    #[test]
+    fn plain_text_is_read_without_a_tool() {
+        let extractor = TextExtractor::new("/nonexistent/pdftotext", "/nonexistent/tesseract");
+        let text = extractor
+            .extract("text/plain", b"  Furnace model 80X  \n\n\n\nSerial 1234\n")
+            .expect("text needs no tool");
+        assert_eq!(text.as_deref(), Some("Furnace model 80X\n\nSerial 1234"));
+        assert_eq!(
+            extractor
+                .extract("application/zip", b"PK")
+                .expect("unknown types are skipped"),
+            None
+        );
+    }
+
+    #[test]
+    fn missing_tool_names_the_config_section() {
+        let extractor = TextExtractor::new("/nonexistent/pdftotext", "/nonexistent/tesseract");
+        let error = extractor
+            .extract("application/pdf", b"%PDF-1.4")
+            .expect_err("tool is missing");
+        let message = format!("{error:#}");
+        assert!(message.contains("/nonexistent/pdftotext"), "{message}");
+        assert!(message.contains("[extraction]"), "{message}");
+    }
+
+    #[test]
+    fn tidy_text_caps_long_transcripts() {
+        let long = "x".repeat(MAX_EXTRACTED_CHARS + 10);
+        assert_eq!(tidy_text(&long).chars().count(), MAX_EXTRACTED_CHARS);
+        assert_eq!(
+            tidy_text("\u{c}page one\n\u{c}\npage two"),
+            "page one\n\npage two"
+        );
+    }
+}
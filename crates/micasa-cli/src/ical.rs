@@ -0,0 +1,368 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result};
+use micasa_app::{DeepLink, DeletionEntity, TabKind, next_due};
+use micasa_db::Store;
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::{Date, Duration, OffsetDateTime};
+
+/// RFC 5545 caps content lines at 75 octets; longer ones are folded.
+const MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalendarEvent {
+    uid: String,
+    date: Date,
+    summary: String,
+    link: Option<DeepLink>,
+}
+
+impl CalendarEvent {
+    fn new(uid: String, date: Date, summary: String, link: Option<DeepLink>) -> Self {
+        Self {
+            uid,
+            date,
+            summary,
+            link,
+        }
+    }
+}
+
+/// All-day events for upcoming maintenance, warranty expirations, the
+/// insurance renewal, and project start/end dates. Deleted and private rows
+/// are left off since the feed usually lands somewhere shared.
+fn collect_events(store: &Store) -> Result<Vec<CalendarEvent>> {
+    let mut events = Vec::new();
+
+    let private = store.private_row_ids(DeletionEntity::Maintenance)?;
+    for item in store.list_maintenance_with_schedule()? {
+        if private.contains(&item.id.get()) {
+            continue;
+        }
+        let Some(due) = next_due(item.last_serviced_at, item.interval_months) else {
+            continue;
+        };
+        events.push(CalendarEvent::new(
+            format!("maint-{}@micasa", item.id.get()),
+            due,
+            format!("Maintenance due: {}", item.name),
+            Some(DeepLink::new(TabKind::Maintenance, item.id.get())?),
+        ));
+    }
+
+    let private = store.private_row_ids(DeletionEntity::Appliance)?;
+    for appliance in store.list_appliances(false)? {
+        let Some(expiry) = appliance.warranty_expiry else {
+            continue;
+        };
+        if private.contains(&appliance.id.get()) {
+            continue;
+        }
+        events.push(CalendarEvent::new(
+            format!("warranty-{}@micasa", appliance.id.get()),
+            expiry,
+            format!("Warranty expires: {}", appliance.name),
+            Some(DeepLink::new(TabKind::Appliances, appliance.id.get())?),
+        ));
+    }
+
+    if let Some(house) = store.get_house_profile()?
+        && let Some(renewal) = house.insurance_renewal
+    {
+        let summary = match house.insurance_carrier.trim() {
+            "" => "Insurance renewal".to_owned(),
+            carrier => format!("Insurance renewal: {carrier}"),
+        };
+        events.push(CalendarEvent::new(
+            "insurance-renewal@micasa".to_owned(),
+            renewal,
+            summary,
+            None,
+        ));
+    }
+
+    let private = store.private_row_ids(DeletionEntity::Project)?;
+    for project in store.list_projects(false)? {
+        if private.contains(&project.id.get()) {
+            continue;
+        }
+        let link = DeepLink::new(TabKind::Projects, project.id.get())?;
+        for (kind, date) in [("start", project.start_date), ("end", project.end_date)] {
+            let Some(date) = date else {
+                continue;
+            };
+            let verb = if kind == "start" { "starts" } else { "ends" };
+            events.push(CalendarEvent::new(
+                format!("project-{kind}-{}@micasa", project.id.get()),
+                date,
+                format!("Project {verb}: {}", project.title),
+                Some(link),
+            ));
+        }
+    }
+
+    events.sort_by(|left, right| {
+        left.date
+            .cmp(&right.date)
+            .then_with(|| left.uid.cmp(&right.uid))
+    });
+    Ok(events)
+}
+
+fn render_events(events: &[CalendarEvent], now: OffsetDateTime) -> String {
+    let stamp = format_timestamp(now);
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//micasa//due dates//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "METHOD:PUBLISH".to_owned(),
+        "X-WR-CALNAME:micasa".to_owned(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_owned());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{stamp}"));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", format_date(event.date)));
+        if let Some(end) = event.date.checked_add(Duration::days(1)) {
+            lines.push(format!("DTEND;VALUE=DATE:{}", format_date(end)));
+        }
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if let Some(link) = event.link {
+            lines.push(format!("URL:{link}"));
+        }
+        lines.push("TRANSP:TRANSPARENT".to_owned());
+        lines.push("END:VEVENT".to_owned());
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    let mut out = String::new();
+    for line in lines {
+        out.push_str(&fold_line(&line));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+pub fn render_calendar(store: &Store, now: OffsetDateTime) -> Result<String> {
+    Ok(render_events(&collect_events(store)?, now))
+}
+
+/// Writes the feed through a temporary file so a calendar app polling the
+/// path never reads a half-written file. Returns the number of events.
+pub fn write_calendar(store: &Store, path: &Path, now: OffsetDateTime) -> Result<usize> {
+    let events = collect_events(store)?;
+    let rendered = render_events(&events, now);
+    let staging = staging_path(path);
+    fs::write(&staging, rendered).with_context(|| {
+        format!(
+            "write calendar feed {}; check the directory exists and is writable",
+            staging.display()
+        )
+    })?;
+    fs::rename(&staging, path)
+        .with_context(|| format!("replace calendar feed {}", path.display()))?;
+    Ok(events.len())
+}
+
+/// Regenerates the feed at `ical.path`, if one is set.
+pub fn refresh_calendar(store: &Store) -> Result<Option<PathBuf>> {
+    let Some(path) = store.get_ical_path()? else {
+        return Ok(None);
+    };
+    write_calendar(store, &path, OffsetDateTime::now_utc())?;
+    Ok(Some(path))
+}
+
+/// Saves `ical.path` (`none` clears it) and writes the feed right away so a
+/// calendar app has something to subscribe to.
+pub fn set_calendar_path(store: &Store, raw: &str) -> Result<String> {
+    let raw = raw.trim();
+    if raw.eq_ignore_ascii_case("none") {
+        store.put_ical_path(None)?;
+        return Ok("calendar feed off".to_owned());
+    }
+    let path = Path::new(raw);
+    if path.extension().is_none_or(|extension| extension != "ics") {
+        anyhow::bail!("calendar feed path `{raw}` must end in .ics, like ~/micasa.ics");
+    }
+    let events = write_calendar(store, path, OffsetDateTime::now_utc())?;
+    store.put_ical_path(Some(path))?;
+    Ok(format!(
+        "calendar feed {} ({events} events)",
+        path.display()
+    ))
+}
+
+fn staging_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+fn format_date(date: Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        u8::from(date.month()),
+        date.day()
+    )
+}
+
+fn format_timestamp(now: OffsetDateTime) -> String {
+    let now = now.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{}T{:02}{:02}{:02}Z",
+        format_date(now.date()),
+        now.hour(),
+        now.minute(),
+        now.second()
+    )
+}
+
+fn escape_text(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        match ch {
+            '\\' => out.push_str("\\\\"),
+            ';' => out.push_str("\\;"),
+            ',' => out.push_str("\\,"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Splits at char boundaries so multi-byte names never break mid-character;
+/// continuation lines start with a space, which counts toward their limit.
+fn fold_line(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut width = 0;
+    for ch in line.chars() {
+        if width + ch.len_utf8() > MAX_LINE_OCTETS {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(ch);
+        width += ch.len_utf8();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{escape_text, fold_line, render_calendar, set_calendar_path};
+    use anyhow::Result;
+    use micasa_db::{NewAppliance, NewMaintenanceItem, Store};
+    use time::macros::{date, datetime};
+
+    fn bootstrapped_store() -> Result<Store> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        Ok(store)
+    }
+
+    #[test]
+    fn calendar_lists_maintenance_warranty_and_skips_unscheduled() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let category_id = store.list_maintenance_categories()?[0].id;
+        store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Furnace filter, 16x25".to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: Some(date!(2026 - 07 - 01)),
+            interval_months: 3,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+        store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Never serviced".to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 12,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+        store.create_appliance(&NewAppliance {
+            name: "Dishwasher".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: Some(date!(2027 - 03 - 15)),
+            location: String::new(),
+            cost_cents: None,
+            notes: String::new(),
+        })?;
+
+        let rendered = render_calendar(&store, datetime!(2026-10-16 09:30 UTC))?;
+        assert!(rendered.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(rendered.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(rendered.matches("BEGIN:VEVENT").count(), 2);
+        assert!(rendered.contains("SUMMARY:Maintenance due: Furnace filter\\, 16x25\r\n"));
+        assert!(rendered.contains("DTSTART;VALUE=DATE:20261001\r\nDTEND;VALUE=DATE:20261002"));
+        assert!(rendered.contains("URL:micasa://maint/1\r\n"));
+        assert!(rendered.contains("SUMMARY:Warranty expires: Dishwasher\r\n"));
+        assert!(rendered.contains("DTSTAMP:20261016T093000Z\r\n"));
+        assert!(!rendered.contains("Never serviced"));
+        // Earliest date first.
+        assert!(
+            rendered.find("Furnace").unwrap_or(usize::MAX)
+                < rendered.find("Dishwasher").unwrap_or(0)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn demo_calendar_includes_projects_and_insurance() -> Result<()> {
+        let store = bootstrapped_store()?;
+        store.seed_demo_data()?;
+        let rendered = render_calendar(&store, datetime!(2026-10-16 09:30 UTC))?;
+        assert!(rendered.contains("SUMMARY:Project starts: "));
+        assert!(rendered.contains("UID:insurance-renewal@micasa\r\n"));
+        for line in rendered.split("\r\n") {
+            assert!(line.len() <= 75, "unfolded line: {line}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn set_calendar_path_writes_feed_and_none_clears_it() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("house.ics");
+
+        let error = set_calendar_path(&store, "house.txt").expect_err("non-ics path should fail");
+        assert!(error.to_string().contains("must end in .ics"));
+
+        let message = set_calendar_path(&store, &path.display().to_string())?;
+        assert!(message.ends_with("(0 events)"));
+        assert!(std::fs::read_to_string(&path)?.contains("BEGIN:VCALENDAR"));
+        assert_eq!(store.get_ical_path()?, Some(path));
+
+        assert_eq!(set_calendar_path(&store, "none")?, "calendar feed off");
+        assert_eq!(store.get_ical_path()?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn text_is_escaped_and_long_lines_fold_on_char_boundaries() {
+        assert_eq!(escape_text("a;b,c\\d\r\ne"), "a\\;b\\,c\\\\d\\ne");
+        let long = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold_line(&long);
+        let parts = folded.split("\r\n").collect::<Vec<_>>();
+        assert_eq!(parts.len(), 2);
+        assert!(parts.iter().all(|part| part.len() <= 75));
+        assert!(parts[1].starts_with(' '));
+        assert_eq!(parts.concat().replacen(' ', "", 1).len(), long.len());
+    }
+}
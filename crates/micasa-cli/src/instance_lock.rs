@@ -0,0 +1,113 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! An advisory lock beside the database file, held for as long as the TUI
+//! runs. It doesn't stop a second micasa from opening the database; it lets
+//! that one warn that both are editing and the last save wins.
+
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// Which process holds the lock for a database.
+#[derive(Debug)]
+pub enum InstanceLock {
+    /// This one, until the value is dropped or the process exits, however it
+    /// exits. The file stays behind; unlocked, it means nobody holds it.
+    Held { _file: File },
+    /// Another micasa, with its process id as far as the file tells.
+    HeldElsewhere { pid: Option<u32> },
+}
+
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.as_os_str().to_owned();
+    name.push(".lock");
+    PathBuf::from(name)
+}
+
+impl InstanceLock {
+    /// Takes the lock for `db_path` and records this process id in it, or
+    /// reports who already has it.
+    pub fn acquire(db_path: &Path) -> Result<Self> {
+        let path = lock_path(db_path);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)
+            .with_context(|| {
+                format!(
+                    "open lock file {} -- check the database directory is writable",
+                    path.display()
+                )
+            })?;
+        match file.try_lock() {
+            Ok(()) => {
+                file.set_len(0)
+                    .and_then(|()| file.seek(SeekFrom::Start(0)))
+                    .and_then(|_| writeln!(file, "{}", std::process::id()))
+                    .with_context(|| format!("write lock file {}", path.display()))?;
+                Ok(Self::Held { _file: file })
+            }
+            Err(TryLockError::WouldBlock) => {
+                let mut holder = String::new();
+                let pid = file
+                    .read_to_string(&mut holder)
+                    .ok()
+                    .and_then(|_| holder.trim().parse().ok());
+                Ok(Self::HeldElsewhere { pid })
+            }
+            Err(TryLockError::Error(error)) => {
+                Err(error).with_context(|| format!("lock {}", path.display()))
+            }
+        }
+    }
+
+    /// The startup warning for a second instance; nothing for the first.
+    pub fn warning(&self) -> Option<String> {
+        match self {
+            Self::Held { .. } => None,
+            Self::HeldElsewhere { pid } => Some(format!(
+                "another micasa{} has this database open; changes there reload here, and when both edit a row the last save wins",
+                pid.map(|pid| format!(" (pid {pid})")).unwrap_or_default()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InstanceLock, lock_path};
+    use anyhow::Result;
+
+    #[test]
+    fn a_second_acquire_sees_the_first_holder_until_it_is_dropped() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("micasa.db");
+        assert_eq!(lock_path(&db_path), temp.path().join("micasa.db.lock"));
+
+        let first = InstanceLock::acquire(&db_path)?;
+        assert!(matches!(first, InstanceLock::Held { .. }));
+        assert_eq!(first.warning(), None);
+
+        let second = InstanceLock::acquire(&db_path)?;
+        let pid = std::process::id();
+        assert!(
+            matches!(second, InstanceLock::HeldElsewhere { pid: Some(holder) } if holder == pid)
+        );
+        let warning = second.warning().expect("held elsewhere warns");
+        assert!(warning.starts_with(&format!(
+            "another micasa (pid {pid}) has this database open"
+        )));
+        assert!(warning.ends_with("the last save wins"));
+
+        drop(first);
+        assert!(matches!(
+            InstanceLock::acquire(&db_path)?,
+            InstanceLock::Held { .. }
+        ));
+        Ok(())
+    }
+}
@@ -1,16 +1,36 @@
 // Copyright 2026 Phillip Cloud
 // Licensed under the Apache License, Version 2.0
 
+mod backup;
+mod checklist;
 mod config;
+mod csv_import;
+mod db_io;
+mod doc_index;
+mod extract;
+mod ical;
+mod instance_lock;
+mod mcp;
+mod migrate;
+mod plugin;
+mod quick_add;
+mod rates;
+mod recalls;
+mod report;
 mod runtime;
+mod schema_doc;
+#[cfg(feature = "serve")]
+mod serve;
+mod settings_io;
+mod weather;
 
 use anyhow::{Context, Result};
 use config::Config;
-use micasa_app::{AppState, TabKind};
+use micasa_app::{AppState, DeepLink, RecalcSummary, TabKind};
 use micasa_db::Store;
-use runtime::DbRuntime;
+use runtime::{BudgetAlerts, DbRuntime};
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn main() {
     if let Err(error) = run() {
@@ -31,6 +51,19 @@ fn run() -> Result<()> {
         return Ok(());
     }
 
+    if options.hash_passphrase {
+        let mut passphrase = String::new();
+        std::io::stdin()
+            .read_line(&mut passphrase)
+            .context("read passphrase from stdin")?;
+        let passphrase = passphrase.trim_end_matches(['\r', '\n']);
+        if passphrase.is_empty() {
+            anyhow::bail!("passphrase is empty; pipe a non-empty line into --hash-passphrase");
+        }
+        println!("{}", micasa_db::crypto::hash_passphrase(passphrase)?);
+        return Ok(());
+    }
+
     if options.print_example {
         print!("{}", Config::example_config(&options.config_path));
         return Ok(());
@@ -52,6 +85,28 @@ fn run() -> Result<()> {
         println!("{}", db_path.display());
         return Ok(());
     }
+    if options.serve && options.demo {
+        anyhow::bail!(
+            "--demo runs in memory, so there is nothing to serve; point at a real database"
+        );
+    }
+    if (options.backup_now || options.restore_source.is_some()) && options.demo {
+        anyhow::bail!("--demo runs in memory, so there is nothing to back up or restore");
+    }
+    let backup_policy = config.backup_policy(&db_path)?;
+    if let Some(source) = &options.restore_source {
+        let safety = backup::restore(
+            &db_path,
+            source,
+            &backup_policy,
+            time::OffsetDateTime::now_utc(),
+        )?;
+        if let Some(safety) = safety {
+            println!("previous database saved to {}", safety.display());
+        }
+        println!("restored {}", db_path.display());
+        return Ok(());
+    }
 
     let mut store = Store::open(&db_path).with_context(|| {
         format!(
@@ -59,36 +114,302 @@ fn run() -> Result<()> {
             db_path.display()
         )
     })?;
+    if !options.demo {
+        migrate::prepare(
+            &store,
+            &db_path,
+            options.assume_yes,
+            &mut std::io::stderr(),
+            confirm_migration,
+            time::OffsetDateTime::now_utc(),
+        )?;
+    }
     store.bootstrap()?;
     store.set_max_document_size(config.max_document_size())?;
     if options.demo {
         store.seed_demo_data()?;
+    } else if let Err(error) = schema_doc::refresh(&store, &db_path) {
+        eprintln!("schema docs: {error:#}; the previous copies may be stale");
     }
 
-    let cache_dir = micasa_db::document_cache_dir()?;
-    let _removed = micasa_db::evict_stale_cache(&cache_dir, config.cache_ttl_days())?;
+    if options.encrypt_documents || options.rekey_documents {
+        if options.demo {
+            anyhow::bail!("--demo runs in memory, so there is nothing to encrypt");
+        }
+        return set_document_passphrase(&store, options.rekey_documents);
+    }
+    if options.backup_now {
+        let path = backup::snapshot(
+            &store,
+            &db_path,
+            &backup_policy,
+            time::OffsetDateTime::now_utc(),
+        )?;
+        println!("backed up to {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &options.export_settings_path {
+        settings_io::export_settings_to_path(
+            &store,
+            path,
+            &config::keymap_path(&options.config_path),
+        )?;
+        println!("settings exported to {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &options.export_database_path {
+        db_io::export_database_to_path(&store, path, options.include_private)?;
+        println!("database exported to {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &options.import_database_path {
+        if store.get_house_profile()?.is_some() && !options.replace_database {
+            anyhow::bail!(
+                "{} already has data and import replaces all of it; rerun as `micasa import {} --replace`",
+                db_path.display(),
+                path.display()
+            );
+        }
+        let imported = db_io::import_database_from_path(&store, path)?;
+        println!("imported {imported} rows from {}", path.display());
+        return Ok(());
+    }
+    if let Some(path) = &options.import_settings_path {
+        let applied = settings_io::import_settings_from_path(
+            &store,
+            path,
+            &config::keymap_path(&options.config_path),
+        )?;
+        let keys = if applied.keys {
+            " and key bindings"
+        } else {
+            ""
+        };
+        println!(
+            "imported {} settings, {} saved views{keys} from {}",
+            applied.settings,
+            applied.views,
+            path.display()
+        );
+        return Ok(());
+    }
+    if let Some(path) = &options.import_csv_path {
+        return import_csv(&store, &db_path, path, &options);
+    }
+    if options.recalculate {
+        let summary = store.recalculate_derived(&mut |step, done, total| {
+            eprint!("\rrecalculating {step}: {done}/{total}");
+            if done == total {
+                eprintln!();
+            }
+        })?;
+        println!("{}", format_recalc_summary(summary));
+        if summary.last_serviced > 0
+            && let Some(path) = ical::refresh_calendar(&store)?
+        {
+            println!("calendar feed rewritten at {}", path.display());
+        }
+        return Ok(());
+    }
 
-    let llm_client = if config.llm_enabled() {
-        Some(
-            micasa_llm::Client::new(
-                config.llm_base_url(),
-                config.llm_model(),
-                config.llm_timeout()?,
-            )
-            .with_context(|| {
-                format!(
-                    "invalid [llm] config in {}; fix base_url/model/timeout values",
+    let home_currency = config.home_currency();
+    if !options.set_rates.is_empty() || options.fetch_rates || options.list_rates {
+        for raw in &options.set_rates {
+            println!("{}", rates::set_manual_rate(&store, &home_currency, raw)?);
+        }
+        if options.fetch_rates {
+            let url = config.currency_rates_url().ok_or_else(|| {
+                anyhow::anyhow!(
+                    "--fetch-rates needs currency.rates_url in {}",
                     options.config_path.display()
                 )
-            })?,
+            })?;
+            let saved = rates::fetch_rates(&store, &home_currency, url, config.llm_timeout()?)?;
+            println!("fetched {saved} rates");
+        }
+        if options.list_rates {
+            print!(
+                "{}",
+                rates::format_rates(&store.list_currency_rates()?, &home_currency)
+            );
+        }
+        return Ok(());
+    }
+
+    if options.set_location.is_some() || options.fetch_weather {
+        if let Some(raw) = &options.set_location {
+            println!("{}", weather::set_location(&store, raw)?);
+        }
+        if options.fetch_weather {
+            if !config.weather_enabled() {
+                anyhow::bail!(
+                    "--fetch-weather needs [weather] enabled = true in {}",
+                    options.config_path.display()
+                );
+            }
+            let saved = weather::fetch_forecast(
+                &store,
+                config.weather_provider(),
+                config.weather_base_url(),
+                config.llm_timeout()?,
+            )?;
+            println!("cached {saved} forecast days");
+        }
+        return Ok(());
+    }
+
+    if let Some(raw) = &options.set_ical_path {
+        println!("{}", ical::set_calendar_path(&store, raw)?);
+        return Ok(());
+    }
+    if options.print_ical {
+        print!(
+            "{}",
+            ical::render_calendar(&store, time::OffsetDateTime::now_utc())?
+        );
+        return Ok(());
+    }
+
+    if options.check_recalls {
+        let timeout = config.llm_timeout()?;
+        let provider: Box<dyn micasa_db::recalls::RecallProvider> = match config.recalls_provider()
+        {
+            "dataset" => {
+                let path = config.recalls_dataset_path().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "recalls.provider = \"dataset\" needs recalls.dataset_path in {}",
+                        options.config_path.display()
+                    )
+                })?;
+                Box::new(recalls::DatasetProvider::load(&path)?)
+            }
+            _ => Box::new(recalls::CpscProvider::new(
+                config.recalls_base_url(),
+                timeout,
+            )?),
+        };
+        print!("{}", recalls::check_recalls(&store, provider.as_ref())?);
+        return Ok(());
+    }
+
+    if options.print_checklist || options.print_checklist_html {
+        let today = time::OffsetDateTime::now_utc().date();
+        if options.print_checklist_html {
+            print!("{}", checklist::render_checklist_html(&store, today)?);
+        } else {
+            print!("{}", checklist::render_checklist(&store, today)?);
+        }
+        return Ok(());
+    }
+    if options.print_document_index {
+        print!("{}", report::document_index_html(&store)?);
+        return Ok(());
+    }
+    if options.print_schema || options.print_schema_json {
+        let tables = store.schema_docs()?;
+        if options.print_schema_json {
+            print!("{}", schema_doc::render_json(&tables));
+        } else {
+            print!("{}", micasa_db::schema_docs::render_markdown(&tables));
+        }
+        return Ok(());
+    }
+    if options.reindex_documents {
+        let Some(client) = build_llm_client(&config, &options.config_path)? else {
+            anyhow::bail!(
+                "--reindex-documents needs [llm] enabled = true in {}",
+                options.config_path.display()
+            );
+        };
+        let model = config.llm_embedding_model().ok_or_else(|| {
+            anyhow::anyhow!(
+                "--reindex-documents needs llm.embedding_model in {}, like \"nomic-embed-text\"",
+                options.config_path.display()
+            )
+        })?;
+        print!("{}", doc_index::reindex_documents(&store, &client, model)?);
+        return Ok(());
+    }
+
+    if options.mcp {
+        return mcp::serve_stdio(&store);
+    }
+    if options.serve {
+        #[cfg(feature = "serve")]
+        {
+            drop(store);
+            let listen = options.listen.as_deref().unwrap_or(serve::DEFAULT_LISTEN);
+            return serve::serve(db_path, listen);
+        }
+        #[cfg(not(feature = "serve"))]
+        anyhow::bail!(
+            "this micasa was built without the web server; rebuild with `cargo install --path crates/micasa-cli --features serve`"
+        );
+    }
+
+    let reports_path = config::reports_path(&options.config_path);
+    let reports = report::load_reports(&reports_path)?;
+    if options.list_reports {
+        if reports.is_empty() {
+            println!(
+                "no reports defined; add [[report]] entries to {}",
+                reports_path.display()
+            );
+        }
+        for report in &reports {
+            println!(
+                "{}  {} ({})",
+                report.name,
+                report.title,
+                report.format.as_str()
+            );
+        }
+        return Ok(());
+    }
+    if let Some(name) = &options.run_report {
+        let report = report::find_report(&reports, name)?;
+        let (columns, rows) = report::run_report(&store, report, false)?;
+        print!("{}", report::render_report(report, &columns, &rows));
+        return Ok(());
+    }
+
+    if config.weather_enabled()
+        && let Err(error) = weather::refresh_if_stale(
+            &store,
+            config.weather_provider(),
+            config.weather_base_url(),
+            config.llm_timeout()?,
         )
-    } else {
-        None
-    };
+    {
+        eprintln!("weather: {error:#}; showing the last cached forecast");
+    }
+
+    let cache_dir = micasa_db::document_cache_dir()?;
+    let _removed = micasa_db::evict_stale_cache(&cache_dir, config.cache_ttl_days())?;
+    // Decrypted copies of sealed documents never outlive a session; this
+    // also catches ones left behind by a crash.
+    let encrypted = store.documents_encrypted()?;
+    if encrypted {
+        store.remove_cached_documents()?;
+    }
+
+    let llm_client = build_llm_client(&config, &options.config_path)?;
+    let plugins = plugin::load_plugins(&config::plugins_path(&options.config_path))?;
+    let keymap = config::load_keymap(&config::keymap_path(&options.config_path))?;
+    let themes = config.themes()?;
+    let theme_name = store
+        .get_theme_override()?
+        .filter(|name| themes.iter().any(|theme| &theme.name == name))
+        .unwrap_or_else(|| config.theme_name().to_owned());
     if options.check_only {
         return Ok(());
     }
 
+    let open_link = options
+        .open_link
+        .as_deref()
+        .map(DeepLink::parse)
+        .transpose()?;
     let show_dashboard = store
         .get_show_dashboard_override()?
         .unwrap_or_else(|| config.show_dashboard());
@@ -97,14 +418,165 @@ fn run() -> Result<()> {
     if !show_dashboard {
         state.active_tab = TabKind::Projects;
     }
+    // Held until the TUI exits; a second instance only warns.
+    let instance_lock = (!options.demo)
+        .then(|| instance_lock::InstanceLock::acquire(&db_path))
+        .transpose()?;
+    if let Some(warning) = instance_lock.as_ref().and_then(|lock| lock.warning()) {
+        state.dispatch(micasa_app::AppCommand::SetStatus(warning));
+    }
+
+    // The runtime and the shutdown check read the Settings toggle each time,
+    // so turning it on or off mid-session takes effect.
+    let auto_backups = (!options.demo).then_some(backup_policy);
+    if let Some(policy) = &auto_backups
+        && store.get_auto_backups()?
+        && let Err(error) =
+            backup::snapshot(&store, &db_path, policy, time::OffsetDateTime::now_utc())
+    {
+        eprintln!("backup: {error:#}; continuing without a startup backup");
+    }
 
     let mut runtime = DbRuntime::with_llm_client_context_and_db_path(
         &store,
         llm_client,
         config.llm_extra_context(),
-        Some(db_path),
+        Some(db_path.clone()),
+    )
+    .with_app_lock(config.lock_after()?, config.lock_passphrase_hash())
+    .with_budget_alerts(BudgetAlerts {
+        threshold_pct: config.budget_alert_threshold_pct(),
+        monthly_limit_cents: config.budget_monthly_limit_cents(),
+    })
+    .with_home_currency(home_currency)
+    .with_weather_alerts(config.weather_enabled())
+    .with_text_extraction(config.text_extractor())
+    .with_document_index(config.llm_embedding_model().map(str::to_owned))
+    .with_startup_focus(open_link)
+    .with_keymap(keymap)
+    .with_themes(themes, &theme_name)
+    .with_reports(reports)
+    .with_plugins(plugins)
+    .with_backups(auto_backups.clone());
+    let result = micasa_tui::run_app(&mut state, &mut runtime);
+    drop(runtime);
+    drop(instance_lock);
+    if encrypted && let Err(error) = store.remove_cached_documents() {
+        eprintln!(
+            "documents: {error:#}; delete the decrypted copies in {} by hand",
+            cache_dir.display()
+        );
+    }
+    if let Some(policy) = &auto_backups
+        && store.get_auto_backups()?
+        && let Err(error) =
+            backup::snapshot(&store, &db_path, policy, time::OffsetDateTime::now_utc())
+    {
+        eprintln!("backup: {error:#}; the shutdown backup was skipped");
+    }
+    result
+}
+
+/// `micasa import-csv`: prints the header mapping, imports every row that
+/// validates, and fails listing the rows that didn't.
+fn import_csv(store: &Store, db_path: &Path, path: &Path, options: &CliOptions) -> Result<()> {
+    use micasa_tui::AppRuntime as _;
+
+    let entity = options.import_entity.as_deref().ok_or_else(|| {
+        anyhow::anyhow!(
+            "import-csv needs --entity, like micasa import-csv vendors.csv --entity vendors"
+        )
+    })?;
+    let tab = micasa_app::tab_for_slug(entity)
+        .ok_or_else(|| anyhow::anyhow!("unknown entity `{entity}` -- use vendors or appliances"))?;
+    let mut preview = csv_import::preview(path, tab)?;
+    csv_import::apply_overrides(&mut preview, &options.import_mappings)?;
+    if preview.mapping.iter().all(Option::is_none) {
+        anyhow::bail!(
+            "no column of {} matches a {} field; map one with --map, like --map name={}",
+            path.display(),
+            tab.label(),
+            preview.headers.first().map_or("Name", String::as_str)
+        );
+    }
+    for (field, column) in preview.fields.iter().zip(&preview.mapping) {
+        match column {
+            Some(column) => println!("{field} <- {}", preview.headers[*column]),
+            None => println!("{field} (skipped)"),
+        }
+    }
+    let mut runtime = DbRuntime::with_llm_client_context_and_db_path(
+        store,
+        None,
+        "",
+        Some(db_path.to_path_buf()),
     );
-    micasa_tui::run_app(&mut state, &mut runtime)
+    let report = runtime.import_csv(path, tab, &preview.mapping)?;
+    for failure in &report.failures {
+        eprintln!("line {}: {}", failure.line, failure.message);
+    }
+    if !report.failures.is_empty() {
+        anyhow::bail!("{}", report.summary(tab));
+    }
+    println!("{}", report.summary(tab));
+    Ok(())
+}
+
+fn format_recalc_summary(summary: RecalcSummary) -> String {
+    if summary == RecalcSummary::default() {
+        return "derived values already up to date".to_owned();
+    }
+    let plural =
+        |count: usize, noun: &str| format!("{count} {noun}{}", if count == 1 { "" } else { "s" });
+    format!(
+        "recalculated {} and {}",
+        plural(summary.last_serviced, "last serviced date"),
+        plural(summary.document_checksums, "document checksum")
+    )
+}
+
+/// Asks on the terminal; without one there is nobody to ask, so `--yes` is
+/// required.
+fn confirm_migration() -> Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("stdin is not a terminal; rerun with --yes to back up and migrate");
+    }
+    eprint!("Back up and migrate now? [y/N] ");
+    std::io::stderr().flush().context("flush prompt")?;
+    let mut answer = String::new();
+    std::io::stdin()
+        .lock()
+        .read_line(&mut answer)
+        .context("read migration answer")?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+fn build_llm_client(config: &Config, config_path: &Path) -> Result<Option<micasa_llm::Client>> {
+    if !config.llm_enabled() {
+        return Ok(None);
+    }
+    let client = micasa_llm::Client::new(
+        config.llm_base_url(),
+        config.llm_model(),
+        config.llm_timeout()?,
+    )
+    .with_context(|| {
+        format!(
+            "invalid [llm] config in {}; fix base_url/model/timeout values",
+            config_path.display()
+        )
+    })?
+    .with_provider(
+        micasa_llm::provider_named(config.llm_provider())
+            .context("llm.provider was validated on load")?,
+    )
+    .with_api_key(config.llm_api_key());
+    Ok(Some(client))
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -116,6 +588,44 @@ struct CliOptions {
     print_example: bool,
     check_only: bool,
     show_help: bool,
+    hash_passphrase: bool,
+    encrypt_documents: bool,
+    rekey_documents: bool,
+    export_settings_path: Option<PathBuf>,
+    import_settings_path: Option<PathBuf>,
+    export_database_path: Option<PathBuf>,
+    import_database_path: Option<PathBuf>,
+    import_csv_path: Option<PathBuf>,
+    import_entity: Option<String>,
+    /// `--map FIELD=HEADER` overrides for `import-csv`.
+    import_mappings: Vec<String>,
+    replace_database: bool,
+    /// `export --include-private` keeps rows marked private.
+    include_private: bool,
+    assume_yes: bool,
+    set_rates: Vec<String>,
+    fetch_rates: bool,
+    list_rates: bool,
+    set_location: Option<String>,
+    fetch_weather: bool,
+    set_ical_path: Option<String>,
+    print_ical: bool,
+    check_recalls: bool,
+    print_checklist: bool,
+    print_checklist_html: bool,
+    print_document_index: bool,
+    print_schema: bool,
+    print_schema_json: bool,
+    reindex_documents: bool,
+    list_reports: bool,
+    run_report: Option<String>,
+    recalculate: bool,
+    backup_now: bool,
+    restore_source: Option<String>,
+    serve: bool,
+    listen: Option<String>,
+    mcp: bool,
+    open_link: Option<String>,
 }
 
 fn parse_cli_args<I, S>(args: I, default_config_path: PathBuf) -> Result<CliOptions>
@@ -131,6 +641,42 @@ where
         print_example: false,
         check_only: false,
         show_help: false,
+        hash_passphrase: false,
+        encrypt_documents: false,
+        rekey_documents: false,
+        export_settings_path: None,
+        import_settings_path: None,
+        export_database_path: None,
+        import_database_path: None,
+        import_csv_path: None,
+        import_entity: None,
+        import_mappings: Vec::new(),
+        replace_database: false,
+        include_private: false,
+        assume_yes: false,
+        set_rates: Vec::new(),
+        fetch_rates: false,
+        list_rates: false,
+        set_location: None,
+        fetch_weather: false,
+        set_ical_path: None,
+        print_ical: false,
+        check_recalls: false,
+        print_checklist: false,
+        print_checklist_html: false,
+        print_document_index: false,
+        print_schema: false,
+        print_schema_json: false,
+        reindex_documents: false,
+        list_reports: false,
+        run_report: None,
+        recalculate: false,
+        backup_now: false,
+        restore_source: None,
+        serve: false,
+        listen: None,
+        mcp: false,
+        open_link: None,
     };
 
     let mut iter = args.into_iter();
@@ -157,9 +703,175 @@ where
             "--check" => {
                 options.check_only = true;
             }
+            "--hash-passphrase" => {
+                options.hash_passphrase = true;
+            }
+            "--export-settings" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--export-settings requires a file path"))?;
+                options.export_settings_path = Some(PathBuf::from(value.as_ref()));
+            }
+            "--import-settings" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| anyhow::anyhow!("--import-settings requires a file path"))?;
+                options.import_settings_path = Some(PathBuf::from(value.as_ref()));
+            }
+            "--set-rate" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--set-rate requires CODE=RATE, like EUR=1.08")
+                })?;
+                options.set_rates.push(value.as_ref().to_owned());
+            }
+            "--fetch-rates" => {
+                options.fetch_rates = true;
+            }
+            "--list-rates" => {
+                options.list_rates = true;
+            }
+            "--set-location" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--set-location requires LAT,LON, like 45.52,-122.68")
+                })?;
+                options.set_location = Some(value.as_ref().to_owned());
+            }
+            "--fetch-weather" => {
+                options.fetch_weather = true;
+            }
+            "--set-ical-path" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--set-ical-path requires a file path, like ~/micasa.ics")
+                })?;
+                options.set_ical_path = Some(value.as_ref().to_owned());
+            }
+            "--print-ical" => {
+                options.print_ical = true;
+            }
+            "--check-recalls" => {
+                options.check_recalls = true;
+            }
+            "--print-checklist" => {
+                options.print_checklist = true;
+            }
+            "--print-checklist-html" => {
+                options.print_checklist_html = true;
+            }
+            "--print-document-index" => {
+                options.print_document_index = true;
+            }
+            "--print-schema" => {
+                options.print_schema = true;
+            }
+            "--print-schema-json" => {
+                options.print_schema_json = true;
+            }
+            "--reindex-documents" => {
+                options.reindex_documents = true;
+            }
+            "--encrypt-documents" => {
+                options.encrypt_documents = true;
+            }
+            "--rekey-documents" => {
+                options.rekey_documents = true;
+            }
+            "--list-reports" => {
+                options.list_reports = true;
+            }
+            "--open" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--open requires a link, like micasa://projects/4")
+                })?;
+                options.open_link = Some(value.as_ref().to_owned());
+            }
             "--help" | "-h" => {
                 options.show_help = true;
             }
+            "export" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("export requires a file path, like micasa export house.json")
+                })?;
+                options.export_database_path = Some(PathBuf::from(value.as_ref()));
+            }
+            "import" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("import requires a file path, like micasa import house.json")
+                })?;
+                options.import_database_path = Some(PathBuf::from(value.as_ref()));
+            }
+            "import-csv" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "import-csv requires a file path, like micasa import-csv vendors.csv --entity vendors"
+                    )
+                })?;
+                options.import_csv_path = Some(PathBuf::from(value.as_ref()));
+            }
+            "--entity" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--entity requires a tab, like --entity vendors")
+                })?;
+                options.import_entity = Some(value.as_ref().to_owned());
+            }
+            "--map" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--map requires FIELD=HEADER, like --map name=Company")
+                })?;
+                options.import_mappings.push(value.as_ref().to_owned());
+            }
+            "report" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "report requires a name, like micasa report spend-by-vendor; --list-reports shows them"
+                    )
+                })?;
+                options.run_report = Some(value.as_ref().to_owned());
+            }
+            "recalc" => {
+                options.recalculate = true;
+            }
+            "backup" => {
+                options.backup_now = true;
+            }
+            "serve" => {
+                options.serve = true;
+            }
+            "mcp" => {
+                options.mcp = true;
+            }
+            "--listen" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("--listen requires HOST:PORT, like 127.0.0.1:8788")
+                })?;
+                options.listen = Some(value.as_ref().to_owned());
+            }
+            "restore" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "restore requires a backup file or `latest`, like micasa restore latest"
+                    )
+                })?;
+                options.restore_source = Some(value.as_ref().to_owned());
+            }
+            "--replace" => {
+                options.replace_database = true;
+            }
+            "--include-private" => {
+                options.include_private = true;
+            }
+            "--yes" | "-y" => {
+                options.assume_yes = true;
+            }
+            // `micasa open <link>`, or the bare link as passed by a URL handler.
+            "open" => {
+                let value = iter.next().ok_or_else(|| {
+                    anyhow::anyhow!("open requires a link, like micasa open micasa://projects/4")
+                })?;
+                options.open_link = Some(value.as_ref().to_owned());
+            }
+            link if link.starts_with(micasa_app::DEEP_LINK_SCHEME) => {
+                options.open_link = Some(link.to_owned());
+            }
             unknown => {
                 return Err(anyhow::anyhow!(
                     "unknown argument {unknown:?}; run with --help to see supported options"
@@ -171,6 +883,38 @@ where
     Ok(options)
 }
 
+/// `--encrypt-documents` reads the new passphrase from stdin;
+/// `--rekey-documents` reads the current one, then the new one.
+fn set_document_passphrase(store: &Store, rekey: bool) -> Result<()> {
+    let flag = if rekey {
+        "--rekey-documents"
+    } else {
+        "--encrypt-documents"
+    };
+    let mut lines = std::io::stdin().lines();
+    let mut next = |which: &str| -> Result<String> {
+        let line = lines
+            .next()
+            .transpose()
+            .context("read passphrase from stdin")?
+            .unwrap_or_default();
+        let line = line.trim_end_matches('\r').to_owned();
+        if line.is_empty() {
+            anyhow::bail!("{which} passphrase is empty; pipe it as a line into {flag}");
+        }
+        Ok(line)
+    };
+    if rekey {
+        let current = next("current")?;
+        let count = store.rekey_documents(&current, &next("new")?)?;
+        println!("re-encrypted {count} documents under the new passphrase");
+    } else {
+        let count = store.encrypt_documents(&next("new")?)?;
+        println!("encrypted {count} documents; micasa asks for the passphrase at startup");
+    }
+    Ok(())
+}
+
 fn print_help() {
     println!("micasa (Rust)");
     println!("  --config <path>          Use a specific config path");
@@ -179,13 +923,51 @@ fn print_help() {
     println!("  --print-example-config   Print a v2 config template");
     println!("  --demo                   Launch with seeded demo data (in-memory)");
     println!("  --check                  Validate config + DB + startup dependencies");
+    println!("  --hash-passphrase        Hash a lock passphrase read from stdin");
+    println!("  --export-settings <path> Write all settings to a portable TOML file");
+    println!("  --import-settings <path> Apply settings from an exported TOML file");
+    println!("  export <path>            Write the whole database to a JSON file");
+    println!("  --include-private        Let export keep rows marked private");
+    println!("  import <path>            Replace the database with an exported JSON file");
+    println!("  --replace                Let import overwrite a database that has data");
+    println!("  import-csv <path>        Add rows from a CSV file (needs --entity)");
+    println!("  --entity <tab>           Where import-csv adds rows: vendors or appliances");
+    println!("  --map <FIELD=HEADER>     Fill a field from a CSV column; FIELD= skips it");
+    println!("  --yes, -y                Back up and migrate an older database without asking");
+    println!("  --set-rate <CODE=RATE>   Save a manual rate (home units per 1 CODE)");
+    println!("  --fetch-rates            Download rates from currency.rates_url");
+    println!("  --list-rates             Print saved conversion rates");
+    println!("  --set-location <LAT,LON> Save the house location (`none` clears it)");
+    println!("  --fetch-weather          Refresh the cached forecast now");
+    println!("  --set-ical-path <path>   Write due dates to an .ics feed (`none` stops it)");
+    println!("  --print-ical             Print the due-date calendar to stdout");
+    println!("  --check-recalls          File incidents for recalled appliances");
+    println!("  --print-checklist        Print due maintenance as a paper checklist");
+    println!("  --print-checklist-html   Same checklist as HTML with QR links");
+    println!("  --print-document-index   Print an HTML document index with QR links");
+    println!("  --print-schema           Print the described schema the chat uses, as Markdown");
+    println!("  --print-schema-json      Same schema description as JSON");
+    println!("  --reindex-documents      Rebuild the chat index of document text");
+    println!("  --encrypt-documents      Encrypt stored documents; passphrase from stdin");
+    println!("  --rekey-documents        Change that passphrase; current, then new, on stdin");
+    println!("  --list-reports           List the reports defined in reports.toml");
+    println!("  report <name>            Run a report from reports.toml and print it");
+    println!("  recalc                   Recompute stored last-serviced dates and checksums");
+    println!("  backup                   Snapshot the database into the backup directory");
+    println!("  restore <path|latest>    Replace the database with a verified backup");
+    println!("  serve                    Read-only web view (builds with --features serve)");
+    println!("  --listen <host:port>     Address for serve (default 127.0.0.1:8788)");
+    println!("  mcp                      Model Context Protocol server on stdin/stdout");
+    println!("  --open <micasa://...>    Start on the linked record");
+    println!("  open <micasa://...>      Same as --open; a bare link works too");
     println!("  --help                   Show this help");
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{CliOptions, parse_cli_args};
+    use super::{CliOptions, format_recalc_summary, parse_cli_args};
     use anyhow::Result;
+    use micasa_app::RecalcSummary;
     use std::path::PathBuf;
 
     fn default_options_path() -> PathBuf {
@@ -205,6 +987,42 @@ mod tests {
                 print_example: false,
                 check_only: false,
                 show_help: false,
+                hash_passphrase: false,
+                encrypt_documents: false,
+                rekey_documents: false,
+                export_settings_path: None,
+                import_settings_path: None,
+                export_database_path: None,
+                import_database_path: None,
+                import_csv_path: None,
+                import_entity: None,
+                import_mappings: Vec::new(),
+                replace_database: false,
+                include_private: false,
+                assume_yes: false,
+                set_rates: Vec::new(),
+                fetch_rates: false,
+                list_rates: false,
+                set_location: None,
+                fetch_weather: false,
+                set_ical_path: None,
+                print_ical: false,
+                check_recalls: false,
+                print_checklist: false,
+                print_checklist_html: false,
+                print_document_index: false,
+                print_schema: false,
+                print_schema_json: false,
+                reindex_documents: false,
+                list_reports: false,
+                run_report: None,
+                recalculate: false,
+                backup_now: false,
+                restore_source: None,
+                serve: false,
+                listen: None,
+                mcp: false,
+                open_link: None,
             }
         );
         Ok(())
@@ -248,6 +1066,7 @@ mod tests {
         assert!(options.print_example);
         assert!(options.check_only);
         assert!(!options.show_help);
+        assert!(!options.hash_passphrase);
         Ok(())
     }
 
@@ -260,6 +1079,198 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_cli_args_collects_rate_flags() -> Result<()> {
+        let options = parse_cli_args(
+            vec![
+                "--set-rate",
+                "EUR=1.08",
+                "--set-rate",
+                "CAD=0.73",
+                "--fetch-rates",
+                "--list-rates",
+            ],
+            default_options_path(),
+        )?;
+        assert_eq!(options.set_rates, vec!["EUR=1.08", "CAD=0.73"]);
+        assert!(options.fetch_rates);
+        assert!(options.list_rates);
+
+        let error = parse_cli_args(vec!["--set-rate"], default_options_path())
+            .expect_err("missing rate should fail");
+        assert!(error.to_string().contains("CODE=RATE"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cli_args_collects_weather_flags() -> Result<()> {
+        let options = parse_cli_args(
+            vec!["--set-location", "45.52,-122.68", "--fetch-weather"],
+            default_options_path(),
+        )?;
+        assert_eq!(options.set_location.as_deref(), Some("45.52,-122.68"));
+        assert!(options.fetch_weather);
+        assert!(!options.check_recalls);
+        assert!(parse_cli_args(vec!["--check-recalls"], default_options_path())?.check_recalls);
+        assert!(parse_cli_args(vec!["--print-checklist"], default_options_path())?.print_checklist);
+        let options = parse_cli_args(
+            vec!["--print-document-index", "--open", "micasa://maint/3"],
+            default_options_path(),
+        )?;
+        assert!(options.print_document_index);
+        assert_eq!(options.open_link.as_deref(), Some("micasa://maint/3"));
+        assert!(!options.reindex_documents);
+        assert!(parse_cli_args(vec!["--print-schema"], default_options_path())?.print_schema);
+        assert!(
+            parse_cli_args(vec!["--print-schema-json"], default_options_path())?.print_schema_json
+        );
+        assert!(
+            parse_cli_args(vec!["--reindex-documents"], default_options_path())?.reindex_documents
+        );
+        let options = parse_cli_args(vec!["--rekey-documents"], default_options_path())?;
+        assert!(options.rekey_documents);
+        assert!(!options.encrypt_documents);
+        let options = parse_cli_args(
+            vec!["--list-reports", "report", "spend-by-vendor"],
+            default_options_path(),
+        )?;
+        assert!(options.list_reports);
+        assert_eq!(options.run_report.as_deref(), Some("spend-by-vendor"));
+        assert!(parse_cli_args(vec!["report"], default_options_path()).is_err());
+        assert!(parse_cli_args(vec!["recalc"], default_options_path())?.recalculate);
+        assert!(parse_cli_args(vec!["backup"], default_options_path())?.backup_now);
+        assert_eq!(
+            parse_cli_args(vec!["restore", "latest"], default_options_path())?
+                .restore_source
+                .as_deref(),
+            Some("latest")
+        );
+        assert!(parse_cli_args(vec!["restore"], default_options_path()).is_err());
+        let options = parse_cli_args(
+            vec!["serve", "--listen", "0.0.0.0:8788"],
+            default_options_path(),
+        )?;
+        assert!(options.serve);
+        assert_eq!(options.listen.as_deref(), Some("0.0.0.0:8788"));
+        assert!(parse_cli_args(vec!["mcp"], default_options_path())?.mcp);
+        for args in [
+            vec!["open", "micasa://projects/4"],
+            vec!["micasa://projects/4"],
+        ] {
+            let options = parse_cli_args(args, default_options_path())?;
+            assert_eq!(options.open_link.as_deref(), Some("micasa://projects/4"));
+        }
+        assert!(parse_cli_args(vec!["open"], default_options_path()).is_err());
+
+        let error = parse_cli_args(vec!["--set-location"], default_options_path())
+            .expect_err("missing location should fail");
+        assert!(error.to_string().contains("LAT,LON"));
+        let options = parse_cli_args(vec!["--set-ical-path", "house.ics"], default_options_path())?;
+        assert_eq!(options.set_ical_path.as_deref(), Some("house.ics"));
+        assert!(parse_cli_args(vec!["--print-ical"], default_options_path())?.print_ical);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cli_args_sets_settings_export_and_import_paths() -> Result<()> {
+        let options = parse_cli_args(
+            vec![
+                "--export-settings",
+                "/tmp/out.toml",
+                "--import-settings",
+                "/tmp/in.toml",
+            ],
+            default_options_path(),
+        )?;
+        assert_eq!(
+            options.export_settings_path,
+            Some(PathBuf::from("/tmp/out.toml"))
+        );
+        assert_eq!(
+            options.import_settings_path,
+            Some(PathBuf::from("/tmp/in.toml"))
+        );
+
+        let error = parse_cli_args(vec!["--import-settings"], default_options_path())
+            .expect_err("missing import path should fail");
+        assert!(
+            error
+                .to_string()
+                .contains("--import-settings requires a file path")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cli_args_sets_database_export_and_import_paths() -> Result<()> {
+        let export = parse_cli_args(vec!["export", "/tmp/house.json"], default_options_path())?;
+        assert_eq!(
+            export.export_database_path,
+            Some(PathBuf::from("/tmp/house.json"))
+        );
+        assert!(!export.replace_database);
+        assert!(!export.include_private);
+        assert!(
+            parse_cli_args(
+                vec!["export", "/tmp/house.json", "--include-private"],
+                default_options_path()
+            )?
+            .include_private
+        );
+
+        let import = parse_cli_args(
+            vec!["import", "/tmp/house.json", "--replace"],
+            default_options_path(),
+        )?;
+        assert_eq!(
+            import.import_database_path,
+            Some(PathBuf::from("/tmp/house.json"))
+        );
+        assert!(import.replace_database);
+        assert!(!import.assume_yes);
+        assert!(parse_cli_args(vec!["--yes"], default_options_path())?.assume_yes);
+        assert!(parse_cli_args(vec!["-y"], default_options_path())?.assume_yes);
+
+        let error = parse_cli_args(vec!["import"], default_options_path())
+            .expect_err("missing import path should fail");
+        assert!(error.to_string().contains("import requires a file path"));
+        Ok(())
+    }
+
+    #[test]
+    fn parse_cli_args_collects_csv_import_entity_and_mappings() -> Result<()> {
+        let options = parse_cli_args(
+            vec![
+                "import-csv",
+                "/tmp/vendors.csv",
+                "--entity",
+                "vendors",
+                "--map",
+                "name=Company",
+                "--map",
+                "email=",
+            ],
+            default_options_path(),
+        )?;
+        assert_eq!(
+            options.import_csv_path,
+            Some(PathBuf::from("/tmp/vendors.csv"))
+        );
+        assert_eq!(options.import_entity.as_deref(), Some("vendors"));
+        assert_eq!(options.import_mappings, vec!["name=Company", "email="]);
+        assert!(options.import_database_path.is_none());
+
+        for (args, expected) in [
+            (vec!["import-csv"], "import-csv requires a file path"),
+            (vec!["--entity"], "--entity requires a tab"),
+            (vec!["--map"], "--map requires FIELD=HEADER"),
+        ] {
+            let error = parse_cli_args(args, default_options_path()).expect_err(expected);
+            assert!(error.to_string().contains(expected));
+        }
+        Ok(())
+    }
+
     #[test]
     fn parse_cli_args_sets_help_flag_for_long_and_short_variants() -> Result<()> {
         let long = parse_cli_args(vec!["--help"], default_options_path())?;
@@ -269,4 +1280,19 @@ mod tests {
         assert!(short.show_help);
         Ok(())
     }
+
+    #[test]
+    fn recalc_summary_counts_what_changed() {
+        assert_eq!(
+            format_recalc_summary(RecalcSummary::default()),
+            "derived values already up to date"
+        );
+        assert_eq!(
+            format_recalc_summary(RecalcSummary {
+                last_serviced: 1,
+                document_checksums: 3,
+            }),
+            "recalculated 1 last serviced date and 3 document checksums"
+        );
+    }
 }
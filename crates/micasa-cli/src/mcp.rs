@@ -0,0 +1,542 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! `micasa mcp`: a Model Context Protocol server over stdin and stdout, so an
+//! outside assistant can read the house data and log service through the
+//! same runtime the TUI uses. Reads leave private rows out; the one write
+//! goes through form validation and is audited as `agent`.
+
+use crate::runtime::DbRuntime;
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{
+    AuditSource, DeletionEntity, FormPayload, MaintenanceItemId, ProjectStatus,
+    ServiceLogEntryFormInput, TabKind, VendorId, tab_for_slug,
+};
+use micasa_db::Store;
+use micasa_db::validation::{DATE_LAYOUT, parse_optional_cents, parse_required_date};
+use micasa_tui::{AppRuntime, TableExport};
+use serde_json::{Map, Value, json};
+use std::io::{BufRead, Write};
+
+/// Newest first; a client asking for one of these gets it back.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Tabs `list_rows` reads. Settings, chat and the query console stay out.
+const AGENT_TABS: [TabKind; 10] = [
+    TabKind::Projects,
+    TabKind::Quotes,
+    TabKind::Maintenance,
+    TabKind::ServiceLog,
+    TabKind::Incidents,
+    TabKind::Appliances,
+    TabKind::Vendors,
+    TabKind::Documents,
+    TabKind::Budget,
+    TabKind::Schedule,
+];
+
+/// Answers one JSON-RPC message per line until stdin closes.
+pub fn serve_stdio(store: &Store) -> Result<()> {
+    let mut server = McpServer::new(store);
+    let mut stdout = std::io::stdout().lock();
+    for line in std::io::stdin().lock().lines() {
+        let line = line.context("read MCP message from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = server.handle_line(&line) {
+            writeln!(stdout, "{reply}").context("write MCP reply")?;
+            stdout.flush().context("flush MCP reply")?;
+        }
+    }
+    Ok(())
+}
+
+pub struct McpServer<'a> {
+    store: &'a Store,
+    runtime: DbRuntime<'a>,
+}
+
+impl<'a> McpServer<'a> {
+    pub fn new(store: &'a Store) -> Self {
+        Self {
+            store,
+            runtime: DbRuntime::with_llm_client_context_and_db_path(store, None, "", None),
+        }
+    }
+
+    /// The reply to one message, or `None` for a notification.
+    pub fn handle_line(&mut self, line: &str) -> Option<String> {
+        let message: Value = match serde_json::from_str(line) {
+            Ok(message) => message,
+            Err(error) => {
+                return Some(error_reply(
+                    Value::Null,
+                    -32700,
+                    &format!("parse error: {error}"),
+                ));
+            }
+        };
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            return Some(error_reply(
+                id.unwrap_or(Value::Null),
+                -32600,
+                "expected a JSON-RPC request with a `method`",
+            ));
+        };
+        // Notifications such as `notifications/initialized` need no answer.
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let result = match method {
+            "initialize" => initialize(&params),
+            "ping" => json!({}),
+            "tools/list" => json!({ "tools": tools() }),
+            "tools/call" => {
+                let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+                let arguments = params
+                    .get("arguments")
+                    .cloned()
+                    .unwrap_or_else(|| json!({}));
+                match self.call_tool(name, &arguments) {
+                    Ok(text) => tool_result(&text, false),
+                    Err(error) if error.is::<Internal>() => {
+                        return Some(error_reply(id, -32603, &format!("{error:#}")));
+                    }
+                    Err(error) => tool_result(&format!("{error:#}"), true),
+                }
+            }
+            other => {
+                return Some(error_reply(
+                    id,
+                    -32601,
+                    &format!("unknown method `{other}`"),
+                ));
+            }
+        };
+        Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string())
+    }
+
+    fn call_tool(&mut self, name: &str, arguments: &Value) -> Result<String> {
+        let value = match name {
+            "list_projects" => self.list_projects(optional_text(arguments, "status")?)?,
+            "maintenance_due" => self.maintenance_due()?,
+            "list_rows" => {
+                let slug = required_text(arguments, "tab")?;
+                let tab = tab_for_slug(slug)
+                    .filter(|tab| AGENT_TABS.contains(tab))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "unknown tab `{slug}`; use one of {}",
+                            agent_tab_labels().join(", ")
+                        )
+                    })?;
+                rows_json(&self.table(tab)?, |_| true)
+            }
+            "add_service_log" => return self.add_service_log(arguments),
+            other => bail!(
+                "unknown tool `{other}`; call tools/list for list_projects, maintenance_due, list_rows and add_service_log"
+            ),
+        };
+        serde_json::to_string_pretty(&value).context("serialize tool result")
+    }
+
+    fn table(&mut self, tab: TabKind) -> Result<TableExport> {
+        let snapshot = self
+            .runtime
+            .load_tab_snapshot(tab, false)?
+            .ok_or_else(|| anyhow!("{} has no rows to show", tab.label()))?;
+        let names = self.runtime.link_names()?;
+        Ok(TableExport::from_snapshot(tab, &snapshot, &names))
+    }
+
+    fn list_projects(&mut self, status: Option<&str>) -> Result<Value> {
+        let status = status
+            .map(|raw| {
+                ProjectStatus::parse(&raw.trim().to_ascii_lowercase()).ok_or_else(|| {
+                    anyhow!(
+                        "unknown status `{raw}`; use ideating, planned, quoted, underway, delayed, completed or abandoned"
+                    )
+                })
+            })
+            .transpose()?;
+        let table = self.table(TabKind::Projects)?;
+        let column = table
+            .columns
+            .iter()
+            .position(|column| column.eq_ignore_ascii_case("status"));
+        Ok(rows_json(&table, |cells| match (status, column) {
+            (Some(status), Some(column)) => cells
+                .get(column)
+                .is_some_and(|cell| cell.eq_ignore_ascii_case(status.as_str())),
+            _ => true,
+        }))
+    }
+
+    fn maintenance_due(&mut self) -> Result<Value> {
+        let snapshot = self.runtime.load_dashboard_snapshot()?;
+        let items = |rows: &[micasa_tui::DashboardMaintenance]| {
+            rows.iter()
+                .map(|row| {
+                    json!({
+                        "maintenance_item_id": row.maintenance_item_id.get(),
+                        "name": row.item_name,
+                        "days_from_now": row.days_from_now,
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        Ok(json!({
+            "overdue": items(&snapshot.overdue),
+            "upcoming": items(&snapshot.upcoming),
+        }))
+    }
+
+    fn add_service_log(&mut self, arguments: &Value) -> Result<String> {
+        let item_id = required_id(arguments, "maintenance_item_id")?;
+        // If privacy can't be checked the item can't be shown to be visible,
+        // so this fails the request rather than treating it as not private.
+        let hidden = self
+            .store
+            .private_row_ids(DeletionEntity::Maintenance)
+            .map_err(|error| {
+                Internal(error.context("check which maintenance items are private"))
+            })?;
+        let item = self
+            .store
+            .list_maintenance_items(false)?
+            .into_iter()
+            .find(|item| item.id.get() == item_id)
+            .filter(|_| !hidden.contains(&item_id))
+            .ok_or_else(|| {
+                anyhow!("maintenance item {item_id} not found; call maintenance_due for ids")
+            })?;
+        let raw_date = required_text(arguments, "serviced_at")?;
+        let serviced_at = parse_required_date(raw_date)
+            .map_err(|error| anyhow!("serviced_at `{raw_date}`: {error}; use {DATE_LAYOUT}"))?;
+        let cost = optional_text(arguments, "cost")?.unwrap_or("");
+        let cost_cents = parse_optional_cents(cost)
+            .map_err(|error| anyhow!("cost `{cost}`: {error}; use dollars like 125.50"))?;
+        let vendor_id = match arguments.get("vendor_id") {
+            None | Some(Value::Null) => None,
+            Some(_) => Some(VendorId::new(required_id(arguments, "vendor_id")?)),
+        };
+        let payload = FormPayload::ServiceLogEntry(ServiceLogEntryFormInput {
+            maintenance_item_id: MaintenanceItemId::new(item_id),
+            serviced_at,
+            vendor_id,
+            cost_cents,
+            notes: optional_text(arguments, "notes")?.unwrap_or("").to_owned(),
+        });
+        let runtime = &mut self.runtime;
+        self.store
+            .with_audit_source(AuditSource::Agent, |_| runtime.submit_form(&payload))?;
+        Ok(format!("logged service of {} on {serviced_at}", item.name))
+    }
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .into_iter()
+        .find(|version| Some(*version) == requested)
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "micasa", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Home maintenance records. Private rows are left out. \
+            add_service_log is the only write; look ids up with maintenance_due or list_rows first.",
+    })
+}
+
+fn tools() -> Value {
+    json!([
+        {
+            "name": "list_projects",
+            "description": "List house projects with their status, budget and dates.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": {
+                        "type": "string",
+                        "description": "Only projects in this status, like underway or planned."
+                    }
+                }
+            }
+        },
+        {
+            "name": "maintenance_due",
+            "description": "Maintenance that is overdue or coming due soon, with item ids.",
+            "inputSchema": { "type": "object", "properties": {} }
+        },
+        {
+            "name": "list_rows",
+            "description": "List the rows of one table as they appear in micasa.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "tab": { "type": "string", "enum": agent_tab_labels() }
+                },
+                "required": ["tab"]
+            }
+        },
+        {
+            "name": "add_service_log",
+            "description": "Record that a maintenance item was serviced.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "maintenance_item_id": { "type": "integer" },
+                    "serviced_at": { "type": "string", "description": "Date as YYYY-MM-DD." },
+                    "vendor_id": { "type": "integer" },
+                    "cost": { "type": "string", "description": "Dollars, like 125.50." },
+                    "notes": { "type": "string" }
+                },
+                "required": ["maintenance_item_id", "serviced_at"]
+            }
+        }
+    ])
+}
+
+fn agent_tab_labels() -> Vec<&'static str> {
+    AGENT_TABS.into_iter().map(TabKind::label).collect()
+}
+
+/// Rows as objects keyed by column, each with its row id.
+fn rows_json(table: &TableExport, keep: impl Fn(&[String]) -> bool) -> Value {
+    Value::Array(
+        table
+            .row_ids
+            .iter()
+            .zip(&table.rows)
+            .filter(|(_, cells)| keep(cells))
+            .map(|(id, cells)| {
+                let mut row = Map::new();
+                row.insert("id".to_owned(), json!(id));
+                for (column, cell) in table.columns.iter().zip(cells) {
+                    row.insert(column.clone(), json!(cell));
+                }
+                Value::Object(row)
+            })
+            .collect(),
+    )
+}
+
+/// A failure of micasa itself rather than of the tool call's arguments. It
+/// goes back as a JSON-RPC internal error instead of a tool result, so an
+/// assistant doesn't read it as something it can retry around.
+#[derive(Debug)]
+struct Internal(anyhow::Error);
+
+impl std::fmt::Display for Internal {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "{:#}", self.0)
+    }
+}
+
+impl std::error::Error for Internal {}
+
+fn tool_result(text: &str, is_error: bool) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    })
+}
+
+fn error_reply(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+fn optional_text<'v>(arguments: &'v Value, name: &str) -> Result<Option<&'v str>> {
+    match arguments.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(text)) => Ok(Some(text)),
+        Some(_) => bail!("`{name}` must be a string"),
+    }
+}
+
+fn required_text<'v>(arguments: &'v Value, name: &str) -> Result<&'v str> {
+    optional_text(arguments, name)?.ok_or_else(|| anyhow!("`{name}` is required"))
+}
+
+fn required_id(arguments: &Value, name: &str) -> Result<i64> {
+    arguments
+        .get(name)
+        .and_then(Value::as_i64)
+        .filter(|id| *id > 0)
+        .ok_or_else(|| anyhow!("`{name}` must be a positive integer id"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::McpServer;
+    use anyhow::Result;
+    use micasa_app::AuditSource;
+    use micasa_db::Store;
+    use serde_json::{Value, json};
+
+    fn call(server: &mut McpServer<'_>, id: i64, method: &str, params: Value) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let reply = server
+            .handle_line(&request.to_string())
+            .expect("requests get replies");
+        serde_json::from_str(&reply).expect("replies are JSON")
+    }
+
+    fn tool(server: &mut McpServer<'_>, name: &str, arguments: Value) -> (bool, String) {
+        let reply = call(
+            server,
+            9,
+            "tools/call",
+            json!({ "name": name, "arguments": arguments }),
+        );
+        let result = &reply["result"];
+        (
+            result["isError"].as_bool().expect("isError"),
+            result["content"][0]["text"]
+                .as_str()
+                .expect("text content")
+                .to_owned(),
+        )
+    }
+
+    #[test]
+    fn tools_read_the_house_and_log_service_as_the_agent() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.seed_demo_data()?;
+        let mut server = McpServer::new(&store);
+
+        let init = call(
+            &mut server,
+            1,
+            "initialize",
+            json!({ "protocolVersion": "2025-03-26", "capabilities": {} }),
+        );
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "micasa");
+        assert!(
+            server
+                .handle_line(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+                .is_none()
+        );
+        let names = call(&mut server, 2, "tools/list", json!({}))["result"]["tools"]
+            .as_array()
+            .expect("tool list")
+            .iter()
+            .map(|tool| tool["name"].as_str().expect("name").to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "list_projects",
+                "maintenance_due",
+                "list_rows",
+                "add_service_log"
+            ]
+        );
+
+        let (failed, text) = tool(&mut server, "list_projects", json!({}));
+        assert!(!failed, "{text}");
+        let all: Vec<Value> = serde_json::from_str(&text)?;
+        assert!(!all.is_empty());
+        let (_, text) = tool(
+            &mut server,
+            "list_projects",
+            json!({ "status": "Underway" }),
+        );
+        let underway: Vec<Value> = serde_json::from_str(&text)?;
+        assert!(underway.len() < all.len());
+        assert!(underway.iter().all(|row| row["status"] == "underway"));
+
+        let (_, text) = tool(&mut server, "maintenance_due", json!({}));
+        let due: Value = serde_json::from_str(&text)?;
+        assert!(due["overdue"].is_array() && due["upcoming"].is_array());
+
+        let item = store.list_maintenance_items(false)?.remove(0);
+        let (failed, text) = tool(
+            &mut server,
+            "add_service_log",
+            json!({
+                "maintenance_item_id": item.id.get(),
+                "serviced_at": "2026-10-16",
+                "cost": "85.00",
+                "notes": "via assistant",
+            }),
+        );
+        assert!(!failed, "{text}");
+        assert_eq!(
+            text,
+            format!("logged service of {} on 2026-10-16", item.name)
+        );
+        let audit = store.list_audit_entries(Some(AuditSource::Agent), 5)?;
+        assert!(!audit.is_empty(), "the write is audited as agent");
+
+        for (name, arguments, expected) in [
+            (
+                "add_service_log",
+                json!({ "maintenance_item_id": 9999, "serviced_at": "2026-10-16" }),
+                "maintenance item 9999 not found",
+            ),
+            (
+                "add_service_log",
+                json!({ "maintenance_item_id": item.id.get(), "serviced_at": "soon" }),
+                "serviced_at `soon`",
+            ),
+            ("list_rows", json!({ "tab": "settings" }), "unknown tab"),
+            ("drop_tables", json!({}), "unknown tool"),
+        ] {
+            let (failed, text) = tool(&mut server, name, arguments);
+            assert!(failed, "{name}: {text}");
+            assert!(text.contains(expected), "{name}: {text}");
+        }
+
+        let unknown = call(&mut server, 3, "resources/list", json!({}));
+        assert_eq!(unknown["error"]["code"], -32601);
+        let garbled: Value =
+            serde_json::from_str(&server.handle_line("{not json").expect("reply"))?;
+        assert_eq!(garbled["error"]["code"], -32700);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_privacy_checks_are_json_rpc_errors() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.seed_demo_data()?;
+        let item = store.list_maintenance_items(false)?.remove(0);
+        store
+            .raw_connection()
+            .execute_batch("ALTER TABLE private_rows RENAME TO private_rows_gone")?;
+        let mut server = McpServer::new(&store);
+        let reply = call(
+            &mut server,
+            7,
+            "tools/call",
+            json!({
+                "name": "add_service_log",
+                "arguments": { "maintenance_item_id": item.id.get(), "serviced_at": "2026-10-16" },
+            }),
+        );
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], -32603, "{reply}");
+        let message = reply["error"]["message"].as_str().expect("message");
+        assert!(
+            message.contains("check which maintenance items are private"),
+            "{message}"
+        );
+        assert!(
+            store
+                .list_audit_entries(Some(AuditSource::Agent), 5)?
+                .is_empty(),
+            "nothing was logged"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Startup schema migration: before `bootstrap` changes an existing database,
+//! show what it will do, ask (or take `--yes`), and keep a backup.
+
+use anyhow::{Result, bail};
+use micasa_db::{SchemaChange, Store};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+/// Prints the pending changes to `out`, asks `confirm` unless `assume_yes`,
+/// then backs up the database next to itself. Returns the backup path, or
+/// `None` when nothing is pending.
+pub fn prepare(
+    store: &Store,
+    db_path: &Path,
+    assume_yes: bool,
+    out: &mut impl Write,
+    confirm: impl FnOnce() -> Result<bool>,
+    now: OffsetDateTime,
+) -> Result<Option<PathBuf>> {
+    let changes = store.pending_schema_changes()?;
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    write!(out, "{}", report(db_path, &changes))?;
+    if !assume_yes && !confirm()? {
+        bail!(
+            "migration cancelled; {} is unchanged -- rerun with --yes to apply it",
+            db_path.display()
+        );
+    }
+
+    let backup = backup_path(db_path, now);
+    store.backup_to(&backup)?;
+    writeln!(out, "backup saved to {}", backup.display())?;
+    Ok(Some(backup))
+}
+
+fn report(db_path: &Path, changes: &[SchemaChange]) -> String {
+    let mut text = format!(
+        "{} needs {} schema change{} from this version of micasa:\n",
+        db_path.display(),
+        changes.len(),
+        if changes.len() == 1 { "" } else { "s" }
+    );
+    for change in changes {
+        text.push_str(&format!("  - {change}\n"));
+    }
+    text.push_str("Existing rows are kept; a backup is written before anything changes.\n");
+    text
+}
+
+/// `house.db` becomes `house.pre-migration-20260314-093000.db` in the same
+/// directory.
+fn backup_path(db_path: &Path, now: OffsetDateTime) -> PathBuf {
+    let stem = db_path
+        .file_stem()
+        .map_or_else(|| "micasa".into(), |stem| stem.to_string_lossy());
+    let extension = db_path
+        .extension()
+        .map_or_else(|| "db".into(), |extension| extension.to_string_lossy());
+    db_path.with_file_name(format!(
+        "{stem}.pre-migration-{:04}{:02}{:02}-{:02}{:02}{:02}.{extension}",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{backup_path, prepare};
+    use anyhow::Result;
+    use micasa_db::Store;
+    use std::path::Path;
+    use time::macros::datetime;
+
+    /// A current database with one additive column dropped, as an older
+    /// micasa would have left it.
+    fn outdated_store(path: &Path) -> Result<Store> {
+        let store = Store::open(path)?;
+        store.bootstrap()?;
+        store
+            .raw_connection()
+            .execute_batch("ALTER TABLE incidents DROP COLUMN follow_up_date;")?;
+        Ok(store)
+    }
+
+    #[test]
+    fn prepare_reports_asks_and_backs_up_before_migrating() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let db_path = temp_dir.path().join("house.db");
+        let store = outdated_store(&db_path)?;
+        let now = datetime!(2026-03-14 09:30:00 UTC);
+
+        let mut out = Vec::new();
+        let error = prepare(&store, &db_path, false, &mut out, || Ok(false), now)
+            .expect_err("declining should stop startup");
+        assert!(error.to_string().contains("--yes"));
+        let report = String::from_utf8(out)?;
+        assert!(report.contains("needs 1 schema change"));
+        assert!(report.contains("  - add column incidents.follow_up_date (TEXT)"));
+        assert!(!backup_path(&db_path, now).exists());
+
+        let mut out = Vec::new();
+        let backup = prepare(
+            &store,
+            &db_path,
+            true,
+            &mut out,
+            || panic!("--yes should not ask"),
+            now,
+        )?
+        .expect("pending changes should be backed up");
+        assert_eq!(
+            backup,
+            temp_dir
+                .path()
+                .join("house.pre-migration-20260314-093000.db")
+        );
+        assert!(String::from_utf8(out)?.contains("backup saved to"));
+        assert_eq!(Store::open(&backup)?.pending_schema_changes()?.len(), 1);
+
+        store.bootstrap()?;
+        let mut out = Vec::new();
+        let later = datetime!(2026-03-15 09:30:00 UTC);
+        assert_eq!(
+            prepare(&store, &db_path, false, &mut out, || Ok(false), later)?,
+            None
+        );
+        assert!(out.is_empty());
+        Ok(())
+    }
+}
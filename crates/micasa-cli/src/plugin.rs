@@ -0,0 +1,551 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Experimental plugins: external programs listed in plugins.toml that add
+//! read-only tabs and row actions. Each call runs the program once with a
+//! JSON request on stdin and reads one JSON reply from stdout, so a plugin
+//! can be written in anything and never shares memory with micasa.
+//!
+//! These are ordinary processes, not sandboxed WASM modules: a plugin runs
+//! with the user's own permissions. It gets the house records micasa is
+//! showing, not the database path, so private and archived rows stay out of
+//! its requests; "read-only" still describes the tabs micasa shows, not
+//! something it can enforce on the program. What micasa does enforce is a
+//! time limit per call.
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{PluginAction, PluginRow, PluginRows, PluginTab};
+use micasa_db::{DumpValue, Store};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long one plugin call may take unless plugins.toml says otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// House records sent to plugins as `data`. Settings, chat history, audit
+/// entries, and the search index stay out.
+const PLUGIN_TABLES: [&str; 16] = [
+    "house_profiles",
+    "projects",
+    "project_types",
+    "project_tasks",
+    "quotes",
+    "vendors",
+    "maintenance_items",
+    "maintenance_categories",
+    "service_log_entries",
+    "appliances",
+    "incidents",
+    "documents",
+    "budgets",
+    "cost_splits",
+    "tags",
+    "entity_tags",
+];
+
+/// Columns left out of `data`: document file contents can be large and may
+/// be sealed.
+const PLUGIN_SKIPPED_COLUMNS: [(&str, &str); 1] = [("documents", "data")];
+
+/// A plugin entry from plugins.toml.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginDef {
+    pub name: String,
+    pub command: String,
+    pub args: Vec<String>,
+    /// Longest a single call may run before the plugin is killed.
+    pub timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginsFile {
+    #[serde(default)]
+    plugin: Vec<RawPlugin>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawPlugin {
+    name: String,
+    command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    timeout: Option<String>,
+}
+
+pub fn load_plugins(path: &Path) -> Result<Vec<PluginDef>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("read plugins file {}", path.display()))?;
+    parse_plugins(&raw).with_context(|| format!("invalid plugins file {}", path.display()))
+}
+
+fn parse_plugins(raw: &str) -> Result<Vec<PluginDef>> {
+    let file: PluginsFile = toml::from_str(raw).context("parse TOML plugins")?;
+    let mut plugins: Vec<PluginDef> = Vec::with_capacity(file.plugin.len());
+    for raw in file.plugin {
+        let name = raw.name.trim().to_owned();
+        if name.is_empty() || raw.command.trim().is_empty() {
+            bail!("every [[plugin]] needs a name and a command");
+        }
+        if plugins.iter().any(|other| other.name == name) {
+            bail!("plugin `{name}` is defined twice; rename one");
+        }
+        let timeout = match raw.timeout.as_deref() {
+            Some(timeout) => crate::config::parse_duration(timeout)
+                .with_context(|| format!("plugin `{name}` has an invalid timeout"))?,
+            None => DEFAULT_TIMEOUT,
+        };
+        if timeout.is_zero() {
+            bail!("plugin `{name}` has a zero timeout; use something like \"10s\"");
+        }
+        plugins.push(PluginDef {
+            name,
+            command: raw.command,
+            args: raw.args,
+            timeout,
+        });
+    }
+    Ok(plugins)
+}
+
+#[derive(Debug, Deserialize)]
+struct DescribeReply {
+    #[serde(default)]
+    tabs: Vec<RawTab>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTab {
+    name: String,
+    title: Option<String>,
+    #[serde(default)]
+    actions: Vec<RawAction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawAction {
+    name: String,
+    label: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RowsReply {
+    columns: Vec<String>,
+    #[serde(default)]
+    rows: Vec<RawRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRow {
+    id: String,
+    cells: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionReply {
+    #[serde(default)]
+    status: String,
+}
+
+/// The records a plugin request carries as `data`, shaped like a database
+/// export's `tables`: `{name: {columns, rows}}`. Deleted rows are left out,
+/// and so are private and archived rows while micasa hides them.
+pub fn house_data(store: &Store, hide_private: bool, hide_archived: bool) -> Result<Value> {
+    let mut tables = serde_json::Map::new();
+    for dump in store.dump_visible_tables(hide_private, hide_archived)? {
+        if !PLUGIN_TABLES.contains(&dump.table.as_str()) {
+            continue;
+        }
+        let kept = dump
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| {
+                !PLUGIN_SKIPPED_COLUMNS.contains(&(dump.table.as_str(), column.as_str()))
+            })
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let deleted_at = dump
+            .columns
+            .iter()
+            .position(|column| column == "deleted_at");
+        let rows = dump
+            .rows
+            .iter()
+            .filter(|row| deleted_at.is_none_or(|index| row[index] == DumpValue::Null))
+            .map(|row| {
+                Value::Array(
+                    kept.iter()
+                        .map(|index| crate::db_io::value_to_json(&row[*index]))
+                        .collect(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let columns = kept
+            .iter()
+            .map(|index| dump.columns[*index].clone())
+            .collect::<Vec<_>>();
+        tables.insert(dump.table, json!({ "columns": columns, "rows": rows }));
+    }
+    Ok(Value::Object(tables))
+}
+
+/// The tabs `plugin` offers, from its `describe` reply.
+pub fn describe(plugin: &PluginDef, data: &Value) -> Result<Vec<PluginTab>> {
+    let reply: DescribeReply = call(plugin, json!({ "request": "describe" }), data)?;
+    Ok(reply
+        .tabs
+        .into_iter()
+        .map(|tab| PluginTab {
+            plugin: plugin.name.clone(),
+            title: tab.title.unwrap_or_else(|| tab.name.clone()),
+            name: tab.name,
+            actions: tab
+                .actions
+                .into_iter()
+                .map(|action| PluginAction {
+                    label: action.label.unwrap_or_else(|| action.name.clone()),
+                    name: action.name,
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Rows of one tab. Cells may be any JSON scalar; each row is padded or cut
+/// to the column count so a sloppy plugin can't skew the table.
+pub fn load_rows(plugin: &PluginDef, tab: &str, data: &Value) -> Result<PluginRows> {
+    let reply: RowsReply = call(plugin, json!({ "request": "rows", "tab": tab }), data)?;
+    let width = reply.columns.len();
+    let rows = reply
+        .rows
+        .into_iter()
+        .map(|row| {
+            let mut cells = row.cells.iter().map(cell_text).collect::<Vec<_>>();
+            cells.resize(width, String::new());
+            PluginRow { id: row.id, cells }
+        })
+        .collect();
+    Ok(PluginRows {
+        columns: reply.columns,
+        rows,
+    })
+}
+
+/// Runs `action` on one row and returns the status the plugin reports.
+pub fn run_action(
+    plugin: &PluginDef,
+    tab: &str,
+    action: &str,
+    row_id: &str,
+    data: &Value,
+) -> Result<String> {
+    let reply: ActionReply = call(
+        plugin,
+        json!({ "request": "action", "tab": tab, "action": action, "row": row_id }),
+        data,
+    )?;
+    Ok(if reply.status.trim().is_empty() {
+        format!("{}: {action} done", plugin.name)
+    } else {
+        reply.status.trim().to_owned()
+    })
+}
+
+fn cell_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Sends one request, with the house records from [`house_data`] as `data`,
+/// and decodes the reply. A plugin that hasn't exited by its timeout is
+/// killed, so a hung program can't freeze the tab that called it.
+fn call<T: serde::de::DeserializeOwned>(
+    plugin: &PluginDef,
+    mut request: Value,
+    data: &Value,
+) -> Result<T> {
+    request["data"] = data.clone();
+    let mut child = Command::new(&plugin.command)
+        .args(&plugin.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "run plugin `{}` ({}); check its command in plugins.toml",
+                plugin.name, plugin.command
+            )
+        })?;
+    let mut stdin = child.stdin.take().context("open plugin stdin")?;
+    let input = request.to_string();
+    // Same as the text extractors: write from another thread so a plugin
+    // that answers before reading everything can't deadlock on the pipe.
+    let writer = thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let stdout = drain(child.stdout.take().context("open plugin stdout")?);
+    let stderr = drain(child.stderr.take().context("open plugin stderr")?);
+    let deadline = Instant::now() + plugin.timeout;
+    let status = loop {
+        if let Some(status) = child
+            .try_wait()
+            .with_context(|| format!("wait for plugin `{}`", plugin.name))?
+        {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            // The readers are left to finish on their own: anything the
+            // plugin forked may still hold the pipes open.
+            bail!(
+                "plugin `{}` did not answer within {}s and was stopped; \
+                 fix the plugin or raise its timeout in plugins.toml",
+                plugin.name,
+                plugin.timeout.as_secs_f64()
+            );
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+    let _ = writer.join();
+    let read = |pipe: thread::JoinHandle<std::io::Result<Vec<u8>>>| {
+        pipe.join()
+            .map_err(|_| anyhow!("plugin `{}` output could not be read", plugin.name))?
+            .with_context(|| format!("plugin `{}` output could not be read", plugin.name))
+    };
+    let output = std::process::Output {
+        status,
+        stdout: read(stdout)?,
+        stderr: read(stderr)?,
+    };
+    if !output.status.success() {
+        bail!(
+            "plugin `{}` failed ({}): {}",
+            plugin.name,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let reply: Value = serde_json::from_slice(&output.stdout)
+        .with_context(|| format!("plugin `{}` did not reply with JSON", plugin.name))?;
+    if let Some(error) = reply.get("error").and_then(Value::as_str) {
+        return Err(anyhow!("plugin `{}`: {error}", plugin.name));
+    }
+    serde_json::from_value(reply)
+        .with_context(|| format!("plugin `{}` sent an unexpected reply", plugin.name))
+}
+
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Reads a pipe to the end on its own thread.
+fn drain(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<std::io::Result<Vec<u8>>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        pipe.read_to_end(&mut bytes)?;
+        Ok(bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DEFAULT_TIMEOUT, PluginDef, describe, house_data, load_rows, parse_plugins, run_action,
+    };
+    use anyhow::Result;
+    use micasa_app::ProjectStatus;
+    use micasa_db::{LifecycleEntityRef, NewProject, Store};
+    use serde_json::{Value, json};
+    use std::time::{Duration, Instant};
+
+    fn script_plugin(body: &str) -> PluginDef {
+        PluginDef {
+            name: "solar".to_owned(),
+            command: "sh".to_owned(),
+            args: vec!["-c".to_owned(), body.to_owned()],
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    #[test]
+    fn parse_plugins_requires_unique_names_and_a_command() -> Result<()> {
+        let plugins = parse_plugins(
+            "[[plugin]]\nname = \"solar\"\ncommand = \"micasa-solar\"\nargs = [\"--site\", \"roof\"]\n",
+        )?;
+        assert_eq!(plugins[0].name, "solar");
+        assert_eq!(plugins[0].args, vec!["--site", "roof"]);
+        assert_eq!(plugins[0].timeout, DEFAULT_TIMEOUT);
+        let slow = parse_plugins("[[plugin]]\nname = \"a\"\ncommand = \"x\"\ntimeout = \"2m\"\n")?;
+        assert_eq!(slow[0].timeout, Duration::from_secs(120));
+        assert!(
+            parse_plugins("[[plugin]]\nname = \"a\"\ncommand = \"x\"\ntimeout = \"0s\"\n").is_err()
+        );
+        assert!(parse_plugins("").expect("empty file").is_empty());
+
+        let error = parse_plugins(
+            "[[plugin]]\nname = \"a\"\ncommand = \"x\"\n[[plugin]]\nname = \"a\"\ncommand = \"y\"\n",
+        )
+        .expect_err("duplicate");
+        assert!(error.to_string().contains("defined twice"));
+        assert!(parse_plugins("[[plugin]]\nname = \"a\"\ncommand = \" \"\n").is_err());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugins_answer_describe_rows_and_actions_over_stdio() -> Result<()> {
+        let plugin = script_plugin(
+            r#"read request
+case "$request" in
+  *describe*) echo '{"tabs":[{"name":"production","title":"Solar production","actions":[{"name":"flag","label":"Flag low day"}]}]}' ;;
+  *rows*) echo '{"columns":["day","kwh"],"rows":[{"id":"d1","cells":["2026-10-01",21.5]},{"id":"d2","cells":["2026-10-02"]}]}' ;;
+  *'"row":"d2"'*) echo '{"status":"flagged 2026-10-02"}' ;;
+  *) echo '{"error":"unknown row"}' ;;
+esac"#,
+        );
+        let tabs = describe(&plugin, &json!({}))?;
+        assert_eq!(tabs.len(), 1);
+        assert_eq!(tabs[0].plugin, "solar");
+        assert_eq!(tabs[0].title, "Solar production");
+        assert_eq!(tabs[0].actions[0].label, "Flag low day");
+
+        let rows = load_rows(&plugin, "production", &json!({}))?;
+        assert_eq!(rows.columns, vec!["day", "kwh"]);
+        assert_eq!(rows.rows[0].cells, vec!["2026-10-01", "21.5"]);
+        assert_eq!(rows.rows[1].cells, vec!["2026-10-02", ""], "padded");
+
+        assert_eq!(
+            run_action(&plugin, "production", "flag", "d2", &json!({}))?,
+            "flagged 2026-10-02"
+        );
+        let error = run_action(&plugin, "production", "flag", "d9", &json!({})).expect_err("error");
+        assert_eq!(error.to_string(), "plugin `solar`: unknown row");
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_failures_name_the_plugin() {
+        let error =
+            describe(&script_plugin("echo boom >&2; exit 3"), &json!({})).expect_err("fails");
+        assert!(
+            error.to_string().contains("plugin `solar` failed"),
+            "{error}"
+        );
+        assert!(error.to_string().contains("boom"), "{error}");
+
+        let error = describe(&script_plugin("echo not json"), &json!({})).expect_err("not json");
+        assert!(format!("{error:#}").contains("did not reply with JSON"));
+
+        let missing = PluginDef {
+            name: "gone".to_owned(),
+            command: "/nonexistent/micasa-plugin".to_owned(),
+            args: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        };
+        let error = describe(&missing, &json!({})).expect_err("missing");
+        assert!(
+            error
+                .to_string()
+                .contains("check its command in plugins.toml")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugins_that_hang_are_stopped_at_their_timeout() {
+        let mut plugin = script_plugin("sleep 30");
+        plugin.timeout = Duration::from_millis(200);
+        let started = Instant::now();
+        let error = describe(&plugin, &json!({})).expect_err("times out");
+        assert!(started.elapsed() < Duration::from_secs(10), "not killed");
+        assert!(
+            error.to_string().contains("did not answer within 0.2s"),
+            "{error}"
+        );
+        assert!(error.to_string().contains("raise its timeout"), "{error}");
+    }
+
+    fn project(store: &Store, title: &str) -> Result<micasa_app::ProjectId> {
+        store.create_project(&NewProject {
+            title: title.to_owned(),
+            project_type_id: store.list_project_types()?[0].id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        })
+    }
+
+    fn titles(data: &Value) -> Vec<String> {
+        let projects = &data["projects"];
+        let title = projects["columns"]
+            .as_array()
+            .and_then(|columns| columns.iter().position(|column| column == "title"))
+            .expect("title column");
+        projects["rows"]
+            .as_array()
+            .expect("project rows")
+            .iter()
+            .map(|row| row[title].as_str().unwrap_or_default().to_owned())
+            .collect()
+    }
+
+    #[test]
+    fn house_data_hides_private_and_archived_rows_like_the_tabs() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        project(&store, "Paint Fence")?;
+        let private = project(&store, "Safe Deposit")?;
+        let archived = project(&store, "Old Deck")?;
+        store.set_row_private(LifecycleEntityRef::Project(private), true)?;
+        store.set_row_archived(LifecycleEntityRef::Project(archived), true)?;
+
+        let data = house_data(&store, true, true)?;
+        assert_eq!(titles(&data), vec!["Paint Fence"]);
+        assert!(data.get("settings").is_none());
+        assert!(data.get("private_rows").is_none());
+        let documents = data["documents"]["columns"]
+            .as_array()
+            .expect("document columns");
+        assert!(!documents.iter().any(|column| column == "data"));
+
+        assert_eq!(
+            titles(&house_data(&store, false, true)?),
+            vec!["Paint Fence", "Safe Deposit"]
+        );
+        assert_eq!(titles(&house_data(&store, false, false)?).len(), 3);
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn plugin_requests_carry_data_and_no_database_path() -> Result<()> {
+        let plugin = script_plugin(
+            r#"read request
+case "$request" in
+  *db_path*) echo '{"error":"got a database path"}' ;;
+  *'"data":{"projects"'*) echo '{"tabs":[{"name":"seen"}]}' ;;
+  *) echo '{"error":"no data"}' ;;
+esac"#,
+        );
+        let data = json!({ "projects": { "columns": ["id"], "rows": [[1]] } });
+        assert_eq!(describe(&plugin, &data)?[0].name, "seen");
+        Ok(())
+    }
+}
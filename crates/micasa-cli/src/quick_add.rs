@@ -0,0 +1,280 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{
+    ApplianceFormInput, FormPayload, IncidentFormInput, IncidentSeverity, IncidentStatus,
+    MaintenanceItemFormInput, ProjectFormInput, ProjectStatus, VendorFormInput,
+};
+use micasa_db::Store;
+use micasa_db::validation::{DATE_LAYOUT, parse_optional_cents, parse_optional_date};
+use micasa_llm::extract_json_object;
+use serde_json::{Map, Value};
+use time::Date;
+
+/// Names the model may use for `project_type` and `category`, so the draft
+/// lands on an existing lookup row instead of a guess.
+pub fn known_values(store: &Store) -> Result<String> {
+    let names = |values: Vec<String>| values.join(", ");
+    Ok(format!(
+        "project types: {}\nmaintenance categories: {}",
+        names(
+            store
+                .list_project_types()?
+                .into_iter()
+                .map(|value| value.name)
+                .collect()
+        ),
+        names(
+            store
+                .list_maintenance_categories()?
+                .into_iter()
+                .map(|value| value.name)
+                .collect()
+        ),
+    ))
+}
+
+/// Converts the model's `{"kind", "fields"}` answer into a form draft. Fields
+/// the note left out get the same defaults as a blank form; nothing is saved
+/// until the user submits it.
+pub fn payload_from_answer(store: &Store, raw: &str, today: Date) -> Result<FormPayload> {
+    let json = extract_json_object(raw)
+        .ok_or_else(|| anyhow!("the model answered without a JSON record; rephrase and retry"))?;
+    let answer: Value = serde_json::from_str(json).context("parse quick-add JSON")?;
+    let kind = answer
+        .get("kind")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("quick-add answer is missing `kind`; rephrase and retry"))?;
+    let empty = Map::new();
+    let fields = Fields(
+        answer
+            .get("fields")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty),
+    );
+
+    let payload = match kind.trim().to_ascii_lowercase().as_str() {
+        "incident" => FormPayload::Incident(IncidentFormInput {
+            title: fields.text("title"),
+            description: fields.text("description"),
+            status: fields
+                .choice("status", IncidentStatus::parse)?
+                .unwrap_or(IncidentStatus::Open),
+            severity: fields
+                .choice("severity", IncidentSeverity::parse)?
+                .unwrap_or(IncidentSeverity::Soon),
+            date_noticed: fields.date("date_noticed")?.unwrap_or(today),
+            date_resolved: None,
+            follow_up_date: None,
+            location: fields.text("location"),
+            cost_cents: fields.money("cost")?,
+            appliance_id: None,
+            vendor_id: None,
+            notes: String::new(),
+        }),
+        "project" => {
+            let types = store.list_project_types()?;
+            let wanted = fields.text("project_type");
+            let project_type = types
+                .iter()
+                .find(|value| value.name.eq_ignore_ascii_case(&wanted))
+                .or_else(|| types.first())
+                .ok_or_else(|| anyhow!("no project types exist; add one and retry"))?;
+            FormPayload::Project(ProjectFormInput {
+                title: fields.text("title"),
+                project_type_id: project_type.id,
+                status: fields
+                    .choice("status", ProjectStatus::parse)?
+                    .unwrap_or(ProjectStatus::Planned),
+                description: fields.text("description"),
+                start_date: fields.date("start_date")?,
+                end_date: fields.date("end_date")?,
+                budget_cents: fields.money("budget")?,
+                actual_cents: None,
+                parent_id: None,
+            })
+        }
+        "appliance" => FormPayload::Appliance(ApplianceFormInput {
+            name: fields.text("name"),
+            brand: fields.text("brand"),
+            model_number: fields.text("model_number"),
+            serial_number: fields.text("serial_number"),
+            purchase_date: fields.date("purchase_date")?,
+            warranty_expiry: fields.date("warranty_expiry")?,
+            location: fields.text("location"),
+            cost_cents: fields.money("cost")?,
+            notes: String::new(),
+        }),
+        "maintenance" => {
+            let categories = store.list_maintenance_categories()?;
+            let wanted = fields.text("category");
+            let category = categories
+                .iter()
+                .find(|value| value.name.eq_ignore_ascii_case(&wanted))
+                .or_else(|| categories.first())
+                .ok_or_else(|| anyhow!("no maintenance categories exist; add one and retry"))?;
+            let interval_months = match fields.text("interval_months") {
+                raw if raw.is_empty() => 1,
+                raw => raw
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|months| *months >= 0)
+                    .ok_or_else(|| anyhow!("interval_months `{raw}` is not a whole number"))?,
+            };
+            FormPayload::Maintenance(MaintenanceItemFormInput {
+                name: fields.text("name"),
+                category_id: category.id,
+                appliance_id: None,
+                last_serviced_at: fields.date("last_serviced_at")?,
+                interval_months,
+                manual_url: String::new(),
+                manual_text: String::new(),
+                notes: String::new(),
+                cost_cents: fields.money("cost")?,
+            })
+        }
+        "vendor" => FormPayload::Vendor(VendorFormInput {
+            name: fields.text("name"),
+            contact_name: fields.text("contact_name"),
+            email: fields.text("email"),
+            phone: fields.text("phone"),
+            website: fields.text("website"),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        }),
+        other => bail!(
+            "quick add can't draft `{other}` records; start the note with incident, project, appliance, maintenance, or vendor"
+        ),
+    };
+    Ok(payload)
+}
+
+struct Fields<'a>(&'a Map<String, Value>);
+
+impl Fields<'_> {
+    /// Strings are trimmed; numbers and booleans come back as written.
+    fn text(&self, key: &str) -> String {
+        match self.0.get(key) {
+            Some(Value::String(value)) => value.trim().to_owned(),
+            Some(Value::Number(value)) => value.to_string(),
+            Some(Value::Bool(value)) => value.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn date(&self, key: &str) -> Result<Option<Date>> {
+        let raw = self.text(key);
+        parse_optional_date(&raw)
+            .map_err(|error| anyhow!("{key} `{raw}`: {error}; expected {DATE_LAYOUT}"))
+    }
+
+    fn money(&self, key: &str) -> Result<Option<i64>> {
+        let raw = self.text(key);
+        parse_optional_cents(raw.trim_start_matches('$'))
+            .map_err(|error| anyhow!("{key} `{raw}`: {error}; expected dollars like 125.50"))
+    }
+
+    fn choice<T>(&self, key: &str, parse: fn(&str) -> Option<T>) -> Result<Option<T>> {
+        let raw = self.text(key).to_ascii_lowercase().replace([' ', '-'], "_");
+        if raw.is_empty() {
+            return Ok(None);
+        }
+        parse(&raw)
+            .map(Some)
+            .ok_or_else(|| anyhow!("{key} `{raw}` is not a known value"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{known_values, payload_from_answer};
+    use anyhow::Result;
+    use micasa_app::{FormPayload, IncidentSeverity, IncidentStatus};
+    use micasa_db::Store;
+    use time::macros::date;
+
+    fn bootstrapped_store() -> Result<Store> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        Ok(store)
+    }
+
+    #[test]
+    fn incident_answer_becomes_a_draft_with_defaults() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let answer = "```json\n{\"kind\": \"incident\", \"fields\": {\"title\": \"Water heater leaking\", \"severity\": \"Urgent\", \"cost\": 125.5}}\n```";
+        let FormPayload::Incident(input) =
+            payload_from_answer(&store, answer, date!(2026 - 10 - 16))?
+        else {
+            panic!("expected an incident draft");
+        };
+        assert_eq!(input.title, "Water heater leaking");
+        assert_eq!(input.severity, IncidentSeverity::Urgent);
+        assert_eq!(input.status, IncidentStatus::Open);
+        assert_eq!(input.date_noticed, date!(2026 - 10 - 16));
+        assert_eq!(input.cost_cents, Some(12_550));
+        Ok(())
+    }
+
+    #[test]
+    fn lookup_names_resolve_and_fall_back_to_the_first_row() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let types = store.list_project_types()?;
+        let last = types.last().expect("seeded project types");
+        let answer = format!(
+            "{{\"kind\": \"project\", \"fields\": {{\"title\": \"Deck\", \"project_type\": \"{}\", \"start_date\": \"2026-11-02\"}}}}",
+            last.name.to_uppercase()
+        );
+        let FormPayload::Project(input) =
+            payload_from_answer(&store, &answer, date!(2026 - 10 - 16))?
+        else {
+            panic!("expected a project draft");
+        };
+        assert_eq!(input.project_type_id, last.id);
+        assert_eq!(input.start_date, Some(date!(2026 - 11 - 02)));
+
+        let FormPayload::Maintenance(input) = payload_from_answer(
+            &store,
+            "{\"kind\": \"maintenance\", \"fields\": {\"name\": \"Gutters\", \"category\": \"nope\", \"interval_months\": 6}}",
+            date!(2026 - 10 - 16),
+        )?
+        else {
+            panic!("expected a maintenance draft");
+        };
+        assert_eq!(
+            input.category_id,
+            store.list_maintenance_categories()?[0].id
+        );
+        assert_eq!(input.interval_months, 6);
+
+        let listed = known_values(&store)?;
+        assert!(listed.starts_with("project types: "));
+        assert!(listed.contains(&last.name));
+        Ok(())
+    }
+
+    #[test]
+    fn bad_answers_explain_what_to_fix() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let today = date!(2026 - 10 - 16);
+        let error = payload_from_answer(&store, "I can't help with that", today)
+            .expect_err("no JSON should fail");
+        assert!(error.to_string().contains("without a JSON record"));
+
+        let error = payload_from_answer(&store, "{\"kind\": \"quote\", \"fields\": {}}", today)
+            .expect_err("quotes are not drafted");
+        assert!(error.to_string().contains("can't draft `quote`"));
+
+        let error = payload_from_answer(
+            &store,
+            "{\"kind\": \"appliance\", \"fields\": {\"purchase_date\": \"last spring\"}}",
+            today,
+        )
+        .expect_err("loose dates should fail");
+        assert!(error.to_string().contains("purchase_date `last spring`"));
+        Ok(())
+    }
+}
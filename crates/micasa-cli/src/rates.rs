@@ -0,0 +1,185 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_db::currency::{
+    format_rate_micros, invert_fetched_rate, parse_currency_code, parse_rate_micros,
+};
+use micasa_db::{CurrencyRate, RateSource, Store};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Parses `EUR=1.08` (home units per one EUR) into a code and scaled rate.
+pub fn parse_rate_arg(raw: &str, home: &str) -> Result<(String, i64)> {
+    let (code, rate) = raw
+        .split_once('=')
+        .ok_or_else(|| anyhow!("rate `{raw}` needs the form CODE=RATE, like EUR=1.08"))?;
+    let code = parse_currency_code(code)?;
+    if code == home {
+        bail!("{code} is the home currency; its rate is always 1");
+    }
+    Ok((code, parse_rate_micros(rate)?))
+}
+
+pub fn set_manual_rate(store: &Store, home: &str, raw: &str) -> Result<String> {
+    let (code, micros) = parse_rate_arg(raw, home)?;
+    store.set_currency_rate(&code, micros, RateSource::Manual)?;
+    Ok(format!("1 {code} = {} {home}", format_rate_micros(micros)))
+}
+
+#[derive(Debug, Deserialize)]
+struct FetchedRates {
+    base: Option<String>,
+    rates: BTreeMap<String, f64>,
+}
+
+/// Fetches `{"base": "USD", "rates": {"EUR": 0.92}}` style JSON (foreign
+/// units per home unit) and stores the inverted rates. `{home}` in the URL is
+/// replaced with the home currency code. Returns how many rates were saved.
+pub fn fetch_rates(store: &Store, home: &str, url: &str, timeout: Duration) -> Result<usize> {
+    let url = url.replace("{home}", home);
+    let http = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .context("build HTTP client")?;
+    let response = http
+        .get(&url)
+        .send()
+        .with_context(|| format!("fetch rates from {url}; check currency.rates_url"))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("rate source {url} answered {status}; check currency.rates_url");
+    }
+    let fetched: FetchedRates = response
+        .json()
+        .with_context(|| format!("decode rates from {url}; expected a JSON `rates` object"))?;
+    if let Some(base) = &fetched.base
+        && !base.eq_ignore_ascii_case(home)
+    {
+        bail!(
+            "rate source quotes against {base} but the home currency is {home}; point currency.rates_url at {home} rates"
+        );
+    }
+
+    let mut parsed = Vec::new();
+    for (code, foreign_per_home) in &fetched.rates {
+        let code = parse_currency_code(code)?;
+        if code == home {
+            continue;
+        }
+        let micros =
+            invert_fetched_rate(*foreign_per_home).with_context(|| format!("rate for {code}"))?;
+        parsed.push((code, micros));
+    }
+    for (code, micros) in &parsed {
+        store.set_currency_rate(code, *micros, RateSource::Fetched)?;
+    }
+    Ok(parsed.len())
+}
+
+pub fn format_rates(rates: &[CurrencyRate], home: &str) -> String {
+    if rates.is_empty() {
+        return format!("no rates; amounts are all in {home}\n");
+    }
+    rates
+        .iter()
+        .map(|rate| {
+            format!(
+                "1 {} = {} {home} ({}, {})\n",
+                rate.code,
+                format_rate_micros(rate.home_per_unit_micros),
+                rate.source.as_str(),
+                rate.updated_at.date()
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fetch_rates, format_rates, parse_rate_arg, set_manual_rate};
+    use anyhow::{Result, anyhow};
+    use micasa_db::Store;
+    use std::thread;
+    use std::time::Duration;
+    use tiny_http::{Response, Server};
+
+    #[test]
+    fn manual_rates_parse_and_reject_the_home_currency() -> Result<()> {
+        assert_eq!(
+            parse_rate_arg("eur=1.08", "USD")?,
+            ("EUR".to_owned(), 1_080_000)
+        );
+        for (raw, expected) in [
+            ("EUR", "CODE=RATE"),
+            ("usd=1", "home currency"),
+            ("EUR=0", "greater than zero"),
+        ] {
+            let error = parse_rate_arg(raw, "USD").expect_err(raw);
+            assert!(error.to_string().contains(expected), "{raw}: {error}");
+        }
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        assert_eq!(
+            set_manual_rate(&store, "USD", "CAD=0.73")?,
+            "1 CAD = 0.73 USD"
+        );
+        let listing = format_rates(&store.list_currency_rates()?, "USD");
+        assert!(listing.starts_with("1 CAD = 0.73 USD (manual, "));
+        Ok(())
+    }
+
+    #[test]
+    fn fetched_rates_are_inverted_and_skip_home() -> Result<()> {
+        let server =
+            Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+        let url = format!("http://{}/latest?from={{home}}", server.server_addr());
+        let handle = thread::spawn(move || {
+            let request = server.recv().expect("request expected");
+            assert_eq!(request.url(), "/latest?from=USD");
+            request
+                .respond(Response::from_string(
+                    r#"{"base":"USD","rates":{"EUR":0.8,"USD":1.0}}"#,
+                ))
+                .expect("response should succeed");
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        assert_eq!(fetch_rates(&store, "USD", &url, Duration::from_secs(2))?, 1);
+        handle.join().expect("server thread");
+        let rates = store.list_currency_rates()?;
+        assert_eq!(rates.len(), 1);
+        assert_eq!(
+            (rates[0].code.as_str(), rates[0].home_per_unit_micros),
+            ("EUR", 1_250_000)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetched_rates_for_another_base_are_rejected() -> Result<()> {
+        let server =
+            Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+        let url = format!("http://{}/latest", server.server_addr());
+        let handle = thread::spawn(move || {
+            let request = server.recv().expect("request expected");
+            request
+                .respond(Response::from_string(
+                    r#"{"base":"EUR","rates":{"USD":1.1}}"#,
+                ))
+                .expect("response should succeed");
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let error = fetch_rates(&store, "USD", &url, Duration::from_secs(2))
+            .expect_err("base mismatch should fail");
+        handle.join().expect("server thread");
+        assert!(error.to_string().contains("home currency is USD"));
+        assert!(store.list_currency_rates()?.is_empty());
+        Ok(())
+    }
+}
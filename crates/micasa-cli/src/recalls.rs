@@ -0,0 +1,328 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, bail};
+use micasa_db::Store;
+use micasa_db::recalls::{Recall, RecallProvider, recall_matches};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+pub const DEFAULT_RECALL_PROVIDER: &str = "cpsc";
+pub const DEFAULT_CPSC_BASE_URL: &str = "https://www.saferproducts.gov/RestWebServices/Recall";
+pub const RECALL_PROVIDERS: &[&str] = &[DEFAULT_RECALL_PROVIDER, "dataset"];
+
+/// Queries the U.S. CPSC recalls API by model number.
+pub struct CpscProvider {
+    base_url: String,
+    http: reqwest::blocking::Client,
+}
+
+impl CpscProvider {
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self> {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(timeout)
+            .build()
+            .context("build HTTP client")?;
+        Ok(Self {
+            base_url: base_url.to_owned(),
+            http,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CpscRecall {
+    recall_number: Option<String>,
+    #[serde(rename = "RecallID")]
+    recall_id: Option<i64>,
+    #[serde(default)]
+    title: String,
+    #[serde(rename = "URL", default)]
+    url: String,
+    #[serde(default)]
+    products: Vec<CpscProduct>,
+    #[serde(default)]
+    manufacturers: Vec<CpscName>,
+    #[serde(default)]
+    hazards: Vec<CpscName>,
+    #[serde(default)]
+    remedies: Vec<CpscName>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CpscProduct {
+    #[serde(default)]
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CpscName {
+    #[serde(default)]
+    name: String,
+}
+
+fn join_names(names: &[CpscName]) -> String {
+    names
+        .iter()
+        .map(|entry| entry.name.trim())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl RecallProvider for CpscProvider {
+    fn name(&self) -> &'static str {
+        "cpsc"
+    }
+
+    fn lookup(&self, _brand: &str, model_number: &str) -> Result<Vec<Recall>> {
+        let response = self
+            .http
+            .get(&self.base_url)
+            .query(&[("format", "json"), ("ProductModel", model_number)])
+            .send()
+            .with_context(|| {
+                format!(
+                    "fetch recalls from {}; check recalls.base_url",
+                    self.base_url
+                )
+            })?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!(
+                "recall source {} answered {status}; check recalls.base_url",
+                self.base_url
+            );
+        }
+        let fetched: Vec<CpscRecall> = response.json().with_context(|| {
+            format!(
+                "decode recalls from {}; expected a JSON array of recalls",
+                self.base_url
+            )
+        })?;
+        Ok(fetched
+            .into_iter()
+            .filter_map(|recall| {
+                let id = recall
+                    .recall_number
+                    .filter(|number| !number.trim().is_empty())
+                    .or_else(|| recall.recall_id.map(|id| id.to_string()))?;
+                Some(Recall {
+                    id,
+                    title: recall.title,
+                    brand: join_names(&recall.manufacturers),
+                    // CPSC lists several models in one free-text field.
+                    model_numbers: recall
+                        .products
+                        .iter()
+                        .flat_map(|product| {
+                            product
+                                .model
+                                .split(|ch: char| ch == ',' || ch == ';' || ch.is_whitespace())
+                                .filter(|model| !model.is_empty())
+                                .map(str::to_owned)
+                                .collect::<Vec<_>>()
+                        })
+                        .collect(),
+                    hazard: join_names(&recall.hazards),
+                    remedy: join_names(&recall.remedies),
+                    url: recall.url,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Reads recalls from a local JSON file, for offline use or other sources.
+pub struct DatasetProvider {
+    recalls: Vec<Recall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DatasetRecall {
+    id: String,
+    title: String,
+    brand: String,
+    models: Vec<String>,
+    #[serde(default)]
+    hazard: String,
+    #[serde(default)]
+    remedy: String,
+    #[serde(default)]
+    url: String,
+}
+
+impl DatasetProvider {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("read recall dataset {}", path.display()))?;
+        let entries: Vec<DatasetRecall> = serde_json::from_str(&raw).with_context(|| {
+            format!(
+                "parse recall dataset {}; expected a JSON array of {{id, title, brand, models}}",
+                path.display()
+            )
+        })?;
+        Ok(Self {
+            recalls: entries
+                .into_iter()
+                .map(|entry| Recall {
+                    id: entry.id,
+                    title: entry.title,
+                    brand: entry.brand,
+                    model_numbers: entry.models,
+                    hazard: entry.hazard,
+                    remedy: entry.remedy,
+                    url: entry.url,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl RecallProvider for DatasetProvider {
+    fn name(&self) -> &'static str {
+        "dataset"
+    }
+
+    fn lookup(&self, brand: &str, model_number: &str) -> Result<Vec<Recall>> {
+        Ok(self
+            .recalls
+            .iter()
+            .filter(|recall| recall_matches(recall, brand, model_number))
+            .cloned()
+            .collect())
+    }
+}
+
+/// Checks every live appliance with a brand and model number, files new
+/// matches as incidents, and returns one report line per appliance.
+pub fn check_recalls(store: &Store, provider: &dyn RecallProvider) -> Result<String> {
+    let mut report = String::new();
+    let appliances = store.list_appliances(false)?;
+    for appliance in &appliances {
+        if appliance.brand.trim().is_empty() || appliance.model_number.trim().is_empty() {
+            report.push_str(&format!(
+                "{}: skipped; add a brand and model number\n",
+                appliance.name
+            ));
+            continue;
+        }
+        let matches = provider
+            .lookup(&appliance.brand, &appliance.model_number)
+            .with_context(|| format!("check {} against {}", appliance.name, provider.name()))?
+            .into_iter()
+            .filter(|recall| recall_matches(recall, &appliance.brand, &appliance.model_number))
+            .collect::<Vec<_>>();
+        let created = store.record_recall_check(appliance.id, &matches)?;
+        let line = match created.as_slice() {
+            [] if matches.is_empty() => "no recalls".to_owned(),
+            [] => format!("{} known recall(s), nothing new", matches.len()),
+            ids => format!(
+                "{} new recall(s) filed as incident {}",
+                ids.len(),
+                ids.iter()
+                    .map(|id| format!("#{}", id.get()))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        report.push_str(&format!("{}: {line}\n", appliance.name));
+    }
+    report.push_str(&format!(
+        "checked {} appliances against {}\n",
+        appliances.len(),
+        provider.name()
+    ));
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CpscProvider, DatasetProvider, check_recalls};
+    use anyhow::{Result, anyhow};
+    use micasa_db::recalls::RecallProvider;
+    use micasa_db::{NewAppliance, Store};
+    use std::thread;
+    use std::time::Duration;
+    use tiny_http::{Response, Server};
+
+    fn appliance(name: &str, brand: &str, model_number: &str) -> NewAppliance {
+        NewAppliance {
+            name: name.to_owned(),
+            brand: brand.to_owned(),
+            model_number: model_number.to_owned(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: None,
+            location: String::new(),
+            cost_cents: None,
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn dataset_matches_file_incidents_once() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("recalls.json");
+        std::fs::write(
+            &path,
+            r#"[{"id":"R-1","title":"Dishwasher heater","brand":"Bosch","models":["SHX-878WD5N"]},
+                {"id":"R-2","title":"Other dishwasher","brand":"Acme","models":["SHX878WD5N"]}]"#,
+        )?;
+        let provider = DatasetProvider::load(&path)?;
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.create_appliance(&appliance("Dishwasher", "Bosch", "shx878wd5n"))?;
+        store.create_appliance(&appliance("Fridge", "", ""))?;
+
+        let report = check_recalls(&store, &provider)?;
+        assert!(report.contains("Dishwasher: 1 new recall(s) filed as incident #1"));
+        assert!(report.contains("Fridge: skipped"));
+        assert!(report.ends_with("checked 2 appliances against dataset\n"));
+
+        let report = check_recalls(&store, &provider)?;
+        assert!(report.contains("Dishwasher: 1 known recall(s), nothing new"));
+        assert_eq!(store.list_incidents(false)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn cpsc_results_split_model_lists() -> Result<()> {
+        let server =
+            Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+        let url = format!("http://{}/Recall", server.server_addr());
+        let handle = thread::spawn(move || {
+            let request = server.recv().expect("request expected");
+            assert_eq!(request.url(), "/Recall?format=json&ProductModel=SHX878WD5N");
+            request
+                .respond(Response::from_string(
+                    r#"[{"RecallID":9,"RecallNumber":"26-042","Title":"Bosch recalls dishwashers",
+                    "URL":"https://www.cpsc.gov/Recalls/2026/26-042",
+                    "Products":[{"Model":"SHX878WD5N, SHX878WD6N"}],
+                    "Manufacturers":[{"Name":"BSH Home Appliances (Bosch)"}],
+                    "Hazards":[{"Name":"Wiring can overheat."}],
+                    "Remedies":[{"Name":"Repair"}]}]"#,
+                ))
+                .expect("response should succeed");
+        });
+
+        let provider = CpscProvider::new(&url, Duration::from_secs(2))?;
+        let recalls = provider.lookup("Bosch", "SHX878WD5N")?;
+        handle.join().expect("server thread");
+        assert_eq!(recalls.len(), 1);
+        assert_eq!(recalls[0].id, "26-042");
+        assert_eq!(recalls[0].model_numbers, vec!["SHX878WD5N", "SHX878WD6N"]);
+        assert!(micasa_db::recalls::recall_matches(
+            &recalls[0],
+            "Bosch",
+            "SHX878WD5N"
+        ));
+        Ok(())
+    }
+}
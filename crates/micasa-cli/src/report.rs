@@ -0,0 +1,619 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{
+    DeepLink, DeletionEntity, DocumentEntityKind, ReportFormat, ReportSummary, TabKind,
+    tab_for_slug,
+};
+use micasa_db::Store;
+use micasa_tui::export::csv_field;
+use micasa_tui::qr::QrCode;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use time::OffsetDateTime;
+
+/// Pixels per QR module in printed reports; about 2.5 cm for a short link.
+const QR_SCALE: usize = 3;
+
+pub fn escape_html(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Self-contained page: no scripts or external assets, so it prints the same
+/// offline.
+pub fn html_page(title: &str, body: &str) -> String {
+    format!(
+        "<!doctype html>\n<html><head><meta charset=\"utf-8\">\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\"><title>{title}</title>\n\
+<style>body{{font-family:sans-serif;max-width:48em;margin:2em auto}}\
+.item{{display:flex;gap:1em;align-items:flex-start;break-inside:avoid;margin-bottom:1em}}\
+figure{{margin:0;text-align:center;font-size:.7em}}.blank{{color:#666}}\
+table{{border-collapse:collapse}}th,td{{border:1px solid #ccc;padding:.2em .5em;text-align:left}}</style></head>\n\
+<body><h1>{title}</h1>\n{body}</body></html>\n",
+        title = escape_html(title),
+    )
+}
+
+/// The link as an inline SVG QR code, captioned with the link text.
+pub fn qr_figure(link: &DeepLink) -> Result<String> {
+    let link = link.to_string();
+    let code = QrCode::encode(link.as_bytes())?;
+    Ok(format!(
+        "<figure>{}<figcaption>{}</figcaption></figure>",
+        code.to_svg(QR_SCALE),
+        escape_html(&link)
+    ))
+}
+
+const fn owner_tab(kind: DocumentEntityKind) -> Option<TabKind> {
+    match kind {
+        DocumentEntityKind::None => None,
+        DocumentEntityKind::Project => Some(TabKind::Projects),
+        DocumentEntityKind::Quote => Some(TabKind::Quotes),
+        DocumentEntityKind::Maintenance => Some(TabKind::Maintenance),
+        DocumentEntityKind::Appliance => Some(TabKind::Appliances),
+        DocumentEntityKind::ServiceLog => Some(TabKind::ServiceLog),
+        DocumentEntityKind::Vendor => Some(TabKind::Vendors),
+        DocumentEntityKind::Incident => Some(TabKind::Incidents),
+    }
+}
+
+/// Printable index of stored documents, each with a QR code for the document
+/// and a text link to the record it is attached to. Private documents are
+/// left off; the title names the active house.
+pub fn document_index_html(store: &Store) -> Result<String> {
+    let private = store.private_row_ids(DeletionEntity::Document)?;
+    let documents = store
+        .list_documents(false)?
+        .into_iter()
+        .filter(|document| !private.contains(&document.id.get()))
+        .collect::<Vec<_>>();
+
+    let mut body = format!("<p>{} documents</p>\n", documents.len());
+    for document in &documents {
+        let link = DeepLink::new(TabKind::Documents, document.id.get())?;
+        body.push_str("<div class=\"item\">");
+        body.push_str(&qr_figure(&link)?);
+        body.push_str(&format!(
+            "<p><strong>{}</strong><br>{} ({} bytes)",
+            escape_html(&document.title),
+            escape_html(&document.file_name),
+            document.size_bytes
+        ));
+        if let Some(owner) = owner_tab(document.entity_kind)
+            .and_then(|tab| DeepLink::new(tab, document.entity_id).ok())
+        {
+            body.push_str(&format!(
+                "<br>attached to {}",
+                escape_html(&owner.to_string())
+            ));
+        }
+        if !document.notes.trim().is_empty() {
+            body.push_str(&format!("<br>{}", escape_html(document.notes.trim())));
+        }
+        body.push_str("</p></div>\n");
+    }
+    let title = match store.active_house_name()? {
+        Some(house) => format!("Document index: {house}"),
+        None => "Document index".to_owned(),
+    };
+    Ok(html_page(&title, &body))
+}
+
+/// Columns a documents report shows by default; the file contents stay out.
+const DOCUMENT_COLUMNS: &str = "id, title, file_name, entity_kind, entity_id, mime_type, \
+size_bytes, notes, created_at, updated_at";
+
+/// A report from `reports.toml`: where its rows come from, how they are
+/// grouped and summed, and how the result is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportDef {
+    pub name: String,
+    pub title: String,
+    /// The rows before grouping: the `sql` as written, or a SELECT built from
+    /// `tab`, `columns` and `filter` that leaves out deleted rows.
+    pub source_sql: String,
+    pub group_by: Option<String>,
+    pub aggregates: Vec<Aggregate>,
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateFn {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+/// `count`, or a function applied to one column, like `sum:cost_cents`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Aggregate {
+    pub function: AggregateFn,
+    pub column: Option<String>,
+}
+
+impl Aggregate {
+    fn parse(raw: &str) -> Result<Self> {
+        let (name, column) = match raw.split_once(':') {
+            Some((name, column)) => (name.trim(), Some(column.trim())),
+            None => (raw.trim(), None),
+        };
+        let function = match name.to_ascii_lowercase().as_str() {
+            "count" => AggregateFn::Count,
+            "sum" => AggregateFn::Sum,
+            "avg" => AggregateFn::Avg,
+            "min" => AggregateFn::Min,
+            "max" => AggregateFn::Max,
+            _ => bail!(
+                "aggregate `{raw}` is unknown -- use count, or sum, avg, min or max with a column like sum:cost_cents"
+            ),
+        };
+        let column = column
+            .filter(|column| !column.is_empty())
+            .map(str::to_owned);
+        if column.is_none() && function != AggregateFn::Count {
+            bail!("aggregate `{raw}` needs a column, like {name}:cost_cents");
+        }
+        Ok(Self { function, column })
+    }
+
+    /// Result column name: `count`, `sum_cost_cents`.
+    fn label(&self) -> String {
+        let name = match self.function {
+            AggregateFn::Count => "count",
+            AggregateFn::Sum => "sum",
+            AggregateFn::Avg => "avg",
+            AggregateFn::Min => "min",
+            AggregateFn::Max => "max",
+        };
+        match &self.column {
+            Some(column) => format!("{name}_{column}"),
+            None => name.to_owned(),
+        }
+    }
+
+    fn sql(&self) -> String {
+        let column = self.column.as_deref().map(quote_identifier);
+        match (self.function, column) {
+            (AggregateFn::Count, None) => "COUNT(*)".to_owned(),
+            (AggregateFn::Count, Some(column)) => format!("COUNT({column})"),
+            (AggregateFn::Sum, Some(column)) => format!("SUM({column})"),
+            (AggregateFn::Avg, Some(column)) => format!("ROUND(AVG({column}), 2)"),
+            (AggregateFn::Min, Some(column)) => format!("MIN({column})"),
+            (AggregateFn::Max, Some(column)) => format!("MAX({column})"),
+            (_, None) => unreachable!("parse requires a column"),
+        }
+    }
+}
+
+impl ReportDef {
+    pub fn summary(&self) -> ReportSummary {
+        ReportSummary {
+            name: self.name.clone(),
+            title: self.title.clone(),
+            format: self.format,
+        }
+    }
+
+    /// The statement to run: the source as is, or wrapped in a GROUP BY when
+    /// the report groups or aggregates. Grouping without aggregates counts.
+    pub fn sql(&self) -> String {
+        if self.group_by.is_none() && self.aggregates.is_empty() {
+            return self.source_sql.clone();
+        }
+        let count = [Aggregate {
+            function: AggregateFn::Count,
+            column: None,
+        }];
+        let aggregates = if self.aggregates.is_empty() {
+            &count[..]
+        } else {
+            &self.aggregates[..]
+        };
+        let mut columns = Vec::new();
+        if let Some(group) = &self.group_by {
+            columns.push(quote_identifier(group));
+        }
+        columns.extend(aggregates.iter().map(|aggregate| {
+            format!(
+                "{} AS {}",
+                aggregate.sql(),
+                quote_identifier(&aggregate.label())
+            )
+        }));
+        let mut sql = format!(
+            "SELECT {} FROM ({}) AS source",
+            columns.join(", "),
+            self.source_sql
+        );
+        if let Some(group) = &self.group_by {
+            let group = quote_identifier(group);
+            sql.push_str(&format!(" GROUP BY {group} ORDER BY {group}"));
+        }
+        sql
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[derive(Debug, Deserialize)]
+struct ReportsFile {
+    #[serde(default)]
+    report: Vec<RawReport>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawReport {
+    name: String,
+    title: Option<String>,
+    sql: Option<String>,
+    tab: Option<String>,
+    columns: Option<Vec<String>>,
+    filter: Option<String>,
+    group_by: Option<String>,
+    #[serde(default)]
+    aggregates: Vec<String>,
+    format: Option<String>,
+}
+
+/// Tables a `tab = ...` report reads from; tabs without plain rows of their
+/// own can't be used.
+const fn tab_table(tab: TabKind) -> Option<&'static str> {
+    match tab {
+        TabKind::Projects => Some("projects"),
+        TabKind::Quotes => Some("quotes"),
+        TabKind::Maintenance => Some("maintenance_items"),
+        TabKind::ServiceLog => Some("service_log_entries"),
+        TabKind::Incidents => Some("incidents"),
+        TabKind::Appliances => Some("appliances"),
+        TabKind::Vendors => Some("vendors"),
+        TabKind::Documents => Some("documents"),
+        TabKind::Budget => Some("budgets"),
+        TabKind::Dashboard
+        | TabKind::House
+        | TabKind::Schedule
+        | TabKind::Settings
+        | TabKind::Query
+        | TabKind::CostHistory => None,
+    }
+}
+
+impl RawReport {
+    fn resolve(self) -> Result<ReportDef> {
+        let name = self.name.trim().to_owned();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || ch == '-' || ch == '_')
+        {
+            bail!("report name `{name}` must be letters, digits, - or _, like spend-by-vendor");
+        }
+        let source_sql = match (self.sql, self.tab) {
+            (Some(sql), None) => {
+                if self.columns.is_some() || self.filter.is_some() {
+                    bail!("report `{name}` sets sql; columns and filter only go with tab");
+                }
+                sql.trim().trim_end_matches(';').trim_end().to_owned()
+            }
+            (None, Some(tab)) => {
+                let table = tab_for_slug(&tab).and_then(tab_table).ok_or_else(|| {
+                    anyhow!(
+                        "report `{name}` names tab `{tab}` -- use projects, quotes, maint, service, incidents, appliances, vendors, docs or budget"
+                    )
+                })?;
+                let columns = match self.columns {
+                    Some(columns) if !columns.is_empty() => columns
+                        .iter()
+                        .map(|column| quote_identifier(column.trim()))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    _ if table == "documents" => DOCUMENT_COLUMNS.to_owned(),
+                    _ => "*".to_owned(),
+                };
+                let mut sql = format!("SELECT {columns} FROM {table} WHERE deleted_at IS NULL");
+                if let Some(filter) = self.filter.filter(|filter| !filter.trim().is_empty()) {
+                    sql.push_str(&format!(" AND ({})", filter.trim()));
+                }
+                sql
+            }
+            (Some(_), Some(_)) => bail!("report `{name}` sets both sql and tab; keep one"),
+            (None, None) => {
+                bail!("report `{name}` needs sql = \"SELECT ...\" or tab = \"projects\"")
+            }
+        };
+        let format = match self.format {
+            Some(raw) => ReportFormat::parse(&raw).ok_or_else(|| {
+                anyhow!("report `{name}` has format `{raw}` -- use text, csv or html")
+            })?,
+            None => ReportFormat::Text,
+        };
+        let aggregates = self
+            .aggregates
+            .iter()
+            .map(|raw| Aggregate::parse(raw))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("report `{name}`"))?;
+        Ok(ReportDef {
+            title: self
+                .title
+                .map(|title| title.trim().to_owned())
+                .filter(|title| !title.is_empty())
+                .unwrap_or_else(|| name.clone()),
+            name,
+            source_sql,
+            group_by: self
+                .group_by
+                .map(|group| group.trim().to_owned())
+                .filter(|group| !group.is_empty()),
+            aggregates,
+            format,
+        })
+    }
+}
+
+/// Report definitions from `path`, in file order; a missing file means none.
+pub fn load_reports(path: &Path) -> Result<Vec<ReportDef>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("read reports file {}", path.display()))?;
+    parse_reports(&raw).with_context(|| format!("invalid reports file {}", path.display()))
+}
+
+fn parse_reports(raw: &str) -> Result<Vec<ReportDef>> {
+    let file: ReportsFile = toml::from_str(raw).context("parse TOML reports")?;
+    let mut reports: Vec<ReportDef> = Vec::with_capacity(file.report.len());
+    for raw in file.report {
+        let report = raw.resolve()?;
+        if reports.iter().any(|other| other.name == report.name) {
+            bail!("report `{}` is defined twice; rename one", report.name);
+        }
+        reports.push(report);
+    }
+    Ok(reports)
+}
+
+pub fn find_report<'a>(reports: &'a [ReportDef], name: &str) -> Result<&'a ReportDef> {
+    reports
+        .iter()
+        .find(|report| report.name == name)
+        .ok_or_else(|| {
+            anyhow!("no report named `{name}`; run `micasa --list-reports` to see the defined ones")
+        })
+}
+
+/// Column names and rows of `report`; private rows are left out unless
+/// `show_private` is set.
+pub fn run_report(
+    store: &Store,
+    report: &ReportDef,
+    show_private: bool,
+) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let result = if show_private {
+        store.read_only_query(&report.sql())
+    } else {
+        store.shadow_private_rows()?;
+        let result = store.read_only_query(&report.sql());
+        store.clear_private_row_shadows()?;
+        result
+    };
+    result.with_context(|| {
+        format!(
+            "run report `{}`; check its query in reports.toml",
+            report.name
+        )
+    })
+}
+
+/// Aligned columns under the title, ending with the row count.
+pub fn report_text_lines(title: &str, columns: &[String], rows: &[Vec<String>]) -> Vec<String> {
+    let widths = columns
+        .iter()
+        .enumerate()
+        .map(|(index, column)| {
+            rows.iter()
+                .filter_map(|row| row.get(index))
+                .map(|value| value.chars().count())
+                .chain([column.chars().count()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+    let line = |values: &[String]| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_owned()
+    };
+    let mut lines = vec![title.to_owned(), String::new(), line(columns)];
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<_>>()
+            .join("  "),
+    );
+    lines.extend(rows.iter().map(|row| line(row)));
+    lines.push(String::new());
+    lines.push(format!("{} rows", rows.len()));
+    lines
+}
+
+/// The report in its own format, ready to print or write.
+pub fn render_report(report: &ReportDef, columns: &[String], rows: &[Vec<String>]) -> String {
+    match report.format {
+        ReportFormat::Text => {
+            let mut out = report_text_lines(&report.title, columns, rows).join("\n");
+            out.push('\n');
+            out
+        }
+        ReportFormat::Csv => std::iter::once(columns)
+            .chain(rows.iter().map(Vec::as_slice))
+            .map(|record| {
+                let mut line = record
+                    .iter()
+                    .map(|field| csv_field(field))
+                    .collect::<Vec<_>>()
+                    .join(",");
+                line.push_str("\r\n");
+                line
+            })
+            .collect(),
+        ReportFormat::Html => {
+            let cells = |record: &[String], tag: &str| {
+                record
+                    .iter()
+                    .map(|value| format!("<{tag}>{}</{tag}>", escape_html(value)))
+                    .collect::<String>()
+            };
+            let mut body = format!("<table>\n<tr>{}</tr>\n", cells(columns, "th"));
+            for row in rows {
+                body.push_str(&format!("<tr>{}</tr>\n", cells(row, "td")));
+            }
+            body.push_str(&format!("</table>\n<p>{} rows</p>\n", rows.len()));
+            html_page(&report.title, &body)
+        }
+    }
+}
+
+/// `micasa-report-spend-by-vendor-20261016-142233.csv`
+pub fn report_file_name(report: &ReportDef, now: OffsetDateTime) -> String {
+    format!(
+        "micasa-report-{}-{:04}{:02}{:02}-{:02}{:02}{:02}.{}",
+        report.name,
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second(),
+        match report.format {
+            ReportFormat::Text => "txt",
+            format => format.as_str(),
+        }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{document_index_html, escape_html, parse_reports, render_report, run_report};
+    use anyhow::Result;
+    use micasa_app::{DocumentEntityKind, ReportFormat};
+    use micasa_db::{NewDocument, Store};
+
+    #[test]
+    fn document_index_links_each_document_and_its_owner() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.insert_document(&NewDocument {
+            title: "Furnace <manual>".to_owned(),
+            file_name: "furnace.pdf".to_owned(),
+            entity_kind: DocumentEntityKind::Appliance,
+            entity_id: 3,
+            mime_type: "application/pdf".to_owned(),
+            data: b"%PDF".to_vec(),
+            notes: String::new(),
+        })?;
+
+        let html = document_index_html(&store)?;
+        assert!(html.contains("<p>1 documents</p>"));
+        assert!(html.contains("Furnace &lt;manual&gt;"));
+        assert!(html.contains("<figcaption>micasa://docs/1</figcaption>"));
+        assert!(html.contains("attached to micasa://appliances/3"));
+        assert_eq!(escape_html("a & \"b\""), "a &amp; &quot;b&quot;");
+        Ok(())
+    }
+
+    #[test]
+    fn reports_group_and_aggregate_over_a_tab_or_sql() -> Result<()> {
+        let reports = parse_reports(
+            r#"
+[[report]]
+name = "by-status"
+title = "Projects by status"
+tab = "projects"
+group_by = "status"
+aggregates = ["count", "sum:budget_cents"]
+
+[[report]]
+name = "vendors"
+sql = "SELECT name FROM vendors WHERE deleted_at IS NULL ORDER BY name;"
+format = "csv"
+"#,
+        )?;
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].title, "vendors", "title defaults to the name");
+        assert_eq!(reports[1].format, ReportFormat::Csv);
+        assert!(
+            reports[0]
+                .sql()
+                .starts_with("SELECT \"status\", COUNT(*) AS \"count\", SUM(\"budget_cents\")")
+        );
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.seed_demo_data()?;
+        let (columns, rows) = run_report(&store, &reports[0], false)?;
+        assert_eq!(columns, ["status", "count", "sum_budget_cents"]);
+        let counted = rows
+            .iter()
+            .map(|row| row[1].parse::<usize>())
+            .sum::<Result<usize, _>>()?;
+        assert_eq!(counted, store.list_projects(false)?.len());
+
+        let (columns, rows) = run_report(&store, &reports[1], false)?;
+        let csv = render_report(&reports[1], &columns, &rows);
+        assert!(csv.starts_with("name\r\n"));
+        assert_eq!(csv.lines().count(), rows.len() + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_report_definitions_say_what_to_fix() {
+        for (raw, expected) in [
+            ("[[report]]\nname = \"x\"", "needs sql"),
+            (
+                "[[report]]\nname = \"x\"\nsql = \"SELECT 1\"\ntab = \"projects\"",
+                "both sql and tab",
+            ),
+            (
+                "[[report]]\nname = \"x\"\ntab = \"garage\"",
+                "names tab `garage`",
+            ),
+            (
+                "[[report]]\nname = \"x\"\ntab = \"projects\"\nformat = \"pdf\"",
+                "use text, csv or html",
+            ),
+            (
+                "[[report]]\nname = \"x\"\ntab = \"projects\"\naggregates = [\"sum\"]",
+                "needs a column",
+            ),
+            (
+                "[[report]]\nname = \"a b\"\ntab = \"projects\"",
+                "letters, digits",
+            ),
+            (
+                "[[report]]\nname = \"x\"\ntab = \"quotes\"\n[[report]]\nname = \"x\"\ntab = \"quotes\"",
+                "defined twice",
+            ),
+        ] {
+            let error = parse_reports(raw).expect_err(raw);
+            assert!(format!("{error:#}").contains(expected), "{raw}: {error:#}");
+        }
+    }
+}
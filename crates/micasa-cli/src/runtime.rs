@@ -1,45 +1,141 @@
 // Copyright 2026 Phillip Cloud
 // Licensed under the Apache License, Version 2.0
 
+use crate::backup::{self, BackupPolicy};
+use crate::csv_import;
+use crate::db_io;
+use crate::doc_index;
+use crate::extract::TextExtractor;
+use crate::ical;
+use crate::plugin::{self, PluginDef};
+use crate::quick_add;
+use crate::report::{self, ReportDef};
 use anyhow::{Context, Result, bail};
-use micasa_app::{FormPayload, TabKind};
+use micasa_app::{
+    AlertEvent, AlertStyle, AppSetting, ApplianceCostHistory, ApplianceId, ApplianceRollup,
+    AuditEntry, AuditSource, BudgetCategory, DataIssue, DataIssueKind, DeepLink, DeletionEntity,
+    DocumentEntityKind, DocumentFormInput, DocumentId, FormPayload, IncidentId, LookupEntry,
+    LookupKind, MaintenanceItemId, PluginRows, PluginTab, ProjectId, ProjectRollup,
+    ProjectTemplate, PurgeScope, PurgeSummary, QUERY_PAGE_ROWS, QueryPage, QuoteId, Reminder,
+    ReminderKind, ReportFormat, ReportOutput, ReportSummary, SCHEDULE_HORIZON_DAYS, SavedView,
+    ScenarioLine, ScheduleEvent, SettingKey, SettingValue, TabKind, Task, TaskId, VendorId,
+    VendorRollup, build_schedule, days_from_to, next_due,
+};
+use micasa_db::weather;
 use micasa_db::{
-    HouseProfileInput, LifecycleEntityRef, NewAppliance, NewDocument, NewIncident,
-    NewMaintenanceItem, NewProject, NewQuote, NewServiceLogEntry, NewVendor, Store,
+    CostSplit, HouseProfileInput, LifecycleEntityRef, ListPage, NewAppliance, NewBudget,
+    NewDocument, NewIncident, NewMaintenanceItem, NewProject, NewQuote, NewServiceLogEntry,
+    NewVendor, Store,
+    cost_splits::parse_cost_split_spec,
+    currency::parse_currency_code,
+    validation::{add_months, format_cents, observed_interval_months},
 };
 use micasa_llm::{
     Client as LlmClient, ColumnInfo, Message as LlmMessage, Role as LlmRole, TableInfo,
-    build_fallback_prompt, build_sql_prompt, build_summary_prompt, extract_sql,
-    format_results_table, format_sql,
+    build_explain_prompt, build_fallback_prompt, build_quick_add_prompt, build_sql_prompt,
+    build_summary_prompt, extract_sql, format_results_table, format_sql,
 };
+use micasa_tui::keymap::Keymap;
+use micasa_tui::theme::Theme;
 use micasa_tui::{
-    ChatHistoryMessage, ChatHistoryRole, ChatPipelineEvent, ChatPipelineResult, DashboardIncident,
-    DashboardInsuranceRenewal, DashboardMaintenance, DashboardProject, DashboardServiceEntry,
-    DashboardSnapshot, DashboardWarranty, InternalEvent, LifecycleAction, TabSnapshot,
+    ChatHistoryMessage, ChatHistoryRole, ChatPipelineEvent, ChatPipelineResult, ChatStageModels,
+    DashboardFollowUp, DashboardIncident, DashboardInsuranceRenewal, DashboardIntervalDrift,
+    DashboardLayout, DashboardMaintenance, DashboardProject, DashboardServiceEntry,
+    DashboardSnapshot, DashboardVendorInsurance, DashboardWarranty, DashboardWeatherAlert,
+    FileEntry, InternalEvent, LifecycleAction, LinkNames, RowDelta, RowPage, TabSnapshot,
+    TableExport,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::mpsc::Sender;
 use std::thread;
-use time::{Date, Duration, Month, OffsetDateTime};
+use time::{Date, OffsetDateTime};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
 
 const MAX_UNDO_STACK: usize = 50;
 
+fn id_counts<K>(counts: BTreeMap<K, usize>, get: fn(K) -> i64) -> BTreeMap<i64, usize> {
+    counts
+        .into_iter()
+        .map(|(id, count)| (get(id), count))
+        .collect()
+}
+
+/// Optional prompt section: `None` when there is nothing to add.
+fn non_empty(text: &str) -> Option<&str> {
+    if text.is_empty() { None } else { Some(text) }
+}
+
+/// Forecast days, counting today, that can raise a dashboard weather alert.
+const WEATHER_ALERT_DAYS: i64 = 7;
+
+/// When to warn that a new quote or service cost is eating into a budget.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetAlerts {
+    pub threshold_pct: i64,
+    pub monthly_limit_cents: Option<i64>,
+}
+
+impl Default for BudgetAlerts {
+    fn default() -> Self {
+        Self {
+            threshold_pct: 100,
+            monthly_limit_cents: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum MutationRecord {
     Created(LifecycleEntityRef),
     SoftDeleted(LifecycleEntityRef),
     Restored(LifecycleEntityRef),
+    /// Inline cell edit; values are the typed text before and after.
+    FieldUpdated {
+        target: LifecycleEntityRef,
+        field: String,
+        before: String,
+        after: String,
+    },
+    /// Document moved from one linked record to another.
+    Relinked {
+        document: DocumentId,
+        before: (DocumentEntityKind, i64),
+        after: (DocumentEntityKind, i64),
+    },
+    /// Several records undone and redone as one step.
+    Batch(Vec<MutationRecord>),
 }
 
 impl MutationRecord {
-    const fn inverse(self) -> Self {
+    fn inverse(&self) -> Self {
         match self {
-            Self::Created(target) => Self::SoftDeleted(target),
-            Self::SoftDeleted(target) => Self::Restored(target),
-            Self::Restored(target) => Self::SoftDeleted(target),
+            Self::Created(target) => Self::SoftDeleted(*target),
+            Self::SoftDeleted(target) => Self::Restored(*target),
+            Self::Restored(target) => Self::SoftDeleted(*target),
+            Self::FieldUpdated {
+                target,
+                field,
+                before,
+                after,
+            } => Self::FieldUpdated {
+                target: *target,
+                field: field.clone(),
+                before: after.clone(),
+                after: before.clone(),
+            },
+            Self::Relinked {
+                document,
+                before,
+                after,
+            } => Self::Relinked {
+                document: *document,
+                before: *after,
+                after: *before,
+            },
+            Self::Batch(records) => Self::Batch(records.iter().rev().map(Self::inverse).collect()),
         }
     }
 }
@@ -49,9 +145,45 @@ pub struct DbRuntime<'a> {
     undo_stack: Vec<MutationRecord>,
     redo_stack: Vec<MutationRecord>,
     llm_client: Option<LlmClient>,
+    /// The provider and URL from `[llm]`, so `/provider` can switch back.
+    configured_llm: Option<(&'static str, String)>,
     llm_extra_context: String,
     db_path: Option<PathBuf>,
-    chat_cancellations: HashMap<u64, Arc<AtomicBool>>,
+    chat_tasks: HashMap<u64, ChatTask>,
+    lock_idle_timeout: Option<std::time::Duration>,
+    lock_passphrase_hash: Option<String>,
+    show_private: bool,
+    show_archived: bool,
+    budget_alerts: BudgetAlerts,
+    pending_budget_alert: Option<String>,
+    /// Why the last calendar feed refresh failed; shown after the next save.
+    calendar_error: Option<String>,
+    home_currency: String,
+    weather_alerts: bool,
+    /// Reads text out of saved documents; `None` when extraction is off.
+    text_extractor: Option<TextExtractor>,
+    /// Why the last document's text couldn't be read; shown after the save.
+    extraction_error: Option<String>,
+    /// Embeds documents for chat retrieval; `None` leaves chat on SQL alone.
+    embedding_model: Option<String>,
+    /// Why the last document couldn't be indexed; shown after the save.
+    index_error: Option<String>,
+    startup_focus: Option<DeepLink>,
+    keymap: Keymap,
+    themes: Vec<Theme>,
+    theme: Theme,
+    /// Where CSV and database exports go; `None` means the working directory.
+    export_dir: Option<PathBuf>,
+    /// Definitions from reports.toml, in file order.
+    reports: Vec<ReportDef>,
+    /// Programs from plugins.toml, in file order.
+    plugins: Vec<PluginDef>,
+    /// Where timed backups go; `None` (demo mode) never backs up.
+    backups: Option<BackupPolicy>,
+    /// Set while edits are held in a what-if sandbox.
+    what_if: Option<WhatIfBaseline>,
+    /// `data_version` at the last change poll; `None` before the first.
+    data_version: Option<i64>,
 }
 
 impl<'a> DbRuntime<'a> {
@@ -61,14 +193,380 @@ impl<'a> DbRuntime<'a> {
         llm_extra_context: impl Into<String>,
         db_path: Option<PathBuf>,
     ) -> Self {
+        let configured_llm = llm_client
+            .as_ref()
+            .map(|client| (client.provider().name(), client.base_url().to_owned()));
         Self {
             store,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             llm_client,
+            configured_llm,
             llm_extra_context: llm_extra_context.into(),
             db_path,
-            chat_cancellations: HashMap::new(),
+            chat_tasks: HashMap::new(),
+            lock_idle_timeout: None,
+            lock_passphrase_hash: None,
+            show_private: false,
+            show_archived: false,
+            budget_alerts: BudgetAlerts::default(),
+            home_currency: "USD".to_owned(),
+            pending_budget_alert: None,
+            calendar_error: None,
+            weather_alerts: false,
+            text_extractor: None,
+            extraction_error: None,
+            embedding_model: None,
+            index_error: None,
+            startup_focus: None,
+            keymap: Keymap::default(),
+            themes: Theme::presets(),
+            theme: Theme::default(),
+            export_dir: None,
+            reports: Vec::new(),
+            plugins: Vec::new(),
+            backups: None,
+            what_if: None,
+            data_version: None,
+        }
+    }
+
+    pub fn with_app_lock(
+        mut self,
+        idle_timeout: Option<std::time::Duration>,
+        passphrase_hash: Option<&str>,
+    ) -> Self {
+        self.lock_idle_timeout = idle_timeout;
+        self.lock_passphrase_hash = passphrase_hash.map(str::to_owned);
+        self
+    }
+
+    pub fn with_budget_alerts(mut self, budget_alerts: BudgetAlerts) -> Self {
+        self.budget_alerts = budget_alerts;
+        self
+    }
+
+    pub fn with_home_currency(mut self, home_currency: impl Into<String>) -> Self {
+        self.home_currency = home_currency.into();
+        self
+    }
+
+    /// Fills the count columns the table leaves blank.
+    fn export_dir(&self) -> Result<PathBuf> {
+        match &self.export_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => std::env::current_dir().context("resolve working directory for export"),
+        }
+    }
+
+    fn fill_export_counts(&self, table: &mut TableExport) -> Result<()> {
+        let ids = table.row_ids.clone();
+        let counts: Vec<(&str, BTreeMap<i64, usize>)> = match table.tab {
+            TabKind::Appliances => {
+                let ids = ids
+                    .iter()
+                    .copied()
+                    .map(ApplianceId::new)
+                    .collect::<Vec<_>>();
+                vec![(
+                    "maint",
+                    id_counts(
+                        self.store.count_maintenance_items_by_appliance(&ids)?,
+                        ApplianceId::get,
+                    ),
+                )]
+            }
+            TabKind::Vendors => {
+                let ids = ids.iter().copied().map(VendorId::new).collect::<Vec<_>>();
+                vec![
+                    (
+                        "quotes",
+                        id_counts(self.store.count_quotes_by_vendor(&ids)?, VendorId::get),
+                    ),
+                    (
+                        "jobs",
+                        id_counts(
+                            self.store.count_service_logs_by_vendor(&ids)?,
+                            VendorId::get,
+                        ),
+                    ),
+                ]
+            }
+            _ => Vec::new(),
+        };
+        for (label, counts) in counts {
+            let Some(column) = table.columns.iter().position(|column| column == label) else {
+                continue;
+            };
+            for (row, id) in table.rows.iter_mut().zip(&ids) {
+                row[column] = counts.get(id).copied().unwrap_or(0).to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Swaps ids for names in columns that point at other rows. Ids whose
+    /// row is gone (deleted) are left as they are.
+    fn resolve_export_names(&self, table: &mut TableExport) -> Result<()> {
+        let links: &[(&str, DocumentEntityKind)] = match table.tab {
+            TabKind::Projects => &[("parent", DocumentEntityKind::Project)],
+            TabKind::Quotes => &[
+                ("project", DocumentEntityKind::Project),
+                ("vendor", DocumentEntityKind::Vendor),
+            ],
+            TabKind::Maintenance => &[("appliance", DocumentEntityKind::Appliance)],
+            TabKind::ServiceLog => &[
+                ("maint", DocumentEntityKind::Maintenance),
+                ("vendor", DocumentEntityKind::Vendor),
+            ],
+            _ => &[],
+        };
+        for (label, kind) in links {
+            let Some(column) = table.columns.iter().position(|column| column == label) else {
+                continue;
+            };
+            let names = self
+                .store
+                .list_link_targets(*kind)?
+                .into_iter()
+                .map(|(id, name)| (id.to_string(), name))
+                .collect::<HashMap<_, _>>();
+            for row in &mut table.rows {
+                if let Some(name) = names.get(&row[column]) {
+                    row[column].clone_from(name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Shows forecast risks from the cached forecast on the dashboard.
+    pub fn with_weather_alerts(mut self, enabled: bool) -> Self {
+        self.weather_alerts = enabled;
+        self
+    }
+
+    /// Reads text out of PDFs and images as they are saved, for search and
+    /// chat.
+    pub fn with_text_extraction(mut self, extractor: Option<TextExtractor>) -> Self {
+        self.text_extractor = extractor;
+        self
+    }
+
+    /// Stores the document's text when extraction is on. The document is
+    /// already saved, so a failure is kept for the status bar instead.
+    fn extract_document_text(&mut self, id: DocumentId, mime_type: &str, data: &[u8]) {
+        let Some(extractor) = &self.text_extractor else {
+            return;
+        };
+        // Encrypted databases store no text read out of their files.
+        match self.store.documents_encrypted() {
+            Ok(false) => {}
+            Ok(true) => return,
+            Err(error) => {
+                self.extraction_error = Some(format!("text extraction skipped: {error:#}"));
+                return;
+            }
+        }
+        let stored = extractor
+            .extract(mime_type, data)
+            .and_then(|text| match text {
+                Some(text) => self.store.set_document_text(id, &text),
+                None => Ok(()),
+            });
+        if let Err(error) = stored {
+            self.extraction_error = Some(format!("text extraction failed: {error:#}"));
+        }
+    }
+
+    pub fn with_document_index(mut self, embedding_model: Option<String>) -> Self {
+        self.embedding_model = embedding_model;
+        self
+    }
+
+    /// Re-embeds a saved document when the index is on. Like extraction, a
+    /// failure is kept for the status bar rather than failing the save.
+    fn index_document(&mut self, id: DocumentId) {
+        let (Some(client), Some(model)) = (&self.llm_client, &self.embedding_model) else {
+            return;
+        };
+        match self.store.documents_locked() {
+            Ok(false) => {}
+            Ok(true) => return,
+            Err(error) => {
+                self.index_error = Some(format!("document index not updated: {error:#}"));
+                return;
+            }
+        }
+        if let Err(error) = doc_index::index_document(self.store, client, model, id) {
+            self.index_error = Some(format!("document index not updated: {error:#}"));
+        }
+    }
+
+    /// Re-embeds a maintenance item's manual text when the index is on,
+    /// keeping a failure for the status bar like [`Self::index_document`].
+    fn index_manual(&mut self, id: MaintenanceItemId) {
+        let (Some(client), Some(model)) = (&self.llm_client, &self.embedding_model) else {
+            return;
+        };
+        if let Err(error) = doc_index::index_manual(self.store, client, model, id) {
+            self.index_error = Some(format!("manual index not updated: {error:#}"));
+        }
+    }
+
+    /// Passages from indexed documents and manuals that look relevant to
+    /// `question`, and a notice when they couldn't be retrieved.
+    fn document_excerpts(&self, question: &str) -> (String, Option<String>) {
+        match (&self.llm_client, &self.embedding_model) {
+            (Some(client), Some(model)) => doc_index::excerpts_or_notice(
+                doc_index::document_excerpts(self.store, client, model, question),
+            ),
+            _ => (String::new(), None),
+        }
+    }
+
+    /// Opens on this record instead of the usual start tab.
+    pub fn with_startup_focus(mut self, link: Option<DeepLink>) -> Self {
+        self.startup_focus = link;
+        self
+    }
+
+    /// Key bindings loaded from keys.toml.
+    pub fn with_keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    /// Palettes offered in Settings and the one to start with; an unknown
+    /// `active` name starts with the first palette.
+    pub fn with_themes(mut self, themes: Vec<Theme>, active: &str) -> Self {
+        self.theme = themes
+            .iter()
+            .find(|theme| theme.name == active)
+            .or_else(|| themes.first())
+            .cloned()
+            .unwrap_or_default();
+        self.themes = themes;
+        self
+    }
+
+    pub fn with_reports(mut self, reports: Vec<ReportDef>) -> Self {
+        self.reports = reports;
+        self
+    }
+
+    pub fn with_plugins(mut self, plugins: Vec<PluginDef>) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    pub fn with_backups(mut self, backups: Option<BackupPolicy>) -> Self {
+        self.backups = backups;
+        self
+    }
+
+    /// The records plugins are sent, hidden the way the tabs hide them.
+    fn plugin_data(&self) -> Result<serde_json::Value> {
+        plugin::house_data(self.store, !self.show_private, !self.show_archived)
+    }
+
+    fn plugin_for(&self, tab: &PluginTab) -> Result<&PluginDef> {
+        self.plugins
+            .iter()
+            .find(|plugin| plugin.name == tab.plugin)
+            .with_context(|| format!("no plugin named `{}` in plugins.toml", tab.plugin))
+    }
+
+    /// Stored settings, with the theme row showing the palette in use
+    /// rather than a blank when nothing was picked yet.
+    fn load_settings(&self) -> Result<Vec<AppSetting>> {
+        let mut settings = self.store.list_settings()?;
+        for setting in &mut settings {
+            if setting.key == SettingKey::UiTheme {
+                setting.value = SettingValue::Text(self.theme.name.clone());
+            }
+        }
+        Ok(settings)
+    }
+
+    fn load_weather_alerts(
+        &self,
+        today: Date,
+        hidden_maintenance: &BTreeSet<i64>,
+    ) -> Result<Vec<DashboardWeatherAlert>> {
+        if !self.weather_alerts {
+            return Ok(Vec::new());
+        }
+        let forecast = self
+            .store
+            .weather_forecast(today)?
+            .into_iter()
+            .filter(|day| days_from_to(today, day.day) < WEATHER_ALERT_DAYS)
+            .collect::<Vec<_>>();
+        if forecast.is_empty() {
+            return Ok(Vec::new());
+        }
+        let items = self
+            .store
+            .list_maintenance_items(false)?
+            .into_iter()
+            .filter(|item| !hidden_maintenance.contains(&item.id.get()))
+            .map(|item| (item.id, item.name))
+            .collect::<Vec<_>>();
+        Ok(weather::weather_alerts(&forecast, &items)
+            .into_iter()
+            .map(|alert| DashboardWeatherAlert {
+                label: alert.risk.label().to_owned(),
+                advice: alert.risk.advice().to_owned(),
+                days_from_now: days_from_to(today, alert.day),
+                items: alert.items,
+            })
+            .collect())
+    }
+
+    fn budget_alert_for(&self, payload: &FormPayload) -> Result<Option<String>> {
+        let threshold_pct = self.budget_alerts.threshold_pct;
+        match payload {
+            FormPayload::Quote(form) => {
+                let project = self.store.get_project(form.project_id)?;
+                let Some(budget) = project.budget_cents.filter(|budget| *budget > 0) else {
+                    return Ok(None);
+                };
+                if form.total_cents * 100 < budget * threshold_pct {
+                    return Ok(None);
+                }
+                Ok(Some(format!(
+                    "{} quote {} is {}% of {} budget",
+                    project.title,
+                    format_cents(form.total_cents),
+                    form.total_cents * 100 / budget,
+                    format_cents(budget)
+                )))
+            }
+            FormPayload::ServiceLogEntry(form) if form.cost_cents.is_some() => {
+                let Some(limit) = self.budget_alerts.monthly_limit_cents else {
+                    return Ok(None);
+                };
+                let month_start = form
+                    .serviced_at
+                    .replace_day(1)
+                    .context("compute start of service month")?;
+                let spent = self
+                    .store
+                    .service_cost_cents_between(month_start, add_months(month_start, 1))?;
+                if spent * 100 < limit * threshold_pct {
+                    return Ok(None);
+                }
+                Ok(Some(format!(
+                    "{}-{:02} service spend {} is {}% of {} monthly limit",
+                    month_start.year(),
+                    u8::from(month_start.month()),
+                    format_cents(spent),
+                    spent * 100 / limit,
+                    format_cents(limit)
+                )))
+            }
+            _ => Ok(None),
         }
     }
 
@@ -81,39 +579,49 @@ impl<'a> DbRuntime<'a> {
         }
     }
 
+    /// Extra context for chat prompts: the active house first, so answers
+    /// stay scoped to it, then the configured `[llm].extra_context`.
+    fn chat_context(&self) -> String {
+        let house = self
+            .store
+            .active_house_name()
+            .ok()
+            .flatten()
+            .map(|house| format!("All data belongs to the house \"{house}\"."));
+        house
+            .into_iter()
+            .chain(self.llm_extra_context().map(str::to_owned))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
     fn build_table_info(&self) -> Vec<TableInfo> {
         Self::build_table_info_from_store(self.store)
     }
 
     fn build_table_info_from_store(store: &Store) -> Vec<TableInfo> {
-        let table_names = match store.table_names() {
-            Ok(names) => names,
-            Err(_) => return Vec::new(),
+        let Ok(tables) = store.schema_docs() else {
+            return Vec::new();
         };
-
-        let mut tables = Vec::new();
-        for name in table_names {
-            let columns = match store.table_columns(&name) {
-                Ok(columns) => columns,
-                Err(_) => continue,
-            };
-
-            let mut llm_columns = Vec::with_capacity(columns.len());
-            for column in columns {
-                llm_columns.push(ColumnInfo {
-                    name: column.name,
-                    column_type: column.column_type,
-                    not_null: column.not_null,
-                    primary_key: column.primary_key > 0,
-                });
-            }
-
-            tables.push(TableInfo {
-                name,
-                columns: llm_columns,
-            });
-        }
         tables
+            .into_iter()
+            .map(|table| TableInfo {
+                name: table.name,
+                description: table.description,
+                columns: table
+                    .columns
+                    .into_iter()
+                    .map(|column| ColumnInfo {
+                        name: column.name,
+                        column_type: column.column_type,
+                        not_null: column.not_null,
+                        primary_key: column.primary_key,
+                        references: column.references,
+                        description: column.description,
+                    })
+                    .collect(),
+            })
+            .collect()
     }
 
     fn build_history_messages(history: &[ChatHistoryMessage]) -> Vec<LlmMessage> {
@@ -145,18 +653,17 @@ impl<'a> DbRuntime<'a> {
     fn stream_chat_with_events<F>(
         client: &LlmClient,
         messages: &[LlmMessage],
-        cancel: &AtomicBool,
+        cancel: &Arc<AtomicBool>,
         mut on_chunk: F,
     ) -> Result<String>
     where
         F: FnMut(String) -> bool,
     {
         let mut response = String::new();
-        let stream = client.chat_stream(messages).context("start LLM stream")?;
+        // Cancelable so ctrl+c is noticed while the model is still thinking,
+        // not just between tokens.
+        let stream = client.chat_stream_cancelable(messages, Arc::clone(cancel));
         for chunk in stream {
-            if cancel.load(Ordering::Acquire) {
-                break;
-            }
             let chunk = chunk.context("read LLM stream chunk")?;
             if !chunk.content.is_empty() {
                 response.push_str(&chunk.content);
@@ -180,7 +687,8 @@ impl<'a> DbRuntime<'a> {
         now: OffsetDateTime,
     ) -> Result<ChatPipelineResult> {
         let data_dump = self.store.data_dump();
-        let fallback_prompt = build_fallback_prompt(
+        let chat_context = self.chat_context();
+        let mut fallback_prompt = build_fallback_prompt(
             tables,
             if data_dump.is_empty() {
                 "(no rows)\n"
@@ -188,8 +696,10 @@ impl<'a> DbRuntime<'a> {
                 &data_dump
             },
             now,
-            self.llm_extra_context(),
+            non_empty(&chat_context),
         );
+        let (excerpts, notice) = self.document_excerpts(question);
+        fallback_prompt.push_str(&excerpts);
 
         let mut messages = Vec::with_capacity(history.len() + 2);
         messages.push(LlmMessage {
@@ -209,27 +719,110 @@ impl<'a> DbRuntime<'a> {
             answer,
             sql: None,
             used_fallback: true,
+            notice,
         })
     }
 
-    fn record_mutation(&mut self, record: MutationRecord) {
+    /// Rewrites the `ical.path` feed after a change. The edit is already
+    /// saved, so a failure is kept for the post-save alert instead.
+    fn refresh_calendar(&mut self) {
+        if self.store.in_sandbox() {
+            return;
+        }
+        self.calendar_error = ical::refresh_calendar(self.store).err().map(|error| {
+            format!("calendar feed not refreshed: {error:#}; rerun `micasa --set-ical-path PATH`")
+        });
+    }
+
+    /// Moves the item's `last_serviced_at` up to `serviced_at` when the new
+    /// visit is the latest one, so undoing the log entry undoes both.
+    fn advance_last_serviced(
+        &self,
+        item_id: MaintenanceItemId,
+        serviced_at: Date,
+    ) -> Result<Option<MutationRecord>> {
+        let target = LifecycleEntityRef::MaintenanceItem(item_id);
+        let before = self.store.field_text(target, "last_serviced_at")?;
+        let after = serviced_at.to_string();
+        if !before.is_empty() && before >= after {
+            return Ok(None);
+        }
+        self.store
+            .update_field(target, "last_serviced_at", &after)?;
+        Ok(Some(MutationRecord::FieldUpdated {
+            target,
+            field: "last_serviced_at".to_owned(),
+            before,
+            after,
+        }))
+    }
+
+    fn record_mutation(&mut self, record: MutationRecord) -> Result<()> {
+        self.audit(&record)?;
         self.undo_stack.push(record);
         if self.undo_stack.len() > MAX_UNDO_STACK {
             let overflow = self.undo_stack.len() - MAX_UNDO_STACK;
             self.undo_stack.drain(0..overflow);
         }
         self.redo_stack.clear();
+        Ok(())
+    }
+
+    /// One audit entry per row the record touched, under the store's
+    /// current source.
+    fn audit(&self, record: &MutationRecord) -> Result<()> {
+        let (target, action) = match record {
+            MutationRecord::Created(target) => (*target, "create".to_owned()),
+            MutationRecord::SoftDeleted(target) => (*target, "delete".to_owned()),
+            MutationRecord::Restored(target) => (*target, "restore".to_owned()),
+            MutationRecord::FieldUpdated { target, field, .. } => {
+                (*target, format!("edit {field}"))
+            }
+            MutationRecord::Relinked { document, .. } => {
+                (LifecycleEntityRef::Document(*document), "relink".to_owned())
+            }
+            MutationRecord::Batch(records) => {
+                return records.iter().try_for_each(|record| self.audit(record));
+            }
+        };
+        self.store.record_audit(target, &action)?;
+        Ok(())
     }
 
-    fn apply_record(&self, record: MutationRecord) -> Result<()> {
+    fn apply_record(&self, record: &MutationRecord) -> Result<()> {
         match record {
             MutationRecord::Created(target) | MutationRecord::Restored(target) => {
-                self.store.restore(target)
+                self.store.restore(*target)
             }
-            MutationRecord::SoftDeleted(target) => self.store.soft_delete(target),
+            MutationRecord::SoftDeleted(target) => self.store.soft_delete(*target),
+            MutationRecord::FieldUpdated {
+                target,
+                field,
+                after,
+                ..
+            } => self.store.update_field(*target, field, after),
+            MutationRecord::Relinked {
+                document, after, ..
+            } => self.store.relink_document(*document, after.0, after.1),
+            MutationRecord::Batch(records) => self.apply_batch(records),
         }
     }
 
+    /// Applies records in order; on failure, reverts the ones already applied
+    /// so the batch lands whole or not at all.
+    fn apply_batch(&self, records: &[MutationRecord]) -> Result<()> {
+        for (index, record) in records.iter().enumerate() {
+            if let Err(error) = self.apply_record(record) {
+                for applied in records[..index].iter().rev() {
+                    self.apply_record(&applied.inverse())
+                        .with_context(|| format!("roll back after failed batch step: {error:#}"))?;
+                }
+                return Err(error);
+            }
+        }
+        Ok(())
+    }
+
     fn lifecycle_target(tab: TabKind, row_id: i64) -> Result<LifecycleEntityRef> {
         if row_id <= 0 {
             bail!("row id must be positive, got {row_id}");
@@ -249,7 +842,14 @@ impl<'a> DbRuntime<'a> {
                 LifecycleEntityRef::Appliance(micasa_app::ApplianceId::new(row_id))
             }
             TabKind::Vendors => LifecycleEntityRef::Vendor(micasa_app::VendorId::new(row_id)),
-            TabKind::House | TabKind::Documents | TabKind::Dashboard | TabKind::Settings => {
+            TabKind::Budget => LifecycleEntityRef::Budget(micasa_app::BudgetId::new(row_id)),
+            TabKind::House
+            | TabKind::Documents
+            | TabKind::Dashboard
+            | TabKind::Schedule
+            | TabKind::Settings
+            | TabKind::Query
+            | TabKind::CostHistory => {
                 bail!(
                     "tab {} does not support delete/restore actions",
                     tab.label()
@@ -259,110 +859,526 @@ impl<'a> DbRuntime<'a> {
         Ok(target)
     }
 
-    fn today_utc() -> Result<Date> {
-        Ok(time::OffsetDateTime::now_utc().date())
+    fn private_target(tab: TabKind, row_id: i64) -> Result<LifecycleEntityRef> {
+        match tab {
+            TabKind::Documents if row_id > 0 => Ok(LifecycleEntityRef::Document(
+                micasa_app::DocumentId::new(row_id),
+            )),
+            TabKind::House
+            | TabKind::Dashboard
+            | TabKind::Schedule
+            | TabKind::Settings
+            | TabKind::Query
+            | TabKind::CostHistory => {
+                bail!("tab {} does not support private rows", tab.label())
+            }
+            _ => Self::lifecycle_target(tab, row_id),
+        }
     }
 
-    fn compute_next_due(last_serviced_at: Option<Date>, interval_months: i32) -> Option<Date> {
-        let start = last_serviced_at?;
-        if interval_months <= 0 {
-            return None;
+    fn archive_target(tab: TabKind, row_id: i64) -> Result<LifecycleEntityRef> {
+        if Self::private_entity(tab).is_none() {
+            bail!("tab {} has no rows to archive", tab.label());
         }
-        add_months_clamped(start, interval_months)
+        Self::private_target(tab, row_id)
     }
-}
 
-impl micasa_tui::AppRuntime for DbRuntime<'_> {
-    fn load_dashboard_counts(&mut self) -> Result<micasa_app::DashboardCounts> {
-        self.store.dashboard_counts()
+    fn cost_split_target(tab: TabKind, row_id: i64) -> Result<LifecycleEntityRef> {
+        match tab {
+            TabKind::ServiceLog | TabKind::Incidents => Self::lifecycle_target(tab, row_id),
+            _ => bail!(
+                "tab {} has no splittable cost; cost splits apply to service log and incidents",
+                tab.label()
+            ),
+        }
     }
 
-    fn load_dashboard_snapshot(&mut self) -> Result<DashboardSnapshot> {
-        let today = Self::today_utc()?;
+    const fn private_entity(tab: TabKind) -> Option<DeletionEntity> {
+        match tab {
+            TabKind::Projects => Some(DeletionEntity::Project),
+            TabKind::Quotes => Some(DeletionEntity::Quote),
+            TabKind::Maintenance => Some(DeletionEntity::Maintenance),
+            TabKind::ServiceLog => Some(DeletionEntity::ServiceLog),
+            TabKind::Incidents => Some(DeletionEntity::Incident),
+            TabKind::Appliances => Some(DeletionEntity::Appliance),
+            TabKind::Vendors => Some(DeletionEntity::Vendor),
+            TabKind::Documents => Some(DeletionEntity::Document),
+            TabKind::Budget => Some(DeletionEntity::Budget),
+            TabKind::House
+            | TabKind::Dashboard
+            | TabKind::Schedule
+            | TabKind::Settings
+            | TabKind::Query
+            | TabKind::CostHistory => None,
+        }
+    }
 
-        let incidents = self
-            .store
-            .list_open_incidents()?
-            .into_iter()
-            .map(|incident| DashboardIncident {
-                incident_id: incident.id,
-                title: incident.title,
-                severity: incident.severity,
-                days_open: days_from_to(incident.date_noticed, today).max(0),
-            })
-            .collect::<Vec<_>>();
+    const fn tag_entity(tab: TabKind) -> Option<DeletionEntity> {
+        match tab {
+            TabKind::Projects
+            | TabKind::Incidents
+            | TabKind::Appliances
+            | TabKind::Vendors
+            | TabKind::Documents => Self::private_entity(tab),
+            _ => None,
+        }
+    }
 
-        let mut overdue = Vec::new();
-        let mut upcoming = Vec::new();
-        for item in self.store.list_maintenance_with_schedule()? {
-            let Some(next_due) =
-                Self::compute_next_due(item.last_serviced_at, item.interval_months)
-            else {
+    /// Service dates per maintenance item, from the log entries the views
+    /// show. Interval drift on the dashboard and adopting the observed
+    /// interval both read this, so they agree.
+    fn visible_service_dates(&self) -> Result<HashMap<i64, Vec<Date>>> {
+        let hidden = self.hidden_row_ids(DeletionEntity::ServiceLog)?;
+        let mut dates = HashMap::<i64, Vec<Date>>::new();
+        for entry in self.store.list_service_log_entries(false)? {
+            if hidden.contains(&entry.id.get()) {
                 continue;
-            };
-            let days_from_now = days_from_to(today, next_due);
-            let entry = DashboardMaintenance {
-                maintenance_item_id: item.id,
-                item_name: item.name,
-                days_from_now,
-            };
-            if days_from_now < 0 {
-                overdue.push(entry);
-            } else if days_from_now <= 30 {
-                upcoming.push(entry);
             }
+            dates
+                .entry(entry.maintenance_item_id.get())
+                .or_default()
+                .push(entry.serviced_at);
         }
-        overdue.sort_by_key(|entry| entry.days_from_now);
-        upcoming.sort_by_key(|entry| entry.days_from_now);
+        Ok(dates)
+    }
 
-        let active_projects = self
-            .store
-            .list_active_projects()?
-            .into_iter()
-            .map(|project| DashboardProject {
-                project_id: project.id,
-                title: project.title,
-                status: project.status,
-            })
-            .collect::<Vec<_>>();
+    /// Ids to drop from views: private rows until revealed, plus archived
+    /// rows while the archive filter is on.
+    fn hidden_row_ids(&self, entity: DeletionEntity) -> Result<BTreeSet<i64>> {
+        let mut hidden = if self.show_private {
+            BTreeSet::new()
+        } else {
+            self.store.private_row_ids(entity)?
+        };
+        if !self.show_archived {
+            hidden.extend(self.store.archived_row_ids(entity)?);
+        }
+        Ok(hidden)
+    }
 
-        let expiring_warranties = self
+    /// Models for each chat stage: `llm.sql_model` and `llm.summary_model`
+    /// where set, the chat model otherwise.
+    fn stage_models(&self, client: &LlmClient) -> Result<ChatStageModels> {
+        let chat_model = self
             .store
-            .list_expiring_warranties(today, 30, 90)?
-            .into_iter()
-            .filter_map(|appliance| {
-                let warranty_expiry = appliance.warranty_expiry?;
-                Some(DashboardWarranty {
-                    appliance_id: appliance.id,
-                    appliance_name: appliance.name,
-                    days_from_now: days_from_to(today, warranty_expiry),
-                })
-            })
-            .collect::<Vec<_>>();
+            .get_last_model()?
+            .unwrap_or_else(|| client.model().to_owned());
+        let pick = |key| -> Result<String> {
+            Ok(self
+                .store
+                .get_stage_model(key)?
+                .unwrap_or_else(|| chat_model.clone()))
+        };
+        Ok(ChatStageModels {
+            sql: pick(SettingKey::LlmSqlModel)?,
+            summary: pick(SettingKey::LlmSummaryModel)?,
+        })
+    }
 
-        let insurance_renewal = self.store.get_house_profile()?.and_then(|house| {
-            let renewal_date = house.insurance_renewal?;
-            let days_from_now = days_from_to(today, renewal_date);
-            if !(-30..=90).contains(&days_from_now) {
-                return None;
-            }
-            let carrier = if house.insurance_carrier.trim().is_empty() {
-                "insurance renewal".to_owned()
-            } else {
-                house.insurance_carrier
-            };
-            Some(DashboardInsuranceRenewal {
-                house_profile_id: house.id,
-                carrier,
-                renewal_date,
-                days_from_now,
-            })
-        });
+    fn client_with_model(client: &LlmClient, model: &str) -> LlmClient {
+        let mut client = client.clone();
+        client.set_model(model);
+        client
+    }
 
-        let recent_activity = self
-            .store
-            .list_recent_service_logs(5)?
+    fn answer_chat_question(
+        &self,
+        question: &str,
+        history: &[ChatHistoryMessage],
+    ) -> Result<ChatPipelineResult> {
+        let trimmed_question = question.trim();
+        if trimmed_question.is_empty() {
+            bail!("question is empty; enter a prompt and retry");
+        }
+
+        let Some(client) = self.llm_client.as_ref() else {
+            bail!("LLM disabled -- set [llm].enabled = true and restart");
+        };
+        let models = self.stage_models(client)?;
+        let sql_client = Self::client_with_model(client, &models.sql);
+        let summary_client = Self::client_with_model(client, &models.summary);
+
+        let now = OffsetDateTime::now_utc();
+        let tables = self.build_table_info();
+        let column_hints = self.store.column_hints();
+        let chat_context = self.chat_context();
+        let sql_prompt = build_sql_prompt(
+            &tables,
+            now,
+            non_empty(&column_hints),
+            non_empty(&chat_context),
+        );
+
+        let mut sql_messages = Vec::with_capacity(history.len() + 2);
+        sql_messages.push(LlmMessage {
+            role: LlmRole::System,
+            content: sql_prompt,
+        });
+        sql_messages.extend(Self::build_history_messages(history));
+        sql_messages.push(LlmMessage {
+            role: LlmRole::User,
+            content: trimmed_question.to_owned(),
+        });
+
+        let raw_sql = Self::stream_chat_complete(&sql_client, &sql_messages).context(
+            "SQL generation failed; verify the selected model is available and LLM server is reachable",
+        )?;
+        let sql_raw = extract_sql(&raw_sql);
+        if sql_raw.is_empty() {
+            return self
+                .run_fallback_pipeline(&summary_client, trimmed_question, history, &tables, now)
+                .context("LLM returned empty SQL and fallback query failed");
+        }
+        let sql = format_sql(&sql_raw, 96);
+
+        let (columns, rows) = match self.store.read_only_query(&sql_raw) {
+            Ok(output) => output,
+            Err(_) => {
+                return self
+                    .run_fallback_pipeline(&summary_client, trimmed_question, history, &tables, now)
+                    .context("generated SQL could not be executed and fallback query failed");
+            }
+        };
+
+        let results_table = format_results_table(&columns, &rows);
+        let mut summary_prompt = build_summary_prompt(
+            trimmed_question,
+            &sql,
+            &results_table,
+            now,
+            non_empty(&chat_context),
+        );
+        let (excerpts, notice) = self.document_excerpts(trimmed_question);
+        summary_prompt.push_str(&excerpts);
+
+        let summary_messages = vec![
+            LlmMessage {
+                role: LlmRole::System,
+                content: summary_prompt,
+            },
+            LlmMessage {
+                role: LlmRole::User,
+                content: "Summarize these results.".to_owned(),
+            },
+        ];
+        let answer = Self::stream_chat_complete(&summary_client, &summary_messages).context(
+            "result summarization failed; retry with a smaller question or switch to another model",
+        )?;
+
+        Ok(ChatPipelineResult {
+            answer,
+            sql: Some(sql),
+            used_fallback: false,
+            notice,
+        })
+    }
+
+    fn today_utc() -> Result<Date> {
+        Ok(time::OffsetDateTime::now_utc().date())
+    }
+
+    /// Spend per budget category in the current calendar year.
+    fn annual_spend(&self) -> Result<BTreeMap<BudgetCategory, i64>> {
+        let year_start = Self::today_utc()?
+            .replace_month(time::Month::January)
+            .and_then(|date| date.replace_day(1))
+            .context("compute start of year")?;
+        self.store
+            .budget_spend_cents(year_start, add_months(year_start, 12))
+    }
+
+    /// Every category with a budget or spend on either side, named and
+    /// paired up.
+    fn scenario_lines(
+        &self,
+        real: &BTreeMap<BudgetCategory, i64>,
+        what_if: &BTreeMap<BudgetCategory, i64>,
+    ) -> Result<Vec<ScenarioLine>> {
+        let budgets = self.store.list_budgets(false)?;
+        let mut names = self
+            .store
+            .list_maintenance_categories()?
+            .into_iter()
+            .map(|lookup| (BudgetCategory::Maintenance(lookup.id), lookup.name))
+            .chain(
+                self.store
+                    .list_project_types()?
+                    .into_iter()
+                    .map(|lookup| (BudgetCategory::ProjectType(lookup.id), lookup.name)),
+            )
+            .collect::<BTreeMap<_, _>>();
+        let categories = budgets
+            .iter()
+            .map(|budget| budget.category)
+            .chain(real.keys().copied())
+            .chain(what_if.keys().copied())
+            .collect::<BTreeSet<_>>();
+        Ok(categories
+            .into_iter()
+            .map(|category| ScenarioLine {
+                category,
+                category_name: names
+                    .remove(&category)
+                    .unwrap_or_else(|| format!("{} {}", category.scope(), category.id())),
+                annual_budget_cents: budgets
+                    .iter()
+                    .find(|budget| budget.category == category)
+                    .map(|budget| budget.monthly_cents * 12),
+                real_cents: real.get(&category).copied().unwrap_or_default(),
+                what_if_cents: what_if.get(&category).copied().unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Warranties ending within the warranty notice, overdue maintenance, and
+    /// house or vendor insurance due within the insurance notice, as of
+    /// `today`. Rows hidden from the tables stay out.
+    fn reminders_on(&self, today: Date) -> Result<Vec<Reminder>> {
+        let warranty_days = self.store.get_notice_days(SettingKey::NotifyWarrantyDays)?;
+        let insurance_days = self
+            .store
+            .get_notice_days(SettingKey::NotifyInsuranceDays)?;
+        let hidden_appliances = self.hidden_row_ids(DeletionEntity::Appliance)?;
+        let hidden_maintenance = self.hidden_row_ids(DeletionEntity::Maintenance)?;
+        let hidden_vendors = self.hidden_row_ids(DeletionEntity::Vendor)?;
+
+        let mut reminders = Vec::new();
+        for appliance in self
+            .store
+            .list_expiring_warranties(today, 0, warranty_days)?
+        {
+            if let Some(expiry) = appliance.warranty_expiry
+                && !hidden_appliances.contains(&appliance.id.get())
+            {
+                reminders.push(Reminder {
+                    kind: ReminderKind::Warranty,
+                    title: appliance.name,
+                    days_from_now: days_from_to(today, expiry),
+                });
+            }
+        }
+        for item in self.store.list_maintenance_with_schedule()? {
+            if let Some(due) = next_due(item.last_serviced_at, item.interval_months)
+                && due < today
+                && !hidden_maintenance.contains(&item.id.get())
+            {
+                reminders.push(Reminder {
+                    kind: ReminderKind::Maintenance,
+                    title: item.name,
+                    days_from_now: days_from_to(today, due),
+                });
+            }
+        }
+        if let Some(house) = self.store.get_house_profile()?
+            && let Some(renewal) = house.insurance_renewal
+            && days_from_to(today, renewal) <= insurance_days
+        {
+            let title = if house.insurance_carrier.trim().is_empty() {
+                "House".to_owned()
+            } else {
+                house.insurance_carrier
+            };
+            reminders.push(Reminder {
+                kind: ReminderKind::Insurance,
+                title,
+                days_from_now: days_from_to(today, renewal),
+            });
+        }
+        for vendor in self
+            .store
+            .list_vendor_insurance_lapses(today, insurance_days)?
+        {
+            if let Some(expiry) = vendor.insurance_expiry
+                && !hidden_vendors.contains(&vendor.id.get())
+            {
+                reminders.push(Reminder {
+                    kind: ReminderKind::Insurance,
+                    title: vendor.name,
+                    days_from_now: days_from_to(today, expiry),
+                });
+            }
+        }
+        reminders.sort_by_key(|reminder| reminder.days_from_now);
+        Ok(reminders)
+    }
+}
+
+/// Most reminder lines one desktop notification lists.
+const NOTIFICATION_LINES: usize = 5;
+
+/// Notification title and body: the soonest reminders, then a count of the
+/// rest.
+fn reminder_notification(reminders: &[Reminder]) -> (String, String) {
+    let summary = match reminders.len() {
+        1 => "micasa: 1 reminder".to_owned(),
+        count => format!("micasa: {count} reminders"),
+    };
+    let mut lines = reminders
+        .iter()
+        .take(NOTIFICATION_LINES)
+        .map(Reminder::summary)
+        .collect::<Vec<_>>();
+    if reminders.len() > NOTIFICATION_LINES {
+        lines.push(format!(
+            "and {} more in micasa",
+            reminders.len() - NOTIFICATION_LINES
+        ));
+    }
+    (summary, lines.join("\n"))
+}
+
+impl micasa_tui::AppRuntime for DbRuntime<'_> {
+    fn load_dashboard_counts(&mut self) -> Result<micasa_app::DashboardCounts> {
+        self.store.dashboard_counts()
+    }
+
+    fn load_dashboard_snapshot(&mut self) -> Result<DashboardSnapshot> {
+        let today = Self::today_utc()?;
+        let hidden_incidents = self.hidden_row_ids(DeletionEntity::Incident)?;
+        let hidden_maintenance = self.hidden_row_ids(DeletionEntity::Maintenance)?;
+        let hidden_projects = self.hidden_row_ids(DeletionEntity::Project)?;
+        let hidden_appliances = self.hidden_row_ids(DeletionEntity::Appliance)?;
+        let hidden_service_logs = self.hidden_row_ids(DeletionEntity::ServiceLog)?;
+        let hidden_vendors = self.hidden_row_ids(DeletionEntity::Vendor)?;
+
+        let incidents = self
+            .store
+            .list_open_incidents()?
+            .into_iter()
+            .filter(|incident| !hidden_incidents.contains(&incident.id.get()))
+            .map(|incident| DashboardIncident {
+                incident_id: incident.id,
+                title: incident.title,
+                severity: incident.severity,
+                days_open: days_from_to(incident.date_noticed, today).max(0),
+            })
+            .collect::<Vec<_>>();
+        let follow_ups = self
+            .store
+            .list_due_incident_follow_ups(today)?
+            .into_iter()
+            .filter(|incident| !hidden_incidents.contains(&incident.id.get()))
+            .filter_map(|incident| {
+                let due = incident.follow_up_date?;
+                Some(DashboardFollowUp {
+                    incident_id: incident.id,
+                    title: incident.title,
+                    days_overdue: days_from_to(due, today).max(0),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let service_dates = self.visible_service_dates()?;
+
+        let mut overdue = Vec::new();
+        let mut upcoming = Vec::new();
+        let mut interval_drift = Vec::new();
+        for item in self.store.list_maintenance_with_schedule()? {
+            if hidden_maintenance.contains(&item.id.get()) {
+                continue;
+            }
+            if let Some(observed_months) = service_dates
+                .get(&item.id.get())
+                .and_then(|dates| observed_interval_months(dates))
+                .filter(|observed| *observed != item.interval_months)
+            {
+                interval_drift.push(DashboardIntervalDrift {
+                    maintenance_item_id: item.id,
+                    item_name: item.name.clone(),
+                    interval_months: item.interval_months,
+                    observed_months,
+                });
+            }
+            let Some(next_due) = next_due(item.last_serviced_at, item.interval_months) else {
+                continue;
+            };
+            let days_from_now = days_from_to(today, next_due);
+            let entry = DashboardMaintenance {
+                maintenance_item_id: item.id,
+                item_name: item.name,
+                days_from_now,
+            };
+            if days_from_now < 0 {
+                overdue.push(entry);
+            } else if days_from_now <= 30 {
+                upcoming.push(entry);
+            }
+        }
+        overdue.sort_by_key(|entry| entry.days_from_now);
+        upcoming.sort_by_key(|entry| entry.days_from_now);
+        interval_drift.sort_by_key(|drift| {
+            (
+                std::cmp::Reverse((drift.observed_months - drift.interval_months).abs()),
+                std::cmp::Reverse(drift.maintenance_item_id.get()),
+            )
+        });
+
+        let vendor_insurance = self
+            .store
+            .list_vendor_insurance_lapses(today, 30)?
+            .into_iter()
+            .filter(|vendor| !hidden_vendors.contains(&vendor.id.get()))
+            .filter_map(|vendor| {
+                let expiry = vendor.insurance_expiry?;
+                Some(DashboardVendorInsurance {
+                    vendor_id: vendor.id,
+                    vendor_name: vendor.name,
+                    days_from_now: days_from_to(today, expiry),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let active_projects = self
+            .store
+            .list_active_projects()?
+            .into_iter()
+            .filter(|project| !hidden_projects.contains(&project.id.get()))
+            .map(|project| DashboardProject {
+                project_id: project.id,
+                title: project.title,
+                status: project.status,
+            })
+            .collect::<Vec<_>>();
+
+        let expiring_warranties = self
+            .store
+            .list_expiring_warranties(today, 30, 90)?
+            .into_iter()
+            .filter(|appliance| !hidden_appliances.contains(&appliance.id.get()))
+            .filter_map(|appliance| {
+                let warranty_expiry = appliance.warranty_expiry?;
+                Some(DashboardWarranty {
+                    appliance_id: appliance.id,
+                    appliance_name: appliance.name,
+                    days_from_now: days_from_to(today, warranty_expiry),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let insurance_renewal = self.store.get_house_profile()?.and_then(|house| {
+            let renewal_date = house.insurance_renewal?;
+            let days_from_now = days_from_to(today, renewal_date);
+            if !(-30..=90).contains(&days_from_now) {
+                return None;
+            }
+            let carrier = if house.insurance_carrier.trim().is_empty() {
+                "insurance renewal".to_owned()
+            } else {
+                house.insurance_carrier
+            };
+            Some(DashboardInsuranceRenewal {
+                house_profile_id: house.id,
+                carrier,
+                renewal_date,
+                days_from_now,
+            })
+        });
+
+        let recent_activity = self
+            .store
+            .list_recent_service_logs(5)?
             .into_iter()
+            .filter(|entry| !hidden_service_logs.contains(&entry.id.get()))
             .map(|entry| DashboardServiceEntry {
                 service_log_entry_id: entry.id,
                 maintenance_item_id: entry.maintenance_item_id,
@@ -371,14 +1387,22 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
             })
             .collect::<Vec<_>>();
 
+        let weather_alerts = self.load_weather_alerts(today, &hidden_maintenance)?;
+
         Ok(DashboardSnapshot {
             incidents,
+            follow_ups,
+            weather_alerts,
             overdue,
             upcoming,
+            interval_drift,
+            vendor_insurance,
             active_projects,
             expiring_warranties,
             insurance_renewal,
             recent_activity,
+            data_issues: Vec::new(),
+            layout: DashboardLayout::default(),
         })
     }
 
@@ -387,62 +1411,286 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
         tab: TabKind,
         include_deleted: bool,
     ) -> Result<Option<TabSnapshot>> {
-        let snapshot = match tab {
+        let mut snapshot = match tab {
             TabKind::Dashboard => None,
             TabKind::House => Some(TabSnapshot::House(Box::new(
                 self.store.get_house_profile()?,
             ))),
             TabKind::Projects => Some(TabSnapshot::Projects(
                 self.store.list_projects(include_deleted)?,
+                BTreeMap::new(),
+                BTreeMap::new(),
+                BTreeMap::new(),
             )),
             TabKind::Quotes => Some(TabSnapshot::Quotes(
                 self.store.list_quotes(include_deleted)?,
             )),
             TabKind::Maintenance => Some(TabSnapshot::Maintenance(
                 self.store.list_maintenance_items(include_deleted)?,
+                BTreeMap::new(),
+                BTreeMap::new(),
             )),
             TabKind::ServiceLog => Some(TabSnapshot::ServiceLog(
                 self.store.list_service_log_entries(include_deleted)?,
             )),
             TabKind::Incidents => Some(TabSnapshot::Incidents(
                 self.store.list_incidents(include_deleted)?,
+                BTreeMap::new(),
+                BTreeMap::new(),
             )),
             TabKind::Appliances => Some(TabSnapshot::Appliances(
                 self.store.list_appliances(include_deleted)?,
+                BTreeMap::new(),
+                BTreeMap::new(),
             )),
             TabKind::Vendors => Some(TabSnapshot::Vendors(
                 self.store.list_vendors(include_deleted)?,
+                BTreeMap::new(),
+                BTreeMap::new(),
             )),
             TabKind::Documents => Some(TabSnapshot::Documents(
                 self.store.list_documents(include_deleted)?,
+                BTreeMap::new(),
             )),
-            TabKind::Settings => Some(TabSnapshot::Settings(self.store.list_settings()?)),
+            TabKind::Budget => Some(TabSnapshot::Budgets(
+                self.store.list_budgets(include_deleted)?,
+                BTreeMap::new(),
+            )),
+            TabKind::Schedule => Some(TabSnapshot::Schedule(self.load_maintenance_schedule()?)),
+            TabKind::Settings => Some(TabSnapshot::Settings(self.load_settings()?)),
+            // Results exist only once the console runs a statement.
+            TabKind::Query => None,
+            // Built per appliance by `load_appliance_cost_history`.
+            TabKind::CostHistory => None,
         };
+        if let (Some(snapshot), Some(entity)) = (&mut snapshot, Self::private_entity(tab)) {
+            snapshot.retain_rows_not_in(&self.hidden_row_ids(entity)?);
+        }
         Ok(snapshot)
     }
 
-    fn load_chat_history(&mut self) -> Result<Vec<String>> {
-        Ok(self
-            .store
-            .load_chat_history()?
-            .into_iter()
-            .map(|entry| entry.input)
-            .collect())
-    }
-
-    fn append_chat_input(&mut self, input: &str) -> Result<()> {
-        let trimmed = input.trim();
-        if trimmed.is_empty() {
-            return Ok(());
+    fn load_rows_since(
+        &mut self,
+        tab: TabKind,
+        cursor: OffsetDateTime,
+        include_deleted: bool,
+    ) -> Result<Option<RowDelta>> {
+        let (mut changed, mut ids) = match tab {
+            TabKind::Quotes => {
+                let changes = self.store.quotes_changed_since(cursor, include_deleted)?;
+                (TabSnapshot::Quotes(changes.rows), changes.ids)
+            }
+            TabKind::ServiceLog => {
+                let changes = self
+                    .store
+                    .service_log_entries_changed_since(cursor, include_deleted)?;
+                (TabSnapshot::ServiceLog(changes.rows), changes.ids)
+            }
+            _ => return Ok(None),
+        };
+        if let Some(entity) = Self::private_entity(tab) {
+            let hidden = self.hidden_row_ids(entity)?;
+            changed.retain_rows_not_in(&hidden);
+            ids.retain(|id| !hidden.contains(id));
         }
-        self.store.append_chat_input(trimmed)
+        Ok(Some(RowDelta { changed, ids }))
     }
 
-    fn submit_form(&mut self, payload: &FormPayload) -> Result<()> {
-        payload.validate()?;
-
-        let mutation = match payload {
-            FormPayload::HouseProfile(form) => {
+    fn load_rows_page(
+        &mut self,
+        tab: TabKind,
+        offset: usize,
+        limit: usize,
+        include_deleted: bool,
+    ) -> Result<Option<RowPage>> {
+        // The tabs that grow without bound; the rest load in full.
+        let entity = match tab {
+            TabKind::Quotes => DeletionEntity::Quote,
+            TabKind::ServiceLog => DeletionEntity::ServiceLog,
+            _ => return Ok(None),
+        };
+        let skip = self.hidden_row_ids(entity)?;
+        let page = ListPage {
+            skip: &skip,
+            offset,
+            limit,
+        };
+        let page = if tab == TabKind::Quotes {
+            let page = self.store.quotes_page(include_deleted, page)?;
+            RowPage {
+                rows: TabSnapshot::Quotes(page.rows),
+                total: page.total,
+            }
+        } else {
+            let page = self.store.service_log_page(include_deleted, page)?;
+            RowPage {
+                rows: TabSnapshot::ServiceLog(page.rows),
+                total: page.total,
+            }
+        };
+        Ok(Some(page))
+    }
+
+    fn load_maintenance_schedule(&mut self) -> Result<Vec<ScheduleEvent>> {
+        let hidden_maintenance = self.hidden_row_ids(DeletionEntity::Maintenance)?;
+        let hidden_appliances = self.hidden_row_ids(DeletionEntity::Appliance)?;
+        let maintenance = self
+            .store
+            .list_maintenance_with_schedule()?
+            .into_iter()
+            .filter(|item| !hidden_maintenance.contains(&item.id.get()))
+            .collect::<Vec<_>>();
+        let appliances = self
+            .store
+            .list_appliances(false)?
+            .into_iter()
+            .filter(|appliance| !hidden_appliances.contains(&appliance.id.get()))
+            .collect::<Vec<_>>();
+        let house = self.store.get_house_profile()?;
+        Ok(build_schedule(
+            Self::today_utc()?,
+            SCHEDULE_HORIZON_DAYS,
+            &maintenance,
+            &appliances,
+            house.as_ref(),
+        ))
+    }
+
+    fn load_project_rollups(&mut self) -> Result<BTreeMap<ProjectId, ProjectRollup>> {
+        self.store.project_rollups()
+    }
+
+    fn load_maintenance_log_counts(&mut self) -> Result<BTreeMap<MaintenanceItemId, usize>> {
+        self.store.service_log_counts_by_maintenance()
+    }
+
+    fn load_incident_doc_counts(&mut self) -> Result<BTreeMap<IncidentId, usize>> {
+        self.store.document_counts_by_incident()
+    }
+
+    fn load_appliance_rollups(&mut self) -> Result<BTreeMap<ApplianceId, ApplianceRollup>> {
+        self.store.appliance_rollups()
+    }
+
+    fn load_appliance_cost_history(
+        &mut self,
+        appliance_id: ApplianceId,
+    ) -> Result<ApplianceCostHistory> {
+        let appliance = self
+            .store
+            .list_appliances(false)?
+            .into_iter()
+            .find(|appliance| appliance.id == appliance_id)
+            .with_context(|| {
+                format!(
+                    "appliance {} not found or deleted -- refresh the view and pick a live appliance",
+                    appliance_id.get()
+                )
+            })?;
+        let hidden_services = self.hidden_row_ids(DeletionEntity::ServiceLog)?;
+        let hidden_incidents = self.hidden_row_ids(DeletionEntity::Incident)?;
+        let service_log = self
+            .store
+            .list_service_log_entries(false)?
+            .into_iter()
+            .filter(|entry| !hidden_services.contains(&entry.id.get()))
+            .collect::<Vec<_>>();
+        let incidents = self
+            .store
+            .list_incidents(false)?
+            .into_iter()
+            .filter(|incident| !hidden_incidents.contains(&incident.id.get()))
+            .collect::<Vec<_>>();
+        Ok(ApplianceCostHistory::build(
+            &appliance,
+            &self.store.list_maintenance_items(false)?,
+            &service_log,
+            &incidents,
+        ))
+    }
+
+    fn load_vendor_rollups(&mut self) -> Result<BTreeMap<VendorId, VendorRollup>> {
+        self.store.vendor_rollups()
+    }
+
+    fn load_budget_spend(&mut self, months: usize) -> Result<BTreeMap<BudgetCategory, Vec<i64>>> {
+        let current_month = Self::today_utc()?
+            .replace_day(1)
+            .context("compute start of current month")?;
+        let mut spend = BTreeMap::<BudgetCategory, Vec<i64>>::new();
+        for (index, months_back) in (0..months).rev().enumerate() {
+            let months_back = i32::try_from(months_back).context("budget trend is too long")?;
+            let start = add_months(current_month, -months_back);
+            for (category, cents) in self.store.budget_spend_cents(start, add_months(start, 1))? {
+                spend.entry(category).or_insert_with(|| vec![0; months])[index] = cents;
+            }
+        }
+        Ok(spend)
+    }
+
+    fn toggle_quote_accepted(&mut self, row_id: i64) -> Result<bool> {
+        let quote_id = QuoteId::new(row_id);
+        let Some(quote) = self
+            .store
+            .list_quotes(false)?
+            .into_iter()
+            .find(|quote| quote.id == quote_id)
+        else {
+            bail!("quote {row_id} not found or deleted -- refresh the view and pick a live quote");
+        };
+        let accepted = quote.accepted_at.is_none();
+        self.store.set_quote_accepted(quote_id, accepted)?;
+        Ok(accepted)
+    }
+
+    fn load_audit_entries(
+        &mut self,
+        source: Option<AuditSource>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        let mut entries = self.store.list_audit_entries(source, limit)?;
+        if !self.show_private {
+            let mut private = Vec::<(DeletionEntity, BTreeSet<i64>)>::new();
+            for entry in &entries {
+                if !private.iter().any(|(entity, _)| *entity == entry.entity) {
+                    private.push((entry.entity, self.store.private_row_ids(entry.entity)?));
+                }
+            }
+            entries.retain(|entry| {
+                !private
+                    .iter()
+                    .any(|(entity, ids)| *entity == entry.entity && ids.contains(&entry.target_id))
+            });
+        }
+        Ok(entries)
+    }
+
+    fn settle_incident_follow_up(&mut self, incident_id: IncidentId, reopen: bool) -> Result<()> {
+        self.store.settle_incident_follow_up(incident_id, reopen)
+    }
+
+    fn load_chat_history(&mut self) -> Result<Vec<String>> {
+        Ok(self
+            .store
+            .load_chat_history()?
+            .into_iter()
+            .map(|entry| entry.input)
+            .collect())
+    }
+
+    fn append_chat_input(&mut self, input: &str) -> Result<()> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Ok(());
+        }
+        self.store.append_chat_input(trimmed)
+    }
+
+    fn submit_form(&mut self, payload: &FormPayload) -> Result<()> {
+        payload.validate()?;
+
+        let mutation = match payload {
+            FormPayload::HouseProfile(form) => {
                 self.store.upsert_house_profile(&HouseProfileInput {
                     nickname: form.nickname.clone(),
                     address_line_1: form.address_line_1.clone(),
@@ -484,6 +1732,7 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     end_date: form.end_date,
                     budget_cents: form.budget_cents,
                     actual_cents: form.actual_cents,
+                    parent_id: form.parent_id,
                 })?;
                 Some(MutationRecord::Created(LifecycleEntityRef::Project(id)))
             }
@@ -495,6 +1744,9 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     phone: form.phone.clone(),
                     website: form.website.clone(),
                     notes: form.notes.clone(),
+                    license_number: form.license_number.clone(),
+                    insurance_expiry: form.insurance_expiry,
+                    insurance_document_id: form.insurance_document_id,
                 })?;
                 Some(MutationRecord::Created(LifecycleEntityRef::Vendor(id)))
             }
@@ -537,6 +1789,7 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     notes: form.notes.clone(),
                     cost_cents: form.cost_cents,
                 })?;
+                self.index_manual(id);
                 Some(MutationRecord::Created(
                     LifecycleEntityRef::MaintenanceItem(id),
                 ))
@@ -549,9 +1802,11 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     cost_cents: form.cost_cents,
                     notes: form.notes.clone(),
                 })?;
-                Some(MutationRecord::Created(
-                    LifecycleEntityRef::ServiceLogEntry(id),
-                ))
+                let created = MutationRecord::Created(LifecycleEntityRef::ServiceLogEntry(id));
+                match self.advance_last_serviced(form.maintenance_item_id, form.serviced_at)? {
+                    Some(advanced) => Some(MutationRecord::Batch(vec![created, advanced])),
+                    None => Some(created),
+                }
             }
             FormPayload::Incident(form) => {
                 let id = self.store.create_incident(&NewIncident {
@@ -566,11 +1821,20 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     appliance_id: form.appliance_id,
                     vendor_id: form.vendor_id,
                     notes: form.notes.clone(),
+                    follow_up_date: form.follow_up_date,
                 })?;
                 Some(MutationRecord::Created(LifecycleEntityRef::Incident(id)))
             }
+            FormPayload::Budget(form) => {
+                let id = self.store.create_budget(&NewBudget {
+                    category: form.category,
+                    monthly_cents: form.monthly_cents,
+                    notes: form.notes.clone(),
+                })?;
+                Some(MutationRecord::Created(LifecycleEntityRef::Budget(id)))
+            }
             FormPayload::Document(form) => {
-                self.store.insert_document(&NewDocument {
+                let id = self.store.insert_document(&NewDocument {
                     title: form.title.clone(),
                     file_name: form.file_name.clone(),
                     entity_kind: form.entity_kind,
@@ -579,35 +1843,52 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                     data: form.data.clone(),
                     notes: form.notes.clone(),
                 })?;
+                // Not undoable, but still part of the audit trail.
+                self.store
+                    .record_audit(LifecycleEntityRef::Document(id), "create")?;
+                self.extract_document_text(id, &form.mime_type, &form.data);
+                self.index_document(id);
                 None
             }
         };
 
         if let Some(mutation) = mutation {
-            self.record_mutation(mutation);
+            self.record_mutation(mutation)?;
         }
+        self.refresh_calendar();
 
+        // The row is already saved; a failed check is surfaced as the alert
+        // itself rather than reported as a failed save.
+        self.pending_budget_alert = self
+            .budget_alert_for(payload)
+            .unwrap_or_else(|error| Some(format!("budget check failed: {error}")));
         Ok(())
     }
 
     fn apply_lifecycle(
         &mut self,
         tab: TabKind,
-        row_id: i64,
+        row_ids: &[i64],
         action: LifecycleAction,
     ) -> Result<()> {
-        let target = Self::lifecycle_target(tab, row_id)?;
-        let record = match action {
-            LifecycleAction::Delete => {
-                self.store.soft_delete(target)?;
-                MutationRecord::SoftDeleted(target)
-            }
-            LifecycleAction::Restore => {
-                self.store.restore(target)?;
-                MutationRecord::Restored(target)
-            }
+        let mut records = row_ids
+            .iter()
+            .map(|row_id| {
+                let target = Self::lifecycle_target(tab, *row_id)?;
+                Ok(match action {
+                    LifecycleAction::Delete => MutationRecord::SoftDeleted(target),
+                    LifecycleAction::Restore => MutationRecord::Restored(target),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.apply_batch(&records)?;
+        let record = match records.len() {
+            0 => return Ok(()),
+            1 => records.remove(0),
+            _ => MutationRecord::Batch(records),
         };
-        self.record_mutation(record);
+        self.record_mutation(record)?;
+        self.refresh_calendar();
         Ok(())
     }
 
@@ -617,12 +1898,14 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
         };
 
         let inverse = record.inverse();
-        self.apply_record(inverse)?;
+        self.apply_record(&inverse)?;
+        self.audit(&inverse)?;
         self.redo_stack.push(record);
         if self.redo_stack.len() > MAX_UNDO_STACK {
             let overflow = self.redo_stack.len() - MAX_UNDO_STACK;
             self.redo_stack.drain(0..overflow);
         }
+        self.refresh_calendar();
         Ok(true)
     }
 
@@ -631,26 +1914,260 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
             return Ok(false);
         };
 
-        self.apply_record(record)?;
+        self.apply_record(&record)?;
+        self.audit(&record)?;
         self.undo_stack.push(record);
         if self.undo_stack.len() > MAX_UNDO_STACK {
             let overflow = self.undo_stack.len() - MAX_UNDO_STACK;
             self.undo_stack.drain(0..overflow);
         }
+        self.refresh_calendar();
         Ok(true)
     }
 
+    fn last_service_vendor(&mut self, item_id: MaintenanceItemId) -> Result<Option<VendorId>> {
+        Ok(self
+            .store
+            .list_service_log_for_maintenance(item_id, false)?
+            .iter()
+            .find_map(|entry| entry.vendor_id))
+    }
+
     fn set_show_dashboard_preference(&mut self, show: bool) -> Result<()> {
         self.store.put_show_dashboard(show)
     }
 
+    fn hide_settled_preference(&mut self, tab: TabKind) -> Result<bool> {
+        self.store.get_hide_settled(tab)
+    }
+
+    fn set_notify_preference(&mut self, key: SettingKey, value: SettingValue) -> Result<()> {
+        if !key.as_str().starts_with("notify.") {
+            bail!("setting `{}` is not a reminder setting", key.as_str());
+        }
+        self.store.put_setting(key, value)
+    }
+
+    fn set_auto_backups(&mut self, enabled: bool) -> Result<()> {
+        self.store
+            .put_setting(SettingKey::BackupAuto, SettingValue::Bool(enabled))
+    }
+
+    fn auto_refresh_seconds(&mut self) -> Result<u64> {
+        self.store.get_auto_refresh_seconds()
+    }
+
+    fn set_auto_refresh_seconds(&mut self, seconds: u64) -> Result<()> {
+        self.store.put_setting(
+            SettingKey::UiAutoRefresh,
+            SettingValue::Text(seconds.to_string()),
+        )
+    }
+
+    fn dashboard_layout(&mut self) -> Result<DashboardLayout> {
+        let text = |key| -> Result<String> {
+            Ok(match self.store.get_setting(key)? {
+                Some(SettingValue::Text(value)) => value,
+                _ => String::new(),
+            })
+        };
+        Ok(DashboardLayout::parse(
+            &text(SettingKey::DashboardSections)?,
+            &text(SettingKey::DashboardRowLimits)?,
+        ))
+    }
+
+    fn set_dashboard_layout(&mut self, layout: &DashboardLayout) -> Result<()> {
+        self.store.put_setting(
+            SettingKey::DashboardSections,
+            SettingValue::Text(layout.sections_setting()),
+        )?;
+        self.store.put_setting(
+            SettingKey::DashboardRowLimits,
+            SettingValue::Text(layout.row_limits_setting()),
+        )
+    }
+
+    fn run_scheduled_backup(&mut self) -> Result<Option<PathBuf>> {
+        let (Some(policy), Some(db_path)) = (&self.backups, &self.db_path) else {
+            return Ok(None);
+        };
+        if self.what_if.is_some() || !self.store.get_auto_backups()? {
+            return Ok(None);
+        }
+        backup::snapshot_if_due(self.store, db_path, policy, OffsetDateTime::now_utc())
+    }
+
+    fn external_changes(&mut self) -> Result<bool> {
+        let version = self.store.data_version()?;
+        let changed = self.data_version.is_some_and(|seen| seen != version);
+        self.data_version = Some(version);
+        Ok(changed)
+    }
+
+    fn set_purge_deleted_days(&mut self, days: i64) -> Result<()> {
+        self.store
+            .put_setting(SettingKey::PurgeDeletedDays, SettingValue::Days(days))
+    }
+
+    fn deleted_row_count(&mut self) -> Result<usize> {
+        self.store.deleted_row_count()
+    }
+
+    fn purge_deleted(&mut self, scope: PurgeScope) -> Result<PurgeSummary> {
+        let cutoff = match scope {
+            PurgeScope::All => None,
+            PurgeScope::Expired => match self.store.get_purge_deleted_days()? {
+                0 => return Ok(PurgeSummary::default()),
+                days => Some(OffsetDateTime::now_utc() - time::Duration::days(days)),
+            },
+        };
+        let summary = self.store.purge_deleted(cutoff)?;
+        if summary.purged > 0 {
+            // Recorded edits may point at rows that are gone now.
+            self.undo_stack.clear();
+            self.redo_stack.clear();
+        }
+        Ok(summary)
+    }
+
+    fn begin_what_if(&mut self) -> Result<()> {
+        let spend = self.annual_spend()?;
+        self.store.begin_sandbox()?;
+        self.what_if = Some(WhatIfBaseline {
+            spend,
+            undo_stack: self.undo_stack.clone(),
+            redo_stack: self.redo_stack.clone(),
+        });
+        Ok(())
+    }
+
+    fn what_if_comparison(&mut self) -> Result<Vec<ScenarioLine>> {
+        let Some(baseline) = &self.what_if else {
+            bail!("no what-if sandbox is open; press ctrl+w to start one");
+        };
+        self.scenario_lines(&baseline.spend, &self.annual_spend()?)
+    }
+
+    fn discard_what_if(&mut self) -> Result<()> {
+        self.store.discard_sandbox()?;
+        if let Some(baseline) = self.what_if.take() {
+            self.undo_stack = baseline.undo_stack;
+            self.redo_stack = baseline.redo_stack;
+        }
+        Ok(())
+    }
+
+    fn load_reminders(&mut self) -> Result<Vec<Reminder>> {
+        self.reminders_on(Self::today_utc()?)
+    }
+
+    fn notify_reminders(&mut self, reminders: &[Reminder]) -> Result<()> {
+        if reminders.is_empty() || !self.store.get_desktop_notifications()? {
+            return Ok(());
+        }
+        let (summary, body) = reminder_notification(reminders);
+        // Showing blocks on the notification daemon, so it runs off the UI
+        // thread. Without a daemon the popup is lost, but the tab bar badge
+        // still counts the reminders.
+        thread::spawn(move || {
+            let _ = notify_rust::Notification::new()
+                .appname("micasa")
+                .summary(&summary)
+                .body(&body)
+                .show();
+        });
+        Ok(())
+    }
+
+    fn data_quality_issues(&mut self) -> Result<Vec<DataIssue>> {
+        let hidden = [
+            (DataIssueKind::ZeroInterval, DeletionEntity::Maintenance),
+            (DataIssueKind::QuoteParts, DeletionEntity::Quote),
+            (
+                DataIssueKind::ResolvedBeforeNoticed,
+                DeletionEntity::Incident,
+            ),
+            (DataIssueKind::EmptyDocument, DeletionEntity::Document),
+        ]
+        .into_iter()
+        .map(|(kind, entity)| Ok((kind, self.hidden_row_ids(entity)?)))
+        .collect::<Result<Vec<_>>>()?;
+        let mut issues = self.store.list_data_issues()?;
+        issues.retain(|issue| {
+            !hidden
+                .iter()
+                .any(|(kind, rows)| *kind == issue.kind && rows.contains(&issue.row_id))
+        });
+        Ok(issues)
+    }
+
+    fn set_hide_settled_preference(&mut self, key: SettingKey, hide: bool) -> Result<()> {
+        if key.hides_settled_on().is_none() {
+            bail!("setting `{}` is not a visibility rule", key.as_str());
+        }
+        self.store.put_setting(key, SettingValue::Bool(hide))
+    }
+
+    fn quick_add_payload(&mut self, text: &str) -> Result<FormPayload> {
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            bail!("quick add needs a description; try `/add leaking faucet in the kitchen`");
+        }
+        let Some(client) = self.llm_client.as_ref() else {
+            bail!("LLM disabled -- set [llm].enabled = true and restart");
+        };
+
+        let prompt = build_quick_add_prompt(
+            OffsetDateTime::now_utc(),
+            &quick_add::known_values(self.store)?,
+            self.llm_extra_context(),
+        );
+        let messages = [
+            LlmMessage {
+                role: LlmRole::System,
+                content: prompt,
+            },
+            LlmMessage {
+                role: LlmRole::User,
+                content: trimmed.to_owned(),
+            },
+        ];
+        let answer = Self::stream_chat_complete(client, &messages).context(
+            "quick add failed; verify the selected model is available and LLM server is reachable",
+        )?;
+        quick_add::payload_from_answer(self.store, &answer, Self::today_utc()?)
+    }
+
+    fn explain_chat_sql(&mut self, question: &str, sql: &str) -> Result<String> {
+        let Some(client) = self.llm_client.as_ref() else {
+            bail!("LLM disabled -- set [llm].enabled = true and restart");
+        };
+        let models = self.stage_models(client)?;
+        let client = Self::client_with_model(client, &models.summary);
+        let messages = [LlmMessage {
+            role: LlmRole::User,
+            content: build_explain_prompt(question, sql, self.llm_extra_context()),
+        }];
+        let explanation = Self::stream_chat_complete(&client, &messages)
+            .context("verify the selected model is available and LLM server is reachable")?;
+        if explanation.trim().is_empty() {
+            bail!("model returned an empty explanation; try again or switch models");
+        }
+        Ok(explanation)
+    }
+
     fn list_chat_models(&mut self) -> Result<Vec<String>> {
         let Some(client) = self.llm_client.as_ref() else {
             bail!("LLM disabled -- set [llm].enabled = true and restart");
         };
-        client
-            .list_models()
-            .context("list models; ensure Ollama (or another OpenAI-compatible server) is running")
+        client.list_models().with_context(|| {
+            format!(
+                "list models from {} ({}); ensure the server is running",
+                client.base_url(),
+                client.provider().name()
+            )
+        })
     }
 
     fn active_chat_model(&mut self) -> Result<Option<String>> {
@@ -684,30 +2201,20 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
                 client.base_url()
             )
         })?;
-        let exists = available
-            .iter()
-            .any(|entry| entry == trimmed || entry.starts_with(&format!("{trimmed}:")));
-
-        if !exists {
-            if client.base_url().contains("11434") {
-                let mut scanner = client.pull_model(trimmed).with_context(|| {
-                    format!(
-                        "model `{trimmed}` is missing and auto-pull failed to start; run `ollama pull {trimmed}`"
-                    )
-                })?;
-                while let Some(chunk) = scanner.next_chunk()? {
-                    if let Some(error) = chunk.error
-                        && !error.is_empty()
-                    {
-                        bail!(
-                            "model pull failed for `{trimmed}`: {error}; run `ollama pull {trimmed}` and retry"
-                        );
-                    }
+        if !client.has_model(&available, trimmed) {
+            let hint = client.provider().missing_model_hint(trimmed);
+            let Some(mut scanner) = client.pull_model(trimmed).with_context(|| {
+                format!("model `{trimmed}` is missing and auto-pull failed to start; {hint}")
+            })?
+            else {
+                bail!("model `{trimmed}` not found on server -- {hint}");
+            };
+            while let Some(chunk) = scanner.next_chunk()? {
+                if let Some(error) = chunk.error
+                    && !error.is_empty()
+                {
+                    bail!("model pull failed for `{trimmed}`: {error}; {hint} and retry");
                 }
-            } else {
-                bail!(
-                    "model `{trimmed}` not found on server -- run `/models` and choose one that exists"
-                );
             }
         }
 
@@ -716,16 +2223,107 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
         Ok(())
     }
 
+    fn list_chat_providers(&mut self) -> Result<Vec<String>> {
+        Ok(micasa_llm::PROVIDERS
+            .iter()
+            .map(|name| (*name).to_owned())
+            .collect())
+    }
+
+    fn active_chat_provider(&mut self) -> Result<Option<String>> {
+        Ok(self
+            .llm_client
+            .as_ref()
+            .map(|client| client.provider().name().to_owned()))
+    }
+
+    fn select_chat_provider(&mut self, name: &str) -> Result<String> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            bail!("usage: /provider <name>");
+        }
+        let Some(provider) = micasa_llm::provider_named(trimmed) else {
+            bail!(
+                "unknown provider `{trimmed}`; choose one of {}",
+                micasa_llm::PROVIDERS.join(", ")
+            );
+        };
+        let Some(client) = self.llm_client.as_mut() else {
+            bail!("LLM disabled -- set [llm].enabled = true and restart");
+        };
+        let base_url = match &self.configured_llm {
+            Some((configured, url)) if *configured == provider.name() => url.clone(),
+            _ => provider.default_base_url().to_owned(),
+        };
+        client.set_provider(provider, &base_url);
+        Ok(base_url)
+    }
+
+    fn chat_offline_reason(&mut self) -> Option<String> {
+        let error = self.llm_client.as_ref()?.probe().err()?;
+        Some(format!("{error:#}"))
+    }
+
+    fn chat_stage_models(&mut self) -> Result<Option<ChatStageModels>> {
+        let Some(client) = self.llm_client.as_ref() else {
+            return Ok(None);
+        };
+        self.stage_models(client).map(Some)
+    }
+
+    fn set_stage_model(&mut self, key: SettingKey, model: &str) -> Result<()> {
+        self.store.put_stage_model(key, model)
+    }
+
+    fn link_names(&mut self) -> Result<LinkNames> {
+        [
+            TabKind::Projects,
+            TabKind::Vendors,
+            TabKind::Maintenance,
+            TabKind::Appliances,
+        ]
+        .into_iter()
+        .map(|tab| Ok((tab, self.store.row_names(tab)?)))
+        .collect()
+    }
+
+    fn search_link_targets(
+        &mut self,
+        tab: TabKind,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(i64, String)>> {
+        self.store.search_row_names(tab, query, limit)
+    }
+
+    fn alert_styles(&mut self) -> Result<BTreeMap<AlertEvent, AlertStyle>> {
+        AlertEvent::ALL
+            .into_iter()
+            .map(|event| Ok((event, self.store.get_alert_style(event)?)))
+            .collect()
+    }
+
+    fn set_alert_style(&mut self, event: AlertEvent, style: AlertStyle) -> Result<()> {
+        self.store.put_alert_style(event, style)
+    }
+
     fn spawn_chat_pipeline(
         &mut self,
         request_id: u64,
         question: &str,
         history: &[ChatHistoryMessage],
-        tx: Sender<InternalEvent>,
+        tx: UnboundedSender<InternalEvent>,
     ) -> Result<()> {
-        self.chat_cancellations
-            .retain(|_, flag| !flag.load(Ordering::Acquire));
+        // One pipeline at a time: a new question cancels whatever is still
+        // streaming, so answers never interleave.
+        self.chat_tasks.retain(|_, task| !task.handle.is_finished());
+        for task in self.chat_tasks.values() {
+            task.cancel.store(true, Ordering::Release);
+        }
 
+        let Ok(executor) = tokio::runtime::Handle::try_current() else {
+            bail!("chat needs the TUI event loop; ask from the chat overlay (`@`)");
+        };
         let Some(client) = self.llm_client.clone() else {
             bail!("LLM disabled -- set [llm].enabled = true and restart");
         };
@@ -735,530 +2333,2024 @@ impl micasa_tui::AppRuntime for DbRuntime<'_> {
             );
         };
 
+        let models = self.stage_models(&client)?;
         let cancel = Arc::new(AtomicBool::new(false));
-        self.chat_cancellations.insert(request_id, cancel.clone());
         let worker = ChatWorker {
             request_id,
-            client,
-            llm_extra_context: self.llm_extra_context.clone(),
+            sql_client: Self::client_with_model(&client, &models.sql),
+            summary_client: Self::client_with_model(&client, &models.summary),
+            llm_extra_context: self.chat_context(),
+            embedding_model: self.embedding_model.clone(),
             question: question.to_owned(),
             history: history.to_vec(),
-            cancel,
+            hide_private: !self.show_private,
+            cancel: cancel.clone(),
             tx,
         };
 
-        thread::spawn(move || worker.run(db_path));
+        let handle = executor.spawn_blocking(move || worker.run(db_path));
+        self.chat_tasks
+            .insert(request_id, ChatTask { cancel, handle });
         Ok(())
     }
 
-    fn cancel_chat_pipeline(&mut self, request_id: u64) -> Result<()> {
-        if let Some(cancel) = self.chat_cancellations.remove(&request_id) {
-            cancel.store(true, Ordering::Release);
-        }
-        Ok(())
+    fn lock_idle_timeout(&mut self) -> Option<std::time::Duration> {
+        self.lock_idle_timeout
     }
 
-    fn run_chat_pipeline(
-        &mut self,
-        question: &str,
-        history: &[ChatHistoryMessage],
-    ) -> Result<ChatPipelineResult> {
-        let trimmed_question = question.trim();
-        if trimmed_question.is_empty() {
-            bail!("question is empty; enter a prompt and retry");
-        }
+    fn startup_focus(&mut self) -> Option<DeepLink> {
+        self.startup_focus.take()
+    }
 
-        let Some(client) = self.llm_client.as_ref() else {
-            bail!("LLM disabled -- set [llm].enabled = true and restart");
-        };
+    fn keymap(&self) -> Keymap {
+        self.keymap.clone()
+    }
 
-        let now = OffsetDateTime::now_utc();
-        let tables = self.build_table_info();
-        let column_hints = self.store.column_hints();
-        let sql_prompt = build_sql_prompt(
-            &tables,
-            now,
-            if column_hints.is_empty() {
-                None
-            } else {
-                Some(column_hints.as_str())
-            },
-            self.llm_extra_context(),
-        );
+    fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
 
-        let mut sql_messages = Vec::with_capacity(history.len() + 2);
-        sql_messages.push(LlmMessage {
-            role: LlmRole::System,
-            content: sql_prompt,
-        });
-        sql_messages.extend(Self::build_history_messages(history));
-        sql_messages.push(LlmMessage {
-            role: LlmRole::User,
-            content: trimmed_question.to_owned(),
-        });
+    fn themes(&self) -> Vec<Theme> {
+        self.themes.clone()
+    }
 
-        let raw_sql = Self::stream_chat_complete(client, &sql_messages).context(
-            "SQL generation failed; verify the selected model is available and LLM server is reachable",
-        )?;
-        let sql_raw = extract_sql(&raw_sql);
-        if sql_raw.is_empty() {
-            return self
-                .run_fallback_pipeline(client, trimmed_question, history, &tables, now)
-                .context("LLM returned empty SQL and fallback query failed");
+    fn set_theme_preference(&mut self, name: &str) -> Result<()> {
+        let Some(theme) = self.themes.iter().find(|theme| theme.name == name) else {
+            bail!("unknown theme `{name}`; pick one from Settings");
+        };
+        self.store.put_theme(name)?;
+        self.theme = theme.clone();
+        Ok(())
+    }
+
+    fn lock_requires_passphrase(&mut self) -> bool {
+        self.lock_passphrase_hash.is_some()
+    }
+
+    fn verify_lock_passphrase(&mut self, passphrase: &str) -> Result<bool> {
+        match &self.lock_passphrase_hash {
+            Some(hash) => micasa_db::crypto::verify_passphrase(passphrase, hash),
+            None => Ok(true),
         }
-        let sql = format_sql(&sql_raw, 96);
+    }
 
-        let (columns, rows) = match self.store.read_only_query(&sql_raw) {
-            Ok(output) => output,
-            Err(_) => {
-                return self
-                    .run_fallback_pipeline(client, trimmed_question, history, &tables, now)
-                    .context("generated SQL could not be executed and fallback query failed");
-            }
-        };
+    fn documents_locked(&mut self) -> bool {
+        // An unreadable key record locks too; unlocking then shows why.
+        self.store.documents_locked().unwrap_or(true)
+    }
 
-        let results_table = format_results_table(&columns, &rows);
-        let summary_prompt = build_summary_prompt(
-            trimmed_question,
-            &sql,
-            &results_table,
-            now,
-            self.llm_extra_context(),
-        );
+    fn unlock_documents(&mut self, passphrase: &str) -> Result<bool> {
+        self.store.unlock_documents(passphrase)
+    }
 
-        let summary_messages = vec![
-            LlmMessage {
-                role: LlmRole::System,
-                content: summary_prompt,
-            },
-            LlmMessage {
-                role: LlmRole::User,
-                content: "Summarize these results.".to_owned(),
-            },
-        ];
-        let answer = Self::stream_chat_complete(client, &summary_messages).context(
-            "result summarization failed; retry with a smaller question or switch to another model",
-        )?;
+    fn toggle_row_private(&mut self, tab: TabKind, row_id: i64) -> Result<bool> {
+        let target = Self::private_target(tab, row_id)?;
+        let private = !self.store.is_row_private(target)?;
+        self.store.set_row_private(target, private)?;
+        self.refresh_calendar();
+        Ok(private)
+    }
 
-        Ok(ChatPipelineResult {
-            answer,
-            sql: Some(sql),
-            used_fallback: false,
-        })
+    fn set_private_rows_visible(&mut self, visible: bool) -> Result<()> {
+        self.show_private = visible;
+        Ok(())
     }
-}
 
-struct ChatWorker {
-    request_id: u64,
-    client: LlmClient,
-    llm_extra_context: String,
-    question: String,
-    history: Vec<ChatHistoryMessage>,
-    cancel: Arc<AtomicBool>,
-    tx: Sender<InternalEvent>,
-}
+    fn private_row_ids(&mut self, tab: TabKind) -> Result<BTreeSet<i64>> {
+        match Self::private_entity(tab) {
+            Some(entity) => self.store.private_row_ids(entity),
+            None => Ok(BTreeSet::new()),
+        }
+    }
 
-impl ChatWorker {
-    fn send(&self, event: ChatPipelineEvent) -> bool {
-        self.tx.send(InternalEvent::ChatPipeline(event)).is_ok()
+    fn tags_for(&mut self, tab: TabKind) -> Result<BTreeMap<i64, Vec<String>>> {
+        match Self::tag_entity(tab) {
+            Some(entity) => self.store.tags_by_row(entity),
+            None => Ok(BTreeMap::new()),
+        }
     }
 
-    fn extra_context(&self) -> Option<&str> {
-        let trimmed = self.llm_extra_context.trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed)
+    fn set_tags(&mut self, tab: TabKind, row_id: i64, tags: &[String]) -> Result<()> {
+        if Self::tag_entity(tab).is_none() {
+            bail!(
+                "{} rows can't be tagged -- tag projects, incidents, appliances, vendors or documents",
+                tab.label()
+            );
         }
+        self.store
+            .set_tags(Self::private_target(tab, row_id)?, tags)
     }
 
-    fn is_canceled(&self) -> bool {
-        self.cancel.load(Ordering::Acquire)
+    fn toggle_row_archived(&mut self, tab: TabKind, row_id: i64) -> Result<bool> {
+        let target = Self::archive_target(tab, row_id)?;
+        let archived = !self.store.is_row_archived(target)?;
+        self.store.set_row_archived(target, archived)?;
+        Ok(archived)
     }
 
-    fn run_fallback(&self, store: &Store, tables: &[TableInfo], now: OffsetDateTime) -> Result<()> {
-        if self.is_canceled() {
-            return Ok(());
+    fn archive_settled_projects(&mut self) -> Result<usize> {
+        self.store.archive_settled_projects()
+    }
+
+    fn open_url(&mut self, url: &str) -> Result<()> {
+        open_in_browser(url)
+    }
+
+    fn set_archived_rows_visible(&mut self, visible: bool) -> Result<()> {
+        self.show_archived = visible;
+        Ok(())
+    }
+
+    fn archived_row_ids(&mut self, tab: TabKind) -> Result<BTreeSet<i64>> {
+        match Self::private_entity(tab) {
+            Some(entity) => self.store.archived_row_ids(entity),
+            None => Ok(BTreeSet::new()),
         }
-        if !self.send(ChatPipelineEvent::FallbackStarted {
-            request_id: self.request_id,
-        }) {
-            return Ok(());
+    }
+
+    fn take_budget_alert(&mut self) -> Option<String> {
+        self.pending_budget_alert
+            .take()
+            .or_else(|| self.calendar_error.take())
+            .or_else(|| self.extraction_error.take())
+            .or_else(|| self.index_error.take())
+    }
+
+    fn adopt_observed_interval(&mut self, tab: TabKind, row_id: i64) -> Result<Option<i32>> {
+        if tab != TabKind::Maintenance {
+            bail!(
+                "tab {} has no service interval; switch to maintenance",
+                tab.label()
+            );
         }
+        let maintenance_id = micasa_app::MaintenanceItemId::new(row_id);
+        let Some(months) = self
+            .visible_service_dates()?
+            .get(&row_id)
+            .and_then(|dates| observed_interval_months(dates))
+        else {
+            return Ok(None);
+        };
+        self.store
+            .set_maintenance_interval(maintenance_id, months)?;
+        self.refresh_calendar();
+        Ok(Some(months))
+    }
 
-        let data_dump = store.data_dump();
-        let fallback_prompt = build_fallback_prompt(
-            tables,
-            if data_dump.is_empty() {
-                "(no rows)\n"
-            } else {
-                &data_dump
-            },
-            now,
-            self.extra_context(),
-        );
-        let mut fallback_messages = Vec::with_capacity(self.history.len() + 2);
-        fallback_messages.push(LlmMessage {
-            role: LlmRole::System,
-            content: fallback_prompt,
-        });
-        fallback_messages.extend(DbRuntime::build_history_messages(&self.history));
-        fallback_messages.push(LlmMessage {
-            role: LlmRole::User,
-            content: self.question.trim().to_owned(),
-        });
+    fn cost_split_spec(&mut self, tab: TabKind, row_id: i64) -> Result<String> {
+        let target = Self::cost_split_target(tab, row_id)?;
+        let splits = self.store.list_cost_splits(target)?;
+        if splits.is_empty() {
+            return Ok(String::new());
+        }
+        let categories = self.store.list_maintenance_categories()?;
+        Ok(splits
+            .into_iter()
+            .map(|split| {
+                let name = categories
+                    .iter()
+                    .find(|category| category.id == split.category_id)
+                    .map_or_else(
+                        || format!("category {}", split.category_id.get()),
+                        |category| category.name.clone(),
+                    );
+                format!(
+                    "{name}={}",
+                    format_cents(split.amount_cents).trim_start_matches('$')
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
 
-        let answer = DbRuntime::stream_chat_with_events(
-            &self.client,
-            &fallback_messages,
-            self.cancel.as_ref(),
-            |chunk| {
-                self.send(ChatPipelineEvent::AnswerChunk {
-                    request_id: self.request_id,
-                    chunk,
+    fn set_cost_splits(&mut self, tab: TabKind, row_id: i64, spec: &str) -> Result<usize> {
+        let target = Self::cost_split_target(tab, row_id)?;
+        let total_cents = self.store.cost_split_total_cents(target)?;
+        let shares = parse_cost_split_spec(spec, total_cents)?;
+        let categories = self.store.list_maintenance_categories()?;
+        let splits = shares
+            .into_iter()
+            .map(|share| {
+                let category = categories
+                    .iter()
+                    .find(|category| category.name.eq_ignore_ascii_case(&share.category))
+                    .with_context(|| {
+                        format!(
+                            "unknown category `{}`; use one of: {}",
+                            share.category,
+                            categories
+                                .iter()
+                                .map(|category| category.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })?;
+                Ok(CostSplit {
+                    category_id: category.id,
+                    amount_cents: share.amount_cents,
                 })
-            },
-        )
-        .context(
-            "fallback response failed; verify the LLM server is reachable and selected model exists",
-        )?;
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.store.set_cost_splits(target, &splits)?;
+        Ok(splits.len())
+    }
 
-        if self.is_canceled() {
-            return Ok(());
+    fn money_currencies(&mut self, tab: TabKind) -> Result<BTreeMap<(i64, String), String>> {
+        match Self::private_entity(tab) {
+            Some(entity) => self.store.money_currencies(entity),
+            None => Ok(BTreeMap::new()),
         }
-        let _ = self.send(ChatPipelineEvent::Completed {
-            request_id: self.request_id,
-            result: ChatPipelineResult {
-                answer,
-                sql: None,
-                used_fallback: true,
-            },
-        });
-        Ok(())
     }
 
-    fn run(self, db_path: PathBuf) {
-        let result = (|| -> Result<()> {
-            let trimmed_question = self.question.trim();
-            if trimmed_question.is_empty() {
-                bail!("question is empty; enter a prompt and retry");
+    fn set_money_currency(
+        &mut self,
+        tab: TabKind,
+        row_id: i64,
+        field: &str,
+        code: &str,
+    ) -> Result<Option<String>> {
+        let target = Self::lifecycle_target(tab, row_id)?;
+        let code = code.trim();
+        let code = if code.is_empty() {
+            None
+        } else {
+            Some(parse_currency_code(code)?).filter(|code| *code != self.home_currency)
+        };
+        self.store
+            .set_money_currency(target, field, code.as_deref())?;
+        Ok(code)
+    }
+
+    fn update_field(&mut self, tab: TabKind, row_id: i64, field: &str, value: &str) -> Result<()> {
+        let target = match tab {
+            TabKind::House | TabKind::Dashboard | TabKind::Schedule | TabKind::Settings => bail!(
+                "tab {} has no inline-editable cells; open the full form instead",
+                tab.label()
+            ),
+            _ => Self::private_target(tab, row_id)?,
+        };
+        let before = self.store.field_text(target, field)?;
+        self.store.update_field(target, field, value)?;
+        let after = self.store.field_text(target, field)?;
+        if after != before {
+            self.record_mutation(MutationRecord::FieldUpdated {
+                target,
+                field: field.to_owned(),
+                before,
+                after,
+            })?;
+            self.refresh_calendar();
+            if let LifecycleEntityRef::Document(id) = target {
+                self.index_document(id);
             }
+        }
+        Ok(())
+    }
 
-            let store = Store::open(&db_path)
-                .with_context(|| format!("open database {} for chat worker", db_path.display()))?;
+    fn document_data(&mut self, document_id: DocumentId) -> Result<Vec<u8>> {
+        Ok(self.store.get_document(document_id)?.data)
+    }
 
-            let now = OffsetDateTime::now_utc();
-            let tables = DbRuntime::build_table_info_from_store(&store);
-            let column_hints = store.column_hints();
-            let sql_prompt = build_sql_prompt(
-                &tables,
-                now,
-                if column_hints.is_empty() {
-                    None
-                } else {
-                    Some(column_hints.as_str())
-                },
-                self.extra_context(),
-            );
+    fn run_query(&mut self, sql: &str, page: usize) -> Result<QueryPage> {
+        if self.show_private {
+            return self.store.read_only_query_page(sql, page, QUERY_PAGE_ROWS);
+        }
+        self.store.shadow_private_rows()?;
+        let result = self.store.read_only_query_page(sql, page, QUERY_PAGE_ROWS);
+        self.store.clear_private_row_shadows()?;
+        result
+    }
 
-            let mut sql_messages = Vec::with_capacity(self.history.len() + 2);
-            sql_messages.push(LlmMessage {
-                role: LlmRole::System,
-                content: sql_prompt,
-            });
-            sql_messages.extend(DbRuntime::build_history_messages(&self.history));
-            sql_messages.push(LlmMessage {
-                role: LlmRole::User,
-                content: trimmed_question.to_owned(),
-            });
+    fn active_house(&mut self) -> Result<Option<String>> {
+        self.store.active_house_name()
+    }
 
-            let raw_sql = DbRuntime::stream_chat_with_events(
-                &self.client,
-                &sql_messages,
-                self.cancel.as_ref(),
-                |chunk| {
-                    self.send(ChatPipelineEvent::SqlChunk {
-                        request_id: self.request_id,
-                        chunk,
-                    })
-                },
-            )
-            .context(
-                "SQL generation failed; verify the selected model is available and LLM server is reachable",
-            )?;
+    fn export_table(&mut self, table: &TableExport) -> Result<PathBuf> {
+        let mut table = table.clone();
+        self.fill_export_counts(&mut table)?;
+        if table.resolve_names {
+            self.resolve_export_names(&mut table)?;
+        }
+        let path = self
+            .export_dir()?
+            .join(table.file_name(OffsetDateTime::now_utc()));
+        std::fs::write(&path, table.to_csv()).with_context(|| {
+            format!("write {}; check the directory is writable", path.display())
+        })?;
+        Ok(path)
+    }
 
-            if self.is_canceled() {
-                return Ok(());
-            }
-            let sql_raw = extract_sql(&raw_sql);
-            if sql_raw.is_empty() {
-                return self
-                    .run_fallback(&store, &tables, now)
-                    .context("LLM returned empty SQL and fallback query failed");
-            }
-            let sql = format_sql(&sql_raw, 96);
+    fn list_reports(&mut self) -> Result<Vec<ReportSummary>> {
+        Ok(self.reports.iter().map(ReportDef::summary).collect())
+    }
 
-            if !self.send(ChatPipelineEvent::SqlReady {
-                request_id: self.request_id,
-                sql: sql.clone(),
-            }) {
-                return Ok(());
+    fn run_report(&mut self, name: &str) -> Result<ReportOutput> {
+        let report = report::find_report(&self.reports, name)?;
+        let (columns, rows) = report::run_report(self.store, report, self.show_private)?;
+        if report.format == ReportFormat::Text {
+            return Ok(ReportOutput::Lines(report::report_text_lines(
+                &report.title,
+                &columns,
+                &rows,
+            )));
+        }
+        let path = self
+            .export_dir()?
+            .join(report::report_file_name(report, OffsetDateTime::now_utc()));
+        std::fs::write(&path, report::render_report(report, &columns, &rows)).with_context(
+            || format!("write {}; check the directory is writable", path.display()),
+        )?;
+        Ok(ReportOutput::File(path))
+    }
+
+    fn list_plugin_tabs(&mut self) -> Result<Vec<PluginTab>> {
+        if self.plugins.is_empty() {
+            return Ok(Vec::new());
+        }
+        let data = self.plugin_data()?;
+        let mut tabs = Vec::new();
+        for plugin in &self.plugins {
+            tabs.extend(plugin::describe(plugin, &data)?);
+        }
+        Ok(tabs)
+    }
+
+    fn load_plugin_rows(&mut self, tab: &PluginTab) -> Result<PluginRows> {
+        plugin::load_rows(self.plugin_for(tab)?, &tab.name, &self.plugin_data()?)
+    }
+
+    fn run_plugin_action(&mut self, tab: &PluginTab, action: &str, row_id: &str) -> Result<String> {
+        plugin::run_action(
+            self.plugin_for(tab)?,
+            &tab.name,
+            action,
+            row_id,
+            &self.plugin_data()?,
+        )
+    }
+
+    fn export_all(&mut self) -> Result<PathBuf> {
+        let path = self
+            .export_dir()?
+            .join(db_io::export_file_name(OffsetDateTime::now_utc()));
+        db_io::export_database_to_path(self.store, &path, self.show_private)?;
+        Ok(path)
+    }
+
+    fn latest_export(&mut self) -> Result<Option<PathBuf>> {
+        db_io::latest_export_in(&self.export_dir()?)
+    }
+
+    fn import_all(&mut self, path: &std::path::Path) -> Result<usize> {
+        let rows = db_io::import_database_from_path(self.store, path)?;
+        // Recorded edits point at rows the import just replaced.
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.refresh_calendar();
+        Ok(rows)
+    }
+
+    fn preview_csv_import(
+        &mut self,
+        path: &std::path::Path,
+        tab: TabKind,
+    ) -> Result<micasa_app::CsvImportPreview> {
+        csv_import::preview(path, tab)
+    }
+
+    fn import_csv(
+        &mut self,
+        path: &std::path::Path,
+        tab: TabKind,
+        mapping: &[Option<usize>],
+    ) -> Result<micasa_app::CsvImportReport> {
+        let mut report = micasa_app::CsvImportReport::default();
+        for (line, payload) in csv_import::payloads(path, tab, mapping)? {
+            // Saved like the add form, so each row is validated and undoable.
+            match payload.and_then(|payload| self.submit_form(&payload)) {
+                Ok(()) => report.imported += 1,
+                Err(error) => report.failures.push(micasa_app::CsvImportFailure {
+                    line,
+                    message: format!("{error:#}"),
+                }),
             }
+        }
+        Ok(report)
+    }
 
-            let (columns, rows) = match store.read_only_query(&sql_raw) {
-                Ok(output) => output,
-                Err(_) => {
-                    return self
-                        .run_fallback(&store, &tables, now)
-                        .context("generated SQL could not be executed and fallback query failed");
-                }
-            };
+    fn list_directory(&mut self, dir: &std::path::Path) -> Result<Vec<FileEntry>> {
+        let read_dir = std::fs::read_dir(dir).with_context(|| {
+            format!(
+                "read directory {}; check it exists and is readable",
+                dir.display()
+            )
+        })?;
+        let mut entries = Vec::new();
+        for entry in read_dir {
+            let entry = entry.with_context(|| format!("read directory {}", dir.display()))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            // Follows symlinks so a linked directory opens like a real one.
+            let is_dir = entry.path().is_dir();
+            entries.push(FileEntry { name, is_dir });
+        }
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
 
-            let results_table = format_results_table(&columns, &rows);
-            let summary_prompt = build_summary_prompt(
-                trimmed_question,
-                &sql,
-                &results_table,
-                now,
-                self.extra_context(),
+    fn attach_document_file(
+        &mut self,
+        input: &mut DocumentFormInput,
+        path: &std::path::Path,
+    ) -> Result<()> {
+        let data = std::fs::read(path)
+            .with_context(|| format!("read {}; check the file is readable", path.display()))?;
+        if data.is_empty() {
+            bail!("{} is empty -- pick a file with content", path.display());
+        }
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .with_context(|| format!("{} has no file name", path.display()))?;
+        if input.title.trim().is_empty() {
+            input.title = path.file_stem().map_or_else(
+                || file_name.clone(),
+                |stem| stem.to_string_lossy().into_owned(),
             );
-            let summary_messages = vec![
-                LlmMessage {
-                    role: LlmRole::System,
-                    content: summary_prompt,
-                },
-                LlmMessage {
-                    role: LlmRole::User,
-                    content: "Summarize these results.".to_owned(),
-                },
-            ];
+        }
+        input.mime_type = mime_type_for(&file_name).to_owned();
+        input.checksum_sha256 = sha256_hex(&data);
+        input.file_name = file_name;
+        input.data = data;
+        Ok(())
+    }
 
-            let answer = DbRuntime::stream_chat_with_events(
-                &self.client,
-                &summary_messages,
-                self.cancel.as_ref(),
-                |chunk| {
-                    self.send(ChatPipelineEvent::AnswerChunk {
-                        request_id: self.request_id,
-                        chunk,
-                    })
-                },
-            )
-            .context(
-                "result summarization failed; retry with a smaller question or switch to another model",
-            )?;
+    fn document_link_targets(&mut self, kind: DocumentEntityKind) -> Result<Vec<(i64, String)>> {
+        self.store.list_link_targets(kind)
+    }
 
-            if self.is_canceled() {
-                return Ok(());
-            }
-            let _ = self.send(ChatPipelineEvent::Completed {
-                request_id: self.request_id,
-                result: ChatPipelineResult {
-                    answer,
-                    sql: Some(sql),
-                    used_fallback: false,
-                },
-            });
-            Ok(())
-        })();
+    fn relink_documents(
+        &mut self,
+        document_ids: &[i64],
+        kind: DocumentEntityKind,
+        entity_id: i64,
+    ) -> Result<()> {
+        let after = match kind {
+            DocumentEntityKind::None => (kind, 0),
+            _ => (kind, entity_id),
+        };
+        let mut records = document_ids
+            .iter()
+            .map(|id| {
+                let document = DocumentId::new(*id);
+                Ok(MutationRecord::Relinked {
+                    document,
+                    before: self.store.document_link(document)?,
+                    after,
+                })
+            })
+            .filter(|record| {
+                !matches!(record, Ok(MutationRecord::Relinked { before, after, .. }) if before == after)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.apply_batch(&records)?;
+        let record = match records.len() {
+            0 => return Ok(()),
+            1 => records.remove(0),
+            _ => MutationRecord::Batch(records),
+        };
+        self.record_mutation(record)
+    }
 
-        let was_canceled = self.is_canceled();
-        if let Err(error) = result
-            && !was_canceled
-        {
-            let _ = self.send(ChatPipelineEvent::Failed {
-                request_id: self.request_id,
-                error: error.to_string(),
-            });
+    fn lookup_entries(&mut self, kind: LookupKind) -> Result<Vec<LookupEntry>> {
+        self.store.list_lookup_entries(kind)
+    }
+
+    fn create_lookup_entry(&mut self, kind: LookupKind, name: &str) -> Result<i64> {
+        self.store.create_lookup_entry(kind, name)
+    }
+
+    fn rename_lookup_entry(&mut self, kind: LookupKind, id: i64, name: &str) -> Result<()> {
+        self.store.rename_lookup_entry(kind, id, name)
+    }
+
+    fn delete_lookup_entry(&mut self, kind: LookupKind, id: i64) -> Result<()> {
+        self.store.delete_lookup_entry(kind, id)
+    }
+
+    fn merge_lookup_entries(&mut self, kind: LookupKind, from: i64, into: i64) -> Result<usize> {
+        let moved = self.store.merge_lookup_entries(kind, from, into)?;
+        // Recorded edits may still point at the entry the merge removed.
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        Ok(moved)
+    }
+
+    fn save_view(&mut self, tab: TabKind, view: &SavedView) -> Result<()> {
+        self.store.save_view(tab, view)
+    }
+
+    fn list_views(&mut self, tab: TabKind) -> Result<Vec<String>> {
+        self.store.list_views(tab)
+    }
+
+    fn load_view(&mut self, tab: TabKind, name: &str) -> Result<Option<SavedView>> {
+        self.store.load_view(tab, name)
+    }
+
+    fn delete_view(&mut self, tab: TabKind, name: &str) -> Result<()> {
+        if !self.store.delete_view(tab, name)? {
+            bail!("view `{name}` not found -- reopen the view picker");
         }
-        self.cancel.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    fn project_tasks(&mut self, project_id: ProjectId) -> Result<Vec<Task>> {
+        self.store.list_project_tasks(project_id)
+    }
+
+    fn add_project_task(&mut self, project_id: ProjectId, title: &str) -> Result<TaskId> {
+        self.store.create_project_task(project_id, title)
+    }
+
+    fn set_project_task_done(&mut self, task_id: TaskId, done: bool) -> Result<()> {
+        self.store.set_project_task_done(task_id, done)
+    }
+
+    fn delete_project_task(&mut self, task_id: TaskId) -> Result<()> {
+        self.store.delete_project_task(task_id)
+    }
+
+    fn project_templates(&mut self) -> Result<Vec<ProjectTemplate>> {
+        self.store.list_project_templates()
+    }
+
+    fn create_project_from_template(&mut self, name: &str) -> Result<ProjectId> {
+        let id = self.store.create_project_from_template(name)?;
+        self.record_mutation(MutationRecord::Created(LifecycleEntityRef::Project(id)))?;
+        Ok(id)
+    }
+
+    fn save_project_template(&mut self, name: &str, project_id: ProjectId) -> Result<usize> {
+        self.store.save_project_template(name, project_id)
     }
-}
 
-fn add_months_clamped(date: Date, months: i32) -> Option<Date> {
-    if months <= 0 {
-        return None;
+    fn delete_project_template(&mut self, name: &str) -> Result<()> {
+        if !self.store.delete_project_template(name)? {
+            bail!(
+                "template `{name}` is built in or already gone -- only saved templates can be deleted"
+            );
+        }
+        Ok(())
     }
 
-    let base_month = i32::from(date.month() as u8);
-    let total_month = base_month - 1 + months;
-    let year = date.year() + total_month.div_euclid(12);
-    let month_number = (total_month.rem_euclid(12) + 1) as u8;
-    let month = Month::try_from(month_number).ok()?;
+    fn cancel_chat_pipeline(&mut self, request_id: u64) -> Result<()> {
+        if let Some(task) = self.chat_tasks.remove(&request_id) {
+            task.cancel.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
 
-    let day = date.day();
-    let max_day = last_day_of_month(year, month)?;
-    let clamped_day = day.min(max_day);
-    Date::from_calendar_date(year, month, clamped_day).ok()
+    fn run_chat_pipeline(
+        &mut self,
+        question: &str,
+        history: &[ChatHistoryMessage],
+    ) -> Result<ChatPipelineResult> {
+        if self.show_private {
+            return self.answer_chat_question(question, history);
+        }
+        self.store.shadow_private_rows()?;
+        let result = self.answer_chat_question(question, history);
+        self.store.clear_private_row_shadows()?;
+        result
+    }
 }
 
-fn last_day_of_month(year: i32, month: Month) -> Option<u8> {
-    let (next_year, next_month) = if month == Month::December {
-        (year + 1, Month::January)
-    } else {
-        let next = Month::try_from((month as u8) + 1).ok()?;
-        (year, next)
-    };
+/// The real plan as it stood when the what-if sandbox opened.
+struct WhatIfBaseline {
+    spend: BTreeMap<BudgetCategory, i64>,
+    /// Edit history from before the sandbox; sandbox edits vanish on
+    /// discard, so their records go with them.
+    undo_stack: Vec<MutationRecord>,
+    redo_stack: Vec<MutationRecord>,
+}
 
-    let first_next_month = Date::from_calendar_date(next_year, next_month, 1).ok()?;
-    let last = first_next_month - Duration::days(1);
-    Some(last.day())
+/// A chat pipeline on the blocking pool. The worker checks `cancel` between
+/// chunks and sends nothing once it is set.
+struct ChatTask {
+    cancel: Arc<AtomicBool>,
+    handle: JoinHandle<()>,
 }
 
-fn days_from_to(from: Date, to: Date) -> i64 {
-    i64::from(to.to_julian_day() - from.to_julian_day())
+struct ChatWorker {
+    request_id: u64,
+    sql_client: LlmClient,
+    /// Writes the answer and the fallback answer.
+    summary_client: LlmClient,
+    llm_extra_context: String,
+    embedding_model: Option<String>,
+    question: String,
+    history: Vec<ChatHistoryMessage>,
+    hide_private: bool,
+    cancel: Arc<AtomicBool>,
+    tx: UnboundedSender<InternalEvent>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::DbRuntime;
-    use anyhow::{Result, anyhow};
-    use micasa_app::{
-        FormPayload, HouseProfileFormInput, IncidentSeverity, MaintenanceItemFormInput,
-        ProjectFormInput, ProjectStatus, ProjectTypeId, ServiceLogEntryFormInput, SettingKey,
-        SettingValue, TabKind,
-    };
-    use micasa_db::{NewMaintenanceItem, NewProject, Store};
-    use micasa_llm::{Client as LlmClient, Message as LlmMessage, Role as LlmRole};
-    use micasa_tui::{
-        AppRuntime, ChatHistoryMessage, ChatHistoryRole, LifecycleAction, TabSnapshot,
-    };
-    use std::sync::atomic::{AtomicBool, Ordering};
-    use std::thread;
-    use std::time::Duration;
-    use time::{Date, Duration as TimeDuration, Month, OffsetDateTime};
-    use tiny_http::{Header, Response, Server};
+impl ChatWorker {
+    fn send(&self, event: ChatPipelineEvent) -> bool {
+        !self.is_canceled() && self.tx.send(InternalEvent::ChatPipeline(event)).is_ok()
+    }
 
-    fn house_form_input_with_insurance(
-        carrier: &str,
-        renewal: Option<Date>,
-    ) -> HouseProfileFormInput {
-        HouseProfileFormInput {
-            nickname: "Elm Street".to_owned(),
-            address_line_1: "123 Elm".to_owned(),
-            address_line_2: String::new(),
-            city: "Springfield".to_owned(),
-            state: "IL".to_owned(),
-            postal_code: "62701".to_owned(),
-            year_built: Some(1987),
-            square_feet: Some(2400),
-            lot_square_feet: None,
-            bedrooms: Some(4),
-            bathrooms: Some(2.5),
-            foundation_type: String::new(),
-            wiring_type: String::new(),
-            roof_type: String::new(),
-            exterior_type: String::new(),
-            heating_type: String::new(),
-            cooling_type: String::new(),
-            water_source: String::new(),
-            sewer_type: String::new(),
-            parking_type: String::new(),
-            basement_type: String::new(),
-            insurance_carrier: carrier.to_owned(),
-            insurance_policy: String::new(),
-            insurance_renewal: renewal,
-            property_tax_cents: None,
-            hoa_name: String::new(),
-            hoa_fee_cents: None,
+    fn extra_context(&self) -> Option<&str> {
+        let trimmed = self.llm_extra_context.trim();
+        if trimmed.is_empty() {
+            None
+        } else {
+            Some(trimmed)
+        }
+    }
+
+    fn is_canceled(&self) -> bool {
+        self.cancel.load(Ordering::Acquire)
+    }
+
+    fn document_excerpts(&self, store: &Store) -> (String, Option<String>) {
+        match &self.embedding_model {
+            Some(model) => doc_index::excerpts_or_notice(doc_index::document_excerpts(
+                store,
+                &self.summary_client,
+                model,
+                &self.question,
+            )),
+            None => (String::new(), None),
+        }
+    }
+
+    fn run_fallback(&self, store: &Store, tables: &[TableInfo], now: OffsetDateTime) -> Result<()> {
+        if self.is_canceled() {
+            return Ok(());
+        }
+        if !self.send(ChatPipelineEvent::FallbackStarted {
+            request_id: self.request_id,
+        }) {
+            return Ok(());
         }
+
+        let data_dump = store.data_dump();
+        let mut fallback_prompt = build_fallback_prompt(
+            tables,
+            if data_dump.is_empty() {
+                "(no rows)\n"
+            } else {
+                &data_dump
+            },
+            now,
+            self.extra_context(),
+        );
+        let (excerpts, notice) = self.document_excerpts(store);
+        fallback_prompt.push_str(&excerpts);
+        let mut fallback_messages = Vec::with_capacity(self.history.len() + 2);
+        fallback_messages.push(LlmMessage {
+            role: LlmRole::System,
+            content: fallback_prompt,
+        });
+        fallback_messages.extend(DbRuntime::build_history_messages(&self.history));
+        fallback_messages.push(LlmMessage {
+            role: LlmRole::User,
+            content: self.question.trim().to_owned(),
+        });
+
+        let answer = DbRuntime::stream_chat_with_events(
+            &self.summary_client,
+            &fallback_messages,
+            &self.cancel,
+            |chunk| {
+                self.send(ChatPipelineEvent::AnswerChunk {
+                    request_id: self.request_id,
+                    chunk,
+                })
+            },
+        )
+        .context(
+            "fallback response failed; verify the LLM server is reachable and selected model exists",
+        )?;
+
+        if self.is_canceled() {
+            return Ok(());
+        }
+        let _ = self.send(ChatPipelineEvent::Completed {
+            request_id: self.request_id,
+            result: ChatPipelineResult {
+                answer,
+                sql: None,
+                used_fallback: true,
+                notice,
+            },
+        });
+        Ok(())
+    }
+
+    fn run(self, db_path: PathBuf) {
+        let result = (|| -> Result<()> {
+            let trimmed_question = self.question.trim();
+            if trimmed_question.is_empty() {
+                bail!("question is empty; enter a prompt and retry");
+            }
+
+            let store = Store::open(&db_path)
+                .with_context(|| format!("open database {} for chat worker", db_path.display()))?;
+            if self.hide_private {
+                store.shadow_private_rows()?;
+            }
+
+            let now = OffsetDateTime::now_utc();
+            let tables = DbRuntime::build_table_info_from_store(&store);
+            let column_hints = store.column_hints();
+            let sql_prompt = build_sql_prompt(
+                &tables,
+                now,
+                if column_hints.is_empty() {
+                    None
+                } else {
+                    Some(column_hints.as_str())
+                },
+                self.extra_context(),
+            );
+
+            let mut sql_messages = Vec::with_capacity(self.history.len() + 2);
+            sql_messages.push(LlmMessage {
+                role: LlmRole::System,
+                content: sql_prompt,
+            });
+            sql_messages.extend(DbRuntime::build_history_messages(&self.history));
+            sql_messages.push(LlmMessage {
+                role: LlmRole::User,
+                content: trimmed_question.to_owned(),
+            });
+
+            let raw_sql = DbRuntime::stream_chat_with_events(
+                &self.sql_client,
+                &sql_messages,
+                &self.cancel,
+                |chunk| {
+                    self.send(ChatPipelineEvent::SqlChunk {
+                        request_id: self.request_id,
+                        chunk,
+                    })
+                },
+            )
+            .context(
+                "SQL generation failed; verify the selected model is available and LLM server is reachable",
+            )?;
+
+            if self.is_canceled() {
+                return Ok(());
+            }
+            let sql_raw = extract_sql(&raw_sql);
+            if sql_raw.is_empty() {
+                return self
+                    .run_fallback(&store, &tables, now)
+                    .context("LLM returned empty SQL and fallback query failed");
+            }
+            let sql = format_sql(&sql_raw, 96);
+
+            if !self.send(ChatPipelineEvent::SqlReady {
+                request_id: self.request_id,
+                sql: sql.clone(),
+            }) {
+                return Ok(());
+            }
+
+            let (columns, rows) = match store.read_only_query(&sql_raw) {
+                Ok(output) => output,
+                Err(_) => {
+                    return self
+                        .run_fallback(&store, &tables, now)
+                        .context("generated SQL could not be executed and fallback query failed");
+                }
+            };
+
+            let results_table = format_results_table(&columns, &rows);
+            let mut summary_prompt = build_summary_prompt(
+                trimmed_question,
+                &sql,
+                &results_table,
+                now,
+                self.extra_context(),
+            );
+            let (excerpts, notice) = self.document_excerpts(&store);
+            summary_prompt.push_str(&excerpts);
+            let summary_messages = vec![
+                LlmMessage {
+                    role: LlmRole::System,
+                    content: summary_prompt,
+                },
+                LlmMessage {
+                    role: LlmRole::User,
+                    content: "Summarize these results.".to_owned(),
+                },
+            ];
+
+            let answer = DbRuntime::stream_chat_with_events(
+                &self.summary_client,
+                &summary_messages,
+                &self.cancel,
+                |chunk| {
+                    self.send(ChatPipelineEvent::AnswerChunk {
+                        request_id: self.request_id,
+                        chunk,
+                    })
+                },
+            )
+            .context(
+                "result summarization failed; retry with a smaller question or switch to another model",
+            )?;
+
+            if self.is_canceled() {
+                return Ok(());
+            }
+            let _ = self.send(ChatPipelineEvent::Completed {
+                request_id: self.request_id,
+                result: ChatPipelineResult {
+                    answer,
+                    sql: Some(sql),
+                    used_fallback: false,
+                    notice,
+                },
+            });
+            Ok(())
+        })();
+
+        let was_canceled = self.is_canceled();
+        if let Err(error) = result
+            && !was_canceled
+        {
+            let _ = self.send(ChatPipelineEvent::Failed {
+                request_id: self.request_id,
+                error: error.to_string(),
+            });
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(data);
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Hands a web link to the desktop's browser without waiting for it.
+fn open_in_browser(url: &str) -> Result<()> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        bail!("{url} is not a web link -- hint: manual URLs start with http:// or https://");
+    }
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .context("start the system browser")?;
+    Ok(())
+}
+
+/// MIME type guessed from a file's extension; unknown ones are stored as
+/// opaque bytes.
+fn mime_type_for(file_name: &str) -> &'static str {
+    let extension = std::path::Path::new(file_name)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "heic" => "image/heic",
+        "svg" => "image/svg+xml",
+        "txt" | "log" => "text/plain",
+        "md" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "zip" => "application/zip",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BudgetAlerts, DbRuntime, reminder_notification};
+    use crate::extract::TextExtractor;
+    use anyhow::{Result, anyhow};
+    use micasa_app::{
+        AuditSource, BudgetCategory, DataIssueKind, DocumentEntityKind, DocumentFormInput,
+        DocumentId, FormKind, FormPayload, HouseProfileFormInput, IncidentSeverity,
+        MaintenanceItemFormInput, ProjectFormInput, ProjectRollup, ProjectStatus, ProjectTypeId,
+        PurgeScope, QuoteFormInput, Reminder, ReminderKind, ServiceLogEntryFormInput, SettingKey,
+        SettingValue, TabKind,
+    };
+    use micasa_db::weather::ForecastDay;
+    use micasa_db::{
+        NewAppliance, NewBudget, NewMaintenanceItem, NewProject, NewQuote, NewServiceLogEntry,
+        NewVendor, RateSource, Store,
+    };
+    use micasa_llm::{Client as LlmClient, Message as LlmMessage, Role as LlmRole};
+    use micasa_tui::{
+        AppRuntime, ChatHistoryMessage, ChatHistoryRole, ChatPipelineEvent, ChatStageModels,
+        DashboardLayout, DashboardSection, DashboardWeatherAlert, InternalEvent, LifecycleAction,
+        TabSnapshot, TableExport,
+    };
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+    use time::{Date, Duration as TimeDuration, Month, OffsetDateTime};
+    use tiny_http::{Header, Response, Server};
+
+    fn house_form_input_with_insurance(
+        carrier: &str,
+        renewal: Option<Date>,
+    ) -> HouseProfileFormInput {
+        HouseProfileFormInput {
+            nickname: "Elm Street".to_owned(),
+            address_line_1: "123 Elm".to_owned(),
+            address_line_2: String::new(),
+            city: "Springfield".to_owned(),
+            state: "IL".to_owned(),
+            postal_code: "62701".to_owned(),
+            year_built: Some(1987),
+            square_feet: Some(2400),
+            lot_square_feet: None,
+            bedrooms: Some(4),
+            bathrooms: Some(2.5),
+            foundation_type: String::new(),
+            wiring_type: String::new(),
+            roof_type: String::new(),
+            exterior_type: String::new(),
+            heating_type: String::new(),
+            cooling_type: String::new(),
+            water_source: String::new(),
+            sewer_type: String::new(),
+            parking_type: String::new(),
+            basement_type: String::new(),
+            insurance_carrier: carrier.to_owned(),
+            insurance_policy: String::new(),
+            insurance_renewal: renewal,
+            property_tax_cents: None,
+            hoa_name: String::new(),
+            hoa_fee_cents: None,
+        }
+    }
+
+    #[test]
+    fn submit_form_creates_project_row() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::Project(ProjectFormInput {
+            title: "Deck repair".to_owned(),
+            project_type_id: ProjectTypeId::new(1),
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: Some(9_500),
+            actual_cents: None,
+            parent_id: None,
+        }))?;
+
+        let projects = store.list_projects(false)?;
+        assert_eq!(projects.len(), 1);
+        assert_eq!(projects[0].title, "Deck repair");
+        Ok(())
+    }
+
+    #[test]
+    fn calendar_feed_refreshes_after_saves_and_deletes() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let temp = tempfile::tempdir()?;
+        let feed = temp.path().join("house.ics");
+        store.put_ical_path(Some(&feed))?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::Project(ProjectFormInput {
+            title: "Deck repair".to_owned(),
+            project_type_id: ProjectTypeId::new(1),
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: Some(Date::from_calendar_date(2026, Month::November, 2)?),
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        }))?;
+        let rendered = std::fs::read_to_string(&feed)?;
+        assert!(rendered.contains("SUMMARY:Project starts: Deck repair"));
+        assert!(rendered.contains("DTSTART;VALUE=DATE:20261102"));
+
+        let project_id = store.list_projects(false)?[0].id.get();
+        runtime.apply_lifecycle(TabKind::Projects, &[project_id], LifecycleAction::Delete)?;
+        assert!(!std::fs::read_to_string(&feed)?.contains("Deck repair"));
+
+        // A feed that cannot be written surfaces after the save, not as a failure.
+        store.put_ical_path(Some(&temp.path().join("missing").join("house.ics")))?;
+        runtime.undo_last_edit()?;
+        let alert = runtime
+            .take_budget_alert()
+            .expect("refresh failure should alert");
+        assert!(alert.starts_with("calendar feed not refreshed"));
+        Ok(())
+    }
+
+    #[test]
+    fn relink_documents_is_one_undo_step_and_skips_unchanged_rows() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.seed_demo_data()?;
+        let documents = store.list_documents(false)?;
+        let ids = documents
+            .iter()
+            .take(2)
+            .map(|document| document.id.get())
+            .collect::<Vec<_>>();
+        let before = ids
+            .iter()
+            .map(|id| store.document_link(DocumentId::new(*id)))
+            .collect::<Result<Vec<_>>>()?;
+        let (vendor_id, _) = store.list_link_targets(DocumentEntityKind::Vendor)?[0].clone();
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.relink_documents(&ids, DocumentEntityKind::Vendor, vendor_id)?;
+        for id in &ids {
+            assert_eq!(
+                store.document_link(DocumentId::new(*id))?,
+                (DocumentEntityKind::Vendor, vendor_id)
+            );
+        }
+        // Already filed there: nothing to record.
+        runtime.relink_documents(&ids, DocumentEntityKind::Vendor, vendor_id)?;
+
+        assert!(runtime.undo_last_edit()?);
+        let restored = ids
+            .iter()
+            .map(|id| store.document_link(DocumentId::new(*id)))
+            .collect::<Result<Vec<_>>>()?;
+        assert_eq!(restored, before);
+        assert!(!runtime.undo_last_edit()?);
+        Ok(())
+    }
+
+    #[test]
+    fn house_profile_submit_twice_updates_existing_record() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
+            house_form_input_with_insurance("", None),
+        )))?;
+
+        let original = store
+            .get_house_profile()?
+            .expect("house profile should exist after first submit");
+
+        let mut updated = house_form_input_with_insurance("", None);
+        updated.nickname = "Lake House".to_owned();
+        updated.city = "Tahoe".to_owned();
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(updated)))?;
+
+        let current = store
+            .get_house_profile()?
+            .expect("house profile should still exist after second submit");
+        assert_eq!(current.id, original.id);
+        assert_eq!(current.nickname, "Lake House");
+        assert_eq!(current.city, "Tahoe");
+        Ok(())
+    }
+
+    #[test]
+    fn maintenance_submit_persists_interval_months_in_store_and_snapshot() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let category_id = store.list_maintenance_categories()?[0].id;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::Maintenance(MaintenanceItemFormInput {
+            name: "Gutter Cleaning".to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 30,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        }))?;
+
+        let stored_items = store.list_maintenance_items(false)?;
+        assert_eq!(stored_items.len(), 1);
+        assert_eq!(stored_items[0].interval_months, 30);
+
+        let snapshot = runtime
+            .load_tab_snapshot(TabKind::Maintenance, false)?
+            .expect("maintenance snapshot");
+        match snapshot {
+            TabSnapshot::Maintenance(rows, ..) => {
+                assert_eq!(rows.len(), 1);
+                assert_eq!(rows[0].interval_months, 30);
+            }
+            other => panic!("expected maintenance snapshot, got {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn snapshot_respects_deleted_filter() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_type_id = store.list_project_types()?[0].id;
+        let project_id = store.create_project(&NewProject {
+            title: "Window replacement".to_owned(),
+            project_type_id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        })?;
+        store.soft_delete_project(project_id)?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        let visible = runtime
+            .load_tab_snapshot(TabKind::Projects, false)?
+            .expect("projects snapshot");
+        let with_deleted = runtime
+            .load_tab_snapshot(TabKind::Projects, true)?
+            .expect("projects snapshot");
+        assert_eq!(visible.row_count(), 0);
+        assert_eq!(with_deleted.row_count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn house_snapshot_tracks_profile_presence() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        let before = runtime
+            .load_tab_snapshot(TabKind::House, false)?
+            .expect("house snapshot");
+        assert_eq!(before.row_count(), 0);
+
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
+            house_form_input_with_insurance("", None),
+        )))?;
+
+        let after = runtime
+            .load_tab_snapshot(TabKind::House, false)?
+            .expect("house snapshot");
+        assert_eq!(after.row_count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn what_if_compares_hypothetical_projects_and_discards_them() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let project_type = store.list_project_types()?[0].clone();
+        store.create_budget(&NewBudget {
+            category: BudgetCategory::ProjectType(project_type.id),
+            monthly_cents: 100_000,
+            notes: String::new(),
+        })?;
+        let today = OffsetDateTime::now_utc().date();
+        let project = |title: &str, actual_cents| ProjectFormInput {
+            title: title.to_owned(),
+            project_type_id: project_type.id,
+            status: ProjectStatus::Completed,
+            description: String::new(),
+            start_date: Some(today),
+            end_date: Some(today),
+            budget_cents: None,
+            actual_cents: Some(actual_cents),
+            parent_id: None,
+        };
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::Project(project("Deck", 300_000)))?;
+
+        assert!(runtime.what_if_comparison().is_err(), "no sandbox yet");
+        runtime.begin_what_if()?;
+        runtime.submit_form(&FormPayload::Project(project("Addition", 1_000_000)))?;
+        let lines = runtime.what_if_comparison()?;
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].category_name, project_type.name);
+        assert_eq!(lines[0].annual_budget_cents, Some(1_200_000));
+        assert_eq!(lines[0].real_cents, 300_000);
+        assert_eq!(lines[0].what_if_cents, 1_300_000);
+
+        runtime.discard_what_if()?;
+        let titles = store
+            .list_projects(false)?
+            .into_iter()
+            .map(|project| project.title)
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["Deck"]);
+        assert!(
+            runtime.undo_last_edit()?,
+            "edits from before the sandbox can still be undone"
+        );
+        assert!(store.list_projects(false)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn budget_alerts_fire_for_quotes_and_monthly_service_spend() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_id = store.create_project(&NewProject {
+            title: "Deck".to_owned(),
+            project_type_id: store.list_project_types()?[0].id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: Some(1_000_000),
+            actual_cents: None,
+            parent_id: None,
+        })?;
+        let vendor_id = store.create_vendor(&NewVendor {
+            name: "Acme Decks".to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?;
+        let maintenance_item_id = store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Furnace".to_owned(),
+            category_id: store.list_maintenance_categories()?[0].id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 12,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None)
+            .with_budget_alerts(BudgetAlerts {
+                threshold_pct: 90,
+                monthly_limit_cents: Some(50_000),
+            });
+        let quote = |total_cents| {
+            FormPayload::Quote(QuoteFormInput {
+                project_id,
+                vendor_id,
+                total_cents,
+                labor_cents: None,
+                materials_cents: None,
+                other_cents: None,
+                received_date: None,
+                notes: String::new(),
+            })
+        };
+        let service = |day, cost_cents| -> Result<FormPayload> {
+            Ok(FormPayload::ServiceLogEntry(ServiceLogEntryFormInput {
+                maintenance_item_id,
+                serviced_at: Date::from_calendar_date(2026, Month::March, day)?,
+                vendor_id: None,
+                cost_cents,
+                notes: String::new(),
+            }))
+        };
+
+        runtime.submit_form(&quote(800_000))?;
+        assert_eq!(runtime.take_budget_alert(), None);
+        runtime.submit_form(&quote(950_000))?;
+        assert_eq!(
+            runtime.take_budget_alert().as_deref(),
+            Some("Deck quote $9,500.00 is 95% of $10,000.00 budget")
+        );
+        assert_eq!(runtime.take_budget_alert(), None, "alerts are taken once");
+
+        runtime.submit_form(&service(2, Some(30_000))?)?;
+        assert_eq!(runtime.take_budget_alert(), None);
+        runtime.submit_form(&service(20, None)?)?;
+        assert_eq!(runtime.take_budget_alert(), None);
+        runtime.submit_form(&service(21, Some(20_000))?)?;
+        assert_eq!(
+            runtime.take_budget_alert().as_deref(),
+            Some("2026-03 service spend $500.00 is 100% of $500.00 monthly limit")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn attach_document_file_fills_the_form_from_a_picked_file() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let temp = tempfile::tempdir()?;
+        std::fs::create_dir(temp.path().join("scans"))?;
+        std::fs::write(temp.path().join(".hidden"), "x")?;
+        std::fs::write(temp.path().join("receipt.PDF"), "hello")?;
+        std::fs::write(temp.path().join("notes.txt"), "")?;
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+
+        let names = runtime
+            .list_directory(temp.path())?
+            .into_iter()
+            .map(|entry| (entry.name, entry.is_dir))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec![
+                ("scans".to_owned(), true),
+                ("notes.txt".to_owned(), false),
+                ("receipt.PDF".to_owned(), false),
+            ]
+        );
+
+        let Some(FormPayload::Document(mut input)) = FormPayload::blank_for(FormKind::Document)
+        else {
+            panic!("document forms have a blank payload");
+        };
+        let error = runtime
+            .attach_document_file(&mut input, &temp.path().join("notes.txt"))
+            .expect_err("empty files are refused");
+        assert!(error.to_string().contains("is empty"));
+
+        runtime.attach_document_file(&mut input, &temp.path().join("receipt.PDF"))?;
+        assert_eq!(input.title, "receipt");
+        assert_eq!(input.file_name, "receipt.PDF");
+        assert_eq!(input.mime_type, "application/pdf");
+        assert_eq!(input.data, b"hello");
+        assert_eq!(
+            input.checksum_sha256,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        runtime.submit_form(&FormPayload::Document(input))?;
+        assert_eq!(store.list_documents(false)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn saved_documents_get_their_text_extracted_when_enabled() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let document = |title: &str, file_name: &str, mime_type: &str, data: &[u8]| {
+            FormPayload::Document(DocumentFormInput {
+                title: title.to_owned(),
+                file_name: file_name.to_owned(),
+                entity_kind: DocumentEntityKind::None,
+                entity_id: 0,
+                mime_type: mime_type.to_owned(),
+                data: data.to_vec(),
+                checksum_sha256: String::new(),
+                notes: String::new(),
+            })
+        };
+
+        let mut plain = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        plain.submit_form(&document("Off", "off.txt", "text/plain", b"gas valve"))?;
+        assert_eq!(store.list_documents(false)?[0].extracted_text, "");
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None)
+            .with_text_extraction(Some(TextExtractor::new(
+                "/nonexistent/pdftotext",
+                "/nonexistent/tesseract",
+            )));
+        runtime.submit_form(&document(
+            "Water heater notes",
+            "heater.txt",
+            "text/plain",
+            b"Anode rod replaced\nGas valve model WT-8000\n",
+        ))?;
+        let hits = runtime.search_all("wt-8000")?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].tab, TabKind::Documents);
+        assert_eq!(hits[0].column, "text");
+        assert_eq!(runtime.take_budget_alert(), None);
+
+        runtime.submit_form(&document(
+            "Scan",
+            "scan.pdf",
+            "application/pdf",
+            b"%PDF-1.4",
+        ))?;
+        assert_eq!(
+            store.list_documents(false)?.len(),
+            3,
+            "the save still lands"
+        );
+        let alert = runtime
+            .take_budget_alert()
+            .expect("extraction error is shown");
+        assert!(alert.starts_with("text extraction failed"), "{alert}");
+
+        store.encrypt_documents("secret")?;
+        runtime.submit_form(&document(
+            "Sealed notes",
+            "sealed.txt",
+            "text/plain",
+            b"Safe combination 12-34-56\n",
+        ))?;
+        assert!(runtime.search_all("combination")?.is_empty());
+        assert_eq!(runtime.take_budget_alert(), None, "skipped, not failed");
+        Ok(())
+    }
+
+    #[test]
+    fn import_all_replaces_rows_from_latest_export_and_clears_undo() -> Result<()> {
+        let source = Store::open_memory()?;
+        source.bootstrap()?;
+        source.seed_demo_data()?;
+        let temp = tempfile::tempdir()?;
+        let mut exporter = DbRuntime::with_llm_client_context_and_db_path(&source, None, "", None);
+        exporter.export_dir = Some(temp.path().to_path_buf());
+        let exported = exporter.export_all()?;
+        assert!(exported.starts_with(temp.path()));
+
+        let target = Store::open_memory()?;
+        target.bootstrap()?;
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&target, None, "", None);
+        runtime.export_dir = Some(temp.path().to_path_buf());
+        runtime.submit_form(&FormPayload::Project(ProjectFormInput {
+            title: "Scratch".to_owned(),
+            project_type_id: ProjectTypeId::new(1),
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        }))?;
+        assert_eq!(runtime.latest_export()?, Some(exported.clone()));
+
+        let rows = runtime.import_all(&exported)?;
+        assert!(rows > 0);
+        assert_eq!(target.dump_tables()?, source.dump_tables()?);
+        assert!(!runtime.undo_last_edit()?);
+        Ok(())
     }
 
     #[test]
-    fn submit_form_creates_project_row() -> Result<()> {
+    fn import_csv_saves_valid_rows_and_reports_the_rest() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
+        let temp = tempfile::tempdir()?;
+        let path = temp.path().join("gear.csv");
+        std::fs::write(
+            &path,
+            "Name,Brand,Cost,Purchase Date\nFridge,Acme,\"$1,250.00\",2024-05-01\nWasher,,-5,\n,Bosch,,\n",
+        )?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        runtime.submit_form(&FormPayload::Project(ProjectFormInput {
-            title: "Deck repair".to_owned(),
-            project_type_id: ProjectTypeId::new(1),
-            status: ProjectStatus::Planned,
+        let preview = runtime.preview_csv_import(&path, TabKind::Appliances)?;
+        assert_eq!(preview.row_count, 3);
+        let report = runtime.import_csv(&path, TabKind::Appliances, &preview.mapping)?;
+        assert_eq!(report.imported, 1);
+        assert_eq!(
+            report
+                .failures
+                .iter()
+                .map(|failure| (failure.line, failure.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    3,
+                    "cost `-5`: negative money value; expected dollars like 125.50"
+                ),
+                (4, "appliance name is required -- enter a name and retry"),
+            ]
+        );
+        let appliances = store.list_appliances(false)?;
+        assert_eq!(appliances.len(), 1);
+        assert_eq!(appliances[0].cost_cents, Some(125_000));
+        assert!(runtime.undo_last_edit()?, "imported rows are undoable");
+        assert!(store.list_appliances(false)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn export_table_writes_csv_with_storage_counts_and_link_names() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let appliance_id = store.create_appliance(&NewAppliance {
+            name: "Furnace".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: None,
+            location: "Basement".to_owned(),
+            cost_cents: None,
+            notes: String::new(),
+        })?;
+        store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Replace filter".to_owned(),
+            category_id: store.list_maintenance_categories()?[0].id,
+            appliance_id: Some(appliance_id),
+            last_serviced_at: None,
+            interval_months: 3,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+
+        let temp = tempfile::tempdir()?;
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.export_dir = Some(temp.path().to_path_buf());
+        let path = runtime.export_table(&TableExport {
+            tab: TabKind::Appliances,
+            title: "appliances".to_owned(),
+            columns: vec!["id".to_owned(), "name".to_owned(), "maint".to_owned()],
+            row_ids: vec![appliance_id.get()],
+            rows: vec![vec![
+                appliance_id.get().to_string(),
+                "Furnace".to_owned(),
+                String::new(),
+            ]],
+            resolve_names: false,
+        })?;
+        assert_eq!(path.parent(), Some(temp.path()));
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            format!("id,name,maint\r\n{},Furnace,1\r\n", appliance_id.get())
+        );
+
+        let maintenance = TableExport {
+            tab: TabKind::Maintenance,
+            title: "maintenance".to_owned(),
+            columns: vec!["item".to_owned(), "appliance".to_owned()],
+            row_ids: vec![1, 2],
+            rows: vec![
+                vec!["Replace filter".to_owned(), appliance_id.get().to_string()],
+                vec!["Orphan".to_owned(), "999".to_owned()],
+            ],
+            resolve_names: true,
+        };
+        let path = runtime.export_table(&maintenance)?;
+        assert_eq!(
+            std::fs::read_to_string(&path)?,
+            "item,appliance\r\nReplace filter,Furnace\r\nOrphan,999\r\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn project_rollups_follow_quote_acceptance() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let project_id = store.create_project(&NewProject {
+            title: "Deck".to_owned(),
+            project_type_id: store.list_project_types()?[0].id,
+            status: ProjectStatus::Quoted,
             description: String::new(),
             start_date: None,
             end_date: None,
-            budget_cents: Some(9_500),
+            budget_cents: None,
             actual_cents: None,
-        }))?;
+            parent_id: None,
+        })?;
+        let vendor_id = store.create_vendor(&NewVendor {
+            name: "Deck builder".to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?;
+        let quote_id = store.create_quote(&NewQuote {
+            project_id,
+            vendor_id,
+            total_cents: 640_000,
+            labor_cents: None,
+            materials_cents: None,
+            other_cents: None,
+            received_date: None,
+            notes: String::new(),
+        })?;
 
-        let projects = store.list_projects(false)?;
-        assert_eq!(projects.len(), 1);
-        assert_eq!(projects[0].title, "Deck repair");
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert_eq!(
+            runtime.load_project_rollups()?[&project_id],
+            ProjectRollup {
+                quote_count: 1,
+                accepted_cents: None,
+                doc_count: 0,
+                ..ProjectRollup::default()
+            }
+        );
+
+        assert!(runtime.toggle_quote_accepted(quote_id.get())?);
+        assert_eq!(
+            runtime.load_project_rollups()?[&project_id].accepted_cents,
+            Some(640_000)
+        );
+        assert!(!runtime.toggle_quote_accepted(quote_id.get())?);
+        assert_eq!(
+            runtime.load_project_rollups()?[&project_id].accepted_cents,
+            None
+        );
+
+        let error = runtime
+            .toggle_quote_accepted(quote_id.get() + 100)
+            .expect_err("missing quote should fail");
+        assert!(error.to_string().contains("not found"));
         Ok(())
     }
 
     #[test]
-    fn house_profile_submit_twice_updates_existing_record() -> Result<()> {
+    fn quote_deltas_follow_acceptance_and_skip_private_rows() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
+        let project_id = store.create_project(&NewProject {
+            title: "Deck".to_owned(),
+            project_type_id: store.list_project_types()?[0].id,
+            status: ProjectStatus::Quoted,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        })?;
+        let vendor_id = store.create_vendor(&NewVendor {
+            name: "Deck builder".to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?;
+        let quote = |total_cents| NewQuote {
+            project_id,
+            vendor_id,
+            total_cents,
+            labor_cents: None,
+            materials_cents: None,
+            other_cents: None,
+            received_date: None,
+            notes: String::new(),
+        };
+        let accepted = store.create_quote(&quote(640_000))?;
+        let private = store.create_quote(&quote(710_000))?;
+        store
+            .raw_connection()
+            .execute("UPDATE quotes SET updated_at = '2025-01-01T00:00:00Z'", [])?;
+        let cursor = time::macros::datetime!(2025-06-01 00:00 UTC);
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
-            house_form_input_with_insurance("", None),
-        )))?;
+        assert!(runtime.toggle_row_private(TabKind::Quotes, private.get())?);
+        assert!(runtime.toggle_quote_accepted(accepted.get())?);
+        let delta = runtime
+            .load_rows_since(TabKind::Quotes, cursor, false)?
+            .expect("quotes patch");
+        let TabSnapshot::Quotes(rows) = &delta.changed else {
+            panic!("quote rows");
+        };
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            vec![accepted],
+            "accepting touches the quote"
+        );
+        assert_eq!(
+            delta.ids,
+            std::collections::BTreeSet::from([accepted.get()])
+        );
+        assert!(
+            runtime
+                .load_rows_since(TabKind::Projects, cursor, false)?
+                .is_none()
+        );
+        Ok(())
+    }
 
-        let original = store
-            .get_house_profile()?
-            .expect("house profile should exist after first submit");
+    #[test]
+    fn weather_alerts_follow_the_cached_forecast_when_enabled() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
 
-        let mut updated = house_form_input_with_insurance("", None);
-        updated.nickname = "Lake House".to_owned();
-        updated.city = "Tahoe".to_owned();
-        runtime.submit_form(&FormPayload::HouseProfile(Box::new(updated)))?;
+        let category_id = store.list_maintenance_categories()?[0].id;
+        let faucet_id = store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Winterize outdoor faucets".to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 12,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+        let today = OffsetDateTime::now_utc().date();
+        let day = |offset: i64, min_temp_c: f64| ForecastDay {
+            day: today + TimeDuration::days(offset),
+            min_temp_c,
+            max_temp_c: 8.0,
+            precipitation_mm: 0.0,
+            wind_kmh: 5.0,
+        };
+        store.replace_weather_forecast(&[day(0, 4.0), day(2, -5.0), day(9, -8.0)])?;
 
-        let current = store
-            .get_house_profile()?
-            .expect("house profile should still exist after second submit");
-        assert_eq!(current.id, original.id);
-        assert_eq!(current.nickname, "Lake House");
-        assert_eq!(current.city, "Tahoe");
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert!(runtime.load_dashboard_snapshot()?.weather_alerts.is_empty());
+
+        let mut runtime = runtime.with_weather_alerts(true);
+        let alerts = runtime.load_dashboard_snapshot()?.weather_alerts;
+        assert_eq!(
+            alerts,
+            vec![DashboardWeatherAlert {
+                label: "freeze warning".to_owned(),
+                advice: "winterize outdoor faucets".to_owned(),
+                days_from_now: 2,
+                items: vec![(faucet_id, "Winterize outdoor faucets".to_owned())],
+            }]
+        );
         Ok(())
     }
 
     #[test]
-    fn maintenance_submit_persists_interval_months_in_store_and_snapshot() -> Result<()> {
+    fn interval_drift_flags_history_and_adopts_observed_cadence() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
+
         let category_id = store.list_maintenance_categories()?[0].id;
+        let create = |name: &str| {
+            store.create_maintenance_item(&NewMaintenanceItem {
+                name: name.to_owned(),
+                category_id,
+                appliance_id: None,
+                last_serviced_at: None,
+                interval_months: 6,
+                manual_url: String::new(),
+                manual_text: String::new(),
+                notes: String::new(),
+                cost_cents: None,
+            })
+        };
+        let drifting_id = create("HVAC filter")?;
+        let sparse_id = create("Chimney sweep")?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        runtime.submit_form(&FormPayload::Maintenance(MaintenanceItemFormInput {
-            name: "Gutter Cleaning".to_owned(),
-            category_id,
+        let mut log = |maintenance_item_id, month| {
+            runtime.submit_form(&FormPayload::ServiceLogEntry(ServiceLogEntryFormInput {
+                maintenance_item_id,
+                serviced_at: Date::from_calendar_date(2025, month, 1)?,
+                vendor_id: None,
+                cost_cents: None,
+                notes: String::new(),
+            }))
+        };
+        log(drifting_id, Month::January)?;
+        log(drifting_id, Month::May)?;
+        log(drifting_id, Month::September)?;
+        log(sparse_id, Month::February)?;
+        log(sparse_id, Month::March)?;
+        // A private visit doesn't count toward the observed cadence.
+        log(sparse_id, Month::April)?;
+        let private_visit = store
+            .list_service_log_for_maintenance(sparse_id, false)?
+            .into_iter()
+            .find(|entry| entry.serviced_at.month() == Month::April)
+            .expect("april visit")
+            .id;
+        store.set_row_private(
+            micasa_db::LifecycleEntityRef::ServiceLogEntry(private_visit),
+            true,
+        )?;
+
+        let drift = runtime.load_dashboard_snapshot()?.interval_drift;
+        assert_eq!(drift.len(), 1);
+        assert_eq!(drift[0].maintenance_item_id, drifting_id);
+        assert_eq!((drift[0].interval_months, drift[0].observed_months), (6, 4));
+
+        assert_eq!(
+            runtime.adopt_observed_interval(TabKind::Maintenance, drifting_id.get())?,
+            Some(4)
+        );
+        assert!(runtime.load_dashboard_snapshot()?.interval_drift.is_empty());
+        assert_eq!(
+            runtime.adopt_observed_interval(TabKind::Maintenance, sparse_id.get())?,
+            None
+        );
+
+        let error = runtime
+            .adopt_observed_interval(TabKind::Projects, 1)
+            .expect_err("projects have no interval");
+        assert!(error.to_string().contains("switch to maintenance"));
+        Ok(())
+    }
+
+    #[test]
+    fn cost_splits_resolve_category_names_and_round_trip_as_spec() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let categories = store.list_maintenance_categories()?;
+        let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Sump pump".to_owned(),
+            category_id: categories[0].id,
             appliance_id: None,
             last_serviced_at: None,
-            interval_months: 30,
+            interval_months: 12,
             manual_url: String::new(),
             manual_text: String::new(),
             notes: String::new(),
             cost_cents: None,
-        }))?;
+        })?;
+        let entry_id = store.create_service_log_entry(&NewServiceLogEntry {
+            maintenance_item_id: maintenance_id,
+            serviced_at: Date::from_calendar_date(2026, Month::June, 4)?,
+            vendor_id: None,
+            cost_cents: Some(20_000),
+            notes: String::new(),
+        })?;
 
-        let stored_items = store.list_maintenance_items(false)?;
-        assert_eq!(stored_items.len(), 1);
-        assert_eq!(stored_items[0].interval_months, 30);
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        let spec = format!(
+            "{}=60%, {}=40%",
+            categories[0].name.to_uppercase(),
+            categories[1].name
+        );
+        assert_eq!(
+            runtime.set_cost_splits(TabKind::ServiceLog, entry_id.get(), &spec)?,
+            2
+        );
+        assert_eq!(
+            runtime.cost_split_spec(TabKind::ServiceLog, entry_id.get())?,
+            format!(
+                "{}=120.00, {}=80.00",
+                categories[0].name, categories[1].name
+            )
+        );
 
-        let snapshot = runtime
-            .load_tab_snapshot(TabKind::Maintenance, false)?
-            .expect("maintenance snapshot");
-        match snapshot {
-            TabSnapshot::Maintenance(rows) => {
-                assert_eq!(rows.len(), 1);
-                assert_eq!(rows[0].interval_months, 30);
-            }
-            other => panic!("expected maintenance snapshot, got {other:?}"),
+        let error = runtime
+            .set_cost_splits(TabKind::ServiceLog, entry_id.get(), "nowhere=100%")
+            .expect_err("unknown category");
+        assert!(
+            error
+                .to_string()
+                .contains("unknown category `nowhere`; use one of:")
+        );
+        let error = runtime
+            .set_cost_splits(TabKind::Projects, 1, "")
+            .expect_err("projects have no splittable cost");
+        assert!(
+            error
+                .to_string()
+                .contains("apply to service log and incidents")
+        );
+
+        assert_eq!(
+            runtime.set_cost_splits(TabKind::ServiceLog, entry_id.get(), "")?,
+            0
+        );
+        assert!(
+            runtime
+                .cost_split_spec(TabKind::ServiceLog, entry_id.get())?
+                .is_empty()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn money_currency_maps_home_code_to_none_and_needs_a_rate() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_type_id = store.list_project_types()?[0].id;
+        let project_id = store.create_project(&NewProject {
+            title: "Kitchen".to_owned(),
+            project_type_id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: Some(10_000),
+            actual_cents: None,
+            parent_id: None,
+        })?;
+        let row_id = project_id.get();
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None)
+            .with_home_currency("USD");
+        let error = runtime
+            .set_money_currency(TabKind::Projects, row_id, "budget_cents", "eur")
+            .expect_err("EUR has no rate yet");
+        assert!(error.to_string().contains("--set-rate EUR=<rate>"));
+
+        store.set_currency_rate("EUR", 1_080_000, RateSource::Manual)?;
+        assert_eq!(
+            runtime.set_money_currency(TabKind::Projects, row_id, "budget_cents", "eur")?,
+            Some("EUR".to_owned())
+        );
+        assert_eq!(
+            runtime
+                .money_currencies(TabKind::Projects)?
+                .get(&(row_id, "budget_cents".to_owned()))
+                .map(String::as_str),
+            Some("EUR")
+        );
+
+        for home in ["usd", " "] {
+            assert_eq!(
+                runtime.set_money_currency(TabKind::Projects, row_id, "budget_cents", home)?,
+                None
+            );
         }
+        assert!(runtime.money_currencies(TabKind::Projects)?.is_empty());
+
+        let error = runtime
+            .set_money_currency(TabKind::Projects, row_id, "budget_cents", "EURO")
+            .expect_err("codes are three letters");
+        assert!(error.to_string().contains("three letters"));
+        Ok(())
+    }
+
+    #[test]
+    fn inline_field_edits_undo_and_redo() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_type_id = store.list_project_types()?[0].id;
+        let project_id = store.create_project(&NewProject {
+            title: "Deck".to_owned(),
+            project_type_id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: Some(10_000),
+            actual_cents: None,
+            parent_id: None,
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.update_field(TabKind::Projects, project_id.get(), "budget_cents", "250")?;
+        runtime.update_field(TabKind::Projects, project_id.get(), "title", "Deck rebuild")?;
+        assert_eq!(store.get_project(project_id)?.budget_cents, Some(25_000));
+
+        assert!(runtime.undo_last_edit()?);
+        assert_eq!(store.get_project(project_id)?.title, "Deck");
+        assert!(runtime.undo_last_edit()?);
+        assert_eq!(store.get_project(project_id)?.budget_cents, Some(10_000));
+        assert!(!runtime.undo_last_edit()?);
+
+        assert!(runtime.redo_last_edit()?);
+        assert_eq!(store.get_project(project_id)?.budget_cents, Some(25_000));
+
+        let error = runtime
+            .update_field(TabKind::House, 1, "nickname", "Home")
+            .expect_err("house edits go through the profile form");
+        assert!(error.to_string().contains("open the full form"));
         Ok(())
     }
 
     #[test]
-    fn snapshot_respects_deleted_filter() -> Result<()> {
+    fn mutations_land_in_the_audit_trail_with_their_source() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
 
         let project_type_id = store.list_project_types()?[0].id;
         let project_id = store.create_project(&NewProject {
-            title: "Window replacement".to_owned(),
+            title: "Deck".to_owned(),
             project_type_id,
             status: ProjectStatus::Planned,
             description: String::new(),
@@ -1266,40 +4358,137 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
-        store.soft_delete_project(project_id)?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        let visible = runtime
-            .load_tab_snapshot(TabKind::Projects, false)?
-            .expect("projects snapshot");
-        let with_deleted = runtime
-            .load_tab_snapshot(TabKind::Projects, true)?
-            .expect("projects snapshot");
-        assert_eq!(visible.row_count(), 0);
-        assert_eq!(with_deleted.row_count(), 1);
+        runtime.update_field(TabKind::Projects, project_id.get(), "title", "Deck rebuild")?;
+        store.with_audit_source(AuditSource::Chat, |_| {
+            runtime.submit_form(&FormPayload::Budget(micasa_app::BudgetFormInput {
+                category: micasa_app::BudgetCategory::ProjectType(project_type_id),
+                monthly_cents: 50_000,
+                notes: String::new(),
+            }))
+        })?;
+        runtime.apply_lifecycle(
+            TabKind::Projects,
+            &[project_id.get()],
+            LifecycleAction::Delete,
+        )?;
+        assert!(runtime.undo_last_edit()?);
+
+        let summary = |entries: Vec<micasa_app::AuditEntry>| {
+            entries
+                .into_iter()
+                .map(|entry| {
+                    format!(
+                        "{} {} {} {}",
+                        entry.source.as_str(),
+                        entry.entity.as_str(),
+                        entry.target_id,
+                        entry.action
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(runtime.load_audit_entries(None, 10)?),
+            vec![
+                format!("manual project {} restore", project_id.get()),
+                format!("manual project {} delete", project_id.get()),
+                "chat budget 1 create".to_owned(),
+                format!("manual project {} edit title", project_id.get()),
+            ]
+        );
+        assert_eq!(
+            summary(runtime.load_audit_entries(Some(AuditSource::Chat), 10)?),
+            vec!["chat budget 1 create".to_owned()]
+        );
+
+        runtime.toggle_row_private(TabKind::Projects, project_id.get())?;
+        assert_eq!(runtime.load_audit_entries(None, 10)?.len(), 1);
+        runtime.set_private_rows_visible(true)?;
+        assert_eq!(runtime.load_audit_entries(None, 10)?.len(), 4);
         Ok(())
     }
 
     #[test]
-    fn house_snapshot_tracks_profile_presence() -> Result<()> {
+    fn tags_round_trip_on_taggable_tabs_only() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
+        let vendor_id = store.create_vendor(&NewVendor {
+            name: "Cedar Roofing".to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        let before = runtime
-            .load_tab_snapshot(TabKind::House, false)?
-            .expect("house snapshot");
-        assert_eq!(before.row_count(), 0);
+        let tags = vec!["roof".to_owned(), "trusted".to_owned()];
+        runtime.set_tags(TabKind::Vendors, vendor_id.get(), &tags)?;
+        assert_eq!(
+            runtime.tags_for(TabKind::Vendors)?,
+            std::collections::BTreeMap::from([(vendor_id.get(), tags)])
+        );
+        assert!(runtime.tags_for(TabKind::Quotes)?.is_empty());
 
-        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
-            house_form_input_with_insurance("", None),
-        )))?;
+        let error = runtime
+            .set_tags(TabKind::Quotes, 1, &["roof".to_owned()])
+            .expect_err("quotes are not taggable");
+        assert!(error.to_string().contains("can't be tagged"));
+        Ok(())
+    }
 
-        let after = runtime
-            .load_tab_snapshot(TabKind::House, false)?
-            .expect("house snapshot");
-        assert_eq!(after.row_count(), 1);
+    #[test]
+    fn search_all_spans_tabs_and_skips_private_rows() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let vendor = |name: &str| NewVendor {
+            name: name.to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        };
+        let public_id = store.create_vendor(&vendor("Cedar Roofing"))?;
+        let private_id = store.create_vendor(&vendor("Cedar Fencing"))?;
+        let project_type_id = store.list_project_types()?[0].id;
+        let project_id = store.create_project(&NewProject {
+            title: "Replace cedar shingles".to_owned(),
+            project_type_id,
+            status: ProjectStatus::Planned,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.toggle_row_private(TabKind::Vendors, private_id.get())?;
+        let hits = runtime
+            .search_all("CEDAR")?
+            .into_iter()
+            .map(|hit| (hit.tab, hit.row_id))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            hits,
+            vec![
+                (TabKind::Projects, project_id.get()),
+                (TabKind::Vendors, public_id.get()),
+            ]
+        );
+        assert!(runtime.search_all("  ")?.is_empty());
         Ok(())
     }
 
@@ -1344,6 +4533,76 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn logging_service_advances_last_serviced_and_suggests_last_vendor() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Gutter clean".to_owned(),
+            category_id: store.list_maintenance_categories()?[0].id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 6,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        })?;
+        let vendor_id = store.create_vendor(&NewVendor {
+            name: "Clear Gutters".to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?;
+        let last_serviced = |store: &Store| -> Result<Option<Date>> {
+            Ok(store.list_maintenance_items(false)?[0].last_serviced_at)
+        };
+        let log = |day: u8, vendor_id: Option<micasa_app::VendorId>| -> Result<FormPayload> {
+            Ok(FormPayload::ServiceLogEntry(ServiceLogEntryFormInput {
+                maintenance_item_id: item_id,
+                serviced_at: Date::from_calendar_date(2026, Month::April, day)?,
+                vendor_id,
+                cost_cents: None,
+                notes: String::new(),
+            }))
+        };
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert_eq!(runtime.last_service_vendor(item_id)?, None);
+
+        runtime.submit_form(&log(10, Some(vendor_id))?)?;
+        assert_eq!(
+            last_serviced(&store)?,
+            Some(Date::from_calendar_date(2026, Month::April, 10)?)
+        );
+        runtime.submit_form(&log(12, None)?)?;
+        assert_eq!(runtime.last_service_vendor(item_id)?, Some(vendor_id));
+
+        runtime.submit_form(&log(2, None)?)?;
+        assert_eq!(
+            last_serviced(&store)?,
+            Some(Date::from_calendar_date(2026, Month::April, 12)?),
+            "an older visit leaves the latest date alone"
+        );
+
+        runtime.undo_last_edit()?;
+        runtime.undo_last_edit()?;
+        assert_eq!(
+            last_serviced(&store)?,
+            Some(Date::from_calendar_date(2026, Month::April, 10)?)
+        );
+        assert_eq!(store.list_service_log_entries(false)?.len(), 1);
+        runtime.undo_last_edit()?;
+        assert_eq!(last_serviced(&store)?, None);
+        Ok(())
+    }
+
     #[test]
     fn demo_seed_runtime_loads_non_empty_snapshots_for_all_tabs() -> Result<()> {
         let store = Store::open_memory()?;
@@ -1436,6 +4695,7 @@ mod tests {
             end_date: None,
             budget_cents: Some(5_000),
             actual_cents: None,
+            parent_id: None,
         }))?;
 
         let created_id = store.list_projects(false)?[0].id;
@@ -1445,7 +4705,11 @@ mod tests {
         assert!(runtime.redo_last_edit()?);
         assert_eq!(store.list_projects(false)?.len(), 1);
 
-        runtime.apply_lifecycle(TabKind::Projects, created_id.get(), LifecycleAction::Delete)?;
+        runtime.apply_lifecycle(
+            TabKind::Projects,
+            &[created_id.get()],
+            LifecycleAction::Delete,
+        )?;
         assert!(store.list_projects(false)?.is_empty());
         runtime.undo_last_edit()?;
         assert_eq!(store.list_projects(false)?.len(), 1);
@@ -1453,6 +4717,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn bulk_lifecycle_is_one_undo_step_and_all_or_nothing() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        for title in ["Fence", "Gutters", "Shed"] {
+            runtime.submit_form(&FormPayload::Project(ProjectFormInput {
+                title: title.to_owned(),
+                project_type_id: ProjectTypeId::new(1),
+                status: ProjectStatus::Planned,
+                description: String::new(),
+                start_date: None,
+                end_date: None,
+                budget_cents: None,
+                actual_cents: None,
+                parent_id: None,
+            }))?;
+        }
+        let ids = store
+            .list_projects(false)?
+            .iter()
+            .map(|project| project.id.get())
+            .collect::<Vec<_>>();
+
+        let error = runtime
+            .apply_lifecycle(
+                TabKind::Projects,
+                &[ids[0], ids[1], 999],
+                LifecycleAction::Delete,
+            )
+            .expect_err("missing row should fail the batch");
+        assert!(error.to_string().contains("999"));
+        assert_eq!(store.list_projects(false)?.len(), 3);
+
+        runtime.apply_lifecycle(TabKind::Projects, &ids, LifecycleAction::Delete)?;
+        assert!(store.list_projects(false)?.is_empty());
+        assert!(runtime.undo_last_edit()?);
+        assert_eq!(store.list_projects(false)?.len(), 3);
+        assert!(runtime.redo_last_edit()?);
+        assert!(store.list_projects(false)?.is_empty());
+        Ok(())
+    }
+
     #[test]
     fn chat_history_round_trip_persists_and_dedupes_adjacent_inputs() -> Result<()> {
         let store = Store::open_memory()?;
@@ -1512,6 +4820,7 @@ mod tests {
             appliance_id: None,
             vendor_id: None,
             notes: String::new(),
+            follow_up_date: None,
         })?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
@@ -1610,6 +4919,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
         store.create_project(&NewProject {
             title: "Underway project".to_owned(),
@@ -1620,6 +4930,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
         store.create_project(&NewProject {
             title: "Delayed project".to_owned(),
@@ -1630,6 +4941,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
         store.create_project(&NewProject {
             title: "Completed project".to_owned(),
@@ -1640,6 +4952,7 @@ mod tests {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
 
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
@@ -1738,27 +5051,270 @@ mod tests {
     }
 
     #[test]
-    fn dashboard_snapshot_includes_insurance_renewal_when_in_window() -> Result<()> {
+    fn dashboard_snapshot_includes_insurance_renewal_when_in_window() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let today = OffsetDateTime::now_utc().date();
+        let renewal = today + TimeDuration::days(28);
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
+            house_form_input_with_insurance("State Farm", Some(renewal)),
+        )))?;
+
+        let snapshot = runtime.load_dashboard_snapshot()?;
+        let insurance = snapshot
+            .insurance_renewal
+            .expect("in-window insurance renewal should be present");
+        assert_eq!(insurance.carrier, "State Farm");
+        assert_eq!(insurance.renewal_date, renewal);
+        assert_eq!(insurance.days_from_now, 28);
+        Ok(())
+    }
+
+    #[test]
+    fn maintenance_schedule_covers_ninety_days_and_skips_private_rows() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let today = OffsetDateTime::now_utc().date();
+        let category_id = store.list_maintenance_categories()?[0].id;
+        let maintenance = |name: &str, interval_months| NewMaintenanceItem {
+            name: name.to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: Some(today - TimeDuration::days(5)),
+            interval_months,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        };
+        store.create_maintenance_item(&maintenance("Replace filter", 1))?;
+        let private_id = store.create_maintenance_item(&maintenance("Check safe", 1))?;
+        store.create_appliance(&NewAppliance {
+            name: "Dishwasher".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: Some(today + TimeDuration::days(40)),
+            location: String::new(),
+            cost_cents: None,
+            notes: String::new(),
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
+            house_form_input_with_insurance("State Farm", Some(today + TimeDuration::days(60))),
+        )))?;
+        runtime.toggle_row_private(TabKind::Maintenance, private_id.get())?;
+
+        let events = runtime.load_maintenance_schedule()?;
+        let titles = events
+            .iter()
+            .map(|event| event.title.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            titles
+                .iter()
+                .filter(|title| **title == "Replace filter")
+                .count(),
+            3,
+            "monthly item should repeat inside the 90-day window: {titles:?}"
+        );
+        assert!(titles.contains(&"Dishwasher warranty ends"));
+        assert!(titles.contains(&"State Farm renewal"));
+        assert!(!titles.contains(&"Check safe"));
+        assert!(events.windows(2).all(|pair| pair[0].date <= pair[1].date));
+
+        let snapshot = runtime
+            .load_tab_snapshot(TabKind::Schedule, false)?
+            .expect("schedule snapshot");
+        assert_eq!(snapshot.row_count(), events.len());
+        Ok(())
+    }
+
+    #[test]
+    fn appliance_cost_history_merges_linked_costs_and_skips_private_rows() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let appliance_id = store.create_appliance(&NewAppliance {
+            name: "Furnace".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: Some(Date::from_calendar_date(2024, Month::March, 1)?),
+            warranty_expiry: None,
+            location: String::new(),
+            cost_cents: Some(350_000),
+            notes: String::new(),
+        })?;
+        let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
+            name: "Furnace tune-up".to_owned(),
+            category_id: store.list_maintenance_categories()?[0].id,
+            appliance_id: Some(appliance_id),
+            last_serviced_at: None,
+            interval_months: 12,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: Some(15_000),
+        })?;
+        let service = |month, cost_cents| NewServiceLogEntry {
+            maintenance_item_id: maintenance_id,
+            serviced_at: Date::from_calendar_date(2025, month, 10).expect("valid date"),
+            vendor_id: None,
+            cost_cents,
+            notes: String::new(),
+        };
+        store.create_service_log_entry(&service(Month::January, Some(18_000)))?;
+        store.create_service_log_entry(&service(Month::June, None))?;
+        let private_id = store.create_service_log_entry(&service(Month::July, Some(99_900)))?;
+        store.create_incident(&micasa_db::NewIncident {
+            title: "No heat".to_owned(),
+            description: String::new(),
+            status: micasa_app::IncidentStatus::Resolved,
+            severity: IncidentSeverity::Urgent,
+            date_noticed: Date::from_calendar_date(2025, Month::February, 2)?,
+            date_resolved: Some(Date::from_calendar_date(2025, Month::February, 3)?),
+            location: String::new(),
+            cost_cents: Some(42_000),
+            appliance_id: Some(appliance_id),
+            vendor_id: None,
+            notes: String::new(),
+            follow_up_date: None,
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.toggle_row_private(TabKind::ServiceLog, private_id.get())?;
+        let history = runtime.load_appliance_cost_history(appliance_id)?;
+        let rows = history
+            .events
+            .iter()
+            .map(|event| (event.kind.label(), event.title.as_str(), event.cost_cents))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            vec![
+                ("purchase", "Furnace purchased", 350_000),
+                ("service", "Furnace tune-up", 18_000),
+                ("incident", "No heat", 42_000),
+                ("maint", "Furnace tune-up", 15_000),
+            ]
+        );
+        assert_eq!(history.total_cents(), 425_000);
+
+        let error = runtime
+            .load_appliance_cost_history(micasa_app::ApplianceId::new(999))
+            .expect_err("missing appliance");
+        assert!(error.to_string().contains("not found or deleted"));
+        Ok(())
+    }
+
+    #[test]
+    fn reminders_follow_notice_settings_and_skip_private_rows() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let today = OffsetDateTime::now_utc().date();
+        let category_id = store.list_maintenance_categories()?[0].id;
+        let maintenance = |name: &str, days_ago| NewMaintenanceItem {
+            name: name.to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: Some(today - TimeDuration::days(days_ago)),
+            interval_months: 1,
+            manual_url: String::new(),
+            manual_text: String::new(),
+            notes: String::new(),
+            cost_cents: None,
+        };
+        store.create_maintenance_item(&maintenance("Replace filter", 75))?;
+        store.create_maintenance_item(&maintenance("Test alarms", 5))?;
+        let private_id = store.create_maintenance_item(&maintenance("Check safe", 75))?;
+        store.create_appliance(&NewAppliance {
+            name: "Dishwasher".to_owned(),
+            brand: String::new(),
+            model_number: String::new(),
+            serial_number: String::new(),
+            purchase_date: None,
+            warranty_expiry: Some(today + TimeDuration::days(45)),
+            location: String::new(),
+            cost_cents: None,
+            notes: String::new(),
+        })?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
+            house_form_input_with_insurance("State Farm", Some(today + TimeDuration::days(20))),
+        )))?;
+        runtime.toggle_row_private(TabKind::Maintenance, private_id.get())?;
+
+        let summaries = |runtime: &mut DbRuntime<'_>| -> Result<Vec<String>> {
+            Ok(runtime
+                .load_reminders()?
+                .iter()
+                .map(Reminder::summary)
+                .collect())
+        };
+        let defaults = summaries(&mut runtime)?;
+        assert_eq!(defaults.len(), 2, "{defaults:?}");
+        assert!(defaults[0].starts_with("Replace filter was due"));
+        assert_eq!(defaults[1], "State Farm insurance renews in 20 days");
+
+        runtime.set_notify_preference(SettingKey::NotifyWarrantyDays, SettingValue::Days(60))?;
+        runtime.set_notify_preference(SettingKey::NotifyInsuranceDays, SettingValue::Days(7))?;
+        let widened = summaries(&mut runtime)?;
+        assert!(widened.contains(&"Dishwasher warranty ends in 45 days".to_owned()));
+        assert!(widened.iter().all(|line| !line.contains("State Farm")));
+        assert!(
+            runtime
+                .set_notify_preference(SettingKey::UiTheme, SettingValue::Days(1))
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dashboard_layout_round_trips_through_settings() -> Result<()> {
         let store = Store::open_memory()?;
         store.bootstrap()?;
-        let today = OffsetDateTime::now_utc().date();
-        let renewal = today + TimeDuration::days(28);
-
         let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
-        runtime.submit_form(&FormPayload::HouseProfile(Box::new(
-            house_form_input_with_insurance("State Farm", Some(renewal)),
-        )))?;
+        assert_eq!(runtime.dashboard_layout()?, DashboardLayout::default());
 
-        let snapshot = runtime.load_dashboard_snapshot()?;
-        let insurance = snapshot
-            .insurance_renewal
-            .expect("in-window insurance renewal should be present");
-        assert_eq!(insurance.carrier, "State Farm");
-        assert_eq!(insurance.renewal_date, renewal);
-        assert_eq!(insurance.days_from_now, 28);
+        let layout = DashboardLayout {
+            sections: vec![DashboardSection::Overdue, DashboardSection::Incidents],
+            row_limits: [(DashboardSection::Overdue, 5)].into(),
+        };
+        runtime.set_dashboard_layout(&layout)?;
+        assert_eq!(
+            store.get_setting(SettingKey::DashboardSections)?,
+            Some(SettingValue::Text("overdue,incidents".to_owned()))
+        );
+        assert_eq!(runtime.dashboard_layout()?, layout);
         Ok(())
     }
 
+    #[test]
+    fn reminder_notification_lists_the_soonest_and_counts_the_rest() {
+        let reminders = (0..7)
+            .map(|day| Reminder {
+                kind: ReminderKind::Maintenance,
+                title: format!("Task {day}"),
+                days_from_now: -day - 1,
+            })
+            .collect::<Vec<_>>();
+        let (summary, body) = reminder_notification(&reminders);
+        assert_eq!(summary, "micasa: 7 reminders");
+        let lines = body.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "Task 0 was due yesterday");
+        assert_eq!(lines[5], "and 2 more in micasa");
+        assert_eq!(
+            reminder_notification(&reminders[..1]).0,
+            "micasa: 1 reminder"
+        );
+    }
+
     #[test]
     fn dashboard_snapshot_excludes_insurance_renewal_outside_window() -> Result<()> {
         let store = Store::open_memory()?;
@@ -1905,7 +5461,8 @@ mod tests {
         let store = Store::open_memory()?;
         store.bootstrap()?;
 
-        let client = LlmClient::new(&addr, "qwen3", Duration::from_secs(1))?;
+        let client = LlmClient::new(&addr, "qwen3", Duration::from_secs(1))?
+            .with_provider(micasa_llm::provider_named("openai").expect("openai provider"));
         let mut runtime =
             DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None);
 
@@ -1965,6 +5522,223 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn private_rows_stay_hidden_until_revealed() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_type_id = store.list_project_types()?[0].id;
+        let create = |title: &str| {
+            store.create_project(&NewProject {
+                title: title.to_owned(),
+                project_type_id,
+                status: ProjectStatus::Planned,
+                description: String::new(),
+                start_date: None,
+                end_date: None,
+                budget_cents: None,
+                actual_cents: None,
+                parent_id: None,
+            })
+        };
+        let public_id = create("Paint fence")?;
+        let private_id = create("Estate paperwork")?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert!(runtime.toggle_row_private(TabKind::Projects, private_id.get())?);
+        assert_eq!(
+            runtime.private_row_ids(TabKind::Projects)?,
+            [private_id.get()].into_iter().collect()
+        );
+
+        let Some(TabSnapshot::Projects(rows, ..)) =
+            runtime.load_tab_snapshot(TabKind::Projects, false)?
+        else {
+            return Err(anyhow!("expected projects snapshot"));
+        };
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            vec![public_id]
+        );
+
+        runtime.set_private_rows_visible(true)?;
+        assert_eq!(
+            runtime
+                .load_tab_snapshot(TabKind::Projects, false)?
+                .map(|snapshot| snapshot.row_count()),
+            Some(2)
+        );
+
+        assert!(!runtime.toggle_row_private(TabKind::Projects, private_id.get())?);
+        assert!(runtime.private_row_ids(TabKind::Projects)?.is_empty());
+
+        let error = runtime
+            .toggle_row_private(TabKind::House, 1)
+            .expect_err("house has no private rows");
+        assert!(error.to_string().contains("does not support private rows"));
+        Ok(())
+    }
+
+    #[test]
+    fn archived_rows_leave_views_and_dashboard_until_shown() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let project_type_id = store.list_project_types()?[0].id;
+        let create = |title: &str| {
+            store.create_project(&NewProject {
+                title: title.to_owned(),
+                project_type_id,
+                status: ProjectStatus::Underway,
+                description: String::new(),
+                start_date: None,
+                end_date: None,
+                budget_cents: None,
+                actual_cents: None,
+                parent_id: None,
+            })
+        };
+        let current_id = create("Bathroom tile")?;
+        let old_id = create("2018 roof")?;
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert!(runtime.toggle_row_archived(TabKind::Projects, old_id.get())?);
+
+        let Some(TabSnapshot::Projects(rows, ..)) =
+            runtime.load_tab_snapshot(TabKind::Projects, true)?
+        else {
+            return Err(anyhow!("expected projects snapshot"));
+        };
+        assert_eq!(
+            rows.iter().map(|row| row.id).collect::<Vec<_>>(),
+            vec![current_id]
+        );
+        let dashboard = runtime.load_dashboard_snapshot()?;
+        assert_eq!(
+            dashboard
+                .active_projects
+                .iter()
+                .map(|project| project.project_id)
+                .collect::<Vec<_>>(),
+            vec![current_id]
+        );
+        assert_eq!(runtime.load_dashboard_counts()?.projects_due, 1);
+
+        runtime.set_archived_rows_visible(true)?;
+        assert_eq!(
+            runtime.archived_row_ids(TabKind::Projects)?,
+            [old_id.get()].into_iter().collect()
+        );
+        let Some(TabSnapshot::Projects(rows, ..)) =
+            runtime.load_tab_snapshot(TabKind::Projects, false)?
+        else {
+            return Err(anyhow!("expected projects snapshot"));
+        };
+        assert_eq!(rows.len(), 2);
+        assert!(rows.iter().all(|row| row.deleted_at.is_none()));
+
+        assert!(!runtime.toggle_row_archived(TabKind::Projects, old_id.get())?);
+        assert!(runtime.archived_row_ids(TabKind::Projects)?.is_empty());
+
+        let error = runtime
+            .toggle_row_archived(TabKind::Settings, 1)
+            .expect_err("settings have no rows to archive");
+        assert!(error.to_string().contains("no rows to archive"));
+        Ok(())
+    }
+
+    #[test]
+    fn data_quality_issues_skip_private_rows_until_shown() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let category_id = store.list_maintenance_categories()?[0].id;
+        let item_id = store
+            .create_maintenance_item(&NewMaintenanceItem {
+                name: "Sump check".to_owned(),
+                category_id,
+                appliance_id: None,
+                last_serviced_at: None,
+                interval_months: 0,
+                manual_url: String::new(),
+                manual_text: String::new(),
+                notes: String::new(),
+                cost_cents: None,
+            })?
+            .get();
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert_eq!(runtime.data_quality_issues()?.len(), 1);
+        runtime.toggle_row_private(TabKind::Maintenance, item_id)?;
+        assert!(runtime.data_quality_issues()?.is_empty());
+        runtime.set_private_rows_visible(true)?;
+        let issues = runtime.data_quality_issues()?;
+        assert_eq!(issues[0].kind, DataIssueKind::ZeroInterval);
+        assert_eq!(issues[0].row_id, item_id);
+        Ok(())
+    }
+
+    #[test]
+    fn purge_expired_waits_for_the_setting_and_purge_all_clears_undo() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let project_type_id = store.list_project_types()?[0].id;
+        let project_id = store
+            .create_project(&NewProject {
+                title: "Old fence".to_owned(),
+                project_type_id,
+                status: ProjectStatus::Abandoned,
+                description: String::new(),
+                start_date: None,
+                end_date: None,
+                budget_cents: None,
+                actual_cents: None,
+                parent_id: None,
+            })?
+            .get();
+
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        runtime.apply_lifecycle(TabKind::Projects, &[project_id], LifecycleAction::Delete)?;
+        assert_eq!(runtime.deleted_row_count()?, 1);
+
+        let summary = runtime.purge_deleted(PurgeScope::Expired)?;
+        assert_eq!(summary.purged, 0, "auto-purge is off by default");
+        runtime.set_purge_deleted_days(30)?;
+        let summary = runtime.purge_deleted(PurgeScope::Expired)?;
+        assert_eq!(summary.purged, 0, "the row was deleted just now");
+
+        let summary = runtime.purge_deleted(PurgeScope::All)?;
+        assert_eq!((summary.purged, summary.kept), (1, 0));
+        assert_eq!(runtime.deleted_row_count()?, 0);
+        assert!(
+            !runtime.undo_last_edit()?,
+            "the delete can't be undone once the row is gone"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn app_lock_verifies_passphrase_against_configured_hash() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+
+        let mut unlocked = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert!(!unlocked.lock_requires_passphrase());
+        assert!(unlocked.verify_lock_passphrase("anything")?);
+        assert_eq!(unlocked.lock_idle_timeout(), None);
+
+        let hash = micasa_db::crypto::hash_passphrase("correct horse")?;
+        let mut runtime = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None)
+            .with_app_lock(Some(std::time::Duration::from_secs(300)), Some(&hash));
+        assert!(runtime.lock_requires_passphrase());
+        assert_eq!(
+            runtime.lock_idle_timeout(),
+            Some(std::time::Duration::from_secs(300))
+        );
+        assert!(runtime.verify_lock_passphrase("correct horse")?);
+        assert!(!runtime.verify_lock_passphrase("wrong")?);
+        Ok(())
+    }
+
     #[test]
     fn settings_snapshot_returns_typed_setting_rows() -> Result<()> {
         let store = Store::open_memory()?;
@@ -1994,6 +5768,459 @@ mod tests {
         Ok(())
     }
 
+    /// Reads one HTTP request, headers and body, so the reply isn't cut short
+    /// by unread request bytes.
+    fn read_http_request(socket: &mut std::net::TcpStream) -> String {
+        use std::io::Read;
+        let mut raw = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let read = socket.read(&mut buf).expect("read request");
+            raw.extend_from_slice(&buf[..read]);
+            let text = String::from_utf8_lossy(&raw);
+            if let Some(header_end) = text.find("\r\n\r\n") {
+                let length = text[..header_end]
+                    .lines()
+                    .find_map(|line| {
+                        let (name, value) = line.split_once(':')?;
+                        name.eq_ignore_ascii_case("content-length")
+                            .then(|| value.trim().parse::<usize>().ok())?
+                    })
+                    .unwrap_or(0);
+                if raw.len() >= header_end + 4 + length || read == 0 {
+                    return text.into_owned();
+                }
+            }
+            if read == 0 {
+                return String::from_utf8_lossy(&raw).into_owned();
+            }
+        }
+    }
+
+    #[test]
+    fn chat_stages_run_on_their_own_models() -> Result<()> {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for content in ["SELECT COUNT(*) FROM projects", "No projects yet."] {
+                let (mut socket, _) = listener.accept().expect("chat request");
+                requests.push(read_http_request(&mut socket));
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+                     data: {{\"choices\":[{{\"delta\":{{\"content\":\"{content}\"}},\"finish_reason\":null}}]}}\n\
+                     data: [DONE]\n"
+                );
+                socket.write_all(reply.as_bytes()).expect("write reply");
+            }
+            requests
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(30))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None);
+        runtime.set_stage_model(SettingKey::LlmSqlModel, "qwen3:0.6b")?;
+        assert_eq!(
+            runtime.chat_stage_models()?,
+            Some(ChatStageModels {
+                sql: "qwen3:0.6b".to_owned(),
+                summary: "qwen3".to_owned(),
+            })
+        );
+
+        let result = runtime.run_chat_pipeline("how many projects?", &[])?;
+        assert_eq!(result.answer, "No projects yet.");
+        let requests = server.join().expect("server thread");
+        assert!(
+            requests[0].contains("\"model\":\"qwen3:0.6b\""),
+            "{}",
+            requests[0]
+        );
+        assert!(
+            requests[1].contains("\"model\":\"qwen3\""),
+            "{}",
+            requests[1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn chat_offline_reason_names_the_unreachable_server() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let mut offline = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        assert_eq!(offline.chat_offline_reason(), None, "no LLM is not offline");
+
+        let client = LlmClient::new("http://127.0.0.1:1/v1", "qwen3", Duration::from_millis(50))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None);
+        let reason = runtime.chat_offline_reason().expect("port 1 is closed");
+        assert!(
+            reason.contains("cannot reach http://127.0.0.1:1/v1"),
+            "{reason}"
+        );
+        assert!(reason.contains("ollama serve"), "{reason}");
+        Ok(())
+    }
+
+    #[test]
+    fn chat_provider_switch_uses_default_urls_and_returns_to_the_configured_one() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let client = LlmClient::new("http://gpu-box:11434/v1", "qwen3", Duration::from_secs(1))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None);
+        assert_eq!(runtime.active_chat_provider()?.as_deref(), Some("ollama"));
+        assert_eq!(
+            runtime.list_chat_providers()?,
+            vec!["ollama", "openai", "llamacpp"]
+        );
+
+        assert_eq!(
+            runtime.select_chat_provider("llamacpp")?,
+            "http://localhost:8080/v1"
+        );
+        assert_eq!(runtime.active_chat_provider()?.as_deref(), Some("llamacpp"));
+        assert_eq!(
+            runtime.select_chat_provider(" ollama ")?,
+            "http://gpu-box:11434/v1"
+        );
+        let error = runtime
+            .select_chat_provider("bard")
+            .expect_err("unknown provider");
+        assert!(error.to_string().contains("ollama, openai, llamacpp"));
+        assert_eq!(runtime.active_chat_model()?.as_deref(), Some("qwen3"));
+        Ok(())
+    }
+
+    #[test]
+    fn explain_chat_sql_asks_the_summary_model_about_the_query() -> Result<()> {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let server = thread::spawn(move || {
+            let (mut socket, _) = listener.accept().expect("explain request");
+            let request = read_http_request(&mut socket);
+            socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+                      data: {\"choices\":[{\"delta\":{\"content\":\"Counts every project.\"},\"finish_reason\":null}]}\n\
+                      data: [DONE]\n",
+                )
+                .expect("write reply");
+            request
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(30))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None);
+        runtime.set_stage_model(SettingKey::LlmSummaryModel, "qwen3:8b")?;
+
+        let explanation =
+            runtime.explain_chat_sql("how many projects?", "SELECT COUNT(*) FROM projects")?;
+        assert_eq!(explanation, "Counts every project.");
+        let request = server.join().expect("server thread");
+        assert!(request.contains("\"model\":\"qwen3:8b\""), "{request}");
+        assert!(
+            request.contains("SELECT COUNT(*) FROM projects"),
+            "{request}"
+        );
+        assert!(request.contains("how many projects?"), "{request}");
+
+        let mut offline = DbRuntime::with_llm_client_context_and_db_path(&store, None, "", None);
+        let error = offline
+            .explain_chat_sql("how many projects?", "SELECT 1")
+            .expect_err("LLM is off");
+        assert!(error.to_string().contains("[llm].enabled"));
+        Ok(())
+    }
+
+    #[test]
+    fn indexed_documents_are_quoted_in_chat_answers() -> Result<()> {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let server = thread::spawn(move || {
+            let mut requests = Vec::new();
+            // Embed the document, write SQL, embed the question, summarize.
+            for content in [
+                "",
+                "SELECT COUNT(*) FROM projects",
+                "",
+                "Use 40-gallon units.",
+            ] {
+                let (mut socket, _) = listener.accept().expect("request");
+                let request = read_http_request(&mut socket);
+                let reply = if request.starts_with("POST /v1/embeddings") {
+                    let body = r#"{"data":[{"index":0,"embedding":[1.0,0.0]}]}"#;
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+                         data: {{\"choices\":[{{\"delta\":{{\"content\":\"{content}\"}},\"finish_reason\":null}}]}}\n\
+                         data: [DONE]\n"
+                    )
+                };
+                socket.write_all(reply.as_bytes()).expect("write reply");
+                requests.push(request);
+            }
+            requests
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(30))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None)
+                .with_document_index(Some("nomic-embed-text".to_owned()));
+        runtime.submit_form(&FormPayload::Document(DocumentFormInput {
+            title: "Water heater manual".to_owned(),
+            file_name: "heater.txt".to_owned(),
+            entity_kind: DocumentEntityKind::None,
+            entity_id: 0,
+            mime_type: "text/plain".to_owned(),
+            data: b"see notes".to_vec(),
+            checksum_sha256: String::new(),
+            notes: "Replacement tank is 40 gallons".to_owned(),
+        }))?;
+        assert_eq!(runtime.take_budget_alert(), None);
+        assert_eq!(store.document_chunks("nomic-embed-text")?.len(), 1);
+
+        let result = runtime.run_chat_pipeline("what size tank do I need?", &[])?;
+        assert_eq!(result.answer, "Use 40-gallon units.");
+        assert_eq!(result.notice, None);
+        let requests = server.join().expect("server thread");
+        assert!(
+            requests[0].contains("\"model\":\"nomic-embed-text\""),
+            "{}",
+            requests[0]
+        );
+        assert!(!requests[1].contains("## Document excerpts"));
+        assert!(
+            requests[3].contains("## Document excerpts")
+                && requests[3].contains("Replacement tank is 40 gallons"),
+            "{}",
+            requests[3]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn failed_document_retrieval_is_reported_with_the_answer() -> Result<()> {
+        use std::io::Write;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let server = thread::spawn(move || {
+            // Write SQL, fail to embed the question, summarize anyway.
+            for content in ["SELECT COUNT(*) FROM projects", "", "No projects yet."] {
+                let (mut socket, _) = listener.accept().expect("request");
+                let request = read_http_request(&mut socket);
+                let reply = if request.starts_with("POST /v1/embeddings") {
+                    let body = r#"{"error":{"message":"model not loaded"}}"#;
+                    format!(
+                        "HTTP/1.1 500 Internal Server Error\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n\
+                         data: {{\"choices\":[{{\"delta\":{{\"content\":\"{content}\"}},\"finish_reason\":null}}]}}\n\
+                         data: [DONE]\n"
+                    )
+                };
+                socket.write_all(reply.as_bytes()).expect("write reply");
+            }
+        });
+
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        let document = store.insert_document(&micasa_db::NewDocument {
+            title: "Water heater manual".to_owned(),
+            file_name: "heater.txt".to_owned(),
+            entity_kind: DocumentEntityKind::None,
+            entity_id: 0,
+            mime_type: "text/plain".to_owned(),
+            data: b"40 gallons".to_vec(),
+            notes: String::new(),
+        })?;
+        store.replace_document_chunks(
+            document,
+            "nomic-embed-text",
+            &[("40 gallons".to_owned(), vec![1.0, 0.0])],
+        )?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(30))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", None)
+                .with_document_index(Some("nomic-embed-text".to_owned()));
+
+        let result = runtime.run_chat_pipeline("what size tank do I need?", &[])?;
+        server.join().expect("server thread");
+        assert_eq!(result.answer, "No projects yet.");
+        let notice = result.notice.expect("retrieval failure is reported");
+        assert!(
+            notice.starts_with("answered without document passages: embed the question"),
+            "{notice}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn chat_worker_streams_chunks_and_cancel_ends_it_mid_answer() -> Result<()> {
+        use std::io::Write;
+        use std::sync::mpsc;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+        let server = thread::spawn(move || {
+            let sse = |chunks: &[&str]| {
+                let mut body = String::from(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\n",
+                );
+                for chunk in chunks {
+                    body.push_str(&format!(
+                        "data: {{\"choices\":[{{\"delta\":{{\"content\":\"{chunk}\"}},\"finish_reason\":null}}]}}\n"
+                    ));
+                }
+                body
+            };
+            let (mut socket, _) = listener.accept().expect("sql request");
+            read_http_request(&mut socket);
+            let mut reply = sse(&["SELECT COUNT(*) ", "FROM projects"]);
+            reply.push_str("data: [DONE]\n");
+            socket
+                .write_all(reply.as_bytes())
+                .expect("write sql stream");
+            drop(socket);
+
+            let (mut socket, _) = listener.accept().expect("summary request");
+            read_http_request(&mut socket);
+            socket
+                .write_all(sse(&["You have "]).as_bytes())
+                .expect("write first answer chunk");
+            socket.flush().expect("flush answer chunk");
+            // Hold the answer open until the test is done.
+            let _ = release_rx.recv();
+        });
+
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("micasa.db");
+        let store = Store::open(&db_path)?;
+        store.bootstrap()?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(30))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", Some(db_path));
+
+        let executor = chat_executor()?;
+        let _entered = executor.enter();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        runtime.spawn_chat_pipeline(7, "how many projects?", &[], tx)?;
+        let mut events = Vec::new();
+        while !matches!(events.last(), Some(ChatPipelineEvent::AnswerChunk { .. })) {
+            match next_chat_event(&executor, &mut rx, Duration::from_secs(5))? {
+                Some(InternalEvent::ChatPipeline(event)) => events.push(event),
+                other => panic!("unexpected event {other:?}"),
+            }
+        }
+        assert_eq!(
+            events,
+            vec![
+                ChatPipelineEvent::SqlChunk {
+                    request_id: 7,
+                    chunk: "SELECT COUNT(*) ".to_owned(),
+                },
+                ChatPipelineEvent::SqlChunk {
+                    request_id: 7,
+                    chunk: "FROM projects".to_owned(),
+                },
+                ChatPipelineEvent::SqlReady {
+                    request_id: 7,
+                    sql: "SELECT COUNT(*)\nFROM projects".to_owned(),
+                },
+                ChatPipelineEvent::AnswerChunk {
+                    request_id: 7,
+                    chunk: "You have ".to_owned(),
+                },
+            ]
+        );
+
+        runtime.cancel_chat_pipeline(7)?;
+        // The worker drops its sender once it exits; it must not wait for
+        // the stalled server or report a result.
+        match next_chat_event(&executor, &mut rx, Duration::from_secs(2)) {
+            Ok(None) => {}
+            other => panic!("worker should stop quietly after cancel, got {other:?}"),
+        }
+
+        release_tx.send(()).expect("release server");
+        server.join().expect("server thread should join");
+        Ok(())
+    }
+
+    fn chat_executor() -> Result<tokio::runtime::Runtime> {
+        Ok(tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()?)
+    }
+
+    fn next_chat_event(
+        executor: &tokio::runtime::Runtime,
+        rx: &mut tokio::sync::mpsc::UnboundedReceiver<InternalEvent>,
+        wait: Duration,
+    ) -> Result<Option<InternalEvent>> {
+        Ok(executor.block_on(tokio::time::timeout(wait, rx.recv()))?)
+    }
+
+    #[test]
+    fn a_new_chat_question_cancels_the_one_still_running() -> Result<()> {
+        // Accepts connections but never answers, so the first request hangs
+        // until its client timeout.
+        let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}/v1", listener.local_addr()?);
+        let dir = tempfile::tempdir()?;
+        let db_path = dir.path().join("micasa.db");
+        let store = Store::open(&db_path)?;
+        store.bootstrap()?;
+        let client = LlmClient::new(&base_url, "qwen3", Duration::from_secs(1))?;
+        let mut runtime =
+            DbRuntime::with_llm_client_context_and_db_path(&store, Some(client), "", Some(db_path));
+
+        let executor = chat_executor()?;
+        let _entered = executor.enter();
+        let (first_tx, mut first_rx) = tokio::sync::mpsc::unbounded_channel();
+        runtime.spawn_chat_pipeline(1, "how many projects?", &[], first_tx)?;
+        let (second_tx, mut second_rx) = tokio::sync::mpsc::unbounded_channel();
+        runtime.spawn_chat_pipeline(2, "  ", &[], second_tx)?;
+
+        match next_chat_event(&executor, &mut second_rx, Duration::from_secs(5))? {
+            Some(InternalEvent::ChatPipeline(ChatPipelineEvent::Failed { request_id, error })) => {
+                assert_eq!(request_id, 2);
+                assert!(error.contains("question is empty"), "{error}");
+            }
+            other => panic!("expected the empty question to fail, got {other:?}"),
+        }
+        // The first request times out without reporting, since it was
+        // replaced.
+        match next_chat_event(&executor, &mut first_rx, Duration::from_secs(5)) {
+            Ok(None) => {}
+            other => panic!("replaced request should stop quietly, got {other:?}"),
+        }
+        drop(listener);
+        Ok(())
+    }
+
     #[test]
     fn llm_stream_partial_tokens_can_be_short_circuited_by_callback() -> Result<()> {
         let server =
@@ -2020,7 +6247,7 @@ mod tests {
         });
 
         let client = LlmClient::new(&addr, "qwen3", Duration::from_secs(1))?;
-        let cancel = AtomicBool::new(false);
+        let cancel = Arc::new(AtomicBool::new(false));
         let messages = [LlmMessage {
             role: LlmRole::User,
             content: "Say partial".to_owned(),
@@ -2064,7 +6291,7 @@ mod tests {
         });
 
         let client = LlmClient::new(&addr, "qwen3", Duration::from_secs(1))?;
-        let cancel = AtomicBool::new(false);
+        let cancel = Arc::new(AtomicBool::new(false));
         let messages = [LlmMessage {
             role: LlmRole::User,
             content: "Say partial".to_owned(),
@@ -0,0 +1,115 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Schema docs on disk: the described schema the chat prompt uses, written
+//! next to the database as `<name>.schema.md` and `<name>.schema.json` for
+//! people writing their own SQL. Rewritten on launch whenever a migration
+//! changed what they would say.
+
+use anyhow::{Context, Result};
+use micasa_db::Store;
+use micasa_db::schema_docs::{TableDoc, render_markdown};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn render_json(tables: &[TableDoc]) -> String {
+    let tables = tables
+        .iter()
+        .map(|table| {
+            json!({
+                "name": table.name,
+                "description": table.description,
+                "columns": table.columns.iter().map(|column| json!({
+                    "name": column.name,
+                    "type": column.column_type,
+                    "not_null": column.not_null,
+                    "primary_key": column.primary_key,
+                    "references": column.references,
+                    "description": column.description,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let mut out = serde_json::to_string_pretty(&json!({ "tables": Value::Array(tables) }))
+        .unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// `house.db` keeps its docs in `house.schema.md` and `house.schema.json`.
+fn doc_paths(db_path: &Path) -> [PathBuf; 2] {
+    let stem = db_path
+        .file_stem()
+        .map_or_else(|| "micasa".into(), |stem| stem.to_string_lossy());
+    ["md", "json"].map(|extension| db_path.with_file_name(format!("{stem}.schema.{extension}")))
+}
+
+/// Writes both docs for `store`, skipping files already up to date. Returns
+/// the files that changed.
+pub fn refresh(store: &Store, db_path: &Path) -> Result<Vec<PathBuf>> {
+    let tables = store.schema_docs()?;
+    let mut written = Vec::new();
+    for (path, contents) in doc_paths(db_path)
+        .into_iter()
+        .zip([render_markdown(&tables), render_json(&tables)])
+    {
+        if fs::read_to_string(&path).is_ok_and(|current| current == contents) {
+            continue;
+        }
+        fs::write(&path, contents)
+            .with_context(|| format!("write schema docs {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::refresh;
+    use anyhow::Result;
+    use micasa_db::Store;
+    use serde_json::Value;
+    use std::fs;
+
+    #[test]
+    fn refresh_writes_matching_docs_only_when_they_change() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let store = Store::open(&db_path)?;
+        store.bootstrap()?;
+
+        let written = refresh(&store, &db_path)?;
+        assert_eq!(written.len(), 2);
+        let markdown = fs::read_to_string(temp.path().join("house.schema.md"))?;
+        assert!(markdown.contains("| vendor_id | INTEGER | not null; references vendors(id) |"));
+
+        let json: Value =
+            serde_json::from_str(&fs::read_to_string(temp.path().join("house.schema.json"))?)?;
+        let quotes = json["tables"]
+            .as_array()
+            .and_then(|tables| tables.iter().find(|table| table["name"] == "quotes"))
+            .expect("quotes documented");
+        assert_eq!(quotes["description"], "Vendor quotes for a project.");
+        let total = quotes["columns"]
+            .as_array()
+            .and_then(|columns| {
+                columns
+                    .iter()
+                    .find(|column| column["name"] == "total_cents")
+            })
+            .expect("total_cents documented");
+        assert!(
+            total["description"]
+                .as_str()
+                .is_some_and(|text| text.contains("cents"))
+        );
+
+        assert!(refresh(&store, &db_path)?.is_empty(), "unchanged");
+        store
+            .raw_connection()
+            .execute_batch("CREATE TABLE garden_beds (id INTEGER PRIMARY KEY, crop TEXT)")?;
+        assert_eq!(refresh(&store, &db_path)?.len(), 2, "schema changed");
+        Ok(())
+    }
+}
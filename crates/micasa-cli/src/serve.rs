@@ -0,0 +1,727 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! `micasa serve`: a read-only web view of the dashboard, the tabs, and
+//! document files, so someone without the TUI can check maintenance status
+//! from a phone. Each request opens the database afresh, so edits made in a
+//! running TUI show up on reload, and nothing here ever writes. `/metrics`
+//! reports request counts, latencies, and the database size to Prometheus.
+
+use crate::report::{escape_html, html_page};
+use crate::runtime::DbRuntime;
+use anyhow::{Context, Result, anyhow};
+use axum::Router;
+use axum::extract::{MatchedPath, Path as UrlPath, Request, State};
+use axum::http::{StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Json, Response};
+use axum::routing::get;
+use micasa_app::{DeletionEntity, DocumentId, TabKind, tab_for_slug};
+use micasa_db::Store;
+use micasa_tui::{AppRuntime, DashboardSnapshot, TableExport};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_LISTEN: &str = "127.0.0.1:8788";
+
+/// Tabs the web view offers. Settings and the query console stay in the TUI.
+const WEB_TABS: [TabKind; 11] = [
+    TabKind::House,
+    TabKind::Projects,
+    TabKind::Quotes,
+    TabKind::Maintenance,
+    TabKind::ServiceLog,
+    TabKind::Incidents,
+    TabKind::Appliances,
+    TabKind::Vendors,
+    TabKind::Documents,
+    TabKind::Budget,
+    TabKind::Schedule,
+];
+
+/// Serves `db_path` on `listen` until the process is stopped.
+pub fn serve(db_path: PathBuf, listen: &str) -> Result<()> {
+    let addr: SocketAddr = listen
+        .parse()
+        .with_context(|| format!("--listen must be HOST:PORT, like {DEFAULT_LISTEN}"))?;
+    let listener = TcpListener::bind(addr)
+        .with_context(|| format!("listen on {addr}; is another micasa serve running?"))?;
+    eprintln!(
+        "serving {} read-only at http://{addr}/ (ctrl+c stops)",
+        db_path.display()
+    );
+    run(listener, db_path)
+}
+
+fn run(listener: TcpListener, db_path: PathBuf) -> Result<()> {
+    listener
+        .set_nonblocking(true)
+        .context("set listener non-blocking")?;
+    // One thread is plenty for a household; handlers query SQLite inline.
+    let executor = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .context("start web server runtime")?;
+    executor.block_on(async move {
+        let listener = tokio::net::TcpListener::from_std(listener).context("register listener")?;
+        axum::serve(listener, router(db_path))
+            .await
+            .context("web server stopped")
+    })
+}
+
+fn router(db_path: PathBuf) -> Router {
+    let server = Arc::new(Server {
+        db_path,
+        metrics: Mutex::default(),
+    });
+    Router::new()
+        .route("/", get(dashboard_page))
+        .route("/tabs/{tab}", get(tab_page))
+        .route("/api/dashboard", get(dashboard_json))
+        .route("/api/tabs/{tab}", get(tab_json))
+        .route("/documents/{id}", get(document_file))
+        .route("/metrics", get(metrics_text))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&server),
+            track_request,
+        ))
+        .with_state(server)
+}
+
+struct Server {
+    db_path: PathBuf,
+    metrics: Mutex<Metrics>,
+}
+
+impl Server {
+    fn metrics(&self) -> std::sync::MutexGuard<'_, Metrics> {
+        // Counters stay usable even if a handler panicked mid-update.
+        self.metrics.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Runs one database read, timed under `kind`.
+    fn query<T>(&self, kind: &str, read: impl FnOnce(&Path) -> T) -> T {
+        let started = Instant::now();
+        let result = read(&self.db_path);
+        self.metrics()
+            .query_seconds
+            .entry(kind.to_owned())
+            .or_default()
+            .observe(started.elapsed());
+        result
+    }
+}
+
+type Web = State<Arc<Server>>;
+
+/// Upper bounds of the latency buckets in seconds, as Prometheus clients
+/// default to.
+const LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations at or below each bound of [`LATENCY_BUCKETS`].
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, label: &str, value: &str) {
+        for (count, bound) in self.buckets.iter().zip(LATENCY_BUCKETS) {
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{{label}=\"{value}\",le=\"{bound}\"}} {count}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "{name}_bucket{{{label}=\"{value}\",le=\"+Inf\"}} {}",
+            self.count
+        );
+        let _ = writeln!(out, "{name}_sum{{{label}=\"{value}\"}} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{{{label}=\"{value}\"}} {}", self.count);
+    }
+}
+
+/// What `/metrics` reports, kept in memory for the life of the server.
+/// Labels are route templates and tab names, never row data.
+#[derive(Debug, Default)]
+struct Metrics {
+    requests: BTreeMap<(String, u16), u64>,
+    request_seconds: BTreeMap<String, Histogram>,
+    query_seconds: BTreeMap<String, Histogram>,
+}
+
+impl Metrics {
+    /// The Prometheus text exposition of every metric.
+    fn render(&self, db_size_bytes: u64) -> String {
+        let mut out = String::new();
+        out.push_str(
+            "# HELP micasa_http_requests_total HTTP requests served, by route and status.\n",
+        );
+        out.push_str("# TYPE micasa_http_requests_total counter\n");
+        for ((route, status), count) in &self.requests {
+            let _ = writeln!(
+                out,
+                "micasa_http_requests_total{{route=\"{route}\",status=\"{status}\"}} {count}"
+            );
+        }
+        out.push_str(
+            "# HELP micasa_http_request_duration_seconds Time to answer a request, by route.\n",
+        );
+        out.push_str("# TYPE micasa_http_request_duration_seconds histogram\n");
+        for (route, histogram) in &self.request_seconds {
+            histogram.render(
+                &mut out,
+                "micasa_http_request_duration_seconds",
+                "route",
+                route,
+            );
+        }
+        out.push_str(
+            "# HELP micasa_query_duration_seconds Time spent reading the database, by what was read.\n",
+        );
+        out.push_str("# TYPE micasa_query_duration_seconds histogram\n");
+        for (kind, histogram) in &self.query_seconds {
+            histogram.render(&mut out, "micasa_query_duration_seconds", "kind", kind);
+        }
+        out.push_str("# HELP micasa_db_size_bytes Size of the database file.\n");
+        out.push_str("# TYPE micasa_db_size_bytes gauge\n");
+        let _ = writeln!(out, "micasa_db_size_bytes {db_size_bytes}");
+        out
+    }
+}
+
+/// Counts and times every routed request under its route template, so
+/// `/tabs/maint` and `/tabs/docs` share one `/tabs/{tab}` series.
+async fn track_request(State(server): Web, request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unknown", MatchedPath::as_str)
+        .to_owned();
+    let started = Instant::now();
+    let response = next.run(request).await;
+    let mut metrics = server.metrics();
+    *metrics
+        .requests
+        .entry((route.clone(), response.status().as_u16()))
+        .or_default() += 1;
+    metrics
+        .request_seconds
+        .entry(route)
+        .or_default()
+        .observe(started.elapsed());
+    response
+}
+
+async fn metrics_text(State(server): Web) -> Result<Response, WebError> {
+    let db_size = fs::metadata(&server.db_path)
+        .with_context(|| format!("read size of {}", server.db_path.display()))?
+        .len();
+    let body = server.metrics().render(db_size);
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
+        .into_response())
+}
+
+struct WebError(StatusCode, String);
+
+impl IntoResponse for WebError {
+    fn into_response(self) -> Response {
+        (self.0, self.1).into_response()
+    }
+}
+
+impl From<anyhow::Error> for WebError {
+    fn from(error: anyhow::Error) -> Self {
+        Self(StatusCode::INTERNAL_SERVER_ERROR, format!("{error:#}"))
+    }
+}
+
+fn not_found(what: impl Into<String>) -> WebError {
+    WebError(StatusCode::NOT_FOUND, what.into())
+}
+
+fn open_store(db_path: &Path) -> Result<Store> {
+    Store::open(db_path).with_context(|| format!("open database {}", db_path.display()))
+}
+
+/// A fresh runtime over `store`. Its tab and dashboard snapshots leave out
+/// private and archived rows, as the TUI does until they are shown.
+fn read_runtime(store: &Store) -> DbRuntime<'_> {
+    DbRuntime::with_llm_client_context_and_db_path(store, None, "", None)
+}
+
+fn web_tab(slug: &str) -> Result<TabKind, WebError> {
+    tab_for_slug(slug)
+        .filter(|tab| WEB_TABS.contains(tab))
+        .ok_or_else(|| not_found(format!("no tab named {slug:?}")))
+}
+
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct DashboardSection {
+    name: &'static str,
+    /// Everything the section counts, even past its row limit.
+    count: usize,
+    items: Vec<DashboardItem>,
+}
+
+/// One dashboard line, linked to the row it is about when there is one.
+#[derive(Debug, Serialize, PartialEq, Eq)]
+struct DashboardItem {
+    text: String,
+    tab: Option<&'static str>,
+    id: Option<i64>,
+}
+
+/// The dashboard as the TUI lists it, following the `dashboard.*` layout
+/// settings. Lines only link to tabs the web view serves.
+fn dashboard_sections(snapshot: &DashboardSnapshot) -> Vec<DashboardSection> {
+    micasa_tui::dashboard_sections(snapshot)
+        .into_iter()
+        .map(|section| DashboardSection {
+            name: section.section.label(),
+            count: section.count,
+            items: section
+                .rows
+                .into_iter()
+                .map(|row| {
+                    let target = row.target.filter(|(tab, _)| WEB_TABS.contains(tab));
+                    DashboardItem {
+                        text: row.text,
+                        tab: target.map(|(tab, _)| tab.label()),
+                        id: target.map(|(_, id)| id),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn load_dashboard(db_path: &Path) -> Result<Vec<DashboardSection>> {
+    let store = open_store(db_path)?;
+    let mut runtime = read_runtime(&store);
+    let mut snapshot = runtime.load_dashboard_snapshot()?;
+    snapshot.layout = runtime.dashboard_layout()?;
+    Ok(dashboard_sections(&snapshot))
+}
+
+fn load_table(db_path: &Path, tab: TabKind) -> Result<TableExport> {
+    let store = open_store(db_path)?;
+    let mut runtime = read_runtime(&store);
+    let snapshot = runtime
+        .load_tab_snapshot(tab, false)?
+        .ok_or_else(|| anyhow!("{} has no rows to show", tab.label()))?;
+    let names = runtime.link_names()?;
+    Ok(TableExport::from_snapshot(tab, &snapshot, &names))
+}
+
+fn nav() -> String {
+    let links = WEB_TABS
+        .iter()
+        .map(|tab| format!("<a href=\"/tabs/{0}\">{0}</a>", tab.label()))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!("<p><a href=\"/\">dashboard</a> | {links}</p>\n")
+}
+
+async fn dashboard_page(State(server): Web) -> Result<Html<String>, WebError> {
+    let sections = server.query("dashboard", load_dashboard)?;
+    let mut body = nav();
+    if sections.is_empty() {
+        body.push_str("<p>Nothing needs attention.</p>\n");
+    }
+    for section in &sections {
+        body.push_str(&format!(
+            "<h2>{} ({})</h2>\n<ul>\n",
+            escape_html(section.name),
+            section.count
+        ));
+        for item in &section.items {
+            let text = escape_html(&item.text);
+            match (item.tab, item.id) {
+                (Some(tab), Some(id)) => body.push_str(&format!(
+                    "<li><a href=\"/tabs/{tab}#row-{id}\">{text}</a></li>\n"
+                )),
+                _ => body.push_str(&format!("<li>{text}</li>\n")),
+            }
+        }
+        body.push_str("</ul>\n");
+    }
+    Ok(Html(html_page("micasa", &body)))
+}
+
+async fn tab_page(
+    State(server): Web,
+    UrlPath(slug): UrlPath<String>,
+) -> Result<Html<String>, WebError> {
+    let tab = web_tab(&slug)?;
+    let table = server.query(tab.label(), |db_path| load_table(db_path, tab))?;
+    let mut body = nav();
+    body.push_str("<table>\n<tr>");
+    for column in &table.columns {
+        body.push_str(&format!("<th>{}</th>", escape_html(column)));
+    }
+    body.push_str("</tr>\n");
+    for (id, row) in table.row_ids.iter().zip(&table.rows) {
+        body.push_str(&format!("<tr id=\"row-{id}\">"));
+        for (index, cell) in row.iter().enumerate() {
+            let cell = escape_html(cell);
+            if tab == TabKind::Documents && index == 0 {
+                body.push_str(&format!("<td><a href=\"/documents/{id}\">{cell}</a></td>"));
+            } else {
+                body.push_str(&format!("<td>{cell}</td>"));
+            }
+        }
+        body.push_str("</tr>\n");
+    }
+    body.push_str("</table>\n");
+    Ok(Html(html_page(&table.title, &body)))
+}
+
+async fn dashboard_json(State(server): Web) -> Result<Json<Vec<DashboardSection>>, WebError> {
+    Ok(Json(server.query("dashboard", load_dashboard)?))
+}
+
+#[derive(Debug, Serialize)]
+struct TabRows {
+    tab: &'static str,
+    title: String,
+    columns: Vec<String>,
+    rows: Vec<TabRow>,
+}
+
+#[derive(Debug, Serialize)]
+struct TabRow {
+    id: i64,
+    cells: Vec<String>,
+}
+
+async fn tab_json(
+    State(server): Web,
+    UrlPath(slug): UrlPath<String>,
+) -> Result<Json<TabRows>, WebError> {
+    let tab = web_tab(&slug)?;
+    let table = server.query(tab.label(), |db_path| load_table(db_path, tab))?;
+    Ok(Json(TabRows {
+        tab: tab.label(),
+        title: table.title,
+        columns: table.columns,
+        rows: table
+            .row_ids
+            .into_iter()
+            .zip(table.rows)
+            .map(|(id, cells)| TabRow { id, cells })
+            .collect(),
+    }))
+}
+
+async fn document_file(
+    State(server): Web,
+    UrlPath(id): UrlPath<i64>,
+) -> Result<Response, WebError> {
+    server.query("document", |db_path| load_document(db_path, id))
+}
+
+fn load_document(db_path: &Path, id: i64) -> Result<Response, WebError> {
+    let store = open_store(db_path)?;
+    let missing = || not_found(format!("no document {id}"));
+    // Same rows the docs tab leaves out: private and archived ones.
+    if store
+        .private_row_ids(DeletionEntity::Document)?
+        .contains(&id)
+        || store
+            .archived_row_ids(DeletionEntity::Document)?
+            .contains(&id)
+    {
+        return Err(missing());
+    }
+    // The web view never holds the passphrase, so sealed files stay shut.
+    if store.documents_locked()? {
+        if !store
+            .list_documents(false)?
+            .iter()
+            .any(|document| document.id.get() == id)
+        {
+            return Err(missing());
+        }
+        return Err(WebError(
+            StatusCode::LOCKED,
+            format!(
+                "document {id} is encrypted and micasa serve can't unlock it; \
+                 open it in micasa, which asks for the passphrase at startup"
+            ),
+        ));
+    }
+    let document = store
+        .get_document(DocumentId::new(id))
+        .map_err(|_| missing())?;
+    if document.deleted_at.is_some() {
+        return Err(missing());
+    }
+    let mime_type = if document.mime_type.is_empty() {
+        "application/octet-stream".to_owned()
+    } else {
+        document.mime_type
+    };
+    let file_name = document.file_name.replace(['"', '\\', '\r', '\n'], "_");
+    Ok((
+        [
+            (header::CONTENT_TYPE, mime_type),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("inline; filename=\"{file_name}\""),
+            ),
+        ],
+        document.data,
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_LISTEN, Histogram, Metrics, run, serve};
+    use anyhow::Result;
+    use micasa_app::{DocumentEntityKind, SettingKey, SettingValue};
+    use micasa_db::{LifecycleEntityRef, NewDocument, Store};
+    use std::net::TcpListener;
+    use std::thread;
+    use std::time::Duration;
+
+    fn document(title: &str, data: &[u8]) -> NewDocument {
+        NewDocument {
+            title: title.to_owned(),
+            file_name: format!("{title}.txt"),
+            entity_kind: DocumentEntityKind::None,
+            entity_id: 0,
+            mime_type: "text/plain".to_owned(),
+            data: data.to_vec(),
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn serves_dashboard_tabs_and_documents_but_not_private_or_archived_rows() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let (shared, private, archived) = {
+            let store = Store::open(&db_path)?;
+            store.bootstrap()?;
+            store.seed_demo_data()?;
+            let shared = store.insert_document(&document("Furnace manual", b"filter: 16x25"))?;
+            let private = store.insert_document(&document("Deed", b"secret"))?;
+            store.set_row_private(LifecycleEntityRef::Document(private), true)?;
+            let archived = store.insert_document(&document("Old lease", b"expired"))?;
+            store.set_row_archived(LifecycleEntityRef::Document(archived), true)?;
+            (shared, private, archived)
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base = format!("http://{}", listener.local_addr()?);
+        thread::spawn(move || run(listener, db_path));
+        let get = |path: &str| reqwest::blocking::get(format!("{base}{path}"));
+
+        let home = get("/")?;
+        assert!(home.status().is_success());
+        let home = home.text()?;
+        assert!(home.contains("<a href=\"/tabs/maint\">maint</a>"), "{home}");
+
+        let maint: serde_json::Value = get("/api/tabs/maintenance")?.json()?;
+        assert_eq!(maint["tab"], "maint");
+        assert!(!maint["rows"].as_array().expect("rows").is_empty());
+        assert!(
+            get("/api/dashboard")?
+                .json::<serde_json::Value>()?
+                .is_array()
+        );
+
+        let docs = get("/tabs/docs")?.text()?;
+        assert!(docs.contains("Furnace manual"));
+        assert!(!docs.contains("Deed"), "private rows stay hidden");
+        assert!(!docs.contains("Old lease"), "archived rows stay hidden");
+
+        let file = get(&format!("/documents/{}", shared.get()))?;
+        assert_eq!(file.headers()["content-type"], "text/plain");
+        assert_eq!(file.text()?, "filter: 16x25");
+        let hidden = get(&format!("/documents/{}", private.get()))?;
+        assert_eq!(hidden.status(), 404);
+        let archived = get(&format!("/documents/{}", archived.get()))?;
+        assert_eq!(archived.status(), 404);
+        assert_eq!(get("/tabs/settings")?.status(), 404);
+        Ok(())
+    }
+
+    #[test]
+    fn encrypted_documents_answer_locked_with_a_hint() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let policy = {
+            let store = Store::open(&db_path)?;
+            store.bootstrap()?;
+            let policy = store.insert_document(&document("Policy", b"policy number 42"))?;
+            store.encrypt_documents("secret")?;
+            policy
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base = format!("http://{}", listener.local_addr()?);
+        thread::spawn(move || run(listener, db_path));
+        let get = |path: &str| reqwest::blocking::get(format!("{base}{path}"));
+
+        let locked = get(&format!("/documents/{}", policy.get()))?;
+        assert_eq!(locked.status(), 423);
+        let text = locked.text()?;
+        assert!(text.contains("asks for the passphrase"), "{text}");
+        assert_eq!(get("/documents/9999")?.status(), 404);
+        Ok(())
+    }
+
+    #[test]
+    fn dashboard_follows_the_layout_settings() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        let full = {
+            let store = Store::open(&db_path)?;
+            store.bootstrap()?;
+            store.seed_demo_data()?;
+            super::load_dashboard(&db_path)?
+        };
+        let shown = full.iter().map(|section| section.name).collect::<Vec<_>>();
+        assert!(shown.len() >= 2, "{shown:?}");
+        let (first, last) = (&full[0], &full[full.len() - 1]);
+        {
+            let store = Store::open(&db_path)?;
+            let setting = |name: &str| {
+                micasa_tui::DashboardSection::ALL
+                    .into_iter()
+                    .find(|section| section.label() == name)
+                    .expect("known section")
+                    .setting_name()
+            };
+            store.put_setting(
+                SettingKey::DashboardSections,
+                SettingValue::Text(format!("{},{}", setting(last.name), setting(first.name))),
+            )?;
+            store.put_setting(
+                SettingKey::DashboardRowLimits,
+                SettingValue::Text(format!("{}=1", setting(first.name))),
+            )?;
+        }
+
+        let sections = super::load_dashboard(&db_path)?;
+        let mut names = sections
+            .iter()
+            .map(|section| section.name)
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        let mut expected = vec![first.name, last.name];
+        expected.sort_unstable();
+        assert_eq!(names, expected, "hidden sections stay hidden");
+        let limited = sections
+            .iter()
+            .find(|section| section.name == first.name)
+            .expect("first section");
+        assert_eq!(limited.items.len(), 1);
+        assert_eq!(limited.count, first.count, "the count ignores the limit");
+        Ok(())
+    }
+
+    #[test]
+    fn metrics_count_requests_by_route_and_report_db_size() -> Result<()> {
+        let temp = tempfile::tempdir()?;
+        let db_path = temp.path().join("house.db");
+        {
+            let store = Store::open(&db_path)?;
+            store.bootstrap()?;
+            store.seed_demo_data()?;
+        }
+        let db_size = std::fs::metadata(&db_path)?.len();
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base = format!("http://{}", listener.local_addr()?);
+        thread::spawn(move || run(listener, db_path));
+        let get = |path: &str| reqwest::blocking::get(format!("{base}{path}"));
+
+        assert!(get("/tabs/maint")?.status().is_success());
+        assert!(get("/api/tabs/docs")?.status().is_success());
+        assert_eq!(get("/tabs/settings")?.status(), 404);
+        assert!(get("/")?.status().is_success());
+
+        let metrics = get("/metrics")?;
+        assert_eq!(
+            metrics.headers()["content-type"],
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+        let metrics = metrics.text()?;
+        for line in [
+            "micasa_http_requests_total{route=\"/tabs/{tab}\",status=\"200\"} 1",
+            "micasa_http_requests_total{route=\"/tabs/{tab}\",status=\"404\"} 1",
+            "micasa_http_requests_total{route=\"/api/tabs/{tab}\",status=\"200\"} 1",
+            "micasa_http_request_duration_seconds_count{route=\"/\"} 1",
+            "micasa_query_duration_seconds_count{kind=\"maint\"} 1",
+            "micasa_query_duration_seconds_count{kind=\"docs\"} 1",
+            "micasa_query_duration_seconds_count{kind=\"dashboard\"} 1",
+            "# TYPE micasa_db_size_bytes gauge",
+            &format!("micasa_db_size_bytes {db_size}"),
+        ] {
+            assert!(
+                metrics.lines().any(|found| found == line),
+                "{line}\n{metrics}"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::default();
+        histogram.observe(Duration::from_millis(3));
+        histogram.observe(Duration::from_millis(200));
+        histogram.observe(Duration::from_secs(30));
+        let mut metrics = Metrics::default();
+        metrics.request_seconds.insert("/".to_owned(), histogram);
+        let text = metrics.render(0);
+        for line in [
+            "micasa_http_request_duration_seconds_bucket{route=\"/\",le=\"0.005\"} 1",
+            "micasa_http_request_duration_seconds_bucket{route=\"/\",le=\"0.25\"} 2",
+            "micasa_http_request_duration_seconds_bucket{route=\"/\",le=\"10\"} 2",
+            "micasa_http_request_duration_seconds_bucket{route=\"/\",le=\"+Inf\"} 3",
+            "micasa_http_request_duration_seconds_count{route=\"/\"} 3",
+        ] {
+            assert!(text.lines().any(|found| found == line), "{line}\n{text}");
+        }
+    }
+
+    #[test]
+    fn serve_rejects_a_bad_listen_address() {
+        let error = serve("micasa.db".into(), "localhost").expect_err("needs a port");
+        assert!(error.to_string().contains(DEFAULT_LISTEN));
+    }
+}
@@ -0,0 +1,499 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use crate::config::keymap_from_table;
+use anyhow::{Context, Result, anyhow, bail};
+use micasa_app::{
+    AlertStyle, SavedView, SettingKey, SettingValue, SettingValueKind, SortDirection, TabKind,
+    parse_auto_refresh, tab_for_slug,
+};
+use micasa_db::Store;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const SETTINGS_EXPORT_VERSION: i64 = 1;
+
+/// What one import applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettingsImport {
+    pub settings: usize,
+    pub views: usize,
+    /// Whether keys.toml was rewritten from the file's `[keys]` table.
+    pub keys: bool,
+}
+
+/// Renders every persisted setting, the saved views of every tab, and the
+/// key overrides in `keys_path` as a portable, versioned TOML document.
+pub fn export_settings(store: &Store, keys_path: &Path) -> Result<String> {
+    let mut settings = toml::Table::new();
+    for setting in store.list_settings()? {
+        let value = match setting.value {
+            SettingValue::Bool(value) => toml::Value::Boolean(value),
+            SettingValue::Text(value) => toml::Value::String(value),
+            SettingValue::Days(days) => toml::Value::Integer(days),
+        };
+        settings.insert(setting.key.as_str().to_owned(), value);
+    }
+
+    let mut document = toml::Table::new();
+    document.insert(
+        "version".to_owned(),
+        toml::Value::Integer(SETTINGS_EXPORT_VERSION),
+    );
+    document.insert("settings".to_owned(), toml::Value::Table(settings));
+    document.insert("keys".to_owned(), toml::Value::Table(read_keys(keys_path)?));
+    let mut views = Vec::new();
+    for tab in TabKind::ALL {
+        for name in store.list_views(tab)? {
+            if let Some(view) = store.load_view(tab, &name)? {
+                views.push(toml::Value::Table(view_table(tab, &view)));
+            }
+        }
+    }
+    document.insert("views".to_owned(), toml::Value::Array(views));
+    toml::to_string(&document).context("serialize settings export")
+}
+
+/// keys.toml as a table; a missing file means no overrides.
+fn read_keys(keys_path: &Path) -> Result<toml::Table> {
+    if !keys_path.exists() {
+        return Ok(toml::Table::new());
+    }
+    let raw = fs::read_to_string(keys_path)
+        .with_context(|| format!("read keys file {}", keys_path.display()))?;
+    toml::from_str(&raw).with_context(|| format!("parse TOML keys {}", keys_path.display()))
+}
+
+fn view_table(tab: TabKind, view: &SavedView) -> toml::Table {
+    let strings = |items: Vec<String>| {
+        toml::Value::Array(items.into_iter().map(toml::Value::String).collect())
+    };
+    let mut table = toml::Table::new();
+    table.insert(
+        "tab".to_owned(),
+        toml::Value::String(tab.label().to_owned()),
+    );
+    table.insert("name".to_owned(), toml::Value::String(view.name.clone()));
+    table.insert(
+        "sorts".to_owned(),
+        strings(
+            view.sorts
+                .iter()
+                .map(|(column, direction)| match direction {
+                    SortDirection::Asc => format!("asc:{column}"),
+                    SortDirection::Desc => format!("desc:{column}"),
+                })
+                .collect(),
+        ),
+    );
+    if let Some((column, value)) = &view.pin {
+        table.insert("pin_column".to_owned(), toml::Value::String(column.clone()));
+        table.insert("pin_value".to_owned(), toml::Value::String(value.clone()));
+    }
+    table.insert(
+        "filter_active".to_owned(),
+        toml::Value::Boolean(view.filter_active),
+    );
+    table.insert(
+        "filter_inverted".to_owned(),
+        toml::Value::Boolean(view.filter_inverted),
+    );
+    table.insert(
+        "hidden_columns".to_owned(),
+        strings(view.hidden_columns.clone()),
+    );
+    table.insert(
+        "hide_settled".to_owned(),
+        toml::Value::Boolean(view.hide_settled),
+    );
+    table
+}
+
+/// A `[[views]]` entry as the tab it belongs to and the view.
+fn parse_view(entry: &toml::Value) -> Result<(TabKind, SavedView)> {
+    let table = entry
+        .as_table()
+        .ok_or_else(|| anyhow!("each [[views]] entry must be a table"))?;
+    let text = |field: &str| table.get(field).and_then(toml::Value::as_str);
+    let flag = |field: &str| -> Result<bool> {
+        match table.get(field) {
+            None => Ok(false),
+            Some(toml::Value::Boolean(value)) => Ok(*value),
+            Some(_) => bail!("view field `{field}` must be true or false"),
+        }
+    };
+    let strings = |field: &str| -> Result<Vec<String>> {
+        match table.get(field) {
+            None => Ok(Vec::new()),
+            Some(toml::Value::Array(items)) => items
+                .iter()
+                .map(|item| {
+                    item.as_str()
+                        .map(str::to_owned)
+                        .ok_or_else(|| anyhow!("view field `{field}` must list strings"))
+                })
+                .collect(),
+            Some(_) => bail!("view field `{field}` must be a list of strings"),
+        }
+    };
+    let name = text("name")
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| anyhow!("a [[views]] entry is missing `name`"))?;
+    let slug = text("tab").ok_or_else(|| anyhow!("view `{name}` is missing `tab`"))?;
+    let tab =
+        tab_for_slug(slug).ok_or_else(|| anyhow!("view `{name}` names unknown tab `{slug}`"))?;
+    let sorts = strings("sorts")?
+        .into_iter()
+        .map(|sort| match sort.split_once(':') {
+            Some(("asc", column)) => Ok((column.to_owned(), SortDirection::Asc)),
+            Some(("desc", column)) => Ok((column.to_owned(), SortDirection::Desc)),
+            _ => Err(anyhow!(
+                "view `{name}` has sort `{sort}`; use \"asc:column\" or \"desc:column\""
+            )),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let pin = text("pin_column")
+        .map(str::to_owned)
+        .zip(text("pin_value").map(str::to_owned));
+    Ok((
+        tab,
+        SavedView {
+            name: name.to_owned(),
+            sorts,
+            pin,
+            filter_active: flag("filter_active")?,
+            filter_inverted: flag("filter_inverted")?,
+            hidden_columns: strings("hidden_columns")?,
+            hide_settled: flag("hide_settled")?,
+        },
+    ))
+}
+
+/// Validates the whole document before writing anything, and writes settings
+/// and views in one batch, so a bad file never leaves the database
+/// half-imported. A `[keys]` table replaces keys.toml at
+/// `keys_path`; saved views replace any of the same tab and name.
+pub fn import_settings(store: &Store, raw: &str, keys_path: &Path) -> Result<SettingsImport> {
+    let document: toml::Table = toml::from_str(raw).context("parse settings TOML")?;
+
+    let version = document
+        .get("version")
+        .and_then(toml::Value::as_integer)
+        .ok_or_else(|| {
+            anyhow!("settings file is missing `version = 1`; re-export it with --export-settings")
+        })?;
+    if version != SETTINGS_EXPORT_VERSION {
+        bail!(
+            "unsupported settings file version {version}; expected {SETTINGS_EXPORT_VERSION} -- re-export it with this micasa build"
+        );
+    }
+
+    let empty = toml::Table::new();
+    let entries = match document.get("settings") {
+        Some(entries) => entries
+            .as_table()
+            .ok_or_else(|| anyhow!("[settings] must be a table of `key = value` entries"))?,
+        None => &empty,
+    };
+
+    let mut parsed = BTreeMap::new();
+    for (name, value) in entries {
+        let key = SettingKey::parse(name).ok_or_else(|| {
+            anyhow!(
+                "unknown setting `{name}`; supported keys: {}",
+                SettingKey::ALL.map(SettingKey::as_str).join(", ")
+            )
+        })?;
+        let value = match (key.expected_value_kind(), value) {
+            (SettingValueKind::Bool, toml::Value::Boolean(value)) => SettingValue::Bool(*value),
+            (SettingValueKind::Text, toml::Value::String(value))
+                if key.alert_event().is_some() && AlertStyle::parse(value).is_none() =>
+            {
+                bail!("setting `{name}` must be off, bell, flash, or both")
+            }
+            (SettingValueKind::Text, toml::Value::String(value))
+                if key == SettingKey::UiAutoRefresh && parse_auto_refresh(value).is_none() =>
+            {
+                bail!("setting `{name}` must be a whole number of seconds, like \"60\"")
+            }
+            (SettingValueKind::Text, toml::Value::String(value)) => {
+                SettingValue::Text(value.clone())
+            }
+            (SettingValueKind::Days, toml::Value::Integer(days)) if *days >= 0 => {
+                SettingValue::Days(*days)
+            }
+            (SettingValueKind::Bool, _) => {
+                bail!("setting `{name}` must be true or false")
+            }
+            (SettingValueKind::Days, _) => {
+                bail!("setting `{name}` must be a whole number of days, like 30")
+            }
+            (SettingValueKind::Text, _) => {
+                bail!("setting `{name}` must be a quoted string")
+            }
+        };
+        parsed.insert(name.as_str(), (key, value));
+    }
+
+    let keys = match document.get("keys") {
+        Some(toml::Value::Table(keys)) => {
+            keymap_from_table(keys, "[keys]")?;
+            Some(keys)
+        }
+        Some(_) => bail!("[keys] must be a table of `action = \"chord\"` entries"),
+        None => None,
+    };
+    let views = match document.get("views") {
+        Some(toml::Value::Array(entries)) => {
+            entries.iter().map(parse_view).collect::<Result<Vec<_>>>()?
+        }
+        Some(_) => bail!("views must be a list of [[views]] tables"),
+        None => Vec::new(),
+    };
+
+    let settings = parsed.values().cloned().collect::<Vec<_>>();
+    store.apply_settings(&settings, &views)?;
+    if let Some(keys) = keys {
+        let rendered = toml::to_string(keys).context("serialize key bindings")?;
+        fs::write(keys_path, rendered)
+            .with_context(|| format!("write keys file {}", keys_path.display()))?;
+    }
+    Ok(SettingsImport {
+        settings: parsed.len(),
+        views: views.len(),
+        keys: keys.is_some(),
+    })
+}
+
+pub fn export_settings_to_path(store: &Store, path: &Path, keys_path: &Path) -> Result<()> {
+    let rendered = export_settings(store, keys_path)?;
+    fs::write(path, rendered).with_context(|| format!("write settings export {}", path.display()))
+}
+
+pub fn import_settings_from_path(
+    store: &Store,
+    path: &Path,
+    keys_path: &Path,
+) -> Result<SettingsImport> {
+    let raw = fs::read_to_string(path)
+        .with_context(|| format!("read settings file {}", path.display()))?;
+    import_settings(store, &raw, keys_path)
+        .with_context(|| format!("import settings {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SettingsImport, export_settings, import_settings};
+    use anyhow::Result;
+    use micasa_app::{
+        AlertEvent, AlertStyle, SavedView, SettingKey, SettingValue, SortDirection, TabKind,
+    };
+    use micasa_db::Store;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn bootstrapped_store() -> Result<Store> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        Ok(store)
+    }
+
+    /// A keys.toml path that doesn't exist yet.
+    fn keys_path(dir: &Path) -> PathBuf {
+        dir.join("keys.toml")
+    }
+
+    fn import(store: &Store, raw: &str) -> Result<SettingsImport> {
+        let temp = tempfile::tempdir()?;
+        import_settings(store, raw, &keys_path(temp.path()))
+    }
+
+    #[test]
+    fn export_then_import_round_trips_settings() -> Result<()> {
+        let source = bootstrapped_store()?;
+        source.put_setting(SettingKey::UiShowDashboard, SettingValue::Bool(false))?;
+        source.put_setting(
+            SettingKey::LlmModel,
+            SettingValue::Text("qwen3:32b".to_owned()),
+        )?;
+        let temp = tempfile::tempdir()?;
+        let exported = export_settings(&source, &keys_path(temp.path()))?;
+        assert!(exported.contains("version = 1"));
+        assert!(exported.contains("\"ui.show_dashboard\" = false"));
+
+        let target = bootstrapped_store()?;
+        let applied = import(&target, &exported)?;
+        assert_eq!(applied.settings, SettingKey::ALL.len());
+        assert_eq!(
+            target.get_setting(SettingKey::UiShowDashboard)?,
+            Some(SettingValue::Bool(false))
+        );
+        assert_eq!(
+            target.get_setting(SettingKey::LlmModel)?,
+            Some(SettingValue::Text("qwen3:32b".to_owned()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn import_rejects_unknown_keys_without_partial_writes() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let error = import(
+            &store,
+            "version = 1\n[settings]\n\"llm.model\" = \"qwen3\"\n\"ui.nope\" = true\n",
+        )
+        .expect_err("unknown key should fail");
+        let message = error.to_string();
+        assert!(message.contains("unknown setting `ui.nope`"));
+        assert!(message.contains("llm.model"));
+        assert_eq!(store.get_setting(SettingKey::LlmModel)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn import_rejects_mismatched_value_types() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let error = import(
+            &store,
+            "version = 1\n[settings]\n\"ui.show_dashboard\" = \"maybe\"\n",
+        )
+        .expect_err("string for bool should fail");
+        assert!(error.to_string().contains("must be true or false"));
+
+        let error = import(
+            &store,
+            "version = 1\n[settings]\n\"notify.warranty_days\" = -7\n",
+        )
+        .expect_err("negative days should fail");
+        assert!(error.to_string().contains("whole number of days"));
+        import(
+            &store,
+            "version = 1\n[settings]\n\"notify.warranty_days\" = 60\n",
+        )?;
+        assert_eq!(store.get_notice_days(SettingKey::NotifyWarrantyDays)?, 60);
+
+        let error = import(
+            &store,
+            "version = 1\n[settings]\n\"alert.chat_edit\" = \"siren\"\n",
+        )
+        .expect_err("unknown alert style should fail");
+        assert!(error.to_string().contains("off, bell, flash, or both"));
+        import(
+            &store,
+            "version = 1\n[settings]\n\"alert.chat_edit\" = \"Flash\"\n",
+        )?;
+        assert_eq!(
+            store.get_alert_style(AlertEvent::ChatEdit)?,
+            AlertStyle::Flash
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn export_then_import_round_trips_saved_views() -> Result<()> {
+        let source = bootstrapped_store()?;
+        let view = SavedView {
+            name: "Due soon".to_owned(),
+            sorts: vec![("next".to_owned(), SortDirection::Asc)],
+            pin: Some(("category".to_owned(), "HVAC".to_owned())),
+            filter_active: true,
+            filter_inverted: false,
+            hidden_columns: vec!["notes".to_owned(), "cost".to_owned()],
+            hide_settled: true,
+        };
+        source.save_view(TabKind::Maintenance, &view)?;
+        let temp = tempfile::tempdir()?;
+        let exported = export_settings(&source, &keys_path(temp.path()))?;
+        assert!(exported.contains("[[views]]"), "{exported}");
+
+        let target = bootstrapped_store()?;
+        assert_eq!(import(&target, &exported)?.views, 1);
+        assert_eq!(
+            target.load_view(TabKind::Maintenance, "Due soon")?,
+            Some(view)
+        );
+
+        let error = import(
+            &target,
+            "version = 1\n[[views]]\ntab = \"garage\"\nname = \"x\"\n",
+        )
+        .expect_err("unknown tab should fail");
+        assert!(error.to_string().contains("unknown tab `garage`"));
+        Ok(())
+    }
+
+    #[test]
+    fn import_with_a_bad_view_leaves_existing_settings_unchanged() -> Result<()> {
+        let store = bootstrapped_store()?;
+        store.put_setting(SettingKey::LlmModel, SettingValue::Text("qwen3".to_owned()))?;
+        let error = import(
+            &store,
+            "version = 1\n[settings]\n\"llm.model\" = \"llama3\"\n\
+             [[views]]\ntab = \"incidents\"\nname = \"Open\"\n\
+             [[views]]\ntab = \"incidents\"\nname = \"Late\"\nsorts = [\"up:title\"]\n",
+        )
+        .expect_err("bad sort should fail");
+        assert!(error.to_string().contains("asc:column"), "{error:#}");
+        assert_eq!(
+            store.get_setting(SettingKey::LlmModel)?,
+            Some(SettingValue::Text("qwen3".to_owned()))
+        );
+        assert!(store.list_views(TabKind::Incidents)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn export_then_import_round_trips_key_bindings() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let source = tempfile::tempdir()?;
+        fs::write(
+            keys_path(source.path()),
+            "half_page_down = [\"ctrl+d\", \"J\"]\nqr_link = []\n",
+        )?;
+        let exported = export_settings(&store, &keys_path(source.path()))?;
+        assert!(exported.contains("[keys]"), "{exported}");
+
+        let target = tempfile::tempdir()?;
+        let applied = import_settings(&store, &exported, &keys_path(target.path()))?;
+        assert!(applied.keys);
+        let keymap = crate::config::load_keymap(&keys_path(target.path()))?;
+        assert_eq!(
+            keymap,
+            crate::config::load_keymap(&keys_path(source.path()))?
+        );
+        assert_ne!(keymap, micasa_tui::keymap::Keymap::default());
+
+        let fresh = bootstrapped_store()?;
+        let error = import_settings(
+            &fresh,
+            "version = 1\n[settings]\n\"llm.model\" = \"qwen3\"\n[keys]\nnot_an_action = \"z\"\n",
+            &keys_path(target.path()),
+        )
+        .expect_err("unknown action should fail");
+        assert!(format!("{error:#}").contains("not_an_action"), "{error:#}");
+        assert_eq!(fresh.get_setting(SettingKey::LlmModel)?, None);
+        assert_eq!(
+            crate::config::load_keymap(&keys_path(target.path()))?,
+            keymap,
+            "a rejected file leaves keys.toml alone"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn import_requires_supported_version() -> Result<()> {
+        let store = bootstrapped_store()?;
+        let missing = import(&store, "[settings]\n").expect_err("missing version");
+        assert!(missing.to_string().contains("--export-settings"));
+
+        let future = import(&store, "version = 9\n").expect_err("future version");
+        assert!(
+            future
+                .to_string()
+                .contains("unsupported settings file version 9")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Context, Result, bail};
+use micasa_db::Store;
+use micasa_db::validation::parse_required_date;
+use micasa_db::weather::{ForecastDay, parse_location};
+use serde::Deserialize;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+pub const DEFAULT_WEATHER_PROVIDER: &str = "open-meteo";
+pub const DEFAULT_WEATHER_BASE_URL: &str = "https://api.open-meteo.com/v1/forecast";
+pub const WEATHER_PROVIDERS: &[&str] = &[DEFAULT_WEATHER_PROVIDER];
+const FORECAST_DAYS: usize = 7;
+/// A cached forecast older than this is refreshed on startup.
+const FORECAST_MAX_AGE: time::Duration = time::Duration::hours(6);
+
+/// Applies `--set-location`: `LAT,LON` saves coordinates, `none` clears them.
+pub fn set_location(store: &Store, raw: &str) -> Result<String> {
+    if raw.trim().eq_ignore_ascii_case("none") {
+        store.set_house_location(None)?;
+        return Ok("location cleared".to_owned());
+    }
+    let (lat, lon) = parse_location(raw)?;
+    store.set_house_location(Some((lat, lon)))?;
+    Ok(format!("location set to {lat},{lon}"))
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoResponse {
+    daily: OpenMeteoDaily,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenMeteoDaily {
+    time: Vec<String>,
+    temperature_2m_min: Vec<Option<f64>>,
+    temperature_2m_max: Vec<Option<f64>>,
+    precipitation_sum: Vec<Option<f64>>,
+    wind_speed_10m_max: Vec<Option<f64>>,
+}
+
+/// Downloads a daily forecast for the house location and replaces the cache.
+/// Returns how many days were saved.
+pub fn fetch_forecast(
+    store: &Store,
+    provider: &str,
+    base_url: &str,
+    timeout: Duration,
+) -> Result<usize> {
+    if provider != DEFAULT_WEATHER_PROVIDER {
+        bail!("weather provider `{provider}` is not supported; use one of {WEATHER_PROVIDERS:?}");
+    }
+    let (lat, lon) = store
+        .get_house_profile()?
+        .and_then(|house| house.latitude.zip(house.longitude))
+        .ok_or_else(|| {
+            anyhow::anyhow!("house has no location; run `micasa --set-location LAT,LON` first")
+        })?;
+
+    let http = reqwest::blocking::Client::builder()
+        .timeout(timeout)
+        .build()
+        .context("build HTTP client")?;
+    let response = http
+        .get(base_url)
+        .query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            (
+                "daily",
+                "temperature_2m_min,temperature_2m_max,precipitation_sum,wind_speed_10m_max"
+                    .to_owned(),
+            ),
+            ("timezone", "auto".to_owned()),
+            ("forecast_days", FORECAST_DAYS.to_string()),
+        ])
+        .send()
+        .with_context(|| format!("fetch forecast from {base_url}; check weather.base_url"))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("weather provider {base_url} answered {status}; check weather.base_url");
+    }
+    let fetched: OpenMeteoResponse = response.json().with_context(|| {
+        format!("decode forecast from {base_url}; expected an Open-Meteo `daily` object")
+    })?;
+    let days = parse_daily(fetched.daily)?;
+    store.replace_weather_forecast(&days)?;
+    Ok(days.len())
+}
+
+/// Startup refresh: fetches only when a location is set and the cached
+/// forecast is missing or stale. Returns whether a fetch happened.
+pub fn refresh_if_stale(
+    store: &Store,
+    provider: &str,
+    base_url: &str,
+    timeout: Duration,
+) -> Result<bool> {
+    let has_location = store
+        .get_house_profile()?
+        .is_some_and(|house| house.latitude.is_some() && house.longitude.is_some());
+    if !has_location || !forecast_is_stale(store, OffsetDateTime::now_utc())? {
+        return Ok(false);
+    }
+    fetch_forecast(store, provider, base_url, timeout)?;
+    Ok(true)
+}
+
+fn forecast_is_stale(store: &Store, now: OffsetDateTime) -> Result<bool> {
+    Ok(store
+        .weather_fetched_at()?
+        .is_none_or(|fetched_at| now - fetched_at > FORECAST_MAX_AGE))
+}
+
+fn parse_daily(daily: OpenMeteoDaily) -> Result<Vec<ForecastDay>> {
+    let len = daily.time.len();
+    if [
+        daily.temperature_2m_min.len(),
+        daily.temperature_2m_max.len(),
+        daily.precipitation_sum.len(),
+        daily.wind_speed_10m_max.len(),
+    ]
+    .iter()
+    .any(|other| *other != len)
+    {
+        bail!("forecast columns have different lengths; the provider response is malformed");
+    }
+    let mut days = Vec::with_capacity(len);
+    for (index, raw_day) in daily.time.iter().enumerate() {
+        let day = parse_required_date(raw_day)
+            .with_context(|| format!("forecast day `{raw_day}` is not YYYY-MM-DD"))?;
+        // Providers leave gaps as null at the edge of their range; skip those days.
+        let (Some(min_temp_c), Some(max_temp_c), Some(precipitation_mm), Some(wind_kmh)) = (
+            daily.temperature_2m_min[index],
+            daily.temperature_2m_max[index],
+            daily.precipitation_sum[index],
+            daily.wind_speed_10m_max[index],
+        ) else {
+            continue;
+        };
+        days.push(ForecastDay {
+            day,
+            min_temp_c,
+            max_temp_c,
+            precipitation_mm,
+            wind_kmh,
+        });
+    }
+    Ok(days)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DEFAULT_WEATHER_PROVIDER, fetch_forecast, forecast_is_stale, refresh_if_stale, set_location,
+    };
+    use anyhow::{Result, anyhow};
+    use micasa_db::Store;
+    use std::thread;
+    use std::time::Duration;
+    use time::{Date, Month, OffsetDateTime};
+    use tiny_http::{Response, Server};
+
+    fn store_with_house() -> Result<Store> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        store.seed_demo_data_with_seed(7)?;
+        Ok(store)
+    }
+
+    #[test]
+    fn set_location_saves_and_clears_coordinates() -> Result<()> {
+        let store = store_with_house()?;
+        assert_eq!(
+            set_location(&store, "45.52,-122.68")?,
+            "location set to 45.52,-122.68"
+        );
+        let house = store.get_house_profile()?.expect("house exists");
+        assert_eq!(house.latitude.zip(house.longitude), Some((45.52, -122.68)));
+        assert_eq!(set_location(&store, "none")?, "location cleared");
+        let house = store.get_house_profile()?.expect("house exists");
+        assert_eq!(house.latitude, None);
+        assert!(set_location(&store, "north").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fetched_forecast_replaces_the_cache_and_skips_null_days() -> Result<()> {
+        let store = store_with_house()?;
+        store.set_house_location(Some((45.5, -122.5)))?;
+        let server =
+            Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+        let url = format!("http://{}/v1/forecast", server.server_addr());
+        let handle = thread::spawn(move || {
+            let request = server.recv().expect("request expected");
+            assert!(
+                request
+                    .url()
+                    .starts_with("/v1/forecast?latitude=45.5&longitude=-122.5")
+            );
+            request
+                .respond(Response::from_string(
+                    r#"{"daily":{"time":["2026-01-05","2026-01-06"],
+                    "temperature_2m_min":[-3.5,null],"temperature_2m_max":[4.0,6.0],
+                    "precipitation_sum":[0.0,1.0],"wind_speed_10m_max":[12.0,8.0]}}"#,
+                ))
+                .expect("response should succeed");
+        });
+
+        assert!(forecast_is_stale(&store, OffsetDateTime::now_utc())?);
+        let saved = fetch_forecast(
+            &store,
+            DEFAULT_WEATHER_PROVIDER,
+            &url,
+            Duration::from_secs(2),
+        )?;
+        handle.join().expect("server thread");
+        assert_eq!(saved, 1);
+        let from = Date::from_calendar_date(2026, Month::January, 1)?;
+        let cached = store.weather_forecast(from)?;
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].min_temp_c, -3.5);
+        assert!(!forecast_is_stale(&store, OffsetDateTime::now_utc())?);
+        assert!(!refresh_if_stale(
+            &store,
+            DEFAULT_WEATHER_PROVIDER,
+            &url,
+            Duration::from_secs(1)
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_requires_a_location_and_known_provider() -> Result<()> {
+        let store = store_with_house()?;
+        let error = fetch_forecast(
+            &store,
+            DEFAULT_WEATHER_PROVIDER,
+            "http://127.0.0.1:9/",
+            Duration::from_secs(1),
+        )
+        .expect_err("no location");
+        assert!(error.to_string().contains("--set-location"));
+        let error = fetch_forecast(
+            &store,
+            "darksky",
+            "http://127.0.0.1:9/",
+            Duration::from_secs(1),
+        )
+        .expect_err("unknown provider");
+        assert!(error.to_string().contains("not supported"));
+        Ok(())
+    }
+}
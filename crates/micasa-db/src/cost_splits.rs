@@ -0,0 +1,201 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use crate::validation::{format_cents, parse_required_cents};
+use anyhow::{Result, bail};
+
+const FULL_PERCENT_BASIS_POINTS: i64 = 10_000;
+
+/// One resolved `category=share` entry; `category` is the name as typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostSplitShare {
+    pub category: String,
+    pub amount_cents: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShareKind {
+    Percent,
+    Amount,
+}
+
+/// Parses `hvac=60%, plumbing=40%` or `hvac=120.00, plumbing=80.00` into
+/// amounts that add up to `total_cents` exactly. Percent rounding leftovers go
+/// to the largest share. An empty spec means "no splits".
+pub fn parse_cost_split_spec(raw: &str, total_cents: i64) -> Result<Vec<CostSplitShare>> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return Ok(Vec::new());
+    }
+    if total_cents <= 0 {
+        bail!("row has no cost to split; set its cost first");
+    }
+
+    let mut kind = None::<ShareKind>;
+    let mut entries = Vec::<(String, i64)>::new();
+    for part in trimmed.split(',') {
+        let Some((category, value)) = part.split_once('=') else {
+            bail!(
+                "split `{}` needs the form category=amount or category=percent%",
+                part.trim()
+            );
+        };
+        let category = category.trim();
+        if category.is_empty() {
+            bail!("split `{}` is missing a category name", part.trim());
+        }
+        if entries
+            .iter()
+            .any(|(existing, _)| existing.eq_ignore_ascii_case(category))
+        {
+            bail!("category `{category}` appears twice; combine its shares into one split");
+        }
+
+        let value = value.trim();
+        let (entry_kind, number) = match value.strip_suffix('%') {
+            Some(percent) => (ShareKind::Percent, percent),
+            None => (ShareKind::Amount, value),
+        };
+        if kind.is_some_and(|kind| kind != entry_kind) {
+            bail!("use either percentages or amounts for every split, not both");
+        }
+        kind = Some(entry_kind);
+
+        let parsed = parse_required_cents(number)
+            .ok()
+            .filter(|parsed| *parsed > 0);
+        let Some(parsed) = parsed else {
+            bail!("split `{category}` needs a positive amount or percent, got `{value}`");
+        };
+        entries.push((category.to_owned(), parsed));
+    }
+
+    let sum = entries.iter().map(|(_, value)| value).sum::<i64>();
+    let shares = match kind {
+        Some(ShareKind::Amount) => {
+            if sum != total_cents {
+                bail!(
+                    "splits add up to {} but the cost is {}; adjust them to match",
+                    format_cents(sum),
+                    format_cents(total_cents)
+                );
+            }
+            entries
+        }
+        Some(ShareKind::Percent) => {
+            if sum != FULL_PERCENT_BASIS_POINTS {
+                bail!(
+                    "percentages add up to {}%; they must total 100%",
+                    format_cents(sum).trim_start_matches('$')
+                );
+            }
+            resolve_percent_shares(entries, total_cents)
+        }
+        None => Vec::new(),
+    };
+
+    Ok(shares
+        .into_iter()
+        .map(|(category, amount_cents)| CostSplitShare {
+            category,
+            amount_cents,
+        })
+        .collect())
+}
+
+fn resolve_percent_shares(entries: Vec<(String, i64)>, total_cents: i64) -> Vec<(String, i64)> {
+    let mut resolved = entries
+        .iter()
+        .map(|(category, basis_points)| {
+            (
+                category.clone(),
+                total_cents * basis_points / FULL_PERCENT_BASIS_POINTS,
+            )
+        })
+        .collect::<Vec<_>>();
+    let leftover = total_cents - resolved.iter().map(|(_, amount)| amount).sum::<i64>();
+    let largest = entries
+        .iter()
+        .enumerate()
+        .max_by(|(left_index, (_, left)), (right_index, (_, right))| {
+            left.cmp(right).then(right_index.cmp(left_index))
+        })
+        .map(|(index, _)| index);
+    if let Some(index) = largest {
+        resolved[index].1 += leftover;
+    }
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CostSplitShare, parse_cost_split_spec};
+
+    fn share(category: &str, amount_cents: i64) -> CostSplitShare {
+        CostSplitShare {
+            category: category.to_owned(),
+            amount_cents,
+        }
+    }
+
+    #[test]
+    fn percent_splits_resolve_to_exact_total() {
+        let shares = parse_cost_split_spec("HVAC=33.33%, Plumbing=33.33%, Roof=33.34%", 100)
+            .expect("percent splits should parse");
+        assert_eq!(
+            shares,
+            vec![share("HVAC", 33), share("Plumbing", 33), share("Roof", 34)]
+        );
+
+        let shares = parse_cost_split_spec("a=50%, b=50%", 1_001).expect("odd cents split");
+        assert_eq!(shares, vec![share("a", 501), share("b", 500)]);
+    }
+
+    #[test]
+    fn amount_splits_must_match_total() {
+        let shares =
+            parse_cost_split_spec("HVAC=120.00, Plumbing=80", 20_000).expect("amounts parse");
+        assert_eq!(
+            shares,
+            vec![share("HVAC", 12_000), share("Plumbing", 8_000)]
+        );
+
+        let error = parse_cost_split_spec("HVAC=120.00, Plumbing=70", 20_000)
+            .expect_err("short amounts should fail");
+        assert!(
+            error
+                .to_string()
+                .contains("splits add up to $190.00 but the cost is $200.00")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_specs() {
+        let cases = [
+            ("HVAC 60%", "category=amount"),
+            ("=60%, b=40%", "missing a category"),
+            ("a=60%, A=40%", "appears twice"),
+            ("a=60%, b=80.00", "not both"),
+            ("a=60%, b=30%", "total 100%"),
+            ("a=0", "positive amount"),
+            ("a=lots", "positive amount"),
+        ];
+        for (spec, expected) in cases {
+            let error = parse_cost_split_spec(spec, 10_000).expect_err(spec);
+            assert!(
+                error.to_string().contains(expected),
+                "{spec}: unexpected error {error}"
+            );
+        }
+    }
+
+    #[test]
+    fn empty_spec_clears_and_zero_cost_is_rejected() {
+        assert_eq!(
+            parse_cost_split_spec("  ", 0).expect("empty spec"),
+            Vec::new()
+        );
+        let error = parse_cost_split_spec("a=100%", 0).expect_err("no cost");
+        assert!(error.to_string().contains("set its cost first"));
+    }
+}
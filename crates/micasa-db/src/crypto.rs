@@ -0,0 +1,217 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Passphrase encryption for document bytes. The key comes from Argon2id
+//! over the passphrase and a per-database salt; each blob is sealed with
+//! XChaCha20-Poly1305 under its own random nonce, behind a marker so sealed
+//! and plain blobs can sit side by side. The app lock passphrase is kept as
+//! a salted Argon2id PHC string instead.
+
+use anyhow::{Result, anyhow, bail};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::{Algorithm, Argon2};
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+
+/// Leads every sealed blob; plain documents never start with it in practice.
+const MAGIC: &[u8] = b"micasa-sealed-v1\0";
+const NONCE_LEN: usize = 24;
+pub const SALT_LEN: usize = 16;
+/// Sealed under the key to tell a wrong passphrase from a right one.
+const CHECK_PLAINTEXT: &[u8] = b"micasa document key";
+
+#[derive(Clone)]
+pub struct DocumentKey(Key);
+
+impl std::fmt::Debug for DocumentKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DocumentKey(..)")
+    }
+}
+
+/// What the database keeps about its key: the salt to derive it again and a
+/// sealed check value. Never the key or the passphrase.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRecord {
+    pub salt: Vec<u8>,
+    pub check: Vec<u8>,
+}
+
+impl DocumentKey {
+    pub fn derive(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            bail!("passphrase is empty -- choose a non-empty passphrase");
+        }
+        let mut key = Key::default();
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+            .map_err(|error| anyhow!("derive document key: {error}"))?;
+        Ok(Self(key))
+    }
+
+    /// A fresh key under a new salt, with the record to store for it.
+    pub fn generate(passphrase: &str) -> Result<(Self, KeyRecord)> {
+        let mut salt = vec![0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = Self::derive(passphrase, &salt)?;
+        let check = key.seal(CHECK_PLAINTEXT)?;
+        Ok((key, KeyRecord { salt, check }))
+    }
+
+    /// The key for `record`, or `None` when the passphrase is wrong.
+    pub fn unlock(passphrase: &str, record: &KeyRecord) -> Result<Option<Self>> {
+        let key = Self::derive(passphrase, &record.salt)?;
+        Ok(key
+            .open(&record.check)
+            .ok()
+            .filter(|check| check == CHECK_PLAINTEXT)
+            .map(|_| key))
+    }
+
+    pub fn seal(&self, plain: &[u8]) -> Result<Vec<u8>> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let sealed = XChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, plain)
+            .map_err(|_| anyhow!("encrypt document bytes"))?;
+        let mut out = Vec::with_capacity(MAGIC.len() + NONCE_LEN + sealed.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&sealed);
+        Ok(out)
+    }
+
+    pub fn open(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        let body = sealed
+            .strip_prefix(MAGIC)
+            .filter(|body| body.len() >= NONCE_LEN)
+            .ok_or_else(|| anyhow!("not a sealed document blob"))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(&self.0)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("decrypt document bytes: wrong key or damaged data"))
+    }
+}
+
+pub fn is_sealed(blob: &[u8]) -> bool {
+    blob.starts_with(MAGIC)
+}
+
+/// A salted Argon2id PHC string for `passphrase`, as kept in
+/// `ui.lock_passphrase_hash`.
+pub fn hash_passphrase(passphrase: &str) -> Result<String> {
+    if passphrase.is_empty() {
+        bail!("passphrase is empty -- choose a non-empty passphrase");
+    }
+    let mut salt = [0; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let salt = SaltString::encode_b64(&salt).map_err(|error| anyhow!("encode salt: {error}"))?;
+    Argon2::default()
+        .hash_password(passphrase.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|error| anyhow!("hash passphrase: {error}"))
+}
+
+/// Whether `passphrase` matches a hash from [`hash_passphrase`].
+pub fn verify_passphrase(passphrase: &str, hash: &str) -> Result<bool> {
+    let hash = parse_passphrase_hash(hash)?;
+    Ok(Argon2::default()
+        .verify_password(passphrase.as_bytes(), &hash)
+        .is_ok())
+}
+
+/// Parses an Argon2id PHC string, refusing anything else.
+pub fn parse_passphrase_hash(hash: &str) -> Result<PasswordHash<'_>> {
+    let parsed = PasswordHash::new(hash)
+        .map_err(|error| anyhow!("passphrase hash is not a PHC string: {error}"))?;
+    if parsed.algorithm != Algorithm::Argon2id.ident() || parsed.salt.is_none() {
+        bail!("passphrase hash must be a salted argon2id hash");
+    }
+    Ok(parsed)
+}
+
+impl KeyRecord {
+    /// `v1:<salt hex>:<check hex>`, as kept in the settings table.
+    pub fn to_storage(&self) -> String {
+        format!("v1:{}:{}", hex(&self.salt), hex(&self.check))
+    }
+
+    pub fn parse(raw: &str) -> Result<Self> {
+        let parts = raw.split(':').collect::<Vec<_>>();
+        let [version, salt, check] = parts.as_slice() else {
+            bail!("document key record is malformed; restore the database from a backup");
+        };
+        if *version != "v1" {
+            bail!("document key record is {version}; upgrade micasa to read it");
+        }
+        Ok(Self {
+            salt: unhex(salt)?,
+            check: unhex(check)?,
+        })
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn unhex(raw: &str) -> Result<Vec<u8>> {
+    if !raw.is_ascii() || !raw.len().is_multiple_of(2) {
+        bail!("document key record is malformed; restore the database from a backup");
+    }
+    (0..raw.len())
+        .step_by(2)
+        .map(|at| {
+            u8::from_str_radix(&raw[at..at + 2], 16).map_err(|_| {
+                anyhow!("document key record is malformed; restore the database from a backup")
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DocumentKey, KeyRecord, hash_passphrase, is_sealed, verify_passphrase};
+    use anyhow::Result;
+
+    #[test]
+    fn sealed_blobs_open_only_under_the_same_passphrase() -> Result<()> {
+        let (key, record) = DocumentKey::generate("correct horse")?;
+        let sealed = key.seal(b"policy.pdf bytes")?;
+        assert!(is_sealed(&sealed));
+        assert!(!is_sealed(b"%PDF-1.7"));
+        assert_ne!(
+            key.seal(b"policy.pdf bytes")?,
+            sealed,
+            "fresh nonce per seal"
+        );
+
+        let record = KeyRecord::parse(&record.to_storage())?;
+        let again = DocumentKey::unlock("correct horse", &record)?.expect("right passphrase");
+        assert_eq!(again.open(&sealed)?, b"policy.pdf bytes");
+        assert!(DocumentKey::unlock("battery staple", &record)?.is_none());
+
+        let mut damaged = sealed.clone();
+        *damaged.last_mut().expect("sealed bytes") ^= 1;
+        assert!(again.open(&damaged).is_err());
+        assert!(DocumentKey::derive("", &record.salt).is_err());
+        assert!(KeyRecord::parse("v1:zz:00").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn passphrase_hashes_are_salted_and_verify() -> Result<()> {
+        let hash = hash_passphrase("correct horse")?;
+        assert!(hash.starts_with("$argon2id$"));
+        assert_ne!(
+            hash_passphrase("correct horse")?,
+            hash,
+            "fresh salt per hash"
+        );
+        assert!(verify_passphrase("correct horse", &hash)?);
+        assert!(!verify_passphrase("battery staple", &hash)?);
+        assert!(hash_passphrase("").is_err());
+        assert!(verify_passphrase("correct horse", &"a".repeat(64)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Result, bail};
+
+/// Rates are stored as home-currency units per foreign unit, scaled by this.
+pub const RATE_SCALE: i64 = 1_000_000;
+
+/// Normalizes an ISO 4217 style code (`eur` -> `EUR`).
+pub fn parse_currency_code(raw: &str) -> Result<String> {
+    let code = raw.trim();
+    if code.len() != 3 || !code.chars().all(|ch| ch.is_ascii_alphabetic()) {
+        bail!("currency code `{code}` should be three letters, like EUR or CAD");
+    }
+    Ok(code.to_ascii_uppercase())
+}
+
+/// Parses a positive decimal rate with up to six fractional digits.
+pub fn parse_rate_micros(raw: &str) -> Result<i64> {
+    let trimmed = raw.trim();
+    let (whole, fraction) = trimmed.split_once('.').unwrap_or((trimmed, ""));
+    let digits_only = |part: &str| part.chars().all(|ch| ch.is_ascii_digit());
+    if whole.is_empty() && fraction.is_empty()
+        || !digits_only(whole)
+        || !digits_only(fraction)
+        || fraction.len() > 6
+    {
+        bail!("rate `{trimmed}` should be a positive number with at most 6 decimals, like 1.08");
+    }
+    let whole = if whole.is_empty() {
+        0
+    } else {
+        whole
+            .parse::<i64>()
+            .ok()
+            .filter(|whole| *whole <= i64::MAX / RATE_SCALE)
+            .ok_or_else(|| anyhow::anyhow!("rate `{trimmed}` is too large"))?
+    };
+    let fraction = format!("{fraction:0<6}").parse::<i64>().unwrap_or(0);
+    let micros = whole * RATE_SCALE + fraction;
+    if micros <= 0 {
+        bail!("rate `{trimmed}` must be greater than zero");
+    }
+    Ok(micros)
+}
+
+/// Parses a fetched floating point rate expressed as foreign units per home
+/// unit and inverts it into home units per foreign unit.
+pub fn invert_fetched_rate(foreign_per_home: f64) -> Result<i64> {
+    if !foreign_per_home.is_finite() || foreign_per_home <= 0.0 {
+        bail!("fetched rate {foreign_per_home} is not a positive number");
+    }
+    let micros = (RATE_SCALE as f64 / foreign_per_home).round();
+    if micros < 1.0 || micros > i64::MAX as f64 {
+        bail!("fetched rate {foreign_per_home} is out of range");
+    }
+    Ok(micros as i64)
+}
+
+pub fn format_rate_micros(micros: i64) -> String {
+    let whole = micros / RATE_SCALE;
+    let fraction = format!("{:06}", (micros % RATE_SCALE).abs());
+    let fraction = fraction.trim_end_matches('0');
+    let fraction = if fraction.len() < 2 {
+        format!("{fraction:0<2}")
+    } else {
+        fraction.to_owned()
+    };
+    format!("{whole}.{fraction}")
+}
+
+/// Converts cents in a foreign currency to home cents, rounding half away
+/// from zero.
+pub fn convert_cents(amount_cents: i64, rate_micros: i64) -> i64 {
+    let scaled = i128::from(amount_cents) * i128::from(rate_micros);
+    let half = i128::from(RATE_SCALE / 2);
+    let rounded = if scaled >= 0 {
+        (scaled + half) / i128::from(RATE_SCALE)
+    } else {
+        (scaled - half) / i128::from(RATE_SCALE)
+    };
+    i64::try_from(rounded).unwrap_or(if rounded > 0 { i64::MAX } else { i64::MIN })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        convert_cents, format_rate_micros, invert_fetched_rate, parse_currency_code,
+        parse_rate_micros,
+    };
+
+    #[test]
+    fn currency_codes_are_three_letters() {
+        assert_eq!(parse_currency_code(" eur ").expect("valid code"), "EUR");
+        for raw in ["", "EU", "EURO", "E1R", "€€€"] {
+            assert!(parse_currency_code(raw).is_err(), "{raw:?} should fail");
+        }
+    }
+
+    #[test]
+    fn rates_parse_to_micros_and_format_back() {
+        let cases = [
+            ("1.08", 1_080_000, "1.08"),
+            ("0.000731", 731, "0.000731"),
+            ("2", 2_000_000, "2.00"),
+            (".5", 500_000, "0.50"),
+        ];
+        for (raw, micros, formatted) in cases {
+            assert_eq!(parse_rate_micros(raw).expect(raw), micros);
+            assert_eq!(format_rate_micros(micros), formatted);
+        }
+        for raw in ["", "0", "0.0000001", "-1", "1,08", "abc", "."] {
+            assert!(parse_rate_micros(raw).is_err(), "{raw:?} should fail");
+        }
+    }
+
+    #[test]
+    fn conversion_rounds_half_away_from_zero() {
+        assert_eq!(convert_cents(10_000, 1_080_000), 10_800);
+        assert_eq!(convert_cents(1, 500_000), 1);
+        assert_eq!(convert_cents(-1, 500_000), -1);
+        assert_eq!(convert_cents(333, 1_000_000), 333);
+    }
+
+    #[test]
+    fn fetched_rates_invert_to_home_units() {
+        assert_eq!(invert_fetched_rate(0.8).expect("valid rate"), 1_250_000);
+        assert!(invert_fetched_rate(0.0).is_err());
+        assert!(invert_fetched_rate(f64::NAN).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Result, bail};
+use micasa_app::{DocumentId, MaintenanceItemId};
+
+/// Longest chunk handed to the embedding model, in characters. Small enough
+/// that a few chunks fit in a chat prompt next to the SQL results.
+pub const CHUNK_CHARS: usize = 800;
+
+/// One indexed passage of a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentChunk {
+    pub document_id: DocumentId,
+    pub title: String,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// One indexed passage of a maintenance item's manual text.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManualChunk {
+    pub maintenance_item_id: MaintenanceItemId,
+    pub title: String,
+    pub content: String,
+    pub embedding: Vec<f32>,
+}
+
+/// Anything ranked by [`nearest_chunks`].
+pub trait Embedded {
+    fn embedding(&self) -> &[f32];
+}
+
+impl Embedded for DocumentChunk {
+    fn embedding(&self) -> &[f32] {
+        &self.embedding
+    }
+}
+
+impl Embedded for ManualChunk {
+    fn embedding(&self) -> &[f32] {
+        &self.embedding
+    }
+}
+
+/// Splits `text` into passages of at most `max_chars`, breaking between
+/// lines, then words, so each passage reads on its own. A single word longer
+/// than `max_chars` becomes an oversized passage of its own.
+pub fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        for word in line.split_whitespace() {
+            let mut joined = !current.is_empty() && !current.ends_with('\n');
+            let extra = usize::from(joined) + word.chars().count();
+            if !current.is_empty() && current.chars().count() + extra > max_chars {
+                chunks.push(std::mem::take(&mut current));
+                joined = false;
+            }
+            if joined {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+        if current.chars().count() * 2 >= max_chars {
+            chunks.push(std::mem::take(&mut current));
+        } else if !current.is_empty() {
+            current.push('\n');
+        }
+    }
+    let tail = current.trim_end();
+    if !tail.is_empty() {
+        chunks.push(tail.to_owned());
+    }
+    chunks
+        .into_iter()
+        .map(|chunk| chunk.trim_end().to_owned())
+        .collect()
+}
+
+/// Cosine of the angle between two embeddings; 0 when either is empty or
+/// their lengths differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
+
+/// The `limit` chunks closest to `query`, best first, skipping any scoring
+/// below `min_score`.
+pub fn nearest_chunks<'a, T: Embedded>(
+    chunks: &'a [T],
+    query: &[f32],
+    limit: usize,
+    min_score: f32,
+) -> Vec<&'a T> {
+    let mut scored = chunks
+        .iter()
+        .map(|chunk| (cosine_similarity(chunk.embedding(), query), chunk))
+        .filter(|(score, _)| *score >= min_score)
+        .collect::<Vec<_>>();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, chunk)| chunk)
+        .collect()
+}
+
+pub(crate) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding
+        .iter()
+        .flat_map(|value| value.to_le_bytes())
+        .collect()
+}
+
+pub(crate) fn decode_embedding(raw: &[u8]) -> Result<Vec<f32>> {
+    if !raw.len().is_multiple_of(4) {
+        bail!(
+            "stored embedding is {} bytes, not a whole number of floats; reindex documents",
+            raw.len()
+        );
+    }
+    Ok(raw
+        .chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        DocumentChunk, chunk_text, cosine_similarity, decode_embedding, encode_embedding,
+        nearest_chunks,
+    };
+    use micasa_app::DocumentId;
+
+    #[test]
+    fn chunk_text_breaks_on_words_and_keeps_short_paragraphs_together() {
+        assert_eq!(
+            chunk_text("Title\n\nshort note\n\n", 40),
+            vec!["Title\nshort note"]
+        );
+        let long = "word ".repeat(30);
+        let chunks = chunk_text(&long, 40);
+        assert!(chunks.iter().all(|chunk| chunk.chars().count() <= 40));
+        assert_eq!(chunks.concat().matches("word").count(), 30);
+        assert!(chunk_text("  \n\n ", 40).is_empty());
+    }
+
+    #[test]
+    fn nearest_chunks_ranks_by_cosine_and_drops_weak_matches() {
+        let chunk = |id: i64, embedding: Vec<f32>| DocumentChunk {
+            document_id: DocumentId::new(id),
+            title: format!("doc {id}"),
+            content: String::new(),
+            embedding,
+        };
+        let chunks = vec![
+            chunk(1, vec![1.0, 0.0]),
+            chunk(2, vec![0.6, 0.8]),
+            chunk(3, vec![0.0, 1.0]),
+        ];
+        let nearest = nearest_chunks(&chunks, &[0.0, 2.0], 2, 0.5);
+        let ids = nearest
+            .iter()
+            .map(|chunk| chunk.document_id.get())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec![3, 2]);
+        assert_eq!(cosine_similarity(&[1.0], &[1.0, 0.0]), 0.0);
+
+        let raw = encode_embedding(&[0.25, -1.5]);
+        assert_eq!(decode_embedding(&raw).expect("decodes"), vec![0.25, -1.5]);
+        assert!(decode_embedding(&raw[..3]).is_err());
+    }
+}
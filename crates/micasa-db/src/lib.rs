@@ -1,24 +1,39 @@
 // Copyright 2026 Phillip Cloud
 // Licensed under the Apache License, Version 2.0
 
+pub mod cost_splits;
+pub mod crypto;
+pub mod currency;
+pub mod embeddings;
+pub mod recalls;
+pub mod schema_docs;
 pub mod validation;
+pub mod weather;
 
 use anyhow::{Context, Result, anyhow, bail};
 use micasa_app::{
-    AppSetting, Appliance, ApplianceId, ChatInput, ChatInputId, DashboardCounts, Document,
-    DocumentEntityKind, DocumentId, HouseProfile, HouseProfileId, Incident, IncidentId,
-    IncidentSeverity, IncidentStatus, MaintenanceCategoryId, MaintenanceItem, MaintenanceItemId,
-    Project, ProjectId, ProjectStatus, ProjectTypeId, Quote, QuoteId, ServiceLogEntry,
-    ServiceLogEntryId, SettingKey, SettingValue, Vendor, VendorId,
+    AlertEvent, AlertStyle, AppSetting, Appliance, ApplianceId, ApplianceRollup, AuditEntry,
+    AuditEntryId, AuditSource, Budget, BudgetCategory, BudgetId, ChatInput, ChatInputId,
+    DashboardCounts, DataIssue, DataIssueKind, DeletionEntity, Document, DocumentEntityKind,
+    DocumentId, HouseProfile, HouseProfileId, Incident, IncidentId, IncidentSeverity,
+    IncidentStatus, LookupEntry, LookupKind, MaintenanceCategoryId, MaintenanceItem,
+    MaintenanceItemId, Project, ProjectId, ProjectRollup, ProjectStatus, ProjectTemplate,
+    ProjectTypeId, PurgeSummary, QueryPage, Quote, QuoteId, RecalcSummary, SavedView,
+    ServiceLogEntry, ServiceLogEntryId, SettingKey, SettingValue, SettingValueKind, SortDirection,
+    TabKind, Tag, TagId, Task, TaskId, Vendor, VendorId, VendorRollup, parse_auto_refresh,
 };
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
 use rusqlite::types::ValueRef;
-use rusqlite::{Connection, OptionalExtension, params, params_from_iter};
+use rusqlite::{Connection, OpenFlags, OptionalExtension, params, params_from_iter};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
 use std::collections::{BTreeMap, BTreeSet};
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::time::Duration;
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
@@ -26,8 +41,26 @@ use time::{Date, OffsetDateTime, PrimitiveDateTime};
 
 pub const APP_NAME: &str = "micasa";
 pub const MAX_DOCUMENT_SIZE: i64 = 50 << 20;
+/// Lead time for warranty and insurance reminders until Settings says
+/// otherwise; matches the dashboard's 30-day horizon.
+pub const DEFAULT_NOTICE_DAYS: i64 = 30;
 
 const CHAT_HISTORY_MAX: i64 = 200;
+/// Tables that hold or point at other rows' data without a shadow view of
+/// their own. Ad hoc queries can't read them while private rows are hidden.
+const PRIVATE_SIDE_TABLES: [&str; 9] = [
+    "private_rows",
+    "document_chunks",
+    "manual_chunks",
+    "entity_tags",
+    "audit_entries",
+    "cost_splits",
+    "money_currencies",
+    "project_tasks",
+    "appliance_recalls",
+];
+/// Raw settings row holding the document key's salt and check value.
+const DOCUMENT_KEY_SETTING: &str = "document_key";
 const MAX_QUERY_ROWS: usize = 200;
 
 const DEFAULT_PROJECT_TYPES: [&str; 12] = [
@@ -57,6 +90,64 @@ const DEFAULT_MAINTENANCE_CATEGORIES: [&str; 9] = [
     "Structural",
 ];
 
+/// Templates offered before any are saved: name, project type, checklist.
+const BUILTIN_PROJECT_TEMPLATES: [(&str, &str, &[&str]); 4] = [
+    (
+        "Bathroom remodel",
+        "Remodel",
+        &[
+            "Measure and sketch the layout",
+            "Pick fixtures, tile, and vanity",
+            "Get three contractor quotes",
+            "Pull permits",
+            "Demo and rough-in plumbing and electrical",
+            "Waterproof and tile",
+            "Install fixtures and vanity",
+            "Final inspection",
+        ],
+    ),
+    (
+        "Kitchen remodel",
+        "Remodel",
+        &[
+            "Set a budget and layout",
+            "Order cabinets and countertops",
+            "Get contractor quotes",
+            "Pull permits",
+            "Demo",
+            "Rough-in plumbing and electrical",
+            "Install cabinets and countertops",
+            "Install appliances",
+            "Final inspection",
+        ],
+    ),
+    (
+        "Exterior paint",
+        "Painting",
+        &[
+            "Pick colors and test swatches",
+            "Get painter quotes",
+            "Pressure wash and scrape",
+            "Repair trim and caulk",
+            "Prime bare wood",
+            "Paint",
+            "Walk the house for touch-ups",
+        ],
+    ),
+    (
+        "Roof replacement",
+        "Roof",
+        &[
+            "Inspect attic and decking",
+            "Get roofer quotes",
+            "Check warranty and insurance coverage",
+            "Schedule tear-off",
+            "Replace flashing and underlayment",
+            "Final walkthrough and cleanup",
+        ],
+    ),
+];
+
 const DEMO_VENDOR_TRADES: [&str; 12] = [
     "Plumbing",
     "Electric",
@@ -482,6 +573,287 @@ const REQUIRED_SCHEMA: &[(&str, &[&str])] = &[
     ("chat_inputs", &["id", "input", "created_at"]),
 ];
 
+/// Tables introduced after the Go schema. They are created in place on
+/// existing databases, mirroring the additive-only evolution described in
+/// plans/schema-evolution.md.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AdditiveTable {
+    name: &'static str,
+    create_sql: &'static str,
+}
+
+const ADDITIVE_TABLES: &[AdditiveTable] = &[
+    AdditiveTable {
+        name: "private_rows",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS private_rows (
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              created_at TEXT NOT NULL,
+              PRIMARY KEY (entity, target_id)
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "archived_rows",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS archived_rows (
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              archived_at TEXT NOT NULL,
+              PRIMARY KEY (entity, target_id)
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "cost_splits",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS cost_splits (
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              category_id INTEGER NOT NULL,
+              amount_cents INTEGER NOT NULL,
+              PRIMARY KEY (entity, target_id, category_id),
+              FOREIGN KEY (category_id) REFERENCES maintenance_categories(id) ON DELETE RESTRICT
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "currency_rates",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS currency_rates (
+              code TEXT PRIMARY KEY,
+              home_per_unit_micros INTEGER NOT NULL CHECK (home_per_unit_micros > 0),
+              source TEXT NOT NULL,
+              updated_at TEXT NOT NULL
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "money_currencies",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS money_currencies (
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              field TEXT NOT NULL,
+              currency_code TEXT NOT NULL,
+              PRIMARY KEY (entity, target_id, field),
+              FOREIGN KEY (currency_code) REFERENCES currency_rates(code) ON DELETE RESTRICT
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "weather_forecast_days",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS weather_forecast_days (
+              day TEXT PRIMARY KEY,
+              min_temp_c REAL NOT NULL,
+              max_temp_c REAL NOT NULL,
+              precipitation_mm REAL NOT NULL,
+              wind_kmh REAL NOT NULL,
+              fetched_at TEXT NOT NULL
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "appliance_recalls",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS appliance_recalls (
+              appliance_id INTEGER NOT NULL,
+              recall_id TEXT NOT NULL,
+              incident_id INTEGER,
+              matched_at TEXT NOT NULL,
+              PRIMARY KEY (appliance_id, recall_id),
+              FOREIGN KEY (appliance_id) REFERENCES appliances(id) ON DELETE CASCADE,
+              FOREIGN KEY (incident_id) REFERENCES incidents(id) ON DELETE SET NULL
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "budgets",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS budgets (
+              id INTEGER PRIMARY KEY,
+              scope TEXT NOT NULL,
+              category_id INTEGER NOT NULL,
+              monthly_cents INTEGER NOT NULL CHECK (monthly_cents > 0),
+              notes TEXT NOT NULL DEFAULT '',
+              created_at TEXT NOT NULL,
+              updated_at TEXT NOT NULL,
+              deleted_at TEXT
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "saved_views",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS saved_views (
+              tab TEXT NOT NULL,
+              name TEXT NOT NULL,
+              sorts TEXT NOT NULL DEFAULT '',
+              pin_column TEXT,
+              pin_value TEXT,
+              filter_active INTEGER NOT NULL DEFAULT 0,
+              filter_inverted INTEGER NOT NULL DEFAULT 0,
+              hidden_columns TEXT NOT NULL DEFAULT '',
+              hide_settled INTEGER NOT NULL DEFAULT 0,
+              updated_at TEXT NOT NULL,
+              PRIMARY KEY (tab, name)
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "audit_entries",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS audit_entries (
+              id INTEGER PRIMARY KEY,
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              action TEXT NOT NULL,
+              source TEXT NOT NULL DEFAULT 'manual',
+              recorded_at TEXT NOT NULL
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "tags",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS tags (
+              id INTEGER PRIMARY KEY,
+              name TEXT NOT NULL UNIQUE,
+              created_at TEXT NOT NULL
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "entity_tags",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS entity_tags (
+              entity TEXT NOT NULL,
+              target_id INTEGER NOT NULL,
+              tag_id INTEGER NOT NULL,
+              PRIMARY KEY (entity, target_id, tag_id),
+              FOREIGN KEY (tag_id) REFERENCES tags(id) ON DELETE CASCADE
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "document_chunks",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS document_chunks (
+              document_id INTEGER NOT NULL,
+              chunk_index INTEGER NOT NULL,
+              content TEXT NOT NULL,
+              model TEXT NOT NULL,
+              embedding BLOB NOT NULL,
+              PRIMARY KEY (document_id, chunk_index),
+              FOREIGN KEY (document_id) REFERENCES documents(id) ON DELETE CASCADE
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "manual_chunks",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS manual_chunks (
+              maintenance_item_id INTEGER NOT NULL,
+              chunk_index INTEGER NOT NULL,
+              content TEXT NOT NULL,
+              model TEXT NOT NULL,
+              embedding BLOB NOT NULL,
+              PRIMARY KEY (maintenance_item_id, chunk_index),
+              FOREIGN KEY (maintenance_item_id) REFERENCES maintenance_items(id) ON DELETE CASCADE
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "project_tasks",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS project_tasks (
+              id INTEGER PRIMARY KEY,
+              project_id INTEGER NOT NULL,
+              title TEXT NOT NULL,
+              done_at TEXT,
+              created_at TEXT NOT NULL,
+              updated_at TEXT NOT NULL,
+              FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+        ",
+    },
+    AdditiveTable {
+        name: "project_templates",
+        create_sql: "
+            CREATE TABLE IF NOT EXISTS project_templates (
+              name TEXT PRIMARY KEY,
+              project_type TEXT NOT NULL DEFAULT '',
+              tasks TEXT NOT NULL DEFAULT '',
+              updated_at TEXT NOT NULL
+            );
+        ",
+    },
+];
+
+/// Columns added to Go-era tables, created with `ALTER TABLE ... ADD COLUMN`
+/// when missing. Always nullable so existing rows stay valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct AdditiveColumn {
+    table: &'static str,
+    name: &'static str,
+    definition: &'static str,
+}
+
+const ADDITIVE_COLUMNS: &[AdditiveColumn] = &[
+    AdditiveColumn {
+        table: "house_profiles",
+        name: "latitude",
+        definition: "REAL",
+    },
+    AdditiveColumn {
+        table: "house_profiles",
+        name: "longitude",
+        definition: "REAL",
+    },
+    AdditiveColumn {
+        table: "appliances",
+        name: "recall_checked_at",
+        definition: "TEXT",
+    },
+    AdditiveColumn {
+        table: "quotes",
+        name: "accepted_at",
+        definition: "TEXT",
+    },
+    AdditiveColumn {
+        table: "projects",
+        name: "parent_project_id",
+        definition: "INTEGER REFERENCES projects(id) ON DELETE RESTRICT",
+    },
+    AdditiveColumn {
+        table: "vendors",
+        name: "license_number",
+        definition: "TEXT NOT NULL DEFAULT ''",
+    },
+    AdditiveColumn {
+        table: "vendors",
+        name: "insurance_expiry",
+        definition: "TEXT",
+    },
+    AdditiveColumn {
+        table: "vendors",
+        name: "insurance_document_id",
+        definition: "INTEGER REFERENCES documents(id) ON DELETE RESTRICT",
+    },
+    AdditiveColumn {
+        table: "incidents",
+        name: "follow_up_date",
+        definition: "TEXT",
+    },
+    AdditiveColumn {
+        table: "documents",
+        name: "extracted_text",
+        definition: "TEXT NOT NULL DEFAULT ''",
+    },
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct RequiredIndex {
     name: &'static str,
@@ -622,12 +994,74 @@ const COLUMN_HINTS: &[(&str, &str)] = &[
     ),
 ];
 
+/// Rows updated at or after a cursor, plus the id of every row the full list
+/// holds now, so a cached list can be patched instead of reloaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChanges<T> {
+    pub rows: Vec<T>,
+    pub ids: BTreeSet<i64>,
+}
+
+/// Rows `offset..offset + limit` of a list in its usual order, leaving out
+/// the `skip` ids (private or archived rows the UI hides).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListPage<'a> {
+    pub skip: &'a BTreeSet<i64>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// One page of a list, plus how many rows the whole list holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowPage<T> {
+    pub rows: Vec<T>,
+    pub total: usize,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LookupValue<Id> {
     pub id: Id,
     pub name: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostSplit {
+    pub category_id: MaintenanceCategoryId,
+    pub amount_cents: i64,
+}
+
+/// Spend attributed to one category; `None` collects unsplit incident costs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CategoryCostTotal {
+    pub category_id: Option<MaintenanceCategoryId>,
+    pub category_name: String,
+    pub total_cents: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateSource {
+    Manual,
+    Fetched,
+}
+
+impl RateSource {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Fetched => "fetched",
+        }
+    }
+}
+
+/// Conversion rate into the home currency for one foreign currency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurrencyRate {
+    pub code: String,
+    pub home_per_unit_micros: i64,
+    pub source: RateSource,
+    pub updated_at: OffsetDateTime,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub struct SeedSummary {
     pub vendors: usize,
@@ -640,6 +1074,62 @@ pub struct SeedSummary {
     pub documents: usize,
 }
 
+/// Every row of one table, in column order, for whole-database export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDump {
+    pub table: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<DumpValue>>,
+}
+
+/// A stored SQLite value, kept in its original storage class.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DumpValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// One step [`Store::bootstrap`] would take on an existing database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemaChange {
+    AddTable {
+        table: &'static str,
+    },
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+    AddIndex {
+        name: &'static str,
+    },
+    /// Fills an empty lookup list with its default names.
+    SeedDefaults {
+        kind: LookupKind,
+        count: usize,
+    },
+}
+
+impl std::fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddTable { table } => write!(f, "add table {table}"),
+            Self::AddColumn {
+                table,
+                column,
+                definition,
+            } => write!(f, "add column {table}.{column} ({definition})"),
+            Self::AddIndex { name } => write!(f, "add index {name}"),
+            Self::SeedDefaults { kind, count } => {
+                write!(f, "insert {count} default {}", kind.title())
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PragmaColumn {
     pub cid: i32,
@@ -691,6 +1181,7 @@ pub struct NewProject {
     pub end_date: Option<Date>,
     pub budget_cents: Option<i64>,
     pub actual_cents: Option<i64>,
+    pub parent_id: Option<ProjectId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -703,6 +1194,7 @@ pub struct UpdateProject {
     pub end_date: Option<Date>,
     pub budget_cents: Option<i64>,
     pub actual_cents: Option<i64>,
+    pub parent_id: Option<ProjectId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -713,6 +1205,9 @@ pub struct NewVendor {
     pub phone: String,
     pub website: String,
     pub notes: String,
+    pub license_number: String,
+    pub insurance_expiry: Option<Date>,
+    pub insurance_document_id: Option<DocumentId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -723,6 +1218,9 @@ pub struct UpdateVendor {
     pub phone: String,
     pub website: String,
     pub notes: String,
+    pub license_number: String,
+    pub insurance_expiry: Option<Date>,
+    pub insurance_document_id: Option<DocumentId>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -809,6 +1307,7 @@ pub struct NewIncident {
     pub severity: IncidentSeverity,
     pub date_noticed: Date,
     pub date_resolved: Option<Date>,
+    pub follow_up_date: Option<Date>,
     pub location: String,
     pub cost_cents: Option<i64>,
     pub appliance_id: Option<ApplianceId>,
@@ -824,6 +1323,7 @@ pub struct UpdateIncident {
     pub severity: IncidentSeverity,
     pub date_noticed: Date,
     pub date_resolved: Option<Date>,
+    pub follow_up_date: Option<Date>,
     pub location: String,
     pub cost_cents: Option<i64>,
     pub appliance_id: Option<ApplianceId>,
@@ -831,6 +1331,20 @@ pub struct UpdateIncident {
     pub notes: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NewBudget {
+    pub category: BudgetCategory,
+    pub monthly_cents: i64,
+    pub notes: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateBudget {
+    pub category: BudgetCategory,
+    pub monthly_cents: i64,
+    pub notes: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NewServiceLogEntry {
     pub maintenance_item_id: MaintenanceItemId,
@@ -881,6 +1395,7 @@ pub enum LifecycleEntityRef {
     Vendor(VendorId),
     Incident(IncidentId),
     Document(DocumentId),
+    Budget(BudgetId),
 }
 
 impl LifecycleEntityRef {
@@ -894,6 +1409,7 @@ impl LifecycleEntityRef {
             Self::Vendor(_) => EntityKind::Vendor,
             Self::Incident(_) => EntityKind::Incident,
             Self::Document(_) => EntityKind::Document,
+            Self::Budget(_) => EntityKind::Budget,
         }
     }
 
@@ -907,6 +1423,7 @@ impl LifecycleEntityRef {
             Self::Vendor(id) => id.get(),
             Self::Incident(id) => id.get(),
             Self::Document(id) => id.get(),
+            Self::Budget(id) => id.get(),
         }
     }
 }
@@ -917,6 +1434,7 @@ enum ParentEntityRef {
     Vendor(VendorId),
     Appliance(ApplianceId),
     MaintenanceItem(MaintenanceItemId),
+    Document(DocumentId),
 }
 
 impl ParentEntityRef {
@@ -926,6 +1444,7 @@ impl ParentEntityRef {
             Self::Vendor(_) => ParentKind::Vendor,
             Self::Appliance(_) => ParentKind::Appliance,
             Self::MaintenanceItem(_) => ParentKind::MaintenanceItem,
+            Self::Document(_) => ParentKind::Document,
         }
     }
 
@@ -935,6 +1454,7 @@ impl ParentEntityRef {
             Self::Vendor(id) => id.get(),
             Self::Appliance(id) => id.get(),
             Self::MaintenanceItem(id) => id.get(),
+            Self::Document(id) => id.get(),
         }
     }
 }
@@ -942,34 +1462,40 @@ impl ParentEntityRef {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum DependentRelation {
     ProjectQuotes,
+    ProjectSubprojects,
     VendorQuotes,
     VendorIncidents,
     VendorServiceLogEntries,
     ApplianceMaintenanceItems,
     ApplianceIncidents,
     MaintenanceItemServiceLogEntries,
+    DocumentVendorCertificates,
 }
 
 impl DependentRelation {
     const fn table(self) -> &'static str {
         match self {
             Self::ProjectQuotes | Self::VendorQuotes => "quotes",
+            Self::ProjectSubprojects => "projects",
             Self::VendorIncidents | Self::ApplianceIncidents => "incidents",
             Self::VendorServiceLogEntries | Self::MaintenanceItemServiceLogEntries => {
                 "service_log_entries"
             }
             Self::ApplianceMaintenanceItems => "maintenance_items",
+            Self::DocumentVendorCertificates => "vendors",
         }
     }
 
     const fn fk_column(self) -> &'static str {
         match self {
             Self::ProjectQuotes => "project_id",
+            Self::ProjectSubprojects => "parent_project_id",
             Self::VendorQuotes | Self::VendorIncidents | Self::VendorServiceLogEntries => {
                 "vendor_id"
             }
             Self::ApplianceMaintenanceItems | Self::ApplianceIncidents => "appliance_id",
             Self::MaintenanceItemServiceLogEntries => "maintenance_item_id",
+            Self::DocumentVendorCertificates => "insurance_document_id",
         }
     }
 }
@@ -984,9 +1510,58 @@ enum EntityKind {
     Vendor,
     Incident,
     Document,
+    Budget,
 }
 
+/// Purge order, children before parents, with the columns that point at
+/// each kind of row. A row stays while any of them still refers to it.
+const PURGE_ORDER: &[(EntityKind, &[(&str, &str)])] = &[
+    (EntityKind::ServiceLogEntry, &[]),
+    (EntityKind::Quote, &[]),
+    (EntityKind::Incident, &[]),
+    (EntityKind::Budget, &[]),
+    (
+        EntityKind::MaintenanceItem,
+        &[("service_log_entries", "maintenance_item_id")],
+    ),
+    (
+        EntityKind::Project,
+        &[("quotes", "project_id"), ("projects", "parent_project_id")],
+    ),
+    (
+        EntityKind::Appliance,
+        &[
+            ("maintenance_items", "appliance_id"),
+            ("incidents", "appliance_id"),
+        ],
+    ),
+    (
+        EntityKind::Vendor,
+        &[
+            ("quotes", "vendor_id"),
+            ("incidents", "vendor_id"),
+            ("service_log_entries", "vendor_id"),
+        ],
+    ),
+    (
+        EntityKind::Document,
+        &[("vendors", "insurance_document_id")],
+    ),
+];
+
 impl EntityKind {
+    const ALL: [Self; 9] = [
+        Self::Project,
+        Self::Quote,
+        Self::MaintenanceItem,
+        Self::Appliance,
+        Self::ServiceLogEntry,
+        Self::Vendor,
+        Self::Incident,
+        Self::Document,
+        Self::Budget,
+    ];
+
     const fn table(self) -> &'static str {
         match self {
             Self::Project => "projects",
@@ -997,6 +1572,7 @@ impl EntityKind {
             Self::Vendor => "vendors",
             Self::Incident => "incidents",
             Self::Document => "documents",
+            Self::Budget => "budgets",
         }
     }
 
@@ -1010,16 +1586,192 @@ impl EntityKind {
             Self::Vendor => "vendor",
             Self::Incident => "incident",
             Self::Document => "document",
+            Self::Budget => "budget",
+        }
+    }
+
+    /// Money columns that may carry a non-home currency.
+    const fn money_fields(self) -> &'static [&'static str] {
+        match self {
+            Self::Project => &["budget_cents", "actual_cents"],
+            Self::Quote => &[
+                "total_cents",
+                "labor_cents",
+                "materials_cents",
+                "other_cents",
+            ],
+            Self::MaintenanceItem | Self::Appliance | Self::ServiceLogEntry | Self::Incident => {
+                &["cost_cents"]
+            }
+            Self::Budget => &["monthly_cents"],
+            Self::Vendor | Self::Document => &[],
+        }
+    }
+
+    /// Columns that can be edited in place, outside the full form.
+    const fn editable_fields(self) -> &'static [EditableField] {
+        use EditableField as F;
+        match self {
+            Self::Project => {
+                const {
+                    &[
+                        F::text("title", true),
+                        F::money("budget_cents", false),
+                        F::money("actual_cents", false),
+                        F::project_ref("parent_project_id"),
+                    ]
+                }
+            }
+            Self::Quote => {
+                const {
+                    &[
+                        F::money("total_cents", true),
+                        F::date("received_date", false),
+                    ]
+                }
+            }
+            Self::MaintenanceItem => {
+                const {
+                    &[
+                        F::text("name", true),
+                        F::date("last_serviced_at", false),
+                        F::money("cost_cents", false),
+                    ]
+                }
+            }
+            Self::Appliance => {
+                const {
+                    &[
+                        F::text("name", true),
+                        F::text("brand", false),
+                        F::text("location", false),
+                        F::date("warranty_expiry", false),
+                        F::money("cost_cents", false),
+                    ]
+                }
+            }
+            Self::ServiceLogEntry => {
+                const {
+                    &[
+                        F::date("serviced_at", true),
+                        F::money("cost_cents", false),
+                        F::text("notes", false),
+                    ]
+                }
+            }
+            Self::Vendor => {
+                const {
+                    &[
+                        F::text("name", true),
+                        F::text("contact_name", false),
+                        F::text("email", false),
+                        F::text("phone", false),
+                        F::text("website", false),
+                        F::text("license_number", false),
+                        F::date("insurance_expiry", false),
+                        F::document_ref("insurance_document_id"),
+                    ]
+                }
+            }
+            Self::Incident => {
+                const {
+                    &[
+                        F::text("title", true),
+                        F::date("date_noticed", true),
+                        F::date("date_resolved", false),
+                        F::date("follow_up_date", false),
+                        F::money("cost_cents", false),
+                    ]
+                }
+            }
+            Self::Document => const { &[F::text("title", true), F::text("notes", false)] },
+            Self::Budget => const { &[F::money("monthly_cents", true), F::text("notes", false)] },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditableFieldKind {
+    Text,
+    Money,
+    Date,
+    /// Optional id of another project; blank clears it.
+    ProjectRef,
+    /// Optional id of a live document; blank clears it.
+    DocumentRef,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EditableField {
+    name: &'static str,
+    kind: EditableFieldKind,
+    required: bool,
+}
+
+impl EditableField {
+    const fn text(name: &'static str, required: bool) -> Self {
+        Self {
+            name,
+            kind: EditableFieldKind::Text,
+            required,
+        }
+    }
+
+    const fn money(name: &'static str, required: bool) -> Self {
+        Self {
+            name,
+            kind: EditableFieldKind::Money,
+            required,
+        }
+    }
+
+    const fn date(name: &'static str, required: bool) -> Self {
+        Self {
+            name,
+            kind: EditableFieldKind::Date,
+            required,
+        }
+    }
+
+    const fn project_ref(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: EditableFieldKind::ProjectRef,
+            required: false,
+        }
+    }
+
+    const fn document_ref(name: &'static str) -> Self {
+        Self {
+            name,
+            kind: EditableFieldKind::DocumentRef,
+            required: false,
         }
     }
 }
 
+/// SQL expression for `amount` (a money column on `alias`, a row of `kind`)
+/// converted to home-currency cents. Rows without a currency pass through.
+fn home_cents_sql(kind: EntityKind, alias: &str, field: &str, amount: &str) -> String {
+    format!(
+        "CAST(ROUND({amount} * COALESCE((
+            SELECT cr.home_per_unit_micros
+            FROM money_currencies mcur
+            JOIN currency_rates cr ON cr.code = mcur.currency_code
+            WHERE mcur.entity = '{tag}' AND mcur.target_id = {alias}.id AND mcur.field = '{field}'
+          ), {scale}) / {scale}.0) AS INTEGER)",
+        tag = kind.deleted_tag(),
+        scale = currency::RATE_SCALE,
+    )
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ParentKind {
     Project,
     Vendor,
     Appliance,
     MaintenanceItem,
+    Document,
 }
 
 impl ParentKind {
@@ -1029,6 +1781,7 @@ impl ParentKind {
             Self::Vendor => "vendors",
             Self::Appliance => "appliances",
             Self::MaintenanceItem => "maintenance_items",
+            Self::Document => "documents",
         }
     }
 
@@ -1038,6 +1791,7 @@ impl ParentKind {
             Self::Vendor => "vendor",
             Self::Appliance => "appliance",
             Self::MaintenanceItem => "maintenance item",
+            Self::Document => "document",
         }
     }
 }
@@ -1060,6 +1814,57 @@ const fn document_target_table_and_label(
 pub struct Store {
     conn: Connection,
     max_document_size: i64,
+    /// Source stamped on audit entries; see [`Store::with_audit_source`].
+    audit_source: Cell<AuditSource>,
+    /// Set while a what-if sandbox holds every change; see
+    /// [`Store::begin_sandbox`].
+    sandbox: Cell<bool>,
+    /// Seals and opens document bytes once the database's passphrase is
+    /// entered; see [`Store::unlock_documents`].
+    document_key: RefCell<Option<crypto::DocumentKey>>,
+    /// Set while [`Store::shadow_private_rows`] hides private rows.
+    private_shadowed: Cell<bool>,
+}
+
+/// A write batch on the store's connection. Uses a savepoint rather than
+/// `BEGIN` so batches still work inside the what-if sandbox; rolls back when
+/// dropped without [`WriteBatch::commit`].
+struct WriteBatch<'a> {
+    conn: &'a Connection,
+    open: bool,
+}
+
+impl<'a> WriteBatch<'a> {
+    fn begin(conn: &'a Connection) -> rusqlite::Result<Self> {
+        conn.execute_batch("SAVEPOINT write_batch")?;
+        Ok(Self { conn, open: true })
+    }
+
+    /// On failure (a deferred foreign key, say) the batch stays open and
+    /// rolls back when dropped.
+    fn commit(mut self) -> rusqlite::Result<()> {
+        self.conn.execute_batch("RELEASE write_batch")?;
+        self.open = false;
+        Ok(())
+    }
+}
+
+impl std::ops::Deref for WriteBatch<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn
+    }
+}
+
+impl Drop for WriteBatch<'_> {
+    fn drop(&mut self) {
+        if self.open {
+            let _ = self
+                .conn
+                .execute_batch("ROLLBACK TO write_batch; RELEASE write_batch");
+        }
+    }
 }
 
 impl Store {
@@ -1072,6 +1877,10 @@ impl Store {
         Ok(Self {
             conn,
             max_document_size: MAX_DOCUMENT_SIZE,
+            audit_source: Cell::new(AuditSource::Manual),
+            sandbox: Cell::new(false),
+            document_key: RefCell::new(None),
+            private_shadowed: Cell::new(false),
         })
     }
 
@@ -1081,6 +1890,10 @@ impl Store {
         Ok(Self {
             conn,
             max_document_size: MAX_DOCUMENT_SIZE,
+            audit_source: Cell::new(AuditSource::Manual),
+            sandbox: Cell::new(false),
+            document_key: RefCell::new(None),
+            private_shadowed: Cell::new(false),
         })
     }
 
@@ -1088,6 +1901,15 @@ impl Store {
         &self.conn
     }
 
+    /// SQLite's `data_version`: it moves when another connection, such as a
+    /// second micasa on the same file, commits. Writes through this store
+    /// leave it alone.
+    pub fn data_version(&self) -> Result<i64> {
+        self.conn
+            .query_row("PRAGMA data_version", [], |row| row.get(0))
+            .context("read data_version")
+    }
+
     pub fn bootstrap(&self) -> Result<()> {
         if has_user_tables(&self.conn)? {
             validate_schema(&self.conn)?;
@@ -1097,35 +1919,109 @@ impl Store {
                 .context("create schema")?;
         }
 
+        ensure_additive_schema(&self.conn)?;
         ensure_required_indexes(&self.conn)?;
 
         self.seed_defaults()?;
         Ok(())
     }
 
-    pub fn seed_defaults(&self) -> Result<()> {
-        for project_type in DEFAULT_PROJECT_TYPES {
-            self.conn
-                .execute(
-                    "INSERT OR IGNORE INTO project_types (name) VALUES (?)",
-                    params![project_type],
-                )
-                .with_context(|| format!("insert default project type {project_type}"))?;
+    /// What `bootstrap` would change in an existing database, without
+    /// touching it. Empty for a brand-new file, which gets the whole schema.
+    pub fn pending_schema_changes(&self) -> Result<Vec<SchemaChange>> {
+        if !has_user_tables(&self.conn)? {
+            return Ok(Vec::new());
         }
+        validate_schema(&self.conn)?;
 
-        for category in DEFAULT_MAINTENANCE_CATEGORIES {
-            self.conn
-                .execute(
-                    "INSERT OR IGNORE INTO maintenance_categories (name) VALUES (?)",
-                    params![category],
-                )
-                .with_context(|| format!("insert default maintenance category {category}"))?;
+        let mut changes = Vec::new();
+        for table in ADDITIVE_TABLES {
+            if !table_exists(&self.conn, table.name)? {
+                changes.push(SchemaChange::AddTable { table: table.name });
+            }
         }
-        Ok(())
-    }
-
-    pub fn seed_demo_data(&self) -> Result<()> {
-        self.seed_demo_data_with_seed(42)
+        for column in ADDITIVE_COLUMNS {
+            if !table_columns(&self.conn, column.table)?.contains(column.name) {
+                changes.push(SchemaChange::AddColumn {
+                    table: column.table,
+                    column: column.name,
+                    definition: column.definition,
+                });
+            }
+        }
+        let indexes = index_names(&self.conn)?;
+        for index in REQUIRED_INDEXES {
+            if !indexes.contains(index.name) {
+                changes.push(SchemaChange::AddIndex { name: index.name });
+            }
+        }
+        for (kind, defaults) in self.unseeded_lookups()? {
+            changes.push(SchemaChange::SeedDefaults {
+                kind,
+                count: defaults.len(),
+            });
+        }
+        Ok(changes)
+    }
+
+    /// Writes a consistent copy of the database to `path`, which must not
+    /// exist yet.
+    pub fn backup_to(&self, path: &Path) -> Result<()> {
+        if path.exists() {
+            bail!(
+                "backup target {} already exists; move it aside and retry",
+                path.display()
+            );
+        }
+        self.conn
+            .execute("VACUUM INTO ?", params![path.to_string_lossy()])
+            .with_context(|| format!("back up database to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Lookup lists that are still empty, with the names they get seeded
+    /// with. Only an empty list is seeded, so renamed or merged defaults stay
+    /// gone.
+    fn unseeded_lookups(&self) -> Result<Vec<(LookupKind, &'static [&'static str])>> {
+        let mut unseeded = Vec::new();
+        for (kind, defaults) in [
+            (LookupKind::ProjectType, DEFAULT_PROJECT_TYPES.as_slice()),
+            (
+                LookupKind::MaintenanceCategory,
+                DEFAULT_MAINTENANCE_CATEGORIES.as_slice(),
+            ),
+        ] {
+            let table = lookup_tables(kind).table;
+            let count: i64 = self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                    row.get(0)
+                })
+                .with_context(|| format!("count {}", kind.title()))?;
+            if count == 0 {
+                unseeded.push((kind, defaults));
+            }
+        }
+        Ok(unseeded)
+    }
+
+    pub fn seed_defaults(&self) -> Result<()> {
+        for (kind, defaults) in self.unseeded_lookups()? {
+            let table = lookup_tables(kind).table;
+            for name in defaults {
+                self.conn
+                    .execute(
+                        &format!("INSERT OR IGNORE INTO {table} (name) VALUES (?)"),
+                        params![name],
+                    )
+                    .with_context(|| format!("insert default {} {name}", kind.label()))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn seed_demo_data(&self) -> Result<()> {
+        self.seed_demo_data_with_seed(42)
     }
 
     pub fn seed_demo_data_with_seed(&self, seed: u64) -> Result<()> {
@@ -1212,6 +2108,7 @@ impl Store {
                 end_date,
                 budget_cents,
                 actual_cents,
+                parent_id: None,
             })?;
             projects.push(SeedProjectRef {
                 id: project_id,
@@ -1286,6 +2183,7 @@ impl Store {
                 appliance_id: appliances.get(index).copied(),
                 vendor_id: vendors.get(index).copied(),
                 notes: String::new(),
+                follow_up_date: None,
             })?;
             incidents.push(incident_id);
         }
@@ -1354,6 +2252,7 @@ impl Store {
                     end_date,
                     budget_cents,
                     actual_cents,
+                    parent_id: None,
                 })?;
                 let project_ref = SeedProjectRef {
                     id: project_id,
@@ -1434,6 +2333,7 @@ impl Store {
                         Some(vendors[rng.int_n(vendors.len())])
                     },
                     notes: String::new(),
+                    follow_up_date: None,
                 })?;
                 incidents.push(incident_id);
                 summary.incidents += 1;
@@ -1511,6 +2411,250 @@ impl Store {
             .context("collect maintenance categories")
     }
 
+    /// Entries of a lookup list sorted by name, each with the number of live
+    /// records that use it.
+    pub fn list_lookup_entries(&self, kind: LookupKind) -> Result<Vec<LookupEntry>> {
+        let lookup = lookup_tables(kind);
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "
+                SELECT l.id, l.name, COUNT(r.id)
+                FROM {table} l
+                LEFT JOIN {records} r ON r.{column} = l.id AND r.deleted_at IS NULL
+                GROUP BY l.id
+                ORDER BY l.name ASC
+                ",
+                table = lookup.table,
+                records = lookup.records,
+                column = lookup.column,
+            ))
+            .with_context(|| format!("prepare {} query", kind.title()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(LookupEntry {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    uses: row.get::<_, i64>(2)?.try_into().unwrap_or_default(),
+                })
+            })
+            .with_context(|| format!("query {}", kind.title()))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| format!("collect {}", kind.title()))
+    }
+
+    pub fn create_lookup_entry(&self, kind: LookupKind, name: &str) -> Result<i64> {
+        let name = self.check_lookup_name(kind, name, None)?;
+        let now = now_rfc3339()?;
+        self.conn
+            .execute(
+                &format!(
+                    "INSERT INTO {} (name, created_at, updated_at) VALUES (?, ?, ?)",
+                    lookup_tables(kind).table
+                ),
+                params![name, now, now],
+            )
+            .with_context(|| format!("insert {} {name}", kind.label()))?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    pub fn rename_lookup_entry(&self, kind: LookupKind, id: i64, name: &str) -> Result<()> {
+        let name = self.check_lookup_name(kind, name, Some(id))?;
+        let rows_affected = self
+            .conn
+            .execute(
+                &format!(
+                    "UPDATE {} SET name = ?, updated_at = ? WHERE id = ?",
+                    lookup_tables(kind).table
+                ),
+                params![name, now_rfc3339()?, id],
+            )
+            .with_context(|| format!("rename {} {id}", kind.label()))?;
+        if rows_affected == 0 {
+            bail!(
+                "{} {id} not found -- refresh the list and pick an existing one",
+                kind.label()
+            );
+        }
+        Ok(())
+    }
+
+    /// Moves every record, budget and cost split from `from` onto `into`,
+    /// then removes `from`. Returns the number of records moved. Fails when
+    /// both entries have a live budget, since only one can remain.
+    pub fn merge_lookup_entries(&self, kind: LookupKind, from: i64, into: i64) -> Result<usize> {
+        let label = kind.label();
+        if from == into {
+            bail!("pick a different {label} to merge into");
+        }
+        let lookup = lookup_tables(kind);
+        for id in [from, into] {
+            let exists: bool = self
+                .conn
+                .query_row(
+                    &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", lookup.table),
+                    params![id],
+                    |row| row.get(0),
+                )
+                .with_context(|| format!("look up {label} {id}"))?;
+            if !exists {
+                bail!("{label} {id} not found -- refresh the list and pick an existing one");
+            }
+        }
+        let budgets: i64 = self
+            .conn
+            .query_row(
+                "
+                SELECT COUNT(DISTINCT category_id) FROM budgets
+                WHERE scope = ? AND category_id IN (?, ?) AND deleted_at IS NULL
+                ",
+                params![lookup.budget_scope, from, into],
+                |row| row.get(0),
+            )
+            .context("look up budgets to merge")?;
+        if budgets > 1 {
+            bail!("both {label} entries have a budget -- delete one of them first");
+        }
+
+        let now = now_rfc3339()?;
+        let tx = WriteBatch::begin(&self.conn).with_context(|| format!("begin {label} merge"))?;
+        let moved = tx
+            .execute(
+                &format!(
+                    "UPDATE {records} SET {column} = ?, updated_at = ? WHERE {column} = ?",
+                    records = lookup.records,
+                    column = lookup.column,
+                ),
+                params![into, now, from],
+            )
+            .with_context(|| format!("move {} records", lookup.records))?;
+        tx.execute(
+            "UPDATE budgets SET category_id = ?, updated_at = ? WHERE scope = ? AND category_id = ?",
+            params![into, now, lookup.budget_scope, from],
+        )
+        .context("move budgets")?;
+        if kind == LookupKind::MaintenanceCategory {
+            // A row split across both categories keeps one split holding
+            // the combined amount.
+            tx.execute(
+                "
+                INSERT INTO cost_splits (entity, target_id, category_id, amount_cents)
+                SELECT entity, target_id, ?, amount_cents FROM cost_splits WHERE category_id = ?
+                ON CONFLICT (entity, target_id, category_id)
+                DO UPDATE SET amount_cents = amount_cents + excluded.amount_cents
+                ",
+                params![into, from],
+            )
+            .context("move cost splits")?;
+            tx.execute(
+                "DELETE FROM cost_splits WHERE category_id = ?",
+                params![from],
+            )
+            .context("clear merged cost splits")?;
+        }
+        tx.execute(
+            &format!("DELETE FROM {} WHERE id = ?", lookup.table),
+            params![from],
+        )
+        .with_context(|| format!("remove merged {label} {from}"))?;
+        tx.commit()
+            .with_context(|| format!("commit {label} merge"))?;
+        Ok(moved)
+    }
+
+    /// Removes an entry nothing points at: no record, deleted ones included,
+    /// no budget and no cost split. The last entry stays so the forms always
+    /// have one to pick.
+    pub fn delete_lookup_entry(&self, kind: LookupKind, id: i64) -> Result<()> {
+        let label = kind.label();
+        let lookup = lookup_tables(kind);
+        let name: Option<String> = self
+            .conn
+            .query_row(
+                &format!("SELECT name FROM {} WHERE id = ?", lookup.table),
+                params![id],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("look up {label} {id}"))?;
+        let Some(name) = name else {
+            bail!("{label} {id} not found -- refresh the list and pick an existing one");
+        };
+        let count = |sql: String| -> Result<i64> {
+            self.conn
+                .query_row(&sql, params![id], |row| row.get(0))
+                .with_context(|| format!("count uses of {label} {name}"))
+        };
+        let others = count(format!(
+            "SELECT COUNT(*) FROM {} WHERE id <> ?",
+            lookup.table
+        ))?;
+        if others == 0 {
+            bail!("{name} is the only {label} left -- add another before deleting it");
+        }
+        let records = count(format!(
+            "SELECT COUNT(*) FROM {} WHERE {} = ?",
+            lookup.records, lookup.column
+        ))?;
+        if records > 0 {
+            let noun = kind.record_label();
+            let plural = if records == 1 { "" } else { "s" };
+            bail!(
+                "{label} {name} is used by {records} {noun}{plural}, deleted ones included -- merge it into another instead"
+            );
+        }
+        let budgets = count(format!(
+            "SELECT COUNT(*) FROM budgets WHERE scope = '{}' AND category_id = ?",
+            lookup.budget_scope
+        ))?;
+        if budgets > 0 {
+            bail!("{label} {name} has a budget -- merge it into another instead");
+        }
+        if kind == LookupKind::MaintenanceCategory
+            && count("SELECT COUNT(*) FROM cost_splits WHERE category_id = ?".to_owned())? > 0
+        {
+            bail!("{label} {name} holds cost splits -- merge it into another instead");
+        }
+        self.conn
+            .execute(
+                &format!("DELETE FROM {} WHERE id = ?", lookup.table),
+                params![id],
+            )
+            .with_context(|| format!("delete {label} {name}"))?;
+        Ok(())
+    }
+
+    /// Trimmed `name` when it is non-empty and no other entry (other than
+    /// `except`) already uses it, ignoring case.
+    fn check_lookup_name(
+        &self,
+        kind: LookupKind,
+        name: &str,
+        except: Option<i64>,
+    ) -> Result<String> {
+        let label = kind.label();
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("{label} name is required");
+        }
+        let existing: Option<String> = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT name FROM {} WHERE name = ? COLLATE NOCASE AND id IS NOT ?",
+                    lookup_tables(kind).table
+                ),
+                params![name, except],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("look up {label} {name}"))?;
+        if let Some(existing) = existing {
+            bail!("{label} `{existing}` already exists -- merge into it instead");
+        }
+        Ok(name.to_owned())
+    }
+
     pub fn table_names(&self) -> Result<Vec<String>> {
         let mut stmt = self
             .conn
@@ -1520,6 +2664,9 @@ impl Store {
                 FROM sqlite_master
                 WHERE type = 'table'
                   AND name NOT LIKE 'sqlite_%'
+                  AND name <> 'private_rows'
+                  AND name <> 'document_chunks'
+                  AND name <> 'manual_chunks'
                 ORDER BY name ASC
                 ",
             )
@@ -1527,8 +2674,266 @@ impl Store {
         let rows = stmt
             .query_map([], |row| row.get::<_, String>(0))
             .context("query table names")?;
-        rows.collect::<rusqlite::Result<Vec<_>>>()
-            .context("collect table names")
+        let mut names = rows
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect table names")?;
+        if self.private_shadowed.get() {
+            names.retain(|name| !PRIVATE_SIDE_TABLES.contains(&name.as_str()));
+        }
+        Ok(names)
+    }
+
+    /// Dumps every table, `private_rows` and lookups included, ordered by
+    /// table name and rowid.
+    pub fn dump_tables(&self) -> Result<Vec<TableDump>> {
+        let mut dumps = Vec::new();
+        for table in user_table_names(&self.conn)? {
+            let columns = self
+                .table_columns(&table)?
+                .into_iter()
+                .map(|column| column.name)
+                .collect::<Vec<_>>();
+            let mut stmt = self
+                .conn
+                .prepare(&format!(
+                    "SELECT {} FROM {table} ORDER BY rowid ASC",
+                    columns.join(", ")
+                ))
+                .with_context(|| format!("prepare dump of {table}"))?;
+            let rows = stmt
+                .query_map([], |row| {
+                    (0..columns.len())
+                        .map(|index| {
+                            Ok(match row.get_ref(index)? {
+                                ValueRef::Null => DumpValue::Null,
+                                ValueRef::Integer(value) => DumpValue::Integer(value),
+                                ValueRef::Real(value) => DumpValue::Real(value),
+                                ValueRef::Text(value) => {
+                                    DumpValue::Text(String::from_utf8_lossy(value).into_owned())
+                                }
+                                ValueRef::Blob(value) => DumpValue::Blob(value.to_vec()),
+                            })
+                        })
+                        .collect::<rusqlite::Result<Vec<_>>>()
+                })
+                .with_context(|| format!("dump {table}"))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .with_context(|| format!("collect rows of {table}"))?;
+            dumps.push(TableDump {
+                table,
+                columns,
+                rows,
+            });
+        }
+        Ok(dumps)
+    }
+
+    /// [`Self::dump_tables`] minus private rows, for exports made while they
+    /// are locked. Rows whose foreign keys lead to a private row go too, as
+    /// do `(entity, target_id)` entries naming one and the `private_rows`
+    /// markers themselves, so the dump still imports cleanly.
+    pub fn dump_tables_without_private(&self) -> Result<Vec<TableDump>> {
+        self.dump_tables_hiding(&["private_rows"])
+    }
+
+    /// [`Self::dump_tables_without_private`] that also drops archived rows,
+    /// the same way, when `hide_archived` is set.
+    pub fn dump_visible_tables(
+        &self,
+        hide_private: bool,
+        hide_archived: bool,
+    ) -> Result<Vec<TableDump>> {
+        let mut markers = Vec::new();
+        if hide_private {
+            markers.push("private_rows");
+        }
+        if hide_archived {
+            markers.push("archived_rows");
+        }
+        self.dump_tables_hiding(&markers)
+    }
+
+    /// Dumps every table without the rows that `markers` tables (each an
+    /// `(entity, target_id)` list) point at, or anything leading to them.
+    fn dump_tables_hiding(&self, markers: &[&str]) -> Result<Vec<TableDump>> {
+        let mut hidden: BTreeMap<String, BTreeSet<i64>> = BTreeMap::new();
+        let mut marked = Vec::new();
+        for marker in markers {
+            let mut stmt = self
+                .conn
+                .prepare(&format!("SELECT entity, target_id FROM {marker}"))
+                .with_context(|| format!("prepare {marker} query"))?;
+            marked.extend(
+                stmt.query_map([], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+                })
+                .with_context(|| format!("query {marker}"))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .with_context(|| format!("collect {marker}"))?,
+            );
+        }
+        for (tag, id) in marked {
+            if let Some(kind) = EntityKind::ALL
+                .into_iter()
+                .find(|kind| kind.deleted_tag() == tag)
+            {
+                hidden
+                    .entry(kind.table().to_owned())
+                    .or_default()
+                    .insert(id);
+            }
+        }
+
+        let mut dumps = self.dump_tables()?;
+        let mut foreign_keys = BTreeMap::new();
+        for dump in &dumps {
+            let mut stmt = self
+                .conn
+                .prepare(&format!("PRAGMA foreign_key_list({})", dump.table))
+                .with_context(|| format!("prepare foreign keys of {}", dump.table))?;
+            let keys = stmt
+                .query_map([], |row| {
+                    Ok((row.get::<_, String>(3)?, row.get::<_, String>(2)?))
+                })
+                .with_context(|| format!("query foreign keys of {}", dump.table))?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .with_context(|| format!("collect foreign keys of {}", dump.table))?
+                .into_iter()
+                .filter_map(|(from, to_table)| {
+                    let index = dump.columns.iter().position(|column| *column == from)?;
+                    Some((index, to_table))
+                })
+                .collect::<Vec<_>>();
+            foreign_keys.insert(dump.table.clone(), keys);
+        }
+        let integer = |value: &DumpValue| match value {
+            DumpValue::Integer(value) => Some(*value),
+            _ => None,
+        };
+        let is_hidden = |hidden: &BTreeMap<String, BTreeSet<i64>>, table: &str, id| {
+            hidden.get(table).is_some_and(|ids| ids.contains(&id))
+        };
+
+        // Dropping a row can orphan rows pointing at it, so repeat until
+        // nothing else goes.
+        loop {
+            let mut changed = false;
+            for dump in &mut dumps {
+                if markers.contains(&dump.table.as_str()) {
+                    dump.rows.clear();
+                    continue;
+                }
+                let column = |name: &str| dump.columns.iter().position(|column| column == name);
+                let id_index = column("id");
+                let target = column("entity").zip(column("target_id"));
+                let keys = &foreign_keys[&dump.table];
+                let mut dropped = Vec::new();
+                dump.rows.retain(|row| {
+                    let own = id_index
+                        .and_then(|index| integer(&row[index]))
+                        .is_some_and(|id| is_hidden(&hidden, &dump.table, id));
+                    let referenced = keys.iter().any(|(index, table)| {
+                        integer(&row[*index]).is_some_and(|id| is_hidden(&hidden, table, id))
+                    });
+                    let named = target.is_some_and(|(entity, target_id)| {
+                        let DumpValue::Text(tag) = &row[entity] else {
+                            return false;
+                        };
+                        let table = EntityKind::ALL
+                            .into_iter()
+                            .find(|kind| kind.deleted_tag() == tag)
+                            .map(EntityKind::table);
+                        table
+                            .zip(integer(&row[target_id]))
+                            .is_some_and(|(table, id)| is_hidden(&hidden, table, id))
+                    });
+                    if own || referenced || named {
+                        if let Some(id) = id_index.and_then(|index| integer(&row[index])) {
+                            dropped.push(id);
+                        }
+                        return false;
+                    }
+                    true
+                });
+                let ids = hidden.entry(dump.table.clone()).or_default();
+                for id in dropped {
+                    changed |= ids.insert(id);
+                }
+            }
+            if !changed {
+                return Ok(dumps);
+            }
+        }
+    }
+
+    /// Replaces the contents of every table with `dumps` in one transaction.
+    /// Tables missing from the dump end up empty, and columns missing from a
+    /// table dump take their defaults. Returns the number of rows written.
+    pub fn restore_tables(&self, dumps: &[TableDump]) -> Result<usize> {
+        let tables = user_table_names(&self.conn)?;
+        for dump in dumps {
+            if !tables.contains(&dump.table) {
+                bail!(
+                    "unknown table `{}` in import; it may come from a newer micasa -- upgrade and retry",
+                    dump.table
+                );
+            }
+            let known = table_columns(&self.conn, &dump.table)?;
+            if let Some(column) = dump.columns.iter().find(|column| !known.contains(*column)) {
+                bail!(
+                    "unknown column `{}.{column}` in import; it may come from a newer micasa -- upgrade and retry",
+                    dump.table
+                );
+            }
+            if let Some(index) = dump
+                .rows
+                .iter()
+                .position(|row| row.len() != dump.columns.len())
+            {
+                bail!(
+                    "row {} of `{}` has the wrong number of values; expected {}",
+                    index + 1,
+                    dump.table,
+                    dump.columns.len()
+                );
+            }
+        }
+
+        let tx = WriteBatch::begin(&self.conn).context("begin database import")?;
+        tx.execute_batch("PRAGMA defer_foreign_keys = ON;")
+            .context("defer foreign keys for import")?;
+        for table in &tables {
+            tx.execute(&format!("DELETE FROM {table}"), [])
+                .with_context(|| format!("clear {table}"))?;
+        }
+        let mut written = 0;
+        for dump in dumps.iter().filter(|dump| !dump.columns.is_empty()) {
+            let sql = format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                dump.table,
+                dump.columns.join(", "),
+                vec!["?"; dump.columns.len()].join(", ")
+            );
+            let mut stmt = tx
+                .prepare(&sql)
+                .with_context(|| format!("prepare import into {}", dump.table))?;
+            for row in &dump.rows {
+                let values = row.iter().map(|value| match value {
+                    DumpValue::Null => rusqlite::types::Value::Null,
+                    DumpValue::Integer(value) => rusqlite::types::Value::Integer(*value),
+                    DumpValue::Real(value) => rusqlite::types::Value::Real(*value),
+                    DumpValue::Text(value) => rusqlite::types::Value::Text(value.clone()),
+                    DumpValue::Blob(value) => rusqlite::types::Value::Blob(value.clone()),
+                });
+                stmt.execute(params_from_iter(values))
+                    .with_context(|| format!("import row into {}", dump.table))?;
+                written += 1;
+            }
+        }
+        tx.commit().context(
+            "commit database import; the file references rows it does not contain -- re-export it and retry",
+        )?;
+        Ok(written)
     }
 
     pub fn table_columns(&self, table: &str) -> Result<Vec<PragmaColumn>> {
@@ -1558,8 +2963,73 @@ impl Store {
             .with_context(|| format!("collect columns for {table}"))
     }
 
+    /// Every table `table_names` lists, with column types, foreign keys, and
+    /// the descriptions from [`schema_docs`]. Read live, so it follows
+    /// migrations without a rebuild.
+    pub fn schema_docs(&self) -> Result<Vec<schema_docs::TableDoc>> {
+        let mut tables = Vec::new();
+        for name in self.table_names()? {
+            let references = self.foreign_keys(&name)?;
+            let columns = self
+                .table_columns(&name)?
+                .into_iter()
+                .map(|column| schema_docs::ColumnDoc {
+                    description: schema_docs::column_description(&name, &column.name),
+                    references: references
+                        .iter()
+                        .find(|(from, _)| *from == column.name)
+                        .map(|(_, target)| target.clone()),
+                    name: column.name,
+                    column_type: column.column_type,
+                    not_null: column.not_null,
+                    primary_key: column.primary_key > 0,
+                })
+                .collect();
+            tables.push(schema_docs::TableDoc {
+                description: schema_docs::table_description(&name).to_owned(),
+                name,
+                columns,
+            });
+        }
+        Ok(tables)
+    }
+
+    /// `(column, "table(column)")` for each foreign key on `table`.
+    fn foreign_keys(&self, table: &str) -> Result<Vec<(String, String)>> {
+        if !is_safe_identifier(table) {
+            bail!("invalid table name: {table:?}");
+        }
+        let mut stmt = self
+            .conn
+            .prepare(&format!("PRAGMA foreign_key_list({table})"))
+            .with_context(|| format!("inspect foreign keys for {table}"))?;
+        let rows = stmt
+            .query_map([], |row| {
+                let target: String = row.get(2)?;
+                let from: String = row.get(3)?;
+                let to: Option<String> = row.get(4)?;
+                Ok((from, format!("{target}({})", to.as_deref().unwrap_or("id"))))
+            })
+            .with_context(|| format!("query foreign keys for {table}"))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| format!("collect foreign keys for {table}"))
+    }
+
     pub fn read_only_query(&self, query: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
-        let trimmed = query.trim();
+        let page = self.read_only_query_page(query, 0, MAX_QUERY_ROWS)?;
+        Ok((page.columns, page.rows))
+    }
+
+    /// Rows `page * page_rows ..` of a guarded read-only query, plus whether
+    /// more follow. Only a single SELECT (optionally behind a WITH) passes,
+    /// and SQLite must also report the prepared statement as read-only.
+    pub fn read_only_query_page(
+        &self,
+        query: &str,
+        page: usize,
+        page_rows: usize,
+    ) -> Result<QueryPage> {
+        let trimmed = query.trim().trim_end_matches(';').trim_end();
         if trimmed.is_empty() {
             bail!("empty query");
         }
@@ -1568,7 +3038,7 @@ impl Store {
         }
 
         let upper = trimmed.to_ascii_uppercase();
-        if !upper.starts_with("SELECT") {
+        if !upper.starts_with("SELECT") && !upper.starts_with("WITH") {
             bail!("only SELECT queries are allowed");
         }
 
@@ -1582,10 +3052,10 @@ impl Store {
             }
         }
 
-        let mut stmt = self
-            .conn
-            .prepare(trimmed)
-            .context("prepare read-only query")?;
+        let mut stmt = self.prepare_guarded(trimmed)?;
+        if !stmt.readonly() {
+            bail!("only SELECT queries are allowed");
+        }
         let columns = stmt
             .column_names()
             .iter()
@@ -1593,9 +3063,17 @@ impl Store {
             .collect::<Vec<_>>();
         let mut rows = stmt.query([]).context("execute read-only query")?;
 
+        let skip = page.saturating_mul(page_rows);
+        let mut seen = 0_usize;
         let mut output_rows = Vec::new();
+        let mut has_more = false;
         while let Some(row) = rows.next().context("scan read-only query rows")? {
-            if output_rows.len() >= MAX_QUERY_ROWS {
+            seen += 1;
+            if seen <= skip {
+                continue;
+            }
+            if output_rows.len() >= page_rows {
+                has_more = true;
                 break;
             }
 
@@ -1610,7 +3088,73 @@ impl Store {
             output_rows.push(output);
         }
 
-        Ok((columns, output_rows))
+        Ok(QueryPage {
+            sql: trimmed.to_owned(),
+            columns,
+            rows: output_rows,
+            page,
+            has_more,
+        })
+    }
+
+    /// Prepares an ad hoc query. While private rows are hidden, an
+    /// authorizer refuses what the shadow views can't cover: the real tables
+    /// behind them (`main.projects`) and the side tables that describe rows.
+    fn prepare_guarded(&self, sql: &str) -> Result<rusqlite::Statement<'_>> {
+        if !self.private_shadowed.get() {
+            return self.conn.prepare(sql).context("prepare read-only query");
+        }
+        if let Some(name) = common_table_names(sql).into_iter().find(|name| {
+            EntityKind::ALL.iter().any(|kind| kind.table() == name)
+                || PRIVATE_SIDE_TABLES.contains(&name.as_str())
+        }) {
+            bail!(
+                "a WITH clause can't reuse the table name {name} while private rows are hidden \
+                 -- give it another name"
+            );
+        }
+        let denied = Arc::new(Mutex::new(None::<String>));
+        let record = Arc::clone(&denied);
+        self.conn.authorizer(Some(move |context: AuthContext<'_>| {
+            let AuthAction::Read { table_name, .. } = context.action else {
+                return Authorization::Allow;
+            };
+            // The shadow views read their own table and private_rows; every
+            // other path to those tables (qualified names, CTEs, subqueries)
+            // is refused. Side tables aren't read by any view.
+            let view = context
+                .accessor
+                .filter(|accessor| EntityKind::ALL.iter().any(|kind| kind.table() == *accessor));
+            let allowed = if table_name == "private_rows" {
+                view.is_some()
+            } else if PRIVATE_SIDE_TABLES.contains(&table_name) {
+                false
+            } else if context.database_name == Some("main")
+                && EntityKind::ALL
+                    .iter()
+                    .any(|kind| kind.table() == table_name)
+            {
+                view == Some(table_name)
+            } else {
+                true
+            };
+            if allowed {
+                return Authorization::Allow;
+            }
+            *record.lock().unwrap_or_else(PoisonError::into_inner) = Some(table_name.to_owned());
+            Authorization::Deny
+        }));
+        let prepared = self.conn.prepare(sql);
+        self.conn
+            .authorizer(None::<fn(AuthContext<'_>) -> Authorization>);
+        let denied = denied.lock().unwrap_or_else(PoisonError::into_inner).take();
+        match (prepared, denied) {
+            (Err(_), Some(table)) => bail!(
+                "{table} can't be read while private rows are hidden -- query tables by their \
+                 plain names, or show private rows first"
+            ),
+            (prepared, _) => prepared.context("prepare read-only query"),
+        }
     }
 
     pub fn data_dump(&self) -> String {
@@ -1715,7 +3259,8 @@ impl Store {
                   foundation_type, wiring_type, roof_type, exterior_type,
                   heating_type, cooling_type, water_source, sewer_type, parking_type,
                   basement_type, insurance_carrier, insurance_policy, insurance_renewal,
-                  property_tax_cents, hoa_name, hoa_fee_cents, created_at, updated_at
+                  property_tax_cents, hoa_name, hoa_fee_cents, created_at, updated_at,
+                  latitude, longitude
                 FROM house_profiles
                 ORDER BY id ASC
                 LIMIT 1
@@ -1757,6 +3302,8 @@ impl Store {
                         hoa_fee_cents: row.get(27)?,
                         created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                         updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
+                        latitude: row.get(30)?,
+                        longitude: row.get(31)?,
                     })
                 },
             )
@@ -1764,6 +3311,24 @@ impl Store {
             .context("load house profile")
     }
 
+    /// Nickname of the house that exports, reports and chat are scoped to.
+    /// There is one house profile today, so this is that profile; `None`
+    /// until it exists or while its nickname is blank.
+    pub fn active_house_name(&self) -> Result<Option<String>> {
+        let nickname: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT nickname FROM house_profiles ORDER BY id ASC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("load active house")?;
+        Ok(nickname
+            .map(|name| name.trim().to_owned())
+            .filter(|name| !name.is_empty()))
+    }
+
     pub fn create_house_profile(&self, profile: &HouseProfileInput) -> Result<HouseProfileId> {
         let count: i64 = self
             .conn
@@ -1912,6 +3477,32 @@ impl Store {
         Ok(())
     }
 
+    /// Sets or clears the coordinates used for weather forecasts. Kept apart
+    /// from the profile form so saving the form leaves them alone.
+    pub fn set_house_location(&self, location: Option<(f64, f64)>) -> Result<()> {
+        if let Some((lat, lon)) = location {
+            weather::validate_location(lat, lon)?;
+        }
+        let (lat, lon) = location.unzip();
+        let rows_affected = self
+            .conn
+            .execute(
+                "
+                UPDATE house_profiles
+                SET latitude = ?, longitude = ?, updated_at = ?
+                WHERE id = (SELECT id FROM house_profiles ORDER BY id ASC LIMIT 1)
+                ",
+                params![lat, lon, now_rfc3339()?],
+            )
+            .context("update house location")?;
+        if rows_affected == 0 {
+            bail!(
+                "house profile not found -- create one (press p in the app) before setting a location"
+            );
+        }
+        Ok(())
+    }
+
     pub fn upsert_house_profile(&self, profile: &HouseProfileInput) -> Result<HouseProfileId> {
         if let Some(existing) = self.get_house_profile()? {
             self.update_house_profile(profile)?;
@@ -1921,6 +3512,9 @@ impl Store {
     }
 
     pub fn create_project(&self, new_project: &NewProject) -> Result<ProjectId> {
+        if let Some(parent_id) = new_project.parent_id {
+            self.check_project_parent(None, parent_id)?;
+        }
         let now = now_rfc3339()?;
         self.conn
             .execute(
@@ -1928,8 +3522,8 @@ impl Store {
                 INSERT INTO projects (
                   title, project_type_id, status, description,
                   start_date, end_date, budget_cents, actual_cents,
-                  created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                  parent_project_id, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 ",
                 params![
                     new_project.title,
@@ -1940,6 +3534,7 @@ impl Store {
                     new_project.end_date.map(format_date),
                     new_project.budget_cents,
                     new_project.actual_cents,
+                    new_project.parent_id.map(ProjectId::get),
                     now,
                     now,
                 ],
@@ -1950,6 +3545,9 @@ impl Store {
     }
 
     pub fn update_project(&self, project_id: ProjectId, update: &UpdateProject) -> Result<()> {
+        if let Some(parent_id) = update.parent_id {
+            self.check_project_parent(Some(project_id), parent_id)?;
+        }
         let now = now_rfc3339()?;
         let rows_affected = self
             .conn
@@ -1965,6 +3563,7 @@ impl Store {
                   end_date = ?,
                   budget_cents = ?,
                   actual_cents = ?,
+                  parent_project_id = ?,
                   updated_at = ?
                 WHERE id = ? AND deleted_at IS NULL
                 ",
@@ -1977,6 +3576,7 @@ impl Store {
                     update.end_date.map(format_date),
                     update.budget_cents,
                     update.actual_cents,
+                    update.parent_id.map(ProjectId::get),
                     now,
                     project_id.get(),
                 ],
@@ -1998,7 +3598,7 @@ impl Store {
                 SELECT
                   id, title, project_type_id, status, description,
                   start_date, end_date, budget_cents, actual_cents,
-                  created_at, updated_at, deleted_at
+                  created_at, updated_at, deleted_at, parent_project_id
                 FROM projects
                 WHERE id = ?
                 ",
@@ -2035,6 +3635,7 @@ impl Store {
                         created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                         updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                         deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
+                        parent_id: row.get::<_, Option<i64>>(12)?.map(ProjectId::new),
                     })
                 },
             )
@@ -2047,7 +3648,7 @@ impl Store {
             SELECT
               id, title, project_type_id, status, description,
               start_date, end_date, budget_cents, actual_cents,
-              created_at, updated_at, deleted_at
+              created_at, updated_at, deleted_at, parent_project_id
             FROM projects
             ",
         );
@@ -2090,6 +3691,7 @@ impl Store {
                     created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                     updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                     deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
+                    parent_id: row.get::<_, Option<i64>>(12)?.map(ProjectId::new),
                 })
             })
             .context("query projects")?;
@@ -2108,20 +3710,271 @@ impl Store {
         self.restore_entity(target.kind(), target.id())
     }
 
-    pub fn soft_delete_project(&self, project_id: ProjectId) -> Result<()> {
-        self.soft_delete(LifecycleEntityRef::Project(project_id))
+    /// How many rows are soft-deleted across every tab.
+    pub fn deleted_row_count(&self) -> Result<usize> {
+        let mut total = 0;
+        for kind in EntityKind::ALL {
+            let count: i64 = self
+                .conn
+                .query_row(
+                    &format!(
+                        "SELECT COUNT(*) FROM {} WHERE deleted_at IS NOT NULL",
+                        kind.table()
+                    ),
+                    [],
+                    |row| row.get(0),
+                )
+                .with_context(|| format!("count deleted rows in {}", kind.table()))?;
+            total += usize::try_from(count).unwrap_or_default();
+        }
+        Ok(total)
     }
 
-    pub fn restore_project(&self, project_id: ProjectId) -> Result<()> {
-        self.restore(LifecycleEntityRef::Project(project_id))
+    /// Permanently removes soft-deleted rows, or with `deleted_before` only
+    /// those deleted before then, along with their deletion records, flags,
+    /// tags, splits and currencies. A row another row still points at stays
+    /// and is counted as kept; it goes once that row is purged too.
+    pub fn purge_deleted(&self, deleted_before: Option<OffsetDateTime>) -> Result<PurgeSummary> {
+        let tx = WriteBatch::begin(&self.conn).context("begin purge")?;
+        let mut summary = PurgeSummary::default();
+        for (kind, references) in PURGE_ORDER {
+            // Sub-projects point at their parent, so projects may take a few
+            // passes.
+            let mut kept;
+            loop {
+                kept = 0;
+                let mut purged = 0;
+                for id in self.purge_candidates(*kind, deleted_before)? {
+                    if self.purge_blocked(id, references)? {
+                        kept += 1;
+                        continue;
+                    }
+                    self.purge_row(*kind, id)?;
+                    purged += 1;
+                }
+                summary.purged += purged;
+                if purged == 0 || kept == 0 {
+                    break;
+                }
+            }
+            summary.kept += kept;
+        }
+        tx.execute(
+            "DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM entity_tags)",
+            [],
+        )
+        .context("drop unused tags")?;
+        tx.commit().context("commit purge")?;
+        Ok(summary)
     }
 
-    pub fn list_vendors(&self, include_deleted: bool) -> Result<Vec<Vendor>> {
-        let mut sql = String::from(
-            "
-            SELECT
-              id, name, contact_name, email, phone, website, notes,
-              created_at, updated_at, deleted_at
+    /// Deleted rows of `kind` that are due; a deletion time that doesn't
+    /// parse is never due for an age-based purge.
+    fn purge_candidates(
+        &self,
+        kind: EntityKind,
+        deleted_before: Option<OffsetDateTime>,
+    ) -> Result<Vec<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT id, deleted_at FROM {} WHERE deleted_at IS NOT NULL ORDER BY id",
+                kind.table()
+            ))
+            .with_context(|| format!("prepare deleted {} query", kind.table()))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .with_context(|| format!("query deleted {}", kind.table()))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| format!("scan deleted {}", kind.table()))?;
+        Ok(rows
+            .into_iter()
+            .filter(|(_, deleted_at)| match deleted_before {
+                None => true,
+                Some(cutoff) => parse_datetime(deleted_at).is_ok_and(|at| at < cutoff),
+            })
+            .map(|(id, _)| id)
+            .collect())
+    }
+
+    fn purge_blocked(&self, id: i64, references: &[(&str, &str)]) -> Result<bool> {
+        for (table, column) in references {
+            let referenced: bool = self
+                .conn
+                .query_row(
+                    &format!("SELECT EXISTS (SELECT 1 FROM {table} WHERE {column} = ?)"),
+                    params![id],
+                    |row| row.get(0),
+                )
+                .with_context(|| format!("check {table}.{column} references"))?;
+            if referenced {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn purge_row(&self, kind: EntityKind, id: i64) -> Result<()> {
+        self.conn
+            .execute(
+                &format!("DELETE FROM {} WHERE id = ?", kind.table()),
+                params![id],
+            )
+            .with_context(|| format!("purge {} {id}", kind.deleted_tag()))?;
+        for table in [
+            "deletion_records",
+            "private_rows",
+            "archived_rows",
+            "cost_splits",
+            "money_currencies",
+            "entity_tags",
+        ] {
+            self.conn
+                .execute(
+                    &format!("DELETE FROM {table} WHERE entity = ? AND target_id = ?"),
+                    params![kind.deleted_tag(), id],
+                )
+                .with_context(|| format!("clear {table} for {} {id}", kind.deleted_tag()))?;
+        }
+        Ok(())
+    }
+
+    /// Age after which deleted rows are purged at startup; 0 means never.
+    pub fn get_purge_deleted_days(&self) -> Result<i64> {
+        match self.get_setting(SettingKey::PurgeDeletedDays)? {
+            Some(SettingValue::Days(days)) => Ok(days),
+            Some(_) => bail!(
+                "setting `{}` must be a number of days; open Settings and pick one",
+                SettingKey::PurgeDeletedDays.as_str()
+            ),
+            None => Ok(0),
+        }
+    }
+
+    /// Seconds between background reloads in the TUI; 0 when off.
+    pub fn get_auto_refresh_seconds(&self) -> Result<u64> {
+        match self.get_setting(SettingKey::UiAutoRefresh)? {
+            Some(SettingValue::Text(raw)) => parse_auto_refresh(&raw).ok_or_else(|| {
+                anyhow!(
+                    "setting `{}` must be a number of seconds, got `{raw}`; open Settings and pick one",
+                    SettingKey::UiAutoRefresh.as_str()
+                )
+            }),
+            Some(_) => bail!(
+                "setting `{}` must be a number of seconds; open Settings and pick one",
+                SettingKey::UiAutoRefresh.as_str()
+            ),
+            None => Ok(0),
+        }
+    }
+
+    /// Runs `apply` with audit entries attributed to `source` instead of
+    /// `manual`, e.g. while the chat or an import writes records.
+    pub fn with_audit_source<T>(
+        &self,
+        source: AuditSource,
+        apply: impl FnOnce(&Self) -> Result<T>,
+    ) -> Result<T> {
+        let previous = self.audit_source.replace(source);
+        let result = apply(self);
+        self.audit_source.set(previous);
+        result
+    }
+
+    pub fn audit_source(&self) -> AuditSource {
+        self.audit_source.get()
+    }
+
+    /// Appends one entry to the audit trail under the current source.
+    /// `action` should be `create`, `delete`, `restore`, or `edit <field>`.
+    pub fn record_audit(&self, target: LifecycleEntityRef, action: &str) -> Result<AuditEntryId> {
+        if action.trim().is_empty() {
+            bail!("audit action is required -- pass create, delete, restore, or edit <field>");
+        }
+        let now = now_rfc3339()?;
+        self.conn
+            .execute(
+                "
+                INSERT INTO audit_entries (entity, target_id, action, source, recorded_at)
+                VALUES (?, ?, ?, ?, ?)
+                ",
+                params![
+                    target.kind().deleted_tag(),
+                    target.id(),
+                    action.trim(),
+                    self.audit_source.get().as_str(),
+                    now
+                ],
+            )
+            .with_context(|| {
+                format!(
+                    "record audit entry for {} {}",
+                    target.kind().deleted_tag(),
+                    target.id()
+                )
+            })?;
+        Ok(AuditEntryId::new(self.conn.last_insert_rowid()))
+    }
+
+    /// Most recent audit entries first, optionally only those from `source`.
+    pub fn list_audit_entries(
+        &self,
+        source: Option<AuditSource>,
+        limit: usize,
+    ) -> Result<Vec<AuditEntry>> {
+        let limit = i64::try_from(limit).context("audit entry limit out of range")?;
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT id, entity, target_id, action, source, recorded_at
+                FROM audit_entries
+                WHERE ?1 IS NULL OR source = ?1
+                ORDER BY recorded_at DESC, id DESC
+                LIMIT ?2
+                ",
+            )
+            .context("prepare audit entries query")?;
+        let rows = stmt
+            .query_map(params![source.map(AuditSource::as_str), limit], |row| {
+                let entity_raw: String = row.get(1)?;
+                let source_raw: String = row.get(4)?;
+                let recorded_at_raw: String = row.get(5)?;
+                Ok(AuditEntry {
+                    id: AuditEntryId::new(row.get(0)?),
+                    entity: DeletionEntity::parse(&entity_raw).ok_or_else(|| {
+                        to_sql_error(anyhow!("unknown audit entity {entity_raw}"))
+                    })?,
+                    target_id: row.get(2)?,
+                    action: row.get(3)?,
+                    source: AuditSource::parse(&source_raw).ok_or_else(|| {
+                        to_sql_error(anyhow!("unknown audit source {source_raw}"))
+                    })?,
+                    recorded_at: parse_datetime(&recorded_at_raw).map_err(to_sql_error)?,
+                })
+            })
+            .context("query audit entries")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect audit entries")
+    }
+
+    pub fn soft_delete_project(&self, project_id: ProjectId) -> Result<()> {
+        self.soft_delete(LifecycleEntityRef::Project(project_id))
+    }
+
+    pub fn restore_project(&self, project_id: ProjectId) -> Result<()> {
+        self.restore(LifecycleEntityRef::Project(project_id))
+    }
+
+    pub fn list_vendors(&self, include_deleted: bool) -> Result<Vec<Vendor>> {
+        let mut sql = String::from(
+            "
+            SELECT
+              id, name, contact_name, email, phone, website, notes,
+              created_at, updated_at, deleted_at,
+              license_number, insurance_expiry, insurance_document_id
             FROM vendors
             ",
         );
@@ -2136,6 +3989,8 @@ impl Store {
                 let created_at_raw: String = row.get(7)?;
                 let updated_at_raw: String = row.get(8)?;
                 let deleted_at_raw: Option<String> = row.get(9)?;
+                let insurance_expiry_raw: Option<String> = row.get(11)?;
+                let insurance_document_id: Option<i64> = row.get(12)?;
 
                 Ok(Vendor {
                     id: VendorId::new(row.get(0)?),
@@ -2145,6 +4000,9 @@ impl Store {
                     phone: row.get(4)?,
                     website: row.get(5)?,
                     notes: row.get(6)?,
+                    license_number: row.get(10)?,
+                    insurance_expiry: parse_opt_date(insurance_expiry_raw).map_err(to_sql_error)?,
+                    insurance_document_id: insurance_document_id.map(DocumentId::new),
                     created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                     updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                     deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
@@ -2157,14 +4015,18 @@ impl Store {
     }
 
     pub fn create_vendor(&self, vendor: &NewVendor) -> Result<VendorId> {
+        if let Some(document_id) = vendor.insurance_document_id {
+            self.require_parent_alive(ParentEntityRef::Document(document_id))?;
+        }
         let now = now_rfc3339()?;
         self.conn
             .execute(
                 "
                 INSERT INTO vendors (
                   name, contact_name, email, phone, website, notes,
+                  license_number, insurance_expiry, insurance_document_id,
                   created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 ",
                 params![
                     vendor.name,
@@ -2173,6 +4035,9 @@ impl Store {
                     vendor.phone,
                     vendor.website,
                     vendor.notes,
+                    vendor.license_number.trim(),
+                    vendor.insurance_expiry.map(format_date),
+                    vendor.insurance_document_id.map(DocumentId::get),
                     now,
                     now,
                 ],
@@ -2182,6 +4047,9 @@ impl Store {
     }
 
     pub fn update_vendor(&self, vendor_id: VendorId, update: &UpdateVendor) -> Result<()> {
+        if let Some(document_id) = update.insurance_document_id {
+            self.require_parent_alive(ParentEntityRef::Document(document_id))?;
+        }
         let now = now_rfc3339()?;
         let rows_affected = self
             .conn
@@ -2195,6 +4063,9 @@ impl Store {
                   phone = ?,
                   website = ?,
                   notes = ?,
+                  license_number = ?,
+                  insurance_expiry = ?,
+                  insurance_document_id = ?,
                   updated_at = ?
                 WHERE id = ? AND deleted_at IS NULL
                 ",
@@ -2205,6 +4076,9 @@ impl Store {
                     update.phone,
                     update.website,
                     update.notes,
+                    update.license_number.trim(),
+                    update.insurance_expiry.map(format_date),
+                    update.insurance_document_id.map(DocumentId::get),
                     now,
                     vendor_id.get(),
                 ],
@@ -2228,27 +4102,58 @@ impl Store {
     }
 
     pub fn list_quotes(&self, include_deleted: bool) -> Result<Vec<Quote>> {
+        self.query_quotes(include_deleted, None, None)
+    }
+
+    pub fn quotes_page(&self, include_deleted: bool, page: ListPage<'_>) -> Result<RowPage<Quote>> {
+        Ok(RowPage {
+            rows: self.query_quotes(include_deleted, None, Some(page))?,
+            total: self.row_count(EntityKind::Quote, include_deleted, page.skip)?,
+        })
+    }
+
+    pub fn quotes_changed_since(
+        &self,
+        since: OffsetDateTime,
+        include_deleted: bool,
+    ) -> Result<RowChanges<Quote>> {
+        Ok(RowChanges {
+            rows: self.query_quotes(include_deleted, Some(since), None)?,
+            ids: self.row_ids(EntityKind::Quote, include_deleted)?,
+        })
+    }
+
+    fn query_quotes(
+        &self,
+        include_deleted: bool,
+        since: Option<OffsetDateTime>,
+        page: Option<ListPage<'_>>,
+    ) -> Result<Vec<Quote>> {
         let mut sql = String::from(
             "
             SELECT
               id, project_id, vendor_id, total_cents, labor_cents,
               materials_cents, other_cents, received_date, notes,
-              created_at, updated_at, deleted_at
+              created_at, updated_at, deleted_at, accepted_at
             FROM quotes
             ",
         );
-        if !include_deleted {
-            sql.push_str("WHERE deleted_at IS NULL\n");
-        }
+        sql.push_str(&list_filter(
+            include_deleted,
+            since,
+            page.map(|page| page.skip),
+        ));
         sql.push_str("ORDER BY updated_at DESC, id DESC");
+        sql.push_str(&page_limit(page));
 
         let mut stmt = self.conn.prepare(&sql).context("prepare quotes query")?;
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params_from_iter(since.map(second_prefix)), |row| {
                 let received_date_raw: Option<String> = row.get(7)?;
                 let created_at_raw: String = row.get(9)?;
                 let updated_at_raw: String = row.get(10)?;
                 let deleted_at_raw: Option<String> = row.get(11)?;
+                let accepted_at_raw: Option<String> = row.get(12)?;
 
                 Ok(Quote {
                     id: QuoteId::new(row.get(0)?),
@@ -2263,6 +4168,7 @@ impl Store {
                     created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                     updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                     deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
+                    accepted_at: parse_opt_datetime(accepted_at_raw).map_err(to_sql_error)?,
                 })
             })
             .context("query quotes")?;
@@ -2271,6 +4177,42 @@ impl Store {
             .context("collect quotes")
     }
 
+    /// Ids of the rows of `kind` a list with the same `include_deleted`
+    /// would return.
+    fn row_ids(&self, kind: EntityKind, include_deleted: bool) -> Result<BTreeSet<i64>> {
+        let mut sql = format!("SELECT id FROM {}", kind.table());
+        if !include_deleted {
+            sql.push_str(" WHERE deleted_at IS NULL");
+        }
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .with_context(|| format!("prepare {} id query", kind.table()))?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, i64>(0))
+            .with_context(|| format!("query {} ids", kind.table()))?
+            .collect::<rusqlite::Result<BTreeSet<_>>>()
+            .with_context(|| format!("scan {} ids", kind.table()))?;
+        Ok(ids)
+    }
+
+    /// Rows of `kind` a list with the same `include_deleted` would return,
+    /// less the `skip` ids.
+    fn row_count(
+        &self,
+        kind: EntityKind,
+        include_deleted: bool,
+        skip: &BTreeSet<i64>,
+    ) -> Result<usize> {
+        let mut sql = format!("SELECT COUNT(*) FROM {} ", kind.table());
+        sql.push_str(&list_filter(include_deleted, None, Some(skip)));
+        let count = self
+            .conn
+            .query_row(&sql, [], |row| row.get::<_, i64>(0))
+            .with_context(|| format!("count {} rows", kind.table()))?;
+        Ok(usize::try_from(count).unwrap_or_default())
+    }
+
     pub fn count_quotes_by_vendor(
         &self,
         vendor_ids: &[VendorId],
@@ -2371,232 +4313,571 @@ impl Store {
         Ok(counts)
     }
 
-    pub fn create_quote(&self, quote: &NewQuote) -> Result<QuoteId> {
-        self.require_parent_alive(ParentEntityRef::Project(quote.project_id))?;
-        self.require_parent_alive(ParentEntityRef::Vendor(quote.vendor_id))?;
-
-        let now = now_rfc3339()?;
-        self.conn
-            .execute(
-                "
-                INSERT INTO quotes (
-                  project_id, vendor_id, total_cents, labor_cents,
-                  materials_cents, other_cents, received_date, notes,
-                  created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-                ",
-                params![
-                    quote.project_id.get(),
-                    quote.vendor_id.get(),
-                    quote.total_cents,
-                    quote.labor_cents,
-                    quote.materials_cents,
-                    quote.other_cents,
-                    quote.received_date.map(format_date),
-                    quote.notes,
-                    now,
-                    now,
-                ],
-            )
-            .context("insert quote")?;
-        Ok(QuoteId::new(self.conn.last_insert_rowid()))
-    }
-
-    pub fn update_quote(&self, quote_id: QuoteId, update: &UpdateQuote) -> Result<()> {
-        self.require_parent_alive(ParentEntityRef::Project(update.project_id))?;
-        self.require_parent_alive(ParentEntityRef::Vendor(update.vendor_id))?;
-
+    /// Marks a live quote accepted, or clears the mark.
+    pub fn set_quote_accepted(&self, quote_id: QuoteId, accepted: bool) -> Result<()> {
         let now = now_rfc3339()?;
-        let rows_affected = self
+        let accepted_at = accepted.then(|| now.clone());
+        let changed = self
             .conn
             .execute(
-                "
-                UPDATE quotes
-                SET
-                  project_id = ?,
-                  vendor_id = ?,
-                  total_cents = ?,
-                  labor_cents = ?,
-                  materials_cents = ?,
-                  other_cents = ?,
-                  received_date = ?,
-                  notes = ?,
-                  updated_at = ?
-                WHERE id = ? AND deleted_at IS NULL
-                ",
-                params![
-                    update.project_id.get(),
-                    update.vendor_id.get(),
-                    update.total_cents,
-                    update.labor_cents,
-                    update.materials_cents,
-                    update.other_cents,
-                    update.received_date.map(format_date),
-                    update.notes,
-                    now,
-                    quote_id.get(),
-                ],
+                "UPDATE quotes SET accepted_at = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+                params![accepted_at, now, quote_id.get()],
             )
-            .context("update quote")?;
-        if rows_affected == 0 {
+            .with_context(|| format!("update acceptance of quote {}", quote_id.get()))?;
+        if changed == 0 {
             bail!(
-                "quote {} not found or deleted -- choose an existing quote and retry",
+                "quote {} not found or deleted -- refresh the view and pick a live quote",
                 quote_id.get()
             );
         }
         Ok(())
     }
 
-    pub fn soft_delete_quote(&self, quote_id: QuoteId) -> Result<()> {
-        self.soft_delete(LifecycleEntityRef::Quote(quote_id))
-    }
+    /// Quote counts, accepted quote totals, linked document counts, and task
+    /// progress for every project that has any. Deleted quotes and documents
+    /// are skipped.
+    pub fn project_rollups(&self) -> Result<BTreeMap<ProjectId, ProjectRollup>> {
+        let mut rollups = BTreeMap::<ProjectId, ProjectRollup>::new();
 
-    pub fn restore_quote(&self, quote_id: QuoteId) -> Result<()> {
-        self.restore(LifecycleEntityRef::Quote(quote_id))
-    }
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT
+                  project_id,
+                  COUNT(*),
+                  SUM(CASE WHEN accepted_at IS NOT NULL THEN total_cents END)
+                FROM quotes
+                WHERE deleted_at IS NULL
+                GROUP BY project_id
+                ",
+            )
+            .context("prepare project quote rollup query")?;
+        let quote_rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                ))
+            })
+            .context("query project quote rollup")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect project quote rollup")?;
+        for (project_id, count, accepted_cents) in quote_rows {
+            let rollup = rollups.entry(ProjectId::new(project_id)).or_default();
+            rollup.quote_count = usize::try_from(count).with_context(|| {
+                format!(
+                    "quote count overflow for project {project_id} -- reduce row volume and retry"
+                )
+            })?;
+            rollup.accepted_cents = accepted_cents;
+        }
 
-    pub fn list_appliances(&self, include_deleted: bool) -> Result<Vec<Appliance>> {
-        let mut sql = String::from(
+        for (project_id, count) in self.document_counts(DocumentEntityKind::Project)? {
+            rollups
+                .entry(ProjectId::new(project_id))
+                .or_default()
+                .doc_count = count;
+        }
+
+        let tasks = self.grouped_counts(
+            "project task",
+            "SELECT project_id, COUNT(*) FROM project_tasks GROUP BY project_id",
+            [],
+        )?;
+        let done = self.grouped_counts(
+            "done task",
             "
-            SELECT
-              id, name, brand, model_number, serial_number,
-              purchase_date, warranty_expiry, location, cost_cents, notes,
-              created_at, updated_at, deleted_at
-            FROM appliances
+            SELECT project_id, COUNT(*) FROM project_tasks
+            WHERE done_at IS NOT NULL
+            GROUP BY project_id
             ",
-        );
-        if !include_deleted {
-            sql.push_str("WHERE deleted_at IS NULL\n");
+            [],
+        )?;
+        for (project_id, count) in tasks {
+            let rollup = rollups.entry(ProjectId::new(project_id)).or_default();
+            rollup.task_count = count;
+            rollup.tasks_done = done.get(&project_id).copied().unwrap_or_default();
         }
-        sql.push_str("ORDER BY updated_at DESC, id DESC");
 
+        Ok(rollups)
+    }
+
+    /// Checklist of a project, in the order the tasks were added.
+    pub fn list_project_tasks(&self, project_id: ProjectId) -> Result<Vec<Task>> {
         let mut stmt = self
             .conn
-            .prepare(&sql)
-            .context("prepare appliances query")?;
+            .prepare(
+                "
+                SELECT id, project_id, title, done_at, created_at
+                FROM project_tasks
+                WHERE project_id = ?
+                ORDER BY id ASC
+                ",
+            )
+            .context("prepare project tasks query")?;
         let rows = stmt
-            .query_map([], |row| {
-                let purchase_date_raw: Option<String> = row.get(5)?;
-                let warranty_expiry_raw: Option<String> = row.get(6)?;
-                let created_at_raw: String = row.get(10)?;
-                let updated_at_raw: String = row.get(11)?;
-                let deleted_at_raw: Option<String> = row.get(12)?;
-
-                Ok(Appliance {
-                    id: ApplianceId::new(row.get(0)?),
-                    name: row.get(1)?,
-                    brand: row.get(2)?,
-                    model_number: row.get(3)?,
-                    serial_number: row.get(4)?,
-                    purchase_date: parse_opt_date(purchase_date_raw).map_err(to_sql_error)?,
-                    warranty_expiry: parse_opt_date(warranty_expiry_raw).map_err(to_sql_error)?,
-                    location: row.get(7)?,
-                    cost_cents: row.get(8)?,
-                    notes: row.get(9)?,
+            .query_map(params![project_id.get()], |row| {
+                let done_at_raw: Option<String> = row.get(3)?;
+                let created_at_raw: String = row.get(4)?;
+                Ok(Task {
+                    id: TaskId::new(row.get(0)?),
+                    project_id: ProjectId::new(row.get(1)?),
+                    title: row.get(2)?,
+                    done_at: parse_opt_datetime(done_at_raw).map_err(to_sql_error)?,
                     created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
-                    updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
-                    deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
                 })
             })
-            .context("query appliances")?;
-
+            .context("query project tasks")?;
         rows.collect::<rusqlite::Result<Vec<_>>>()
-            .context("collect appliances")
+            .context("collect project tasks")
     }
 
-    pub fn create_appliance(&self, appliance: &NewAppliance) -> Result<ApplianceId> {
+    pub fn create_project_task(&self, project_id: ProjectId, title: &str) -> Result<TaskId> {
+        let title = title.trim();
+        if title.is_empty() {
+            bail!("task title is required -- type what needs doing");
+        }
+        self.require_parent_alive(ParentEntityRef::Project(project_id))?;
         let now = now_rfc3339()?;
         self.conn
             .execute(
                 "
-                INSERT INTO appliances (
-                  name, brand, model_number, serial_number, purchase_date,
-                  warranty_expiry, location, cost_cents, notes,
-                  created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                INSERT INTO project_tasks (project_id, title, created_at, updated_at)
+                VALUES (?, ?, ?, ?)
                 ",
-                params![
-                    appliance.name,
-                    appliance.brand,
-                    appliance.model_number,
-                    appliance.serial_number,
-                    appliance.purchase_date.map(format_date),
-                    appliance.warranty_expiry.map(format_date),
-                    appliance.location,
-                    appliance.cost_cents,
-                    appliance.notes,
+                params![project_id.get(), title, now, now],
+            )
+            .context("insert project task")?;
+        Ok(TaskId::new(self.conn.last_insert_rowid()))
+    }
+
+    /// Checks a task off, or back on when `done` is false.
+    pub fn set_project_task_done(&self, task_id: TaskId, done: bool) -> Result<()> {
+        let now = now_rfc3339()?;
+        let rows_affected = self
+            .conn
+            .execute(
+                "UPDATE project_tasks SET done_at = ?, updated_at = ? WHERE id = ?",
+                params![done.then_some(&now), now, task_id.get()],
+            )
+            .context("update project task")?;
+        if rows_affected == 0 {
+            bail!(
+                "task {} not found -- reopen the checklist and retry",
+                task_id.get()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn delete_project_task(&self, task_id: TaskId) -> Result<()> {
+        let rows_affected = self
+            .conn
+            .execute(
+                "DELETE FROM project_tasks WHERE id = ?",
+                params![task_id.get()],
+            )
+            .context("delete project task")?;
+        if rows_affected == 0 {
+            bail!(
+                "task {} not found -- reopen the checklist and retry",
+                task_id.get()
+            );
+        }
+        Ok(())
+    }
+
+    /// Saved templates plus the built-in ones they don't replace, by name.
+    pub fn list_project_templates(&self) -> Result<Vec<ProjectTemplate>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name, project_type, tasks FROM project_templates")
+            .context("prepare project templates query")?;
+        let saved = stmt
+            .query_map([], |row| {
+                let tasks: String = row.get(2)?;
+                Ok(ProjectTemplate {
+                    name: row.get(0)?,
+                    project_type: row.get(1)?,
+                    tasks: tasks.lines().map(str::to_owned).collect(),
+                    builtin: false,
+                })
+            })
+            .context("query project templates")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect project templates")?;
+        let builtin = BUILTIN_PROJECT_TEMPLATES
+            .iter()
+            .filter(|(name, _, _)| {
+                !saved
+                    .iter()
+                    .any(|template| template.name.eq_ignore_ascii_case(name))
+            })
+            .map(|(name, project_type, tasks)| ProjectTemplate {
+                name: (*name).to_owned(),
+                project_type: (*project_type).to_owned(),
+                tasks: tasks.iter().map(|task| (*task).to_owned()).collect(),
+                builtin: true,
+            })
+            .collect::<Vec<_>>();
+        let mut templates = builtin.into_iter().chain(saved).collect::<Vec<_>>();
+        templates.sort_by_key(|template| template.name.to_lowercase());
+        Ok(templates)
+    }
+
+    /// Saves `project_id`'s type and checklist as the template `name`,
+    /// replacing a saved template of the same name. Returns the task count.
+    pub fn save_project_template(&self, name: &str, project_id: ProjectId) -> Result<usize> {
+        let name = name.trim();
+        if name.is_empty() {
+            bail!("template name is required -- type a name before saving");
+        }
+        let project_type: String = self
+            .conn
+            .query_row(
+                "
+                SELECT COALESCE(pt.name, '')
+                FROM projects p
+                LEFT JOIN project_types pt ON pt.id = p.project_type_id
+                WHERE p.id = ?
+                ",
+                params![project_id.get()],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("load project {}", project_id.get()))?
+            .ok_or_else(|| anyhow!("project {} no longer exists", project_id.get()))?;
+        let tasks = self
+            .list_project_tasks(project_id)?
+            .into_iter()
+            .map(|task| task.title.replace('\n', " "))
+            .collect::<Vec<_>>();
+        if tasks.is_empty() {
+            bail!("project has no tasks to save -- add some with `a` first");
+        }
+        let existing: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT name FROM project_templates WHERE name = ? COLLATE NOCASE",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("look up existing template")?;
+        self.conn
+            .execute(
+                "
+                INSERT INTO project_templates (name, project_type, tasks, updated_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (name) DO UPDATE SET
+                  project_type = excluded.project_type,
+                  tasks = excluded.tasks,
+                  updated_at = excluded.updated_at
+                ",
+                params![
+                    existing.as_deref().unwrap_or(name),
+                    project_type,
+                    tasks.join("\n"),
+                    now_rfc3339()?,
+                ],
+            )
+            .with_context(|| format!("save template `{name}`"))?;
+        Ok(tasks.len())
+    }
+
+    /// Removes a saved template; false when only a built-in one has that
+    /// name, or none does.
+    pub fn delete_project_template(&self, name: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM project_templates WHERE name = ? COLLATE NOCASE",
+                params![name],
+            )
+            .with_context(|| format!("delete template `{name}`"))?;
+        Ok(removed > 0)
+    }
+
+    /// Creates a planned project named after the template, with its
+    /// checklist, in one step.
+    pub fn create_project_from_template(&self, name: &str) -> Result<ProjectId> {
+        let template = self
+            .list_project_templates()?
+            .into_iter()
+            .find(|template| template.name.eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| {
+                anyhow!("template `{name}` not found -- pick one from the template list")
+            })?;
+        let types = self.list_project_types()?;
+        let project_type = types
+            .iter()
+            .find(|value| value.name.eq_ignore_ascii_case(&template.project_type))
+            .or_else(|| types.iter().min_by_key(|value| value.id.get()))
+            .ok_or_else(|| anyhow!("no project types exist -- add one with T first"))?;
+
+        let now = now_rfc3339()?;
+        let tx = WriteBatch::begin(&self.conn).context("begin project from template")?;
+        tx.execute(
+            "
+            INSERT INTO projects (
+              title, project_type_id, status, description, created_at, updated_at
+            ) VALUES (?, ?, ?, '', ?, ?)
+            ",
+            params![
+                template.name,
+                project_type.id.get(),
+                ProjectStatus::Planned.as_str(),
+                now,
+                now,
+            ],
+        )
+        .context("insert project")?;
+        let project_id = tx.last_insert_rowid();
+        for task in &template.tasks {
+            tx.execute(
+                "
+                INSERT INTO project_tasks (project_id, title, created_at, updated_at)
+                VALUES (?, ?, ?, ?)
+                ",
+                params![project_id, task, now, now],
+            )
+            .context("insert project task")?;
+        }
+        tx.commit().context("commit project from template")?;
+        Ok(ProjectId::new(project_id))
+    }
+
+    /// Live maintenance item and linked document counts for every appliance
+    /// that has any.
+    pub fn appliance_rollups(&self) -> Result<BTreeMap<ApplianceId, ApplianceRollup>> {
+        let mut rollups = BTreeMap::<ApplianceId, ApplianceRollup>::new();
+        let maintenance = self.grouped_counts(
+            "maintenance item",
+            "
+            SELECT appliance_id, COUNT(*)
+            FROM maintenance_items
+            WHERE deleted_at IS NULL AND appliance_id IS NOT NULL
+            GROUP BY appliance_id
+            ",
+            [],
+        )?;
+        for (appliance_id, count) in maintenance {
+            rollups
+                .entry(ApplianceId::new(appliance_id))
+                .or_default()
+                .maintenance_count = count;
+        }
+        for (appliance_id, count) in self.document_counts(DocumentEntityKind::Appliance)? {
+            rollups
+                .entry(ApplianceId::new(appliance_id))
+                .or_default()
+                .doc_count = count;
+        }
+        Ok(rollups)
+    }
+
+    /// Live quote and service log counts for every vendor that has any.
+    pub fn vendor_rollups(&self) -> Result<BTreeMap<VendorId, VendorRollup>> {
+        let mut rollups = BTreeMap::<VendorId, VendorRollup>::new();
+        let quotes = self.grouped_counts(
+            "quote",
+            "
+            SELECT vendor_id, COUNT(*)
+            FROM quotes
+            WHERE deleted_at IS NULL
+            GROUP BY vendor_id
+            ",
+            [],
+        )?;
+        for (vendor_id, count) in quotes {
+            rollups
+                .entry(VendorId::new(vendor_id))
+                .or_default()
+                .quote_count = count;
+        }
+        let jobs = self.grouped_counts(
+            "service log",
+            "
+            SELECT vendor_id, COUNT(*)
+            FROM service_log_entries
+            WHERE deleted_at IS NULL AND vendor_id IS NOT NULL
+            GROUP BY vendor_id
+            ",
+            [],
+        )?;
+        for (vendor_id, count) in jobs {
+            rollups
+                .entry(VendorId::new(vendor_id))
+                .or_default()
+                .job_count = count;
+        }
+        Ok(rollups)
+    }
+
+    /// Live service log entries per maintenance item.
+    pub fn service_log_counts_by_maintenance(&self) -> Result<BTreeMap<MaintenanceItemId, usize>> {
+        Ok(self
+            .grouped_counts(
+                "service log",
+                "
+                SELECT maintenance_item_id, COUNT(*)
+                FROM service_log_entries
+                WHERE deleted_at IS NULL
+                GROUP BY maintenance_item_id
+                ",
+                [],
+            )?
+            .into_iter()
+            .map(|(id, count)| (MaintenanceItemId::new(id), count))
+            .collect())
+    }
+
+    /// Live documents linked to each incident.
+    pub fn document_counts_by_incident(&self) -> Result<BTreeMap<IncidentId, usize>> {
+        Ok(self
+            .document_counts(DocumentEntityKind::Incident)?
+            .into_iter()
+            .map(|(id, count)| (IncidentId::new(id), count))
+            .collect())
+    }
+
+    fn document_counts(&self, kind: DocumentEntityKind) -> Result<BTreeMap<i64, usize>> {
+        self.grouped_counts(
+            "document",
+            "
+            SELECT entity_id, COUNT(*)
+            FROM documents
+            WHERE deleted_at IS NULL
+              AND entity_kind = ?
+            GROUP BY entity_id
+            ",
+            params![kind.as_str()],
+        )
+    }
+
+    /// Runs an `id, COUNT(*)` grouping query; `what` names the counted rows
+    /// in errors.
+    fn grouped_counts<P: rusqlite::Params>(
+        &self,
+        what: &str,
+        sql: &str,
+        params: P,
+    ) -> Result<BTreeMap<i64, usize>> {
+        let mut stmt = self
+            .conn
+            .prepare(sql)
+            .with_context(|| format!("prepare {what} count query"))?;
+        let rows = stmt
+            .query_map(params, |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?))
+            })
+            .with_context(|| format!("query {what} counts"))?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| format!("collect {what} counts"))?;
+        rows.into_iter()
+            .map(|(id, count)| {
+                let count = usize::try_from(count).with_context(|| {
+                    format!("{what} count overflow for row {id} -- reduce row volume and retry")
+                })?;
+                Ok((id, count))
+            })
+            .collect()
+    }
+
+    pub fn create_quote(&self, quote: &NewQuote) -> Result<QuoteId> {
+        self.require_parent_alive(ParentEntityRef::Project(quote.project_id))?;
+        self.require_parent_alive(ParentEntityRef::Vendor(quote.vendor_id))?;
+
+        let now = now_rfc3339()?;
+        self.conn
+            .execute(
+                "
+                INSERT INTO quotes (
+                  project_id, vendor_id, total_cents, labor_cents,
+                  materials_cents, other_cents, received_date, notes,
+                  created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ",
+                params![
+                    quote.project_id.get(),
+                    quote.vendor_id.get(),
+                    quote.total_cents,
+                    quote.labor_cents,
+                    quote.materials_cents,
+                    quote.other_cents,
+                    quote.received_date.map(format_date),
+                    quote.notes,
                     now,
                     now,
                 ],
             )
-            .context("insert appliance")?;
-        Ok(ApplianceId::new(self.conn.last_insert_rowid()))
+            .context("insert quote")?;
+        Ok(QuoteId::new(self.conn.last_insert_rowid()))
     }
 
-    pub fn update_appliance(
-        &self,
-        appliance_id: ApplianceId,
-        update: &UpdateAppliance,
-    ) -> Result<()> {
+    pub fn update_quote(&self, quote_id: QuoteId, update: &UpdateQuote) -> Result<()> {
+        self.require_parent_alive(ParentEntityRef::Project(update.project_id))?;
+        self.require_parent_alive(ParentEntityRef::Vendor(update.vendor_id))?;
+
         let now = now_rfc3339()?;
         let rows_affected = self
             .conn
             .execute(
                 "
-                UPDATE appliances
+                UPDATE quotes
                 SET
-                  name = ?,
-                  brand = ?,
-                  model_number = ?,
-                  serial_number = ?,
-                  purchase_date = ?,
-                  warranty_expiry = ?,
-                  location = ?,
-                  cost_cents = ?,
+                  project_id = ?,
+                  vendor_id = ?,
+                  total_cents = ?,
+                  labor_cents = ?,
+                  materials_cents = ?,
+                  other_cents = ?,
+                  received_date = ?,
                   notes = ?,
                   updated_at = ?
                 WHERE id = ? AND deleted_at IS NULL
                 ",
                 params![
-                    update.name,
-                    update.brand,
-                    update.model_number,
-                    update.serial_number,
-                    update.purchase_date.map(format_date),
-                    update.warranty_expiry.map(format_date),
-                    update.location,
-                    update.cost_cents,
+                    update.project_id.get(),
+                    update.vendor_id.get(),
+                    update.total_cents,
+                    update.labor_cents,
+                    update.materials_cents,
+                    update.other_cents,
+                    update.received_date.map(format_date),
                     update.notes,
                     now,
-                    appliance_id.get(),
+                    quote_id.get(),
                 ],
             )
-            .context("update appliance")?;
+            .context("update quote")?;
         if rows_affected == 0 {
             bail!(
-                "appliance {} not found or deleted -- choose an existing appliance and retry",
-                appliance_id.get()
+                "quote {} not found or deleted -- choose an existing quote and retry",
+                quote_id.get()
             );
         }
         Ok(())
     }
 
-    pub fn soft_delete_appliance(&self, appliance_id: ApplianceId) -> Result<()> {
-        self.soft_delete(LifecycleEntityRef::Appliance(appliance_id))
+    pub fn soft_delete_quote(&self, quote_id: QuoteId) -> Result<()> {
+        self.soft_delete(LifecycleEntityRef::Quote(quote_id))
     }
 
-    pub fn restore_appliance(&self, appliance_id: ApplianceId) -> Result<()> {
-        self.restore(LifecycleEntityRef::Appliance(appliance_id))
+    pub fn restore_quote(&self, quote_id: QuoteId) -> Result<()> {
+        self.restore(LifecycleEntityRef::Quote(quote_id))
     }
 
-    pub fn list_maintenance_items(&self, include_deleted: bool) -> Result<Vec<MaintenanceItem>> {
+    pub fn list_appliances(&self, include_deleted: bool) -> Result<Vec<Appliance>> {
         let mut sql = String::from(
             "
             SELECT
-              id, name, category_id, appliance_id, last_serviced_at,
-              interval_months, manual_url, manual_text, notes, cost_cents,
-              created_at, updated_at, deleted_at
-            FROM maintenance_items
+              id, name, brand, model_number, serial_number,
+              purchase_date, warranty_expiry, location, cost_cents, notes,
+              created_at, updated_at, deleted_at, recall_checked_at
+            FROM appliances
             ",
         );
         if !include_deleted {
@@ -2607,27 +4888,164 @@ impl Store {
         let mut stmt = self
             .conn
             .prepare(&sql)
-            .context("prepare maintenance items query")?;
+            .context("prepare appliances query")?;
         let rows = stmt
             .query_map([], |row| {
-                let appliance_id: Option<i64> = row.get(3)?;
-                let last_serviced_at_raw: Option<String> = row.get(4)?;
+                let purchase_date_raw: Option<String> = row.get(5)?;
+                let warranty_expiry_raw: Option<String> = row.get(6)?;
                 let created_at_raw: String = row.get(10)?;
                 let updated_at_raw: String = row.get(11)?;
                 let deleted_at_raw: Option<String> = row.get(12)?;
+                let recall_checked_at_raw: Option<String> = row.get(13)?;
 
-                Ok(MaintenanceItem {
-                    id: MaintenanceItemId::new(row.get(0)?),
+                Ok(Appliance {
+                    id: ApplianceId::new(row.get(0)?),
                     name: row.get(1)?,
-                    category_id: MaintenanceCategoryId::new(row.get(2)?),
-                    appliance_id: appliance_id.map(ApplianceId::new),
-                    last_serviced_at: parse_opt_date(last_serviced_at_raw).map_err(to_sql_error)?,
-                    interval_months: row.get(5)?,
-                    manual_url: row.get(6)?,
-                    manual_text: row.get(7)?,
-                    notes: row.get(8)?,
-                    cost_cents: row.get(9)?,
-                    created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
+                    brand: row.get(2)?,
+                    model_number: row.get(3)?,
+                    serial_number: row.get(4)?,
+                    purchase_date: parse_opt_date(purchase_date_raw).map_err(to_sql_error)?,
+                    warranty_expiry: parse_opt_date(warranty_expiry_raw).map_err(to_sql_error)?,
+                    location: row.get(7)?,
+                    cost_cents: row.get(8)?,
+                    notes: row.get(9)?,
+                    created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
+                    updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
+                    deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
+                    recall_checked_at: parse_opt_datetime(recall_checked_at_raw)
+                        .map_err(to_sql_error)?,
+                })
+            })
+            .context("query appliances")?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect appliances")
+    }
+
+    pub fn create_appliance(&self, appliance: &NewAppliance) -> Result<ApplianceId> {
+        let now = now_rfc3339()?;
+        self.conn
+            .execute(
+                "
+                INSERT INTO appliances (
+                  name, brand, model_number, serial_number, purchase_date,
+                  warranty_expiry, location, cost_cents, notes,
+                  created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ",
+                params![
+                    appliance.name,
+                    appliance.brand,
+                    appliance.model_number,
+                    appliance.serial_number,
+                    appliance.purchase_date.map(format_date),
+                    appliance.warranty_expiry.map(format_date),
+                    appliance.location,
+                    appliance.cost_cents,
+                    appliance.notes,
+                    now,
+                    now,
+                ],
+            )
+            .context("insert appliance")?;
+        Ok(ApplianceId::new(self.conn.last_insert_rowid()))
+    }
+
+    pub fn update_appliance(
+        &self,
+        appliance_id: ApplianceId,
+        update: &UpdateAppliance,
+    ) -> Result<()> {
+        let now = now_rfc3339()?;
+        let rows_affected = self
+            .conn
+            .execute(
+                "
+                UPDATE appliances
+                SET
+                  name = ?,
+                  brand = ?,
+                  model_number = ?,
+                  serial_number = ?,
+                  purchase_date = ?,
+                  warranty_expiry = ?,
+                  location = ?,
+                  cost_cents = ?,
+                  notes = ?,
+                  updated_at = ?
+                WHERE id = ? AND deleted_at IS NULL
+                ",
+                params![
+                    update.name,
+                    update.brand,
+                    update.model_number,
+                    update.serial_number,
+                    update.purchase_date.map(format_date),
+                    update.warranty_expiry.map(format_date),
+                    update.location,
+                    update.cost_cents,
+                    update.notes,
+                    now,
+                    appliance_id.get(),
+                ],
+            )
+            .context("update appliance")?;
+        if rows_affected == 0 {
+            bail!(
+                "appliance {} not found or deleted -- choose an existing appliance and retry",
+                appliance_id.get()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn soft_delete_appliance(&self, appliance_id: ApplianceId) -> Result<()> {
+        self.soft_delete(LifecycleEntityRef::Appliance(appliance_id))
+    }
+
+    pub fn restore_appliance(&self, appliance_id: ApplianceId) -> Result<()> {
+        self.restore(LifecycleEntityRef::Appliance(appliance_id))
+    }
+
+    pub fn list_maintenance_items(&self, include_deleted: bool) -> Result<Vec<MaintenanceItem>> {
+        let mut sql = String::from(
+            "
+            SELECT
+              id, name, category_id, appliance_id, last_serviced_at,
+              interval_months, manual_url, manual_text, notes, cost_cents,
+              created_at, updated_at, deleted_at
+            FROM maintenance_items
+            ",
+        );
+        if !include_deleted {
+            sql.push_str("WHERE deleted_at IS NULL\n");
+        }
+        sql.push_str("ORDER BY updated_at DESC, id DESC");
+
+        let mut stmt = self
+            .conn
+            .prepare(&sql)
+            .context("prepare maintenance items query")?;
+        let rows = stmt
+            .query_map([], |row| {
+                let appliance_id: Option<i64> = row.get(3)?;
+                let last_serviced_at_raw: Option<String> = row.get(4)?;
+                let created_at_raw: String = row.get(10)?;
+                let updated_at_raw: String = row.get(11)?;
+                let deleted_at_raw: Option<String> = row.get(12)?;
+
+                Ok(MaintenanceItem {
+                    id: MaintenanceItemId::new(row.get(0)?),
+                    name: row.get(1)?,
+                    category_id: MaintenanceCategoryId::new(row.get(2)?),
+                    appliance_id: appliance_id.map(ApplianceId::new),
+                    last_serviced_at: parse_opt_date(last_serviced_at_raw).map_err(to_sql_error)?,
+                    interval_months: row.get(5)?,
+                    manual_url: row.get(6)?,
+                    manual_text: row.get(7)?,
+                    notes: row.get(8)?,
+                    cost_cents: row.get(9)?,
+                    created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                     updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                     deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
                 })
@@ -2773,6 +5191,34 @@ impl Store {
         Ok(())
     }
 
+    pub fn set_maintenance_interval(
+        &self,
+        maintenance_id: MaintenanceItemId,
+        interval_months: i32,
+    ) -> Result<()> {
+        if interval_months <= 0 {
+            bail!("interval must be at least 1 month, got {interval_months}");
+        }
+        let rows_affected = self
+            .conn
+            .execute(
+                "
+                UPDATE maintenance_items
+                SET interval_months = ?, updated_at = ?
+                WHERE id = ? AND deleted_at IS NULL
+                ",
+                params![interval_months, now_rfc3339()?, maintenance_id.get()],
+            )
+            .context("update maintenance interval")?;
+        if rows_affected == 0 {
+            bail!(
+                "maintenance item {} not found or deleted -- choose an existing item and retry",
+                maintenance_id.get()
+            );
+        }
+        Ok(())
+    }
+
     pub fn soft_delete_maintenance_item(&self, maintenance_id: MaintenanceItemId) -> Result<()> {
         self.soft_delete(LifecycleEntityRef::MaintenanceItem(maintenance_id))
     }
@@ -2782,6 +5228,37 @@ impl Store {
     }
 
     pub fn list_service_log_entries(&self, include_deleted: bool) -> Result<Vec<ServiceLogEntry>> {
+        self.query_service_log_entries(include_deleted, None, None)
+    }
+
+    pub fn service_log_page(
+        &self,
+        include_deleted: bool,
+        page: ListPage<'_>,
+    ) -> Result<RowPage<ServiceLogEntry>> {
+        Ok(RowPage {
+            rows: self.query_service_log_entries(include_deleted, None, Some(page))?,
+            total: self.row_count(EntityKind::ServiceLogEntry, include_deleted, page.skip)?,
+        })
+    }
+
+    pub fn service_log_entries_changed_since(
+        &self,
+        since: OffsetDateTime,
+        include_deleted: bool,
+    ) -> Result<RowChanges<ServiceLogEntry>> {
+        Ok(RowChanges {
+            rows: self.query_service_log_entries(include_deleted, Some(since), None)?,
+            ids: self.row_ids(EntityKind::ServiceLogEntry, include_deleted)?,
+        })
+    }
+
+    fn query_service_log_entries(
+        &self,
+        include_deleted: bool,
+        since: Option<OffsetDateTime>,
+        page: Option<ListPage<'_>>,
+    ) -> Result<Vec<ServiceLogEntry>> {
         let mut sql = String::from(
             "
             SELECT
@@ -2790,17 +5267,20 @@ impl Store {
             FROM service_log_entries
             ",
         );
-        if !include_deleted {
-            sql.push_str("WHERE deleted_at IS NULL\n");
-        }
+        sql.push_str(&list_filter(
+            include_deleted,
+            since,
+            page.map(|page| page.skip),
+        ));
         sql.push_str("ORDER BY serviced_at DESC, id DESC");
+        sql.push_str(&page_limit(page));
 
         let mut stmt = self
             .conn
             .prepare(&sql)
             .context("prepare service log query")?;
         let rows = stmt
-            .query_map([], |row| {
+            .query_map(params_from_iter(since.map(second_prefix)), |row| {
                 let serviced_at_raw: String = row.get(2)?;
                 let vendor_id: Option<i64> = row.get(3)?;
                 let created_at_raw: String = row.get(6)?;
@@ -3032,7 +5512,7 @@ impl Store {
                 entry_id.get()
             );
         }
-        Ok(())
+        self.clear_stale_cost_splits(LifecycleEntityRef::ServiceLogEntry(entry_id))
     }
 
     pub fn soft_delete_service_log_entry(&self, entry_id: ServiceLogEntryId) -> Result<()> {
@@ -3049,7 +5529,7 @@ impl Store {
             SELECT
               id, title, description, status, severity, date_noticed,
               date_resolved, location, cost_cents, appliance_id, vendor_id,
-              notes, created_at, updated_at, deleted_at
+              notes, created_at, updated_at, deleted_at, follow_up_date
             FROM incidents
             ",
         );
@@ -3101,6 +5581,7 @@ impl Store {
                     severity,
                     date_noticed: parse_date(&date_noticed_raw).map_err(to_sql_error)?,
                     date_resolved: parse_opt_date(date_resolved_raw).map_err(to_sql_error)?,
+                    follow_up_date: parse_opt_date(row.get(15)?).map_err(to_sql_error)?,
                     location: row.get(7)?,
                     cost_cents: row.get(8)?,
                     appliance_id: appliance_id.map(ApplianceId::new),
@@ -3132,8 +5613,8 @@ impl Store {
                 INSERT INTO incidents (
                   title, description, status, severity, date_noticed,
                   date_resolved, location, cost_cents, appliance_id, vendor_id,
-                  notes, created_at, updated_at
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                  notes, created_at, updated_at, follow_up_date
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 ",
                 params![
                     incident.title,
@@ -3149,12 +5630,85 @@ impl Store {
                     incident.notes,
                     now,
                     now,
+                    incident.follow_up_date.map(format_date),
                 ],
             )
             .context("insert incident")?;
         Ok(IncidentId::new(self.conn.last_insert_rowid()))
     }
 
+    /// Files an open incident for each recall not already filed against the
+    /// appliance and stamps its last recall check. Returns the new incidents.
+    pub fn record_recall_check(
+        &self,
+        appliance_id: ApplianceId,
+        recalls: &[recalls::Recall],
+    ) -> Result<Vec<IncidentId>> {
+        self.require_parent_alive(ParentEntityRef::Appliance(appliance_id))?;
+        let location: String = self
+            .conn
+            .query_row(
+                "SELECT location FROM appliances WHERE id = ?",
+                params![appliance_id.get()],
+                |row| row.get(0),
+            )
+            .context("load appliance location")?;
+        let now = now_rfc3339()?;
+        let today = OffsetDateTime::now_utc().date();
+        let tx = WriteBatch::begin(&self.conn).context("begin recall check")?;
+        let mut created = Vec::new();
+        for recall in recalls {
+            let already_filed = tx
+                .query_row(
+                    "SELECT 1 FROM appliance_recalls WHERE appliance_id = ? AND recall_id = ?",
+                    params![appliance_id.get(), recall.id],
+                    |_| Ok(()),
+                )
+                .optional()
+                .context("look up filed recall")?
+                .is_some();
+            if already_filed {
+                continue;
+            }
+            let incident_id = self.create_incident(&NewIncident {
+                title: format!("Recall: {}", recall.title),
+                description: [recall.hazard.as_str(), recall.remedy.as_str()]
+                    .into_iter()
+                    .filter(|part| !part.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                status: IncidentStatus::Open,
+                severity: IncidentSeverity::Soon,
+                date_noticed: today,
+                date_resolved: None,
+                location: location.clone(),
+                cost_cents: None,
+                appliance_id: Some(appliance_id),
+                vendor_id: None,
+                notes: format!("recall {} {}", recall.id, recall.url)
+                    .trim_end()
+                    .to_owned(),
+                follow_up_date: None,
+            })?;
+            tx.execute(
+                "
+                INSERT INTO appliance_recalls (appliance_id, recall_id, incident_id, matched_at)
+                VALUES (?, ?, ?, ?)
+                ",
+                params![appliance_id.get(), recall.id, incident_id.get(), now],
+            )
+            .context("record filed recall")?;
+            created.push(incident_id);
+        }
+        tx.execute(
+            "UPDATE appliances SET recall_checked_at = ? WHERE id = ?",
+            params![now, appliance_id.get()],
+        )
+        .context("stamp recall check")?;
+        tx.commit().context("commit recall check")?;
+        Ok(created)
+    }
+
     pub fn update_incident(&self, incident_id: IncidentId, update: &UpdateIncident) -> Result<()> {
         if let Some(appliance_id) = update.appliance_id {
             self.require_parent_alive(ParentEntityRef::Appliance(appliance_id))?;
@@ -3181,6 +5735,7 @@ impl Store {
                   appliance_id = ?,
                   vendor_id = ?,
                   notes = ?,
+                  follow_up_date = ?,
                   updated_at = ?
                 WHERE id = ? AND deleted_at IS NULL
                 ",
@@ -3196,6 +5751,7 @@ impl Store {
                     update.appliance_id.map(ApplianceId::get),
                     update.vendor_id.map(VendorId::get),
                     update.notes,
+                    update.follow_up_date.map(format_date),
                     now,
                     incident_id.get(),
                 ],
@@ -3207,7 +5763,7 @@ impl Store {
                 incident_id.get()
             );
         }
-        Ok(())
+        self.clear_stale_cost_splits(LifecycleEntityRef::Incident(incident_id))
     }
 
     pub fn soft_delete_incident(&self, incident_id: IncidentId) -> Result<()> {
@@ -3218,46 +5774,262 @@ impl Store {
         self.restore(LifecycleEntityRef::Incident(incident_id))
     }
 
-    pub fn dashboard_counts(&self) -> Result<DashboardCounts> {
-        let projects_due: i64 = self
+    /// Settles a due follow-up. "Still fine" just clears the follow-up date;
+    /// reopening also restores a deleted incident and marks it open again.
+    pub fn settle_incident_follow_up(&self, incident_id: IncidentId, reopen: bool) -> Result<()> {
+        let deleted: Option<Option<String>> = self
             .conn
             .query_row(
-                "
-                SELECT COUNT(*)
-                FROM projects
-                WHERE deleted_at IS NULL
-                  AND status NOT IN ('completed', 'abandoned')
-                ",
-                [],
+                "SELECT deleted_at FROM incidents WHERE id = ?",
+                params![incident_id.get()],
                 |row| row.get(0),
             )
-            .context("count projects due")?;
+            .optional()
+            .context("load incident for follow-up")?;
+        let Some(deleted_at) = deleted else {
+            bail!(
+                "incident {} not found -- choose an existing incident and retry",
+                incident_id.get()
+            );
+        };
+        if reopen && deleted_at.is_some() {
+            self.restore_incident(incident_id)?;
+        }
 
-        let maintenance_due: i64 = self
-            .conn
-            .query_row(
-                "
-                SELECT COUNT(*)
-                FROM maintenance_items
-                WHERE deleted_at IS NULL
-                  AND (
-                    last_serviced_at IS NULL
-                    OR date(last_serviced_at, '+' || interval_months || ' months') <= date('now')
-                  )
-                ",
-                [],
-                |row| row.get(0),
-            )
-            .context("count maintenance due")?;
+        let now = now_rfc3339()?;
+        if reopen {
+            self.conn
+                .execute(
+                    "
+                    UPDATE incidents
+                    SET status = ?, date_resolved = NULL, follow_up_date = NULL, updated_at = ?
+                    WHERE id = ?
+                    ",
+                    params![IncidentStatus::Open.as_str(), now, incident_id.get()],
+                )
+                .context("reopen incident")?;
+        } else {
+            self.conn
+                .execute(
+                    "UPDATE incidents SET follow_up_date = NULL, updated_at = ? WHERE id = ?",
+                    params![now, incident_id.get()],
+                )
+                .context("clear incident follow-up")?;
+        }
+        Ok(())
+    }
 
-        let incidents_open: i64 = self
-            .conn
-            .query_row(
-                "
-                SELECT COUNT(*)
+    pub fn list_budgets(&self, include_deleted: bool) -> Result<Vec<Budget>> {
+        let mut sql = String::from(
+            "
+            SELECT
+              b.id, b.scope, b.category_id, b.monthly_cents, b.notes,
+              b.created_at, b.updated_at, b.deleted_at,
+              COALESCE(mc.name, pt.name, '')
+            FROM budgets b
+            LEFT JOIN maintenance_categories mc
+              ON b.scope = 'maintenance' AND mc.id = b.category_id
+            LEFT JOIN project_types pt
+              ON b.scope = 'project' AND pt.id = b.category_id
+            ",
+        );
+        if !include_deleted {
+            sql.push_str("WHERE b.deleted_at IS NULL\n");
+        }
+        sql.push_str("ORDER BY b.scope ASC, 9 ASC, b.id DESC");
+
+        let mut stmt = self.conn.prepare(&sql).context("prepare budgets query")?;
+        let rows = stmt
+            .query_map([], |row| {
+                let scope: String = row.get(1)?;
+                let category =
+                    BudgetCategory::from_parts(&scope, row.get(2)?).ok_or_else(|| {
+                        rusqlite::Error::FromSqlConversionFailure(
+                            1,
+                            rusqlite::types::Type::Text,
+                            Box::new(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                format!("unknown budget scope {scope}"),
+                            )),
+                        )
+                    })?;
+                let created_at_raw: String = row.get(5)?;
+                let updated_at_raw: String = row.get(6)?;
+                let deleted_at_raw: Option<String> = row.get(7)?;
+
+                Ok(Budget {
+                    id: BudgetId::new(row.get(0)?),
+                    category,
+                    category_name: row.get(8)?,
+                    monthly_cents: row.get(3)?,
+                    notes: row.get(4)?,
+                    created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
+                    updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
+                    deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
+                })
+            })
+            .context("query budgets")?;
+
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect budgets")
+    }
+
+    pub fn create_budget(&self, budget: &NewBudget) -> Result<BudgetId> {
+        self.check_budget_category(budget.category, None)?;
+        let now = now_rfc3339()?;
+        self.conn
+            .execute(
+                "
+                INSERT INTO budgets (
+                  scope, category_id, monthly_cents, notes, created_at, updated_at
+                ) VALUES (?, ?, ?, ?, ?, ?)
+                ",
+                params![
+                    budget.category.scope(),
+                    budget.category.id(),
+                    budget.monthly_cents,
+                    budget.notes,
+                    now,
+                    now,
+                ],
+            )
+            .context("insert budget")?;
+        Ok(BudgetId::new(self.conn.last_insert_rowid()))
+    }
+
+    pub fn update_budget(&self, budget_id: BudgetId, update: &UpdateBudget) -> Result<()> {
+        self.check_budget_category(update.category, Some(budget_id))?;
+        let now = now_rfc3339()?;
+        let rows_affected = self
+            .conn
+            .execute(
+                "
+                UPDATE budgets
+                SET
+                  scope = ?,
+                  category_id = ?,
+                  monthly_cents = ?,
+                  notes = ?,
+                  updated_at = ?
+                WHERE id = ? AND deleted_at IS NULL
+                ",
+                params![
+                    update.category.scope(),
+                    update.category.id(),
+                    update.monthly_cents,
+                    update.notes,
+                    now,
+                    budget_id.get(),
+                ],
+            )
+            .context("update budget")?;
+        if rows_affected == 0 {
+            bail!(
+                "budget {} not found or deleted -- choose an existing budget and retry",
+                budget_id.get()
+            );
+        }
+        Ok(())
+    }
+
+    pub fn soft_delete_budget(&self, budget_id: BudgetId) -> Result<()> {
+        self.soft_delete(LifecycleEntityRef::Budget(budget_id))
+    }
+
+    pub fn restore_budget(&self, budget_id: BudgetId) -> Result<()> {
+        self.restore(LifecycleEntityRef::Budget(budget_id))
+    }
+
+    /// The category must exist, and only one live budget may cover it.
+    fn check_budget_category(
+        &self,
+        category: BudgetCategory,
+        except: Option<BudgetId>,
+    ) -> Result<()> {
+        let (table, label) = match category {
+            BudgetCategory::Maintenance(_) => ("maintenance_categories", "maintenance category"),
+            BudgetCategory::ProjectType(_) => ("project_types", "project type"),
+        };
+        let exists = self
+            .conn
+            .query_row(
+                &format!("SELECT 1 FROM {table} WHERE id = ?"),
+                params![category.id()],
+                |_| Ok(()),
+            )
+            .optional()
+            .with_context(|| format!("look up {label} {}", category.id()))?
+            .is_some();
+        if !exists {
+            bail!(
+                "{label} {} not found -- pick an existing {label}",
+                category.id()
+            );
+        }
+
+        let existing: Option<i64> = self
+            .conn
+            .query_row(
+                "
+                SELECT id FROM budgets
+                WHERE scope = ? AND category_id = ? AND deleted_at IS NULL AND id IS NOT ?
+                ORDER BY id DESC
+                LIMIT 1
+                ",
+                params![category.scope(), category.id(), except.map(BudgetId::get)],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("look up existing budget")?;
+        if let Some(existing) = existing {
+            bail!("budget {existing} already covers this {label} -- edit that one instead");
+        }
+        Ok(())
+    }
+
+    pub fn dashboard_counts(&self) -> Result<DashboardCounts> {
+        let projects_due: i64 = self
+            .conn
+            .query_row(
+                "
+                SELECT COUNT(*)
+                FROM projects
+                WHERE deleted_at IS NULL
+                  AND status NOT IN ('completed', 'abandoned')
+                  AND id NOT IN (SELECT target_id FROM archived_rows WHERE entity = 'project')
+                ",
+                [],
+                |row| row.get(0),
+            )
+            .context("count projects due")?;
+
+        let maintenance_due: i64 = self
+            .conn
+            .query_row(
+                "
+                SELECT COUNT(*)
+                FROM maintenance_items
+                WHERE deleted_at IS NULL
+                  AND id NOT IN (SELECT target_id FROM archived_rows WHERE entity = 'maintenance')
+                  AND (
+                    last_serviced_at IS NULL
+                    OR date(last_serviced_at, '+' || interval_months || ' months') <= date('now')
+                  )
+                ",
+                [],
+                |row| row.get(0),
+            )
+            .context("count maintenance due")?;
+
+        let incidents_open: i64 = self
+            .conn
+            .query_row(
+                "
+                SELECT COUNT(*)
                 FROM incidents
                 WHERE deleted_at IS NULL
                   AND status IN ('open', 'in_progress')
+                  AND id NOT IN (SELECT target_id FROM archived_rows WHERE entity = 'incident')
                 ",
                 [],
                 |row| row.get(0),
@@ -3271,6 +6043,32 @@ impl Store {
         })
     }
 
+    /// Total service-log cost for `start <= serviced_at < end`, ignoring
+    /// deleted entries.
+    pub fn service_cost_cents_between(&self, start: Date, end: Date) -> Result<i64> {
+        self.conn
+            .query_row(
+                &format!(
+                    "
+                    SELECT COALESCE(SUM({cost}), 0)
+                    FROM service_log_entries sle
+                    WHERE deleted_at IS NULL
+                      AND serviced_at >= ?
+                      AND serviced_at < ?
+                    ",
+                    cost = home_cents_sql(
+                        EntityKind::ServiceLogEntry,
+                        "sle",
+                        "cost_cents",
+                        "sle.cost_cents"
+                    ),
+                ),
+                params![format_date(start), format_date(end)],
+                |row| row.get(0),
+            )
+            .context("sum service costs")
+    }
+
     pub fn list_maintenance_with_schedule(&self) -> Result<Vec<MaintenanceItem>> {
         let mut items = self.list_maintenance_items(false)?;
         items.retain(|item| item.interval_months > 0);
@@ -3305,6 +6103,23 @@ impl Store {
         Ok(incidents)
     }
 
+    /// Resolved or deleted incidents whose follow-up date has arrived,
+    /// oldest follow-up first.
+    pub fn list_due_incident_follow_ups(&self, today: Date) -> Result<Vec<Incident>> {
+        let mut incidents = self.list_incidents(true)?;
+        incidents.retain(|incident| {
+            let resolved =
+                incident.deleted_at.is_some() || incident.status == IncidentStatus::Resolved;
+            resolved && incident.follow_up_date.is_some_and(|due| due <= today)
+        });
+        incidents.sort_by(|left, right| {
+            left.follow_up_date
+                .cmp(&right.follow_up_date)
+                .then_with(|| right.id.cmp(&left.id))
+        });
+        Ok(incidents)
+    }
+
     pub fn list_expiring_warranties(
         &self,
         now: Date,
@@ -3335,6 +6150,253 @@ impl Store {
         Ok(appliances)
     }
 
+    /// Live vendors whose insurance certificate has lapsed or lapses within
+    /// `horizon_days`, soonest first.
+    pub fn list_vendor_insurance_lapses(
+        &self,
+        now: Date,
+        horizon_days: i64,
+    ) -> Result<Vec<Vendor>> {
+        if horizon_days < 0 {
+            bail!("horizon_days must be non-negative, got {horizon_days}");
+        }
+
+        let to = now + time::Duration::days(horizon_days);
+        let mut vendors = self.list_vendors(false)?;
+        vendors.retain(|vendor| vendor.insurance_expiry.is_some_and(|expiry| expiry <= to));
+        vendors.sort_by(|left, right| {
+            left.insurance_expiry
+                .cmp(&right.insurance_expiry)
+                .then_with(|| right.id.cmp(&left.id))
+        });
+        Ok(vendors)
+    }
+
+    /// Live rows that look wrong: maintenance that never comes due, quotes
+    /// whose parts don't add up, incidents resolved before they were
+    /// noticed, and documents without any bytes.
+    pub fn list_data_issues(&self) -> Result<Vec<DataIssue>> {
+        let checks: [(DataIssueKind, &str); 4] = [
+            (
+                DataIssueKind::ZeroInterval,
+                "
+                SELECT id, name, interval_months, NULL
+                FROM maintenance_items
+                WHERE deleted_at IS NULL AND interval_months <= 0
+                ORDER BY id
+                ",
+            ),
+            (
+                DataIssueKind::QuoteParts,
+                "
+                SELECT q.id, COALESCE(p.title, '') || ' / ' || COALESCE(v.name, ''),
+                  COALESCE(q.labor_cents, 0) + COALESCE(q.materials_cents, 0)
+                    + COALESCE(q.other_cents, 0),
+                  q.total_cents
+                FROM quotes q
+                LEFT JOIN projects p ON p.id = q.project_id
+                LEFT JOIN vendors v ON v.id = q.vendor_id
+                WHERE q.deleted_at IS NULL
+                  AND COALESCE(q.labor_cents, q.materials_cents, q.other_cents) IS NOT NULL
+                  AND COALESCE(q.labor_cents, 0) + COALESCE(q.materials_cents, 0)
+                    + COALESCE(q.other_cents, 0) != q.total_cents
+                ORDER BY q.id
+                ",
+            ),
+            (
+                DataIssueKind::ResolvedBeforeNoticed,
+                "
+                SELECT id, title, date_resolved, date_noticed
+                FROM incidents
+                WHERE deleted_at IS NULL AND date_resolved < date_noticed
+                ORDER BY id
+                ",
+            ),
+            (
+                DataIssueKind::EmptyDocument,
+                "
+                SELECT id, title, file_name, NULL
+                FROM documents
+                WHERE deleted_at IS NULL AND (size_bytes <= 0 OR length(data) = 0)
+                ORDER BY id
+                ",
+            ),
+        ];
+
+        let mut issues = Vec::new();
+        for (kind, sql) in checks {
+            let mut stmt = self
+                .conn
+                .prepare(sql)
+                .context("prepare data quality check")?;
+            let rows = stmt
+                .query_map([], |row| {
+                    let detail = match kind {
+                        DataIssueKind::ZeroInterval => {
+                            format!("interval is {} months", row.get::<_, i64>(2)?)
+                        }
+                        DataIssueKind::QuoteParts => format!(
+                            "parts {} vs total {}",
+                            validation::format_cents(row.get(2)?),
+                            validation::format_cents(row.get(3)?)
+                        ),
+                        DataIssueKind::ResolvedBeforeNoticed => format!(
+                            "resolved {} but noticed {}",
+                            row.get::<_, String>(2)?,
+                            row.get::<_, String>(3)?
+                        ),
+                        DataIssueKind::EmptyDocument => {
+                            format!("{} has no bytes", row.get::<_, String>(2)?)
+                        }
+                    };
+                    Ok(DataIssue {
+                        kind,
+                        row_id: row.get(0)?,
+                        title: row.get(1)?,
+                        detail,
+                    })
+                })
+                .context("run data quality check")?;
+            issues.extend(
+                rows.collect::<rusqlite::Result<Vec<_>>>()
+                    .context("collect data quality issues")?,
+            );
+        }
+        Ok(issues)
+    }
+
+    /// Recomputes the stored values that other columns determine, in one
+    /// transaction: each maintenance item with live service log entries takes
+    /// the date of its latest one as `last_serviced_at`, and each document's
+    /// size and checksum are taken from its bytes again. `progress` gets the
+    /// step name, rows done and rows in the step after every row.
+    pub fn recalculate_derived(
+        &self,
+        progress: &mut dyn FnMut(&str, usize, usize),
+    ) -> Result<RecalcSummary> {
+        let tx = WriteBatch::begin(&self.conn).context("begin recalc")?;
+        let now = now_rfc3339()?;
+        let mut summary = RecalcSummary::default();
+
+        let items = {
+            let mut stmt = tx
+                .prepare(
+                    "
+                    SELECT m.id, m.last_serviced_at, MAX(s.serviced_at)
+                    FROM maintenance_items m
+                    JOIN service_log_entries s
+                      ON s.maintenance_item_id = m.id AND s.deleted_at IS NULL
+                    WHERE m.deleted_at IS NULL
+                    GROUP BY m.id
+                    ORDER BY m.id
+                    ",
+                )
+                .context("prepare last serviced recalc")?;
+            stmt.query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, Option<String>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .context("query last serviced dates")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("scan last serviced dates")?
+        };
+        for (done, (id, stored, latest)) in items.iter().enumerate() {
+            if stored.as_deref() != Some(latest.as_str()) {
+                tx.execute(
+                    "UPDATE maintenance_items SET last_serviced_at = ?, updated_at = ? WHERE id = ?",
+                    params![latest, now, id],
+                )
+                .with_context(|| format!("update last serviced date of maintenance item {id}"))?;
+                summary.last_serviced += 1;
+            }
+            progress("last serviced", done + 1, items.len());
+        }
+
+        let document_ids = {
+            let mut stmt = tx
+                .prepare("SELECT id FROM documents WHERE deleted_at IS NULL ORDER BY id")
+                .context("prepare document recalc")?;
+            stmt.query_map([], |row| row.get::<_, i64>(0))
+                .context("query documents")?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("scan documents")?
+        };
+        // One row at a time so only one document's bytes are in memory.
+        for (done, id) in document_ids.iter().enumerate() {
+            let (size_bytes, sha256, data) = tx
+                .query_row(
+                    "SELECT size_bytes, sha256, data FROM documents WHERE id = ?",
+                    params![id],
+                    |row| {
+                        Ok((
+                            row.get::<_, i64>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, Vec<u8>>(2)?,
+                        ))
+                    },
+                )
+                .with_context(|| format!("load document {id}"))?;
+            // Sealed bytes can't be checked without the passphrase.
+            let data = match self.document_key.borrow().as_ref() {
+                Some(key) if crypto::is_sealed(&data) => key
+                    .open(&data)
+                    .with_context(|| format!("decrypt document {id}"))?,
+                None if crypto::is_sealed(&data) => {
+                    progress("documents", done + 1, document_ids.len());
+                    continue;
+                }
+                _ => data,
+            };
+            let actual_size = i64::try_from(data.len()).context("document size overflow")?;
+            let actual_sha256 = checksum_sha256(&data);
+            if size_bytes != actual_size || sha256 != actual_sha256 {
+                tx.execute(
+                    "UPDATE documents SET size_bytes = ?, sha256 = ?, updated_at = ? WHERE id = ?",
+                    params![actual_size, actual_sha256, now, id],
+                )
+                .with_context(|| format!("update checksum of document {id}"))?;
+                summary.document_checksums += 1;
+            }
+            progress("documents", done + 1, document_ids.len());
+        }
+
+        tx.commit().context("commit recalc")?;
+        Ok(summary)
+    }
+
+    /// Starts holding every change in an open savepoint so it can be thrown
+    /// away with [`Store::discard_sandbox`]. Nothing written meanwhile
+    /// reaches the file, and closing the store discards it too.
+    pub fn begin_sandbox(&self) -> Result<()> {
+        if self.sandbox.get() {
+            bail!("a what-if sandbox is already open; discard it first");
+        }
+        self.conn
+            .execute_batch("SAVEPOINT sandbox")
+            .context("begin what-if sandbox")?;
+        self.sandbox.set(true);
+        Ok(())
+    }
+
+    /// Rolls back everything written since [`Store::begin_sandbox`].
+    pub fn discard_sandbox(&self) -> Result<()> {
+        if !self.sandbox.get() {
+            bail!("no what-if sandbox is open");
+        }
+        self.conn
+            .execute_batch("ROLLBACK TO sandbox; RELEASE sandbox")
+            .context("discard what-if sandbox")?;
+        self.sandbox.set(false);
+        Ok(())
+    }
+
+    pub fn in_sandbox(&self) -> bool {
+        self.sandbox.get()
+    }
+
     pub fn list_recent_service_logs(&self, limit: usize) -> Result<Vec<ServiceLogEntry>> {
         let mut logs = self.list_service_log_entries(false)?;
         logs.truncate(limit);
@@ -3345,12 +6407,20 @@ impl Store {
         let total: i64 = self
             .conn
             .query_row(
-                "
-                SELECT COALESCE(SUM(cost_cents), 0)
-                FROM service_log_entries
-                WHERE deleted_at IS NULL
-                  AND serviced_at >= ?
-                ",
+                &format!(
+                    "
+                    SELECT COALESCE(SUM({cost}), 0)
+                    FROM service_log_entries sle
+                    WHERE deleted_at IS NULL
+                      AND serviced_at >= ?
+                    ",
+                    cost = home_cents_sql(
+                        EntityKind::ServiceLogEntry,
+                        "sle",
+                        "cost_cents",
+                        "sle.cost_cents"
+                    ),
+                ),
                 params![format_date(year_start)],
                 |row| row.get(0),
             )
@@ -3362,11 +6432,15 @@ impl Store {
         let total: i64 = self
             .conn
             .query_row(
-                "
-                SELECT COALESCE(SUM(actual_cents), 0)
-                FROM projects
-                WHERE deleted_at IS NULL
-                ",
+                &format!(
+                    "
+                    SELECT COALESCE(SUM({actual}), 0)
+                    FROM projects p
+                    WHERE deleted_at IS NULL
+                    ",
+                    actual =
+                        home_cents_sql(EntityKind::Project, "p", "actual_cents", "p.actual_cents"),
+                ),
                 [],
                 |row| row.get(0),
             )
@@ -3379,7 +6453,8 @@ impl Store {
             "
             SELECT
               id, title, file_name, entity_kind, entity_id, mime_type,
-              size_bytes, sha256, notes, created_at, updated_at, deleted_at
+              size_bytes, sha256, notes, created_at, updated_at, deleted_at,
+              extracted_text
             FROM documents
             ",
         );
@@ -3417,6 +6492,7 @@ impl Store {
                     checksum_sha256: row.get(7)?,
                     data: Vec::new(),
                     notes: row.get(8)?,
+                    extracted_text: row.get(12)?,
                     created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                     updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                     deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
@@ -3438,6 +6514,7 @@ impl Store {
         }
 
         let checksum = checksum_sha256(&new_document.data);
+        let data = self.seal_document_data(&new_document.data)?;
         let now = now_rfc3339()?;
         self.conn
             .execute(
@@ -3455,7 +6532,7 @@ impl Store {
                     new_document.mime_type,
                     size,
                     checksum,
-                    new_document.data,
+                    data,
                     new_document.notes,
                     now,
                     now,
@@ -3471,7 +6548,8 @@ impl Store {
                 "
                 SELECT
                   id, title, file_name, entity_kind, entity_id, mime_type,
-                  size_bytes, sha256, data, notes, created_at, updated_at, deleted_at
+                  size_bytes, sha256, data, notes, created_at, updated_at, deleted_at,
+                  extracted_text
                 FROM documents
                 WHERE id = ?
                 ",
@@ -3503,6 +6581,7 @@ impl Store {
                         checksum_sha256: row.get(7)?,
                         data: row.get(8)?,
                         notes: row.get(9)?,
+                        extracted_text: row.get(13)?,
                         created_at: parse_datetime(&created_at_raw).map_err(to_sql_error)?,
                         updated_at: parse_datetime(&updated_at_raw).map_err(to_sql_error)?,
                         deleted_at: parse_opt_datetime(deleted_at_raw).map_err(to_sql_error)?,
@@ -3510,6 +6589,10 @@ impl Store {
                 },
             )
             .with_context(|| format!("load document {}", document_id.get()))
+            .and_then(|mut document| {
+                document.data = self.open_document_data(document_id, document.data)?;
+                Ok(document)
+            })
     }
 
     pub fn update_document(&self, document_id: DocumentId, update: &UpdateDocument) -> Result<()> {
@@ -3524,6 +6607,7 @@ impl Store {
                 );
             }
             let checksum = checksum_sha256(data);
+            let data = self.seal_document_data(data)?;
             self.conn
                 .execute(
                     "
@@ -3537,6 +6621,7 @@ impl Store {
                       size_bytes = ?,
                       sha256 = ?,
                       data = ?,
+                      extracted_text = '',
                       notes = ?,
                       updated_at = ?
                     WHERE id = ? AND deleted_at IS NULL
@@ -3594,15 +6679,474 @@ impl Store {
         Ok(())
     }
 
-    pub fn soft_delete_document(&self, document_id: DocumentId) -> Result<()> {
-        self.soft_delete(LifecycleEntityRef::Document(document_id))
-    }
-
-    pub fn restore_document(&self, document_id: DocumentId) -> Result<()> {
-        self.restore(LifecycleEntityRef::Document(document_id))
+    /// Stores the text read out of a document's file. Replacing the file
+    /// clears it, so a stale transcript never outlives its bytes. Encrypted
+    /// databases keep none, since the text would give the sealed file away.
+    pub fn set_document_text(&self, document_id: DocumentId, text: &str) -> Result<()> {
+        if !text.is_empty() && self.documents_encrypted()? {
+            bail!(
+                "documents are encrypted, so text read out of document {} is not stored",
+                document_id.get()
+            );
+        }
+        let rows_affected = self
+            .conn
+            .execute(
+                "UPDATE documents SET extracted_text = ? WHERE id = ?",
+                params![text, document_id.get()],
+            )
+            .with_context(|| format!("store text of document {}", document_id.get()))?;
+        if rows_affected == 0 {
+            bail!(
+                "document {} not found -- refresh the view and pick an existing row",
+                document_id.get()
+            );
+        }
+        Ok(())
     }
 
-    pub fn extract_document(&self, document_id: DocumentId) -> Result<PathBuf> {
+    /// Swaps a document's indexed passages for `chunks`, each embedded with
+    /// `model`. An empty slice just clears the index for that document.
+    pub fn replace_document_chunks(
+        &self,
+        document_id: DocumentId,
+        model: &str,
+        chunks: &[(String, Vec<f32>)],
+    ) -> Result<()> {
+        let tx = WriteBatch::begin(&self.conn).context("begin document index update")?;
+        tx.execute(
+            "DELETE FROM document_chunks WHERE document_id = ?",
+            params![document_id.get()],
+        )
+        .context("clear document index")?;
+        for (index, (content, embedding)) in chunks.iter().enumerate() {
+            tx.execute(
+                "
+                INSERT INTO document_chunks (document_id, chunk_index, content, model, embedding)
+                VALUES (?, ?, ?, ?, ?)
+                ",
+                params![
+                    document_id.get(),
+                    index as i64,
+                    content,
+                    model,
+                    embeddings::encode_embedding(embedding),
+                ],
+            )
+            .with_context(|| format!("index document {}", document_id.get()))?;
+        }
+        tx.commit().context("commit document index")
+    }
+
+    /// Indexed passages embedded with `model`, from live documents only.
+    /// Private documents drop out while private rows are hidden.
+    pub fn document_chunks(&self, model: &str) -> Result<Vec<embeddings::DocumentChunk>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT c.document_id, d.title, c.content, c.embedding
+                FROM main.document_chunks c
+                JOIN main.documents d ON d.id = c.document_id
+                WHERE c.model = ? AND d.deleted_at IS NULL
+                  AND NOT (? AND c.document_id IN (
+                    SELECT target_id FROM main.private_rows WHERE entity = 'document'
+                  ))
+                ORDER BY c.document_id ASC, c.chunk_index ASC
+                ",
+            )
+            .context("prepare document index query")?;
+        let rows = stmt
+            .query_map(params![model, self.private_shadowed.get()], |row| {
+                let raw: Vec<u8> = row.get(3)?;
+                Ok(embeddings::DocumentChunk {
+                    document_id: DocumentId::new(row.get(0)?),
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    embedding: embeddings::decode_embedding(&raw).map_err(to_sql_error)?,
+                })
+            })
+            .context("query document index")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect document index")
+    }
+
+    /// Swaps a maintenance item's indexed manual passages for `chunks`, each
+    /// embedded with `model`. An empty slice just clears them.
+    pub fn replace_manual_chunks(
+        &self,
+        maintenance_id: MaintenanceItemId,
+        model: &str,
+        chunks: &[(String, Vec<f32>)],
+    ) -> Result<()> {
+        let tx = WriteBatch::begin(&self.conn).context("begin manual index update")?;
+        tx.execute(
+            "DELETE FROM manual_chunks WHERE maintenance_item_id = ?",
+            params![maintenance_id.get()],
+        )
+        .context("clear manual index")?;
+        for (index, (content, embedding)) in chunks.iter().enumerate() {
+            tx.execute(
+                "
+                INSERT INTO manual_chunks (maintenance_item_id, chunk_index, content, model, embedding)
+                VALUES (?, ?, ?, ?, ?)
+                ",
+                params![
+                    maintenance_id.get(),
+                    index as i64,
+                    content,
+                    model,
+                    embeddings::encode_embedding(embedding),
+                ],
+            )
+            .with_context(|| format!("index manual for maintenance item {}", maintenance_id.get()))?;
+        }
+        tx.commit().context("commit manual index")
+    }
+
+    /// Indexed manual passages embedded with `model`, from live maintenance
+    /// items only, each titled by the item's name. Private items drop out
+    /// while private rows are hidden.
+    pub fn manual_chunks(&self, model: &str) -> Result<Vec<embeddings::ManualChunk>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT c.maintenance_item_id, m.name, c.content, c.embedding
+                FROM main.manual_chunks c
+                JOIN main.maintenance_items m ON m.id = c.maintenance_item_id
+                WHERE c.model = ? AND m.deleted_at IS NULL
+                  AND NOT (? AND c.maintenance_item_id IN (
+                    SELECT target_id FROM main.private_rows WHERE entity = 'maintenance'
+                  ))
+                ORDER BY c.maintenance_item_id ASC, c.chunk_index ASC
+                ",
+            )
+            .context("prepare manual index query")?;
+        let rows = stmt
+            .query_map(params![model, self.private_shadowed.get()], |row| {
+                let raw: Vec<u8> = row.get(3)?;
+                Ok(embeddings::ManualChunk {
+                    maintenance_item_id: MaintenanceItemId::new(row.get(0)?),
+                    title: row.get(1)?,
+                    content: row.get(2)?,
+                    embedding: embeddings::decode_embedding(&raw).map_err(to_sql_error)?,
+                })
+            })
+            .context("query manual index")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect manual index")
+    }
+
+    /// The `(kind, id)` a document is filed under; unlinked documents report
+    /// `DocumentEntityKind::None`.
+    pub fn document_link(&self, document_id: DocumentId) -> Result<(DocumentEntityKind, i64)> {
+        let (kind_raw, entity_id): (String, i64) = self
+            .conn
+            .query_row(
+                "SELECT entity_kind, entity_id FROM documents WHERE id = ?",
+                params![document_id.get()],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .with_context(|| format!("load document {} link", document_id.get()))?
+            .ok_or_else(|| {
+                anyhow!(
+                    "document {} not found -- refresh the view and pick an existing row",
+                    document_id.get()
+                )
+            })?;
+        let kind = DocumentEntityKind::parse(&kind_raw)
+            .ok_or_else(|| anyhow!("unknown document entity kind {kind_raw}"))?;
+        Ok((kind, entity_id))
+    }
+
+    /// Files a live document under another record, or unlinks it with
+    /// `DocumentEntityKind::None`. The target must exist and not be deleted.
+    pub fn relink_document(
+        &self,
+        document_id: DocumentId,
+        kind: DocumentEntityKind,
+        entity_id: i64,
+    ) -> Result<()> {
+        let entity_id = match document_link_table(kind) {
+            None => 0,
+            Some(table) => {
+                let deleted_at: Option<Option<String>> = self
+                    .conn
+                    .query_row(
+                        &format!("SELECT deleted_at FROM {table} WHERE id = ?"),
+                        params![entity_id],
+                        |row| row.get(0),
+                    )
+                    .optional()
+                    .with_context(|| format!("load {} {entity_id}", kind.as_str()))?;
+                match deleted_at {
+                    Some(None) => entity_id,
+                    Some(Some(_)) => {
+                        bail!(
+                            "{} {entity_id} is deleted -- restore it first",
+                            kind.as_str()
+                        )
+                    }
+                    None => bail!(
+                        "{} {entity_id} no longer exists -- pick another target",
+                        kind.as_str()
+                    ),
+                }
+            }
+        };
+        let rows_affected = self
+            .conn
+            .execute(
+                "
+                UPDATE documents
+                SET entity_kind = ?, entity_id = ?, updated_at = ?
+                WHERE id = ? AND deleted_at IS NULL
+                ",
+                params![kind.as_str(), entity_id, now_rfc3339()?, document_id.get()],
+            )
+            .with_context(|| format!("relink document {}", document_id.get()))?;
+        if rows_affected == 0 {
+            bail!(
+                "document {} not found or deleted -- refresh the view and pick an existing row",
+                document_id.get()
+            );
+        }
+        Ok(())
+    }
+
+    /// Live rows a document can be filed under, as `(id, label)` sorted by
+    /// label. Quotes and service log entries borrow their parents' names.
+    pub fn list_link_targets(&self, kind: DocumentEntityKind) -> Result<Vec<(i64, String)>> {
+        let sql = match kind {
+            DocumentEntityKind::None => return Ok(Vec::new()),
+            DocumentEntityKind::Project => {
+                "SELECT id, title FROM projects WHERE deleted_at IS NULL ORDER BY title, id DESC"
+            }
+            DocumentEntityKind::Quote => {
+                "
+                SELECT q.id, v.name || ' / ' || p.title AS label
+                FROM quotes q
+                JOIN vendors v ON v.id = q.vendor_id
+                JOIN projects p ON p.id = q.project_id
+                WHERE q.deleted_at IS NULL
+                ORDER BY label, q.id DESC
+                "
+            }
+            DocumentEntityKind::Maintenance => {
+                "SELECT id, name FROM maintenance_items WHERE deleted_at IS NULL ORDER BY name, id DESC"
+            }
+            DocumentEntityKind::Appliance => {
+                "SELECT id, name FROM appliances WHERE deleted_at IS NULL ORDER BY name, id DESC"
+            }
+            DocumentEntityKind::ServiceLog => {
+                "
+                SELECT s.id, m.name || ' ' || s.serviced_at AS label
+                FROM service_log_entries s
+                JOIN maintenance_items m ON m.id = s.maintenance_item_id
+                WHERE s.deleted_at IS NULL
+                ORDER BY label, s.id DESC
+                "
+            }
+            DocumentEntityKind::Vendor => {
+                "SELECT id, name FROM vendors WHERE deleted_at IS NULL ORDER BY name, id DESC"
+            }
+            DocumentEntityKind::Incident => {
+                "SELECT id, title FROM incidents WHERE deleted_at IS NULL ORDER BY title, id DESC"
+            }
+        };
+        let mut stmt = self
+            .conn
+            .prepare(sql)
+            .with_context(|| format!("prepare {} link targets", kind.as_str()))?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .with_context(|| format!("list {} link targets", kind.as_str()))?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .with_context(|| format!("read {} link targets", kind.as_str()))
+    }
+
+    pub fn soft_delete_document(&self, document_id: DocumentId) -> Result<()> {
+        self.soft_delete(LifecycleEntityRef::Document(document_id))
+    }
+
+    pub fn restore_document(&self, document_id: DocumentId) -> Result<()> {
+        self.restore(LifecycleEntityRef::Document(document_id))
+    }
+
+    /// Whether document bytes are sealed under a passphrase.
+    pub fn documents_encrypted(&self) -> Result<bool> {
+        Ok(self.document_key_record()?.is_some())
+    }
+
+    /// True until [`Store::unlock_documents`] takes the passphrase of an
+    /// encrypted database; documents can't be opened or added meanwhile.
+    pub fn documents_locked(&self) -> Result<bool> {
+        Ok(self.document_key.borrow().is_none() && self.documents_encrypted()?)
+    }
+
+    /// Takes the passphrase for sealed documents; false when it is wrong.
+    pub fn unlock_documents(&self, passphrase: &str) -> Result<bool> {
+        let record = self.document_key_record()?.ok_or_else(|| {
+            anyhow!("documents are not encrypted; turn it on with `micasa --encrypt-documents`")
+        })?;
+        let key = crypto::DocumentKey::unlock(passphrase, &record)?;
+        let unlocked = key.is_some();
+        if unlocked {
+            *self.document_key.borrow_mut() = key;
+        }
+        Ok(unlocked)
+    }
+
+    /// Seals every stored document, deleted ones included, under a key from
+    /// `passphrase`. Returns how many were sealed.
+    pub fn encrypt_documents(&self, passphrase: &str) -> Result<usize> {
+        if self.documents_encrypted()? {
+            bail!(
+                "documents are already encrypted; change the passphrase with `micasa --rekey-documents`"
+            );
+        }
+        let (key, record) = crypto::DocumentKey::generate(passphrase)?;
+        let sealed = self.reseal_documents(None, &key, &record)?;
+        *self.document_key.borrow_mut() = Some(key);
+        Ok(sealed)
+    }
+
+    /// Rotates the document key: checks `current`, then reseals every
+    /// document under a new salt and `new_passphrase` in one batch.
+    pub fn rekey_documents(&self, current: &str, new_passphrase: &str) -> Result<usize> {
+        let record = self.document_key_record()?.ok_or_else(|| {
+            anyhow!("documents are not encrypted; turn it on with `micasa --encrypt-documents`")
+        })?;
+        let old = crypto::DocumentKey::unlock(current, &record)?
+            .ok_or_else(|| anyhow!("current passphrase is wrong; nothing was changed"))?;
+        let (key, record) = crypto::DocumentKey::generate(new_passphrase)?;
+        let resealed = self.reseal_documents(Some(&old), &key, &record)?;
+        *self.document_key.borrow_mut() = Some(key);
+        Ok(resealed)
+    }
+
+    fn reseal_documents(
+        &self,
+        old: Option<&crypto::DocumentKey>,
+        key: &crypto::DocumentKey,
+        record: &crypto::KeyRecord,
+    ) -> Result<usize> {
+        let tx = WriteBatch::begin(&self.conn).context("begin document encryption")?;
+        let ids = {
+            let mut stmt = tx
+                .prepare("SELECT id FROM documents WHERE length(data) > 0 ORDER BY id")
+                .context("prepare document encryption")?;
+            stmt.query_map([], |row| row.get::<_, i64>(0))
+                .context("query documents")?
+                .collect::<rusqlite::Result<Vec<_>>>()
+                .context("scan documents")?
+        };
+        // One row at a time so only one document's bytes are in memory.
+        for id in &ids {
+            let data: Vec<u8> = tx
+                .query_row(
+                    "SELECT data FROM documents WHERE id = ?",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .with_context(|| format!("load document {id}"))?;
+            let plain = match old {
+                Some(old) if crypto::is_sealed(&data) => old
+                    .open(&data)
+                    .with_context(|| format!("decrypt document {id}"))?,
+                _ => data,
+            };
+            tx.execute(
+                "UPDATE documents SET data = ? WHERE id = ?",
+                params![key.seal(&plain)?, id],
+            )
+            .with_context(|| format!("encrypt document {id}"))?;
+        }
+        // Text read out of the files and the passages indexed from it would
+        // leave the contents readable next to the sealed bytes.
+        tx.execute("UPDATE documents SET extracted_text = ''", [])
+            .context("clear extracted document text")?;
+        tx.execute("DELETE FROM document_chunks", [])
+            .context("clear document index")?;
+        self.put_setting_raw(DOCUMENT_KEY_SETTING, &record.to_storage())?;
+        tx.commit().context("commit document encryption")?;
+        self.remove_cached_documents()?;
+        Ok(ids.len())
+    }
+
+    /// Deletes the copies [`Store::extract_document`] left in the cache for
+    /// this database's documents; returns how many it removed. Encrypted
+    /// databases call this so decrypted files don't outlive the session.
+    pub fn remove_cached_documents(&self) -> Result<usize> {
+        let checksums = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT DISTINCT sha256 FROM documents WHERE sha256 <> ''")
+                .context("prepare document checksums query")?;
+            stmt.query_map([], |row| row.get::<_, String>(0))
+                .context("query document checksums")?
+                .collect::<rusqlite::Result<BTreeSet<_>>>()
+                .context("scan document checksums")?
+        };
+        if checksums.is_empty() {
+            return Ok(0);
+        }
+        let dir = document_cache_dir()?;
+        let mut removed = 0usize;
+        for entry in
+            fs::read_dir(&dir).with_context(|| format!("read cache dir {}", dir.display()))?
+        {
+            let entry = entry?;
+            let name = entry.file_name();
+            let ours = name
+                .to_string_lossy()
+                .split_once('-')
+                .is_some_and(|(checksum, _)| checksums.contains(checksum));
+            if ours {
+                fs::remove_file(entry.path())
+                    .with_context(|| format!("remove cache file {}", entry.path().display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn document_key_record(&self) -> Result<Option<crypto::KeyRecord>> {
+        self.get_setting_raw(DOCUMENT_KEY_SETTING)?
+            .map(|raw| crypto::KeyRecord::parse(&raw))
+            .transpose()
+    }
+
+    /// Bytes as stored: sealed when a key is held, refused while an
+    /// encrypted database is locked.
+    fn seal_document_data<'a>(&self, data: &'a [u8]) -> Result<Cow<'a, [u8]>> {
+        if let Some(key) = self.document_key.borrow().as_ref() {
+            return Ok(Cow::Owned(key.seal(data)?));
+        }
+        if self.documents_encrypted()? {
+            bail!("documents are encrypted and locked -- restart micasa and enter the passphrase");
+        }
+        Ok(Cow::Borrowed(data))
+    }
+
+    fn open_document_data(&self, document_id: DocumentId, data: Vec<u8>) -> Result<Vec<u8>> {
+        if !crypto::is_sealed(&data) {
+            return Ok(data);
+        }
+        match self.document_key.borrow().as_ref() {
+            Some(key) => key
+                .open(&data)
+                .with_context(|| format!("decrypt document {}", document_id.get())),
+            None => bail!(
+                "document {} is encrypted and locked -- restart micasa and enter the passphrase",
+                document_id.get()
+            ),
+        }
+    }
+
+    pub fn extract_document(&self, document_id: DocumentId) -> Result<PathBuf> {
         let row = self
             .conn
             .query_row(
@@ -3619,6 +7163,7 @@ impl Store {
             .with_context(|| format!("load document content {}", document_id.get()))?;
 
         let (data, file_name, checksum, size_bytes) = row;
+        let data = self.open_document_data(document_id, data)?;
         if data.is_empty() {
             bail!("document {} has no content", document_id.get());
         }
@@ -3704,6 +7249,23 @@ impl Store {
         self.put_setting_raw(key.as_str(), &raw)
     }
 
+    /// Writes imported settings and saved views together; a failure on any
+    /// one leaves all of them as they were.
+    pub fn apply_settings(
+        &self,
+        settings: &[(SettingKey, SettingValue)],
+        views: &[(TabKind, SavedView)],
+    ) -> Result<()> {
+        let tx = WriteBatch::begin(&self.conn).context("begin settings import")?;
+        for (key, value) in settings {
+            self.put_setting(*key, value.clone())?;
+        }
+        for (tab, view) in views {
+            self.save_view(*tab, view)?;
+        }
+        tx.commit().context("commit settings import")
+    }
+
     pub fn list_settings(&self) -> Result<Vec<AppSetting>> {
         let mut settings = Vec::with_capacity(SettingKey::ALL.len());
         for key in SettingKey::ALL {
@@ -3725,42 +7287,1337 @@ impl Store {
                     Ok(Some(trimmed.to_owned()))
                 }
             }
-            Some(SettingValue::Bool(_)) => bail!(
-                "setting `{}` must be text; open Settings and choose a model name",
-                SettingKey::LlmModel.as_str()
-            ),
-            None => Ok(None),
-        }
+            Some(_) => bail!(
+                "setting `{}` must be text; open Settings and choose a model name",
+                SettingKey::LlmModel.as_str()
+            ),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_last_model(&self, model: &str) -> Result<()> {
+        self.put_setting(SettingKey::LlmModel, SettingValue::Text(model.to_owned()))
+    }
+
+    /// Model picked for one chat stage (`llm.sql_model` or
+    /// `llm.summary_model`); `None` when the stage uses the chat model.
+    pub fn get_stage_model(&self, key: SettingKey) -> Result<Option<String>> {
+        match self.get_setting(key)? {
+            Some(SettingValue::Text(value)) if !value.trim().is_empty() => {
+                Ok(Some(value.trim().to_owned()))
+            }
+            Some(SettingValue::Text(_)) | None => Ok(None),
+            Some(_) => bail!(
+                "setting `{}` must be text; open Settings and choose a model name",
+                key.as_str()
+            ),
+        }
+    }
+
+    /// Saves the model for one chat stage; an empty name goes back to the
+    /// chat model.
+    pub fn put_stage_model(&self, key: SettingKey, model: &str) -> Result<()> {
+        if !matches!(key, SettingKey::LlmSqlModel | SettingKey::LlmSummaryModel) {
+            bail!(
+                "setting `{}` is not a chat stage model; use llm.sql_model or llm.summary_model",
+                key.as_str()
+            );
+        }
+        self.put_setting(key, SettingValue::Text(model.trim().to_owned()))
+    }
+
+    pub fn get_show_dashboard(&self) -> Result<bool> {
+        match self.get_setting(SettingKey::UiShowDashboard)? {
+            Some(SettingValue::Bool(value)) => Ok(value),
+            Some(_) => bail!(
+                "setting `{}` must be on/off; open Settings and toggle it",
+                SettingKey::UiShowDashboard.as_str()
+            ),
+            None => Ok(true),
+        }
+    }
+
+    pub fn get_show_dashboard_override(&self) -> Result<Option<bool>> {
+        match self.get_setting(SettingKey::UiShowDashboard)? {
+            Some(SettingValue::Bool(value)) => Ok(Some(value)),
+            Some(_) => bail!(
+                "setting `{}` must be on/off; open Settings and toggle it",
+                SettingKey::UiShowDashboard.as_str()
+            ),
+            None => Ok(None),
+        }
+    }
+
+    pub fn put_show_dashboard(&self, show: bool) -> Result<()> {
+        self.put_setting(SettingKey::UiShowDashboard, SettingValue::Bool(show))
+    }
+
+    /// Whether `tab` opens with its settled rows hidden; tabs without a
+    /// visibility rule always show everything.
+    pub fn get_hide_settled(&self, tab: TabKind) -> Result<bool> {
+        let Some(key) = SettingKey::hide_settled_for(tab) else {
+            return Ok(false);
+        };
+        match self.get_setting(key)? {
+            Some(SettingValue::Bool(value)) => Ok(value),
+            Some(_) => bail!(
+                "setting `{}` must be on/off; open Settings and toggle it",
+                key.as_str()
+            ),
+            None => Ok(false),
+        }
+    }
+
+    /// Saves `view` for `tab`, replacing a view of the same name.
+    pub fn save_view(&self, tab: TabKind, view: &SavedView) -> Result<()> {
+        let name = view.name.trim();
+        if name.is_empty() {
+            bail!("view name is required -- type a name before saving");
+        }
+        // One `direction:column` per line; column names never hold newlines.
+        let sorts = view
+            .sorts
+            .iter()
+            .map(|(column, direction)| {
+                let direction = match direction {
+                    SortDirection::Asc => "asc",
+                    SortDirection::Desc => "desc",
+                };
+                format!("{direction}:{column}")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (pin_column, pin_value) = view.pin.clone().unzip();
+        self.conn
+            .execute(
+                "
+                INSERT INTO saved_views (
+                  tab, name, sorts, pin_column, pin_value, filter_active,
+                  filter_inverted, hidden_columns, hide_settled, updated_at
+                )
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT (tab, name) DO UPDATE SET
+                  sorts = excluded.sorts,
+                  pin_column = excluded.pin_column,
+                  pin_value = excluded.pin_value,
+                  filter_active = excluded.filter_active,
+                  filter_inverted = excluded.filter_inverted,
+                  hidden_columns = excluded.hidden_columns,
+                  hide_settled = excluded.hide_settled,
+                  updated_at = excluded.updated_at
+                ",
+                params![
+                    tab.label(),
+                    name,
+                    sorts,
+                    pin_column,
+                    pin_value,
+                    view.filter_active,
+                    view.filter_inverted,
+                    view.hidden_columns.join("\n"),
+                    view.hide_settled,
+                    now_rfc3339()?,
+                ],
+            )
+            .with_context(|| format!("save view `{name}`"))?;
+        Ok(())
+    }
+
+    /// Names of the views saved for `tab`, alphabetically.
+    pub fn list_views(&self, tab: TabKind) -> Result<Vec<String>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT name FROM saved_views WHERE tab = ? ORDER BY name COLLATE NOCASE")
+            .context("prepare saved views query")?;
+        let rows = stmt
+            .query_map(params![tab.label()], |row| row.get(0))
+            .context("query saved views")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect saved views")
+    }
+
+    pub fn load_view(&self, tab: TabKind, name: &str) -> Result<Option<SavedView>> {
+        let row = self
+            .conn
+            .query_row(
+                "
+                SELECT name, sorts, pin_column, pin_value, filter_active,
+                  filter_inverted, hidden_columns, hide_settled
+                FROM saved_views
+                WHERE tab = ? AND name = ?
+                ",
+                params![tab.label(), name],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, bool>(4)?,
+                        row.get::<_, bool>(5)?,
+                        row.get::<_, String>(6)?,
+                        row.get::<_, bool>(7)?,
+                    ))
+                },
+            )
+            .optional()
+            .with_context(|| format!("load view `{name}`"))?;
+        let Some((
+            name,
+            sorts,
+            pin_column,
+            pin_value,
+            filter_active,
+            filter_inverted,
+            hidden_columns,
+            hide_settled,
+        )) = row
+        else {
+            return Ok(None);
+        };
+        let sorts = sorts
+            .lines()
+            .map(|line| match line.split_once(':') {
+                Some(("asc", column)) => Ok((column.to_owned(), SortDirection::Asc)),
+                Some(("desc", column)) => Ok((column.to_owned(), SortDirection::Desc)),
+                _ => Err(anyhow!(
+                    "view `{name}` has a malformed sort `{line}` -- save the view again"
+                )),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Some(SavedView {
+            sorts,
+            pin: pin_column.zip(pin_value),
+            filter_active,
+            filter_inverted,
+            hidden_columns: hidden_columns.lines().map(str::to_owned).collect(),
+            hide_settled,
+            name,
+        }))
+    }
+
+    /// Removes a saved view; false when no such view exists.
+    pub fn delete_view(&self, tab: TabKind, name: &str) -> Result<bool> {
+        let removed = self
+            .conn
+            .execute(
+                "DELETE FROM saved_views WHERE tab = ? AND name = ?",
+                params![tab.label(), name],
+            )
+            .with_context(|| format!("delete view `{name}`"))?;
+        Ok(removed > 0)
+    }
+
+    /// Theme picked in Settings; `None` defers to `ui.theme` in the config.
+    pub fn get_theme_override(&self) -> Result<Option<String>> {
+        match self.get_setting(SettingKey::UiTheme)? {
+            Some(SettingValue::Text(value)) if !value.trim().is_empty() => {
+                Ok(Some(value.trim().to_owned()))
+            }
+            Some(SettingValue::Text(_)) | None => Ok(None),
+            Some(_) => bail!(
+                "setting `{}` must be a theme name; open Settings and pick a theme",
+                SettingKey::UiTheme.as_str()
+            ),
+        }
+    }
+
+    pub fn put_theme(&self, name: &str) -> Result<()> {
+        self.put_setting(SettingKey::UiTheme, SettingValue::Text(name.to_owned()))
+    }
+
+    /// What `event` does beyond its status line entry.
+    pub fn get_alert_style(&self, event: AlertEvent) -> Result<AlertStyle> {
+        let key = event.setting();
+        match self.get_setting(key)? {
+            Some(SettingValue::Text(value)) => AlertStyle::parse(&value).ok_or_else(|| {
+                anyhow!(
+                    "setting `{}` is `{value}`; open Settings and pick off, bell, flash, or both",
+                    key.as_str()
+                )
+            }),
+            Some(_) => bail!(
+                "setting `{}` must be off, bell, flash, or both; open Settings and pick one",
+                key.as_str()
+            ),
+            None => Ok(AlertStyle::default()),
+        }
+    }
+
+    pub fn put_alert_style(&self, event: AlertEvent, style: AlertStyle) -> Result<()> {
+        self.put_setting(
+            event.setting(),
+            SettingValue::Text(style.as_str().to_owned()),
+        )
+    }
+
+    /// Whether reminders also go out as desktop notifications.
+    pub fn get_desktop_notifications(&self) -> Result<bool> {
+        match self.get_setting(SettingKey::NotifyDesktop)? {
+            Some(SettingValue::Bool(value)) => Ok(value),
+            Some(_) => bail!(
+                "setting `{}` must be on/off; open Settings and toggle it",
+                SettingKey::NotifyDesktop.as_str()
+            ),
+            None => Ok(true),
+        }
+    }
+
+    /// Whether startup, shutdown, and timed backups are on.
+    pub fn get_auto_backups(&self) -> Result<bool> {
+        match self.get_setting(SettingKey::BackupAuto)? {
+            Some(SettingValue::Bool(value)) => Ok(value),
+            Some(_) => bail!(
+                "setting `{}` must be on/off; open Settings and toggle it",
+                SettingKey::BackupAuto.as_str()
+            ),
+            None => Ok(true),
+        }
+    }
+
+    /// How many days ahead of a date the reminder for `key` starts.
+    pub fn get_notice_days(&self, key: SettingKey) -> Result<i64> {
+        if key.expected_value_kind() != SettingValueKind::Days {
+            bail!("setting `{}` is not a notice lead time", key.as_str());
+        }
+        match self.get_setting(key)? {
+            Some(SettingValue::Days(days)) => Ok(days),
+            Some(_) => bail!(
+                "setting `{}` must be a number of days; open Settings and pick one",
+                key.as_str()
+            ),
+            None => Ok(DEFAULT_NOTICE_DAYS),
+        }
+    }
+
+    /// Where the iCalendar feed is written; `None` means the feed is off.
+    pub fn get_ical_path(&self) -> Result<Option<PathBuf>> {
+        match self.get_setting(SettingKey::IcalPath)? {
+            Some(SettingValue::Text(value)) if !value.trim().is_empty() => {
+                Ok(Some(PathBuf::from(value.trim())))
+            }
+            Some(SettingValue::Text(_)) | None => Ok(None),
+            Some(_) => bail!(
+                "setting `{}` must be a file path; set it with `micasa --set-ical-path PATH`",
+                SettingKey::IcalPath.as_str()
+            ),
+        }
+    }
+
+    pub fn put_ical_path(&self, path: Option<&Path>) -> Result<()> {
+        let value = path
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        self.put_setting(SettingKey::IcalPath, SettingValue::Text(value))
+    }
+
+    /// Archives or unarchives a row. Archived rows stay live (not deleted) but
+    /// drop out of default views and dashboard counts.
+    pub fn set_row_archived(&self, target: LifecycleEntityRef, archived: bool) -> Result<()> {
+        let kind = target.kind();
+        let deleted: Option<bool> = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT deleted_at IS NOT NULL FROM {} WHERE id = ?",
+                    kind.table()
+                ),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("look up {} {}", kind.deleted_tag(), target.id()))?;
+        match deleted {
+            None => bail!(
+                "{} {} not found -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            ),
+            Some(true) if archived => bail!(
+                "{} {} is deleted -- restore it before archiving",
+                kind.deleted_tag(),
+                target.id()
+            ),
+            Some(_) => {}
+        }
+
+        if archived {
+            self.conn
+                .execute(
+                    "INSERT OR IGNORE INTO archived_rows (entity, target_id, archived_at) VALUES (?, ?, ?)",
+                    params![kind.deleted_tag(), target.id(), now_rfc3339()?],
+                )
+                .with_context(|| format!("archive {} {}", kind.deleted_tag(), target.id()))?;
+        } else {
+            self.conn
+                .execute(
+                    "DELETE FROM archived_rows WHERE entity = ? AND target_id = ?",
+                    params![kind.deleted_tag(), target.id()],
+                )
+                .with_context(|| format!("unarchive {} {}", kind.deleted_tag(), target.id()))?;
+        }
+        Ok(())
+    }
+
+    /// Archives every live project that is completed or abandoned and not
+    /// archived already; returns how many it archived.
+    pub fn archive_settled_projects(&self) -> Result<usize> {
+        self.conn
+            .execute(
+                "INSERT OR IGNORE INTO archived_rows (entity, target_id, archived_at)
+                 SELECT ?, id, ? FROM projects
+                 WHERE deleted_at IS NULL AND status IN (?, ?)",
+                params![
+                    DeletionEntity::Project.as_str(),
+                    now_rfc3339()?,
+                    ProjectStatus::Completed.as_str(),
+                    ProjectStatus::Abandoned.as_str()
+                ],
+            )
+            .context("archive settled projects")
+    }
+
+    pub fn is_row_archived(&self, target: LifecycleEntityRef) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM archived_rows WHERE entity = ? AND target_id = ?)",
+                params![target.kind().deleted_tag(), target.id()],
+                |row| row.get(0),
+            )
+            .context("check archived flag")
+    }
+
+    pub fn archived_row_ids(&self, entity: DeletionEntity) -> Result<BTreeSet<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT target_id FROM archived_rows WHERE entity = ? ORDER BY target_id")
+            .context("prepare archived rows query")?;
+        let ids = stmt
+            .query_map(params![entity.as_str()], |row| row.get::<_, i64>(0))
+            .context("query archived rows")?
+            .collect::<rusqlite::Result<BTreeSet<_>>>()
+            .context("scan archived rows")?;
+        Ok(ids)
+    }
+
+    pub fn set_row_private(&self, target: LifecycleEntityRef, private: bool) -> Result<()> {
+        let kind = target.kind();
+        let exists: bool = self
+            .conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", kind.table()),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .with_context(|| format!("look up {} {}", kind.deleted_tag(), target.id()))?;
+        if !exists {
+            bail!(
+                "{} {} not found -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            );
+        }
+
+        if private {
+            self.conn
+                .execute(
+                    "INSERT OR IGNORE INTO private_rows (entity, target_id, created_at) VALUES (?, ?, ?)",
+                    params![kind.deleted_tag(), target.id(), now_rfc3339()?],
+                )
+                .with_context(|| format!("mark {} {} private", kind.deleted_tag(), target.id()))?;
+        } else {
+            self.conn
+                .execute(
+                    "DELETE FROM private_rows WHERE entity = ? AND target_id = ?",
+                    params![kind.deleted_tag(), target.id()],
+                )
+                .with_context(|| {
+                    format!(
+                        "clear private flag on {} {}",
+                        kind.deleted_tag(),
+                        target.id()
+                    )
+                })?;
+        }
+        Ok(())
+    }
+
+    pub fn is_row_private(&self, target: LifecycleEntityRef) -> Result<bool> {
+        self.conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM private_rows WHERE entity = ? AND target_id = ?)",
+                params![target.kind().deleted_tag(), target.id()],
+                |row| row.get(0),
+            )
+            .context("check private flag")
+    }
+
+    pub fn private_row_ids(&self, entity: DeletionEntity) -> Result<BTreeSet<i64>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT target_id FROM private_rows WHERE entity = ? ORDER BY target_id")
+            .context("prepare private rows query")?;
+        let ids = stmt
+            .query_map(params![entity.as_str()], |row| row.get::<_, i64>(0))
+            .context("query private rows")?
+            .collect::<rusqlite::Result<BTreeSet<_>>>()
+            .context("scan private rows")?;
+        Ok(ids)
+    }
+
+    /// Name per row id for the tabs other rows link to: project titles,
+    /// vendor, maintenance item and appliance names. Deleted rows are kept,
+    /// so a link to one still reads.
+    pub fn row_names(&self, tab: TabKind) -> Result<BTreeMap<i64, String>> {
+        let (table, column) = named_table(tab)?;
+        let mut stmt = self
+            .conn
+            .prepare(&format!("SELECT id, {column} FROM {table}"))
+            .with_context(|| format!("prepare {table} names query"))?;
+        let names = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .with_context(|| format!("query {table} names"))?
+            .collect::<rusqlite::Result<BTreeMap<_, _>>>()
+            .with_context(|| format!("scan {table} names"))?;
+        Ok(names)
+    }
+
+    /// Live rows of `tab` whose name contains `query`, ignoring case, as
+    /// `(id, name)`. Names starting with `query` come first, then A to Z.
+    pub fn search_row_names(
+        &self,
+        tab: TabKind,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<(i64, String)>> {
+        let (table, column) = named_table(tab)?;
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "
+                SELECT id, {column}
+                FROM {table}
+                WHERE deleted_at IS NULL
+                  AND instr(LOWER({column}), LOWER(?1)) > 0
+                ORDER BY instr(LOWER({column}), LOWER(?1)) <> 1, {column} COLLATE NOCASE, id
+                LIMIT ?2
+                "
+            ))
+            .with_context(|| format!("prepare {table} name search"))?;
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        stmt.query_map(params![query.trim(), limit], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .with_context(|| format!("search {table} names"))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .with_context(|| format!("scan {table} names"))
+    }
+
+    /// Tag names per row id for one kind of row, each list sorted.
+    pub fn tags_by_row(&self, entity: DeletionEntity) -> Result<BTreeMap<i64, Vec<String>>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT entity_tags.target_id, tags.name
+                FROM entity_tags
+                JOIN tags ON tags.id = entity_tags.tag_id
+                WHERE entity_tags.entity = ?
+                ORDER BY entity_tags.target_id, tags.name
+                ",
+            )
+            .context("prepare row tags query")?;
+        let rows = stmt
+            .query_map(params![entity.as_str()], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .context("query row tags")?;
+        let mut tags = BTreeMap::<i64, Vec<String>>::new();
+        for row in rows {
+            let (target_id, name) = row.context("scan row tag")?;
+            tags.entry(target_id).or_default().push(name);
+        }
+        Ok(tags)
+    }
+
+    /// Replaces the tags on one project, incident, appliance, vendor or
+    /// document. Names should come from `micasa_app::parse_tags`; tags no row
+    /// uses any more are dropped.
+    pub fn set_tags(&self, target: LifecycleEntityRef, names: &[String]) -> Result<()> {
+        let kind = target.kind();
+        if !matches!(
+            kind,
+            EntityKind::Project
+                | EntityKind::Incident
+                | EntityKind::Appliance
+                | EntityKind::Vendor
+                | EntityKind::Document
+        ) {
+            bail!(
+                "{} rows can't be tagged -- tag a project, incident, appliance, vendor or document",
+                kind.deleted_tag()
+            );
+        }
+        let exists: bool = self
+            .conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", kind.table()),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .with_context(|| format!("look up {} {}", kind.deleted_tag(), target.id()))?;
+        if !exists {
+            bail!(
+                "{} {} not found -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            );
+        }
+
+        let tx = WriteBatch::begin(&self.conn).context("begin tag update")?;
+        tx.execute(
+            "DELETE FROM entity_tags WHERE entity = ? AND target_id = ?",
+            params![kind.deleted_tag(), target.id()],
+        )
+        .context("clear row tags")?;
+        let now = now_rfc3339()?;
+        for name in names {
+            tx.execute(
+                "INSERT OR IGNORE INTO tags (name, created_at) VALUES (?, ?)",
+                params![name, now],
+            )
+            .with_context(|| format!("create tag {name}"))?;
+            tx.execute(
+                "
+                INSERT OR IGNORE INTO entity_tags (entity, target_id, tag_id)
+                SELECT ?, ?, id FROM tags WHERE name = ?
+                ",
+                params![kind.deleted_tag(), target.id(), name],
+            )
+            .with_context(|| format!("tag {} {} with {name}", kind.deleted_tag(), target.id()))?;
+        }
+        tx.execute(
+            "DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM entity_tags)",
+            [],
+        )
+        .context("drop unused tags")?;
+        tx.commit().context("commit tag update")
+    }
+
+    /// Every tag in use, by name, with how many rows carry it.
+    pub fn list_tags(&self) -> Result<Vec<Tag>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT tags.id, tags.name, COUNT(entity_tags.tag_id)
+                FROM tags
+                LEFT JOIN entity_tags ON entity_tags.tag_id = tags.id
+                GROUP BY tags.id
+                ORDER BY tags.name
+                ",
+            )
+            .context("prepare tags query")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(Tag {
+                    id: TagId::new(row.get(0)?),
+                    name: row.get(1)?,
+                    uses: row.get::<_, i64>(2)? as usize,
+                })
+            })
+            .context("query tags")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect tags")
+    }
+
+    /// Shadows every entity table on this connection with a temp view that
+    /// drops private rows, so ad-hoc SQL (chat, data dumps) never sees them.
+    /// Pair with `clear_private_row_shadows` on a shared connection. The
+    /// OFFSET keeps SQLite from flattening a view into the outer query, which
+    /// would make its table look like a direct read to the query guard.
+    pub fn shadow_private_rows(&self) -> Result<()> {
+        for kind in EntityKind::ALL {
+            self.conn
+                .execute_batch(&format!(
+                    "
+                    DROP VIEW IF EXISTS temp.{table};
+                    CREATE TEMP VIEW {table} AS
+                      SELECT * FROM main.{table}
+                      WHERE id NOT IN (
+                        SELECT target_id FROM main.private_rows WHERE entity = '{tag}'
+                      )
+                      LIMIT -1 OFFSET 0;
+                    ",
+                    table = kind.table(),
+                    tag = kind.deleted_tag(),
+                ))
+                .with_context(|| format!("hide private rows in {}", kind.table()))?;
+        }
+        self.private_shadowed.set(true);
+        Ok(())
+    }
+
+    pub fn clear_private_row_shadows(&self) -> Result<()> {
+        for kind in EntityKind::ALL {
+            self.conn
+                .execute_batch(&format!("DROP VIEW IF EXISTS temp.{};", kind.table()))
+                .with_context(|| format!("restore {} view", kind.table()))?;
+        }
+        self.private_shadowed.set(false);
+        Ok(())
+    }
+
+    /// Current cost of a row that can carry cost splits (service log entries
+    /// and incidents). Missing costs count as zero.
+    pub fn cost_split_total_cents(&self, target: LifecycleEntityRef) -> Result<i64> {
+        if !matches!(
+            target,
+            LifecycleEntityRef::ServiceLogEntry(_) | LifecycleEntityRef::Incident(_)
+        ) {
+            bail!("cost splits apply to service log entries and incidents only");
+        }
+        let kind = target.kind();
+        let cost: Option<Option<i64>> = self
+            .conn
+            .query_row(
+                &format!(
+                    "SELECT cost_cents FROM {} WHERE id = ? AND deleted_at IS NULL",
+                    kind.table()
+                ),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("look up {} {} cost", kind.deleted_tag(), target.id()))?;
+        let Some(cost) = cost else {
+            bail!(
+                "{} {} not found or deleted -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            );
+        };
+        Ok(cost.unwrap_or(0))
+    }
+
+    /// Replaces the row's splits. They must add up to its cost exactly; an
+    /// empty slice removes them.
+    pub fn set_cost_splits(&self, target: LifecycleEntityRef, splits: &[CostSplit]) -> Result<()> {
+        let total_cents = self.cost_split_total_cents(target)?;
+        if !splits.is_empty() {
+            let mut seen = BTreeSet::new();
+            for split in splits {
+                if split.amount_cents <= 0 {
+                    bail!("each split needs a positive amount");
+                }
+                if !seen.insert(split.category_id.get()) {
+                    bail!(
+                        "category {} appears twice; combine its shares into one split",
+                        split.category_id.get()
+                    );
+                }
+            }
+            let sum = splits.iter().map(|split| split.amount_cents).sum::<i64>();
+            if sum != total_cents {
+                bail!(
+                    "splits add up to {} but the cost is {}; adjust them to match",
+                    validation::format_cents(sum),
+                    validation::format_cents(total_cents)
+                );
+            }
+        }
+
+        let entity = target.kind().deleted_tag();
+        let tx = WriteBatch::begin(&self.conn).context("begin cost split update")?;
+        tx.execute(
+            "DELETE FROM cost_splits WHERE entity = ? AND target_id = ?",
+            params![entity, target.id()],
+        )
+        .context("clear cost splits")?;
+        for split in splits {
+            tx.execute(
+                "INSERT INTO cost_splits (entity, target_id, category_id, amount_cents) VALUES (?, ?, ?, ?)",
+                params![entity, target.id(), split.category_id.get(), split.amount_cents],
+            )
+            .with_context(|| {
+                format!(
+                    "save split for category {} -- pick an existing maintenance category",
+                    split.category_id.get()
+                )
+            })?;
+        }
+        tx.commit().context("commit cost splits")
+    }
+
+    pub fn list_cost_splits(&self, target: LifecycleEntityRef) -> Result<Vec<CostSplit>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT category_id, amount_cents
+                FROM cost_splits
+                WHERE entity = ? AND target_id = ?
+                ORDER BY amount_cents DESC, category_id DESC
+                ",
+            )
+            .context("prepare cost splits query")?;
+        let rows = stmt
+            .query_map(params![target.kind().deleted_tag(), target.id()], |row| {
+                Ok(CostSplit {
+                    category_id: MaintenanceCategoryId::new(row.get(0)?),
+                    amount_cents: row.get(1)?,
+                })
+            })
+            .context("query cost splits")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect cost splits")
+    }
+
+    /// Service-log and incident spend in `start <= date < end` per category.
+    /// Split rows count by their splits; unsplit service costs fall back to
+    /// the maintenance item's category and unsplit incident costs to `None`.
+    pub fn category_cost_totals(&self, start: Date, end: Date) -> Result<Vec<CategoryCostTotal>> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "
+                WITH spend(category_id, amount_cents) AS (
+                  SELECT COALESCE(cs.category_id, mi.category_id), {service_amount}
+                  FROM service_log_entries sle
+                  JOIN maintenance_items mi ON mi.id = sle.maintenance_item_id
+                  LEFT JOIN cost_splits cs
+                    ON cs.entity = 'service_log' AND cs.target_id = sle.id
+                  WHERE sle.deleted_at IS NULL
+                    AND sle.cost_cents IS NOT NULL
+                    AND sle.serviced_at >= ?1 AND sle.serviced_at < ?2
+                  UNION ALL
+                  SELECT cs.category_id, {incident_amount}
+                  FROM incidents i
+                  LEFT JOIN cost_splits cs
+                    ON cs.entity = 'incident' AND cs.target_id = i.id
+                  WHERE i.deleted_at IS NULL
+                    AND i.cost_cents IS NOT NULL
+                    AND i.date_noticed >= ?1 AND i.date_noticed < ?2
+                )
+                SELECT spend.category_id, mc.name, SUM(spend.amount_cents) AS total
+                FROM spend
+                LEFT JOIN maintenance_categories mc ON mc.id = spend.category_id
+                GROUP BY spend.category_id
+                ORDER BY total DESC, spend.category_id DESC
+                ",
+                service_amount = home_cents_sql(
+                    EntityKind::ServiceLogEntry,
+                    "sle",
+                    "cost_cents",
+                    "COALESCE(cs.amount_cents, sle.cost_cents)"
+                ),
+                incident_amount = home_cents_sql(
+                    EntityKind::Incident,
+                    "i",
+                    "cost_cents",
+                    "COALESCE(cs.amount_cents, i.cost_cents)"
+                ),
+            ))
+            .context("prepare category cost totals query")?;
+        let rows = stmt
+            .query_map(params![format_date(start), format_date(end)], |row| {
+                let category_id: Option<i64> = row.get(0)?;
+                let name: Option<String> = row.get(1)?;
+                Ok(CategoryCostTotal {
+                    category_id: category_id.map(MaintenanceCategoryId::new),
+                    category_name: name.unwrap_or_else(|| "uncategorized".to_owned()),
+                    total_cents: row.get(2)?,
+                })
+            })
+            .context("query category cost totals")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect category cost totals")
+    }
+
+    /// Spend in `start <= date < end` for every budget category that has any.
+    /// Maintenance categories take service-log and incident costs as in
+    /// `category_cost_totals`. Project types take each project's actual cost
+    /// on its end (or start) date, or, while no actual is recorded, its
+    /// accepted quotes on the day they were accepted.
+    pub fn budget_spend_cents(
+        &self,
+        start: Date,
+        end: Date,
+    ) -> Result<BTreeMap<BudgetCategory, i64>> {
+        let mut spend = self
+            .category_cost_totals(start, end)?
+            .into_iter()
+            .filter_map(|total| {
+                total
+                    .category_id
+                    .map(|id| (BudgetCategory::Maintenance(id), total.total_cents))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "
+                WITH spend(project_type_id, amount_cents, spent_on) AS (
+                  SELECT p.project_type_id, {actual_amount}, COALESCE(p.end_date, p.start_date)
+                  FROM projects p
+                  WHERE p.deleted_at IS NULL AND p.actual_cents IS NOT NULL
+                  UNION ALL
+                  SELECT p.project_type_id, {quote_amount}, substr(q.accepted_at, 1, 10)
+                  FROM quotes q
+                  JOIN projects p ON p.id = q.project_id
+                  WHERE q.deleted_at IS NULL
+                    AND q.accepted_at IS NOT NULL
+                    AND p.deleted_at IS NULL
+                    AND p.actual_cents IS NULL
+                )
+                SELECT project_type_id, SUM(amount_cents)
+                FROM spend
+                WHERE spent_on >= ?1 AND spent_on < ?2
+                GROUP BY project_type_id
+                ",
+                actual_amount =
+                    home_cents_sql(EntityKind::Project, "p", "actual_cents", "p.actual_cents"),
+                quote_amount =
+                    home_cents_sql(EntityKind::Quote, "q", "total_cents", "q.total_cents"),
+            ))
+            .context("prepare project spend query")?;
+        let rows = stmt
+            .query_map(params![format_date(start), format_date(end)], |row| {
+                Ok((ProjectTypeId::new(row.get(0)?), row.get::<_, i64>(1)?))
+            })
+            .context("query project spend")?;
+        for row in rows {
+            let (project_type_id, cents) = row.context("scan project spend")?;
+            spend.insert(BudgetCategory::ProjectType(project_type_id), cents);
+        }
+        Ok(spend)
+    }
+
+    /// Drops splits that no longer add up to the row's cost after an edit.
+    fn clear_stale_cost_splits(&self, target: LifecycleEntityRef) -> Result<()> {
+        self.conn
+            .execute(
+                &format!(
+                    "
+                    DELETE FROM cost_splits
+                    WHERE entity = ?1 AND target_id = ?2
+                      AND (SELECT SUM(amount_cents) FROM cost_splits WHERE entity = ?1 AND target_id = ?2)
+                          IS NOT (SELECT cost_cents FROM {} WHERE id = ?2)
+                    ",
+                    target.kind().table()
+                ),
+                params![target.kind().deleted_tag(), target.id()],
+            )
+            .context("clear stale cost splits")?;
+        Ok(())
+    }
+
+    /// Inserts or replaces the rate for `code` (home units per foreign unit,
+    /// scaled by `currency::RATE_SCALE`).
+    pub fn set_currency_rate(
+        &self,
+        code: &str,
+        home_per_unit_micros: i64,
+        source: RateSource,
+    ) -> Result<()> {
+        let code = currency::parse_currency_code(code)?;
+        if home_per_unit_micros <= 0 {
+            bail!("rate for {code} must be greater than zero");
+        }
+        self.conn
+            .execute(
+                "
+                INSERT INTO currency_rates (code, home_per_unit_micros, source, updated_at)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (code) DO UPDATE SET
+                  home_per_unit_micros = excluded.home_per_unit_micros,
+                  source = excluded.source,
+                  updated_at = excluded.updated_at
+                ",
+                params![code, home_per_unit_micros, source.as_str(), now_rfc3339()?],
+            )
+            .with_context(|| format!("save {code} rate"))?;
+        Ok(())
+    }
+
+    pub fn list_currency_rates(&self) -> Result<Vec<CurrencyRate>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT code, home_per_unit_micros, source, updated_at
+                FROM currency_rates
+                ORDER BY code ASC
+                ",
+            )
+            .context("prepare currency rates query")?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            })
+            .context("query currency rates")?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect currency rates")?;
+        rows.into_iter()
+            .map(|(code, home_per_unit_micros, source, updated_at)| {
+                Ok(CurrencyRate {
+                    code,
+                    home_per_unit_micros,
+                    source: if source == RateSource::Fetched.as_str() {
+                        RateSource::Fetched
+                    } else {
+                        RateSource::Manual
+                    },
+                    updated_at: parse_datetime(&updated_at)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Tags one money field of a row with a foreign currency; `None` puts it
+    /// back in the home currency. The currency needs a rate first.
+    /// Replaces the cached forecast with freshly fetched days.
+    pub fn replace_weather_forecast(&self, days: &[weather::ForecastDay]) -> Result<()> {
+        let now = now_rfc3339()?;
+        let tx = WriteBatch::begin(&self.conn).context("begin weather forecast update")?;
+        tx.execute("DELETE FROM weather_forecast_days", [])
+            .context("clear weather forecast")?;
+        for day in days {
+            tx.execute(
+                "
+                INSERT INTO weather_forecast_days (
+                  day, min_temp_c, max_temp_c, precipitation_mm, wind_kmh, fetched_at
+                ) VALUES (?, ?, ?, ?, ?, ?)
+                ",
+                params![
+                    format_date(day.day),
+                    day.min_temp_c,
+                    day.max_temp_c,
+                    day.precipitation_mm,
+                    day.wind_kmh,
+                    now,
+                ],
+            )
+            .context("insert weather forecast day")?;
+        }
+        tx.commit().context("commit weather forecast")
+    }
+
+    /// Cached forecast days on or after `from`, earliest first.
+    pub fn weather_forecast(&self, from: Date) -> Result<Vec<weather::ForecastDay>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT day, min_temp_c, max_temp_c, precipitation_mm, wind_kmh
+                FROM weather_forecast_days
+                WHERE day >= ?
+                ORDER BY day ASC
+                ",
+            )
+            .context("prepare weather forecast query")?;
+        let rows = stmt
+            .query_map(params![format_date(from)], |row| {
+                let day_raw: String = row.get(0)?;
+                Ok(weather::ForecastDay {
+                    day: parse_date(&day_raw).map_err(to_sql_error)?,
+                    min_temp_c: row.get(1)?,
+                    max_temp_c: row.get(2)?,
+                    precipitation_mm: row.get(3)?,
+                    wind_kmh: row.get(4)?,
+                })
+            })
+            .context("query weather forecast")?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+            .context("collect weather forecast")
+    }
+
+    pub fn weather_fetched_at(&self) -> Result<Option<OffsetDateTime>> {
+        let raw: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT MAX(fetched_at) FROM weather_forecast_days",
+                [],
+                |row| row.get(0),
+            )
+            .context("load weather fetch time")?;
+        raw.map(|raw| parse_datetime(&raw)).transpose()
+    }
+
+    /// Updates one column of a live row from its typed text. Money accepts
+    /// `1,234.56` style input and dates use YYYY-MM-DD; empty clears optional
+    /// fields.
+    pub fn update_field(&self, target: LifecycleEntityRef, field: &str, raw: &str) -> Result<()> {
+        let kind = target.kind();
+        let Some(spec) = kind
+            .editable_fields()
+            .iter()
+            .find(|spec| spec.name == field)
+        else {
+            bail!(
+                "{} field `{field}` can't be edited inline; pick one of: {}",
+                kind.deleted_tag(),
+                kind.editable_fields()
+                    .iter()
+                    .map(|spec| spec.name)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        };
+        let label = field.trim_end_matches("_cents").replace('_', " ");
+        let trimmed = raw.trim();
+        if spec.required && trimmed.is_empty() {
+            bail!("{label} is required -- enter a value and retry");
+        }
+
+        let value = match spec.kind {
+            EditableFieldKind::Text => rusqlite::types::Value::Text(trimmed.to_owned()),
+            EditableFieldKind::Money => {
+                match validation::parse_optional_cents(trimmed)
+                    .map_err(|error| anyhow!("{label}: {error}; use a form like 1234.56"))?
+                {
+                    Some(0) if spec.required => bail!("{label} must be positive"),
+                    Some(cents) => rusqlite::types::Value::Integer(cents),
+                    None => rusqlite::types::Value::Null,
+                }
+            }
+            EditableFieldKind::Date => {
+                let date = validation::parse_optional_date(trimmed).map_err(|error| {
+                    anyhow!("{label}: {error}; use {}", validation::DATE_LAYOUT)
+                })?;
+                if let Some(date) = date {
+                    self.check_incident_date_order(target, field, date)?;
+                }
+                date.map_or(rusqlite::types::Value::Null, |date| {
+                    rusqlite::types::Value::Text(format_date(date))
+                })
+            }
+            EditableFieldKind::ProjectRef => {
+                if trimmed.is_empty() {
+                    rusqlite::types::Value::Null
+                } else {
+                    let parent_id = trimmed.parse::<i64>().map_err(|_| {
+                        anyhow!("{label}: expected a project id like 12, or blank to clear")
+                    })?;
+                    self.check_project_parent(
+                        Some(ProjectId::new(target.id())),
+                        ProjectId::new(parent_id),
+                    )?;
+                    rusqlite::types::Value::Integer(parent_id)
+                }
+            }
+            EditableFieldKind::DocumentRef => {
+                if trimmed.is_empty() {
+                    rusqlite::types::Value::Null
+                } else {
+                    let document_id = trimmed.parse::<i64>().map_err(|_| {
+                        anyhow!("{label}: expected a document id like 12, or blank to clear")
+                    })?;
+                    self.require_parent_alive(ParentEntityRef::Document(DocumentId::new(
+                        document_id,
+                    )))?;
+                    rusqlite::types::Value::Integer(document_id)
+                }
+            }
+        };
+
+        let rows_affected = self
+            .conn
+            .execute(
+                &format!(
+                    "UPDATE {} SET {field} = ?, updated_at = ? WHERE id = ? AND deleted_at IS NULL",
+                    kind.table()
+                ),
+                params![value, now_rfc3339()?, target.id()],
+            )
+            .with_context(|| format!("update {} {label}", kind.deleted_tag()))?;
+        if rows_affected == 0 {
+            bail!(
+                "{} {} not found or deleted -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            );
+        }
+        Ok(())
+    }
+
+    /// Current value of an inline-editable field as `update_field` accepts it.
+    pub fn field_text(&self, target: LifecycleEntityRef, field: &str) -> Result<String> {
+        let kind = target.kind();
+        let Some(spec) = kind
+            .editable_fields()
+            .iter()
+            .find(|spec| spec.name == field)
+        else {
+            bail!(
+                "{} field `{field}` can't be edited inline",
+                kind.deleted_tag()
+            );
+        };
+        let value: rusqlite::types::Value = self
+            .conn
+            .query_row(
+                &format!("SELECT {} FROM {} WHERE id = ?", spec.name, kind.table()),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .optional()
+            .with_context(|| format!("look up {} {}", kind.deleted_tag(), target.id()))?
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} {} not found -- refresh the view and pick an existing row",
+                    kind.deleted_tag(),
+                    target.id()
+                )
+            })?;
+        Ok(match value {
+            rusqlite::types::Value::Integer(cents) if spec.kind == EditableFieldKind::Money => {
+                format!("{}.{:02}", cents / 100, cents % 100)
+            }
+            rusqlite::types::Value::Integer(value) => value.to_string(),
+            rusqlite::types::Value::Text(text) => text,
+            _ => String::new(),
+        })
     }
 
-    pub fn put_last_model(&self, model: &str) -> Result<()> {
-        self.put_setting(SettingKey::LlmModel, SettingValue::Text(model.to_owned()))
+    fn check_incident_date_order(
+        &self,
+        target: LifecycleEntityRef,
+        field: &str,
+        date: Date,
+    ) -> Result<()> {
+        let LifecycleEntityRef::Incident(incident_id) = target else {
+            return Ok(());
+        };
+        let other_field = match field {
+            "date_noticed" => "date_resolved",
+            "date_resolved" => "date_noticed",
+            _ => return Ok(()),
+        };
+        let other: Option<String> = self
+            .conn
+            .query_row(
+                &format!("SELECT {other_field} FROM incidents WHERE id = ?"),
+                params![incident_id.get()],
+                |row| row.get(0),
+            )
+            .optional()
+            .context("look up incident dates")?
+            .flatten();
+        let Some(other) = other.map(|raw| parse_date(&raw)).transpose()? else {
+            return Ok(());
+        };
+        let (noticed, resolved) = if field == "date_noticed" {
+            (date, other)
+        } else {
+            (other, date)
+        };
+        if resolved < noticed {
+            bail!("incident resolved date must be on/after date noticed");
+        }
+        Ok(())
     }
 
-    pub fn get_show_dashboard(&self) -> Result<bool> {
-        match self.get_setting(SettingKey::UiShowDashboard)? {
-            Some(SettingValue::Bool(value)) => Ok(value),
-            Some(SettingValue::Text(_)) => bail!(
-                "setting `{}` must be on/off; open Settings and toggle it",
-                SettingKey::UiShowDashboard.as_str()
-            ),
-            None => Ok(true),
+    pub fn set_money_currency(
+        &self,
+        target: LifecycleEntityRef,
+        field: &str,
+        code: Option<&str>,
+    ) -> Result<()> {
+        let kind = target.kind();
+        if !kind.money_fields().contains(&field) {
+            bail!(
+                "{} has no money field `{field}`; pick one of: {}",
+                kind.deleted_tag(),
+                kind.money_fields().join(", ")
+            );
+        }
+        let exists: bool = self
+            .conn
+            .query_row(
+                &format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", kind.table()),
+                params![target.id()],
+                |row| row.get(0),
+            )
+            .with_context(|| format!("look up {} {}", kind.deleted_tag(), target.id()))?;
+        if !exists {
+            bail!(
+                "{} {} not found -- refresh the view and pick an existing row",
+                kind.deleted_tag(),
+                target.id()
+            );
         }
-    }
 
-    pub fn get_show_dashboard_override(&self) -> Result<Option<bool>> {
-        match self.get_setting(SettingKey::UiShowDashboard)? {
-            Some(SettingValue::Bool(value)) => Ok(Some(value)),
-            Some(SettingValue::Text(_)) => bail!(
-                "setting `{}` must be on/off; open Settings and toggle it",
-                SettingKey::UiShowDashboard.as_str()
-            ),
-            None => Ok(None),
+        let Some(code) = code else {
+            self.conn
+                .execute(
+                    "DELETE FROM money_currencies WHERE entity = ? AND target_id = ? AND field = ?",
+                    params![kind.deleted_tag(), target.id(), field],
+                )
+                .context("clear money currency")?;
+            return Ok(());
+        };
+        let code = currency::parse_currency_code(code)?;
+        let has_rate: bool = self
+            .conn
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM currency_rates WHERE code = ?)",
+                params![code],
+                |row| row.get(0),
+            )
+            .context("look up currency rate")?;
+        if !has_rate {
+            bail!("no rate for {code}; add one with `micasa --set-rate {code}=<rate>` first");
         }
+        self.conn
+            .execute(
+                "
+                INSERT INTO money_currencies (entity, target_id, field, currency_code)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT (entity, target_id, field) DO UPDATE SET
+                  currency_code = excluded.currency_code
+                ",
+                params![kind.deleted_tag(), target.id(), field, code],
+            )
+            .context("save money currency")?;
+        Ok(())
     }
 
-    pub fn put_show_dashboard(&self, show: bool) -> Result<()> {
-        self.put_setting(SettingKey::UiShowDashboard, SettingValue::Bool(show))
+    /// Foreign currency codes for `entity`, keyed by `(row id, field)`.
+    pub fn money_currencies(
+        &self,
+        entity: DeletionEntity,
+    ) -> Result<BTreeMap<(i64, String), String>> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "
+                SELECT target_id, field, currency_code
+                FROM money_currencies
+                WHERE entity = ?
+                ORDER BY target_id, field
+                ",
+            )
+            .context("prepare money currencies query")?;
+        let rows = stmt
+            .query_map(params![entity.as_str()], |row| {
+                Ok((
+                    (row.get::<_, i64>(0)?, row.get::<_, String>(1)?),
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .context("query money currencies")?
+            .collect::<rusqlite::Result<BTreeMap<_, _>>>()
+            .context("collect money currencies")?;
+        Ok(rows)
     }
 
     pub fn append_chat_input(&self, input: &str) -> Result<()> {
@@ -3860,6 +8717,18 @@ impl Store {
                         project_id.get()
                     );
                 }
+                let subproject_count = self
+                    .count_active_dependents(
+                        DependentRelation::ProjectSubprojects,
+                        project_id.get(),
+                    )
+                    .context("count sub-projects of project")?;
+                if subproject_count > 0 {
+                    bail!(
+                        "cannot delete project {} because it has {subproject_count} sub-project(s); delete them or clear their parent first",
+                        project_id.get()
+                    );
+                }
             }
             LifecycleEntityRef::Vendor(vendor_id) => {
                 let quote_count = self
@@ -3936,16 +8805,43 @@ impl Store {
                     );
                 }
             }
+            LifecycleEntityRef::Document(document_id) => {
+                let vendor_count = self
+                    .count_active_dependents(
+                        DependentRelation::DocumentVendorCertificates,
+                        document_id.get(),
+                    )
+                    .context("count vendors using document as insurance certificate")?;
+                if vendor_count > 0 {
+                    bail!(
+                        "document {} is the insurance certificate of {vendor_count} vendor(s) -- clear their cert first",
+                        document_id.get()
+                    );
+                }
+            }
             LifecycleEntityRef::Quote(_)
             | LifecycleEntityRef::ServiceLogEntry(_)
             | LifecycleEntityRef::Incident(_)
-            | LifecycleEntityRef::Document(_) => {}
+            | LifecycleEntityRef::Budget(_) => {}
         }
         Ok(())
     }
 
     fn ensure_can_restore(&self, target: LifecycleEntityRef) -> Result<()> {
         match target {
+            LifecycleEntityRef::Project(project_id) => {
+                let parent_id: Option<i64> = self
+                    .conn
+                    .query_row(
+                        "SELECT parent_project_id FROM projects WHERE id = ?",
+                        params![project_id.get()],
+                        |row| row.get(0),
+                    )
+                    .with_context(|| format!("load project {}", project_id.get()))?;
+                if let Some(parent_id) = parent_id {
+                    self.require_parent_alive(ParentEntityRef::Project(ProjectId::new(parent_id)))?;
+                }
+            }
             LifecycleEntityRef::Quote(quote_id) => {
                 let (project_id, vendor_id): (i64, i64) = self
                     .conn
@@ -4047,9 +8943,40 @@ impl Store {
                     }
                 }
             }
-            LifecycleEntityRef::Project(_)
-            | LifecycleEntityRef::Vendor(_)
-            | LifecycleEntityRef::Appliance(_) => {}
+            LifecycleEntityRef::Budget(budget_id) => {
+                let (scope, category_id): (String, i64) = self
+                    .conn
+                    .query_row(
+                        "SELECT scope, category_id FROM budgets WHERE id = ?",
+                        params![budget_id.get()],
+                        |row| Ok((row.get(0)?, row.get(1)?)),
+                    )
+                    .with_context(|| format!("load budget {}", budget_id.get()))?;
+                let category =
+                    BudgetCategory::from_parts(&scope, category_id).ok_or_else(|| {
+                        anyhow!(
+                            "budget {} has unknown scope `{scope}` -- fix the row and retry",
+                            budget_id.get()
+                        )
+                    })?;
+                self.check_budget_category(category, Some(budget_id))?;
+            }
+            LifecycleEntityRef::Vendor(vendor_id) => {
+                let document_id: Option<i64> = self
+                    .conn
+                    .query_row(
+                        "SELECT insurance_document_id FROM vendors WHERE id = ?",
+                        params![vendor_id.get()],
+                        |row| row.get(0),
+                    )
+                    .with_context(|| format!("load vendor {}", vendor_id.get()))?;
+                if let Some(document_id) = document_id {
+                    self.require_parent_alive(ParentEntityRef::Document(DocumentId::new(
+                        document_id,
+                    )))?;
+                }
+            }
+            LifecycleEntityRef::Appliance(_) => {}
         }
         Ok(())
     }
@@ -4116,6 +9043,51 @@ impl Store {
         Ok(())
     }
 
+    /// Rejects a parent that is missing, deleted, the project itself, or one
+    /// of its own sub-projects.
+    fn check_project_parent(
+        &self,
+        project_id: Option<ProjectId>,
+        parent_id: ProjectId,
+    ) -> Result<()> {
+        if project_id == Some(parent_id) {
+            bail!(
+                "project {} can't be its own parent -- pick another project or clear the parent",
+                parent_id.get()
+            );
+        }
+        self.require_parent_alive(ParentEntityRef::Project(parent_id))?;
+        let Some(project_id) = project_id else {
+            return Ok(());
+        };
+        let cycle: bool = self
+            .conn
+            .query_row(
+                "
+                WITH RECURSIVE ancestors(id) AS (
+                  SELECT ?
+                  UNION
+                  SELECT p.parent_project_id
+                  FROM projects p
+                  JOIN ancestors a ON p.id = a.id
+                  WHERE p.parent_project_id IS NOT NULL
+                )
+                SELECT EXISTS (SELECT 1 FROM ancestors WHERE id = ?)
+                ",
+                params![parent_id.get(), project_id.get()],
+                |row| row.get(0),
+            )
+            .with_context(|| format!("walk ancestors of project {}", parent_id.get()))?;
+        if cycle {
+            bail!(
+                "project {} is a sub-project of project {}; pick a parent outside its own tree",
+                parent_id.get(),
+                project_id.get()
+            );
+        }
+        Ok(())
+    }
+
     fn require_parent_alive(&self, parent: ParentEntityRef) -> Result<()> {
         let parent_kind = parent.kind();
         let parent_id = parent.id();
@@ -4308,6 +9280,9 @@ fn seeded_vendor_input(rng: &mut DeterministicRng, trade: &str, serial: usize) -
         ),
         website: format!("https://{slug}-{label}.example-home.com"),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     }
 }
 
@@ -4671,6 +9646,28 @@ pub fn evict_stale_cache(dir: &Path, ttl_days: i64) -> Result<usize> {
     Ok(removed)
 }
 
+/// Runs `PRAGMA integrity_check` on the database file at `path`, opened
+/// read-only so a bad file is never touched.
+pub fn check_integrity(path: &Path) -> Result<()> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("open {} to check it", path.display()))?;
+    let mut stmt = conn
+        .prepare("PRAGMA integrity_check")
+        .with_context(|| format!("{} is not a readable SQLite database", path.display()))?;
+    let problems = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .with_context(|| format!("check integrity of {}", path.display()))?;
+    if problems != ["ok"] {
+        bail!(
+            "{} failed its integrity check: {}",
+            path.display(),
+            problems.join("; ")
+        );
+    }
+    Ok(())
+}
+
 pub fn validate_db_path(path: &str) -> Result<()> {
     if path.is_empty() {
         bail!("database path must not be empty");
@@ -4771,6 +9768,122 @@ fn is_identifier_char(byte: u8) -> bool {
     byte.is_ascii_alphanumeric() || byte == b'_'
 }
 
+/// Lowercased names defined by `name [(columns)] AS [[NOT] MATERIALIZED] (`,
+/// which in a SELECT only introduces a common table expression or a named
+/// window. Comments and string literals are skipped.
+fn common_table_names(sql: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '-' if chars.peek() == Some(&'-') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '\'' => {
+                for next in chars.by_ref() {
+                    if next == '\'' {
+                        break;
+                    }
+                }
+                tokens.push(String::from("'"));
+            }
+            '"' | '`' | '[' => {
+                let close = if ch == '[' { ']' } else { ch };
+                let mut name = String::new();
+                for next in chars.by_ref() {
+                    if next == close {
+                        break;
+                    }
+                    name.push(next);
+                }
+                tokens.push(name.to_ascii_lowercase());
+            }
+            '(' | ')' | ',' | ';' | '.' => tokens.push(ch.to_string()),
+            ch if ch.is_alphanumeric() || ch == '_' => {
+                let mut word = String::from(ch);
+                while let Some(&next) = chars.peek() {
+                    if !(next.is_alphanumeric() || next == '_' || next == '$') {
+                        break;
+                    }
+                    word.push(next);
+                    chars.next();
+                }
+                tokens.push(word.to_ascii_lowercase());
+            }
+            _ => {}
+        }
+    }
+
+    let mut names = Vec::new();
+    for (start, name) in tokens.iter().enumerate() {
+        if matches!(name.as_str(), "(" | ")" | "," | ";" | "." | "'") {
+            continue;
+        }
+        let mut index = start + 1;
+        if tokens.get(index).map(String::as_str) == Some("(") {
+            let mut depth = 0usize;
+            while let Some(token) = tokens.get(index) {
+                match token.as_str() {
+                    "(" => depth += 1,
+                    ")" => depth -= 1,
+                    _ => {}
+                }
+                index += 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+        }
+        if tokens.get(index).map(String::as_str) != Some("as") {
+            continue;
+        }
+        index += 1;
+        if tokens.get(index).map(String::as_str) == Some("not") {
+            index += 1;
+        }
+        if tokens.get(index).map(String::as_str) == Some("materialized") {
+            index += 1;
+        }
+        if tokens.get(index).map(String::as_str) == Some("(") {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+fn user_table_names(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn
+        .prepare(
+            "
+            SELECT name
+            FROM sqlite_master
+            WHERE type = 'table'
+              AND name NOT LIKE 'sqlite_%'
+            ORDER BY name ASC
+            ",
+        )
+        .context("prepare user tables query")?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .context("query user tables")?;
+    rows.collect::<rusqlite::Result<Vec<_>>>()
+        .context("collect user tables")
+}
+
 fn has_user_tables(conn: &Connection) -> Result<bool> {
     let count: i64 = conn
         .query_row(
@@ -4813,6 +9926,24 @@ fn validate_schema(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+fn ensure_additive_schema(conn: &Connection) -> Result<()> {
+    for table in ADDITIVE_TABLES {
+        conn.execute_batch(table.create_sql)
+            .with_context(|| format!("ensure table `{}`", table.name))?;
+    }
+    for column in ADDITIVE_COLUMNS {
+        if table_columns(conn, column.table)?.contains(column.name) {
+            continue;
+        }
+        conn.execute_batch(&format!(
+            "ALTER TABLE {} ADD COLUMN {} {};",
+            column.table, column.name, column.definition
+        ))
+        .with_context(|| format!("add column `{}.{}`", column.table, column.name))?;
+    }
+    Ok(())
+}
+
 fn ensure_required_indexes(conn: &Connection) -> Result<()> {
     for index in REQUIRED_INDEXES {
         conn.execute_batch(index.create_sql)
@@ -4835,6 +9966,20 @@ fn ensure_required_indexes(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// The table and name column behind a tab other rows link to.
+fn named_table(tab: TabKind) -> Result<(&'static str, &'static str)> {
+    Ok(match tab {
+        TabKind::Projects => ("projects", "title"),
+        TabKind::Vendors => ("vendors", "name"),
+        TabKind::Maintenance => ("maintenance_items", "name"),
+        TabKind::Appliances => ("appliances", "name"),
+        other => bail!(
+            "{} rows have no names to link by; use projects, vendors, maintenance, or appliances",
+            other.label()
+        ),
+    })
+}
+
 fn table_exists(conn: &Connection, table: &str) -> Result<bool> {
     let exists = conn
         .query_row(
@@ -4897,13 +10042,124 @@ fn configure_connection(conn: &Connection) -> Result<()> {
     .context("configure sqlite pragmas")
 }
 
+/// Where a lookup list lives and which records point at it.
+struct LookupTables {
+    table: &'static str,
+    records: &'static str,
+    column: &'static str,
+    budget_scope: &'static str,
+}
+
+const fn lookup_tables(kind: LookupKind) -> LookupTables {
+    match kind {
+        LookupKind::MaintenanceCategory => LookupTables {
+            table: "maintenance_categories",
+            records: "maintenance_items",
+            column: "category_id",
+            budget_scope: "maintenance",
+        },
+        LookupKind::ProjectType => LookupTables {
+            table: "project_types",
+            records: "projects",
+            column: "project_type_id",
+            budget_scope: "project",
+        },
+    }
+}
+
+/// Table holding the rows a document of this kind links to.
+const fn document_link_table(kind: DocumentEntityKind) -> Option<&'static str> {
+    match kind {
+        DocumentEntityKind::None => None,
+        DocumentEntityKind::Project => Some("projects"),
+        DocumentEntityKind::Quote => Some("quotes"),
+        DocumentEntityKind::Maintenance => Some("maintenance_items"),
+        DocumentEntityKind::Appliance => Some("appliances"),
+        DocumentEntityKind::ServiceLog => Some("service_log_entries"),
+        DocumentEntityKind::Vendor => Some("vendors"),
+        DocumentEntityKind::Incident => Some("incidents"),
+    }
+}
+
 fn default_setting_value(key: SettingKey) -> SettingValue {
     match key {
         SettingKey::UiShowDashboard => SettingValue::Bool(true),
-        SettingKey::LlmModel => SettingValue::Text(String::new()),
+        SettingKey::UiHideSettledProjects
+        | SettingKey::UiHideResolvedIncidents
+        | SettingKey::UiHideExpiredWarranties => SettingValue::Bool(false),
+        SettingKey::LlmModel
+        | SettingKey::LlmSqlModel
+        | SettingKey::LlmSummaryModel
+        | SettingKey::UiTheme
+        | SettingKey::IcalPath
+        | SettingKey::DashboardSections
+        | SettingKey::DashboardRowLimits
+        | SettingKey::UiAutoRefresh => SettingValue::Text(String::new()),
+        SettingKey::NotifyDesktop | SettingKey::BackupAuto => SettingValue::Bool(true),
+        SettingKey::NotifyWarrantyDays | SettingKey::NotifyInsuranceDays => {
+            SettingValue::Days(DEFAULT_NOTICE_DAYS)
+        }
+        SettingKey::PurgeDeletedDays => SettingValue::Days(0),
+        SettingKey::AlertUrgentIncident
+        | SettingKey::AlertOverdueMaintenance
+        | SettingKey::AlertChatEdit => {
+            SettingValue::Text(AlertStyle::default().as_str().to_owned())
+        }
+    }
+}
+
+/// `WHERE` clause for a list query: live rows unless `include_deleted`, and
+/// only rows updated since the bound `?1` when `since` is set.
+fn list_filter(
+    include_deleted: bool,
+    since: Option<OffsetDateTime>,
+    skip: Option<&BTreeSet<i64>>,
+) -> String {
+    let mut filters = Vec::new();
+    if !include_deleted {
+        filters.push("deleted_at IS NULL".to_owned());
+    }
+    if since.is_some() {
+        filters.push("updated_at >= ?1".to_owned());
+    }
+    if let Some(skip) = skip.filter(|skip| !skip.is_empty()) {
+        // Ids are integers, so they go in as literals rather than one bound
+        // parameter each.
+        let skip = skip
+            .iter()
+            .map(i64::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        filters.push(format!("id NOT IN ({skip})"));
+    }
+    if filters.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}\n", filters.join(" AND "))
     }
 }
 
+fn page_limit(page: Option<ListPage<'_>>) -> String {
+    page.map(|page| format!("\nLIMIT {} OFFSET {}", page.limit, page.offset))
+        .unwrap_or_default()
+}
+
+/// `2026-03-01T14:05:09`: a prefix of every stored UTC timestamp in that
+/// second. Fractional digits vary in length, so comparing whole timestamps as
+/// text can put a later one first; a prefix compares below all of them.
+fn second_prefix(at: OffsetDateTime) -> String {
+    let at = at.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        at.year(),
+        u8::from(at.month()),
+        at.day(),
+        at.hour(),
+        at.minute(),
+        at.second()
+    )
+}
+
 fn now_rfc3339() -> Result<String> {
     OffsetDateTime::now_utc()
         .format(&Rfc3339)
@@ -5026,9 +10282,9 @@ fn set_private_permissions(path: &Path) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Store, contains_word, is_safe_identifier};
+    use super::{Store, common_table_names, contains_word, is_safe_identifier};
     use anyhow::Result;
-    use micasa_app::{SettingKey, SettingValue};
+    use micasa_app::{AlertEvent, AlertStyle, SettingKey, SettingValue, TabKind};
 
     #[test]
     fn list_settings_returns_typed_defaults() -> Result<()> {
@@ -5036,11 +10292,85 @@ mod tests {
         store.bootstrap()?;
 
         let settings = store.list_settings()?;
-        assert_eq!(settings.len(), 2);
+        assert_eq!(settings.len(), 20);
         assert_eq!(settings[0].key, SettingKey::UiShowDashboard);
         assert_eq!(settings[0].value, SettingValue::Bool(true));
         assert_eq!(settings[1].key, SettingKey::LlmModel);
         assert_eq!(settings[1].value, SettingValue::Text(String::new()));
+        assert_eq!(settings[2].key, SettingKey::UiTheme);
+        assert_eq!(settings[2].value, SettingValue::Text(String::new()));
+        for setting in &settings[3..6] {
+            assert!(setting.key.hides_settled_on().is_some());
+            assert_eq!(setting.value, SettingValue::Bool(false));
+        }
+        assert_eq!(settings[6].key, SettingKey::IcalPath);
+        assert_eq!(settings[6].value, SettingValue::Text(String::new()));
+        assert_eq!(settings[7].key, SettingKey::NotifyDesktop);
+        assert_eq!(settings[7].value, SettingValue::Bool(true));
+        for setting in &settings[8..10] {
+            assert_eq!(
+                setting.value,
+                SettingValue::Days(super::DEFAULT_NOTICE_DAYS)
+            );
+        }
+        assert_eq!(settings[10].key, SettingKey::PurgeDeletedDays);
+        assert_eq!(settings[10].value, SettingValue::Days(0));
+        assert_eq!(settings[11].key, SettingKey::LlmSqlModel);
+        assert_eq!(settings[12].key, SettingKey::LlmSummaryModel);
+        assert_eq!(settings[12].value, SettingValue::Text(String::new()));
+        for setting in &settings[13..16] {
+            assert!(setting.key.alert_event().is_some());
+            assert_eq!(setting.value, SettingValue::Text("bell".to_owned()));
+        }
+        assert_eq!(settings[16].key, SettingKey::BackupAuto);
+        assert_eq!(settings[16].value, SettingValue::Bool(true));
+        for setting in &settings[17..] {
+            assert_eq!(setting.value, SettingValue::Text(String::new()));
+        }
+        assert_eq!(store.get_auto_refresh_seconds()?, 0);
+        assert!(
+            store
+                .put_setting(
+                    SettingKey::UiAutoRefresh,
+                    SettingValue::Text("soon".to_owned())
+                )
+                .is_err()
+        );
+
+        store.put_alert_style(AlertEvent::OverdueMaintenance, AlertStyle::Off)?;
+        assert_eq!(
+            store.get_alert_style(AlertEvent::OverdueMaintenance)?,
+            AlertStyle::Off
+        );
+        assert!(
+            store
+                .put_setting(
+                    SettingKey::AlertChatEdit,
+                    SettingValue::Text("siren".to_owned())
+                )
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn hide_settled_rules_map_to_tabs_and_default_off() -> Result<()> {
+        let store = Store::open_memory()?;
+        store.bootstrap()?;
+        assert!(!store.get_hide_settled(TabKind::Incidents)?);
+
+        store.put_setting(
+            SettingKey::UiHideResolvedIncidents,
+            SettingValue::Bool(true),
+        )?;
+        assert!(store.get_hide_settled(TabKind::Incidents)?);
+        assert!(!store.get_hide_settled(TabKind::Appliances)?);
+        assert!(!store.get_hide_settled(TabKind::Vendors)?);
+        assert_eq!(
+            SettingKey::hide_settled_for(TabKind::Appliances),
+            Some(SettingKey::UiHideExpiredWarranties)
+        );
+        assert_eq!(SettingKey::hide_settled_for(TabKind::Vendors), None);
         Ok(())
     }
 
@@ -5051,9 +10381,12 @@ mod tests {
 
         store.put_show_dashboard(false)?;
         store.put_last_model("qwen3:32b")?;
+        assert_eq!(store.get_theme_override()?, None);
+        store.put_theme("light")?;
 
         assert!(!store.get_show_dashboard()?);
         assert_eq!(store.get_last_model()?.as_deref(), Some("qwen3:32b"));
+        assert_eq!(store.get_theme_override()?.as_deref(), Some("light"));
 
         let settings = store.list_settings()?;
         assert!(
@@ -5068,6 +10401,16 @@ mod tests {
                 .any(|setting| setting.key == SettingKey::LlmModel
                     && setting.value == SettingValue::Text("qwen3:32b".to_owned()))
         );
+
+        assert_eq!(store.get_stage_model(SettingKey::LlmSqlModel)?, None);
+        store.put_stage_model(SettingKey::LlmSqlModel, " qwen3:0.6b ")?;
+        assert_eq!(
+            store.get_stage_model(SettingKey::LlmSqlModel)?.as_deref(),
+            Some("qwen3:0.6b")
+        );
+        store.put_stage_model(SettingKey::LlmSqlModel, "")?;
+        assert_eq!(store.get_stage_model(SettingKey::LlmSqlModel)?, None);
+        assert!(store.put_stage_model(SettingKey::UiTheme, "qwen3").is_err());
         Ok(())
     }
 
@@ -5101,4 +10444,16 @@ mod tests {
         assert!(contains_word("DROP TABLE x", "DROP"));
         assert!(!contains_word("BACKDROP", "DROP"));
     }
+
+    #[test]
+    fn common_table_names_finds_with_clause_names_only() {
+        assert_eq!(
+            common_table_names(
+                "WITH c AS (SELECT 1), \"Projects\"(id) AS MATERIALIZED (SELECT 2) \
+                 SELECT * FROM c AS alias"
+            ),
+            vec!["c".to_owned(), "projects".to_owned()]
+        );
+        assert!(common_table_names("SELECT 'x AS (' FROM projects AS p -- t AS (").is_empty());
+    }
 }
@@ -0,0 +1,83 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::Result;
+
+/// One published recall, normalized from whichever source reported it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recall {
+    /// Stable id from the source (for example a CPSC recall number); used to
+    /// avoid filing the same recall twice for an appliance.
+    pub id: String,
+    pub title: String,
+    pub brand: String,
+    pub model_numbers: Vec<String>,
+    pub hazard: String,
+    pub remedy: String,
+    pub url: String,
+}
+
+/// A source of recall notices. Implementations may call a remote API or read
+/// a local dataset; they only need to return candidates, which are matched
+/// against the appliance with [`recall_matches`] afterwards.
+pub trait RecallProvider {
+    fn name(&self) -> &'static str;
+    fn lookup(&self, brand: &str, model_number: &str) -> Result<Vec<Recall>>;
+}
+
+/// Uppercase alphanumerics only, so `WF-45T 6000` matches `wf45t6000`.
+pub fn normalize_model_number(raw: &str) -> String {
+    raw.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect()
+}
+
+/// A recall applies when the brand matches (either name containing the other,
+/// ignoring case) and one of its model numbers matches after normalizing.
+pub fn recall_matches(recall: &Recall, brand: &str, model_number: &str) -> bool {
+    let brand = brand.trim().to_lowercase();
+    let recall_brand = recall.brand.trim().to_lowercase();
+    let model = normalize_model_number(model_number);
+    if brand.is_empty() || recall_brand.is_empty() || model.is_empty() {
+        return false;
+    }
+    let brand_matches = recall_brand.contains(&brand) || brand.contains(&recall_brand);
+    brand_matches
+        && recall
+            .model_numbers
+            .iter()
+            .any(|candidate| normalize_model_number(candidate) == model)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Recall, normalize_model_number, recall_matches};
+
+    fn recall(brand: &str, models: &[&str]) -> Recall {
+        Recall {
+            id: "26-001".to_owned(),
+            title: "Dishwasher fire hazard".to_owned(),
+            brand: brand.to_owned(),
+            model_numbers: models.iter().map(|model| (*model).to_owned()).collect(),
+            hazard: "Fire".to_owned(),
+            remedy: "Repair".to_owned(),
+            url: String::new(),
+        }
+    }
+
+    #[test]
+    fn model_numbers_normalize_punctuation_and_case() {
+        assert_eq!(normalize_model_number(" wf-45t 6000/a "), "WF45T6000A");
+    }
+
+    #[test]
+    fn matches_need_brand_and_model() {
+        let notice = recall("Bosch Home Appliances", &["SHX-878WD5N", "SHX878WD6N"]);
+        assert!(recall_matches(&notice, "bosch", "shx878wd5n"));
+        assert!(!recall_matches(&notice, "Whirlpool", "SHX878WD5N"));
+        assert!(!recall_matches(&notice, "Bosch", "SHX878"));
+        assert!(!recall_matches(&notice, "Bosch", ""));
+        assert!(!recall_matches(&notice, "", "SHX878WD5N"));
+    }
+}
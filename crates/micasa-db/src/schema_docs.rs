@@ -0,0 +1,286 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Described schema: the live table layout plus a sentence per table and
+//! column. The chat prompt and the `--print-schema` artifact are both built
+//! from this, so what the model is told and what users read stay the same.
+
+/// One table as the chat prompt and schema docs see it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableDoc {
+    pub name: String,
+    pub description: String,
+    pub columns: Vec<ColumnDoc>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnDoc {
+    pub name: String,
+    pub column_type: String,
+    pub not_null: bool,
+    pub primary_key: bool,
+    /// `table(column)` for a foreign key.
+    pub references: Option<String>,
+    pub description: String,
+}
+
+const TABLE_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("house_profiles", "The house itself; at most one row."),
+    (
+        "project_types",
+        "Kinds of project, like Plumbing or Landscaping.",
+    ),
+    (
+        "vendors",
+        "Contractors and companies that quote or do work.",
+    ),
+    ("projects", "Planned, running, and finished home projects."),
+    ("quotes", "Vendor quotes for a project."),
+    (
+        "maintenance_categories",
+        "Kinds of maintenance, like HVAC or Exterior.",
+    ),
+    ("appliances", "Appliances and equipment in the house."),
+    (
+        "maintenance_items",
+        "Recurring maintenance tasks, due every interval_months after last_serviced_at.",
+    ),
+    ("incidents", "Problems noticed around the house."),
+    (
+        "service_log_entries",
+        "Each time a maintenance item was serviced.",
+    ),
+    (
+        "documents",
+        "Files attached to records, stored in the database.",
+    ),
+    (
+        "deletion_records",
+        "History of soft deletes and restores, for undo.",
+    ),
+    ("settings", "App preferences as key/value text."),
+    ("chat_inputs", "Past chat questions, for prompt history."),
+    (
+        "archived_rows",
+        "Rows hidden from the default views; join on entity and target_id.",
+    ),
+    (
+        "cost_splits",
+        "A record's cost divided across maintenance categories.",
+    ),
+    ("currency_rates", "Exchange rates into the home currency."),
+    (
+        "money_currencies",
+        "Money fields entered in a currency other than the home one.",
+    ),
+    (
+        "weather_forecast_days",
+        "Cached daily forecast for the house location.",
+    ),
+    ("appliance_recalls", "Safety recalls matched to appliances."),
+    (
+        "budgets",
+        "Monthly spending limits per maintenance category.",
+    ),
+    (
+        "saved_views",
+        "Named sort, filter, and column layouts per tab.",
+    ),
+    (
+        "audit_entries",
+        "Who created, changed, or deleted each record.",
+    ),
+    ("tags", "Free-form labels."),
+    ("project_tasks", "Checklist steps of a project."),
+    (
+        "project_templates",
+        "Saved starting points for new projects: a type and a checklist.",
+    ),
+    (
+        "entity_tags",
+        "Tags attached to records; join on entity and target_id.",
+    ),
+    (
+        "private_rows",
+        "Records hidden unless private rows are shown; join on entity and target_id.",
+    ),
+    (
+        "document_chunks",
+        "Passages of document text with their embeddings, for chat search.",
+    ),
+    (
+        "manual_chunks",
+        "Passages of maintenance manual text with their embeddings, for chat search.",
+    ),
+];
+
+const COLUMN_DESCRIPTIONS: &[(&str, &str, &str)] = &[
+    (
+        "projects",
+        "status",
+        "One of ideating, planned, quoted, underway, delayed, completed, abandoned.",
+    ),
+    (
+        "projects",
+        "parent_project_id",
+        "The project this is a phase of, if any.",
+    ),
+    ("quotes", "accepted_at", "Set once the quote was accepted."),
+    (
+        "maintenance_items",
+        "last_serviced_at",
+        "Date of the latest service log entry.",
+    ),
+    (
+        "maintenance_items",
+        "interval_months",
+        "Months between services.",
+    ),
+    (
+        "maintenance_items",
+        "manual_text",
+        "Excerpt from the owner's manual.",
+    ),
+    ("incidents", "status", "One of open, in_progress, resolved."),
+    ("incidents", "severity", "One of urgent, soon, whenever."),
+    (
+        "documents",
+        "entity_kind",
+        "Kind of record the file is attached to: project, quote, maintenance, appliance, service_log, vendor, incident, or empty.",
+    ),
+    (
+        "documents",
+        "entity_id",
+        "Id of the record the file is attached to; 0 when unattached.",
+    ),
+    ("documents", "data", "The file contents. Never select this."),
+    (
+        "documents",
+        "extracted_text",
+        "Text read out of the file, often empty. Search with LOWER(extracted_text) LIKE '%word%'.",
+    ),
+    (
+        "project_tasks",
+        "done_at",
+        "Set once the task was checked off; NULL while still to do.",
+    ),
+    ("project_templates", "tasks", "Task titles, one per line."),
+    (
+        "vendors",
+        "insurance_document_id",
+        "Certificate of insurance on file.",
+    ),
+    (
+        "currency_rates",
+        "home_per_unit_micros",
+        "Home currency per unit, in millionths.",
+    ),
+];
+
+/// The sentence for `table`, or empty when none is written yet.
+pub fn table_description(table: &str) -> &'static str {
+    TABLE_DESCRIPTIONS
+        .iter()
+        .find(|(name, _)| *name == table)
+        .map_or("", |(_, description)| description)
+}
+
+/// The sentence for one column. Specific notes win; otherwise shared
+/// conventions (money in cents, soft deletes, timestamps) fill in.
+pub fn column_description(table: &str, column: &str) -> String {
+    if let Some((_, _, description)) = COLUMN_DESCRIPTIONS
+        .iter()
+        .find(|(owner, name, _)| *owner == table && *name == column)
+    {
+        return (*description).to_owned();
+    }
+    match column {
+        "id" => "Row id.".to_owned(),
+        "deleted_at" => "Set when soft-deleted; filter with deleted_at IS NULL.".to_owned(),
+        "created_at" | "updated_at" => "UTC timestamp.".to_owned(),
+        "entity" => {
+            "Kind of record: project, quote, maintenance, appliance, service_log, vendor, incident, document, or budget.".to_owned()
+        }
+        "target_id" => "Id of the record in the table named by entity.".to_owned(),
+        name if name.ends_with("_cents") => {
+            "Money in cents; divide by 100.0 for display.".to_owned()
+        }
+        name if name.ends_with("_date") || name.ends_with("_expiry") => {
+            "Date as YYYY-MM-DD.".to_owned()
+        }
+        _ => String::new(),
+    }
+}
+
+/// Markdown reference for people writing their own SQL.
+pub fn render_markdown(tables: &[TableDoc]) -> String {
+    let mut out = String::from("# micasa database schema\n");
+    for table in tables {
+        out.push_str(&format!("\n## {}\n\n", table.name));
+        if !table.description.is_empty() {
+            out.push_str(&table.description);
+            out.push_str("\n\n");
+        }
+        out.push_str("| Column | Type | Notes |\n| --- | --- | --- |\n");
+        for column in &table.columns {
+            let mut notes = Vec::new();
+            if column.primary_key {
+                notes.push("primary key".to_owned());
+            }
+            if column.not_null {
+                notes.push("not null".to_owned());
+            }
+            if let Some(target) = &column.references {
+                notes.push(format!("references {target}"));
+            }
+            if !column.description.is_empty() {
+                notes.push(column.description.replace('|', "\\|"));
+            }
+            out.push_str(&format!(
+                "| {} | {} | {} |\n",
+                column.name,
+                column.column_type,
+                notes.join("; ")
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnDoc, TableDoc, column_description, render_markdown};
+
+    #[test]
+    fn specific_notes_win_over_shared_conventions() {
+        assert_eq!(
+            column_description("incidents", "severity"),
+            "One of urgent, soon, whenever."
+        );
+        assert!(column_description("quotes", "total_cents").contains("cents"));
+        assert!(column_description("vendors", "deleted_at").contains("IS NULL"));
+        assert_eq!(column_description("vendors", "phone"), "");
+    }
+
+    #[test]
+    fn markdown_lists_keys_references_and_notes() {
+        let tables = [TableDoc {
+            name: "quotes".to_owned(),
+            description: "Vendor quotes for a project.".to_owned(),
+            columns: vec![ColumnDoc {
+                name: "vendor_id".to_owned(),
+                column_type: "INTEGER".to_owned(),
+                not_null: true,
+                primary_key: false,
+                references: Some("vendors(id)".to_owned()),
+                description: "a | b".to_owned(),
+            }],
+        }];
+        let markdown = render_markdown(&tables);
+        assert!(markdown.contains("## quotes\n\nVendor quotes for a project.\n"));
+        assert!(
+            markdown
+                .contains("| vendor_id | INTEGER | not null; references vendors(id); a \\| b |")
+        );
+    }
+}
@@ -207,6 +207,27 @@ pub fn compute_next_due(last: Option<Date>, interval_months: i32) -> Option<Date
     Some(add_months(last, interval_months))
 }
 
+/// Fewest service dates needed before the observed cadence is trusted: two
+/// gaps, so a single early or late visit doesn't produce a suggestion.
+pub const MIN_SERVICE_DATES_FOR_INTERVAL: usize = 3;
+
+/// Average gap between service dates, rounded to whole months (minimum 1).
+/// Order doesn't matter; duplicate same-day entries count as one visit.
+pub fn observed_interval_months(serviced: &[Date]) -> Option<i32> {
+    let mut dates = serviced.to_vec();
+    dates.sort_unstable();
+    dates.dedup();
+    if dates.len() < MIN_SERVICE_DATES_FOR_INTERVAL {
+        return None;
+    }
+    let first = *dates.first()?;
+    let last = *dates.last()?;
+    let gaps = (dates.len() - 1) as f64;
+    let average_days = (last - first).whole_days() as f64 / gaps;
+    let months = (average_days / (365.25 / 12.0)).round() as i32;
+    Some(months.max(1))
+}
+
 pub fn add_months(date: Date, months: i32) -> Date {
     let base_month = i32::from(date.month() as u8);
     let total_month = base_month - 1 + months;
@@ -358,9 +379,10 @@ fn last_day_of_month(year: i32, month: Month) -> u8 {
 mod tests {
     use super::{
         ValidationError, add_months, compute_next_due, format_cents, format_compact_cents,
-        format_compact_optional_cents, format_date, format_optional_cents, parse_interval_months,
-        parse_optional_cents, parse_optional_date, parse_optional_float, parse_optional_int,
-        parse_required_cents, parse_required_date, parse_required_float, parse_required_int,
+        format_compact_optional_cents, format_date, format_optional_cents,
+        observed_interval_months, parse_interval_months, parse_optional_cents, parse_optional_date,
+        parse_optional_float, parse_optional_int, parse_required_cents, parse_required_date,
+        parse_required_float, parse_required_int,
     };
     use std::collections::BTreeMap;
     use time::{Date, Month};
@@ -555,6 +577,46 @@ mod tests {
         assert_eq!(compute_next_due(Some(date), 0), None);
     }
 
+    #[test]
+    fn observed_interval_months_averages_gaps() {
+        let date = |month, day| Date::from_calendar_date(2025, month, day).expect("valid date");
+        let dates = [
+            date(Month::September, 2),
+            date(Month::January, 5),
+            date(Month::May, 1),
+            date(Month::May, 1),
+        ];
+        assert_eq!(observed_interval_months(&dates), Some(4));
+    }
+
+    #[test]
+    fn observed_interval_months_needs_three_visits() {
+        let date = |month| Date::from_calendar_date(2025, month, 1).expect("valid date");
+        assert_eq!(observed_interval_months(&[]), None);
+        assert_eq!(
+            observed_interval_months(&[date(Month::January), date(Month::July)]),
+            None
+        );
+        assert_eq!(
+            observed_interval_months(&[
+                date(Month::March),
+                date(Month::March),
+                date(Month::March),
+                date(Month::April),
+            ]),
+            None
+        );
+    }
+
+    #[test]
+    fn observed_interval_months_floors_at_one() {
+        let date = |day| Date::from_calendar_date(2025, Month::June, day).expect("valid date");
+        assert_eq!(
+            observed_interval_months(&[date(1), date(3), date(5)]),
+            Some(1)
+        );
+    }
+
     #[test]
     fn format_compact_cents_test() {
         let cases = [
@@ -0,0 +1,225 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use anyhow::{Result, anyhow, bail};
+use micasa_app::MaintenanceItemId;
+use time::Date;
+
+/// One day of cached forecast, in metric units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastDay {
+    pub day: Date,
+    pub min_temp_c: f64,
+    pub max_temp_c: f64,
+    pub precipitation_mm: f64,
+    pub wind_kmh: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum WeatherRisk {
+    Freeze,
+    HeavyRain,
+    HighWind,
+    Heat,
+}
+
+impl WeatherRisk {
+    pub const ALL: [Self; 4] = [Self::Freeze, Self::HeavyRain, Self::HighWind, Self::Heat];
+
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Freeze => "freeze warning",
+            Self::HeavyRain => "heavy rain",
+            Self::HighWind => "high wind",
+            Self::Heat => "heat wave",
+        }
+    }
+
+    pub const fn advice(self) -> &'static str {
+        match self {
+            Self::Freeze => "winterize outdoor faucets",
+            Self::HeavyRain => "clear gutters and test the sump pump",
+            Self::HighWind => "secure loose roofing and trim trees",
+            Self::Heat => "service the AC before it runs hard",
+        }
+    }
+
+    /// Word prefixes that mark a maintenance item as sensitive to this risk.
+    const fn keywords(self) -> &'static [&'static str] {
+        match self {
+            Self::Freeze => &[
+                "faucet",
+                "spigot",
+                "hose",
+                "sprinkler",
+                "irrigation",
+                "pipe",
+                "winteriz",
+                "pool",
+            ],
+            Self::HeavyRain => &["gutter", "downspout", "sump", "drain", "grading"],
+            Self::HighWind => &["roof", "shingle", "tree", "fence", "siding"],
+            Self::Heat => &["hvac", "ac", "a/c", "conditioner", "condenser", "cooling"],
+        }
+    }
+
+    fn threatens(self, day: &ForecastDay) -> bool {
+        match self {
+            Self::Freeze => day.min_temp_c <= 0.0,
+            Self::HeavyRain => day.precipitation_mm >= 25.0,
+            Self::HighWind => day.wind_kmh >= 60.0,
+            Self::Heat => day.max_temp_c >= 32.0,
+        }
+    }
+
+    fn matches(self, name: &str) -> bool {
+        let lowered = name.to_ascii_lowercase();
+        lowered
+            .split(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '/'))
+            .any(|word| {
+                self.keywords()
+                    .iter()
+                    .any(|keyword| word.starts_with(keyword))
+            })
+    }
+}
+
+/// The first forecast day with a given risk, plus the maintenance items it
+/// puts in play. `items` is empty when nothing tracked matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeatherAlert {
+    pub day: Date,
+    pub risk: WeatherRisk,
+    pub items: Vec<(MaintenanceItemId, String)>,
+}
+
+/// Flags weather-sensitive maintenance for each risk in the forecast.
+/// Alerts are ordered by day, then by risk; items keep the input order.
+pub fn weather_alerts(
+    forecast: &[ForecastDay],
+    items: &[(MaintenanceItemId, String)],
+) -> Vec<WeatherAlert> {
+    let mut alerts = WeatherRisk::ALL
+        .into_iter()
+        .filter_map(|risk| {
+            let day = forecast
+                .iter()
+                .filter(|day| risk.threatens(day))
+                .map(|day| day.day)
+                .min()?;
+            Some(WeatherAlert {
+                day,
+                risk,
+                items: items
+                    .iter()
+                    .filter(|(_, name)| risk.matches(name))
+                    .cloned()
+                    .collect(),
+            })
+        })
+        .collect::<Vec<_>>();
+    alerts.sort_by_key(|alert| (alert.day, alert.risk));
+    alerts
+}
+
+/// Parses `45.52,-122.68` into a latitude and longitude pair.
+pub fn parse_location(raw: &str) -> Result<(f64, f64)> {
+    let (lat, lon) = raw
+        .split_once(',')
+        .ok_or_else(|| anyhow!("location `{raw}` needs the form LAT,LON, like 45.52,-122.68"))?;
+    let parse = |part: &str, label: &str| {
+        part.trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| anyhow!("{label} `{}` is not a number", part.trim()))
+    };
+    let (lat, lon) = (parse(lat, "latitude")?, parse(lon, "longitude")?);
+    validate_location(lat, lon)?;
+    Ok((lat, lon))
+}
+
+pub fn validate_location(lat: f64, lon: f64) -> Result<()> {
+    if !(-90.0..=90.0).contains(&lat) {
+        bail!("latitude {lat} must be between -90 and 90");
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        bail!("longitude {lon} must be between -180 and 180");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ForecastDay, WeatherRisk, parse_location, weather_alerts};
+    use micasa_app::MaintenanceItemId;
+    use time::{Date, Month};
+
+    fn day(day: u8, min: f64, max: f64, rain: f64, wind: f64) -> ForecastDay {
+        ForecastDay {
+            day: Date::from_calendar_date(2026, Month::January, day).expect("valid date"),
+            min_temp_c: min,
+            max_temp_c: max,
+            precipitation_mm: rain,
+            wind_kmh: wind,
+        }
+    }
+
+    #[test]
+    fn alerts_pick_the_first_risky_day_and_matching_items() {
+        let forecast = [
+            day(5, 3.0, 9.0, 30.0, 20.0),
+            day(6, -4.0, 2.0, 0.0, 70.0),
+            day(7, -6.0, 1.0, 0.0, 10.0),
+        ];
+        let items = [
+            (MaintenanceItemId::new(1), "Winterize hose bibs".to_owned()),
+            (MaintenanceItemId::new(2), "Clean gutters".to_owned()),
+            (MaintenanceItemId::new(3), "Vacuum coils".to_owned()),
+        ];
+        let alerts = weather_alerts(&forecast, &items);
+        let summary = alerts
+            .iter()
+            .map(|alert| {
+                (
+                    alert.day.day(),
+                    alert.risk,
+                    alert.items.iter().map(|(id, _)| id.get()).collect(),
+                )
+            })
+            .collect::<Vec<(u8, WeatherRisk, Vec<i64>)>>();
+        assert_eq!(
+            summary,
+            vec![
+                (5, WeatherRisk::HeavyRain, vec![2]),
+                (6, WeatherRisk::Freeze, vec![1]),
+                (6, WeatherRisk::HighWind, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn keywords_match_word_prefixes_only() {
+        assert!(WeatherRisk::Heat.matches("Service A/C"));
+        assert!(WeatherRisk::Heat.matches("AC tune-up"));
+        assert!(!WeatherRisk::Heat.matches("Vacuum coils"));
+        assert!(WeatherRisk::Freeze.matches("Drain sprinklers"));
+    }
+
+    #[test]
+    fn locations_parse_and_stay_in_range() {
+        assert_eq!(
+            parse_location(" 45.52, -122.68 ").expect("valid"),
+            (45.52, -122.68)
+        );
+        for (raw, expected) in [
+            ("45.52", "LAT,LON"),
+            ("north,1", "latitude"),
+            ("91,0", "between -90 and 90"),
+            ("0,181", "between -180 and 180"),
+        ] {
+            let error = parse_location(raw).expect_err(raw);
+            assert!(error.to_string().contains(expected), "{raw}: {error}");
+        }
+    }
+}
@@ -3,13 +3,17 @@
 
 use anyhow::Result;
 use micasa_app::{
-    DocumentEntityKind, IncidentSeverity, IncidentStatus, ProjectStatus, SettingKey, SettingValue,
+    DataIssueKind, DocumentEntityKind, IncidentSeverity, IncidentStatus, ProjectStatus, SettingKey,
+    SettingValue, TabKind,
 };
+use micasa_db::recalls::Recall;
+use micasa_db::weather::ForecastDay;
 use micasa_db::{
-    HouseProfileInput, LifecycleEntityRef, NewAppliance, NewDocument, NewIncident,
-    NewMaintenanceItem, NewProject, NewQuote, NewServiceLogEntry, NewVendor, SeedSummary, Store,
-    UpdateAppliance, UpdateDocument, UpdateIncident, UpdateMaintenanceItem, UpdateProject,
-    UpdateQuote, UpdateServiceLogEntry, UpdateVendor, default_db_path, document_cache_dir,
+    CostSplit, HouseProfileInput, LifecycleEntityRef, ListPage, NewAppliance, NewBudget,
+    NewDocument, NewIncident, NewMaintenanceItem, NewProject, NewQuote, NewServiceLogEntry,
+    NewVendor, RateSource, SchemaChange, SeedSummary, Store, UpdateAppliance, UpdateBudget,
+    UpdateDocument, UpdateIncident, UpdateMaintenanceItem, UpdateProject, UpdateQuote,
+    UpdateServiceLogEntry, UpdateVendor, check_integrity, default_db_path, document_cache_dir,
     evict_stale_cache, validate_db_path,
 };
 use std::collections::BTreeSet;
@@ -278,6 +282,66 @@ fn bootstrap_accepts_go_schema_fixture_db() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn pending_schema_changes_preview_bootstrap_and_backup_keeps_the_old_schema() -> Result<()> {
+    let fixture_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("go-schema-v1.db");
+    let temp_dir = tempfile::tempdir()?;
+    let db_path = temp_dir.path().join("go-schema-v1-copy.db");
+    fs::copy(&fixture_path, &db_path)?;
+
+    let store = Store::open(&db_path)?;
+    let pending = store.pending_schema_changes()?;
+    assert!(pending.contains(&SchemaChange::AddTable {
+        table: "private_rows"
+    }));
+    assert!(pending.iter().any(|change| change.to_string()
+        == "add column vendors.license_number (TEXT NOT NULL DEFAULT '')"));
+    assert_eq!(
+        store.pending_schema_changes()?,
+        pending,
+        "preview must not migrate"
+    );
+
+    let backup_path = temp_dir.path().join("before.db");
+    store.backup_to(&backup_path)?;
+    let error = store
+        .backup_to(&backup_path)
+        .expect_err("existing backup should not be overwritten");
+    assert!(error.to_string().contains("already exists"));
+
+    store.bootstrap()?;
+    assert!(store.pending_schema_changes()?.is_empty());
+    assert_eq!(
+        Store::open(&backup_path)?.pending_schema_changes()?,
+        pending
+    );
+
+    let fresh = Store::open_memory()?;
+    assert!(fresh.pending_schema_changes()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn check_integrity_accepts_backups_and_rejects_other_files() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let backup_path = temp_dir.path().join("snapshot.db");
+    store.backup_to(&backup_path)?;
+    check_integrity(&backup_path)?;
+
+    let junk = temp_dir.path().join("junk.db");
+    fs::write(&junk, b"definitely not sqlite, just some bytes long enough")?;
+    let error = check_integrity(&junk).expect_err("junk is not a database");
+    assert!(format!("{error:#}").contains("junk.db"), "{error:#}");
+
+    assert!(check_integrity(&temp_dir.path().join("missing.db")).is_err());
+    Ok(())
+}
+
 #[test]
 fn sqlite_pragmas_are_configured_on_open_and_reopen() -> Result<()> {
     let temp_dir = tempfile::tempdir()?;
@@ -386,6 +450,67 @@ fn table_names_include_core_tables_and_exclude_sqlite_internals() -> Result<()>
     Ok(())
 }
 
+#[test]
+fn every_table_has_a_schema_description() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let (_columns, rows) = store.read_only_query(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+    )?;
+    let mut names = rows.into_iter().flatten().collect::<Vec<_>>();
+    names.extend(store.table_names()?);
+    for name in names {
+        assert!(
+            !micasa_db::schema_docs::table_description(&name).is_empty(),
+            "describe {name} in schema_docs::TABLE_DESCRIPTIONS"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn schema_docs_describe_every_table_and_follow_foreign_keys() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let tables = store.schema_docs()?;
+    assert_eq!(tables.len(), store.table_names()?.len());
+    for table in &tables {
+        assert!(
+            !table.description.is_empty(),
+            "describe {} in schema_docs::TABLE_DESCRIPTIONS",
+            table.name
+        );
+    }
+    let column = |table: &str, column: &str| {
+        tables
+            .iter()
+            .find(|doc| doc.name == table)
+            .and_then(|doc| doc.columns.iter().find(|doc| doc.name == column))
+            .cloned()
+            .expect("column documented")
+    };
+    assert_eq!(
+        column("quotes", "vendor_id").references.as_deref(),
+        Some("vendors(id)")
+    );
+    assert_eq!(
+        column("projects", "parent_project_id")
+            .references
+            .as_deref(),
+        Some("projects(id)"),
+        "additive columns too"
+    );
+    assert!(column("projects", "id").primary_key);
+    assert!(
+        column("incidents", "severity")
+            .description
+            .contains("urgent")
+    );
+    Ok(())
+}
+
 #[test]
 fn table_columns_include_primary_key_metadata() -> Result<()> {
     let store = Store::open_memory()?;
@@ -414,6 +539,39 @@ fn table_columns_invalid_name_is_rejected_with_actionable_message() -> Result<()
     Ok(())
 }
 
+#[test]
+fn read_only_query_page_pages_rows_and_accepts_with_clauses() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let sql = "WITH RECURSIVE n(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM n WHERE x < 220) SELECT x FROM n;";
+    let first = store.read_only_query_page(sql, 0, 100)?;
+    assert!(!first.sql.ends_with(';'));
+    assert_eq!(first.columns, vec!["x".to_owned()]);
+    assert_eq!(first.rows.len(), 100);
+    assert!(first.has_more);
+
+    let last = store.read_only_query_page(sql, 2, 100)?;
+    assert_eq!(last.page, 2);
+    assert_eq!(last.rows.len(), 20);
+    assert_eq!(last.rows[0][0], "201");
+    assert!(!last.has_more);
+
+    store.append_chat_input("prompt-0")?;
+    let with = store.read_only_query_page(
+        "WITH recent AS (SELECT input FROM chat_inputs) SELECT replace(input, 'prompt', 'p') FROM recent LIMIT 1",
+        0,
+        100,
+    )?;
+    assert_eq!(with.rows, vec![vec!["p-0".to_owned()]]);
+
+    let error = store
+        .read_only_query_page("WITH gone AS (SELECT 1) DELETE FROM chat_inputs", 0, 100)
+        .expect_err("delete behind a WITH should be rejected");
+    assert!(error.to_string().contains("disallowed keyword: DELETE"));
+    Ok(())
+}
+
 #[test]
 fn read_only_query_rejects_attach_and_pragma_keywords() -> Result<()> {
     let store = Store::open_memory()?;
@@ -539,6 +697,7 @@ fn data_dump_and_column_hints_skip_deleted_rows() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(12_345),
+        parent_id: None,
     })?;
     let remove_id = store.create_project(&NewProject {
         title: "Remove Project".to_owned(),
@@ -549,6 +708,7 @@ fn data_dump_and_column_hints_skip_deleted_rows() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(22_222),
+        parent_id: None,
     })?;
     store.soft_delete_project(remove_id)?;
 
@@ -602,6 +762,7 @@ fn list_projects_uses_deterministic_tiebreaker() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let second = store.create_project(&NewProject {
         title: "B".to_owned(),
@@ -612,6 +773,7 @@ fn list_projects_uses_deterministic_tiebreaker() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.raw_connection().execute(
@@ -641,6 +803,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(10_000),
+        parent_id: None,
     })?;
     let delayed_id = store.create_project(&NewProject {
         title: "Delayed".to_owned(),
@@ -651,6 +814,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(20_000),
+        parent_id: None,
     })?;
     store.create_project(&NewProject {
         title: "Completed".to_owned(),
@@ -661,6 +825,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(30_000),
+        parent_id: None,
     })?;
 
     let category_id = store.list_maintenance_categories()?[0].id;
@@ -719,6 +884,9 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     store.create_service_log_entry(&NewServiceLogEntry {
         maintenance_item_id: maintenance_active,
@@ -747,6 +915,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         appliance_id: Some(appliance_in),
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.create_incident(&NewIncident {
         title: "Soon issue".to_owned(),
@@ -760,6 +929,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         appliance_id: Some(appliance_in),
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.create_incident(&NewIncident {
         title: "Closed issue".to_owned(),
@@ -773,6 +943,7 @@ fn dashboard_query_helpers_filter_and_summarize() -> Result<()> {
         appliance_id: Some(appliance_in),
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     let active_projects = store.list_active_projects()?;
@@ -833,6 +1004,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     store.create_project(&NewProject {
         title: "Underway".to_owned(),
@@ -843,6 +1015,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     store.create_project(&NewProject {
         title: "Completed".to_owned(),
@@ -853,6 +1026,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     let category_id = store.list_maintenance_categories()?[0].id;
@@ -902,6 +1076,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.create_incident(&NewIncident {
         title: "In progress".to_owned(),
@@ -915,6 +1090,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.create_incident(&NewIncident {
         title: "Resolved".to_owned(),
@@ -928,6 +1104,7 @@ fn dashboard_counts_tracks_due_projects_maintenance_and_open_incidents() -> Resu
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     let counts = store.dashboard_counts()?;
@@ -952,6 +1129,7 @@ fn total_project_spend_unaffected_by_project_edits() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: Some(50_000),
+        parent_id: None,
     })?;
 
     store.raw_connection().execute(
@@ -974,6 +1152,7 @@ fn total_project_spend_unaffected_by_project_edits() -> Result<()> {
             end_date: project.end_date,
             budget_cents: project.budget_cents,
             actual_cents: project.actual_cents,
+            parent_id: None,
         },
     )?;
 
@@ -1126,6 +1305,7 @@ fn list_open_incidents_prioritizes_severity_and_skips_deleted() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     let whenever_id = store.create_incident(&NewIncident {
         title: "Cracked tile".to_owned(),
@@ -1139,6 +1319,7 @@ fn list_open_incidents_prioritizes_severity_and_skips_deleted() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     let deleted_id = store.create_incident(&NewIncident {
         title: "Fixed fence".to_owned(),
@@ -1152,6 +1333,7 @@ fn list_open_incidents_prioritizes_severity_and_skips_deleted() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.soft_delete_incident(deleted_id)?;
 
@@ -1189,6 +1371,85 @@ fn document_blob_round_trip_and_cache_extract() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn encrypted_documents_need_the_passphrase_and_survive_a_rekey() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let db_path = temp_dir.path().join("micasa.db");
+    let store = Store::open(&db_path)?;
+    store.bootstrap()?;
+    let document = |title: &str, data: &[u8]| NewDocument {
+        title: title.to_owned(),
+        file_name: format!("{}.pdf", title.to_lowercase()),
+        entity_kind: DocumentEntityKind::Project,
+        entity_id: 7,
+        mime_type: "application/pdf".to_owned(),
+        data: data.to_vec(),
+        notes: String::new(),
+    };
+    let stored_bytes = |store: &Store, id: i64| -> Result<Vec<u8>> {
+        Ok(store.raw_connection().query_row(
+            "SELECT data FROM documents WHERE id = ?",
+            rusqlite::params![id],
+            |row| row.get(0),
+        )?)
+    };
+    let policy = store.insert_document(&document("Policy", b"policy number 42"))?;
+    assert!(!store.documents_encrypted()?);
+    store.set_document_text(policy, "policy number 42")?;
+    store.replace_document_chunks(
+        policy,
+        "embed",
+        &[("policy number 42".to_owned(), vec![1.0])],
+    )?;
+    let cached = store.extract_document(policy)?;
+    assert!(cached.exists());
+
+    assert_eq!(store.encrypt_documents("first secret")?, 1);
+    assert!(store.documents_encrypted()?);
+    assert!(!store.documents_locked()?);
+    assert_eq!(store.list_documents(false)?[0].extracted_text, "");
+    assert!(store.document_chunks("embed")?.is_empty());
+    assert!(!cached.exists(), "the decrypted copy is removed");
+    let error = store
+        .set_document_text(policy, "policy number 42")
+        .expect_err("no plaintext text");
+    assert!(error.to_string().contains("encrypted"), "{error}");
+    let sealed = stored_bytes(&store, policy.get())?;
+    assert!(!sealed.windows(6).any(|window| window == b"number"));
+    assert_eq!(store.get_document(policy)?.data, b"policy number 42");
+    let photo = store.insert_document(&document("Photo", b"leak photo"))?;
+    assert_ne!(stored_bytes(&store, photo.get())?, b"leak photo");
+    let error = store.encrypt_documents("again").expect_err("already on");
+    assert!(error.to_string().contains("--rekey-documents"));
+
+    let locked = Store::open(&db_path)?;
+    assert!(locked.documents_locked()?);
+    let error = locked.get_document(policy).expect_err("locked");
+    assert!(error.to_string().contains("enter the passphrase"));
+    assert!(locked.insert_document(&document("Deed", b"deed")).is_err());
+    assert_eq!(
+        locked.list_documents(false)?.len(),
+        2,
+        "titles stay readable"
+    );
+    assert!(!locked.unlock_documents("wrong")?);
+    assert!(locked.unlock_documents("first secret")?);
+    assert_eq!(locked.get_document(photo)?.data, b"leak photo");
+
+    let error = store
+        .rekey_documents("wrong", "second secret")
+        .expect_err("wrong current passphrase");
+    assert!(error.to_string().contains("nothing was changed"));
+    assert_eq!(store.rekey_documents("first secret", "second secret")?, 2);
+    assert_eq!(store.get_document(policy)?.data, b"policy number 42");
+
+    let reopened = Store::open(&db_path)?;
+    assert!(!reopened.unlock_documents("first secret")?);
+    assert!(reopened.unlock_documents("second secret")?);
+    assert_eq!(reopened.get_document(photo)?.data, b"leak photo");
+    Ok(())
+}
+
 #[test]
 fn document_cache_extract_refreshes_existing_cache_file() -> Result<()> {
     let store = Store::open_memory()?;
@@ -1259,6 +1520,78 @@ fn extract_document_fails_actionably_for_empty_blob() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn relink_document_checks_target_and_lists_live_targets() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let project_type_id = store.list_project_types()?[0].id;
+    let new_project = |title: &str| NewProject {
+        title: title.to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    };
+    let roof_id = store.create_project(&new_project("Roof"))?;
+    let deck_id = store.create_project(&new_project("Deck"))?;
+    let gone_id = store.create_project(&new_project("Abandoned shed"))?;
+    store.soft_delete_project(gone_id)?;
+
+    let document_id = store.insert_document(&NewDocument {
+        title: "Scan".to_owned(),
+        file_name: "scan.pdf".to_owned(),
+        entity_kind: DocumentEntityKind::None,
+        entity_id: 0,
+        mime_type: "application/pdf".to_owned(),
+        data: b"scan".to_vec(),
+        notes: String::new(),
+    })?;
+
+    assert_eq!(
+        store.list_link_targets(DocumentEntityKind::Project)?,
+        vec![
+            (deck_id.get(), "Deck".to_owned()),
+            (roof_id.get(), "Roof".to_owned())
+        ]
+    );
+    assert!(
+        store
+            .list_link_targets(DocumentEntityKind::None)?
+            .is_empty()
+    );
+
+    store.relink_document(document_id, DocumentEntityKind::Project, roof_id.get())?;
+    assert_eq!(
+        store.document_link(document_id)?,
+        (DocumentEntityKind::Project, roof_id.get())
+    );
+
+    let error = store
+        .relink_document(document_id, DocumentEntityKind::Project, gone_id.get())
+        .expect_err("deleted target should be rejected");
+    assert!(error.to_string().contains("restore it first"));
+    let error = store
+        .relink_document(document_id, DocumentEntityKind::Vendor, 999)
+        .expect_err("missing target should be rejected");
+    assert!(error.to_string().contains("no longer exists"));
+    assert_eq!(
+        store.document_link(document_id)?,
+        (DocumentEntityKind::Project, roof_id.get())
+    );
+
+    store.relink_document(document_id, DocumentEntityKind::None, roof_id.get())?;
+    assert_eq!(
+        store.document_link(document_id)?,
+        (DocumentEntityKind::None, 0)
+    );
+    Ok(())
+}
+
 #[test]
 fn deleting_project_with_documents_is_allowed_and_preserves_document_rows() -> Result<()> {
     let store = Store::open_memory()?;
@@ -1274,6 +1607,7 @@ fn deleting_project_with_documents_is_allowed_and_preserves_document_rows() -> R
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     let document_id = store.insert_document(&NewDocument {
@@ -1343,6 +1677,9 @@ fn deleting_vendor_with_documents_is_allowed_and_preserves_document_rows() -> Re
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let document_id = store.insert_document(&NewDocument {
@@ -1379,6 +1716,7 @@ fn deleting_quote_with_documents_is_allowed_and_preserves_document_rows() -> Res
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Quote vendor".to_owned(),
@@ -1387,6 +1725,9 @@ fn deleting_quote_with_documents_is_allowed_and_preserves_document_rows() -> Res
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -1419,91 +1760,328 @@ fn deleting_quote_with_documents_is_allowed_and_preserves_document_rows() -> Res
 }
 
 #[test]
-fn deleting_maintenance_with_documents_is_allowed_and_preserves_document_rows() -> Result<()> {
+fn project_rollups_count_quotes_docs_and_sum_accepted_totals() -> Result<()> {
     let store = Store::open_memory()?;
     store.bootstrap()?;
 
-    let category_id = store.list_maintenance_categories()?[0].id;
-    let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
-        name: "Sump pump check".to_owned(),
-        category_id,
-        appliance_id: None,
-        last_serviced_at: None,
-        interval_months: 12,
-        manual_url: String::new(),
-        manual_text: String::new(),
+    let project_type_id = store.list_project_types()?[0].id;
+    let project = |title: &str| NewProject {
+        title: title.to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    };
+    let roof_id = store.create_project(&project("Roof"))?;
+    let fence_id = store.create_project(&project("Fence"))?;
+    let idle_id = store.create_project(&project("Idle"))?;
+    let vendor_id = store.create_vendor(&NewVendor {
+        name: "Rollup vendor".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
         notes: String::new(),
-        cost_cents: None,
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
-
-    let document_id = store.insert_document(&NewDocument {
-        title: "Maintenance checklist".to_owned(),
-        file_name: "checklist.txt".to_owned(),
-        entity_kind: DocumentEntityKind::Maintenance,
-        entity_id: maintenance_id.get(),
-        mime_type: "text/plain".to_owned(),
-        data: b"checklist".to_vec(),
+    let quote = |project_id, total_cents| NewQuote {
+        project_id,
+        vendor_id,
+        total_cents,
+        labor_cents: None,
+        materials_cents: None,
+        other_cents: None,
+        received_date: None,
         notes: String::new(),
-    })?;
+    };
+    let roof_low = store.create_quote(&quote(roof_id, 800_000))?;
+    let roof_high = store.create_quote(&quote(roof_id, 1_200_000))?;
+    let roof_gutters = store.create_quote(&quote(roof_id, 90_000))?;
+    let dropped = store.create_quote(&quote(roof_id, 50_000))?;
+    store.create_quote(&quote(fence_id, 300_000))?;
+
+    store.set_quote_accepted(roof_low, true)?;
+    store.set_quote_accepted(roof_high, true)?;
+    store.set_quote_accepted(roof_high, false)?;
+    store.set_quote_accepted(roof_gutters, true)?;
+    store.set_quote_accepted(dropped, true)?;
+    store.soft_delete_quote(dropped)?;
+
+    for (entity_kind, entity_id) in [
+        (DocumentEntityKind::Project, roof_id.get()),
+        (DocumentEntityKind::Project, roof_id.get()),
+        (DocumentEntityKind::Quote, roof_low.get()),
+    ] {
+        store.insert_document(&NewDocument {
+            title: "Scan".to_owned(),
+            file_name: "scan.pdf".to_owned(),
+            entity_kind,
+            entity_id,
+            mime_type: "application/pdf".to_owned(),
+            data: b"scan".to_vec(),
+            notes: String::new(),
+        })?;
+    }
 
-    store.soft_delete_maintenance_item(maintenance_id)?;
-    let documents = store.list_documents(false)?;
-    assert_eq!(documents.len(), 1);
-    assert_eq!(documents[0].id, document_id);
-    assert_eq!(documents[0].entity_id, maintenance_id.get());
-    assert_eq!(documents[0].entity_kind, DocumentEntityKind::Maintenance);
+    let rollups = store.project_rollups()?;
+    let roof = rollups[&roof_id];
+    assert_eq!(roof.quote_count, 3);
+    assert_eq!(roof.accepted_cents, Some(890_000));
+    assert_eq!(roof.doc_count, 2);
+    let fence = rollups[&fence_id];
+    assert_eq!(fence.quote_count, 1);
+    assert_eq!(fence.accepted_cents, None);
+    assert_eq!(fence.doc_count, 0);
+    assert!(!rollups.contains_key(&idle_id));
+
+    let accepted = store
+        .list_quotes(false)?
+        .into_iter()
+        .filter(|quote| quote.accepted_at.is_some())
+        .map(|quote| quote.id)
+        .collect::<BTreeSet<_>>();
+    assert_eq!(accepted, BTreeSet::from([roof_low, roof_gutters]));
+
+    let error = store
+        .set_quote_accepted(dropped, false)
+        .expect_err("deleted quote should not change");
+    assert!(error.to_string().contains("not found or deleted"));
     Ok(())
 }
 
 #[test]
-fn deleting_service_log_with_documents_is_allowed_and_preserves_document_rows() -> Result<()> {
+fn drill_counts_skip_deleted_rows() -> Result<()> {
     let store = Store::open_memory()?;
     store.bootstrap()?;
 
+    let appliance_id = store.create_appliance(&NewAppliance {
+        name: "Furnace".to_owned(),
+        brand: String::new(),
+        model_number: String::new(),
+        serial_number: String::new(),
+        purchase_date: None,
+        warranty_expiry: None,
+        location: String::new(),
+        cost_cents: None,
+        notes: String::new(),
+    })?;
+    let idle_appliance_id = store.create_appliance(&NewAppliance {
+        name: "Idle".to_owned(),
+        brand: String::new(),
+        model_number: String::new(),
+        serial_number: String::new(),
+        purchase_date: None,
+        warranty_expiry: None,
+        location: String::new(),
+        cost_cents: None,
+        notes: String::new(),
+    })?;
     let category_id = store.list_maintenance_categories()?[0].id;
-    let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
-        name: "Drain line flush".to_owned(),
+    let maintenance = |name: &str| NewMaintenanceItem {
+        name: name.to_owned(),
         category_id,
-        appliance_id: None,
+        appliance_id: Some(appliance_id),
         last_serviced_at: None,
-        interval_months: 6,
+        interval_months: 12,
         manual_url: String::new(),
         manual_text: String::new(),
         notes: String::new(),
         cost_cents: None,
-    })?;
-    let service_log_id = store.create_service_log_entry(&NewServiceLogEntry {
-        maintenance_item_id: maintenance_id,
-        serviced_at: Date::from_calendar_date(2026, Month::May, 1)?,
-        vendor_id: None,
-        cost_cents: None,
-        notes: String::new(),
-    })?;
+    };
+    let filter_id = store.create_maintenance_item(&maintenance("Filter"))?;
+    let dropped_item = store.create_maintenance_item(&maintenance("Dropped"))?;
+    store.soft_delete_maintenance_item(dropped_item)?;
 
-    let document_id = store.insert_document(&NewDocument {
-        title: "Service receipt".to_owned(),
-        file_name: "receipt.pdf".to_owned(),
-        entity_kind: DocumentEntityKind::ServiceLog,
-        entity_id: service_log_id.get(),
-        mime_type: "application/pdf".to_owned(),
-        data: b"receipt".to_vec(),
+    let vendor_id = store.create_vendor(&NewVendor {
+        name: "Counts vendor".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
+    let service = |day| -> Result<NewServiceLogEntry> {
+        Ok(NewServiceLogEntry {
+            maintenance_item_id: filter_id,
+            serviced_at: Date::from_calendar_date(2026, Month::January, day)?,
+            vendor_id: Some(vendor_id),
+            cost_cents: None,
+            notes: String::new(),
+        })
+    };
+    store.create_service_log_entry(&service(2)?)?;
+    store.create_service_log_entry(&service(3)?)?;
+    let undone = store.create_service_log_entry(&service(4)?)?;
+    store.soft_delete_service_log_entry(undone)?;
+
+    let project_type_id = store.list_project_types()?[0].id;
+    let project_id = store.create_project(&NewProject {
+        title: "Counts".to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    })?;
+    store.create_quote(&NewQuote {
+        project_id,
+        vendor_id,
+        total_cents: 10_000,
+        labor_cents: None,
+        materials_cents: None,
+        other_cents: None,
+        received_date: None,
+        notes: String::new(),
+    })?;
+
+    let incident_id = store.create_incident(&NewIncident {
+        title: "Drip".to_owned(),
+        description: String::new(),
+        status: IncidentStatus::Open,
+        severity: IncidentSeverity::Soon,
+        date_noticed: Date::from_calendar_date(2026, Month::January, 5)?,
+        date_resolved: None,
+        location: String::new(),
+        cost_cents: None,
+        appliance_id: Some(appliance_id),
+        vendor_id: None,
+        notes: String::new(),
+        follow_up_date: None,
+    })?;
+    let mut doc_ids = Vec::new();
+    for (entity_kind, entity_id) in [
+        (DocumentEntityKind::Appliance, appliance_id.get()),
+        (DocumentEntityKind::Incident, incident_id.get()),
+        (DocumentEntityKind::Incident, incident_id.get()),
+    ] {
+        doc_ids.push(store.insert_document(&NewDocument {
+            title: "Photo".to_owned(),
+            file_name: "photo.jpg".to_owned(),
+            entity_kind,
+            entity_id,
+            mime_type: "image/jpeg".to_owned(),
+            data: b"photo".to_vec(),
+            notes: String::new(),
+        })?);
+    }
+    store.soft_delete_document(doc_ids[2])?;
+
+    let appliances = store.appliance_rollups()?;
+    assert_eq!(appliances[&appliance_id].maintenance_count, 1);
+    assert_eq!(appliances[&appliance_id].doc_count, 1);
+    assert!(!appliances.contains_key(&idle_appliance_id));
+
+    let vendors = store.vendor_rollups()?;
+    assert_eq!(vendors[&vendor_id].quote_count, 1);
+    assert_eq!(vendors[&vendor_id].job_count, 2);
+
+    let log_counts = store.service_log_counts_by_maintenance()?;
+    assert_eq!(log_counts.get(&filter_id), Some(&2));
+    assert!(!log_counts.contains_key(&dropped_item));
+
+    let incident_docs = store.document_counts_by_incident()?;
+    assert_eq!(incident_docs.get(&incident_id), Some(&1));
+    Ok(())
+}
+
+#[test]
+fn deleting_maintenance_with_documents_is_allowed_and_preserves_document_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Sump pump check".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 12,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+
+    let document_id = store.insert_document(&NewDocument {
+        title: "Maintenance checklist".to_owned(),
+        file_name: "checklist.txt".to_owned(),
+        entity_kind: DocumentEntityKind::Maintenance,
+        entity_id: maintenance_id.get(),
+        mime_type: "text/plain".to_owned(),
+        data: b"checklist".to_vec(),
+        notes: String::new(),
+    })?;
+
+    store.soft_delete_maintenance_item(maintenance_id)?;
+    let documents = store.list_documents(false)?;
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].id, document_id);
+    assert_eq!(documents[0].entity_id, maintenance_id.get());
+    assert_eq!(documents[0].entity_kind, DocumentEntityKind::Maintenance);
+    Ok(())
+}
+
+#[test]
+fn deleting_service_log_with_documents_is_allowed_and_preserves_document_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Drain line flush".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 6,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let service_log_id = store.create_service_log_entry(&NewServiceLogEntry {
+        maintenance_item_id: maintenance_id,
+        serviced_at: Date::from_calendar_date(2026, Month::May, 1)?,
+        vendor_id: None,
+        cost_cents: None,
+        notes: String::new(),
+    })?;
+
+    let document_id = store.insert_document(&NewDocument {
+        title: "Service receipt".to_owned(),
+        file_name: "receipt.pdf".to_owned(),
+        entity_kind: DocumentEntityKind::ServiceLog,
+        entity_id: service_log_id.get(),
+        mime_type: "application/pdf".to_owned(),
+        data: b"receipt".to_vec(),
+        notes: String::new(),
+    })?;
+
+    store.soft_delete_service_log_entry(service_log_id)?;
+    let documents = store.list_documents(false)?;
+    assert_eq!(documents.len(), 1);
+    assert_eq!(documents[0].id, document_id);
+    assert_eq!(documents[0].entity_id, service_log_id.get());
+    assert_eq!(documents[0].entity_kind, DocumentEntityKind::ServiceLog);
+    Ok(())
+}
+
+#[test]
+fn settings_round_trip_and_defaults_via_generic_api() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
 
-    store.soft_delete_service_log_entry(service_log_id)?;
-    let documents = store.list_documents(false)?;
-    assert_eq!(documents.len(), 1);
-    assert_eq!(documents[0].id, document_id);
-    assert_eq!(documents[0].entity_id, service_log_id.get());
-    assert_eq!(documents[0].entity_kind, DocumentEntityKind::ServiceLog);
-    Ok(())
-}
-
-#[test]
-fn settings_round_trip_and_defaults_via_generic_api() -> Result<()> {
-    let store = Store::open_memory()?;
-    store.bootstrap()?;
-
     assert_eq!(store.get_setting(SettingKey::UiShowDashboard)?, None);
     assert_eq!(store.get_setting(SettingKey::LlmModel)?, None);
 
@@ -1832,6 +2410,9 @@ fn vendor_crud_and_delete_guards() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let vendors = store.list_vendors(false)?;
@@ -1847,6 +2428,9 @@ fn vendor_crud_and_delete_guards() -> Result<()> {
             phone: "555-0000".to_owned(),
             website: "https://example.com".to_owned(),
             notes: "Preferred".to_owned(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
         },
     )?;
     let vendors = store.list_vendors(false)?;
@@ -1862,6 +2446,7 @@ fn vendor_crud_and_delete_guards() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -1898,6 +2483,9 @@ fn vendor_deletion_record_is_created_and_cleared_on_restore() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     store.soft_delete_vendor(vendor_id)?;
@@ -1942,6 +2530,7 @@ fn quote_restore_requires_live_parents() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Vendor".to_owned(),
@@ -1950,6 +2539,9 @@ fn quote_restore_requires_live_parents() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let quote_id = store.create_quote(&NewQuote {
@@ -1995,6 +2587,7 @@ fn delete_project_blocked_by_active_quotes() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Quote Vendor".to_owned(),
@@ -2003,6 +2596,9 @@ fn delete_project_blocked_by_active_quotes() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -2044,6 +2640,7 @@ fn partial_quote_deletion_still_blocks_project_delete() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Shared Vendor".to_owned(),
@@ -2052,6 +2649,9 @@ fn partial_quote_deletion_still_blocks_project_delete() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let first_quote = store.create_quote(&NewQuote {
@@ -2105,6 +2705,7 @@ fn restore_quote_blocked_by_deleted_vendor() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Doomed Quote Vendor".to_owned(),
@@ -2113,6 +2714,9 @@ fn restore_quote_blocked_by_deleted_vendor() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -2153,6 +2757,7 @@ fn typed_lifecycle_api_soft_delete_and_restore_project() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.soft_delete(LifecycleEntityRef::Project(project_id))?;
@@ -2181,6 +2786,7 @@ fn project_deletion_record_is_created_and_cleared_on_restore() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.soft_delete_project(project_id)?;
@@ -2225,6 +2831,7 @@ fn latest_active_project_deletion_record_tracks_recent_unrestored_target() -> Re
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let second = store.create_project(&NewProject {
         title: "Second".to_owned(),
@@ -2235,6 +2842,7 @@ fn latest_active_project_deletion_record_tracks_recent_unrestored_target() -> Re
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.soft_delete_project(first)?;
@@ -2283,6 +2891,7 @@ fn active_deletion_records_remain_entity_scoped() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Scoped vendor".to_owned(),
@@ -2291,6 +2900,9 @@ fn active_deletion_records_remain_entity_scoped() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     store.soft_delete_project(project_id)?;
@@ -2326,6 +2938,7 @@ fn typed_lifecycle_api_restore_guard_for_quote_parent() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Guard Vendor".to_owned(),
@@ -2334,6 +2947,9 @@ fn typed_lifecycle_api_restore_guard_for_quote_parent() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -2460,6 +3076,9 @@ fn incident_crud_and_restore_parent_guards() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let incident_id = store.create_incident(&NewIncident {
@@ -2474,6 +3093,7 @@ fn incident_crud_and_restore_parent_guards() -> Result<()> {
         appliance_id: Some(appliance_id),
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     let incidents = store.list_incidents(false)?;
@@ -2519,6 +3139,9 @@ fn incident_update_persists_fields_and_optional_parent_links() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let incident_id = store.create_incident(&NewIncident {
         title: "Leak".to_owned(),
@@ -2532,6 +3155,7 @@ fn incident_update_persists_fields_and_optional_parent_links() -> Result<()> {
         appliance_id: Some(appliance_id),
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     store.update_incident(
@@ -2548,6 +3172,7 @@ fn incident_update_persists_fields_and_optional_parent_links() -> Result<()> {
             appliance_id: None,
             vendor_id: None,
             notes: "completed".to_owned(),
+            follow_up_date: None,
         },
     )?;
 
@@ -2588,6 +3213,7 @@ fn incident_update_rejects_deleted_rows_with_actionable_error() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     store.soft_delete_incident(incident_id)?;
 
@@ -2606,6 +3232,7 @@ fn incident_update_rejects_deleted_rows_with_actionable_error() -> Result<()> {
                 appliance_id: None,
                 vendor_id: None,
                 notes: String::new(),
+                follow_up_date: None,
             },
         )
         .expect_err("updating a deleted incident should fail");
@@ -2642,6 +3269,7 @@ fn incident_restore_blocked_by_deleted_appliance() -> Result<()> {
         appliance_id: Some(appliance_id),
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     store.soft_delete_incident(incident_id)?;
@@ -2669,6 +3297,9 @@ fn incident_restore_blocked_by_deleted_vendor() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let incident_id = store.create_incident(&NewIncident {
@@ -2683,6 +3314,7 @@ fn incident_restore_blocked_by_deleted_vendor() -> Result<()> {
         appliance_id: None,
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     store.soft_delete_incident(incident_id)?;
@@ -2710,6 +3342,9 @@ fn delete_vendor_blocked_by_active_incident() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let incident_id = store.create_incident(&NewIncident {
         title: "Clogged drain".to_owned(),
@@ -2723,6 +3358,7 @@ fn delete_vendor_blocked_by_active_incident() -> Result<()> {
         appliance_id: None,
         vendor_id: Some(vendor_id),
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     let delete_error = store
@@ -2763,6 +3399,7 @@ fn delete_appliance_blocked_by_active_incident() -> Result<()> {
         appliance_id: Some(appliance_id),
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     let delete_error = store
@@ -2792,6 +3429,7 @@ fn incident_restore_allowed_without_appliance_or_vendor() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
 
     store.soft_delete_incident(incident_id)?;
@@ -2819,6 +3457,7 @@ fn deleting_incident_with_documents_is_allowed_and_preserves_document_rows() ->
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Pipe photo".to_owned(),
@@ -2855,6 +3494,7 @@ fn project_update_persists_fields() -> Result<()> {
         end_date: None,
         budget_cents: Some(120_000),
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.update_project(
@@ -2868,6 +3508,7 @@ fn project_update_persists_fields() -> Result<()> {
             end_date: Some(Date::from_calendar_date(2026, Month::March, 1)?),
             budget_cents: Some(150_000),
             actual_cents: Some(90_000),
+            parent_id: None,
         },
     )?;
 
@@ -2893,6 +3534,7 @@ fn quote_update_persists_fields() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let first_vendor_id = store.create_vendor(&NewVendor {
         name: "Acme Corp".to_owned(),
@@ -2901,6 +3543,9 @@ fn quote_update_persists_fields() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let replacement_vendor_id = store.create_vendor(&NewVendor {
         name: "Acme Corp 2".to_owned(),
@@ -2909,6 +3554,9 @@ fn quote_update_persists_fields() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     let quote_id = store.create_quote(&NewQuote {
@@ -2954,6 +3602,57 @@ fn quote_update_persists_fields() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn recall_checks_file_each_recall_once_and_stamp_the_appliance() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let appliance_id = store.create_appliance(&NewAppliance {
+        name: "Dishwasher".to_owned(),
+        brand: "Bosch".to_owned(),
+        model_number: "SHX878WD5N".to_owned(),
+        serial_number: String::new(),
+        purchase_date: None,
+        warranty_expiry: None,
+        location: "Kitchen".to_owned(),
+        cost_cents: None,
+        notes: String::new(),
+    })?;
+    let recall = Recall {
+        id: "26-042".to_owned(),
+        title: "Bosch dishwashers".to_owned(),
+        brand: "Bosch".to_owned(),
+        model_numbers: vec!["SHX878WD5N".to_owned()],
+        hazard: "Wiring can overheat.".to_owned(),
+        remedy: "Free repair.".to_owned(),
+        url: "https://example.test/26-042".to_owned(),
+    };
+
+    let created = store.record_recall_check(appliance_id, std::slice::from_ref(&recall))?;
+    assert_eq!(created.len(), 1);
+    let again = store.record_recall_check(appliance_id, &[recall])?;
+    assert!(again.is_empty(), "a filed recall is not filed twice");
+
+    let incidents = store.list_incidents(false)?;
+    assert_eq!(incidents.len(), 1);
+    let incident = &incidents[0];
+    assert_eq!(incident.id, created[0]);
+    assert_eq!(incident.title, "Recall: Bosch dishwashers");
+    assert_eq!(incident.status, IncidentStatus::Open);
+    assert_eq!(incident.appliance_id, Some(appliance_id));
+    assert_eq!(incident.location, "Kitchen");
+    assert_eq!(incident.description, "Wiring can overheat.\nFree repair.");
+    assert!(incident.notes.contains("https://example.test/26-042"));
+
+    let appliance = store
+        .list_appliances(false)?
+        .into_iter()
+        .find(|entry| entry.id == appliance_id)
+        .expect("appliance exists");
+    assert!(appliance.recall_checked_at.is_some());
+    Ok(())
+}
+
 #[test]
 fn appliance_update_persists_fields() -> Result<()> {
     let store = Store::open_memory()?;
@@ -3092,6 +3791,20 @@ fn create_house_profile_enforces_single_record() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn active_house_name_is_the_profile_nickname_once_set() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    assert_eq!(store.active_house_name()?, None);
+
+    store.create_house_profile(&house_profile_input("  ", "Portland"))?;
+    assert_eq!(store.active_house_name()?, None);
+
+    store.update_house_profile(&house_profile_input(" Maple St ", "Portland"))?;
+    assert_eq!(store.active_house_name()?.as_deref(), Some("Maple St"));
+    Ok(())
+}
+
 #[test]
 fn update_house_profile_requires_existing_row_then_persists_changes() -> Result<()> {
     let store = Store::open_memory()?;
@@ -3117,6 +3830,50 @@ fn update_house_profile_requires_existing_row_then_persists_changes() -> Result<
     Ok(())
 }
 
+#[test]
+fn house_location_survives_profile_updates_and_caches_forecast() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let missing_error = store
+        .set_house_location(Some((45.52, -122.68)))
+        .expect_err("location needs a profile");
+    assert!(
+        missing_error
+            .to_string()
+            .contains("house profile not found")
+    );
+
+    store.create_house_profile(&house_profile_input("Primary Residence", "Portland"))?;
+    store.set_house_location(Some((45.52, -122.68)))?;
+    store.update_house_profile(&house_profile_input("Primary Residence", "Seattle"))?;
+    let profile = store.get_house_profile()?.expect("profile exists");
+    assert_eq!(
+        (profile.latitude, profile.longitude),
+        (Some(45.52), Some(-122.68))
+    );
+    assert!(store.set_house_location(Some((95.0, 0.0))).is_err());
+    store.set_house_location(None)?;
+    let profile = store.get_house_profile()?.expect("profile exists");
+    assert_eq!((profile.latitude, profile.longitude), (None, None));
+
+    assert_eq!(store.weather_fetched_at()?, None);
+    let day = |day: u8, min_temp_c: f64| ForecastDay {
+        day: Date::from_calendar_date(2026, Month::January, day).expect("valid date"),
+        min_temp_c,
+        max_temp_c: 5.0,
+        precipitation_mm: 0.0,
+        wind_kmh: 10.0,
+    };
+    store.replace_weather_forecast(&[day(4, 1.0), day(5, -3.0), day(6, 2.0)])?;
+    store.replace_weather_forecast(&[day(6, 2.0), day(5, -3.0)])?;
+    let cached = store
+        .weather_forecast(Date::from_calendar_date(2026, Month::January, 5).expect("valid date"))?;
+    assert_eq!(cached, vec![day(5, -3.0), day(6, 2.0)]);
+    assert!(store.weather_fetched_at()?.is_some());
+    Ok(())
+}
+
 #[test]
 fn house_profile_upsert_and_update() -> Result<()> {
     let store = Store::open_memory()?;
@@ -3272,6 +4029,9 @@ fn unicode_round_trip_vendor_names() -> Result<()> {
             phone: String::new(),
             website: String::new(),
             notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
         })?;
     }
 
@@ -3306,6 +4066,7 @@ fn unicode_round_trip_project_description() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     let project = store.get_project(project_id)?;
@@ -3331,6 +4092,7 @@ fn soft_deleted_project_persists_across_reopen() -> Result<()> {
             end_date: None,
             budget_cents: None,
             actual_cents: None,
+            parent_id: None,
         })?;
         store.soft_delete_project(project_id)?;
         project_id
@@ -3377,6 +4139,9 @@ fn service_log_crud_and_restore_parent_guards() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let category_id = store.list_maintenance_categories()?[0].id;
     let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
@@ -3464,6 +4229,9 @@ fn service_log_update_can_assign_vendor() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let category_id = store.list_maintenance_categories()?[0].id;
     let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
@@ -3516,6 +4284,9 @@ fn service_log_update_can_clear_vendor_link() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let category_id = store.list_maintenance_categories()?[0].id;
     let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
@@ -3705,6 +4476,9 @@ fn restore_service_log_allowed_without_vendor_link() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let category_id = store.list_maintenance_categories()?[0].id;
     let maintenance_id = store.create_maintenance_item(&NewMaintenanceItem {
@@ -3956,6 +4730,7 @@ fn list_quotes_filtered_by_vendor_via_typed_list() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_a = store.create_vendor(&NewVendor {
         name: "TestVendor".to_owned(),
@@ -3964,6 +4739,9 @@ fn list_quotes_filtered_by_vendor_via_typed_list() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let vendor_b = store.create_vendor(&NewVendor {
         name: "OtherVendor".to_owned(),
@@ -3972,6 +4750,9 @@ fn list_quotes_filtered_by_vendor_via_typed_list() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     store.create_quote(&NewQuote {
@@ -4020,6 +4801,7 @@ fn list_quotes_filtered_by_project_via_typed_list() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let project_b = store.create_project(&NewProject {
         title: "P2".to_owned(),
@@ -4030,6 +4812,7 @@ fn list_quotes_filtered_by_project_via_typed_list() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "V1".to_owned(),
@@ -4038,6 +4821,9 @@ fn list_quotes_filtered_by_project_via_typed_list() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
 
     store.create_quote(&NewQuote {
@@ -4086,6 +4872,7 @@ fn count_quotes_by_project_via_typed_list_filtering() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_a = store.create_vendor(&NewVendor {
         name: "V1".to_owned(),
@@ -4094,6 +4881,9 @@ fn count_quotes_by_project_via_typed_list_filtering() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let vendor_b = store.create_vendor(&NewVendor {
         name: "V2".to_owned(),
@@ -4102,6 +4892,9 @@ fn count_quotes_by_project_via_typed_list_filtering() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     store.create_quote(&NewQuote {
         project_id,
@@ -4145,6 +4938,9 @@ fn count_quotes_by_vendor_api_matches_go_semantics() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let project_type_id = store.list_project_types()?[0].id;
     let project_id = store.create_project(&NewProject {
@@ -4156,6 +4952,7 @@ fn count_quotes_by_vendor_api_matches_go_semantics() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     for _ in 0..2 {
         store.create_quote(&NewQuote {
@@ -4193,6 +4990,7 @@ fn count_quotes_by_project_api_matches_go_semantics() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_a = store.create_vendor(&NewVendor {
         name: "V1".to_owned(),
@@ -4201,6 +4999,9 @@ fn count_quotes_by_project_api_matches_go_semantics() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let vendor_b = store.create_vendor(&NewVendor {
         name: "V2".to_owned(),
@@ -4209,6 +5010,9 @@ fn count_quotes_by_project_api_matches_go_semantics() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     store.create_quote(&NewQuote {
         project_id,
@@ -4251,6 +5055,9 @@ fn typed_fk_count_apis_exclude_soft_deleted_rows() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let project_type_id = store.list_project_types()?[0].id;
     let project_id = store.create_project(&NewProject {
@@ -4262,6 +5069,7 @@ fn typed_fk_count_apis_exclude_soft_deleted_rows() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let appliance_id = store.create_appliance(&NewAppliance {
         name: "Furnace".to_owned(),
@@ -4375,6 +5183,9 @@ fn list_and_count_service_logs_by_vendor_via_typed_list_filtering() -> Result<()
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let vendor_b = store.create_vendor(&NewVendor {
         name: "OtherVendor".to_owned(),
@@ -4383,6 +5194,9 @@ fn list_and_count_service_logs_by_vendor_via_typed_list_filtering() -> Result<()
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     store.create_service_log_entry(&NewServiceLogEntry {
         maintenance_item_id: maintenance_id,
@@ -4501,6 +5315,9 @@ fn vendor_quote_project_delete_restore_chain_enforces_parent_order() -> Result<(
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let project_type_id = store.list_project_types()?[0].id;
     let project_id = store.create_project(&NewProject {
@@ -4512,6 +5329,7 @@ fn vendor_quote_project_delete_restore_chain_enforces_parent_order() -> Result<(
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -4583,6 +5401,7 @@ fn document_metadata_round_trip_and_list_excludes_blob_data() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     let content = b"fake pdf content".to_vec();
@@ -4661,6 +5480,119 @@ fn document_soft_delete_restore_round_trip() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn document_chunks_replace_per_document_and_hide_deleted_and_private_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let insert = |title: &str| {
+        store.insert_document(&NewDocument {
+            title: title.to_owned(),
+            file_name: format!("{title}.pdf"),
+            entity_kind: DocumentEntityKind::None,
+            entity_id: 0,
+            mime_type: "application/pdf".to_owned(),
+            data: b"pdf".to_vec(),
+            notes: String::new(),
+        })
+    };
+    let manual = insert("Furnace manual")?;
+    let receipt = insert("Receipt")?;
+
+    store.replace_document_chunks(
+        manual,
+        "nomic",
+        &[
+            ("old text".to_owned(), vec![1.0, 0.0]),
+            ("stale".to_owned(), vec![0.0, 1.0]),
+        ],
+    )?;
+    store.replace_document_chunks(
+        manual,
+        "nomic",
+        &[("filter is 16x25".to_owned(), vec![0.5, 0.5])],
+    )?;
+    store.replace_document_chunks(receipt, "nomic", &[("paid $90".to_owned(), vec![0.0, 1.0])])?;
+    let chunks = store.document_chunks("nomic")?;
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|chunk| (chunk.title.as_str(), chunk.content.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Furnace manual", "filter is 16x25"),
+            ("Receipt", "paid $90")
+        ]
+    );
+    assert_eq!(chunks[0].embedding, vec![0.5, 0.5]);
+    assert!(store.document_chunks("other-model")?.is_empty());
+    assert!(
+        !store.table_names()?.contains(&"document_chunks".to_owned()),
+        "chat SQL never sees raw embeddings"
+    );
+
+    store.soft_delete_document(receipt)?;
+    store.set_row_private(LifecycleEntityRef::Document(manual), true)?;
+    store.shadow_private_rows()?;
+    assert!(store.document_chunks("nomic")?.is_empty());
+    store.clear_private_row_shadows()?;
+    assert_eq!(store.document_chunks("nomic")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn manual_chunks_replace_per_item_and_hide_deleted_items() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let insert = |name: &str| {
+        store.create_maintenance_item(&NewMaintenanceItem {
+            name: name.to_owned(),
+            category_id,
+            appliance_id: None,
+            last_serviced_at: None,
+            interval_months: 0,
+            manual_url: String::new(),
+            manual_text: "# Manual".to_owned(),
+            notes: String::new(),
+            cost_cents: None,
+        })
+    };
+    let furnace = insert("Furnace filter")?;
+    let gutters = insert("Gutters")?;
+
+    store.replace_manual_chunks(furnace, "nomic", &[("old".to_owned(), vec![1.0, 0.0])])?;
+    store.replace_manual_chunks(
+        furnace,
+        "nomic",
+        &[("use a 16x25 filter".to_owned(), vec![0.5, 0.5])],
+    )?;
+    store.replace_manual_chunks(
+        gutters,
+        "nomic",
+        &[("clear downspouts".to_owned(), vec![0.0, 1.0])],
+    )?;
+    let chunks = store.manual_chunks("nomic")?;
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|chunk| (chunk.title.as_str(), chunk.content.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("Furnace filter", "use a 16x25 filter"),
+            ("Gutters", "clear downspouts")
+        ]
+    );
+    assert_eq!(chunks[0].maintenance_item_id, furnace);
+    assert!(store.manual_chunks("other-model")?.is_empty());
+    assert!(!store.table_names()?.contains(&"manual_chunks".to_owned()));
+
+    store.soft_delete_maintenance_item(gutters)?;
+    assert_eq!(store.manual_chunks("nomic")?.len(), 1);
+    store.replace_manual_chunks(furnace, "nomic", &[])?;
+    assert!(store.manual_chunks("nomic")?.is_empty());
+    Ok(())
+}
+
 #[test]
 fn restore_document_blocked_by_deleted_project() -> Result<()> {
     let store = Store::open_memory()?;
@@ -4676,6 +5608,7 @@ fn restore_document_blocked_by_deleted_project() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Project Note".to_owned(),
@@ -4751,6 +5684,9 @@ fn restore_document_blocked_by_deleted_vendor() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Vendor Note".to_owned(),
@@ -4790,6 +5726,7 @@ fn restore_document_blocked_by_deleted_quote() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let vendor_id = store.create_vendor(&NewVendor {
         name: "Quote vendor".to_owned(),
@@ -4798,6 +5735,9 @@ fn restore_document_blocked_by_deleted_quote() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let quote_id = store.create_quote(&NewQuote {
         project_id,
@@ -4940,6 +5880,7 @@ fn restore_document_blocked_by_deleted_incident() -> Result<()> {
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Incident Note".to_owned(),
@@ -4979,6 +5920,7 @@ fn update_document_metadata_preserves_blob_and_link() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Invoice".to_owned(),
@@ -5172,6 +6114,7 @@ fn list_documents_for_entity_via_typed_filtering() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     let other_project_id = store.create_project(&NewProject {
         title: "Doc list other".to_owned(),
@@ -5182,6 +6125,7 @@ fn list_documents_for_entity_via_typed_filtering() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
     store.insert_document(&NewDocument {
         title: "Target doc".to_owned(),
@@ -5232,6 +6176,7 @@ fn list_documents_for_entity_include_deleted_via_typed_filtering() -> Result<()>
         appliance_id: None,
         vendor_id: None,
         notes: String::new(),
+        follow_up_date: None,
     })?;
     let document_id = store.insert_document(&NewDocument {
         title: "Incident doc".to_owned(),
@@ -5279,6 +6224,9 @@ fn count_documents_for_entity_via_typed_filtering() -> Result<()> {
         phone: String::new(),
         website: String::new(),
         notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
     })?;
     let project_type_id = store.list_project_types()?[0].id;
     let project_id = store.create_project(&NewProject {
@@ -5290,6 +6238,7 @@ fn count_documents_for_entity_via_typed_filtering() -> Result<()> {
         end_date: None,
         budget_cents: None,
         actual_cents: None,
+        parent_id: None,
     })?;
 
     store.insert_document(&NewDocument {
@@ -5626,3 +6575,2124 @@ fn seed_scaled_data_summary_matches_database_counts() -> Result<()> {
     assert_eq!(summary.service_logs, total_service_logs);
     Ok(())
 }
+
+fn private_test_project(store: &Store, title: &str) -> Result<micasa_app::ProjectId> {
+    let project_type_id = store.list_project_types()?[0].id;
+    store.create_project(&NewProject {
+        title: title.to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    })
+}
+
+#[test]
+fn private_rows_round_trip_and_reject_missing_targets() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let project_id = private_test_project(&store, "Safe Deposit")?;
+    let target = LifecycleEntityRef::Project(project_id);
+    assert!(!store.is_row_private(target)?);
+
+    store.set_row_private(target, true)?;
+    store.set_row_private(target, true)?;
+    assert!(store.is_row_private(target)?);
+    assert_eq!(
+        store.private_row_ids(micasa_app::DeletionEntity::Project)?,
+        BTreeSet::from([project_id.get()])
+    );
+    assert!(
+        store
+            .private_row_ids(micasa_app::DeletionEntity::Vendor)?
+            .is_empty()
+    );
+
+    store.set_row_private(target, false)?;
+    assert!(!store.is_row_private(target)?);
+
+    let error = store
+        .set_row_private(
+            LifecycleEntityRef::Project(micasa_app::ProjectId::new(9_999)),
+            true,
+        )
+        .expect_err("missing row should fail");
+    assert!(error.to_string().contains("not found"));
+    Ok(())
+}
+
+#[test]
+fn row_names_cover_deleted_rows_and_only_linked_tabs() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let deck = private_test_project(&store, "Deck stain")?;
+    store.soft_delete_project(deck)?;
+    let names = store.row_names(TabKind::Projects)?;
+    assert_eq!(
+        names.get(&deck.get()).map(String::as_str),
+        Some("Deck stain")
+    );
+    assert!(store.row_names(TabKind::Vendors)?.is_empty());
+
+    let error = store
+        .row_names(TabKind::Budget)
+        .expect_err("budget rows have no link names");
+    assert!(error.to_string().contains("no names to link by"));
+    Ok(())
+}
+
+#[test]
+fn search_row_names_puts_prefixes_first_and_skips_deleted_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let mut ids = Vec::new();
+    for name in [
+        "Acme Plumbing",
+        "Best Plumbers",
+        "plumb line co",
+        "Roofs R Us",
+    ] {
+        ids.push(store.create_vendor(&NewVendor {
+            name: name.to_owned(),
+            contact_name: String::new(),
+            email: String::new(),
+            phone: String::new(),
+            website: String::new(),
+            notes: String::new(),
+            license_number: String::new(),
+            insurance_expiry: None,
+            insurance_document_id: None,
+        })?);
+    }
+    store.soft_delete_vendor(ids[1])?;
+
+    let names = store
+        .search_row_names(TabKind::Vendors, " PLUMB ", 10)?
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["plumb line co", "Acme Plumbing"]);
+    assert_eq!(store.search_row_names(TabKind::Vendors, "", 2)?.len(), 2);
+    assert!(
+        store
+            .search_row_names(TabKind::Appliances, "x", 5)?
+            .is_empty()
+    );
+    assert!(store.search_row_names(TabKind::Budget, "x", 5).is_err());
+    Ok(())
+}
+
+#[test]
+fn tags_replace_per_row_and_drop_when_unused() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let deck = private_test_project(&store, "Deck stain")?;
+    let gutters = private_test_project(&store, "Gutter guards")?;
+    let tags = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| (*name).to_owned())
+            .collect::<Vec<_>>()
+    };
+    store.set_tags(
+        LifecycleEntityRef::Project(deck),
+        &tags(&["exterior", "summer"]),
+    )?;
+    store.set_tags(LifecycleEntityRef::Project(gutters), &tags(&["exterior"]))?;
+
+    let by_row = store.tags_by_row(micasa_app::DeletionEntity::Project)?;
+    assert_eq!(by_row[&deck.get()], tags(&["exterior", "summer"]));
+    assert_eq!(by_row[&gutters.get()], tags(&["exterior"]));
+    assert!(
+        store
+            .tags_by_row(micasa_app::DeletionEntity::Vendor)?
+            .is_empty()
+    );
+    let listed = store
+        .list_tags()?
+        .into_iter()
+        .map(|tag| (tag.name, tag.uses))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        listed,
+        vec![("exterior".to_owned(), 2), ("summer".to_owned(), 1)]
+    );
+
+    store.set_tags(LifecycleEntityRef::Project(deck), &[])?;
+    assert!(
+        !store
+            .tags_by_row(micasa_app::DeletionEntity::Project)?
+            .contains_key(&deck.get())
+    );
+    assert_eq!(store.list_tags()?.len(), 1, "summer is no longer used");
+
+    let missing = store
+        .set_tags(
+            LifecycleEntityRef::Project(micasa_app::ProjectId::new(9_999)),
+            &tags(&["exterior"]),
+        )
+        .expect_err("missing row should fail");
+    assert!(missing.to_string().contains("not found"));
+    let budget = store
+        .set_tags(
+            LifecycleEntityRef::Budget(micasa_app::BudgetId::new(1)),
+            &tags(&["exterior"]),
+        )
+        .expect_err("budgets can't be tagged");
+    assert!(budget.to_string().contains("can't be tagged"));
+    Ok(())
+}
+
+#[test]
+fn data_issues_flag_suspect_live_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    assert!(store.list_data_issues()?.is_empty());
+
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item = |name: &str, interval_months: i32| NewMaintenanceItem {
+        name: name.to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    };
+    store.create_maintenance_item(&item("Gutter clean", 6))?;
+    let never_due = store.create_maintenance_item(&item("Sump check", 0))?;
+
+    let project_id = private_test_project(&store, "Deck")?;
+    let vendor_id = store.create_vendor(&NewVendor {
+        name: "Acme".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
+        notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
+    })?;
+    let quote = |total_cents: i64, labor_cents: Option<i64>| NewQuote {
+        project_id,
+        vendor_id,
+        total_cents,
+        labor_cents,
+        materials_cents: Some(30_000),
+        other_cents: None,
+        received_date: None,
+        notes: String::new(),
+    };
+    store.create_quote(&quote(50_000, Some(20_000)))?;
+    let off = store.create_quote(&quote(50_000, Some(12_000)))?;
+    let deleted = store.create_quote(&quote(10, None))?;
+    store.soft_delete_quote(deleted)?;
+
+    store.raw_connection().execute(
+        "INSERT INTO incidents (id, title, status, severity, date_noticed, date_resolved)
+         VALUES (40, 'Roof leak', 'resolved', 'soon', '2026-03-10', '2026-03-01')",
+        [],
+    )?;
+    let document_id = store.insert_document(&NewDocument {
+        title: "Warranty".to_owned(),
+        file_name: "warranty.pdf".to_owned(),
+        entity_kind: DocumentEntityKind::Project,
+        entity_id: project_id.get(),
+        mime_type: "application/pdf".to_owned(),
+        data: b"%PDF".to_vec(),
+        notes: String::new(),
+    })?;
+    store.raw_connection().execute(
+        "UPDATE documents SET data = X'', size_bytes = 0 WHERE id = ?",
+        rusqlite::params![document_id.get()],
+    )?;
+
+    let issues = store.list_data_issues()?;
+    assert_eq!(
+        issues
+            .iter()
+            .map(|issue| (issue.kind, issue.row_id))
+            .collect::<Vec<_>>(),
+        vec![
+            (DataIssueKind::ZeroInterval, never_due.get()),
+            (DataIssueKind::QuoteParts, off.get()),
+            (DataIssueKind::ResolvedBeforeNoticed, 40),
+            (DataIssueKind::EmptyDocument, document_id.get()),
+        ]
+    );
+    assert_eq!(issues[1].title, "Deck / Acme");
+    assert_eq!(issues[1].detail, "parts $420.00 vs total $500.00");
+    assert_eq!(
+        issues[2].detail,
+        "resolved 2026-03-01 but noticed 2026-03-10"
+    );
+    assert_eq!(issues[3].detail, "warranty.pdf has no bytes");
+    Ok(())
+}
+
+#[test]
+fn recalculate_derived_resyncs_last_serviced_and_checksums() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Furnace filter".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: Some(Date::from_calendar_date(2026, Month::May, 1)?),
+        interval_months: 3,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let log = |serviced_at: Date| NewServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at,
+        vendor_id: None,
+        cost_cents: None,
+        notes: String::new(),
+    };
+    store.create_service_log_entry(&log(Date::from_calendar_date(2026, Month::February, 1)?))?;
+    // The May visit was logged in error and deleted, leaving the item's date
+    // ahead of its history.
+    let mistaken =
+        store.create_service_log_entry(&log(Date::from_calendar_date(2026, Month::May, 1)?))?;
+    store.soft_delete_service_log_entry(mistaken)?;
+
+    let project_id = private_test_project(&store, "Deck")?;
+    let document_id = store.insert_document(&NewDocument {
+        title: "Plan".to_owned(),
+        file_name: "plan.pdf".to_owned(),
+        entity_kind: DocumentEntityKind::Project,
+        entity_id: project_id.get(),
+        mime_type: "application/pdf".to_owned(),
+        data: b"%PDF".to_vec(),
+        notes: String::new(),
+    })?;
+    let checksum = store.get_document(document_id)?.checksum_sha256;
+    store.raw_connection().execute(
+        "UPDATE documents SET size_bytes = 99, sha256 = 'stale' WHERE id = ?",
+        rusqlite::params![document_id.get()],
+    )?;
+
+    let mut steps = Vec::new();
+    let summary = store.recalculate_derived(&mut |step, done, total| {
+        steps.push(format!("{step} {done}/{total}"));
+    })?;
+    assert_eq!(summary.last_serviced, 1);
+    assert_eq!(summary.document_checksums, 1);
+    assert_eq!(steps, vec!["last serviced 1/1", "documents 1/1"]);
+
+    let item = store
+        .list_maintenance_items(false)?
+        .into_iter()
+        .find(|item| item.id == item_id)
+        .expect("item listed");
+    assert_eq!(
+        item.last_serviced_at,
+        Some(Date::from_calendar_date(2026, Month::February, 1)?)
+    );
+    let document = store.get_document(document_id)?;
+    assert_eq!(document.size_bytes, 4);
+    assert_eq!(document.checksum_sha256, checksum);
+
+    let again = store.recalculate_derived(&mut |_, _, _| {})?;
+    assert_eq!(again, micasa_app::RecalcSummary::default());
+    Ok(())
+}
+
+#[test]
+fn changed_since_returns_touched_rows_and_every_live_id() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Gutters".to_owned(),
+        category_id: store.list_maintenance_categories()?[0].id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 6,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let serviced_at = Date::from_calendar_date(2026, Month::March, 1)?;
+    let log = NewServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at,
+        vendor_id: None,
+        cost_cents: None,
+        notes: String::new(),
+    };
+    let first = store.create_service_log_entry(&log)?;
+    let second = store.create_service_log_entry(&log)?;
+    store.raw_connection().execute(
+        "UPDATE service_log_entries SET updated_at = '2025-01-01T00:00:00.5Z'",
+        [],
+    )?;
+    let cursor = time::macros::datetime!(2025-01-01 00:00:00.7 UTC);
+
+    let changes = store.service_log_entries_changed_since(cursor, false)?;
+    assert_eq!(
+        changes.rows.len(),
+        2,
+        "rows in the cursor's second come back; merging them again is harmless"
+    );
+    store.raw_connection().execute(
+        "UPDATE service_log_entries SET updated_at = '2024-06-01T00:00:00Z'",
+        [],
+    )?;
+    assert!(
+        store
+            .service_log_entries_changed_since(cursor, false)?
+            .rows
+            .is_empty()
+    );
+
+    store.update_service_log_entry(
+        first,
+        &UpdateServiceLogEntry {
+            maintenance_item_id: item_id,
+            serviced_at,
+            vendor_id: None,
+            cost_cents: Some(12_000),
+            notes: String::new(),
+        },
+    )?;
+    store.soft_delete_service_log_entry(second)?;
+    let changes = store.service_log_entries_changed_since(cursor, false)?;
+    assert_eq!(
+        changes
+            .rows
+            .iter()
+            .map(|row| (row.id, row.cost_cents))
+            .collect::<Vec<_>>(),
+        vec![(first, Some(12_000))]
+    );
+    assert_eq!(changes.ids, BTreeSet::from([first.get()]));
+    assert_eq!(
+        store.service_log_entries_changed_since(cursor, true)?.ids,
+        BTreeSet::from([first.get(), second.get()])
+    );
+    Ok(())
+}
+
+#[test]
+fn service_log_pages_follow_list_order_and_skip_hidden_rows() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Furnace filter".to_owned(),
+        category_id: store.list_maintenance_categories()?[0].id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 3,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let mut ids = Vec::new();
+    for day in 1..=5 {
+        ids.push(store.create_service_log_entry(&NewServiceLogEntry {
+            maintenance_item_id: item_id,
+            serviced_at: Date::from_calendar_date(2026, Month::March, day)?,
+            vendor_id: None,
+            cost_cents: None,
+            notes: String::new(),
+        })?);
+    }
+    store.soft_delete_service_log_entry(ids[0])?;
+    let listed = store
+        .list_service_log_entries(false)?
+        .into_iter()
+        .map(|row| row.id)
+        .collect::<Vec<_>>();
+
+    let none = BTreeSet::new();
+    let page = |skip, offset, limit| ListPage {
+        skip,
+        offset,
+        limit,
+    };
+    let first = store.service_log_page(false, page(&none, 0, 2))?;
+    let second = store.service_log_page(false, page(&none, 2, 2))?;
+    assert_eq!(first.total, 4);
+    assert_eq!(
+        first
+            .rows
+            .iter()
+            .chain(&second.rows)
+            .map(|row| row.id)
+            .collect::<Vec<_>>(),
+        listed
+    );
+
+    let hidden = BTreeSet::from([ids[4].get()]);
+    let page = store.service_log_page(true, page(&hidden, 0, 10))?;
+    assert_eq!(page.total, 4, "deleted rows count, hidden ones do not");
+    assert!(page.rows.iter().all(|row| row.id != ids[4]));
+    assert_eq!(
+        store
+            .quotes_page(
+                false,
+                ListPage {
+                    skip: &none,
+                    offset: 0,
+                    limit: 10,
+                }
+            )?
+            .total,
+        0
+    );
+    Ok(())
+}
+
+#[test]
+fn sandbox_changes_stay_out_of_the_file_until_discarded() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let path = temp_dir.path().join("micasa.db");
+    let store = Store::open(&path)?;
+    store.bootstrap()?;
+    private_test_project(&store, "Deck")?;
+
+    store.begin_sandbox()?;
+    assert!(store.in_sandbox());
+    assert!(store.begin_sandbox().is_err(), "sandboxes don't nest");
+    let shed = private_test_project(&store, "Shed")?;
+    // Tag writes run their own batch inside the sandbox.
+    store.set_tags(LifecycleEntityRef::Project(shed), &["exterior".to_owned()])?;
+    assert_eq!(store.list_projects(false)?.len(), 2);
+
+    let other = Store::open(&path)?;
+    assert_eq!(
+        other.list_projects(false)?.len(),
+        1,
+        "other connections only see the real plan"
+    );
+
+    store.discard_sandbox()?;
+    assert!(!store.in_sandbox());
+    let titles = store
+        .list_projects(false)?
+        .into_iter()
+        .map(|project| project.title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["Deck"]);
+    assert!(store.discard_sandbox().is_err());
+    Ok(())
+}
+
+#[test]
+fn purge_drops_deleted_rows_by_age_and_keeps_referenced_ones() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let old = private_test_project(&store, "Old shed")?;
+    store.set_tags(LifecycleEntityRef::Project(old), &["exterior".to_owned()])?;
+    store.set_row_private(LifecycleEntityRef::Project(old), true)?;
+    store.soft_delete_project(old)?;
+    store.raw_connection().execute(
+        "UPDATE projects SET deleted_at = ? WHERE id = ?",
+        rusqlite::params!["2024-01-01T00:00:00Z", old.get()],
+    )?;
+    let parent = private_test_project(&store, "Basement")?;
+    let project_type_id = store.list_project_types()?[0].id;
+    let child = store.create_project(&NewProject {
+        title: "Basement drywall".to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: Some(parent),
+    })?;
+    store.soft_delete_project(child)?;
+    store.soft_delete_project(parent)?;
+    assert_eq!(store.deleted_row_count()?, 3);
+
+    let month_ago = time::OffsetDateTime::now_utc() - time::Duration::days(30);
+    let summary = store.purge_deleted(Some(month_ago))?;
+    assert_eq!((summary.purged, summary.kept), (1, 0));
+    assert!(
+        store.list_tags()?.is_empty(),
+        "the purged row's tag goes too"
+    );
+    assert!(
+        store
+            .private_row_ids(micasa_app::DeletionEntity::Project)?
+            .is_empty()
+    );
+
+    // A live row pointing at a deleted one keeps it around.
+    let gone = private_test_project(&store, "Gone")?;
+    store.soft_delete_project(gone)?;
+    let live = private_test_project(&store, "Live")?;
+    store.raw_connection().execute(
+        "UPDATE projects SET parent_project_id = ? WHERE id = ?",
+        rusqlite::params![gone.get(), live.get()],
+    )?;
+    let summary = store.purge_deleted(None)?;
+    assert_eq!(
+        (summary.purged, summary.kept),
+        (2, 1),
+        "the sub-project goes first, then its parent"
+    );
+    assert_eq!(store.deleted_row_count()?, 1);
+    assert_eq!(store.list_projects(true)?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn archived_rows_round_trip_and_drop_out_of_dashboard_counts() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let kept_id = private_test_project(&store, "Kitchen refresh")?;
+    let archived_id = private_test_project(&store, "2019 porch rebuild")?;
+    assert_eq!(store.dashboard_counts()?.projects_due, 2);
+
+    let target = LifecycleEntityRef::Project(archived_id);
+    store.set_row_archived(target, true)?;
+    store.set_row_archived(target, true)?;
+    assert!(store.is_row_archived(target)?);
+    assert!(!store.is_row_archived(LifecycleEntityRef::Project(kept_id))?);
+    assert_eq!(
+        store.archived_row_ids(micasa_app::DeletionEntity::Project)?,
+        BTreeSet::from([archived_id.get()])
+    );
+    assert_eq!(store.dashboard_counts()?.projects_due, 1);
+    assert_eq!(store.list_projects(false)?.len(), 2);
+
+    store.set_row_archived(target, false)?;
+    assert!(!store.is_row_archived(target)?);
+    assert_eq!(store.dashboard_counts()?.projects_due, 2);
+
+    store.soft_delete_project(kept_id)?;
+    let error = store
+        .set_row_archived(LifecycleEntityRef::Project(kept_id), true)
+        .expect_err("deleted row should not archive");
+    assert!(error.to_string().contains("restore it before archiving"));
+    let error = store
+        .set_row_archived(
+            LifecycleEntityRef::Project(micasa_app::ProjectId::new(9_999)),
+            true,
+        )
+        .expect_err("missing row should fail");
+    assert!(error.to_string().contains("not found"));
+    Ok(())
+}
+
+#[test]
+fn archive_settled_projects_takes_only_live_finished_ones() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let project_type_id = store.list_project_types()?[0].id;
+    let create = |title: &str, status| {
+        store.create_project(&NewProject {
+            title: title.to_owned(),
+            project_type_id,
+            status,
+            description: String::new(),
+            start_date: None,
+            end_date: None,
+            budget_cents: None,
+            actual_cents: None,
+            parent_id: None,
+        })
+    };
+    let active = create("Kitchen refresh", ProjectStatus::Underway)?;
+    let done = create("2019 porch rebuild", ProjectStatus::Completed)?;
+    let dropped = create("Hot tub", ProjectStatus::Abandoned)?;
+    let deleted = create("Old fence", ProjectStatus::Completed)?;
+    store.soft_delete_project(deleted)?;
+
+    assert_eq!(store.archive_settled_projects()?, 2);
+    assert_eq!(
+        store.archived_row_ids(micasa_app::DeletionEntity::Project)?,
+        BTreeSet::from([done.get(), dropped.get()])
+    );
+    assert!(!store.is_row_archived(LifecycleEntityRef::Project(active))?);
+    assert_eq!(
+        store.archive_settled_projects()?,
+        0,
+        "archived ones aren't counted twice"
+    );
+
+    store.set_row_archived(LifecycleEntityRef::Project(done), false)?;
+    assert_eq!(store.archive_settled_projects()?, 1);
+    Ok(())
+}
+
+#[test]
+fn sub_projects_link_to_parents_and_reject_cycles() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let remodel = private_test_project(&store, "Whole house remodel")?;
+    let bath = store.create_project(&NewProject {
+        title: "Primary bath".to_owned(),
+        project_type_id: store.list_project_types()?[0].id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: Some(500_000),
+        actual_cents: None,
+        parent_id: Some(remodel),
+    })?;
+    let tile = private_test_project(&store, "Shower tile")?;
+    store.update_field(
+        LifecycleEntityRef::Project(tile),
+        "parent_project_id",
+        &bath.get().to_string(),
+    )?;
+    assert_eq!(store.get_project(bath)?.parent_id, Some(remodel));
+    assert_eq!(store.get_project(tile)?.parent_id, Some(bath));
+    assert_eq!(
+        store.field_text(LifecycleEntityRef::Project(tile), "parent_project_id")?,
+        bath.get().to_string()
+    );
+
+    let error = store
+        .update_field(
+            LifecycleEntityRef::Project(remodel),
+            "parent_project_id",
+            &tile.get().to_string(),
+        )
+        .expect_err("cycle should fail");
+    assert!(
+        error.to_string().contains("outside its own tree"),
+        "{error}"
+    );
+    let error = store
+        .update_field(
+            LifecycleEntityRef::Project(bath),
+            "parent_project_id",
+            &bath.get().to_string(),
+        )
+        .expect_err("self parent should fail");
+    assert!(error.to_string().contains("its own parent"), "{error}");
+    let error = store
+        .update_field(LifecycleEntityRef::Project(bath), "parent_project_id", "x")
+        .expect_err("non-numeric parent should fail");
+    assert!(
+        error.to_string().contains("expected a project id"),
+        "{error}"
+    );
+
+    let error = store
+        .soft_delete_project(bath)
+        .expect_err("parent with live children should not delete");
+    assert!(error.to_string().contains("1 sub-project(s)"), "{error}");
+
+    store.soft_delete_project(tile)?;
+    store.soft_delete_project(bath)?;
+    let error = store
+        .restore_project(tile)
+        .expect_err("child of a deleted parent should not restore");
+    assert!(error.to_string().contains("restore it first"), "{error}");
+    store.restore_project(bath)?;
+    store.restore_project(tile)?;
+
+    store.update_field(LifecycleEntityRef::Project(tile), "parent_project_id", " ")?;
+    assert_eq!(store.get_project(tile)?.parent_id, None);
+    Ok(())
+}
+
+#[test]
+fn hidden_private_rows_cant_be_reached_through_main_or_side_tables() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    private_test_project(&store, "Paint Fence")?;
+    let private_id = private_test_project(&store, "Inheritance Paperwork")?;
+    store.set_row_private(LifecycleEntityRef::Project(private_id), true)?;
+    let deed = store.insert_document(&NewDocument {
+        title: "Deed".to_owned(),
+        file_name: "deed.txt".to_owned(),
+        entity_kind: DocumentEntityKind::None,
+        entity_id: 0,
+        mime_type: "text/plain".to_owned(),
+        data: b"deed".to_vec(),
+        notes: String::new(),
+    })?;
+    store.replace_document_chunks(deed, "embed", &[("parcel 17 deed".to_owned(), vec![1.0])])?;
+    store.set_row_private(LifecycleEntityRef::Document(deed), true)?;
+
+    store.shadow_private_rows()?;
+    for sql in [
+        "SELECT title FROM main.projects",
+        "SELECT count(*) FROM main.projects",
+        "SELECT title FROM \"main\".\"projects\"",
+        "SELECT content FROM document_chunks",
+        "SELECT content FROM main.document_chunks",
+        "SELECT target_id FROM private_rows",
+        "WITH c AS (SELECT * FROM document_chunks) SELECT content FROM c",
+        "WITH c AS (SELECT * FROM main.projects) SELECT title FROM c",
+        "WITH projects AS (SELECT * FROM main.projects) SELECT title FROM projects",
+        "SELECT title FROM projects WHERE id IN (SELECT id FROM main.projects)",
+    ] {
+        let error = store.read_only_query(sql).expect_err(sql);
+        assert!(
+            error.to_string().contains("while private rows are hidden"),
+            "{sql}: {error}"
+        );
+    }
+    let (_columns, rows) = store.read_only_query("SELECT title FROM projects")?;
+    assert_eq!(rows, vec![vec!["Paint Fence".to_owned()]]);
+    let (_columns, rows) =
+        store.read_only_query("WITH c AS (SELECT title FROM projects) SELECT title FROM c")?;
+    assert_eq!(rows, vec![vec!["Paint Fence".to_owned()]]);
+    let (_columns, rows) = store.read_only_query("SELECT count(*) FROM projects JOIN documents")?;
+    assert_eq!(rows, vec![vec!["0".to_owned()]]);
+    assert!(store.document_chunks("embed")?.is_empty());
+    assert!(!store.table_names()?.contains(&"entity_tags".to_owned()));
+
+    store.clear_private_row_shadows()?;
+    let (_columns, rows) = store.read_only_query("SELECT content FROM document_chunks")?;
+    assert_eq!(rows, vec![vec!["parcel 17 deed".to_owned()]]);
+    assert_eq!(store.document_chunks("embed")?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn shadow_private_rows_hides_them_from_ad_hoc_queries_and_dump() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let db_path = temp_dir.path().join("private.db");
+    let store = Store::open(&db_path)?;
+    store.bootstrap()?;
+
+    let public_id = private_test_project(&store, "Paint Fence")?;
+    let private_id = private_test_project(&store, "Inheritance Paperwork")?;
+    store.set_row_private(LifecycleEntityRef::Project(private_id), true)?;
+
+    let reader = Store::open(&db_path)?;
+    reader.shadow_private_rows()?;
+    let (_columns, rows) = reader.read_only_query("SELECT id, title FROM projects ORDER BY id")?;
+    assert_eq!(
+        rows,
+        vec![vec![public_id.get().to_string(), "Paint Fence".to_owned()]]
+    );
+    let dump = reader.data_dump();
+    assert!(dump.contains("Paint Fence"));
+    assert!(!dump.contains("Inheritance Paperwork"));
+    assert!(!reader.table_names()?.contains(&"private_rows".to_owned()));
+
+    let (_columns, rows) = store.read_only_query("SELECT id FROM projects")?;
+    assert_eq!(
+        rows.len(),
+        2,
+        "the primary connection keeps seeing every row"
+    );
+
+    reader.clear_private_row_shadows()?;
+    let (_columns, rows) = reader.read_only_query("SELECT id FROM projects")?;
+    assert_eq!(rows.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn set_maintenance_interval_updates_live_items_only() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Gutter clean".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 6,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+
+    store.set_maintenance_interval(item_id, 4)?;
+    let items = store.list_maintenance_items(false)?;
+    assert_eq!(items[0].interval_months, 4);
+
+    let error = store
+        .set_maintenance_interval(item_id, 0)
+        .expect_err("zero interval should fail");
+    assert!(error.to_string().contains("at least 1 month"));
+
+    store.soft_delete_maintenance_item(item_id)?;
+    let error = store
+        .set_maintenance_interval(item_id, 3)
+        .expect_err("deleted item should fail");
+    assert!(error.to_string().contains("not found or deleted"));
+    Ok(())
+}
+
+#[test]
+fn service_cost_cents_between_sums_live_entries_in_range() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Furnace tune-up".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 12,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let log = |month, day, cost_cents| {
+        store.create_service_log_entry(&NewServiceLogEntry {
+            maintenance_item_id: item_id,
+            serviced_at: Date::from_calendar_date(2026, month, day)?,
+            vendor_id: None,
+            cost_cents,
+            notes: String::new(),
+        })
+    };
+    log(Month::February, 28, Some(900))?;
+    log(Month::March, 1, Some(12_000))?;
+    log(Month::March, 31, None)?;
+    let deleted = log(Month::March, 15, Some(5_000))?;
+    log(Month::April, 1, Some(7_000))?;
+    store.soft_delete_service_log_entry(deleted)?;
+
+    let total = store.service_cost_cents_between(
+        Date::from_calendar_date(2026, Month::March, 1)?,
+        Date::from_calendar_date(2026, Month::April, 1)?,
+    )?;
+    assert_eq!(total, 12_000);
+    Ok(())
+}
+
+#[test]
+fn cost_splits_validate_totals_and_feed_category_totals() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let categories = store.list_maintenance_categories()?;
+    let (first, second) = (categories[0].clone(), categories[1].clone());
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Boiler".to_owned(),
+        category_id: first.id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 12,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let march = |day| Date::from_calendar_date(2026, Month::March, day);
+    let split_entry = store.create_service_log_entry(&NewServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at: march(3)?,
+        vendor_id: None,
+        cost_cents: Some(10_000),
+        notes: String::new(),
+    })?;
+    store.create_service_log_entry(&NewServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at: march(9)?,
+        vendor_id: None,
+        cost_cents: Some(2_500),
+        notes: String::new(),
+    })?;
+    store.create_incident(&NewIncident {
+        title: "Flooded basement".to_owned(),
+        description: String::new(),
+        status: IncidentStatus::Open,
+        severity: IncidentSeverity::Urgent,
+        date_noticed: march(12)?,
+        date_resolved: None,
+        location: String::new(),
+        cost_cents: Some(4_000),
+        appliance_id: None,
+        vendor_id: None,
+        notes: String::new(),
+        follow_up_date: None,
+    })?;
+
+    let target = LifecycleEntityRef::ServiceLogEntry(split_entry);
+    let short = store
+        .set_cost_splits(
+            target,
+            &[CostSplit {
+                category_id: first.id,
+                amount_cents: 9_000,
+            }],
+        )
+        .expect_err("splits must add up to the cost");
+    assert!(short.to_string().contains("but the cost is $100.00"));
+
+    let splits = [
+        CostSplit {
+            category_id: first.id,
+            amount_cents: 4_000,
+        },
+        CostSplit {
+            category_id: second.id,
+            amount_cents: 6_000,
+        },
+    ];
+    store.set_cost_splits(target, &splits)?;
+    assert_eq!(
+        store.list_cost_splits(target)?,
+        vec![splits[1], splits[0]],
+        "largest share first"
+    );
+
+    let totals =
+        store.category_cost_totals(march(1)?, Date::from_calendar_date(2026, Month::April, 1)?)?;
+    let summary = totals
+        .iter()
+        .map(|total| (total.category_name.as_str(), total.total_cents))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            (first.name.as_str(), 6_500),
+            (second.name.as_str(), 6_000),
+            ("uncategorized", 4_000),
+        ]
+    );
+
+    let error = store
+        .set_cost_splits(
+            LifecycleEntityRef::Project(micasa_app::ProjectId::new(1)),
+            &[],
+        )
+        .expect_err("projects have no splittable cost");
+    assert!(
+        error
+            .to_string()
+            .contains("service log entries and incidents only")
+    );
+    Ok(())
+}
+
+#[test]
+fn editing_a_cost_drops_splits_that_no_longer_match() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Water heater".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 12,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let serviced_at = Date::from_calendar_date(2026, Month::May, 2)?;
+    let entry_id = store.create_service_log_entry(&NewServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at,
+        vendor_id: None,
+        cost_cents: Some(8_000),
+        notes: String::new(),
+    })?;
+    let target = LifecycleEntityRef::ServiceLogEntry(entry_id);
+    store.set_cost_splits(
+        target,
+        &[CostSplit {
+            category_id,
+            amount_cents: 8_000,
+        }],
+    )?;
+
+    let update = |cost_cents, notes: &str| UpdateServiceLogEntry {
+        maintenance_item_id: item_id,
+        serviced_at,
+        vendor_id: None,
+        cost_cents,
+        notes: notes.to_owned(),
+    };
+    store.update_service_log_entry(entry_id, &update(Some(8_000), "same cost"))?;
+    assert_eq!(store.list_cost_splits(target)?.len(), 1);
+
+    store.update_service_log_entry(entry_id, &update(Some(9_000), "new cost"))?;
+    assert!(store.list_cost_splits(target)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn foreign_currency_costs_normalize_into_home_totals() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Chalet boiler".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 12,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let serviced_at = Date::from_calendar_date(2026, Month::July, 8)?;
+    let log = |cost_cents| {
+        store.create_service_log_entry(&NewServiceLogEntry {
+            maintenance_item_id: item_id,
+            serviced_at,
+            vendor_id: None,
+            cost_cents: Some(cost_cents),
+            notes: String::new(),
+        })
+    };
+    let euro_entry = log(10_000)?;
+    log(2_500)?;
+    let target = LifecycleEntityRef::ServiceLogEntry(euro_entry);
+
+    let error = store
+        .set_money_currency(target, "cost_cents", Some("eur"))
+        .expect_err("currency needs a rate first");
+    assert!(error.to_string().contains("--set-rate EUR=<rate>"));
+    let error = store
+        .set_money_currency(target, "budget_cents", Some("EUR"))
+        .expect_err("service log has no budget");
+    assert!(error.to_string().contains("pick one of: cost_cents"));
+
+    store.set_currency_rate("eur", 1_000_000, RateSource::Manual)?;
+    store.set_currency_rate("EUR", 1_080_000, RateSource::Fetched)?;
+    let rates = store.list_currency_rates()?;
+    assert_eq!(rates.len(), 1);
+    assert_eq!(
+        (
+            rates[0].code.as_str(),
+            rates[0].home_per_unit_micros,
+            rates[0].source
+        ),
+        ("EUR", 1_080_000, RateSource::Fetched)
+    );
+
+    store.set_money_currency(target, "cost_cents", Some("eur"))?;
+    assert_eq!(
+        store
+            .money_currencies(micasa_app::DeletionEntity::ServiceLog)?
+            .get(&(euro_entry.get(), "cost_cents".to_owned()))
+            .map(String::as_str),
+        Some("EUR")
+    );
+
+    let start = Date::from_calendar_date(2026, Month::July, 1)?;
+    let end = Date::from_calendar_date(2026, Month::August, 1)?;
+    assert_eq!(store.service_cost_cents_between(start, end)?, 13_300);
+    assert_eq!(store.ytd_service_spend_cents(start)?, 13_300);
+    assert_eq!(
+        store.category_cost_totals(start, end)?[0].total_cents,
+        13_300
+    );
+
+    store.set_money_currency(target, "cost_cents", None)?;
+    assert!(
+        store
+            .money_currencies(micasa_app::DeletionEntity::ServiceLog)?
+            .is_empty()
+    );
+    assert_eq!(store.service_cost_cents_between(start, end)?, 12_500);
+    Ok(())
+}
+
+#[test]
+fn update_field_saves_single_cells_with_validation() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let project_type_id = store.list_project_types()?[0].id;
+    let project_id = store.create_project(&NewProject {
+        title: "Deck".to_owned(),
+        project_type_id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: Some(10_000),
+        actual_cents: None,
+        parent_id: None,
+    })?;
+    let project = LifecycleEntityRef::Project(project_id);
+
+    store.update_field(project, "title", "  Deck rebuild ")?;
+    store.update_field(project, "budget_cents", "$1,250.50")?;
+    store.update_field(project, "actual_cents", "99")?;
+    let saved = store.get_project(project_id)?;
+    assert_eq!(saved.title, "Deck rebuild");
+    assert_eq!(saved.budget_cents, Some(125_050));
+    assert_eq!(saved.actual_cents, Some(9_900));
+
+    store.update_field(project, "actual_cents", "")?;
+    assert_eq!(store.get_project(project_id)?.actual_cents, None);
+
+    for (field, raw, expected) in [
+        ("title", " ", "title is required"),
+        ("budget_cents", "-5", "negative money value"),
+        ("budget_cents", "lots", "use a form like 1234.56"),
+        (
+            "status",
+            "done",
+            "can't be edited inline; pick one of: title",
+        ),
+    ] {
+        let error = store.update_field(project, field, raw).expect_err(field);
+        assert!(error.to_string().contains(expected), "{field}: {error}");
+    }
+
+    let incident_id = store.create_incident(&NewIncident {
+        title: "Leak".to_owned(),
+        description: String::new(),
+        status: IncidentStatus::Open,
+        severity: IncidentSeverity::Soon,
+        date_noticed: Date::from_calendar_date(2026, Month::March, 10)?,
+        date_resolved: None,
+        location: String::new(),
+        cost_cents: None,
+        appliance_id: None,
+        vendor_id: None,
+        notes: String::new(),
+        follow_up_date: None,
+    })?;
+    let incident = LifecycleEntityRef::Incident(incident_id);
+    store.update_field(incident, "date_resolved", "2026-03-12")?;
+    let error = store
+        .update_field(incident, "date_noticed", "2026-03-20")
+        .expect_err("noticed after resolved");
+    assert!(error.to_string().contains("on/after date noticed"));
+    let error = store
+        .update_field(incident, "date_resolved", "03/12/2026")
+        .expect_err("bad date");
+    assert!(error.to_string().contains("use YYYY-MM-DD"));
+
+    store.soft_delete_project(project_id)?;
+    let error = store
+        .update_field(project, "title", "Ghost")
+        .expect_err("deleted rows are read-only");
+    assert!(error.to_string().contains("not found or deleted"));
+    Ok(())
+}
+
+#[test]
+fn vendor_insurance_tracks_license_expiry_and_certificate() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let today = Date::from_calendar_date(2026, Month::June, 1)?;
+    let roofer = store.create_vendor(&NewVendor {
+        name: "Acme Roofing".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
+        notes: String::new(),
+        license_number: " C-39 1029 ".to_owned(),
+        insurance_expiry: Some(Date::from_calendar_date(2026, Month::May, 20)?),
+        insurance_document_id: None,
+    })?;
+    let electrician = store.create_vendor(&NewVendor {
+        name: "Bright Electric".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
+        notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: Some(Date::from_calendar_date(2027, Month::January, 1)?),
+        insurance_document_id: None,
+    })?;
+
+    let certificate = store.insert_document(&NewDocument {
+        title: "Roofing COI".to_owned(),
+        file_name: "coi.pdf".to_owned(),
+        entity_kind: DocumentEntityKind::Vendor,
+        entity_id: roofer.get(),
+        mime_type: "application/pdf".to_owned(),
+        data: b"%PDF-1.7".to_vec(),
+        notes: String::new(),
+    })?;
+    store.update_field(
+        LifecycleEntityRef::Vendor(roofer),
+        "insurance_document_id",
+        &certificate.get().to_string(),
+    )?;
+    store.update_field(
+        LifecycleEntityRef::Vendor(electrician),
+        "insurance_expiry",
+        "2026-06-15",
+    )?;
+
+    let vendors = store.list_vendors(false)?;
+    let acme = vendors
+        .iter()
+        .find(|vendor| vendor.id == roofer)
+        .expect("roofer listed");
+    assert_eq!(acme.license_number, "C-39 1029");
+    assert_eq!(acme.insurance_document_id, Some(certificate));
+
+    let lapses = store
+        .list_vendor_insurance_lapses(today, 30)?
+        .into_iter()
+        .map(|vendor| vendor.id)
+        .collect::<Vec<_>>();
+    assert_eq!(lapses, vec![roofer, electrician]);
+    assert_eq!(
+        store
+            .list_vendor_insurance_lapses(today, 0)?
+            .into_iter()
+            .map(|vendor| vendor.id)
+            .collect::<Vec<_>>(),
+        vec![roofer]
+    );
+
+    let error = store
+        .soft_delete_document(certificate)
+        .expect_err("certificate in use should not delete");
+    assert!(
+        error
+            .to_string()
+            .contains("insurance certificate of 1 vendor(s)"),
+        "{error}"
+    );
+    let error = store
+        .update_field(
+            LifecycleEntityRef::Vendor(electrician),
+            "insurance_document_id",
+            "999",
+        )
+        .expect_err("missing certificate should fail");
+    assert!(error.to_string().contains("no longer exists"), "{error}");
+    let error = store
+        .update_field(
+            LifecycleEntityRef::Vendor(electrician),
+            "insurance_document_id",
+            "coi",
+        )
+        .expect_err("non-numeric certificate should fail");
+    assert!(
+        error.to_string().contains("expected a document id"),
+        "{error}"
+    );
+
+    store.update_field(
+        LifecycleEntityRef::Vendor(roofer),
+        "insurance_document_id",
+        "",
+    )?;
+    store.soft_delete_document(certificate)?;
+    Ok(())
+}
+
+#[test]
+fn budgets_cover_one_category_each_and_survive_restore_checks() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let project_type_id = store.list_project_types()?[0].id;
+    let maintenance = micasa_app::BudgetCategory::Maintenance(category_id);
+    let project = micasa_app::BudgetCategory::ProjectType(project_type_id);
+    let budget = |category, monthly_cents| NewBudget {
+        category,
+        monthly_cents,
+        notes: String::new(),
+    };
+
+    let maintenance_budget = store.create_budget(&budget(maintenance, 20_000))?;
+    let project_budget = store.create_budget(&budget(project, 100_000))?;
+    let error = store
+        .create_budget(&budget(maintenance, 5_000))
+        .expect_err("one budget per category");
+    assert!(error.to_string().contains("edit that one instead"));
+    let error = store
+        .create_budget(&budget(
+            micasa_app::BudgetCategory::ProjectType(micasa_app::ProjectTypeId::new(9_999)),
+            5_000,
+        ))
+        .expect_err("unknown project type");
+    assert!(error.to_string().contains("pick an existing project type"));
+
+    let budgets = store.list_budgets(false)?;
+    assert_eq!(budgets.len(), 2);
+    assert_eq!(budgets[0].id, maintenance_budget);
+    assert!(!budgets[0].category_name.is_empty());
+    assert_eq!(budgets[1].category, project);
+
+    store.update_budget(
+        maintenance_budget,
+        &UpdateBudget {
+            category: maintenance,
+            monthly_cents: 25_000,
+            notes: "raised after filter swap".to_owned(),
+        },
+    )?;
+    assert_eq!(store.list_budgets(false)?[0].monthly_cents, 25_000);
+
+    store.soft_delete_budget(project_budget)?;
+    assert_eq!(store.list_budgets(false)?.len(), 1);
+    let replacement = store.create_budget(&budget(project, 50_000))?;
+    let error = store
+        .restore_budget(project_budget)
+        .expect_err("restore would duplicate the category");
+    assert!(error.to_string().contains("edit that one instead"));
+    store.soft_delete_budget(replacement)?;
+    store.restore_budget(project_budget)?;
+    assert_eq!(store.list_budgets(false)?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn budget_spend_rolls_up_service_costs_project_actuals_and_accepted_quotes() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let category_id = store.list_maintenance_categories()?[0].id;
+    let project_type_id = store.list_project_types()?[0].id;
+    let item_id = store.create_maintenance_item(&NewMaintenanceItem {
+        name: "Filter".to_owned(),
+        category_id,
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 3,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    })?;
+    let today = time::OffsetDateTime::now_utc().date();
+    let start = today.replace_day(1)?;
+    let end = micasa_db::validation::add_months(start, 1);
+    for cost_cents in [4_000, 2_500] {
+        store.create_service_log_entry(&NewServiceLogEntry {
+            maintenance_item_id: item_id,
+            serviced_at: today,
+            vendor_id: None,
+            cost_cents: Some(cost_cents),
+            notes: String::new(),
+        })?;
+    }
+
+    let project = |title: &str, actual_cents| NewProject {
+        title: title.to_owned(),
+        project_type_id,
+        status: ProjectStatus::Underway,
+        description: String::new(),
+        start_date: Some(today),
+        end_date: None,
+        budget_cents: None,
+        actual_cents,
+        parent_id: None,
+    };
+    let finished = store.create_project(&project("Deck", Some(80_000)))?;
+    let quoted = store.create_project(&project("Fence", None))?;
+    let vendor_id = store.create_vendor(&NewVendor {
+        name: "Builder".to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
+        notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
+    })?;
+    let quote = |project_id, total_cents| NewQuote {
+        project_id,
+        vendor_id,
+        total_cents,
+        labor_cents: None,
+        materials_cents: None,
+        other_cents: None,
+        received_date: None,
+        notes: String::new(),
+    };
+    let accepted = store.create_quote(&quote(quoted, 30_000))?;
+    store.set_quote_accepted(accepted, true)?;
+    store.create_quote(&quote(quoted, 99_000))?;
+    // Superseded by the project's actual cost.
+    let replaced = store.create_quote(&quote(finished, 70_000))?;
+    store.set_quote_accepted(replaced, true)?;
+
+    let spend = store.budget_spend_cents(start, end)?;
+    assert_eq!(
+        spend.get(&micasa_app::BudgetCategory::Maintenance(category_id)),
+        Some(&6_500)
+    );
+    assert_eq!(
+        spend.get(&micasa_app::BudgetCategory::ProjectType(project_type_id)),
+        Some(&110_000)
+    );
+
+    let next = store.budget_spend_cents(end, micasa_db::validation::add_months(end, 1))?;
+    assert!(next.is_empty());
+    Ok(())
+}
+
+#[test]
+fn incident_follow_ups_come_due_and_settle_or_reopen() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let incident = |title: &str, status, follow_up_date| NewIncident {
+        title: title.to_owned(),
+        description: String::new(),
+        status,
+        severity: IncidentSeverity::Soon,
+        date_noticed: Date::from_calendar_date(2026, Month::January, 10).expect("date"),
+        date_resolved: None,
+        location: String::new(),
+        cost_cents: None,
+        appliance_id: None,
+        vendor_id: None,
+        notes: String::new(),
+        follow_up_date,
+    };
+    let due = Date::from_calendar_date(2026, Month::February, 9)?;
+    let later = Date::from_calendar_date(2026, Month::March, 1)?;
+
+    let leak = store.create_incident(&incident("Leak", IncidentStatus::Open, Some(due)))?;
+    let crack = store.create_incident(&incident("Crack", IncidentStatus::Resolved, Some(due)))?;
+    let mold = store.create_incident(&incident("Mold", IncidentStatus::Open, Some(later)))?;
+    store.create_incident(&incident("Noise", IncidentStatus::Resolved, None))?;
+
+    // An open incident with a follow-up date is not a recurrence check yet.
+    let ids = |today| -> Result<Vec<_>> {
+        Ok(store
+            .list_due_incident_follow_ups(today)?
+            .into_iter()
+            .map(|incident| incident.id)
+            .collect())
+    };
+    assert_eq!(ids(due)?, vec![crack]);
+
+    store.soft_delete_incident(leak)?;
+    store.soft_delete_incident(mold)?;
+    assert_eq!(ids(due)?, vec![crack, leak]);
+    assert_eq!(ids(later)?, vec![crack, leak, mold]);
+    assert!(ids(due.previous_day().expect("date"))?.is_empty());
+
+    store.settle_incident_follow_up(crack, false)?;
+    store.settle_incident_follow_up(leak, true)?;
+    assert_eq!(ids(later)?, vec![mold]);
+
+    let incidents = store.list_incidents(true)?;
+    let crack_row = incidents.iter().find(|row| row.id == crack).expect("crack");
+    assert_eq!(crack_row.status, IncidentStatus::Resolved);
+    assert_eq!(crack_row.follow_up_date, None);
+    let leak_row = incidents.iter().find(|row| row.id == leak).expect("leak");
+    assert_eq!(leak_row.status, IncidentStatus::Open);
+    assert_eq!(leak_row.deleted_at, None);
+    assert_eq!(leak_row.date_resolved, None);
+    assert_eq!(leak_row.follow_up_date, None);
+
+    let error = store
+        .settle_incident_follow_up(micasa_app::IncidentId::new(9_999), false)
+        .expect_err("unknown incident");
+    assert!(error.to_string().contains("choose an existing incident"));
+    Ok(())
+}
+
+#[test]
+fn audit_entries_carry_their_source_and_list_newest_first() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+
+    let incident = LifecycleEntityRef::Incident(micasa_app::IncidentId::new(4));
+    let vendor = LifecycleEntityRef::Vendor(micasa_app::VendorId::new(9));
+    store.record_audit(incident, "create")?;
+    store.with_audit_source(micasa_app::AuditSource::Chat, |store| {
+        store.record_audit(vendor, "create")?;
+        store.record_audit(vendor, "edit phone")
+    })?;
+    assert_eq!(store.audit_source(), micasa_app::AuditSource::Manual);
+
+    let failed = store.with_audit_source(micasa_app::AuditSource::Import, |store| {
+        store.record_audit(incident, "  ")
+    });
+    assert!(
+        failed
+            .expect_err("blank action")
+            .to_string()
+            .contains("audit action is required")
+    );
+    assert_eq!(
+        store.audit_source(),
+        micasa_app::AuditSource::Manual,
+        "source resets even when the closure fails"
+    );
+
+    let summary = |source| -> Result<Vec<(String, i64, String, &'static str)>> {
+        Ok(store
+            .list_audit_entries(source, 10)?
+            .into_iter()
+            .map(|entry| {
+                (
+                    entry.entity.as_str().to_owned(),
+                    entry.target_id,
+                    entry.action,
+                    entry.source.as_str(),
+                )
+            })
+            .collect())
+    };
+    assert_eq!(
+        summary(None)?,
+        vec![
+            ("vendor".to_owned(), 9, "edit phone".to_owned(), "chat"),
+            ("vendor".to_owned(), 9, "create".to_owned(), "chat"),
+            ("incident".to_owned(), 4, "create".to_owned(), "manual"),
+        ]
+    );
+    assert_eq!(
+        summary(Some(micasa_app::AuditSource::Manual))?,
+        vec![("incident".to_owned(), 4, "create".to_owned(), "manual")]
+    );
+    assert!(summary(Some(micasa_app::AuditSource::Import))?.is_empty());
+    assert_eq!(store.list_audit_entries(None, 1)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn dump_and_restore_tables_round_trips_every_row() -> Result<()> {
+    let source = Store::open_memory()?;
+    source.bootstrap()?;
+    source.seed_demo_data()?;
+    let project_id = private_test_project(&source, "Safe Deposit")?;
+    source.set_row_private(LifecycleEntityRef::Project(project_id), true)?;
+    let dumps = source.dump_tables()?;
+    assert!(dumps.iter().any(|dump| dump.table == "private_rows"));
+
+    let target = Store::open_memory()?;
+    target.bootstrap()?;
+    private_test_project(&target, "Overwritten")?;
+    let written = target.restore_tables(&dumps)?;
+
+    assert_eq!(
+        written,
+        dumps.iter().map(|dump| dump.rows.len()).sum::<usize>()
+    );
+    assert_eq!(target.dump_tables()?, dumps);
+    assert!(target.is_row_private(LifecycleEntityRef::Project(project_id))?);
+    Ok(())
+}
+
+#[test]
+fn restore_tables_rejects_bad_dumps_without_partial_writes() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let project_id = private_test_project(&store, "Keep Me")?;
+
+    let unknown = vec![micasa_db::TableDump {
+        table: "spaceships".to_owned(),
+        columns: vec!["id".to_owned()],
+        rows: Vec::new(),
+    }];
+    let error = store
+        .restore_tables(&unknown)
+        .expect_err("unknown table should fail");
+    assert!(error.to_string().contains("unknown table `spaceships`"));
+
+    let mut dumps = store.dump_tables()?;
+    for dump in &mut dumps {
+        if dump.table == "projects" {
+            dump.rows.clear();
+        }
+    }
+    let quote = micasa_db::TableDump {
+        table: "quotes".to_owned(),
+        columns: vec![
+            "id".to_owned(),
+            "project_id".to_owned(),
+            "vendor_id".to_owned(),
+            "total_cents".to_owned(),
+        ],
+        rows: vec![vec![
+            micasa_db::DumpValue::Integer(1),
+            micasa_db::DumpValue::Integer(404),
+            micasa_db::DumpValue::Integer(404),
+            micasa_db::DumpValue::Integer(100),
+        ]],
+    };
+    dumps.retain(|dump| dump.table != "quotes");
+    dumps.push(quote);
+    let error = store
+        .restore_tables(&dumps)
+        .expect_err("dangling references should fail");
+    assert!(format!("{error:#}").contains("re-export it and retry"));
+    assert_eq!(
+        store
+            .list_projects(false)?
+            .into_iter()
+            .map(|project| project.id)
+            .collect::<Vec<_>>(),
+        vec![project_id]
+    );
+    Ok(())
+}
+
+#[test]
+fn maintenance_categories_can_be_created_renamed_and_merged() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let kind = micasa_app::LookupKind::MaintenanceCategory;
+
+    let gutters = store.create_lookup_entry(kind, "  Gutters ")?;
+    let error = store
+        .create_lookup_entry(kind, "gutters")
+        .expect_err("names are unique ignoring case");
+    assert!(error.to_string().contains("`Gutters` already exists"));
+    assert!(store.create_lookup_entry(kind, " ").is_err());
+    store.rename_lookup_entry(kind, gutters, "Gutters & downspouts")?;
+
+    let roof = store
+        .list_lookup_entries(kind)?
+        .into_iter()
+        .find(|entry| entry.name == "Exterior")
+        .expect("default category")
+        .id;
+    let item = |name: &str, category_id| NewMaintenanceItem {
+        name: name.to_owned(),
+        category_id: micasa_app::MaintenanceCategoryId::new(category_id),
+        appliance_id: None,
+        last_serviced_at: None,
+        interval_months: 6,
+        manual_url: String::new(),
+        manual_text: String::new(),
+        notes: String::new(),
+        cost_cents: None,
+    };
+    let cleaning = store.create_maintenance_item(&item("Clean gutters", gutters))?;
+    store.create_maintenance_item(&item("Check roof", roof))?;
+    let entry_id = store.create_service_log_entry(&NewServiceLogEntry {
+        maintenance_item_id: cleaning,
+        serviced_at: Date::from_calendar_date(2026, Month::May, 2)?,
+        vendor_id: None,
+        cost_cents: Some(10_000),
+        notes: String::new(),
+    })?;
+    let target = LifecycleEntityRef::ServiceLogEntry(entry_id);
+    store.set_cost_splits(
+        target,
+        &[
+            CostSplit {
+                category_id: micasa_app::MaintenanceCategoryId::new(gutters),
+                amount_cents: 6_000,
+            },
+            CostSplit {
+                category_id: micasa_app::MaintenanceCategoryId::new(roof),
+                amount_cents: 4_000,
+            },
+        ],
+    )?;
+    let budget = |category_id| NewBudget {
+        category: micasa_app::BudgetCategory::Maintenance(micasa_app::MaintenanceCategoryId::new(
+            category_id,
+        )),
+        monthly_cents: 5_000,
+        notes: String::new(),
+    };
+    let gutter_budget = store.create_budget(&budget(gutters))?;
+    let roof_budget = store.create_budget(&budget(roof))?;
+    let error = store
+        .merge_lookup_entries(kind, gutters, roof)
+        .expect_err("only one budget can survive");
+    assert!(error.to_string().contains("delete one of them first"));
+    store.soft_delete_budget(roof_budget)?;
+
+    assert_eq!(store.merge_lookup_entries(kind, gutters, roof)?, 1);
+    let entries = store.list_lookup_entries(kind)?;
+    assert!(entries.iter().all(|entry| entry.id != gutters));
+    assert_eq!(
+        entries
+            .iter()
+            .find(|entry| entry.id == roof)
+            .map(|entry| entry.uses),
+        Some(2)
+    );
+    assert_eq!(
+        store.list_cost_splits(target)?,
+        vec![CostSplit {
+            category_id: micasa_app::MaintenanceCategoryId::new(roof),
+            amount_cents: 10_000,
+        }]
+    );
+    let budgets = store.list_budgets(false)?;
+    assert_eq!(budgets.len(), 1);
+    assert_eq!(budgets[0].id, gutter_budget);
+    assert_eq!(budgets[0].category_name, "Exterior");
+
+    // Bootstrapping again leaves the edited list alone.
+    store.merge_lookup_entries(
+        kind,
+        entries
+            .iter()
+            .find(|entry| entry.name == "Safety")
+            .expect("default")
+            .id,
+        roof,
+    )?;
+    store.bootstrap()?;
+    assert!(
+        store
+            .list_lookup_entries(kind)?
+            .iter()
+            .all(|entry| entry.name != "Safety")
+    );
+    Ok(())
+}
+
+#[test]
+fn project_types_merge_moves_projects_and_budgets() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let kind = micasa_app::LookupKind::ProjectType;
+    let id_of = |name: &str| -> Result<i64> {
+        Ok(store
+            .list_lookup_entries(kind)?
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .expect("default project type")
+            .id)
+    };
+    let windows = id_of("Windows")?;
+    let exterior = id_of("Exterior")?;
+    store.rename_lookup_entry(kind, windows, "Windows & doors")?;
+
+    store.create_project(&NewProject {
+        title: "Replace slider".to_owned(),
+        project_type_id: micasa_app::ProjectTypeId::new(windows),
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    })?;
+    let budget = store.create_budget(&NewBudget {
+        category: micasa_app::BudgetCategory::ProjectType(micasa_app::ProjectTypeId::new(windows)),
+        monthly_cents: 20_000,
+        notes: String::new(),
+    })?;
+
+    assert_eq!(store.merge_lookup_entries(kind, windows, exterior)?, 1);
+    let projects = store.list_projects(false)?;
+    assert_eq!(projects[0].project_type_id.get(), exterior);
+    let budgets = store.list_budgets(false)?;
+    assert_eq!(budgets[0].id, budget);
+    assert_eq!(budgets[0].category_name, "Exterior");
+
+    store.bootstrap()?;
+    assert!(
+        store
+            .list_lookup_entries(kind)?
+            .iter()
+            .all(|entry| !entry.name.starts_with("Windows"))
+    );
+    Ok(())
+}
+
+#[test]
+fn project_types_delete_only_when_nothing_uses_them() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let kind = micasa_app::LookupKind::ProjectType;
+    let sheds = store.create_lookup_entry(kind, "Sheds")?;
+    let project = store.create_project(&NewProject {
+        title: "New shed".to_owned(),
+        project_type_id: micasa_app::ProjectTypeId::new(sheds),
+        status: ProjectStatus::Ideating,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    })?;
+    store.soft_delete_project(project)?;
+
+    let error = store
+        .delete_lookup_entry(kind, sheds)
+        .expect_err("a deleted project still points at it");
+    assert!(
+        error
+            .to_string()
+            .contains("used by 1 project, deleted ones included"),
+        "{error}"
+    );
+
+    let spare = store.create_lookup_entry(kind, "Spare")?;
+    store.delete_lookup_entry(kind, spare)?;
+    assert!(
+        store
+            .list_lookup_entries(kind)?
+            .iter()
+            .all(|entry| entry.id != spare)
+    );
+    let error = store
+        .delete_lookup_entry(kind, spare)
+        .expect_err("already gone");
+    assert!(error.to_string().contains("not found"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn the_last_lookup_entry_cannot_be_deleted() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let kind = micasa_app::LookupKind::MaintenanceCategory;
+    let entries = store.list_lookup_entries(kind)?;
+    let (last, rest) = entries.split_last().expect("seeded categories");
+    for entry in rest {
+        store.delete_lookup_entry(kind, entry.id)?;
+    }
+    let error = store
+        .delete_lookup_entry(kind, last.id)
+        .expect_err("forms need one left");
+    assert!(
+        error.to_string().contains("the only category left"),
+        "{error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn data_version_moves_only_for_commits_from_another_connection() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let db_path = temp_dir.path().join("micasa.db");
+    let mine = Store::open(&db_path)?;
+    mine.bootstrap()?;
+    let theirs = Store::open(&db_path)?;
+    let vendor = |name: &str| NewVendor {
+        name: name.to_owned(),
+        contact_name: String::new(),
+        email: String::new(),
+        phone: String::new(),
+        website: String::new(),
+        notes: String::new(),
+        license_number: String::new(),
+        insurance_expiry: None,
+        insurance_document_id: None,
+    };
+
+    let before = mine.data_version()?;
+    mine.create_vendor(&vendor("Mine"))?;
+    assert_eq!(mine.data_version()?, before, "own writes don't count");
+    theirs.create_vendor(&vendor("Theirs"))?;
+    assert_ne!(mine.data_version()?, before);
+    Ok(())
+}
+
+#[test]
+fn apply_settings_writes_nothing_when_a_view_fails() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    store.put_setting(SettingKey::LlmModel, SettingValue::Text("qwen3".to_owned()))?;
+
+    let named = micasa_app::SavedView {
+        name: "Open".to_owned(),
+        ..micasa_app::SavedView::default()
+    };
+    let error = store
+        .apply_settings(
+            &[(
+                SettingKey::LlmModel,
+                SettingValue::Text("llama3".to_owned()),
+            )],
+            &[
+                (TabKind::Incidents, named),
+                (TabKind::Incidents, micasa_app::SavedView::default()),
+            ],
+        )
+        .expect_err("a nameless view should fail");
+    assert!(error.to_string().contains("view name is required"));
+    assert_eq!(
+        store.get_setting(SettingKey::LlmModel)?,
+        Some(SettingValue::Text("qwen3".to_owned()))
+    );
+    assert!(store.list_views(TabKind::Incidents)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn saved_views_round_trip_per_tab_and_overwrite_by_name() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let view = micasa_app::SavedView {
+        name: "Over budget".to_owned(),
+        sorts: vec![
+            ("tot budget".to_owned(), micasa_app::SortDirection::Desc),
+            ("title".to_owned(), micasa_app::SortDirection::Asc),
+        ],
+        pin: Some(("status".to_owned(), "underway".to_owned())),
+        filter_active: true,
+        filter_inverted: false,
+        hidden_columns: vec!["docs".to_owned(), "parent".to_owned()],
+        hide_settled: true,
+    };
+    store.save_view(TabKind::Projects, &view)?;
+    store.save_view(
+        TabKind::Projects,
+        &micasa_app::SavedView {
+            name: "all".to_owned(),
+            ..micasa_app::SavedView::default()
+        },
+    )?;
+    assert_eq!(
+        store.list_views(TabKind::Projects)?,
+        vec!["all".to_owned(), "Over budget".to_owned()]
+    );
+    assert!(store.list_views(TabKind::Incidents)?.is_empty());
+    assert_eq!(
+        store.load_view(TabKind::Projects, "Over budget")?,
+        Some(view.clone())
+    );
+    assert_eq!(
+        store.load_view(TabKind::Projects, "all")?,
+        Some(micasa_app::SavedView {
+            name: "all".to_owned(),
+            ..micasa_app::SavedView::default()
+        })
+    );
+
+    let narrowed = micasa_app::SavedView {
+        pin: None,
+        filter_active: false,
+        ..view
+    };
+    store.save_view(TabKind::Projects, &narrowed)?;
+    assert_eq!(
+        store.load_view(TabKind::Projects, "Over budget")?,
+        Some(narrowed)
+    );
+    assert!(
+        store
+            .save_view(TabKind::Projects, &micasa_app::SavedView::default())
+            .is_err()
+    );
+
+    assert!(store.delete_view(TabKind::Projects, "all")?);
+    assert!(!store.delete_view(TabKind::Projects, "all")?);
+    assert_eq!(store.load_view(TabKind::Projects, "all")?, None);
+    Ok(())
+}
+
+#[test]
+fn project_tasks_check_off_and_roll_up_into_progress() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let project_id = store.create_project(&NewProject {
+        title: "Deck".to_owned(),
+        project_type_id: store.list_project_types()?[0].id,
+        status: ProjectStatus::Planned,
+        description: String::new(),
+        start_date: None,
+        end_date: None,
+        budget_cents: None,
+        actual_cents: None,
+        parent_id: None,
+    })?;
+
+    let footings = store.create_project_task(project_id, "  Pour footings ")?;
+    let boards = store.create_project_task(project_id, "Lay boards")?;
+    let stain = store.create_project_task(project_id, "Stain")?;
+    assert!(store.create_project_task(project_id, "  ").is_err());
+    store.set_project_task_done(footings, true)?;
+    store.set_project_task_done(boards, true)?;
+    store.set_project_task_done(boards, false)?;
+    store.delete_project_task(stain)?;
+    assert!(store.delete_project_task(stain).is_err());
+
+    let tasks = store.list_project_tasks(project_id)?;
+    assert_eq!(
+        tasks
+            .iter()
+            .map(|task| (task.title.as_str(), task.done_at.is_some()))
+            .collect::<Vec<_>>(),
+        vec![("Pour footings", true), ("Lay boards", false)]
+    );
+    let rollup = store.project_rollups()?[&project_id];
+    assert_eq!((rollup.tasks_done, rollup.task_count), (1, 2));
+
+    store.soft_delete_project(project_id)?;
+    assert!(store.create_project_task(project_id, "Rail").is_err());
+    Ok(())
+}
+
+#[test]
+fn project_templates_create_projects_and_save_over_builtins() -> Result<()> {
+    let store = Store::open_memory()?;
+    store.bootstrap()?;
+    let templates = store.list_project_templates()?;
+    let bathroom = templates
+        .iter()
+        .find(|template| template.name == "Bathroom remodel")
+        .expect("built-in bathroom template");
+    assert!(bathroom.builtin);
+
+    let project_id = store.create_project_from_template("bathroom REMODEL")?;
+    let project = store
+        .list_projects(false)?
+        .into_iter()
+        .find(|project| project.id == project_id)
+        .expect("created project");
+    assert_eq!(project.title, "Bathroom remodel");
+    assert_eq!(project.status, ProjectStatus::Planned);
+    let remodel = store
+        .list_project_types()?
+        .into_iter()
+        .find(|value| value.name == "Remodel")
+        .expect("remodel type");
+    assert_eq!(project.project_type_id, remodel.id);
+    assert_eq!(
+        store.list_project_tasks(project_id)?.len(),
+        bathroom.tasks.len()
+    );
+    assert!(store.create_project_from_template("Treehouse").is_err());
+
+    let tasks = store.list_project_tasks(project_id)?;
+    for task in &tasks[1..] {
+        store.delete_project_task(task.id)?;
+    }
+    assert_eq!(
+        store.save_project_template("Bathroom Remodel", project_id)?,
+        1
+    );
+    let saved = store
+        .list_project_templates()?
+        .into_iter()
+        .filter(|template| template.name.eq_ignore_ascii_case("bathroom remodel"))
+        .collect::<Vec<_>>();
+    assert_eq!(saved.len(), 1, "the saved template replaces the built-in");
+    assert!(!saved[0].builtin);
+    assert_eq!(saved[0].name, "Bathroom Remodel");
+    assert_eq!(saved[0].project_type, "Remodel");
+    assert_eq!(saved[0].tasks, vec![tasks[0].title.clone()]);
+
+    assert!(store.delete_project_template("bathroom remodel")?);
+    assert!(!store.delete_project_template("bathroom remodel")?);
+    assert!(
+        store
+            .list_project_templates()?
+            .iter()
+            .any(|template| template.name == "Bathroom remodel" && template.builtin)
+    );
+    Ok(())
+}
@@ -1,11 +1,21 @@
 // Copyright 2026 Phillip Cloud
 // Licensed under the Apache License, Version 2.0
 
+mod provider;
+
+pub use provider::{
+    DEFAULT_PROVIDER, LlamaCpp, LlmProvider, Ollama, OpenAiCompatible, PROVIDERS, provider_named,
+};
+
 use anyhow::{Context, Result, anyhow, bail};
 use reqwest::StatusCode;
-use reqwest::blocking::{Client as HttpClient, Response};
+use reqwest::blocking::{Client as HttpClient, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
 use std::io::{BufRead, BufReader, Lines};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
 use std::time::Duration;
 use time::OffsetDateTime;
 
@@ -69,12 +79,18 @@ impl PullScanner {
     }
 }
 
+/// How often a cancelable stream looks at its cancel flag while waiting on
+/// the server.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
 #[derive(Debug, Clone)]
 pub struct Client {
     base_url: String,
     model: String,
     timeout: Duration,
     http: HttpClient,
+    provider: Arc<dyn LlmProvider>,
+    api_key: Option<String>,
 }
 
 impl Client {
@@ -97,13 +113,40 @@ impl Client {
             model: model.to_owned(),
             timeout,
             http,
+            provider: Arc::new(Ollama),
+            api_key: None,
         })
     }
 
+    /// Talks to `provider` instead of the default Ollama.
+    pub fn with_provider(mut self, provider: Arc<dyn LlmProvider>) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Sends `api_key` as a bearer token on every request; blank means none.
+    pub fn with_api_key(mut self, api_key: Option<&str>) -> Self {
+        self.api_key = api_key
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(str::to_owned);
+        self
+    }
+
     pub fn base_url(&self) -> &str {
         &self.base_url
     }
 
+    pub fn provider(&self) -> &dyn LlmProvider {
+        self.provider.as_ref()
+    }
+
+    /// Points the client at another backend; the model name is kept.
+    pub fn set_provider(&mut self, provider: Arc<dyn LlmProvider>, base_url: &str) {
+        self.provider = provider;
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+    }
+
     pub fn model(&self) -> &str {
         &self.model
     }
@@ -117,70 +160,58 @@ impl Client {
     }
 
     pub fn list_models(&self) -> Result<Vec<String>> {
-        let response = self
-            .http
-            .get(format!("{}/models", self.base_url))
-            .send()
-            .map_err(|error| connection_error(&self.base_url, error))?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().unwrap_or_default();
-            return Err(clean_error_response(status, &body));
-        }
+        self.provider.list_models(self)
+    }
 
-        let parsed: ModelsResponse = response.json().context("decode model list")?;
-        Ok(parsed.data.into_iter().map(|model| model.id).collect())
+    /// Whether the server lists `model`, as the provider matches names.
+    pub fn has_model(&self, available: &[String], model: &str) -> bool {
+        self.provider.has_model(available, model)
     }
 
     pub fn ping(&self) -> Result<()> {
         let models = self.list_models()?;
-        let exists = models
-            .iter()
-            .any(|name| name == &self.model || name.starts_with(&format!("{}:", self.model)));
-        if !exists {
+        if !self.has_model(&models, &self.model) {
             bail!(
-                "model {:?} not found -- pull it with `ollama pull {}`",
+                "model {:?} not found -- {}",
                 self.model,
-                self.model
+                self.provider.missing_model_hint(&self.model)
             );
         }
         Ok(())
     }
 
-    pub fn pull_model(&self, model: &str) -> Result<PullScanner> {
-        let ollama_base = self
-            .base_url
-            .trim_end_matches("/v1")
-            .trim_end_matches('/')
-            .to_owned();
-
-        let response = self
-            .http
-            .post(format!("{ollama_base}/api/pull"))
-            .json(&serde_json::json!({ "name": model }))
+    /// Whether the server answers at all. Any HTTP reply counts, so a bad key
+    /// or a missing model still surfaces through the usual errors; only a
+    /// failed connection is reported here.
+    pub fn probe(&self) -> Result<()> {
+        self.authorize(self.http.get(format!("{}/models", self.base_url)))
             .send()
-            .map_err(|error| connection_error(&ollama_base, error))?;
+            .map_err(|error| connection_error(&self.base_url, self.provider.start_hint(), error))?;
+        Ok(())
+    }
 
-        let status = response.status();
-        if !status.is_success() {
-            let body = response.text().unwrap_or_default();
-            return Err(clean_error_response(status, &body));
-        }
+    /// Starts downloading `model`; `None` when the provider can't.
+    pub fn pull_model(&self, model: &str) -> Result<Option<PullScanner>> {
+        self.provider.pull_model(self, model)
+    }
 
-        Ok(PullScanner {
-            lines: BufReader::new(response).lines(),
-        })
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
     }
 
     pub fn chat_complete(&self, messages: &[Message]) -> Result<String> {
         let request = ChatRequest::new(&self.model, messages, false);
         let response = self
-            .http
-            .post(format!("{}/chat/completions", self.base_url))
+            .authorize(
+                self.http
+                    .post(format!("{}/chat/completions", self.base_url)),
+            )
             .json(&request)
             .send()
-            .map_err(|error| connection_error(&self.base_url, error))?;
+            .map_err(|error| connection_error(&self.base_url, self.provider.start_hint(), error))?;
 
         let status = response.status();
         if !status.is_success() {
@@ -197,14 +228,47 @@ impl Client {
         Ok(content)
     }
 
+    /// One embedding per input, in input order, computed by `model` (which
+    /// may differ from the chat model).
+    pub fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let response = self
+            .authorize(self.http.post(format!("{}/embeddings", self.base_url)))
+            .json(&serde_json::json!({ "model": model, "input": inputs }))
+            .send()
+            .map_err(|error| connection_error(&self.base_url, self.provider.start_hint(), error))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(clean_error_response(status, &body));
+        }
+
+        let mut parsed: EmbeddingsResponse =
+            response.json().context("decode embeddings response")?;
+        if parsed.data.len() != inputs.len() {
+            bail!(
+                "asked for {} embeddings but got {}; check that `{model}` is an embedding model",
+                inputs.len(),
+                parsed.data.len()
+            );
+        }
+        parsed.data.sort_by_key(|row| row.index);
+        Ok(parsed.data.into_iter().map(|row| row.embedding).collect())
+    }
+
     pub fn chat_stream(&self, messages: &[Message]) -> Result<ChatStream> {
         let request = ChatRequest::new(&self.model, messages, true);
         let response = self
-            .http
-            .post(format!("{}/chat/completions", self.base_url))
+            .authorize(
+                self.http
+                    .post(format!("{}/chat/completions", self.base_url)),
+            )
             .json(&request)
             .send()
-            .map_err(|error| connection_error(&self.base_url, error))?;
+            .map_err(|error| connection_error(&self.base_url, self.provider.start_hint(), error))?;
 
         let status = response.status();
         if !status.is_success() {
@@ -217,6 +281,79 @@ impl Client {
             lines: BufReader::new(response).lines(),
         })
     }
+
+    /// `chat_stream` with the request running on a reader thread, so setting
+    /// `cancel` ends the stream within a few milliseconds even while the
+    /// server is still loading the model or thinking. The reader drops the
+    /// connection as soon as its pending read returns, which tells the server
+    /// to stop generating.
+    pub fn chat_stream_cancelable(
+        &self,
+        messages: &[Message],
+        cancel: Arc<AtomicBool>,
+    ) -> CancelableChatStream {
+        let (tx, rx) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let client = self.clone();
+        let messages = messages.to_vec();
+        let reader_cancel = Arc::clone(&cancel);
+        let reader_stopped = Arc::clone(&stopped);
+        thread::spawn(move || {
+            let halted =
+                || reader_cancel.load(Ordering::Acquire) || reader_stopped.load(Ordering::Acquire);
+            if halted() {
+                return;
+            }
+            let stream = match client.chat_stream(&messages) {
+                Ok(stream) => stream,
+                Err(error) => {
+                    let _ = tx.send(Err(error));
+                    return;
+                }
+            };
+            for chunk in stream {
+                if halted() || tx.send(chunk).is_err() {
+                    break;
+                }
+            }
+        });
+        CancelableChatStream {
+            chunks: rx,
+            cancel,
+            stopped,
+        }
+    }
+}
+
+/// Chunks from `Client::chat_stream_cancelable`. Ends early once the cancel
+/// flag is set; dropping it also stops the reader without touching the flag.
+pub struct CancelableChatStream {
+    chunks: Receiver<Result<StreamChunk>>,
+    cancel: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl Iterator for CancelableChatStream {
+    type Item = Result<StreamChunk>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.cancel.load(Ordering::Acquire) {
+                return None;
+            }
+            match self.chunks.recv_timeout(CANCEL_POLL_INTERVAL) {
+                Ok(chunk) => return Some(chunk),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+}
+
+impl Drop for CancelableChatStream {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Release);
+    }
 }
 
 pub struct ChatStream {
@@ -289,15 +426,19 @@ impl Iterator for ChatStream {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TableInfo {
     pub name: String,
+    pub description: String,
     pub columns: Vec<ColumnInfo>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ColumnInfo {
     pub name: String,
     pub column_type: String,
     pub not_null: bool,
     pub primary_key: bool,
+    /// `table(column)` for a foreign key.
+    pub references: Option<String>,
+    pub description: String,
 }
 
 pub fn build_sql_prompt(
@@ -415,6 +556,86 @@ pub fn build_fallback_prompt(
     out
 }
 
+/// Prompt that turns one free-text sentence into a JSON record draft for
+/// `/add`. `known_values` lists the project types and maintenance categories
+/// the answer may name.
+pub fn build_quick_add_prompt(
+    now: OffsetDateTime,
+    known_values: &str,
+    extra_context: Option<&str>,
+) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "You turn a short note about a house into one new record for a home management app. Output only a single JSON object.\n",
+    );
+    out.push_str(&format!("Current date: {}\n\n", format_human_date(now)));
+    out.push_str(QUICK_ADD_SHAPES);
+    if !known_values.trim().is_empty() {
+        out.push_str("\n## Known values in the database\n\n");
+        out.push_str(known_values);
+        out.push('\n');
+    }
+    out.push_str(QUICK_ADD_RULES);
+    if let Some(context) = extra_context
+        && !context.is_empty()
+    {
+        out.push_str("\n## Additional context\n\n");
+        out.push_str(context);
+        out.push('\n');
+    }
+    out
+}
+
+/// Prompt for `/explain`: walks through a generated query in plain words so
+/// the user can judge whether it asked the right thing.
+pub fn build_explain_prompt(question: &str, sql: &str, extra_context: Option<&str>) -> String {
+    let mut out = String::new();
+    out.push_str(
+        "You explain SQL queries from a home management app to someone who does not read SQL.\n",
+    );
+    if !question.trim().is_empty() {
+        out.push_str("\n## User question\n\n");
+        out.push_str(question.trim());
+        out.push('\n');
+    }
+    out.push_str("\n## SQL executed\n\n```sql\n");
+    out.push_str(sql.trim());
+    out.push_str("\n```\n\n");
+    out.push_str(EXPLAIN_RULES);
+    if let Some(context) = extra_context
+        && !context.is_empty()
+    {
+        out.push_str("\n## Additional context\n\n");
+        out.push_str(context);
+        out.push('\n');
+    }
+    out
+}
+
+/// Prompt section quoting document passages retrieved for a question, so
+/// answers about manuals and receipts can cite them. Empty when there are
+/// none.
+pub fn build_document_excerpts(excerpts: &[(&str, &str)]) -> String {
+    if excerpts.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from(
+        "\n## Document excerpts\n\nPassages from the user's documents that may answer the question. Name the document when you use one; ignore them if they don't apply.\n",
+    );
+    for (title, content) in excerpts {
+        out.push_str(&format!("\n### {title}\n\n{}\n", content.trim()));
+    }
+    out
+}
+
+/// The outermost `{...}` in a model answer, ignoring code fences and any
+/// chatter around it.
+pub fn extract_json_object(raw: &str) -> Option<&str> {
+    let start = raw.find('{')?;
+    let end = raw.rfind('}')?;
+    (start < end).then(|| &raw[start..=end])
+}
+
 pub fn format_results_table(columns: &[String], rows: &[Vec<String>]) -> String {
     if rows.is_empty() {
         return "(no rows)\n".to_owned();
@@ -893,6 +1114,9 @@ fn layout_clauses(raw_tokens: &[SqlToken]) -> String {
 
 fn format_ddl(table: &TableInfo) -> String {
     let mut out = String::new();
+    if !table.description.is_empty() {
+        out.push_str(&format!("-- {}\n", table.description));
+    }
     out.push_str(&format!("CREATE TABLE {} (\n", table.name));
     for (index, column) in table.columns.iter().enumerate() {
         out.push_str("  ");
@@ -905,9 +1129,17 @@ fn format_ddl(table: &TableInfo) -> String {
         if column.not_null {
             out.push_str(" NOT NULL");
         }
+        if let Some(target) = &column.references {
+            out.push_str(" REFERENCES ");
+            out.push_str(target);
+        }
         if index + 1 < table.columns.len() {
             out.push(',');
         }
+        if !column.description.is_empty() {
+            out.push_str(" -- ");
+            out.push_str(&column.description);
+        }
         out.push('\n');
     }
     out.push_str(");\n");
@@ -917,14 +1149,25 @@ fn format_ddl(table: &TableInfo) -> String {
 fn format_table(table: &TableInfo) -> String {
     let mut out = String::new();
     out.push_str(&format!("### {}\n", table.name));
+    if !table.description.is_empty() {
+        out.push_str(&table.description);
+        out.push('\n');
+    }
     for column in &table.columns {
         out.push_str(&format!(
-            "- {} {}{}{}\n",
+            "- {} {}{}{}",
             column.name,
             column.column_type,
             if column.primary_key { " PK" } else { "" },
             if column.not_null { " NOT NULL" } else { "" }
         ));
+        if let Some(target) = &column.references {
+            out.push_str(&format!(" -> {target}"));
+        }
+        if !column.description.is_empty() {
+            out.push_str(&format!(": {}", column.description));
+        }
+        out.push('\n');
     }
     out
 }
@@ -937,6 +1180,36 @@ fn format_human_date(now: OffsetDateTime) -> String {
         .unwrap_or_else(|_| now.date().to_string())
 }
 
+const QUICK_ADD_SHAPES: &str = r#"## Output shape
+
+{"kind": "<kind>", "fields": {...}} where kind and fields are one of:
+
+- "incident": title, description, status (open|in_progress|resolved),
+  severity (urgent|soon|whenever), date_noticed, location, cost
+- "project": title, description, project_type, status
+  (ideating|planned|quoted|underway|delayed|completed|abandoned),
+  start_date, end_date, budget
+- "appliance": name, brand, model_number, serial_number, purchase_date,
+  warranty_expiry, location, cost
+- "maintenance": name, category, last_serviced_at, interval_months, cost
+- "vendor": name, contact_name, email, phone, website
+"#;
+
+const QUICK_ADD_RULES: &str = r#"
+Rules:
+1. Dates are YYYY-MM-DD; resolve words like "today" or "last Tuesday" against the current date.
+2. Money (cost, budget) is a plain number of dollars, like 125.50.
+3. Leave out any field the note does not mention; never invent values.
+4. project_type and category must be one of the known values when given.
+"#;
+
+const EXPLAIN_RULES: &str = r#"Explain in a few short sentences or bullets:
+1. Which tables the query reads and how they are joined.
+2. Which rows it keeps or drops (filters, deleted rows, date ranges) and how results are grouped, sorted, or limited.
+3. What each returned column means, including units such as cents.
+If the query does not answer the question that was asked, say so plainly. Do not rewrite the query and do not guess at results.
+"#;
+
 const ENTITY_RELATIONSHIPS: &str = r#"
 ## Entity Relationships
 
@@ -960,6 +1233,7 @@ const SQL_SCHEMA_NOTES: &str = r#"
 - case-insensitive matching: use LOWER() on both sides for text comparisons.
 - Incident statuses: open, in_progress.
 - Incident severities: urgent, soon, whenever.
+- documents.extracted_text is the text read out of the file (often empty). To find documents that mention something, use LOWER(extracted_text) LIKE '%word%'. Never select documents.data.
 "#;
 
 const SQL_FEW_SHOT_EXAMPLES: &str = r#"
@@ -999,12 +1273,8 @@ const FALLBACK_GUIDELINES: &str = r#"
 - Do not invent data that is not present in the provided summary.
 "#;
 
-fn connection_error(base_url: &str, error: reqwest::Error) -> anyhow::Error {
-    anyhow!(
-        "cannot reach {} -- start it with `ollama serve` ({} )",
-        base_url,
-        error
-    )
+fn connection_error(base_url: &str, start_hint: &str, error: reqwest::Error) -> anyhow::Error {
+    anyhow!("cannot reach {} -- {} ({} )", base_url, start_hint, error)
 }
 
 fn clean_error_response(status: StatusCode, body: &str) -> anyhow::Error {
@@ -1091,6 +1361,18 @@ struct ChunkDelta {
     content: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingRow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingRow {
+    #[serde(default)]
+    index: usize,
+    embedding: Vec<f32>,
+}
+
 #[derive(Debug, Deserialize)]
 struct ModelsResponse {
     data: Vec<ModelRow>,
@@ -1119,8 +1401,9 @@ struct OllamaErrorEnvelope {
 #[cfg(test)]
 mod tests {
     use super::{
-        ColumnInfo, Message, Role, SqlTokenKind, TableInfo, build_fallback_prompt,
-        build_sql_prompt, build_summary_prompt, extract_sql, format_results_table, format_sql,
+        ColumnInfo, Message, Role, SqlTokenKind, TableInfo, build_document_excerpts,
+        build_explain_prompt, build_fallback_prompt, build_quick_add_prompt, build_sql_prompt,
+        build_summary_prompt, extract_json_object, extract_sql, format_results_table, format_sql,
         tokenize_sql,
     };
     use anyhow::Result;
@@ -1131,47 +1414,55 @@ mod tests {
         vec![
             TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![
                     ColumnInfo {
                         name: "id".to_owned(),
                         column_type: "integer".to_owned(),
                         not_null: false,
                         primary_key: true,
+                        ..Default::default()
                     },
                     ColumnInfo {
                         name: "title".to_owned(),
                         column_type: "text".to_owned(),
                         not_null: true,
                         primary_key: false,
+                        ..Default::default()
                     },
                     ColumnInfo {
                         name: "budget_cents".to_owned(),
                         column_type: "integer".to_owned(),
                         not_null: false,
                         primary_key: false,
+                        ..Default::default()
                     },
                     ColumnInfo {
                         name: "status".to_owned(),
                         column_type: "text".to_owned(),
                         not_null: false,
                         primary_key: false,
+                        ..Default::default()
                     },
                 ],
             },
             TableInfo {
                 name: "appliances".to_owned(),
+                description: String::new(),
                 columns: vec![
                     ColumnInfo {
                         name: "id".to_owned(),
                         column_type: "integer".to_owned(),
                         not_null: false,
                         primary_key: true,
+                        ..Default::default()
                     },
                     ColumnInfo {
                         name: "name".to_owned(),
                         column_type: "text".to_owned(),
                         not_null: true,
                         primary_key: false,
+                        ..Default::default()
                     },
                 ],
             },
@@ -1182,6 +1473,40 @@ mod tests {
         datetime!(2026-02-13 10:00 UTC)
     }
 
+    #[test]
+    fn prompts_carry_table_and_column_descriptions() {
+        let tables = [TableInfo {
+            name: "quotes".to_owned(),
+            description: "Vendor quotes for a project.".to_owned(),
+            columns: vec![
+                ColumnInfo {
+                    name: "vendor_id".to_owned(),
+                    column_type: "INTEGER".to_owned(),
+                    not_null: true,
+                    references: Some("vendors(id)".to_owned()),
+                    ..Default::default()
+                },
+                ColumnInfo {
+                    name: "total_cents".to_owned(),
+                    column_type: "INTEGER".to_owned(),
+                    description: "Money in cents; divide by 100.0 for display.".to_owned(),
+                    ..Default::default()
+                },
+            ],
+        }];
+        let sql = build_sql_prompt(&tables, prompt_test_now(), None, None);
+        assert!(sql.contains("-- Vendor quotes for a project.\nCREATE TABLE quotes (\n"));
+        assert!(sql.contains("  vendor_id INTEGER NOT NULL REFERENCES vendors(id),\n"));
+        assert!(
+            sql.contains("  total_cents INTEGER -- Money in cents; divide by 100.0 for display.\n")
+        );
+
+        let fallback = build_fallback_prompt(&tables, "", prompt_test_now(), None);
+        assert!(fallback.contains("### quotes\nVendor quotes for a project.\n"));
+        assert!(fallback.contains("- vendor_id INTEGER NOT NULL -> vendors(id)\n"));
+        assert!(fallback.contains("- total_cents INTEGER: Money in cents"));
+    }
+
     #[test]
     fn extract_sql_handles_fenced_blocks() {
         let raw = "```sql\nSELECT * FROM projects;\n```";
@@ -1194,16 +1519,77 @@ mod tests {
         assert_eq!(rendered, "(no rows)\n");
     }
 
+    #[test]
+    fn build_quick_add_prompt_lists_shapes_known_values_and_date() {
+        let prompt = build_quick_add_prompt(
+            datetime!(2026-10-16 09:30 UTC),
+            "project types: Plumbing, Roof",
+            Some("House is a 1920s bungalow"),
+        );
+        assert!(prompt.contains("Output only a single JSON object"));
+        assert!(prompt.contains("Current date: Friday, October 16, 2026"));
+        assert!(prompt.contains("\"incident\": title"));
+        assert!(prompt.contains("project types: Plumbing, Roof"));
+        assert!(prompt.contains("never invent values"));
+        assert!(prompt.contains("1920s bungalow"));
+        assert!(
+            !build_quick_add_prompt(datetime!(2026-10-16 09:30 UTC), "", None)
+                .contains("Known values")
+        );
+    }
+
+    #[test]
+    fn build_document_excerpts_quotes_each_passage_under_its_title() {
+        assert_eq!(build_document_excerpts(&[]), "");
+        let block = build_document_excerpts(&[
+            ("Furnace manual", "Filter size 16x25x1.\n"),
+            ("Receipt", "Paid $90"),
+        ]);
+        assert!(block.starts_with("\n## Document excerpts"));
+        assert!(block.contains("### Furnace manual\n\nFilter size 16x25x1.\n"));
+        assert!(block.ends_with("### Receipt\n\nPaid $90\n"));
+    }
+
+    #[test]
+    fn build_explain_prompt_includes_question_sql_and_rules() {
+        let prompt = build_explain_prompt(
+            "how much did I spend on plumbing?",
+            "SELECT SUM(cost_cents) FROM service_log_entries\n",
+            Some("House is a 1920s bungalow"),
+        );
+        assert!(prompt.contains("## User question\n\nhow much did I spend on plumbing?"));
+        assert!(prompt.contains("```sql\nSELECT SUM(cost_cents) FROM service_log_entries\n```"));
+        assert!(prompt.contains("does not answer the question"));
+        assert!(prompt.contains("1920s bungalow"));
+        assert!(!build_explain_prompt("", "SELECT 1", None).contains("User question"));
+    }
+
+    #[test]
+    fn extract_json_object_strips_fences_and_chatter() {
+        assert_eq!(
+            extract_json_object("```json\n{\"kind\": \"vendor\", \"fields\": {}}\n```"),
+            Some("{\"kind\": \"vendor\", \"fields\": {}}")
+        );
+        assert_eq!(
+            extract_json_object("Sure! {\"a\": 1} done"),
+            Some("{\"a\": 1}")
+        );
+        assert_eq!(extract_json_object("no json here"), None);
+        assert_eq!(extract_json_object("} backwards {"), None);
+    }
+
     #[test]
     fn build_sql_prompt_includes_context() {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "id".to_owned(),
                     column_type: "INTEGER".to_owned(),
                     not_null: true,
                     primary_key: true,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1243,11 +1629,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "id".to_owned(),
                     column_type: "INTEGER".to_owned(),
                     not_null: true,
                     primary_key: true,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1269,11 +1657,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "status".to_owned(),
                     column_type: "TEXT".to_owned(),
                     not_null: false,
                     primary_key: false,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1294,11 +1684,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "incidents".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "status".to_owned(),
                     column_type: "TEXT".to_owned(),
                     not_null: false,
                     primary_key: false,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1307,6 +1699,7 @@ mod tests {
         );
         assert!(prompt.contains("Incident statuses: open, in_progress."));
         assert!(prompt.contains("Incident severities: urgent, soon, whenever."));
+        assert!(prompt.contains("LOWER(extracted_text) LIKE"));
     }
 
     #[test]
@@ -1314,11 +1707,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "id".to_owned(),
                     column_type: "INTEGER".to_owned(),
                     not_null: true,
                     primary_key: true,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1336,11 +1731,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "status".to_owned(),
                     column_type: "TEXT".to_owned(),
                     not_null: false,
                     primary_key: false,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1356,11 +1753,13 @@ mod tests {
         let prompt = build_sql_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "status".to_owned(),
                     column_type: "TEXT".to_owned(),
                     not_null: false,
                     primary_key: false,
+                    ..Default::default()
                 }],
             }],
             OffsetDateTime::UNIX_EPOCH,
@@ -1406,18 +1805,21 @@ mod tests {
         let prompt = build_fallback_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![
                     ColumnInfo {
                         name: "id".to_owned(),
                         column_type: "INTEGER".to_owned(),
                         not_null: true,
                         primary_key: true,
+                        ..Default::default()
                     },
                     ColumnInfo {
                         name: "title".to_owned(),
                         column_type: "TEXT".to_owned(),
                         not_null: true,
                         primary_key: false,
+                        ..Default::default()
                     },
                 ],
             }],
@@ -1455,11 +1857,13 @@ mod tests {
         let prompt = build_fallback_prompt(
             &[TableInfo {
                 name: "incidents".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "status".to_owned(),
                     column_type: "TEXT".to_owned(),
                     not_null: false,
                     primary_key: false,
+                    ..Default::default()
                 }],
             }],
             "",
@@ -1478,11 +1882,13 @@ mod tests {
         let prompt = build_fallback_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "id".to_owned(),
                     column_type: "INTEGER".to_owned(),
                     not_null: true,
                     primary_key: true,
+                    ..Default::default()
                 }],
             }],
             "",
@@ -1500,11 +1906,13 @@ mod tests {
         let prompt = build_fallback_prompt(
             &[TableInfo {
                 name: "projects".to_owned(),
+                description: String::new(),
                 columns: vec![ColumnInfo {
                     name: "id".to_owned(),
                     column_type: "INTEGER".to_owned(),
                     not_null: true,
                     primary_key: true,
+                    ..Default::default()
                 }],
             }],
             "### projects (1 rows)\n- title: Deck",
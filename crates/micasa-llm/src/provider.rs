@@ -0,0 +1,189 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use super::{Client, ModelsResponse, PullScanner, clean_error_response, connection_error};
+use anyhow::{Context, Result};
+use std::fmt;
+use std::io::{BufRead, BufReader};
+use std::sync::Arc;
+
+pub const DEFAULT_PROVIDER: &str = "ollama";
+pub const PROVIDERS: &[&str] = &[DEFAULT_PROVIDER, "openai", "llamacpp"];
+
+/// What differs between chat backends. Every supported server speaks the
+/// OpenAI chat-completions API, so chat and streaming are shared; providers
+/// only decide how models are listed, matched, and fetched, and what to tell
+/// the user when the server is down or a model is missing.
+pub trait LlmProvider: fmt::Debug + Send + Sync {
+    /// Name used by `[llm] provider` and `/provider`.
+    fn name(&self) -> &'static str;
+    /// Base URL when the config doesn't set one.
+    fn default_base_url(&self) -> &'static str;
+    /// How to get an unreachable server running.
+    fn start_hint(&self) -> &'static str;
+    /// What to run when `model` isn't on the server.
+    fn missing_model_hint(&self, model: &str) -> String;
+
+    fn list_models(&self, client: &Client) -> Result<Vec<String>> {
+        openai_models(client)
+    }
+
+    /// Whether the server can answer as `model`. A bare name also matches any
+    /// tag of it, so `qwen3` finds `qwen3:8b`.
+    fn has_model(&self, available: &[String], model: &str) -> bool {
+        available
+            .iter()
+            .any(|name| name == model || name.starts_with(&format!("{model}:")))
+    }
+
+    /// Starts downloading `model`, or `None` when the backend can't.
+    fn pull_model(&self, _client: &Client, _model: &str) -> Result<Option<PullScanner>> {
+        Ok(None)
+    }
+}
+
+/// The provider registered under `name`, if any.
+pub fn provider_named(name: &str) -> Option<Arc<dyn LlmProvider>> {
+    match name {
+        "ollama" => Some(Arc::new(Ollama)),
+        "openai" => Some(Arc::new(OpenAiCompatible)),
+        "llamacpp" => Some(Arc::new(LlamaCpp)),
+        _ => None,
+    }
+}
+
+/// A local Ollama server; missing models are pulled on demand.
+#[derive(Debug, Clone, Copy)]
+pub struct Ollama;
+
+impl LlmProvider for Ollama {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn default_base_url(&self) -> &'static str {
+        "http://localhost:11434/v1"
+    }
+
+    fn start_hint(&self) -> &'static str {
+        "start it with `ollama serve`"
+    }
+
+    fn missing_model_hint(&self, model: &str) -> String {
+        format!("pull it with `ollama pull {model}`")
+    }
+
+    fn pull_model(&self, client: &Client, model: &str) -> Result<Option<PullScanner>> {
+        let ollama_base = client
+            .base_url
+            .trim_end_matches("/v1")
+            .trim_end_matches('/')
+            .to_owned();
+
+        let response = client
+            .authorize(client.http.post(format!("{ollama_base}/api/pull")))
+            .json(&serde_json::json!({ "name": model }))
+            .send()
+            .map_err(|error| connection_error(&ollama_base, self.start_hint(), error))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(clean_error_response(status, &body));
+        }
+
+        Ok(Some(PullScanner {
+            lines: BufReader::new(response).lines(),
+        }))
+    }
+}
+
+/// Any server with the OpenAI API: OpenAI itself, vLLM, LM Studio, and
+/// hosted gateways. Models must already exist there.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenAiCompatible;
+
+impl LlmProvider for OpenAiCompatible {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn default_base_url(&self) -> &'static str {
+        "https://api.openai.com/v1"
+    }
+
+    fn start_hint(&self) -> &'static str {
+        "check llm.base_url and that the server is running"
+    }
+
+    fn missing_model_hint(&self, _model: &str) -> String {
+        "run `/models` and choose one the server lists".to_owned()
+    }
+}
+
+/// A llama.cpp `llama-server`. It serves the one model it was started with
+/// and answers to any model name, so there is nothing to match or pull.
+#[derive(Debug, Clone, Copy)]
+pub struct LlamaCpp;
+
+impl LlmProvider for LlamaCpp {
+    fn name(&self) -> &'static str {
+        "llamacpp"
+    }
+
+    fn default_base_url(&self) -> &'static str {
+        "http://localhost:8080/v1"
+    }
+
+    fn start_hint(&self) -> &'static str {
+        "start it with `llama-server -m <model.gguf>`"
+    }
+
+    fn missing_model_hint(&self, _model: &str) -> String {
+        "restart `llama-server` with the model you want".to_owned()
+    }
+
+    fn has_model(&self, available: &[String], _model: &str) -> bool {
+        !available.is_empty()
+    }
+}
+
+/// `GET {base_url}/models` in the OpenAI list format.
+fn openai_models(client: &Client) -> Result<Vec<String>> {
+    let response = client
+        .authorize(client.http.get(format!("{}/models", client.base_url)))
+        .send()
+        .map_err(|error| connection_error(&client.base_url, client.provider.start_hint(), error))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().unwrap_or_default();
+        return Err(clean_error_response(status, &body));
+    }
+
+    let parsed: ModelsResponse = response.json().context("decode model list")?;
+    Ok(parsed.data.into_iter().map(|model| model.id).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PROVIDERS, provider_named};
+
+    #[test]
+    fn every_listed_provider_resolves_and_matches_models_its_own_way() {
+        for name in PROVIDERS {
+            let provider = provider_named(name).expect("listed provider exists");
+            assert_eq!(provider.name(), *name);
+            assert!(provider.default_base_url().starts_with("http"));
+        }
+        assert!(provider_named("anthropic").is_none());
+
+        let available = vec!["qwen3:8b".to_owned()];
+        let ollama = provider_named("ollama").expect("ollama");
+        assert!(ollama.has_model(&available, "qwen3"));
+        assert!(!ollama.has_model(&available, "llama3"));
+        let llamacpp = provider_named("llamacpp").expect("llamacpp");
+        assert!(llamacpp.has_model(&available, "anything"));
+        assert!(!llamacpp.has_model(&[], "anything"));
+    }
+}
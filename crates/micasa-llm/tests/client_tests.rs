@@ -2,12 +2,14 @@
 // Licensed under the Apache License, Version 2.0
 
 use anyhow::{Result, anyhow};
-use micasa_llm::{Client, Message, Role};
+use micasa_llm::{Client, Message, Role, provider_named};
 use std::io::{Read, Write};
 use std::net::TcpListener;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tiny_http::{Header, Response, Server};
 
 #[test]
@@ -22,6 +24,25 @@ fn ping_error_contains_actionable_remediation() {
     assert!(message.contains("ollama serve"));
 }
 
+#[test]
+fn probe_only_fails_when_the_server_cannot_be_reached() -> Result<()> {
+    let down = Client::new("http://127.0.0.1:1/v1", "qwen3", Duration::from_millis(50))?;
+    let error = down.probe().expect_err("nothing listens on port 1");
+    assert!(error.to_string().contains("cannot reach"));
+
+    let server = Server::http("127.0.0.1:0").map_err(|error| anyhow!("start server: {error}"))?;
+    let base_url = format!("http://{}/v1", server.server_addr());
+    let handle = thread::spawn(move || {
+        let request = server.recv().expect("probe request");
+        request
+            .respond(Response::from_string("unauthorized").with_status_code(401))
+            .expect("respond");
+    });
+    Client::new(&base_url, "qwen3", Duration::from_secs(5))?.probe()?;
+    handle.join().expect("server thread");
+    Ok(())
+}
+
 #[test]
 fn list_models_server_down_returns_actionable_error() {
     let client = Client::new("http://127.0.0.1:1/v1", "qwen3", Duration::from_millis(50))
@@ -191,6 +212,102 @@ fn chat_stream_drop_disconnects_server_promptly() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn chat_stream_cancelable_stops_while_server_is_silent() -> Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .map_err(|error| anyhow!("bind cancellation test server: {error}"))?;
+    let addr = listener
+        .local_addr()
+        .map_err(|error| anyhow!("read local addr: {error}"))?;
+    let base_url = format!("http://{addr}/v1");
+
+    let (resume_tx, resume_rx) = mpsc::channel::<()>();
+    let (done_tx, done_rx) = mpsc::channel();
+    let handle = thread::spawn(move || {
+        let (mut socket, _) = listener.accept().expect("accept connection");
+        let mut request = [0u8; 4096];
+        let _ = socket.read(&mut request).expect("read request bytes");
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nConnection: close\r\n\r\ndata: {\"choices\":[{\"delta\":{\"content\":\"think\"},\"finish_reason\":null}]}\n",
+            )
+            .expect("write first stream chunk");
+        socket.flush().expect("flush first stream chunk");
+
+        // Stay silent like a model mid-thought until the client has given up.
+        resume_rx.recv().expect("resume signal");
+        let _ = socket.write_all(
+            b"data: {\"choices\":[{\"delta\":{\"content\":\"ing\"},\"finish_reason\":null}]}\n",
+        );
+        let _ = socket.flush();
+        socket
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .expect("set read timeout");
+        let mut disconnected = false;
+        for _ in 0..40 {
+            let mut probe = [0u8; 1];
+            match socket.read(&mut probe) {
+                Ok(0) => {
+                    disconnected = true;
+                    break;
+                }
+                Ok(_) => {}
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) => {}
+                Err(_) => {
+                    disconnected = true;
+                    break;
+                }
+            }
+        }
+        done_tx
+            .send(disconnected)
+            .expect("report disconnect status");
+    });
+
+    let client = Client::new(&base_url, "qwen3", Duration::from_secs(30))?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    let mut stream = client.chat_stream_cancelable(
+        &[Message {
+            role: Role::User,
+            content: "Think hard".to_owned(),
+        }],
+        Arc::clone(&cancel),
+    );
+    let first = stream.next().expect("first chunk should exist")?;
+    assert_eq!(first.content, "think");
+
+    let canceler = {
+        let cancel = Arc::clone(&cancel);
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            cancel.store(true, Ordering::Release);
+        })
+    };
+    let started = Instant::now();
+    assert!(stream.next().is_none(), "canceled stream should end");
+    assert!(
+        started.elapsed() < Duration::from_secs(2),
+        "cancel should not wait for the next token: {:?}",
+        started.elapsed()
+    );
+    canceler.join().expect("canceler should join");
+
+    resume_tx.send(()).expect("resume server");
+    let disconnected = done_rx
+        .recv_timeout(Duration::from_secs(3))
+        .expect("server should report disconnect");
+    assert!(
+        disconnected,
+        "reader should drop the connection after cancel"
+    );
+    handle.join().expect("server thread should join");
+    Ok(())
+}
+
 #[test]
 fn chat_stream_parses_server_sent_events() -> Result<()> {
     let server =
@@ -540,6 +657,78 @@ fn model_and_base_url_accessors_and_setter_work() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn openai_provider_sends_the_api_key_and_never_pulls() -> Result<()> {
+    let server =
+        Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+    let addr = format!("http://{}/v1", server.server_addr());
+
+    let handle = thread::spawn(move || {
+        let request = server.recv().expect("request expected");
+        assert_eq!(request.url(), "/v1/models");
+        let auth = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .map(|header| header.value.to_string());
+        assert_eq!(auth.as_deref(), Some("Bearer sk-test"));
+        let response =
+            Response::from_string(r#"{"data":[{"id":"gpt-4o-mini"}]}"#).with_status_code(200);
+        request.respond(response).expect("response should succeed");
+    });
+
+    let client = Client::new(&addr, "gpt-4o", Duration::from_secs(1))?
+        .with_provider(provider_named("openai").expect("openai provider"))
+        .with_api_key(Some(" sk-test "));
+    let error = client.ping().expect_err("gpt-4o is not listed");
+    assert!(error.to_string().contains("run `/models`"), "{error}");
+    assert!(client.pull_model("gpt-4o")?.is_none());
+    handle.join().expect("server thread should join");
+
+    let down = Client::new("http://127.0.0.1:1/v1", "any", Duration::from_millis(50))?
+        .with_provider(provider_named("llamacpp").expect("llamacpp provider"));
+    let error = down.list_models().expect_err("server is down");
+    assert!(error.to_string().contains("llama-server"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn embed_posts_inputs_and_returns_vectors_in_input_order() -> Result<()> {
+    let server =
+        Server::http("127.0.0.1:0").map_err(|error| anyhow!("start mock server: {error}"))?;
+    let addr = format!("http://{}/v1", server.server_addr());
+
+    let handle = thread::spawn(move || {
+        let mut request = server.recv().expect("request expected");
+        assert_eq!(request.url(), "/v1/embeddings");
+        let mut body = String::new();
+        request
+            .as_reader()
+            .read_to_string(&mut body)
+            .expect("request body should be readable");
+        assert!(body.contains("\"model\":\"nomic-embed-text\""), "{body}");
+        assert!(
+            body.contains("\"input\":[\"furnace\",\"filter\"]"),
+            "{body}"
+        );
+        let response = Response::from_string(
+            r#"{"data":[{"index":1,"embedding":[0.0,1.0]},{"index":0,"embedding":[1.0,0.5]}]}"#,
+        )
+        .with_status_code(200);
+        request.respond(response).expect("response should succeed");
+    });
+
+    let client = Client::new(&addr, "qwen3", Duration::from_secs(1))?;
+    assert!(client.embed("nomic-embed-text", &[])?.is_empty());
+    let vectors = client.embed(
+        "nomic-embed-text",
+        &["furnace".to_owned(), "filter".to_owned()],
+    )?;
+    assert_eq!(vectors, vec![vec![1.0, 0.5], vec![0.0, 1.0]]);
+    handle.join().expect("server thread should join");
+    Ok(())
+}
+
 #[test]
 fn pull_model_posts_to_ollama_api_and_streams_chunks() -> Result<()> {
     let server =
@@ -574,7 +763,9 @@ fn pull_model_posts_to_ollama_api_and_streams_chunks() -> Result<()> {
     });
 
     let client = Client::new(&addr, "qwen3", Duration::from_secs(1))?;
-    let mut scanner = client.pull_model("qwen3:32b")?;
+    let mut scanner = client
+        .pull_model("qwen3:32b")?
+        .expect("ollama can pull models");
 
     let first = scanner
         .next_chunk()?
@@ -0,0 +1,295 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! The `:` command line: typed commands like `goto projects 12` or
+//! `sort budget desc` for reaching what otherwise takes several keys, with
+//! tab completion of command names, tabs, and the active tab's columns.
+
+use anyhow::{Result, anyhow, bail};
+use micasa_app::{SortDirection, TabKind, tab_for_slug};
+
+/// What completion offers; `q` works as a short `quit`.
+const COMMANDS: [&str; 7] = [
+    "archive", "export", "filter", "goto", "import", "quit", "sort",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Command {
+    /// Archives every completed or abandoned project.
+    ArchiveSettled,
+    /// Writes the table's shown columns to a CSV file.
+    ExportCsv,
+    /// Reads a CSV file into the active tab through the mapping overlay.
+    ImportCsv(String),
+    Goto {
+        tab: TabKind,
+        row_id: Option<i64>,
+    },
+    /// A `|` filter expression; empty clears it.
+    Filter(String),
+    /// Sorts on one column alone, or clears every sort when `None`.
+    Sort(Option<(usize, SortDirection)>),
+    Quit,
+}
+
+impl Command {
+    /// Parses `input`, with or without its leading `:`, against `columns`,
+    /// the labels of the active tab's table.
+    pub(crate) fn parse(input: &str, columns: &[&str]) -> Result<Self> {
+        let input = input.trim().trim_start_matches(':').trim_start();
+        let (name, rest) = input.split_once(char::is_whitespace).unwrap_or((input, ""));
+        let rest = rest.trim();
+        let args = rest.split_whitespace().collect::<Vec<_>>();
+        match name.to_ascii_lowercase().as_str() {
+            "" => bail!("type a command: {}", COMMANDS.join(", ")),
+            "q" | "quit" if args.is_empty() => Ok(Self::Quit),
+            "q" | "quit" => bail!("quit takes no arguments"),
+            "archive" if args.is_empty() => Ok(Self::ArchiveSettled),
+            "archive" => bail!("archive takes no arguments; it archives settled projects"),
+            "export" => match args.as_slice() {
+                [] => Ok(Self::ExportCsv),
+                [format] if format.eq_ignore_ascii_case("csv") => Ok(Self::ExportCsv),
+                [format] => bail!("export writes csv, not `{format}`"),
+                _ => bail!("usage: export csv"),
+            },
+            "import" if rest.is_empty() => bail!("usage: import <file>, like import ~/vendors.csv"),
+            "import" => Ok(Self::ImportCsv(rest.to_owned())),
+            "goto" => {
+                let [tab, id @ ..] = args.as_slice() else {
+                    bail!("usage: goto <tab> [id], like goto projects 12");
+                };
+                let tab = tab_for_slug(tab).ok_or_else(|| {
+                    anyhow!("unknown tab `{tab}`; try {}", tab_labels().join(", "))
+                })?;
+                let row_id = match id {
+                    [] => None,
+                    [id] => {
+                        Some(id.parse::<i64>().ok().filter(|id| *id > 0).ok_or_else(|| {
+                            anyhow!("row id must be a positive number, got `{id}`")
+                        })?)
+                    }
+                    _ => bail!("usage: goto <tab> [id], like goto projects 12"),
+                };
+                Ok(Self::Goto { tab, row_id })
+            }
+            "filter" => Ok(Self::Filter(rest.to_owned())),
+            "sort" => {
+                if args.is_empty() {
+                    return Ok(Self::Sort(None));
+                }
+                // Labels like `tot budget` hold spaces, so only a trailing
+                // direction is split off.
+                let (words, direction) = match args.split_last() {
+                    Some((last, words)) if !words.is_empty() => match parse_direction(last) {
+                        Some(direction) => (words, direction),
+                        None => (args.as_slice(), SortDirection::Asc),
+                    },
+                    _ => (args.as_slice(), SortDirection::Asc),
+                };
+                let name = words.join(" ");
+                let column = columns
+                    .iter()
+                    .position(|column| column.eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| {
+                        anyhow!(
+                            "unknown column `{name}`; this tab has {}",
+                            columns.join(", ")
+                        )
+                    })?;
+                Ok(Self::Sort(Some((column, direction))))
+            }
+            other => bail!("unknown command `{other}`; try {}", COMMANDS.join(", ")),
+        }
+    }
+}
+
+fn parse_direction(word: &str) -> Option<SortDirection> {
+    match word.to_ascii_lowercase().as_str() {
+        "asc" => Some(SortDirection::Asc),
+        "desc" => Some(SortDirection::Desc),
+        _ => None,
+    }
+}
+
+fn tab_labels() -> Vec<&'static str> {
+    TabKind::ALL.into_iter().map(TabKind::label).collect()
+}
+
+/// The input after a tab press, plus the choices still open when more than
+/// one candidate fits.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub(crate) struct Completion {
+    pub input: String,
+    pub choices: Vec<String>,
+}
+
+/// Completes the word being typed: a command name first, then a tab for
+/// `goto`, a column and direction for `sort`, the column of the last
+/// condition for `filter`, and `csv` for `export`.
+pub(crate) fn complete(input: &str, columns: &[&str]) -> Completion {
+    let typed = input.trim_start();
+    let Some((name, rest)) = typed.split_once(char::is_whitespace) else {
+        return extend("", typed, &COMMANDS);
+    };
+    let rest = rest.trim_start();
+    let head = &input[..input.len() - rest.len()];
+    match name.to_ascii_lowercase().as_str() {
+        "export" if !rest.contains(' ') => extend(head, rest, &["csv"]),
+        "goto" if !rest.contains(' ') => extend(head, rest, &tab_labels()),
+        "sort" => {
+            let chosen = columns.iter().find_map(|column| {
+                let (label, after) = (rest.get(..column.len())?, rest.get(column.len()..)?);
+                (label.eq_ignore_ascii_case(column) && after.starts_with(' ')).then_some(after)
+            });
+            match chosen {
+                Some(after) => {
+                    let after = after.trim_start();
+                    let head = &input[..input.len() - after.len()];
+                    extend(head, after, &["asc", "desc"])
+                }
+                None => extend(head, rest, columns),
+            }
+        }
+        "filter" => {
+            let start = [" and ", " or "]
+                .iter()
+                .filter_map(|joiner| rest.rfind(joiner).map(|at| at + joiner.len()))
+                .max()
+                .unwrap_or(0);
+            let condition = rest[start..].trim_start();
+            if condition.contains(['=', '!', '<', '>', '~']) {
+                return unchanged(input);
+            }
+            extend(&input[..input.len() - condition.len()], condition, columns)
+        }
+        _ => unchanged(input),
+    }
+}
+
+fn unchanged(input: &str) -> Completion {
+    Completion {
+        input: input.to_owned(),
+        choices: Vec::new(),
+    }
+}
+
+/// Fills in `partial` from `candidates`: a single match is finished off with
+/// a space, several are extended to the prefix they share.
+fn extend(head: &str, partial: &str, candidates: &[&str]) -> Completion {
+    let lower = partial.to_ascii_lowercase();
+    let matches = candidates
+        .iter()
+        .filter(|candidate| candidate.to_ascii_lowercase().starts_with(&lower))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [] => unchanged(&format!("{head}{partial}")),
+        [only] => unchanged(&format!("{head}{only} ")),
+        [first, others @ ..] => {
+            let shared = others.iter().fold(first.len(), |shared, other| {
+                first
+                    .bytes()
+                    .zip(other.bytes())
+                    .take(shared)
+                    .take_while(|(left, right)| left == right)
+                    .count()
+            });
+            let filled = if shared > partial.len() {
+                &first[..shared]
+            } else {
+                partial
+            };
+            Completion {
+                input: format!("{head}{filled}"),
+                choices: matches.iter().map(|choice| (**choice).to_owned()).collect(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Command, complete};
+    use micasa_app::{SortDirection, TabKind};
+
+    const COLUMNS: [&str; 5] = ["id", "title", "status", "budget", "tot budget"];
+
+    #[test]
+    fn commands_parse_into_their_arguments() {
+        let parse = |input: &str| Command::parse(input, &COLUMNS).expect(input);
+        assert_eq!(parse(":q"), Command::Quit);
+        assert_eq!(parse("export CSV"), Command::ExportCsv);
+        assert_eq!(parse("archive"), Command::ArchiveSettled);
+        assert_eq!(
+            parse("import /tmp/my vendors.csv"),
+            Command::ImportCsv("/tmp/my vendors.csv".to_owned())
+        );
+        assert_eq!(
+            parse("goto maintenance 12"),
+            Command::Goto {
+                tab: TabKind::Maintenance,
+                row_id: Some(12),
+            }
+        );
+        assert_eq!(
+            parse("goto vendors"),
+            Command::Goto {
+                tab: TabKind::Vendors,
+                row_id: None,
+            }
+        );
+        assert_eq!(
+            parse("filter  status=wip and budget>5 "),
+            Command::Filter("status=wip and budget>5".to_owned())
+        );
+        assert_eq!(
+            parse("sort tot budget desc"),
+            Command::Sort(Some((4, SortDirection::Desc)))
+        );
+        assert_eq!(
+            parse("sort Budget"),
+            Command::Sort(Some((3, SortDirection::Asc)))
+        );
+        assert_eq!(parse("sort"), Command::Sort(None));
+
+        for (input, expected) in [
+            ("", "type a command"),
+            ("open 3", "unknown command `open`"),
+            ("export json", "not `json`"),
+            ("import", "usage: import <file>"),
+            ("goto garage", "unknown tab `garage`"),
+            ("goto projects x", "positive number"),
+            ("sort cost", "unknown column `cost`; this tab has id, title"),
+            ("q now", "no arguments"),
+            ("archive 12", "archives settled projects"),
+        ] {
+            let error = Command::parse(input, &COLUMNS).expect_err(input);
+            assert!(error.to_string().contains(expected), "{input}: {error}");
+        }
+    }
+
+    #[test]
+    fn tab_completes_names_tabs_columns_and_directions() {
+        let done = |input: &str| complete(input, &COLUMNS).input;
+        assert_eq!(done("go"), "goto ");
+        assert_eq!(done("im"), "import ");
+        assert_eq!(done("ar"), "archive ");
+        assert_eq!(done("goto pro"), "goto projects ");
+        assert_eq!(done("export "), "export csv ");
+        assert_eq!(done("sort st"), "sort status ");
+        assert_eq!(done("sort tot budget d"), "sort tot budget desc ");
+        assert_eq!(
+            done("filter status=wip and bu"),
+            "filter status=wip and budget "
+        );
+        assert_eq!(done("filter status=w"), "filter status=w");
+        assert_eq!(done("frob"), "frob");
+
+        let open = complete("sort t", &COLUMNS);
+        assert_eq!(open.input, "sort t");
+        assert_eq!(open.choices, vec!["title", "tot budget"]);
+        let shared = complete("goto s", &COLUMNS);
+        assert_eq!(shared.input, "goto s");
+        assert_eq!(shared.choices, vec!["service", "schedule", "settings"]);
+        assert_eq!(complete("sort bu", &COLUMNS).input, "sort budget ");
+    }
+}
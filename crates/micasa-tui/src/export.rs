@@ -0,0 +1,158 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+use crate::{LinkNames, TabSnapshot, TableCell, TableProjection};
+use micasa_app::TabKind;
+use time::OffsetDateTime;
+
+/// The table as shown, limited to the chosen columns: rows after sorting and
+/// filtering. Values are exact (money as `1234.56`, not `1.2k`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableExport {
+    pub tab: TabKind,
+    pub title: String,
+    pub columns: Vec<String>,
+    /// Entity id of each row, parallel to `rows`.
+    pub row_ids: Vec<i64>,
+    pub rows: Vec<Vec<String>>,
+    /// Write names instead of ids in columns that point at other rows,
+    /// such as a quote's vendor.
+    pub resolve_names: bool,
+}
+
+impl TableExport {
+    /// Just the `columns` (projection indices), in the order given.
+    pub(crate) fn from_projection(
+        tab: TabKind,
+        projection: &TableProjection,
+        columns: &[usize],
+    ) -> Self {
+        Self {
+            tab,
+            title: projection.title.to_owned(),
+            columns: columns
+                .iter()
+                .map(|index| projection.columns[*index].to_owned())
+                .collect(),
+            row_ids: projection
+                .rows
+                .iter()
+                .map(|row| match row.cells.first() {
+                    Some(TableCell::Integer(id)) => *id,
+                    _ => 0,
+                })
+                .collect(),
+            rows: projection
+                .rows
+                .iter()
+                .map(|row| {
+                    columns
+                        .iter()
+                        .map(|index| row.cells.get(*index).map(csv_value).unwrap_or_default())
+                        .collect()
+                })
+                .collect(),
+            resolve_names: false,
+        }
+    }
+
+    /// Every column of a loaded tab, in load order with no sorting or
+    /// filtering: the table as it looks before any view settings. For
+    /// readers outside the TUI, such as the web view.
+    pub fn from_snapshot(tab: TabKind, snapshot: &TabSnapshot, names: &LinkNames) -> Self {
+        let projection = crate::base_projection(snapshot, names);
+        let columns = (0..projection.columns.len()).collect::<Vec<_>>();
+        Self::from_projection(tab, &projection, &columns)
+    }
+
+    /// RFC 4180 CSV with a header row and CRLF line endings.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        for record in std::iter::once(&self.columns).chain(&self.rows) {
+            let line = record
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
+            out.push_str(&line);
+            out.push_str("\r\n");
+        }
+        out
+    }
+
+    /// `micasa-projects-20261016-142233.csv`
+    pub fn file_name(&self, now: OffsetDateTime) -> String {
+        format!(
+            "micasa-{}-{:04}{:02}{:02}-{:02}{:02}{:02}.csv",
+            self.title.replace(' ', "-"),
+            now.year(),
+            u8::from(now.month()),
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        )
+    }
+}
+
+fn csv_value(cell: &TableCell) -> String {
+    match cell {
+        TableCell::Money(Some(cents)) => {
+            let sign = if *cents < 0 { "-" } else { "" };
+            let absolute = cents.unsigned_abs();
+            format!("{sign}{}.{:02}", absolute / 100, absolute % 100)
+        }
+        TableCell::Decimal(Some(value)) => value.to_string(),
+        // A spreadsheet wants the instant, not "3d ago" as of the export.
+        TableCell::Timestamp(at) => at
+            .format(&time::format_description::well_known::Rfc3339)
+            .unwrap_or_else(|_| cell.display()),
+        other => other.display(),
+    }
+}
+
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) || value.trim() != value {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TableExport;
+    use crate::{TableCell, TableProjection, TableRowProjection};
+    use micasa_app::TabKind;
+    use time::macros::datetime;
+
+    #[test]
+    fn export_keeps_visible_columns_and_exact_values() {
+        let projection = TableProjection {
+            title: "projects",
+            columns: vec!["id", "title", "budget", "notes", "updated"],
+            rows: vec![TableRowProjection {
+                cells: vec![
+                    TableCell::Integer(7),
+                    TableCell::Text("Deck, \"phase 2\"".to_owned()),
+                    TableCell::Money(Some(1_234_567)),
+                    TableCell::Text("hidden".to_owned()),
+                    TableCell::Timestamp(datetime!(2026-10-13 09:00:00 UTC)),
+                ],
+                deleted: false,
+                tag: None,
+                outline: None,
+            }],
+        };
+        let export = TableExport::from_projection(TabKind::Projects, &projection, &[0, 1, 2, 4]);
+        assert_eq!(export.row_ids, vec![7]);
+        assert_eq!(
+            export.to_csv(),
+            "id,title,budget,updated\r\n7,\"Deck, \"\"phase 2\"\"\",12345.67,2026-10-13T09:00:00Z\r\n"
+        );
+        assert_eq!(
+            export.file_name(datetime!(2026-10-16 14:22:33 UTC)),
+            "micasa-projects-20261016-142233.csv"
+        );
+    }
+}
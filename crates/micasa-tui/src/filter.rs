@@ -0,0 +1,289 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! The `|` scratch filter: conditions like `status=open and cost>200` typed
+//! against the active tab's columns, for narrowing a table past what a
+//! single pin can say without leaving the keyboard.
+
+use crate::TableCell;
+use anyhow::{Result, anyhow, bail};
+use std::cmp::Ordering;
+use time::Date;
+use time::macros::format_description;
+
+/// Longest first, so `>=` is not read as `>` followed by `=5`.
+const OPERATORS: [(&str, Op); 7] = [
+    (">=", Op::Ge),
+    ("<=", Op::Le),
+    ("!=", Op::Ne),
+    ("=", Op::Eq),
+    (">", Op::Gt),
+    ("<", Op::Lt),
+    ("~", Op::Contains),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    column: usize,
+    op: Op,
+    value: String,
+}
+
+/// A parsed expression: rows match when every condition of any one group
+/// holds, so `and` binds tighter than `or`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RowFilter {
+    /// The expression as typed, for the table title and the prompt.
+    pub text: String,
+    groups: Vec<Vec<Condition>>,
+}
+
+impl RowFilter {
+    /// Parses `input` against `columns`, the labels of the tab's table.
+    pub(crate) fn parse(input: &str, columns: &[&str]) -> Result<Self> {
+        let mut groups = vec![Vec::new()];
+        let mut words = Vec::new();
+        for word in split_words(input) {
+            let joiner = word.to_ascii_lowercase();
+            if joiner == "and" || joiner == "or" {
+                let condition = parse_condition(&words.join(" "), &joiner, columns)?;
+                groups.last_mut().expect("a group").push(condition);
+                words.clear();
+                if joiner == "or" {
+                    groups.push(Vec::new());
+                }
+            } else {
+                words.push(word);
+            }
+        }
+        if words.is_empty() {
+            bail!("expected a condition like cost>200 at the end");
+        }
+        let condition = parse_condition(&words.join(" "), "the end", columns)?;
+        groups.last_mut().expect("a group").push(condition);
+        Ok(Self {
+            text: input.split_whitespace().collect::<Vec<_>>().join(" "),
+            groups,
+        })
+    }
+
+    pub(crate) fn matches(&self, cells: &[TableCell]) -> bool {
+        self.groups.iter().any(|group| {
+            group.iter().all(|condition| {
+                cells
+                    .get(condition.column)
+                    .is_some_and(|cell| condition_holds(cell, condition.op, &condition.value))
+            })
+        })
+    }
+}
+
+/// Whitespace-separated words; double quotes keep spaces (and the words
+/// `and`/`or`) inside one value.
+fn split_words(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    for ch in input.chars() {
+        match ch {
+            '"' => {
+                quoted = !quoted;
+                word.push(ch);
+            }
+            ch if ch.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            ch => word.push(ch),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+fn parse_condition(raw: &str, before: &str, columns: &[&str]) -> Result<Condition> {
+    if raw.is_empty() {
+        bail!("expected a condition like cost>200 before {before}");
+    }
+    let start = raw
+        .find(['=', '!', '<', '>', '~'])
+        .ok_or_else(|| anyhow!("`{raw}` needs an operator: = != < <= > >= ~"))?;
+    let (op_text, op) = OPERATORS
+        .iter()
+        .find(|(text, _)| raw[start..].starts_with(text))
+        .copied()
+        .ok_or_else(|| anyhow!("`{raw}` needs an operator: = != < <= > >= ~"))?;
+    let name = raw[..start].trim();
+    if name.is_empty() {
+        bail!("`{raw}` needs a column before {op_text}");
+    }
+    let column = columns
+        .iter()
+        .position(|column| column.eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            anyhow!(
+                "unknown column `{name}`; this tab has {}",
+                columns.join(", ")
+            )
+        })?;
+    let value = raw[start + op_text.len()..].trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+    Ok(Condition {
+        column,
+        op,
+        value: value.to_owned(),
+    })
+}
+
+fn condition_holds(cell: &TableCell, op: Op, value: &str) -> bool {
+    let value = value.to_lowercase();
+    let shown = cell.display().to_lowercase();
+    if op == Op::Contains {
+        return shown.contains(&value);
+    }
+    if let TableCell::Tags(tags) = cell
+        && matches!(op, Op::Eq | Op::Ne)
+    {
+        let tag = value.trim_start_matches('#');
+        let tagged = tags
+            .iter()
+            .any(|candidate| candidate.eq_ignore_ascii_case(tag));
+        return tagged == (op == Op::Eq);
+    }
+    // Empty cells equal only an empty value and never compare.
+    if cell.is_null() || shown.is_empty() {
+        return match op {
+            Op::Eq => value.is_empty(),
+            Op::Ne => !value.is_empty(),
+            _ => false,
+        };
+    }
+    let order = match (number(cell), parse_number(&value)) {
+        (Some(left), Some(right)) => left.partial_cmp(&right),
+        _ => match (date(cell), parse_date(&value)) {
+            (Some(left), Some(right)) => Some(left.cmp(&right)),
+            _ => Some(shown.as_str().cmp(value.as_str())),
+        },
+    };
+    let Some(order) = order else {
+        return false;
+    };
+    match op {
+        Op::Eq => order == Ordering::Equal,
+        Op::Ne => order != Ordering::Equal,
+        Op::Lt => order == Ordering::Less,
+        Op::Le => order != Ordering::Greater,
+        Op::Gt => order == Ordering::Greater,
+        Op::Ge => order != Ordering::Less,
+        Op::Contains => unreachable!("contains is handled above"),
+    }
+}
+
+/// The cell as a number in the units people type: dollars for money,
+/// months for intervals.
+fn number(cell: &TableCell) -> Option<f64> {
+    match cell {
+        TableCell::Integer(value)
+        | TableCell::OptionalInteger(Some(value))
+        | TableCell::UsageBar(value) => Some(*value as f64),
+        TableCell::Money(Some(cents)) => Some(*cents as f64 / 100.0),
+        TableCell::Decimal(Some(value)) => Some(*value),
+        TableCell::IntervalMonths(months) => Some(f64::from(*months)),
+        TableCell::Progress { .. } => cell.progress_percent().map(|percent| percent as f64),
+        _ => None,
+    }
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim_start_matches('$').trim_end_matches('%');
+    value.replace(',', "").parse().ok()
+}
+
+fn date(cell: &TableCell) -> Option<Date> {
+    match cell {
+        TableCell::Date(Some(date)) => Some(*date),
+        TableCell::Timestamp(at) => Some(at.date()),
+        _ => None,
+    }
+}
+
+fn parse_date(value: &str) -> Option<Date> {
+    Date::parse(value, format_description!("[year]-[month]-[day]")).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RowFilter;
+    use crate::TableCell;
+    use time::macros::date;
+
+    const COLUMNS: [&str; 4] = ["id", "status", "cost", "date"];
+
+    fn row(status: &str, cost: Option<i64>) -> Vec<TableCell> {
+        vec![
+            TableCell::Integer(1),
+            TableCell::Text(status.to_owned()),
+            TableCell::Money(cost),
+            TableCell::Date(Some(date!(2026 - 03 - 01))),
+        ]
+    }
+
+    #[test]
+    fn conditions_compare_by_the_cell_type() {
+        let filter = RowFilter::parse("Status=OPEN and cost>200", &COLUMNS).expect("parses");
+        assert!(filter.matches(&row("open", Some(25_000))));
+        assert!(!filter.matches(&row("open", Some(20_000))));
+        assert!(!filter.matches(&row("closed", Some(90_000))));
+        assert!(
+            !filter.matches(&row("open", None)),
+            "empty costs never compare"
+        );
+
+        let filter = RowFilter::parse("date<2026-04-01 or status~\"wait and see\"", &COLUMNS)
+            .expect("parses");
+        assert!(filter.matches(&row("closed", None)));
+        let filter = RowFilter::parse("date>=2026-04-01 or status~\"wait and see\"", &COLUMNS)
+            .expect("parses");
+        assert!(filter.matches(&row("wait and see", None)));
+        assert!(!filter.matches(&row("closed", None)));
+        assert!(
+            RowFilter::parse("cost=", &COLUMNS)
+                .expect("parses")
+                .matches(&row("open", None))
+        );
+    }
+
+    #[test]
+    fn bad_expressions_say_what_is_missing() {
+        for (input, expected) in [
+            ("status", "needs an operator"),
+            ("=open", "needs a column"),
+            (
+                "owner=me",
+                "unknown column `owner`; this tab has id, status, cost, date",
+            ),
+            ("status=open and", "at the end"),
+            ("or cost>1", "before or"),
+        ] {
+            let error = RowFilter::parse(input, &COLUMNS).expect_err(input);
+            assert!(error.to_string().contains(expected), "{input}: {error}");
+        }
+    }
+}
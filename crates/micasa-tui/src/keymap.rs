@@ -0,0 +1,624 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! User-remappable key bindings for table, navigation, and edit actions.
+//! Text entry (forms, prompts, chat input) and the ctrl+q/ctrl+c/ctrl+l/ctrl+o
+//! escape hatches stay fixed so a bad keys.toml can't lock anyone out.
+
+use crate::{COLUMN_WIDTH_STEP, TableCommand};
+use anyhow::{Result, anyhow, bail};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Where an action is live. Table actions run first, then global ones, then
+/// the ones for the current mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyScope {
+    Table,
+    Global,
+    Nav,
+    Edit,
+}
+
+impl KeyScope {
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Table => "table",
+            Self::Global => "global",
+            Self::Nav => "nav",
+            Self::Edit => "edit",
+        }
+    }
+}
+
+macro_rules! actions {
+    ($($variant:ident => $name:literal, $scope:ident, [$($chord:literal),*];)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub enum Action {
+            $($variant,)*
+        }
+
+        impl Action {
+            pub const ALL: &'static [Action] = &[$(Action::$variant,)*];
+
+            /// Name used in keys.toml.
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $name,)*
+                }
+            }
+
+            pub const fn scope(self) -> KeyScope {
+                match self {
+                    $(Self::$variant => KeyScope::$scope,)*
+                }
+            }
+
+            const fn default_chords(self) -> &'static [&'static str] {
+                match self {
+                    $(Self::$variant => &[$($chord),*],)*
+                }
+            }
+        }
+    };
+}
+
+actions! {
+    MoveDown => "move_down", Table, ["j", "down"];
+    MoveUp => "move_up", Table, ["k", "up"];
+    MoveLeft => "move_left", Table, ["h", "left"];
+    MoveRight => "move_right", Table, ["l", "right"];
+    HalfPageDown => "half_page_down", Table, ["ctrl+d"];
+    HalfPageUp => "half_page_up", Table, ["ctrl+u"];
+    PageDown => "page_down", Table, ["pgdn"];
+    PageUp => "page_up", Table, ["pgup"];
+    FirstRow => "first_row", Table, ["g"];
+    LastRow => "last_row", Table, ["G"];
+    FirstColumn => "first_column", Table, ["^"];
+    LastColumn => "last_column", Table, ["$"];
+    CycleSort => "cycle_sort", Table, ["s"];
+    ClearSort => "clear_sort", Table, ["S"];
+    TogglePin => "toggle_pin", Table, ["n"];
+    ClearPins => "clear_pins", Table, ["ctrl+n"];
+    ToggleFilter => "toggle_filter", Table, ["N"];
+    InvertFilter => "invert_filter", Table, ["!"];
+    ToggleSettled => "toggle_settled", Table, ["t"];
+    HideColumn => "hide_column", Table, ["c"];
+    ShowColumns => "show_columns", Table, ["C"];
+    ColumnFinder => "column_finder", Table, ["/"];
+    RowFinder => "row_finder", Table, ["ctrl+g"];
+    MarkRow => "mark_row", Table, ["space"];
+    MarkRange => "mark_range", Table, ["V"];
+    ToggleFold => "toggle_fold", Table, ["o"];
+    ResetView => "reset_view", Table, ["ctrl+r"];
+    NarrowColumn => "narrow_column", Table, ["<"];
+    WidenColumn => "widen_column", Table, [">"];
+    Search => "search", Global, ["ctrl+f"];
+    Export => "export", Global, ["ctrl+e"];
+    Views => "views", Global, ["m"];
+    TagFilter => "tag_filter", Global, ["#"];
+    FilterExpression => "filter_expression", Global, ["|"];
+    CommandLine => "command_line", Global, [":"];
+    StatsFooter => "stats_footer", Global, ["_"];
+    ColumnStats => "column_stats", Global, ["="];
+    Reports => "reports", Global, ["ctrl+p"];
+    Plugins => "plugins", Global, ["ctrl+x"];
+    WhatIf => "what_if", Global, ["ctrl+w"];
+    NextTab => "next_tab", Global, ["f"];
+    PrevTab => "prev_tab", Global, ["b"];
+    LastTab => "last_tab", Global, ["F"];
+    FirstTab => "first_tab", Global, ["B"];
+    LinkIds => "link_ids", Global, ["ctrl+k"];
+    OpenChat => "open_chat", Global, ["@"];
+    Help => "help", Global, ["?"];
+    EnterEdit => "enter_edit", Nav, ["i"];
+    ToggleHouse => "toggle_house", Nav, ["tab"];
+    QrLink => "qr_link", Nav, ["Q"];
+    Timeline => "timeline", Nav, ["T"];
+    Split => "split", Nav, ["\\"];
+    SwitchPane => "switch_pane", Nav, ["shift+tab"];
+    RowDetail => "row_detail", Nav, ["v"];
+    Manual => "manual", Nav, ["U"];
+    AuditLog => "audit_log", Nav, ["H"];
+    Messages => "messages", Nav, ["M"];
+    ToggleDashboard => "toggle_dashboard", Nav, ["D"];
+    NavHalfPageDown => "nav_half_page_down", Nav, ["d"];
+    NavHalfPageUp => "nav_half_page_up", Nav, ["u"];
+    Open => "open", Nav, ["enter"];
+    NextResultPage => "next_result_page", Nav, ["]"];
+    PrevResultPage => "prev_result_page", Nav, ["["];
+    Back => "back", Nav, ["esc"];
+    DetailJump => "detail_jump", Nav, ["backspace"];
+    ExitEdit => "exit_edit", Edit, ["esc"];
+    Add => "add", Edit, ["a"];
+    EditCell => "edit", Edit, ["e"];
+    HouseProfile => "house_profile", Edit, ["p"];
+    DeleteRestore => "delete", Edit, ["d"];
+    ToggleDeleted => "toggle_deleted", Edit, ["x"];
+    TogglePrivate => "toggle_private", Edit, ["z"];
+    ShowPrivate => "show_private", Edit, ["Z"];
+    ToggleArchived => "toggle_archived", Edit, ["w"];
+    ShowArchived => "show_archived", Edit, ["W"];
+    AdoptInterval => "adopt_interval", Edit, ["I"];
+    AcceptQuote => "accept_quote", Edit, ["A"];
+    DuplicateEntry => "duplicate_entry", Edit, ["y"];
+    LogService => "log_service", Edit, ["L"];
+    CostSplit => "cost_split", Edit, ["%"];
+    Currency => "currency", Edit, ["M"];
+    Undo => "undo", Edit, ["u"];
+    Redo => "redo", Edit, ["r"];
+    ExportAll => "export_all", Edit, ["E"];
+    ImportAll => "import_all", Edit, ["R"];
+    Purge => "purge", Edit, ["P"];
+    Relink => "relink", Edit, ["K"];
+    Lookups => "lookups", Edit, ["T"];
+    FromTemplate => "from_template", Edit, ["ctrl+t"];
+}
+
+impl Action {
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    pub(crate) const fn table_command(self) -> Option<TableCommand> {
+        Some(match self {
+            Self::MoveDown => TableCommand::MoveRow(1),
+            Self::MoveUp => TableCommand::MoveRow(-1),
+            Self::MoveLeft => TableCommand::MoveColumn(-1),
+            Self::MoveRight => TableCommand::MoveColumn(1),
+            Self::HalfPageDown => TableCommand::MoveHalfPageDown,
+            Self::HalfPageUp => TableCommand::MoveHalfPageUp,
+            Self::PageDown => TableCommand::MoveFullPageDown,
+            Self::PageUp => TableCommand::MoveFullPageUp,
+            Self::FirstRow => TableCommand::JumpFirstRow,
+            Self::LastRow => TableCommand::JumpLastRow,
+            Self::FirstColumn => TableCommand::JumpFirstColumn,
+            Self::LastColumn => TableCommand::JumpLastColumn,
+            Self::CycleSort => TableCommand::CycleSort,
+            Self::ClearSort => TableCommand::ClearSort,
+            Self::TogglePin => TableCommand::TogglePin,
+            Self::ClearPins => TableCommand::ClearPins,
+            Self::ToggleFilter => TableCommand::ToggleFilter,
+            Self::InvertFilter => TableCommand::ToggleFilterInversion,
+            Self::ToggleSettled => TableCommand::ToggleSettled,
+            Self::HideColumn => TableCommand::HideCurrentColumn,
+            Self::ShowColumns => TableCommand::ShowAllColumns,
+            Self::ColumnFinder => TableCommand::OpenColumnFinder,
+            Self::RowFinder => TableCommand::OpenRowFinder,
+            Self::MarkRow => TableCommand::ToggleRowMark,
+            Self::MarkRange => TableCommand::ToggleRangeMark,
+            Self::ToggleFold => TableCommand::ToggleProjectFold,
+            Self::ResetView => TableCommand::ResetView,
+            Self::NarrowColumn => TableCommand::ResizeColumn(-COLUMN_WIDTH_STEP),
+            Self::WidenColumn => TableCommand::ResizeColumn(COLUMN_WIDTH_STEP),
+            _ => return None,
+        })
+    }
+
+    /// Whether the action can fire in Edit mode; conflicts only matter
+    /// between actions that are live at the same time.
+    fn live_in_edit(self) -> bool {
+        match self.scope() {
+            KeyScope::Table => self
+                .table_command()
+                .is_some_and(crate::table_command_allowed_in_edit),
+            KeyScope::Global | KeyScope::Edit => true,
+            KeyScope::Nav => false,
+        }
+    }
+
+    fn live_in_nav(self) -> bool {
+        self.scope() != KeyScope::Edit
+    }
+}
+
+/// Keys handled before the keymap is consulted.
+const FIXED_CHORDS: [(&str, &str); 4] = [
+    ("ctrl+q", "quit"),
+    ("ctrl+c", "cancel llm"),
+    ("ctrl+l", "lock"),
+    ("ctrl+o", "mag mode"),
+];
+
+/// One key plus modifiers. Shift is folded into the character for printable
+/// keys, so `G` and `shift+g` are the same chord.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            bail!("empty key; use a key such as `j`, `ctrl+d`, or `space`");
+        }
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts = trimmed.split('+').collect::<Vec<_>>();
+        let key = parts.pop().unwrap_or_default();
+        for part in parts {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => {
+                    bail!("unknown modifier `{other}` in `{trimmed}`; use ctrl, alt, or shift")
+                }
+            };
+        }
+        let code = match key.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "plus" => KeyCode::Char('+'),
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdn" | "pagedown" => KeyCode::PageDown,
+            lower => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(ch), None) => KeyCode::Char(ch),
+                    _ => match lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                        Some(n @ 1..=12) => KeyCode::F(n),
+                        _ => bail!(
+                            "unknown key `{key}` in `{trimmed}`; use a single character or a name like space, enter, esc, tab, up, pgdn, f1"
+                        ),
+                    },
+                }
+            }
+        };
+        Ok(Self::normalized(code, modifiers))
+    }
+
+    pub fn from_event(key: KeyEvent) -> Self {
+        Self::normalized(key.code, key.modifiers)
+    }
+
+    fn normalized(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(ch.to_ascii_uppercase())
+            }
+            KeyCode::Char(ch) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(ch.to_ascii_lowercase())
+            }
+            other => other,
+        };
+        Self {
+            code,
+            modifiers: modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT),
+        }
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            f.write_str("ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            f.write_str("alt+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("space"),
+            KeyCode::Char('+') => f.write_str("plus"),
+            KeyCode::Char(ch) => write!(f, "{ch}"),
+            KeyCode::Enter => f.write_str("enter"),
+            KeyCode::Esc => f.write_str("esc"),
+            KeyCode::Tab => f.write_str("tab"),
+            KeyCode::BackTab => f.write_str("shift+tab"),
+            KeyCode::Backspace => f.write_str("backspace"),
+            KeyCode::Delete => f.write_str("delete"),
+            KeyCode::Up => f.write_str("up"),
+            KeyCode::Down => f.write_str("down"),
+            KeyCode::Left => f.write_str("left"),
+            KeyCode::Right => f.write_str("right"),
+            KeyCode::Home => f.write_str("home"),
+            KeyCode::End => f.write_str("end"),
+            KeyCode::PageUp => f.write_str("pgup"),
+            KeyCode::PageDown => f.write_str("pgdn"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let chords = action
+                    .default_chords()
+                    .iter()
+                    .map(|raw| KeyChord::parse(raw).expect("default chords parse"))
+                    .collect();
+                (*action, chords)
+            })
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Defaults with `(action name, chords)` overrides applied. Each override
+    /// replaces that action's chords; an empty list unbinds it. Fails on
+    /// unknown actions, bad chords, and chords that would shadow each other.
+    pub fn with_overrides<I, S>(overrides: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (S, Vec<String>)>,
+        S: AsRef<str>,
+    {
+        let mut keymap = Self::default();
+        for (name, chords) in overrides {
+            let name = name.as_ref();
+            let action = Action::parse(name).ok_or_else(|| {
+                anyhow!("unknown action `{name}`; the help overlay (?) lists every action name")
+            })?;
+            let chords = chords
+                .iter()
+                .map(|raw| KeyChord::parse(raw))
+                .collect::<Result<Vec<_>>>()
+                .map_err(|error| anyhow!("action `{name}`: {error}"))?;
+            keymap.bindings.insert(action, chords);
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        let mut problems = Vec::new();
+        for (mode, live) in [
+            ("nav", Action::live_in_nav as fn(Action) -> bool),
+            ("edit", Action::live_in_edit),
+        ] {
+            let mut seen = BTreeMap::<String, Action>::new();
+            for (action, chords) in &self.bindings {
+                if !live(*action) {
+                    continue;
+                }
+                for chord in chords {
+                    let label = chord.to_string();
+                    let problem = if let Some((_, fixed)) =
+                        FIXED_CHORDS.iter().find(|(raw, _)| *raw == label)
+                    {
+                        format!(
+                            "`{label}` is reserved for {fixed} and can't be bound to {}",
+                            action.name()
+                        )
+                    } else if let Some(other) = seen.insert(label.clone(), *action)
+                        && other != *action
+                    {
+                        format!(
+                            "`{label}` is bound to both {} and {} in {mode} mode",
+                            other.name(),
+                            action.name()
+                        )
+                    } else {
+                        continue;
+                    };
+                    if !problems.contains(&problem) {
+                        problems.push(problem);
+                    }
+                }
+            }
+        }
+        if problems.is_empty() {
+            return Ok(());
+        }
+        bail!("key conflicts: {}", problems.join("; "))
+    }
+
+    /// The action in `scope` bound to `key`, if any.
+    pub fn action_for(&self, scope: KeyScope, key: KeyEvent) -> Option<Action> {
+        let chord = KeyChord::from_event(key);
+        self.bindings
+            .iter()
+            .find(|(action, chords)| action.scope() == scope && chords.contains(&chord))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn chords(&self, action: Action) -> &[KeyChord] {
+        self.bindings.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// `name  keys` rows for the help overlay, grouped by scope; changed
+    /// bindings are marked with `*`.
+    pub fn describe(&self) -> Vec<String> {
+        let defaults = Self::default();
+        let width = Action::ALL
+            .iter()
+            .map(|action| action.name().len())
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        for scope in [
+            KeyScope::Table,
+            KeyScope::Global,
+            KeyScope::Nav,
+            KeyScope::Edit,
+        ] {
+            lines.push(format!("{}:", scope.label()));
+            for action in Action::ALL.iter().filter(|action| action.scope() == scope) {
+                let chords = self.chords(*action);
+                let keys = if chords.is_empty() {
+                    "(unbound)".to_owned()
+                } else {
+                    chords
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let marker = if chords == defaults.chords(*action) {
+                    ' '
+                } else {
+                    '*'
+                };
+                lines.push(format!(
+                    "  {marker}{:<width$}  {keys}",
+                    action.name(),
+                    width = width
+                ));
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, KeyChord, KeyScope, Keymap};
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    #[test]
+    fn chords_parse_and_display_round_trip() {
+        for raw in [
+            "j",
+            "G",
+            "ctrl+d",
+            "space",
+            "pgdn",
+            "shift+tab",
+            "alt+x",
+            "f5",
+            "$",
+            "enter",
+        ] {
+            let chord = KeyChord::parse(raw).expect("chord should parse");
+            assert_eq!(chord.to_string(), raw);
+        }
+        assert_eq!(
+            KeyChord::parse("shift+g").expect("shift chord"),
+            KeyChord::parse("G").expect("upper chord")
+        );
+        assert_eq!(
+            KeyChord::parse("Ctrl+D").expect("mixed case modifier"),
+            KeyChord::parse("ctrl+d").expect("lower modifier")
+        );
+        assert!(KeyChord::parse("hyper+x").is_err());
+        assert!(KeyChord::parse("jk").is_err());
+        assert!(KeyChord::parse(" ").is_err());
+    }
+
+    #[test]
+    fn events_match_chords_regardless_of_shift_reporting() {
+        let keymap = Keymap::default();
+        let shifted = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        let bare = KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE);
+        assert_eq!(
+            keymap.action_for(KeyScope::Table, shifted),
+            Some(Action::LastRow)
+        );
+        assert_eq!(
+            keymap.action_for(KeyScope::Table, bare),
+            Some(Action::LastRow)
+        );
+        assert_eq!(
+            keymap.action_for(
+                KeyScope::Table,
+                KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::ClearPins)
+        );
+        assert_eq!(
+            keymap.action_for(
+                KeyScope::Edit,
+                KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE)
+            ),
+            Some(Action::DeleteRestore)
+        );
+    }
+
+    #[test]
+    fn overrides_replace_defaults_and_can_unbind() {
+        let keymap = Keymap::with_overrides([
+            ("move_down", vec!["ctrl+j".to_owned(), "down".to_owned()]),
+            ("toggle_settled", Vec::new()),
+        ])
+        .expect("overrides should apply");
+        let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(keymap.action_for(KeyScope::Table, j), None);
+        assert_eq!(
+            keymap.action_for(
+                KeyScope::Table,
+                KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL)
+            ),
+            Some(Action::MoveDown)
+        );
+        assert!(keymap.chords(Action::ToggleSettled).is_empty());
+
+        let help = keymap.describe().join("\n");
+        assert!(help.contains("*move_down"));
+        assert!(help.contains("ctrl+j, down"));
+        assert!(help.contains("(unbound)"));
+        assert!(help.contains(" cycle_sort"));
+    }
+
+    #[test]
+    fn overrides_report_conflicts_unknown_actions_and_reserved_keys() {
+        let error = Keymap::with_overrides([("add", vec!["x".to_owned()])])
+            .expect_err("x is already toggle_deleted in edit mode");
+        assert!(
+            error
+                .to_string()
+                .contains("`x` is bound to both add and toggle_deleted in edit mode"),
+            "{error}"
+        );
+
+        // Nav-only and edit-only actions may share keys.
+        Keymap::with_overrides([("enter_edit", vec!["a".to_owned()])])
+            .expect("nav and edit keys never collide");
+        // Sorting is not live in edit mode, so it may reuse an edit key...
+        Keymap::with_overrides([("cycle_sort", vec!["x".to_owned()])])
+            .expect("sort and toggle_deleted are never live together");
+        // ...but not a nav one.
+        Keymap::with_overrides([("cycle_sort", vec!["u".to_owned()])])
+            .expect_err("u is half page up in nav mode");
+
+        let error = Keymap::with_overrides([("open_chat", vec!["ctrl+q".to_owned()])])
+            .expect_err("ctrl+q is reserved");
+        assert!(error.to_string().contains("reserved for quit"));
+
+        let error =
+            Keymap::with_overrides([("fly", vec!["x".to_owned()])]).expect_err("unknown action");
+        assert!(error.to_string().contains("unknown action `fly`"));
+
+        let error =
+            Keymap::with_overrides([("undo", vec!["ctrl+".to_owned()])]).expect_err("bad chord");
+        assert!(error.to_string().contains("action `undo`"));
+    }
+
+    #[test]
+    fn default_keymap_has_no_conflicts_and_unique_names() {
+        Keymap::with_overrides(Vec::<(String, Vec<String>)>::new())
+            .expect("defaults should not conflict");
+        for action in Action::ALL {
+            assert_eq!(Action::parse(action.name()), Some(*action));
+        }
+    }
+}
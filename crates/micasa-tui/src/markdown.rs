@@ -0,0 +1,171 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Enough markdown for maintenance manuals pasted from a vendor page or a
+//! PDF: headings, lists, quotes, code blocks, and `**bold**`, `*italic*` and
+//! `` `code` `` inline. Anything else reads as the plain text it is.
+
+use crate::theme::Theme;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+
+/// Styled lines for `text`, ready for a wrapping paragraph.
+pub(crate) fn render(text: &str, theme: &Theme) -> Vec<Line<'static>> {
+    let code = Style::default().fg(theme.muted);
+    let mut lines = Vec::new();
+    let mut fenced = false;
+    for raw in text.lines() {
+        let trimmed = raw.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fenced = !fenced;
+            continue;
+        }
+        if fenced {
+            lines.push(Line::from(Span::styled(format!("  {raw}"), code)));
+            continue;
+        }
+        if let Some((level, title)) = heading(trimmed) {
+            let mut style = Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD);
+            if level == 1 {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            if level <= 2 && !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::from(Span::styled(title.to_owned(), style)));
+            continue;
+        }
+        if matches!(trimmed, "---" | "***" | "___") {
+            lines.push(Line::from(Span::styled("─".repeat(40), code)));
+            continue;
+        }
+        let indent = &raw[..raw.len() - trimmed.len()];
+        let (prefix, body) = if let Some(body) = trimmed.strip_prefix("> ") {
+            (Span::styled(format!("{indent}│ "), code), body)
+        } else if let Some(body) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|marker| trimmed.strip_prefix(marker))
+        {
+            (Span::raw(format!("{indent}• ")), body)
+        } else {
+            (Span::raw(indent.to_owned()), trimmed)
+        };
+        let mut spans = Vec::new();
+        if !prefix.content.is_empty() {
+            spans.push(prefix);
+        }
+        spans.extend(inline(body, code));
+        lines.push(Line::from(spans));
+    }
+    lines
+}
+
+/// `# Title` through `###### Title`, as the level and the title.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let title = line[level..].strip_prefix(' ')?;
+    (1..=6).contains(&level).then(|| (level, title.trim()))
+}
+
+/// Spans for one line of running text; an unclosed marker stays literal.
+fn inline(text: &str, code: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let styled = match c {
+            '`' => delimited(rest, "`").map(|(inner, after)| (inner, code, after)),
+            '*' | '_' if rest[1..].starts_with(c) => {
+                delimited(rest, &rest[..2]).map(|(inner, after)| {
+                    (inner, Style::default().add_modifier(Modifier::BOLD), after)
+                })
+            }
+            '*' | '_' => delimited(rest, &rest[..1]).map(|(inner, after)| {
+                (
+                    inner,
+                    Style::default().add_modifier(Modifier::ITALIC),
+                    after,
+                )
+            }),
+            _ => None,
+        };
+        match styled {
+            Some((inner, style, after)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::raw(std::mem::take(&mut plain)));
+                }
+                spans.push(Span::styled(inner.to_owned(), style));
+                rest = after;
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    spans
+}
+
+/// The text between `marker` at the start of `text` and its next match,
+/// and what follows; `None` when it never closes or encloses nothing.
+fn delimited<'a>(text: &'a str, marker: &str) -> Option<(&'a str, &'a str)> {
+    let body = &text[marker.len()..];
+    let end = body.find(marker)?;
+    (end > 0).then(|| (&body[..end], &body[end + marker.len()..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+    use crate::theme::Theme;
+    use ratatui::style::Modifier;
+
+    #[test]
+    fn manuals_render_headings_lists_code_and_inline_styles() {
+        let theme = Theme::default();
+        let lines = render(
+            "# Furnace\nReplace the **filter** every *3 months*.\n\n- Turn off `power`\n  * wait 5 min\n> Warning: hot\n```\nERR 31\n```\n2 * 3 stays",
+            &theme,
+        );
+        let text = lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            text,
+            vec![
+                "Furnace",
+                "Replace the filter every 3 months.",
+                "",
+                "• Turn off power",
+                "  • wait 5 min",
+                "│ Warning: hot",
+                "  ERR 31",
+                "2 * 3 stays",
+            ]
+        );
+        let heading = &lines[0].spans[0];
+        assert!(heading.style.add_modifier.contains(Modifier::BOLD));
+        assert_eq!(heading.style.fg, Some(theme.accent));
+        let bold = &lines[1].spans[1];
+        assert_eq!(bold.content, "filter");
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        assert!(
+            lines[1].spans[3]
+                .style
+                .add_modifier
+                .contains(Modifier::ITALIC)
+        );
+        assert_eq!(lines[3].spans[2].style.fg, Some(theme.muted));
+    }
+}
@@ -0,0 +1,594 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Minimal QR Code encoder: byte mode, error correction level M, versions
+//! 1-40, with the mask chosen by the standard penalty rules. Enough to put a
+//! `micasa://` link or file path on a printed page.
+
+use anyhow::{Result, bail};
+
+/// Error correction codewords per block for level M, indexed by version.
+const ECC_CODEWORDS_PER_BLOCK: [usize; 41] = [
+    0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28, 28,
+    28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+];
+
+/// Error correction blocks for level M, indexed by version.
+const NUM_ERROR_CORRECTION_BLOCKS: [usize; 41] = [
+    0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21, 23,
+    25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+];
+
+/// Format bits for level M are `00`.
+const ECC_FORMAT_BITS: u32 = 0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QrCode {
+    size: usize,
+    /// Row-major; `true` is a dark module.
+    modules: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encodes `data` in the smallest version that fits.
+    pub fn encode(data: &[u8]) -> Result<Self> {
+        let Some(version) = (1..=40)
+            .find(|version| byte_mode_bits(*version, data.len()) <= data_capacity_bits(*version))
+        else {
+            bail!(
+                "{} bytes is too long for a QR code; keep links under 2331 bytes",
+                data.len()
+            );
+        };
+
+        let capacity = data_capacity_bits(version);
+        let mut bits = BitBuffer::default();
+        bits.push(0b0100, 4);
+        bits.push(data.len() as u32, if version <= 9 { 8 } else { 16 });
+        for byte in data {
+            bits.push(u32::from(*byte), 8);
+        }
+        bits.push(0, (capacity - bits.len()).min(4));
+        bits.push(0, (8 - bits.len() % 8) % 8);
+        for pad in [0xEC, 0x11].into_iter().cycle() {
+            if bits.len() >= capacity {
+                break;
+            }
+            bits.push(pad, 8);
+        }
+
+        let mut code = Self::blank(version);
+        let mut function = vec![false; code.size * code.size];
+        code.draw_function_patterns(version, &mut function);
+        code.draw_codewords(
+            &add_ecc_and_interleave(version, &bits.to_bytes()),
+            &function,
+        );
+
+        let mut best = (usize::MAX, 0);
+        for mask in 0..8 {
+            code.apply_mask(mask, &function);
+            code.draw_format_bits(mask, &mut function);
+            let penalty = code.penalty();
+            if penalty < best.0 {
+                best = (penalty, mask);
+            }
+            code.apply_mask(mask, &function);
+        }
+        code.apply_mask(best.1, &function);
+        code.draw_format_bits(best.1, &mut function);
+        Ok(code)
+    }
+
+    fn blank(version: usize) -> Self {
+        let size = version * 4 + 17;
+        Self {
+            size,
+            modules: vec![false; size * size],
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Dark module at column `x`, row `y`; outside the symbol is light.
+    pub fn is_dark(&self, x: isize, y: isize) -> bool {
+        let size = self.size as isize;
+        (0..size).contains(&x)
+            && (0..size).contains(&y)
+            && self.modules[y as usize * self.size + x as usize]
+    }
+
+    /// Two modules per character using half blocks, with a two-module quiet
+    /// zone. Render dark-on-light (black on white) so phones can scan it.
+    pub fn to_unicode_lines(&self) -> Vec<String> {
+        let border = 2;
+        let span = -border..self.size as isize + border;
+        span.clone()
+            .step_by(2)
+            .map(|y| {
+                span.clone()
+                    .map(|x| match (self.is_dark(x, y), self.is_dark(x, y + 1)) {
+                        (true, true) => '█',
+                        (true, false) => '▀',
+                        (false, true) => '▄',
+                        (false, false) => ' ',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Standalone SVG with the spec's four-module quiet zone; `scale` is the
+    /// rendered size of one module in pixels.
+    pub fn to_svg(&self, scale: usize) -> String {
+        let border = 4;
+        let dimension = self.size + border * 2;
+        let mut path = String::new();
+        for y in 0..self.size {
+            for x in 0..self.size {
+                if self.modules[y * self.size + x] {
+                    path.push_str(&format!("M{},{}h1v1h-1z", x + border, y + border));
+                }
+            }
+        }
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{pixels}\" height=\"{pixels}\" viewBox=\"0 0 {dimension} {dimension}\" shape-rendering=\"crispEdges\"><rect width=\"100%\" height=\"100%\" fill=\"#fff\"/><path d=\"{path}\" fill=\"#000\"/></svg>",
+            pixels = dimension * scale,
+        )
+    }
+
+    fn set(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+    }
+
+    fn set_function(&mut self, function: &mut [bool], x: usize, y: usize, dark: bool) {
+        self.set(x, y, dark);
+        function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize, function: &mut [bool]) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(function, 6, i, i % 2 == 0);
+            self.set_function(function, i, 6, i % 2 == 0);
+        }
+
+        for (cx, cy) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4isize..=4 {
+                for dx in -4isize..=4 {
+                    let (x, y) = (cx as isize + dx, cy as isize + dy);
+                    if (0..size as isize).contains(&x) && (0..size as isize).contains(&y) {
+                        let distance = dx.abs().max(dy.abs());
+                        self.set_function(
+                            function,
+                            x as usize,
+                            y as usize,
+                            distance != 2 && distance != 4,
+                        );
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, cx) in positions.iter().enumerate() {
+            for (j, cy) in positions.iter().enumerate() {
+                // The three corners already hold finder patterns.
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2isize..=2 {
+                    for dx in -2isize..=2 {
+                        let x = (*cx as isize + dx) as usize;
+                        let y = (*cy as isize + dy) as usize;
+                        self.set_function(function, x, y, dx.abs().max(dy.abs()) != 1);
+                    }
+                }
+            }
+        }
+
+        // Reserve the format areas now; the real bits land once a mask is chosen.
+        self.draw_format_bits(0, function);
+
+        if version >= 7 {
+            let bits = version_bits(version);
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 == 1;
+                let a = size - 11 + i % 3;
+                let b = i / 3;
+                self.set_function(function, a, b, dark);
+                self.set_function(function, b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32, function: &mut [bool]) {
+        let bits = format_bits(mask);
+        let bit = |i: usize| (bits >> i) & 1 == 1;
+        let size = self.size;
+        for i in 0..=5 {
+            self.set_function(function, 8, i, bit(i));
+        }
+        self.set_function(function, 8, 7, bit(6));
+        self.set_function(function, 8, 8, bit(7));
+        self.set_function(function, 7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(function, 14 - i, 8, bit(i));
+        }
+        for i in 0..8 {
+            self.set_function(function, size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(function, 8, size - 15 + i, bit(i));
+        }
+        self.set_function(function, 8, size - 8, true);
+    }
+
+    fn draw_codewords(&mut self, codewords: &[u8], function: &[bool]) {
+        let size = self.size;
+        let total_bits = codewords.len() * 8;
+        let mut index = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for offset in 0..2 {
+                    let x = right - offset;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !function[y * size + x] && index < total_bits {
+                        let dark = (codewords[index >> 3] >> (7 - (index & 7))) & 1 == 1;
+                        self.set(x, y, dark);
+                        index += 1;
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u32, function: &[bool]) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                let index = y * self.size + x;
+                if invert && !function[index] {
+                    self.modules[index] = !self.modules[index];
+                }
+            }
+        }
+    }
+
+    fn penalty(&self) -> usize {
+        let size = self.size;
+        let at = |x: usize, y: usize| self.modules[y * size + x];
+        let mut penalty = 0;
+
+        for transpose in [false, true] {
+            let cell = |line: usize, i: usize| if transpose { at(line, i) } else { at(i, line) };
+            for line in 0..size {
+                let mut run = 1;
+                for i in 1..=size {
+                    if i < size && cell(line, i) == cell(line, i - 1) {
+                        run += 1;
+                        continue;
+                    }
+                    if run >= 5 {
+                        penalty += run - 2;
+                    }
+                    run = 1;
+                }
+                // Finder-like 1:1:3:1:1 runs with four light modules on a side.
+                for i in 0..size.saturating_sub(10) {
+                    let window = (i..i + 11).map(|k| cell(line, k)).collect::<Vec<_>>();
+                    let core = [true, false, true, true, true, false, true];
+                    if window[4..] == core && window[..4].iter().all(|dark| !dark)
+                        || window[..7] == core && window[7..].iter().all(|dark| !dark)
+                    {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = at(x, y);
+                if at(x + 1, y) == color && at(x, y + 1) == color && at(x + 1, y + 1) == color {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let total = size * size;
+        let dark = self.modules.iter().filter(|dark| **dark).count();
+        let deviation = (dark * 20).abs_diff(total * 10);
+        penalty + (deviation.div_ceil(total)).saturating_sub(1) * 10
+    }
+}
+
+#[derive(Default)]
+struct BitBuffer {
+    bits: Vec<bool>,
+}
+
+impl BitBuffer {
+    fn push(&mut self, value: u32, count: usize) {
+        for i in (0..count).rev() {
+            self.bits.push((value >> i) & 1 == 1);
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.bits
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u8, |byte, bit| byte << 1 | u8::from(*bit))
+            })
+            .collect()
+    }
+}
+
+fn byte_mode_bits(version: usize, len: usize) -> usize {
+    4 + if version <= 9 { 8 } else { 16 } + len * 8
+}
+
+fn raw_data_modules(version: usize) -> usize {
+    let mut modules = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignments = version / 7 + 2;
+        modules -= (25 * alignments - 10) * alignments - 55;
+        if version >= 7 {
+            modules -= 36;
+        }
+    }
+    modules
+}
+
+fn data_capacity_bits(version: usize) -> usize {
+    (raw_data_modules(version) / 8
+        - ECC_CODEWORDS_PER_BLOCK[version] * NUM_ERROR_CORRECTION_BLOCKS[version])
+        * 8
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 8 + count * 3 + 5) / (count * 4 - 4) * 2;
+    let size = version * 4 + 17;
+    let mut positions = (0..count - 1)
+        .map(|i| size - 7 - i * step)
+        .collect::<Vec<_>>();
+    positions.push(6);
+    positions.reverse();
+    positions
+}
+
+fn version_bits(version: usize) -> u32 {
+    let mut remainder = version as u32;
+    for _ in 0..12 {
+        remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1F25);
+    }
+    (version as u32) << 12 | remainder
+}
+
+fn format_bits(mask: u32) -> u32 {
+    let data = ECC_FORMAT_BITS << 3 | mask;
+    let mut remainder = data;
+    for _ in 0..10 {
+        remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+    }
+    (data << 10 | remainder) ^ 0x5412
+}
+
+fn add_ecc_and_interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = NUM_ERROR_CORRECTION_BLOCKS[version];
+    let ecc_len = ECC_CODEWORDS_PER_BLOCK[version];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw_codewords % blocks;
+    let short_len = raw_codewords / blocks;
+    let divisor = reed_solomon_divisor(ecc_len);
+
+    let mut split = Vec::with_capacity(blocks);
+    let mut start = 0;
+    for block in 0..blocks {
+        let len = short_len - ecc_len + usize::from(block >= short_blocks);
+        let mut codewords = data[start..start + len].to_vec();
+        start += len;
+        let ecc = reed_solomon_remainder(&codewords, &divisor);
+        if block < short_blocks {
+            codewords.push(0);
+        }
+        codewords.extend(ecc);
+        split.push(codewords);
+    }
+
+    let mut interleaved = Vec::with_capacity(raw_codewords);
+    for i in 0..split[0].len() {
+        for (block, codewords) in split.iter().enumerate() {
+            if i != short_len - ecc_len || block >= short_blocks {
+                interleaved.push(codewords[i]);
+            }
+        }
+    }
+    interleaved
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (slot, coefficient) in result.iter_mut().zip(divisor) {
+            *slot ^= gf_multiply(*coefficient, factor);
+        }
+    }
+    result
+}
+
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((u32::from(y) >> i) & 1) * u32::from(x);
+    }
+    z as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        QrCode, add_ecc_and_interleave, alignment_positions, data_capacity_bits, format_bits,
+        reed_solomon_divisor, reed_solomon_remainder, version_bits,
+    };
+
+    /// Reads the data codewords back out of a symbol: mask from the format
+    /// bits, then the zigzag walk over non-function modules.
+    fn read_codewords(code: &QrCode, version: usize) -> Vec<u8> {
+        let mut function = vec![false; code.size * code.size];
+        QrCode::blank(version).draw_function_patterns(version, &mut function);
+        let format = (0..=5).fold(0u32, |bits, i| {
+            bits | u32::from(code.is_dark(8, i as isize)) << i
+        });
+        let mask = (0..8)
+            .find(|mask| format_bits(*mask) & 0x3F == format)
+            .expect("format bits name a mask");
+        let mut unmasked = code.clone();
+        unmasked.apply_mask(mask, &function);
+
+        let mut bits = Vec::new();
+        let size = code.size;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..size {
+                for offset in 0..2 {
+                    let x = right - offset;
+                    let y = if (right + 1) & 2 == 0 {
+                        size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !function[y * size + x] {
+                        bits.push(unmasked.modules[y * size + x]);
+                    }
+                }
+            }
+            if right < 3 {
+                break;
+            }
+            right -= 2;
+        }
+        bits.chunks_exact(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u8, |byte, bit| byte << 1 | u8::from(*bit))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reed_solomon_matches_the_published_version_1m_example() {
+        let data = [
+            32, 91, 11, 120, 209, 114, 220, 77, 67, 64, 236, 17, 236, 17, 236, 17,
+        ];
+        assert_eq!(
+            reed_solomon_remainder(&data, &reed_solomon_divisor(10)),
+            vec![196, 35, 39, 119, 235, 215, 231, 226, 93, 23]
+        );
+        assert_eq!(add_ecc_and_interleave(1, &data).len(), 26);
+    }
+
+    #[test]
+    fn tables_match_the_spec() {
+        assert_eq!(data_capacity_bits(1), 16 * 8);
+        assert_eq!(data_capacity_bits(10), 216 * 8);
+        assert_eq!(data_capacity_bits(40), 2334 * 8);
+        assert_eq!(alignment_positions(7), vec![6, 22, 38]);
+        assert_eq!(alignment_positions(32), vec![6, 34, 60, 86, 112, 138]);
+        assert_eq!(format_bits(0), 0b101_0100_0001_0010);
+        assert_eq!(format_bits(5), 0b100_0000_1100_1110);
+        assert_eq!(version_bits(7), 0b00_0111_1100_1001_0100);
+    }
+
+    #[test]
+    fn encodes_links_with_finders_and_a_quiet_zone() {
+        let code = QrCode::encode(b"micasa://maint/12").expect("short link fits");
+        assert_eq!(code.size(), 25);
+        for (x, y) in [(0, 0), (18, 0), (0, 18)] {
+            assert!((0..7).all(|i| code.is_dark(x + i, y) && code.is_dark(x, y + i)));
+            assert!(!code.is_dark(x + 1, y + 1));
+            assert!(code.is_dark(x + 3, y + 3));
+        }
+        assert!(code.is_dark(8, 17), "dark module");
+
+        let lines = code.to_unicode_lines();
+        assert_eq!(lines.len(), 15);
+        assert!(lines[0].chars().all(|ch| ch == ' '));
+        assert_eq!(lines[0].chars().count(), 29);
+        assert!(
+            code.to_svg(4)
+                .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"132\"")
+        );
+
+        // Mode 0100, length 17, then the bytes themselves.
+        let codewords = read_codewords(&code, 2);
+        assert_eq!(&codewords[..2], &[0x41, 0x16]);
+        let payload = codewords[1..19]
+            .windows(2)
+            .map(|pair| pair[0] << 4 | pair[1] >> 4)
+            .collect::<Vec<_>>();
+        assert_eq!(payload, b"micasa://maint/12");
+
+        assert!(QrCode::encode(&[b'x'; 2332]).is_err());
+        assert_eq!(QrCode::encode(&[b'x'; 2331]).expect("max fits").size(), 177);
+    }
+}
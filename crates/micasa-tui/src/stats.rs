@@ -0,0 +1,328 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Column statistics for the `+` popup: counts, the range, and a small text
+//! histogram of the rows as shown, so skew and outliers stand out without a
+//! spreadsheet. The `=` footer is the one-line version for number columns.
+
+use crate::{TableCell, format_compact_money, format_interval_months};
+use std::collections::BTreeMap;
+use time::Date;
+
+/// Equal-width buckets for numbers and dates.
+const BUCKETS: usize = 8;
+/// Most common values listed for text columns.
+const TOP_VALUES: usize = 8;
+const BAR_WIDTH: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberKind {
+    Plain,
+    Decimal,
+    Money,
+    Months,
+    Percent,
+}
+
+impl NumberKind {
+    fn format(self, value: f64) -> String {
+        match self {
+            Self::Plain => format!("{value:.0}"),
+            Self::Decimal => format!("{value:.1}"),
+            Self::Money => format_compact_money(value.round() as i64),
+            Self::Months => format_interval_months(value.round() as i32),
+            Self::Percent => format!("{value:.0}%"),
+        }
+    }
+}
+
+/// Lines describing one column's `cells`, in row order.
+pub(crate) fn column_stats_lines(cells: &[TableCell]) -> Vec<String> {
+    let filled = cells
+        .iter()
+        .filter(|cell| !cell.is_null() && !cell.display().is_empty())
+        .collect::<Vec<_>>();
+    let mut distinct = filled.iter().map(|cell| cell.pin_key()).collect::<Vec<_>>();
+    distinct.sort();
+    distinct.dedup();
+    let mut lines = vec![format!(
+        "rows {} | empty {} | distinct {}",
+        cells.len(),
+        cells.len() - filled.len(),
+        distinct.len()
+    )];
+    let Some(first) = filled.first() else {
+        return lines;
+    };
+
+    if let Some(kind) = number_kind(first) {
+        let values = filled.iter().filter_map(|cell| number(cell)).collect();
+        lines.extend(number_lines(values, kind));
+    } else if date(first).is_some() {
+        let days = filled
+            .iter()
+            .filter_map(|cell| date(cell))
+            .map(Date::to_julian_day)
+            .collect();
+        lines.extend(date_lines(days));
+    } else {
+        lines.extend(top_value_lines(&filled));
+    }
+    lines
+}
+
+/// One line for the `=` footer: count, sum, mean, min and max of a number or
+/// money column. Other columns only get a count.
+pub(crate) fn column_footer(cells: &[TableCell]) -> String {
+    let filled = cells
+        .iter()
+        .filter(|cell| !cell.is_null() && !cell.display().is_empty())
+        .collect::<Vec<_>>();
+    let count = if filled.len() == cells.len() {
+        format!("count {}", filled.len())
+    } else {
+        format!("count {} of {}", filled.len(), cells.len())
+    };
+    let Some(kind) = filled.first().and_then(|cell| number_kind(cell)) else {
+        return count;
+    };
+    let values = filled
+        .iter()
+        .filter_map(|cell| number(cell))
+        .collect::<Vec<_>>();
+    if values.is_empty() {
+        return count;
+    }
+    let sum = values.iter().sum::<f64>();
+    let mean = sum / values.len() as f64;
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean_kind = if kind == NumberKind::Plain {
+        NumberKind::Decimal
+    } else {
+        kind
+    };
+    let mut parts = vec![count];
+    if kind != NumberKind::Percent {
+        parts.push(format!("sum {}", kind.format(sum)));
+    }
+    parts.push(format!("mean {}", mean_kind.format(mean)));
+    parts.push(format!("min {}", kind.format(min)));
+    parts.push(format!("max {}", kind.format(max)));
+    parts.join(" | ")
+}
+
+fn number_kind(cell: &TableCell) -> Option<NumberKind> {
+    Some(match cell {
+        TableCell::Integer(_) | TableCell::OptionalInteger(_) => NumberKind::Plain,
+        TableCell::Decimal(_) => NumberKind::Decimal,
+        TableCell::Money(_) => NumberKind::Money,
+        TableCell::IntervalMonths(_) => NumberKind::Months,
+        TableCell::UsageBar(_) | TableCell::Progress { .. } => NumberKind::Percent,
+        _ => return None,
+    })
+}
+
+fn number(cell: &TableCell) -> Option<f64> {
+    match cell {
+        TableCell::Integer(value)
+        | TableCell::OptionalInteger(Some(value))
+        | TableCell::Money(Some(value))
+        | TableCell::UsageBar(value) => Some(*value as f64),
+        TableCell::Decimal(Some(value)) => Some(*value),
+        TableCell::IntervalMonths(months) => Some(f64::from(*months)),
+        TableCell::Progress { .. } => cell.progress_percent().map(|percent| percent as f64),
+        _ => None,
+    }
+}
+
+fn date(cell: &TableCell) -> Option<Date> {
+    match cell {
+        TableCell::Date(Some(date)) => Some(*date),
+        TableCell::Timestamp(at) => Some(at.date()),
+        _ => None,
+    }
+}
+
+fn number_lines(mut values: Vec<f64>, kind: NumberKind) -> Vec<String> {
+    values.sort_by(f64::total_cmp);
+    let (min, max) = (values[0], values[values.len() - 1]);
+    let median = values[values.len() / 2];
+    let mut summary = format!(
+        "min {} | median {} | max {}",
+        kind.format(min),
+        kind.format(median),
+        kind.format(max)
+    );
+    if kind == NumberKind::Money {
+        summary.push_str(&format!(" | sum {}", kind.format(values.iter().sum())));
+    }
+
+    let counts = bucket_counts(&values, min, max);
+    let width = (max - min) / BUCKETS as f64;
+    let labels = (0..counts.len())
+        .map(|bucket| {
+            let low = min + width * bucket as f64;
+            format!("{} - {}", kind.format(low), kind.format(low + width))
+        })
+        .collect::<Vec<_>>();
+    let mut lines = vec![summary, String::new()];
+    lines.extend(bar_lines(&labels, &counts));
+    lines
+}
+
+fn date_lines(mut days: Vec<i32>) -> Vec<String> {
+    days.sort_unstable();
+    let (min, max) = (days[0], days[days.len() - 1]);
+    let as_date = |day: f64| {
+        Date::from_julian_day(day.round() as i32).map_or_else(|_| String::new(), |d| d.to_string())
+    };
+    let values = days.iter().map(|day| f64::from(*day)).collect::<Vec<_>>();
+    let counts = bucket_counts(&values, f64::from(min), f64::from(max));
+    let width = f64::from(max - min) / BUCKETS as f64;
+    let labels = (0..counts.len())
+        .map(|bucket| format!("from {}", as_date(f64::from(min) + width * bucket as f64)))
+        .collect::<Vec<_>>();
+    let mut lines = vec![
+        format!(
+            "earliest {} | latest {}",
+            as_date(f64::from(min)),
+            as_date(f64::from(max))
+        ),
+        String::new(),
+    ];
+    lines.extend(bar_lines(&labels, &counts));
+    lines
+}
+
+/// Counts per equal-width bucket between `min` and `max`; one bucket when
+/// every value is the same.
+fn bucket_counts(values: &[f64], min: f64, max: f64) -> Vec<usize> {
+    if max <= min {
+        return vec![values.len()];
+    }
+    let mut counts = vec![0; BUCKETS];
+    for value in values {
+        let bucket = ((value - min) / (max - min) * BUCKETS as f64) as usize;
+        counts[bucket.min(BUCKETS - 1)] += 1;
+    }
+    counts
+}
+
+/// Most common values first; tags count one by one.
+fn top_value_lines(cells: &[&TableCell]) -> Vec<String> {
+    let mut counts = BTreeMap::<String, usize>::new();
+    for cell in cells {
+        match cell {
+            TableCell::Tags(tags) => {
+                for tag in tags {
+                    *counts.entry(format!("#{tag}")).or_default() += 1;
+                }
+            }
+            _ => *counts.entry(cell.display()).or_default() += 1,
+        }
+    }
+    let mut ranked = counts.into_iter().collect::<Vec<_>>();
+    ranked.sort_by(|(left_value, left), (right_value, right)| {
+        right.cmp(left).then_with(|| left_value.cmp(right_value))
+    });
+    let rest = ranked.len().saturating_sub(TOP_VALUES);
+    ranked.truncate(TOP_VALUES);
+
+    let (labels, counts): (Vec<_>, Vec<_>) = ranked.into_iter().unzip();
+    let mut lines = vec![String::new()];
+    lines.extend(bar_lines(&labels, &counts));
+    if rest > 0 {
+        lines.push(format!("(+{rest} more values)"));
+    }
+    lines
+}
+
+fn bar_lines(labels: &[String], counts: &[usize]) -> Vec<String> {
+    let width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .max()
+        .unwrap_or_default();
+    let most = counts.iter().copied().max().unwrap_or_default().max(1);
+    labels
+        .iter()
+        .zip(counts)
+        .map(|(label, count)| {
+            let bar = if *count == 0 {
+                0
+            } else {
+                (count * BAR_WIDTH).div_ceil(most)
+            };
+            format!("{label:<width$}  {:<BAR_WIDTH$} {count}", "█".repeat(bar))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{column_footer, column_stats_lines};
+    use crate::TableCell;
+    use time::macros::date;
+
+    #[test]
+    fn money_columns_bucket_and_show_outliers() {
+        let mut cells = (1..=9)
+            .map(|dollars| TableCell::Money(Some(dollars * 100)))
+            .collect::<Vec<_>>();
+        cells.push(TableCell::Money(Some(80_000)));
+        cells.push(TableCell::Money(None));
+
+        let lines = column_stats_lines(&cells);
+        assert_eq!(lines[0], "rows 11 | empty 1 | distinct 10");
+        assert_eq!(lines[1], "min 1.00 | median 6.00 | max 800.00 | sum 845.00");
+        assert_eq!(lines.len(), 3 + 8);
+        assert!(lines[3].starts_with("1.00 - 100.88"));
+        assert!(lines[3].ends_with(" 9"), "skew sits in the first bucket");
+        assert!(lines[10].ends_with(" 1"), "the outlier gets its own bucket");
+    }
+
+    #[test]
+    fn footer_totals_money_and_only_counts_text() {
+        let cells = [
+            TableCell::Money(Some(12_000)),
+            TableCell::Money(Some(4_050)),
+            TableCell::Money(None),
+        ];
+        assert_eq!(
+            column_footer(&cells),
+            "count 2 of 3 | sum 160.50 | mean 80.25 | min 40.50 | max 120.00"
+        );
+        let counts = [TableCell::Integer(1), TableCell::Integer(2)];
+        assert_eq!(
+            column_footer(&counts),
+            "count 2 | sum 3 | mean 1.5 | min 1 | max 2"
+        );
+        let text = [
+            TableCell::Text("Plan".to_owned()),
+            TableCell::Text(String::new()),
+        ];
+        assert_eq!(column_footer(&text), "count 1 of 2");
+    }
+
+    #[test]
+    fn text_columns_rank_top_values_and_dates_span_the_range() {
+        let text = ["Plan", "Done", "Plan", "Active", "Plan"]
+            .into_iter()
+            .map(|value| TableCell::Text(value.to_owned()))
+            .collect::<Vec<_>>();
+        let lines = column_stats_lines(&text);
+        assert_eq!(lines[0], "rows 5 | empty 0 | distinct 3");
+        assert!(lines[2].starts_with("Plan  "));
+        assert!(lines[2].ends_with(" 3"));
+        assert!(lines[3].starts_with("Active"), "ties sort by value");
+
+        let dates = [date!(2026 - 01 - 01), date!(2026 - 01 - 01)]
+            .into_iter()
+            .map(|day| TableCell::Date(Some(day)))
+            .collect::<Vec<_>>();
+        let lines = column_stats_lines(&dates);
+        assert_eq!(lines[1], "earliest 2026-01-01 | latest 2026-01-01");
+        assert_eq!(lines.len(), 4, "one bucket when every date matches");
+    }
+}
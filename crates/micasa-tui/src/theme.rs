@@ -0,0 +1,293 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Color palettes for the table, status line, and overlays. The QR preview is
+//! the one exception: it stays black on white so phones can scan it.
+
+use anyhow::{Result, anyhow, bail};
+use ratatui::style::Color;
+
+/// Name of the palette built from `[ui.palette]`.
+pub const CUSTOM_THEME: &str = "custom";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,
+    /// Tab bar and other plain chrome.
+    pub text: Color,
+    /// Active tab and overlay borders.
+    pub accent: Color,
+    pub header: Color,
+    pub status: Color,
+    pub alert_fg: Color,
+    pub alert_bg: Color,
+    /// Deleted rows and rows dimmed by a pin preview.
+    pub muted: Color,
+    pub private: Color,
+    pub archived: Color,
+    pub marked: Color,
+    pub selected_bg: Color,
+    pub cursor_fg: Color,
+    pub cursor_bg: Color,
+    /// Urgent incidents on the dashboard.
+    pub urgent: Color,
+    /// Overdue maintenance on the dashboard.
+    pub overdue: Color,
+    /// Warranties and insurance about to end, or already ended.
+    pub expiring: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub const PRESETS: [&'static str; 3] = ["dark", "light", "high-contrast"];
+
+    /// Palette tuned for dark terminal backgrounds; the long-standing look.
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_owned(),
+            text: Color::White,
+            accent: Color::Cyan,
+            header: Color::White,
+            status: Color::Yellow,
+            alert_fg: Color::White,
+            alert_bg: Color::Red,
+            muted: Color::DarkGray,
+            private: Color::Magenta,
+            archived: Color::Blue,
+            marked: Color::Yellow,
+            selected_bg: Color::DarkGray,
+            cursor_fg: Color::Black,
+            cursor_bg: Color::Cyan,
+            urgent: Color::LightRed,
+            overdue: Color::Yellow,
+            expiring: Color::LightMagenta,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_owned(),
+            text: Color::Black,
+            accent: Color::Blue,
+            header: Color::Black,
+            status: Color::Blue,
+            alert_fg: Color::White,
+            alert_bg: Color::Red,
+            muted: Color::Gray,
+            private: Color::Magenta,
+            archived: Color::Cyan,
+            marked: Color::Red,
+            selected_bg: Color::Gray,
+            cursor_fg: Color::White,
+            cursor_bg: Color::Blue,
+            urgent: Color::Red,
+            overdue: Color::Magenta,
+            expiring: Color::Cyan,
+        }
+    }
+
+    /// Bright foregrounds only, so nothing relies on dim grays.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_owned(),
+            text: Color::White,
+            accent: Color::LightYellow,
+            header: Color::LightYellow,
+            status: Color::White,
+            alert_fg: Color::Black,
+            alert_bg: Color::LightRed,
+            muted: Color::Gray,
+            private: Color::LightMagenta,
+            archived: Color::LightCyan,
+            marked: Color::LightGreen,
+            selected_bg: Color::Blue,
+            cursor_fg: Color::Black,
+            cursor_bg: Color::LightYellow,
+            urgent: Color::LightRed,
+            overdue: Color::LightYellow,
+            expiring: Color::LightCyan,
+        }
+    }
+
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            _ => None,
+        }
+    }
+
+    pub fn presets() -> Vec<Self> {
+        Self::PRESETS
+            .iter()
+            .filter_map(|name| Self::preset(name))
+            .collect()
+    }
+
+    /// Builds the custom palette: `base` names the preset to start from
+    /// (dark when absent) and every other entry replaces one color.
+    pub fn custom<I, K, V>(entries: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (key.as_ref().to_owned(), value.as_ref().trim().to_owned()))
+            .collect::<Vec<_>>();
+        let mut theme = match entries.iter().find(|(key, _)| key == "base") {
+            Some((_, base)) => Self::preset(base).ok_or_else(|| {
+                anyhow!(
+                    "unknown base theme `{base}`; use one of: {}",
+                    Self::PRESETS.join(", ")
+                )
+            })?,
+            None => Self::dark(),
+        };
+        theme.name = CUSTOM_THEME.to_owned();
+        for (key, value) in entries.iter().filter(|(key, _)| key != "base") {
+            let Some(slot) = theme.slot_mut(key) else {
+                bail!(
+                    "unknown palette entry `{key}`; use base or one of: {}",
+                    Self::SLOTS.join(", ")
+                );
+            };
+            *slot = value.parse::<Color>().map_err(|_| {
+                anyhow!(
+                    "`{key}` = {value:?} is not a color; use a name like \"blue\" or hex like \"#1e90ff\""
+                )
+            })?;
+        }
+        Ok(theme)
+    }
+
+    const SLOTS: [&'static str; 16] = [
+        "text",
+        "accent",
+        "header",
+        "status",
+        "alert_fg",
+        "alert_bg",
+        "muted",
+        "private",
+        "archived",
+        "marked",
+        "selected_bg",
+        "cursor_fg",
+        "cursor_bg",
+        "urgent",
+        "overdue",
+        "expiring",
+    ];
+
+    fn slot_mut(&mut self, key: &str) -> Option<&mut Color> {
+        Some(match key {
+            "text" => &mut self.text,
+            "accent" => &mut self.accent,
+            "header" => &mut self.header,
+            "status" => &mut self.status,
+            "alert_fg" => &mut self.alert_fg,
+            "alert_bg" => &mut self.alert_bg,
+            "muted" => &mut self.muted,
+            "private" => &mut self.private,
+            "archived" => &mut self.archived,
+            "marked" => &mut self.marked,
+            "selected_bg" => &mut self.selected_bg,
+            "cursor_fg" => &mut self.cursor_fg,
+            "cursor_bg" => &mut self.cursor_bg,
+            "urgent" => &mut self.urgent,
+            "overdue" => &mut self.overdue,
+            "expiring" => &mut self.expiring,
+            _ => return None,
+        })
+    }
+}
+
+/// Theme after `current` in `themes`, wrapping around; the first theme when
+/// `current` isn't listed.
+pub fn next_theme<'a>(themes: &'a [Theme], current: &str) -> Option<&'a Theme> {
+    let index = themes
+        .iter()
+        .position(|theme| theme.name == current)
+        .map_or(0, |index| (index + 1) % themes.len());
+    themes.get(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CUSTOM_THEME, Theme, next_theme};
+    use ratatui::style::Color;
+
+    #[test]
+    fn presets_are_named_and_distinct() {
+        let presets = Theme::presets();
+        assert_eq!(
+            presets
+                .iter()
+                .map(|theme| theme.name.as_str())
+                .collect::<Vec<_>>(),
+            Theme::PRESETS
+        );
+        assert_ne!(presets[0], presets[1]);
+        assert_ne!(presets[1], presets[2]);
+        assert_eq!(Theme::default(), Theme::dark());
+    }
+
+    #[test]
+    fn custom_palette_starts_from_base_and_overrides_colors() {
+        let theme = Theme::custom([
+            ("base", "light"),
+            ("accent", "#1e90ff"),
+            ("marked", "green"),
+        ])
+        .expect("valid palette");
+        assert_eq!(theme.name, CUSTOM_THEME);
+        assert_eq!(theme.accent, Color::Rgb(0x1e, 0x90, 0xff));
+        assert_eq!(theme.marked, Color::Green);
+        assert_eq!(theme.text, Theme::light().text);
+
+        let dark_based = Theme::custom([("status", "red")]).expect("valid palette");
+        assert_eq!(dark_based.cursor_bg, Theme::dark().cursor_bg);
+    }
+
+    #[test]
+    fn custom_palette_rejects_unknown_entries_and_colors() {
+        let error = Theme::custom([("base", "sepia")]).expect_err("bad base");
+        assert!(error.to_string().contains("unknown base theme `sepia`"));
+
+        let error = Theme::custom([("borders", "red")]).expect_err("bad slot");
+        assert!(
+            error
+                .to_string()
+                .contains("unknown palette entry `borders`")
+        );
+
+        let error = Theme::custom([("accent", "mauve-ish")]).expect_err("bad color");
+        assert!(error.to_string().contains("is not a color"));
+    }
+
+    #[test]
+    fn next_theme_wraps_and_falls_back_to_first() {
+        let themes = Theme::presets();
+        assert_eq!(
+            next_theme(&themes, "dark").map(|t| t.name.as_str()),
+            Some("light")
+        );
+        assert_eq!(
+            next_theme(&themes, "high-contrast").map(|t| t.name.as_str()),
+            Some("dark")
+        );
+        assert_eq!(
+            next_theme(&themes, "gone").map(|t| t.name.as_str()),
+            Some("dark")
+        );
+        assert!(next_theme(&[], "dark").is_none());
+    }
+}
@@ -0,0 +1,474 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Low-resolution previews of PNG and JPEG documents: a half-block mosaic
+//! that works in any color terminal, plus kitty and sixel graphics for
+//! terminals that can show real pixels.
+
+use anyhow::{Context, Result, anyhow, bail};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use std::collections::BTreeSet;
+use zune_jpeg::JpegDecoder;
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+use zune_jpeg::zune_core::options::DecoderOptions;
+
+/// Longest edge kept after decoding: enough for an overlay, small enough to
+/// resend to the terminal each time the preview opens.
+const MAX_EDGE: usize = 320;
+/// Base64 bytes per kitty graphics escape; the protocol's limit.
+const KITTY_CHUNK: usize = 4096;
+const UPPER_HALF_BLOCK: &str = "▀";
+
+/// How the preview reaches the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Graphics {
+    #[default]
+    Mosaic,
+    Kitty,
+    Sixel,
+}
+
+impl Graphics {
+    /// Guesses from what the terminal puts in the environment;
+    /// `MICASA_GRAPHICS=mosaic|kitty|sixel` overrides the guess. Unknown
+    /// terminals and tmux get the mosaic.
+    pub fn detect() -> Self {
+        Self::from_env(|key| std::env::var(key).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        match var("MICASA_GRAPHICS").as_deref().map(str::trim) {
+            Some("kitty") => return Self::Kitty,
+            Some("sixel") => return Self::Sixel,
+            Some("mosaic") => return Self::Mosaic,
+            _ => {}
+        }
+        if var("TMUX").is_some() {
+            return Self::Mosaic;
+        }
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        if var("KITTY_WINDOW_ID").is_some()
+            || term.contains("kitty")
+            || term.contains("ghostty")
+            || matches!(program.as_str(), "WezTerm" | "ghostty")
+        {
+            Self::Kitty
+        } else if term.starts_with("foot") || term.contains("mlterm") || term.contains("sixel") {
+            Self::Sixel
+        } else {
+            Self::Mosaic
+        }
+    }
+}
+
+/// Whether `decode` has a preview for documents of this type.
+pub fn is_image_mime(mime: &str) -> bool {
+    matches!(
+        mime.trim().to_ascii_lowercase().as_str(),
+        "image/png" | "image/jpeg" | "image/jpg"
+    )
+}
+
+/// RGB pixels, row-major. Transparent PNG pixels are blended onto black.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thumbnail {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Thumbnail {
+    /// Decodes a PNG or JPEG and shrinks it to at most `MAX_EDGE` pixels on
+    /// its longest side.
+    pub fn decode(mime: &str, data: &[u8]) -> Result<Self> {
+        let image = match mime.trim().to_ascii_lowercase().as_str() {
+            "image/png" => decode_png(data)?,
+            "image/jpeg" | "image/jpg" => decode_jpeg(data)?,
+            other => bail!("no preview for {other} documents; PNG and JPEG only"),
+        };
+        Ok(image.fit(MAX_EDGE, MAX_EDGE))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Box-averaged copy no larger than `max_width` x `max_height`, keeping
+    /// the aspect ratio. Never upscales.
+    pub fn fit(&self, max_width: usize, max_height: usize) -> Self {
+        if max_width == 0 || max_height == 0 || self.pixels.is_empty() {
+            return Self {
+                width: 0,
+                height: 0,
+                pixels: Vec::new(),
+            };
+        }
+        if self.width <= max_width && self.height <= max_height {
+            return self.clone();
+        }
+        let (width, height) = if self.width * max_height > self.height * max_width {
+            (max_width, (self.height * max_width / self.width).max(1))
+        } else {
+            ((self.width * max_height / self.height).max(1), max_height)
+        };
+
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let rows = y * self.height / height..(y + 1) * self.height / height;
+            for x in 0..width {
+                let columns = x * self.width / width..(x + 1) * self.width / width;
+                let mut sum = [0_usize; 3];
+                let mut count = 0_usize;
+                for source_y in rows.clone() {
+                    for source_x in columns.clone() {
+                        let pixel = self.pixels[source_y * self.width + source_x];
+                        for (total, channel) in sum.iter_mut().zip(pixel) {
+                            *total += usize::from(channel);
+                        }
+                        count += 1;
+                    }
+                }
+                pixels.push(sum.map(|total| u8::try_from(total / count).unwrap_or(u8::MAX)));
+            }
+        }
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Cells the mosaic takes inside a `columns` x `rows` box. A cell shows
+    /// two pixels stacked, which keeps pixels roughly square.
+    pub fn mosaic_size(&self, columns: u16, rows: u16) -> (u16, u16) {
+        let fitted = self.fit(usize::from(columns), usize::from(rows) * 2);
+        (
+            u16::try_from(fitted.width).unwrap_or(columns),
+            u16::try_from(fitted.height.div_ceil(2)).unwrap_or(rows),
+        )
+    }
+
+    /// Upper half blocks colored with the top pixel as foreground and the
+    /// bottom pixel as background, fitted inside `columns` x `rows` cells.
+    pub fn mosaic_lines(&self, columns: u16, rows: u16) -> Vec<Line<'static>> {
+        let fitted = self.fit(usize::from(columns), usize::from(rows) * 2);
+        (0..fitted.height)
+            .step_by(2)
+            .map(|y| {
+                let spans = (0..fitted.width)
+                    .map(|x| {
+                        let top = fitted.pixels[y * fitted.width + x];
+                        let bottom = (y + 1 < fitted.height)
+                            .then(|| fitted.pixels[(y + 1) * fitted.width + x])
+                            .map_or(Color::Reset, rgb);
+                        Span::styled(UPPER_HALF_BLOCK, Style::default().fg(rgb(top)).bg(bottom))
+                    })
+                    .collect::<Vec<_>>();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Kitty graphics escapes that send the pixels and place them over
+    /// `columns` x `rows` cells at the cursor. `q=2` keeps the terminal from
+    /// answering into the input stream.
+    pub fn kitty_escape(&self, columns: u16, rows: u16) -> String {
+        if self.pixels.is_empty() {
+            return String::new();
+        }
+        let raw = self.pixels.iter().flatten().copied().collect::<Vec<_>>();
+        let encoded = base64(&raw);
+        let chunks = encoded.as_bytes().chunks(KITTY_CHUNK).collect::<Vec<_>>();
+        let mut out = String::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let more = u8::from(index + 1 < chunks.len());
+            if index == 0 {
+                out.push_str(&format!(
+                    "\x1b_Ga=T,f=24,s={},v={},c={columns},r={rows},q=2,m={more};",
+                    self.width, self.height
+                ));
+            } else {
+                out.push_str(&format!("\x1b_Gm={more};"));
+            }
+            out.push_str(&String::from_utf8_lossy(chunk));
+            out.push_str("\x1b\\");
+        }
+        out
+    }
+
+    /// Sixel image at its own pixel size, colors rounded to a 6x6x6 cube.
+    pub fn sixel_escape(&self) -> String {
+        if self.pixels.is_empty() {
+            return String::new();
+        }
+        let level = |channel: u8| (usize::from(channel) * 5 + 127) / 255;
+        let colors = self
+            .pixels
+            .iter()
+            .map(|[red, green, blue]| level(*red) * 36 + level(*green) * 6 + level(*blue))
+            .collect::<Vec<_>>();
+
+        let mut out = format!("\x1bPq\"1;1;{};{}", self.width, self.height);
+        for color in colors.iter().copied().collect::<BTreeSet<_>>() {
+            let percent = |step: usize| step * 100 / 5;
+            out.push_str(&format!(
+                "#{color};2;{};{};{}",
+                percent(color / 36),
+                percent(color / 6 % 6),
+                percent(color % 6)
+            ));
+        }
+        for band in (0..self.height).step_by(6) {
+            let rows = band..(band + 6).min(self.height);
+            let band_colors = rows
+                .clone()
+                .flat_map(|y| &colors[y * self.width..(y + 1) * self.width])
+                .copied()
+                .collect::<BTreeSet<_>>();
+            for (index, color) in band_colors.into_iter().enumerate() {
+                if index > 0 {
+                    out.push('$');
+                }
+                out.push_str(&format!("#{color}"));
+                let sixels = (0..self.width).map(|x| {
+                    let bits = rows
+                        .clone()
+                        .filter(|y| colors[y * self.width + x] == color)
+                        .fold(0_u8, |bits, y| bits | 1 << (y - band));
+                    char::from(63 + bits)
+                });
+                push_run_length(&mut out, sixels);
+            }
+            out.push('-');
+        }
+        out.push_str("\x1b\\");
+        out
+    }
+}
+
+fn rgb([red, green, blue]: [u8; 3]) -> Color {
+    Color::Rgb(red, green, blue)
+}
+
+/// Sixel repeat syntax: `!<count><char>` for runs longer than three.
+fn push_run_length(out: &mut String, sixels: impl Iterator<Item = char>) {
+    let flush = |out: &mut String, sixel: char, count: usize| {
+        if count > 3 {
+            out.push_str(&format!("!{count}{sixel}"));
+        } else {
+            out.extend(std::iter::repeat_n(sixel, count));
+        }
+    };
+    let mut run: Option<(char, usize)> = None;
+    for sixel in sixels {
+        run = match run {
+            Some((current, count)) if current == sixel => Some((current, count + 1)),
+            Some((current, count)) => {
+                flush(out, current, count);
+                Some((sixel, 1))
+            }
+            None => Some((sixel, 1)),
+        };
+    }
+    if let Some((sixel, count)) = run {
+        flush(out, sixel, count);
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let word = chunk.iter().enumerate().fold(0_u32, |word, (index, byte)| {
+            word | u32::from(*byte) << (16 - 8 * index)
+        });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                out.push(char::from(
+                    ALPHABET[(word >> (18 - 6 * index)) as usize & 63],
+                ));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+fn decode_png(data: &[u8]) -> Result<Thumbnail> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().context("read PNG header")?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).context("decode PNG")?;
+    let channels = info.color_type.samples();
+    let pixels = buffer[..info.buffer_size()]
+        .chunks_exact(channels)
+        .map(|pixel| {
+            let (color, alpha) = match *pixel {
+                [gray] => ([gray; 3], u8::MAX),
+                [gray, alpha] => ([gray; 3], alpha),
+                [red, green, blue] => ([red, green, blue], u8::MAX),
+                [red, green, blue, alpha, ..] => ([red, green, blue], alpha),
+                [] => ([0; 3], u8::MAX),
+            };
+            color.map(|channel| {
+                u8::try_from(u16::from(channel) * u16::from(alpha) / 255).unwrap_or(u8::MAX)
+            })
+        })
+        .collect();
+    Ok(Thumbnail {
+        width: usize::try_from(info.width).context("PNG width")?,
+        height: usize::try_from(info.height).context("PNG height")?,
+        pixels,
+    })
+}
+
+fn decode_jpeg(data: &[u8]) -> Result<Thumbnail> {
+    let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGB);
+    let mut decoder = JpegDecoder::new_with_options(data, options);
+    let bytes = decoder
+        .decode()
+        .map_err(|error| anyhow!("decode JPEG: {error:?}"))?;
+    let (width, height) = decoder
+        .dimensions()
+        .ok_or_else(|| anyhow!("JPEG has no dimensions"))?;
+    let pixels = bytes
+        .chunks_exact(3)
+        .map(|pixel| [pixel[0], pixel[1], pixel[2]])
+        .collect();
+    Ok(Thumbnail {
+        width,
+        height,
+        pixels,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Graphics, Thumbnail, base64, is_image_mime};
+    use ratatui::style::Color;
+
+    /// Encodes RGB pixels as an 8-bit PNG.
+    fn png_bytes(width: u32, height: u32, pixels: &[[u8; 3]]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, width, height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().expect("png header");
+        let raw = pixels.iter().flatten().copied().collect::<Vec<_>>();
+        writer.write_image_data(&raw).expect("png data");
+        writer.finish().expect("png finish");
+        out
+    }
+
+    #[test]
+    fn png_decodes_and_mosaic_stacks_two_pixels_per_cell() {
+        let red = [255, 0, 0];
+        let blue = [0, 0, 255];
+        let bytes = png_bytes(2, 3, &[red, blue, blue, red, red, red]);
+        let thumbnail = Thumbnail::decode("image/png", &bytes).expect("decode");
+        assert_eq!((thumbnail.width(), thumbnail.height()), (2, 3));
+
+        let lines = thumbnail.mosaic_lines(10, 10);
+        assert_eq!(lines.len(), 2);
+        let first = &lines[0].spans[0];
+        assert_eq!(first.content, "▀");
+        assert_eq!(first.style.fg, Some(Color::Rgb(255, 0, 0)));
+        assert_eq!(first.style.bg, Some(Color::Rgb(0, 0, 255)));
+        assert_eq!(lines[1].spans[1].style.bg, Some(Color::Reset));
+        assert_eq!(thumbnail.mosaic_size(10, 10), (2, 2));
+    }
+
+    #[test]
+    fn fit_averages_blocks_and_keeps_the_aspect_ratio() {
+        let thumbnail = Thumbnail {
+            width: 4,
+            height: 2,
+            pixels: vec![
+                [0, 0, 0],
+                [200, 200, 200],
+                [10, 10, 10],
+                [10, 10, 10],
+                [0, 0, 0],
+                [200, 200, 200],
+                [10, 10, 10],
+                [10, 10, 10],
+            ],
+        };
+        let fitted = thumbnail.fit(2, 2);
+        assert_eq!((fitted.width, fitted.height), (2, 1));
+        assert_eq!(fitted.pixels, vec![[100, 100, 100], [10, 10, 10]]);
+        assert_eq!(thumbnail.fit(8, 8), thumbnail);
+        assert_eq!(thumbnail.fit(0, 3).width, 0);
+    }
+
+    #[test]
+    fn unsupported_or_broken_images_explain_themselves() {
+        assert!(is_image_mime("IMAGE/JPEG"));
+        assert!(!is_image_mime("application/pdf"));
+        let error = Thumbnail::decode("image/gif", b"GIF89a").expect_err("gif");
+        assert!(error.to_string().contains("PNG and JPEG only"));
+        assert!(Thumbnail::decode("image/jpeg", b"not a jpeg").is_err());
+        assert!(Thumbnail::decode("image/png", b"not a png").is_err());
+    }
+
+    #[test]
+    fn kitty_and_sixel_escapes_frame_the_pixels() {
+        let thumbnail = Thumbnail {
+            width: 2,
+            height: 1,
+            pixels: vec![[255, 0, 0], [255, 0, 0]],
+        };
+        assert_eq!(
+            thumbnail.kitty_escape(4, 2),
+            "\x1b_Ga=T,f=24,s=2,v=1,c=4,r=2,q=2,m=0;/wAA/wAA\x1b\\"
+        );
+        assert_eq!(
+            thumbnail.sixel_escape(),
+            "\x1bPq\"1;1;2;1#180;2;100;0;0#180@@-\x1b\\"
+        );
+        assert_eq!(base64(b"micasa!"), "bWljYXNhIQ==");
+    }
+
+    #[test]
+    fn graphics_follow_the_terminal_and_the_override() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == key)
+                    .map(|(_, value)| (*value).to_owned())
+            }
+        };
+        assert_eq!(
+            Graphics::from_env(env(&[("KITTY_WINDOW_ID", "1")])),
+            Graphics::Kitty
+        );
+        assert_eq!(
+            Graphics::from_env(env(&[("TERM", "foot")])),
+            Graphics::Sixel
+        );
+        assert_eq!(
+            Graphics::from_env(env(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")])),
+            Graphics::Mosaic
+        );
+        assert_eq!(
+            Graphics::from_env(env(&[
+                ("TERM", "xterm-256color"),
+                ("MICASA_GRAPHICS", "sixel")
+            ])),
+            Graphics::Sixel
+        );
+        assert_eq!(
+            Graphics::from_env(env(&[("TERM", "xterm-256color")])),
+            Graphics::Mosaic
+        );
+    }
+}
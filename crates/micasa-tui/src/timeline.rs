@@ -0,0 +1,162 @@
+// Copyright 2026 Phillip Cloud
+// Licensed under the Apache License, Version 2.0
+
+//! Calendar math for the Projects timeline (`T`): each project's start to
+//! end span drawn as a bar on a shared month grid, so overlapping work shows
+//! at a glance.
+
+use time::{Date, Month};
+
+/// Cells per month; fits a `Jan 26` label and a gap.
+pub(crate) const MONTH_WIDTH: usize = 7;
+const BAR: char = '█';
+const CLIPPED_LEFT: char = '◀';
+const CLIPPED_RIGHT: char = '▶';
+const TODAY: char = '┊';
+
+/// One row of the grid split so the caller can color the bar alone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BarCells {
+    pub before: String,
+    pub bar: String,
+    pub after: String,
+}
+
+/// First day of the month `months` away from `date`'s month.
+pub(crate) fn month_start(date: Date, months: i32) -> Date {
+    let index = date.year() * 12 + i32::from(date.month() as u8) - 1 + months;
+    let month = Month::try_from((index.rem_euclid(12) + 1) as u8).unwrap_or(Month::January);
+    Date::from_calendar_date(index.div_euclid(12), month, 1).unwrap_or(date)
+}
+
+/// Three-letter month name, like `Mar`.
+pub(crate) fn month_abbrev(date: Date) -> String {
+    date.month().to_string().chars().take(3).collect()
+}
+
+/// `Mar 26 Apr 26 …`, one label per month column.
+pub(crate) fn month_header(first_month: Date, months: usize) -> String {
+    (0..months)
+        .map(|offset| {
+            let month = month_start(first_month, offset as i32);
+            let label = format!(
+                "{} {:02}",
+                month_abbrev(month),
+                month.year().rem_euclid(100)
+            );
+            format!("{label:<MONTH_WIDTH$}")
+        })
+        .collect()
+}
+
+/// Cells from the window's left edge to `date`, negative before it.
+fn column(date: Date, first_month: Date) -> i64 {
+    let months = i64::from(date.year() - first_month.year()) * 12 + i64::from(date.month() as u8)
+        - i64::from(first_month.month() as u8);
+    let days = i64::from(date.month().length(date.year()));
+    months * MONTH_WIDTH as i64 + i64::from(date.day() - 1) * MONTH_WIDTH as i64 / days
+}
+
+/// Lays out the `start..=end` span on a `months`-wide window. Spans cut by
+/// the window edge get an arrow at that end; `None` spans leave the row empty
+/// apart from the today marker.
+pub(crate) fn bar_cells(
+    span: Option<(Date, Date)>,
+    first_month: Date,
+    months: usize,
+    today: Date,
+) -> BarCells {
+    let width = months * MONTH_WIDTH;
+    let mut cells = vec![' '; width];
+    if let Ok(today) = usize::try_from(column(today, first_month))
+        && today < width
+    {
+        cells[today] = TODAY;
+    }
+    let range = span.and_then(|(start, end)| {
+        let left = column(start, first_month);
+        let right = column(end.max(start), first_month) + 1;
+        (right > 0 && left < width as i64).then(|| {
+            (
+                left.max(0) as usize,
+                right.min(width as i64) as usize,
+                left < 0,
+                right > width as i64,
+            )
+        })
+    });
+    let Some((left, right, clipped_left, clipped_right)) = range else {
+        return BarCells {
+            before: cells.into_iter().collect(),
+            bar: String::new(),
+            after: String::new(),
+        };
+    };
+    let mut bar = vec![BAR; right - left];
+    if clipped_left {
+        bar[0] = CLIPPED_LEFT;
+    }
+    if clipped_right && let Some(last) = bar.last_mut() {
+        *last = CLIPPED_RIGHT;
+    }
+    BarCells {
+        before: cells[..left].iter().collect(),
+        bar: bar.into_iter().collect(),
+        after: cells[right..].iter().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MONTH_WIDTH, bar_cells, month_header, month_start};
+    use time::macros::date;
+
+    #[test]
+    fn months_step_across_years_and_label_columns() {
+        assert_eq!(month_start(date!(2026 - 11 - 18), 2), date!(2027 - 01 - 01));
+        assert_eq!(
+            month_start(date!(2026 - 01 - 31), -1),
+            date!(2025 - 12 - 01)
+        );
+        assert_eq!(
+            month_header(date!(2026 - 12 - 01), 2),
+            "Dec 26 Jan 27 ".to_owned()
+        );
+    }
+
+    #[test]
+    fn bars_cover_the_span_and_mark_clipped_ends() {
+        let first = date!(2026 - 03 - 01);
+        let today = date!(2026 - 03 - 01);
+        let cells = bar_cells(
+            Some((date!(2026 - 04 - 01), date!(2026 - 05 - 31))),
+            first,
+            3,
+            today,
+        );
+        assert_eq!(cells.before, format!("┊{}", " ".repeat(MONTH_WIDTH - 1)));
+        assert_eq!(cells.bar, "█".repeat(2 * MONTH_WIDTH));
+        assert!(cells.after.is_empty());
+
+        let cells = bar_cells(
+            Some((date!(2025 - 12 - 15), date!(2026 - 07 - 01))),
+            first,
+            3,
+            today,
+        );
+        assert!(cells.before.is_empty());
+        assert!(cells.bar.starts_with('◀') && cells.bar.ends_with('▶'));
+        assert_eq!(cells.bar.chars().count(), 3 * MONTH_WIDTH);
+
+        let cells = bar_cells(
+            Some((date!(2027 - 01 - 01), date!(2027 - 02 - 01))),
+            first,
+            3,
+            today,
+        );
+        assert!(
+            cells.bar.is_empty(),
+            "spans outside the window draw nothing"
+        );
+    }
+}